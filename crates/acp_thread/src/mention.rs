@@ -60,6 +60,11 @@ pub enum MentionUri {
     GitDiff {
         base_ref: String,
     },
+    McpResource {
+        server_id: String,
+        uri: String,
+        name: String,
+    },
 }
 
 impl MentionUri {
@@ -215,6 +220,24 @@ impl MentionUri {
                     let base_ref =
                         single_query_param(&url, "base")?.unwrap_or_else(|| "main".to_string());
                     Ok(Self::GitDiff { base_ref })
+                } else if path == "/agent/mcp-resource" {
+                    let mut server_id = None;
+                    let mut uri = None;
+                    let mut name = None;
+                    for (key, value) in url.query_pairs() {
+                        match key.as_ref() {
+                            "server" => server_id = Some(value.into_owned()),
+                            "uri" => uri = Some(value.into_owned()),
+                            "name" => name = Some(value.into_owned()),
+                            _ => bail!("invalid query parameter"),
+                        }
+                    }
+                    let uri = uri.context("Missing uri for MCP resource")?;
+                    Ok(Self::McpResource {
+                        server_id: server_id.context("Missing server for MCP resource")?,
+                        name: name.unwrap_or_else(|| uri.clone()),
+                        uri,
+                    })
                 } else {
                     bail!("invalid zed url: {:?}", input);
                 }
@@ -245,6 +268,7 @@ impl MentionUri {
                 }
             }
             MentionUri::GitDiff { base_ref } => format!("Branch Diff ({})", base_ref),
+            MentionUri::McpResource { name, .. } => name.clone(),
             MentionUri::Selection {
                 abs_path: path,
                 line_range,
@@ -285,6 +309,9 @@ impl MentionUri {
                 )
                 .into(),
             ),
+            MentionUri::McpResource { server_id, uri, .. } => {
+                Some(format!("{uri} ({server_id})").into())
+            }
             _ => None,
         }
     }
@@ -306,6 +333,7 @@ impl MentionUri {
             MentionUri::Selection { .. } => IconName::Reader.path().into(),
             MentionUri::Fetch { .. } => IconName::ToolWeb.path().into(),
             MentionUri::GitDiff { .. } => IconName::GitBranch.path().into(),
+            MentionUri::McpResource { .. } => IconName::Server.path().into(),
         }
     }
 
@@ -409,6 +437,18 @@ impl MentionUri {
                 url.query_pairs_mut().append_pair("base", base_ref);
                 url
             }
+            MentionUri::McpResource {
+                server_id,
+                uri,
+                name,
+            } => {
+                let mut url = Url::parse("zed:///agent/mcp-resource").unwrap();
+                url.query_pairs_mut()
+                    .append_pair("server", server_id)
+                    .append_pair("uri", uri)
+                    .append_pair("name", name);
+                url
+            }
         }
     }
 }
@@ -651,6 +691,26 @@ mod tests {
         assert_eq!(parsed.to_uri().to_string(), uri);
     }
 
+    #[test]
+    fn test_parse_mcp_resource_uri() {
+        let uri =
+            "zed:///agent/mcp-resource?server=tickets&uri=tickets%3A%2F%2F123&name=Ticket+123";
+        let parsed = MentionUri::parse(uri, PathStyle::local()).unwrap();
+        match &parsed {
+            MentionUri::McpResource {
+                server_id,
+                uri,
+                name,
+            } => {
+                assert_eq!(server_id, "tickets");
+                assert_eq!(uri, "tickets://123");
+                assert_eq!(name, "Ticket 123");
+            }
+            _ => panic!("Expected McpResource variant"),
+        }
+        assert_eq!(parsed.to_uri().to_string(), uri);
+    }
+
     #[test]
     fn test_invalid_scheme() {
         assert!(MentionUri::parse("ftp://example.com", PathStyle::local()).is_err());
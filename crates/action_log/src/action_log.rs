@@ -62,6 +62,9 @@ pub struct ActionLog {
     last_reject_undo: Option<LastRejectUndo>,
     /// Tracks the last time files were read by the agent, to detect external modifications
     file_read_times: HashMap<PathBuf, MTime>,
+    /// The buffers the agent created, edited or deleted, in order, so that callers can tell
+    /// which buffers an action changed
+    agent_edits: Vec<WeakEntity<Buffer>>,
 }
 
 impl ActionLog {
@@ -73,6 +76,7 @@ impl ActionLog {
             linked_action_log: None,
             last_reject_undo: None,
             file_read_times: HashMap::default(),
+            agent_edits: Vec::new(),
         }
     }
 
@@ -582,6 +586,7 @@ impl ActionLog {
         if record_file_read_time {
             self.update_file_read_time(&buffer, cx);
         }
+        self.record_agent_edit(&buffer);
         self.track_buffer_internal(buffer, true, cx);
     }
 
@@ -605,6 +610,7 @@ impl ActionLog {
         if record_file_read_time {
             self.update_file_read_time(&buffer, cx);
         }
+        self.record_agent_edit(&buffer);
         let new_version = buffer.read(cx).version();
         let tracked_buffer = self.track_buffer_internal(buffer, false, cx);
         if let TrackedBufferStatus::Deleted = tracked_buffer.status {
@@ -618,6 +624,7 @@ impl ActionLog {
     pub fn will_delete_buffer(&mut self, buffer: Entity<Buffer>, cx: &mut Context<Self>) {
         // Ok to propagate file read time removal to linked action log
         self.remove_file_read_time(&buffer, cx);
+        self.record_agent_edit(&buffer);
         let has_linked_action_log = self.linked_action_log.is_some();
         let tracked_buffer = self.track_buffer_internal(buffer.clone(), false, cx);
         match tracked_buffer.status {
@@ -1019,6 +1026,36 @@ impl ActionLog {
         })
     }
 
+    /// Returns how many changes the agent made so far, to pass to [`Self::buffers_edited_since`].
+    pub fn agent_edit_count(&self) -> usize {
+        self.agent_edits.len()
+    }
+
+    /// Returns the buffers the agent created, edited or deleted since [`Self::agent_edit_count`]
+    /// returned `edit_count`.
+    pub fn buffers_edited_since(&self, edit_count: usize) -> Vec<Entity<Buffer>> {
+        let mut buffers = Vec::new();
+        for buffer in self.agent_edits.get(edit_count..).unwrap_or_default() {
+            if let Some(buffer) = buffer.upgrade()
+                && !buffers.contains(&buffer)
+            {
+                buffers.push(buffer);
+            }
+        }
+        buffers
+    }
+
+    fn record_agent_edit(&mut self, buffer: &Entity<Buffer>) {
+        // Streaming edits change the same buffer many times in a row.
+        if self
+            .agent_edits
+            .last()
+            .is_none_or(|last| last.entity_id() != buffer.entity_id())
+        {
+            self.agent_edits.push(buffer.downgrade());
+        }
+    }
+
     /// Returns the set of buffers that contain edits that haven't been reviewed by the user.
     pub fn changed_buffers(&self, cx: &App) -> BTreeMap<Entity<Buffer>, Entity<BufferDiff>> {
         self.tracked_buffers
@@ -1756,6 +1793,54 @@ mod tests {
         );
     }
 
+    #[gpui::test]
+    async fn test_buffers_edited_since(cx: &mut TestAppContext) {
+        init_test(cx);
+
+        let fs = FakeFs::new(cx.executor());
+        fs.insert_tree(
+            path!("/dir"),
+            json!({"file1": "lorem\n", "file2": "ipsum\n"}),
+        )
+        .await;
+        let project = Project::test(fs.clone(), [path!("/dir").as_ref()], cx).await;
+        let action_log = cx.new(|_| ActionLog::new(project.clone()));
+        let open_buffer = |path: &str, cx: &mut TestAppContext| {
+            let project_path = project
+                .read_with(cx, |project, cx| project.find_project_path(path, cx))
+                .unwrap();
+            project.update(cx, |project, cx| project.open_buffer(project_path, cx))
+        };
+        let buffer1 = open_buffer("dir/file1", cx).await.unwrap();
+        let buffer2 = open_buffer("dir/file2", cx).await.unwrap();
+
+        let edit_count = action_log.read_with(cx, |log, _| log.agent_edit_count());
+        cx.update(|cx| {
+            // Reading a buffer doesn't change it.
+            action_log.update(cx, |log, cx| log.buffer_read(buffer1.clone(), cx));
+            action_log.update(cx, |log, cx| log.buffer_read(buffer2.clone(), cx));
+            buffer2.update(cx, |buffer, cx| buffer.set_text("IPSUM\n", cx));
+            action_log.update(cx, |log, cx| log.buffer_edited(buffer2.clone(), cx));
+            action_log.update(cx, |log, cx| log.buffer_edited(buffer2.clone(), cx));
+        });
+        action_log.read_with(cx, |log, _| {
+            assert_eq!(log.buffers_edited_since(edit_count), vec![buffer2.clone()]);
+        });
+
+        let edit_count = action_log.read_with(cx, |log, _| log.agent_edit_count());
+        cx.update(|cx| {
+            action_log.update(cx, |log, cx| log.will_delete_buffer(buffer1.clone(), cx));
+            action_log.update(cx, |log, cx| log.buffer_edited(buffer2.clone(), cx));
+        });
+        action_log.read_with(cx, |log, _| {
+            assert_eq!(
+                log.buffers_edited_since(edit_count),
+                vec![buffer1.clone(), buffer2.clone()]
+            );
+            assert_eq!(log.buffers_edited_since(log.agent_edit_count()), vec![]);
+        });
+    }
+
     #[gpui::test(iterations = 10)]
     async fn test_deleting_files(cx: &mut TestAppContext) {
         init_test(cx);
@@ -1,5 +1,6 @@
 mod db;
 mod edit_agent;
+mod hooks;
 mod legacy_thread;
 mod native_agent_server;
 pub mod outline;
@@ -48,6 +49,7 @@ use prompt_store::{
 use serde::{Deserialize, Serialize};
 use settings::{LanguageModelSelection, update_settings_file};
 use std::any::Any;
+use std::iter;
 use std::path::{Path, PathBuf};
 use std::rc::Rc;
 use std::sync::Arc;
@@ -55,6 +57,9 @@ use util::ResultExt;
 use util::path_list::PathList;
 use util::rel_path::RelPath;
 
+/// The built-in command that compacts the thread, see [`Thread::compact`].
+const COMPACT_COMMAND: &str = "compact";
+
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub struct ProjectSnapshot {
     pub worktree_snapshots: Vec<project::telemetry_snapshot::TelemetryWorktreeSnapshot>,
@@ -691,7 +696,9 @@ impl NativeAgent {
         cx: &mut Context<Self>,
     ) {
         match event {
-            ContextServerRegistryEvent::ToolsChanged => {}
+            ContextServerRegistryEvent::ToolsChanged
+            | ContextServerRegistryEvent::ResourcesChanged
+            | ContextServerRegistryEvent::ResourceUpdated { .. } => {}
             ContextServerRegistryEvent::PromptsChanged => {
                 self.update_available_commands(cx);
             }
@@ -717,16 +724,21 @@ impl NativeAgent {
     fn build_available_commands(&self, cx: &App) -> Vec<acp::AvailableCommand> {
         let registry = self.context_server_registry.read(cx);
 
-        let mut prompt_name_counts: HashMap<&str, usize> = HashMap::default();
+        // Count the built-in command, so that prompts with the same name are prefixed with their server.
+        let mut prompt_name_counts: HashMap<&str, usize> =
+            HashMap::from_iter([(COMPACT_COMMAND, 1)]);
         for context_server_prompt in registry.prompts() {
             *prompt_name_counts
                 .entry(context_server_prompt.prompt.name.as_str())
                 .or_insert(0) += 1;
         }
 
-        registry
-            .prompts()
-            .flat_map(|context_server_prompt| {
+        let compact_command = acp::AvailableCommand::new(
+            COMPACT_COMMAND,
+            "Summarize the conversation so far to free up context",
+        );
+        iter::once(compact_command)
+            .chain(registry.prompts().flat_map(|context_server_prompt| {
                 let prompt = &context_server_prompt.prompt;
 
                 let should_prefix = prompt_name_counts
@@ -762,7 +774,7 @@ impl NativeAgent {
                 }
 
                 Some(command)
-            })
+            }))
             .collect()
     }
 
@@ -1326,6 +1338,20 @@ impl acp_thread::AgentConnection for NativeAgentConnection {
         log::debug!("Prompt blocks count: {}", params.prompt.len());
 
         if let Some(parsed_command) = Command::parse(&params.prompt) {
+            if parsed_command.prompt_name == COMPACT_COMMAND
+                && parsed_command.explicit_server_id.is_none()
+            {
+                let path_style = self.0.read(cx).project.read(cx).path_style(cx);
+                return self.run_turn(session_id, cx, move |thread, cx| {
+                    let content = params
+                        .prompt
+                        .into_iter()
+                        .map(|block| UserMessageContent::from_content_block(block, path_style))
+                        .collect::<Vec<_>>();
+                    thread.update(cx, |thread, cx| thread.compact(id, content, cx))
+                });
+            }
+
             let registry = self.0.read(cx).context_server_registry.read(cx);
 
             let explicit_server_id = parsed_command
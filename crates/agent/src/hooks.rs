@@ -0,0 +1,309 @@
+use agent_settings::{AgentHook, AgentHooks, AgentSettings};
+use anyhow::{Context as _, Result};
+use futures::FutureExt as _;
+use gpui::{App, BackgroundExecutor, Entity};
+use project::Project;
+use serde::Serialize;
+use settings::Settings as _;
+use smol::io::AsyncWriteExt as _;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use util::command::Stdio;
+use util::shell::{ShellKind, get_system_shell};
+
+/// The exit status with which a hook blocks a tool call, or sends feedback to the model.
+const BLOCKING_EXIT_CODE: i32 = 2;
+
+/// A point of the agent's turn at which hooks run, serialized as the payload sent to their stdin.
+#[derive(Debug, Serialize)]
+#[serde(tag = "event", rename_all = "snake_case")]
+pub(crate) enum HookEvent {
+    PreToolUse {
+        tool_name: Arc<str>,
+        tool_input: serde_json::Value,
+    },
+    PostToolUse {
+        tool_name: Arc<str>,
+        tool_input: serde_json::Value,
+        tool_output: Option<String>,
+        is_error: bool,
+    },
+    PostEdit {
+        tool_name: Arc<str>,
+        tool_input: serde_json::Value,
+        /// The absolute paths of the files the tool created, edited or deleted.
+        paths: Vec<PathBuf>,
+    },
+    EndOfTurn,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum HookEventKind {
+    PreToolUse,
+    PostToolUse,
+    PostEdit,
+    EndOfTurn,
+}
+
+impl HookEventKind {
+    fn configured_hooks(self, hooks: &AgentHooks) -> &[AgentHook] {
+        match self {
+            HookEventKind::PreToolUse => &hooks.pre_tool_use,
+            HookEventKind::PostToolUse => &hooks.post_tool_use,
+            HookEventKind::PostEdit => &hooks.post_edit,
+            HookEventKind::EndOfTurn => &hooks.end_of_turn,
+        }
+    }
+}
+
+#[derive(Serialize)]
+struct HookPayload<'a> {
+    session_id: &'a str,
+    cwd: Option<&'a Path>,
+    #[serde(flatten)]
+    event: &'a HookEvent,
+}
+
+/// The hooks to run for an event, along with what they need to run once the event happens.
+pub(crate) struct Hooks {
+    hooks: Vec<AgentHook>,
+    session_id: String,
+    cwd: Option<PathBuf>,
+    executor: BackgroundExecutor,
+}
+
+impl Hooks {
+    /// Returns the hooks configured for `event`, restricted to those for `tool_name` if given,
+    /// or `None` if there aren't any.
+    ///
+    /// Hooks run on the local machine, so they're only supported in local projects.
+    pub(crate) fn for_event(
+        event: HookEventKind,
+        tool_name: Option<&str>,
+        session_id: &str,
+        project: &Entity<Project>,
+        cx: &App,
+    ) -> Option<Self> {
+        let hooks = event
+            .configured_hooks(&AgentSettings::get_global(cx).hooks)
+            .iter()
+            .filter(|hook| tool_name.is_none_or(|tool_name| hook.matches_tool(tool_name)))
+            .cloned()
+            .collect::<Vec<_>>();
+        if hooks.is_empty() {
+            return None;
+        }
+
+        let project = project.read(cx);
+        if !project.is_local() {
+            log::warn!(
+                "Agent hooks are only supported in local projects, skipping {event:?} hooks"
+            );
+            return None;
+        }
+        let cwd = project
+            .visible_worktrees(cx)
+            .next()
+            .map(|worktree| worktree.read(cx).abs_path().to_path_buf());
+
+        Some(Self {
+            hooks,
+            session_id: session_id.to_string(),
+            cwd,
+            executor: cx.background_executor().clone(),
+        })
+    }
+
+    /// Runs the hooks one after the other, returning the stderr of those that exited with status 2.
+    ///
+    /// Before a tool call, the first hook to exit with status 2 blocks the call and the remaining hooks don't run.
+    /// Hooks that fail otherwise, by exiting with another status or timing out, block the call too, so that a
+    /// broken policy check doesn't let everything through. At other points, such failures are logged and ignored.
+    pub(crate) async fn run(self, event: HookEvent) -> Option<String> {
+        let blocks_on_failure = matches!(event, HookEvent::PreToolUse { .. });
+        let payload = match serde_json::to_vec(&HookPayload {
+            session_id: &self.session_id,
+            cwd: self.cwd.as_deref(),
+            event: &event,
+        }) {
+            Ok(payload) => payload,
+            Err(error) => {
+                log::error!("Failed to serialize the {event:?} hook payload: {error}");
+                return blocks_on_failure.then(|| format!("Couldn't run the hooks: {error}"));
+            }
+        };
+
+        let mut feedback = Vec::new();
+        for hook in &self.hooks {
+            match run_hook(hook, &payload, self.cwd.as_deref(), &self.executor).await {
+                Ok(Some(hook_feedback)) => {
+                    feedback.push(hook_feedback);
+                    if blocks_on_failure {
+                        break;
+                    }
+                }
+                Ok(None) => {}
+                Err(error) => {
+                    log::error!("Agent hook `{}` failed: {error:#}", hook.command);
+                    if blocks_on_failure {
+                        feedback.push(format!("The `{}` hook failed: {error:#}", hook.command));
+                        break;
+                    }
+                }
+            }
+        }
+        (!feedback.is_empty()).then(|| feedback.join("\n\n"))
+    }
+}
+
+/// Returns whether any hooks are configured for `event` and `tool_name`, whether or not they can run in the project.
+pub(crate) fn has_hooks(event: HookEventKind, tool_name: &str, cx: &App) -> bool {
+    event
+        .configured_hooks(&AgentSettings::get_global(cx).hooks)
+        .iter()
+        .any(|hook| hook.matches_tool(tool_name))
+}
+
+/// Runs a single hook, returning its feedback if it exited with status 2.
+async fn run_hook(
+    hook: &AgentHook,
+    payload: &[u8],
+    cwd: Option<&Path>,
+    executor: &BackgroundExecutor,
+) -> Result<Option<String>> {
+    let shell_kind = ShellKind::system();
+    let mut command = util::command::new_command(get_system_shell());
+    command
+        .args(shell_kind.args_for_shell(false, hook.command.clone()))
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .kill_on_drop(true);
+    if let Some(cwd) = cwd {
+        command.current_dir(cwd);
+    }
+    let mut child = command.spawn().context("failed to spawn the hook")?;
+
+    // Write stdin while collecting the output, so the timeout covers hooks that don't read it,
+    // and hooks that print a lot before reading it don't block on a full pipe.
+    let stdin = child.stdin.take();
+    let write_stdin = async move {
+        // The hook may exit without reading its stdin.
+        if let Some(mut stdin) = stdin {
+            stdin.write_all(payload).await.ok();
+            stdin.flush().await.ok();
+        }
+    };
+    let run = futures::future::join(child.output(), write_stdin);
+
+    let output = futures::select! {
+        (output, ()) = run.fuse() => output?,
+        _ = executor.timer(hook.timeout).fuse() => {
+            anyhow::bail!("timed out after {:?}", hook.timeout);
+        }
+    };
+
+    match output.status.code() {
+        Some(0) => Ok(None),
+        Some(BLOCKING_EXIT_CODE) => {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            let stderr = stderr.trim();
+            Ok(Some(if stderr.is_empty() {
+                format!("The `{}` hook exited with status 2", hook.command)
+            } else {
+                stderr.to_string()
+            }))
+        }
+        code => anyhow::bail!(
+            "exited with status {code:?}:\nstdout: {}\nstderr: {}",
+            String::from_utf8_lossy(&output.stdout),
+            String::from_utf8_lossy(&output.stderr),
+        ),
+    }
+}
+
+#[cfg(all(test, not(windows)))]
+mod tests {
+    use super::*;
+    use gpui::TestAppContext;
+    use std::time::Duration;
+
+    #[gpui::test]
+    async fn test_run_hooks(cx: &mut TestAppContext) {
+        cx.executor().allow_parking();
+
+        let hook = |command: &str| AgentHook {
+            command: command.to_string(),
+            tools: None,
+            timeout: Duration::from_secs(10),
+        };
+        let hooks = |hooks: Vec<AgentHook>| Hooks {
+            hooks,
+            session_id: "session".to_string(),
+            cwd: None,
+            executor: cx.executor(),
+        };
+
+        // Hooks receive the event on stdin, and exit with status 2 to give feedback.
+        let feedback = hooks(vec![
+            hook("true"),
+            hook("exit 1"),
+            hook(
+                "grep -q '\"event\":\"post_tool_use\"' && echo 'Formatted the file' >&2 && exit 2",
+            ),
+        ])
+        .run(HookEvent::PostToolUse {
+            tool_name: "edit_file".into(),
+            tool_input: serde_json::json!({ "path": "root/main.rs" }),
+            tool_output: Some("Edited main.rs".into()),
+            is_error: false,
+        })
+        .await;
+        assert_eq!(feedback.as_deref(), Some("Formatted the file"));
+
+        // The first hook that blocks a tool call prevents the others from running.
+        let feedback = hooks(vec![
+            hook("echo 'Not allowed' >&2; exit 2"),
+            hook("echo 'Unreachable' >&2; exit 2"),
+        ])
+        .run(HookEvent::PreToolUse {
+            tool_name: "terminal".into(),
+            tool_input: serde_json::json!({ "command": "rm -rf /" }),
+        })
+        .await;
+        assert_eq!(feedback.as_deref(), Some("Not allowed"));
+
+        // Hooks that fail otherwise block tool calls, but are ignored at other points.
+        let feedback = hooks(vec![hook("exit 1"), hook("echo 'Unreachable' >&2; exit 2")])
+            .run(HookEvent::PreToolUse {
+                tool_name: "terminal".into(),
+                tool_input: serde_json::json!({ "command": "ls" }),
+            })
+            .await;
+        assert!(
+            feedback
+                .as_deref()
+                .is_some_and(|feedback| feedback.starts_with("The `exit 1` hook failed")),
+            "unexpected feedback: {feedback:?}"
+        );
+
+        let feedback = hooks(vec![AgentHook {
+            timeout: Duration::from_millis(100),
+            ..hook("sleep 10")
+        }])
+        .run(HookEvent::PreToolUse {
+            tool_name: "terminal".into(),
+            tool_input: serde_json::json!({ "command": "ls" }),
+        })
+        .await;
+        assert_eq!(
+            feedback.as_deref(),
+            Some("The `sleep 10` hook failed: timed out after 100ms")
+        );
+
+        let feedback = hooks(vec![hook("cat > /dev/null")])
+            .run(HookEvent::EndOfTurn)
+            .await;
+        assert_eq!(feedback, None);
+    }
+}
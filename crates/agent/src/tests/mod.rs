@@ -1,7 +1,7 @@
 use super::*;
 use acp_thread::{
-    AgentConnection, AgentModelGroupName, AgentModelList, PermissionOptions, ThreadStatus,
-    UserMessageId,
+    AgentConnection, AgentModelGroupName, AgentModelList, MentionUri, PermissionOptions,
+    ThreadStatus, UserMessageId,
 };
 use agent_client_protocol::{self as acp};
 use agent_settings::AgentProfileId;
@@ -1862,6 +1862,165 @@ async fn test_mcp_tool_truncation(cx: &mut TestAppContext) {
     );
 }
 
+#[gpui::test]
+async fn test_mcp_resource_updates(cx: &mut TestAppContext) {
+    const SERVER: &str = "tickets";
+    const URI: &str = "ticket://ZED-1";
+
+    let ThreadTest {
+        model,
+        thread,
+        context_server_store,
+        ..
+    } = setup(cx, TestModel::Fake).await;
+    let fake_model = model.as_fake();
+
+    cx.update(|cx| {
+        let mut settings = ProjectSettings::get_global(cx).clone();
+        settings.context_servers.insert(
+            SERVER.into(),
+            project::project_settings::ContextServerSettings::Stdio {
+                enabled: true,
+                remote: false,
+                command: ContextServerCommand {
+                    path: "somebinary".into(),
+                    args: Vec::new(),
+                    env: None,
+                    timeout: None,
+                },
+            },
+        );
+        ProjectSettings::override_global(settings, cx);
+    });
+
+    let resource_text = Arc::new(parking_lot::Mutex::new("Status: open".to_string()));
+    let (subscriptions_tx, mut subscriptions_rx) = mpsc::unbounded();
+    let fake_transport = Arc::new(
+        context_server::test::create_fake_transport(SERVER, cx.executor())
+            .on_request::<context_server::types::requests::Initialize, _>(|_params| async {
+                context_server::types::InitializeResponse {
+                    protocol_version: context_server::types::ProtocolVersion(
+                        context_server::types::LATEST_PROTOCOL_VERSION.to_string(),
+                    ),
+                    server_info: context_server::types::Implementation {
+                        name: SERVER.into(),
+                        version: "1.0.0".to_string(),
+                    },
+                    capabilities: context_server::types::ServerCapabilities {
+                        resources: Some(context_server::types::ResourcesCapabilities {
+                            subscribe: Some(true),
+                            list_changed: None,
+                        }),
+                        ..Default::default()
+                    },
+                    meta: None,
+                }
+            })
+            .on_request::<context_server::types::requests::ResourcesList, _>(|_params| async {
+                context_server::types::ResourcesListResponse {
+                    resources: Vec::new(),
+                    next_cursor: None,
+                    meta: None,
+                }
+            })
+            .on_request::<context_server::types::requests::ResourcesSubscribe, _>({
+                let subscriptions_tx = subscriptions_tx.clone();
+                move |params| {
+                    subscriptions_tx
+                        .unbounded_send(("subscribe", params.uri.to_string()))
+                        .unwrap();
+                    async {}
+                }
+            })
+            .on_request::<context_server::types::requests::ResourcesUnsubscribe, _>(move |params| {
+                subscriptions_tx
+                    .unbounded_send(("unsubscribe", params.uri.to_string()))
+                    .unwrap();
+                async {}
+            })
+            .on_request::<context_server::types::requests::ResourcesRead, _>({
+                let resource_text = resource_text.clone();
+                move |params| {
+                    let text = resource_text.lock().clone();
+                    async move {
+                        context_server::types::ResourcesReadResponse {
+                            contents: vec![context_server::types::ResourceContentsType::Text(
+                                context_server::types::TextResourceContents {
+                                    uri: params.uri,
+                                    mime_type: None,
+                                    text,
+                                },
+                            )],
+                            meta: None,
+                        }
+                    }
+                }
+            }),
+    );
+    context_server_store.update(cx, |store, cx| {
+        store.start_server(
+            Arc::new(ContextServer::new(
+                ContextServerId(SERVER.into()),
+                fake_transport.clone(),
+            )),
+            cx,
+        );
+    });
+    cx.run_until_parked();
+
+    // Mentioning the resource subscribes to it.
+    let mention = UserMessageContent::Mention {
+        uri: MentionUri::McpResource {
+            server_id: SERVER.into(),
+            uri: URI.into(),
+            name: "ZED-1".into(),
+        },
+        content: "Status: open".into(),
+    };
+    let events = thread.update(cx, |thread, cx| {
+        thread.send(UserMessageId::new(), [mention], cx).unwrap()
+    });
+    cx.run_until_parked();
+    assert_eq!(
+        subscriptions_rx.next().await,
+        Some(("subscribe", URI.to_string()))
+    );
+    fake_model.send_last_completion_stream_text_chunk("It's open.");
+    fake_model.end_last_completion_stream();
+    events.collect::<Vec<_>>().await;
+
+    // An update is read again and attached to the next message.
+    *resource_text.lock() = "Status: closed".to_string();
+    fake_transport.notify_client::<context_server::types::notifications::ResourcesUpdated>(
+        context_server::types::ResourcesUpdatedParams { uri: URI.into() },
+    );
+    cx.run_until_parked();
+
+    let events = thread.update(cx, |thread, cx| {
+        thread
+            .send(UserMessageId::new(), ["Is it still open?"], cx)
+            .unwrap()
+    });
+    cx.run_until_parked();
+    let completion = fake_model.pending_completions().pop().unwrap();
+    let last_message = completion.messages.last().unwrap().string_contents();
+    assert!(
+        last_message.contains("Is it still open?") && last_message.contains("Status: closed"),
+        "unexpected message: {last_message}"
+    );
+    fake_model.send_last_completion_stream_text_chunk("No.");
+    fake_model.end_last_completion_stream();
+    events.collect::<Vec<_>>().await;
+
+    // Releasing the thread unsubscribes from the resource.
+    drop(thread);
+    cx.run_until_parked();
+    assert_eq!(
+        subscriptions_rx.next().await,
+        Some(("unsubscribe", URI.to_string()))
+    );
+}
+
 #[gpui::test]
 #[cfg_attr(not(feature = "e2e"), ignore)]
 async fn test_cancellation(cx: &mut TestAppContext) {
@@ -3028,6 +3187,597 @@ async fn test_truncate_second_message(cx: &mut TestAppContext) {
     assert_first_message_state(cx);
 }
 
+#[gpui::test]
+async fn test_auto_compaction(cx: &mut TestAppContext) {
+    let ThreadTest { model, thread, .. } = setup(cx, TestModel::Fake).await;
+    let fake_model = model.as_fake();
+
+    thread
+        .update(cx, |thread, cx| {
+            thread.send(UserMessageId::new(), ["Message 1"], cx)
+        })
+        .unwrap();
+    cx.run_until_parked();
+    fake_model.send_last_completion_stream_text_chunk("Response 1");
+    fake_model.send_last_completion_stream_event(LanguageModelCompletionEvent::UsageUpdate(
+        language_model::TokenUsage {
+            input_tokens: 850_000,
+            output_tokens: 1_000,
+            cache_creation_input_tokens: 0,
+            cache_read_input_tokens: 0,
+        },
+    ));
+    fake_model.end_last_completion_stream();
+    cx.run_until_parked();
+
+    // The thread uses more than 80% of the context window, so it's compacted before the next request.
+    thread
+        .update(cx, |thread, cx| {
+            thread.send(UserMessageId::new(), ["Message 2"], cx)
+        })
+        .unwrap();
+    cx.run_until_parked();
+    let completion = fake_model.pending_completions().pop().unwrap();
+    assert_eq!(
+        completion.intent,
+        Some(CompletionIntent::ThreadContextSummarization)
+    );
+    assert_eq!(
+        completion.messages,
+        vec![
+            LanguageModelRequestMessage {
+                role: Role::User,
+                content: vec!["Message 1".into()],
+                cache: false,
+                reasoning_details: None,
+            },
+            LanguageModelRequestMessage {
+                role: Role::User,
+                content: vec![agent_settings::COMPACT_THREAD_PROMPT.into()],
+                cache: false,
+                reasoning_details: None,
+            }
+        ]
+    );
+    fake_model.send_last_completion_stream_text_chunk("Recap of message 1");
+    fake_model.end_last_completion_stream();
+    cx.run_until_parked();
+
+    // The most recent messages are kept verbatim after the recap.
+    let completion = fake_model.pending_completions().pop().unwrap();
+    assert_eq!(completion.intent, Some(CompletionIntent::UserPrompt));
+    assert_eq!(
+        completion.messages[1..],
+        vec![
+            LanguageModelRequestMessage {
+                role: Role::User,
+                content: vec![
+                    "The conversation so far was compacted to fit in your context window. \
+                    Here is a recap of it:\n\nRecap of message 1"
+                        .into()
+                ],
+                cache: false,
+                reasoning_details: None,
+            },
+            LanguageModelRequestMessage {
+                role: Role::Assistant,
+                content: vec!["Response 1".into()],
+                cache: false,
+                reasoning_details: None,
+            },
+            LanguageModelRequestMessage {
+                role: Role::User,
+                content: vec!["Message 2".into()],
+                cache: true,
+                reasoning_details: None,
+            }
+        ]
+    );
+    fake_model.send_last_completion_stream_text_chunk("Response 2");
+    fake_model.end_last_completion_stream();
+    cx.run_until_parked();
+
+    thread.read_with(cx, |thread, _| {
+        assert_eq!(
+            thread.to_markdown(),
+            indoc! {"
+                ## User
+
+                Message 1
+
+                ## Recap
+
+                Recap of message 1
+
+                ## Assistant
+
+                Response 1
+
+                ## User
+
+                Message 2
+
+                ## Assistant
+
+                Response 2
+            "}
+        );
+    });
+}
+
+#[gpui::test]
+async fn test_compaction_after_prompt_too_large(cx: &mut TestAppContext) {
+    let ThreadTest { model, thread, .. } = setup(cx, TestModel::Fake).await;
+    let fake_model = model.as_fake();
+    fake_model.set_max_token_count(2_000);
+
+    thread
+        .update(cx, |thread, cx| {
+            thread.send(UserMessageId::new(), ["Message 1"], cx)
+        })
+        .unwrap();
+    cx.run_until_parked();
+    fake_model.send_last_completion_stream_text_chunk("Response 1");
+    fake_model.end_last_completion_stream();
+    cx.run_until_parked();
+
+    let large_message = "x".repeat(10_000);
+    thread
+        .update(cx, |thread, cx| {
+            thread.send(UserMessageId::new(), [large_message.as_str()], cx)
+        })
+        .unwrap();
+    cx.run_until_parked();
+    fake_model.send_last_completion_stream_error(LanguageModelCompletionError::PromptTooLarge {
+        tokens: None,
+    });
+    cx.run_until_parked();
+
+    // The whole thread is summarized, cut down to fit in the context window.
+    let completion = fake_model.pending_completions().pop().unwrap();
+    assert_eq!(
+        completion.intent,
+        Some(CompletionIntent::ThreadContextSummarization)
+    );
+    let contents = completion
+        .messages
+        .iter()
+        .map(|message| message.string_contents())
+        .collect::<Vec<_>>();
+    assert_eq!(contents.len(), 3);
+    assert!(contents[0].contains("were omitted"), "{}", contents[0]);
+    assert!(contents[1].starts_with("xxx"));
+    assert!(contents[1].ends_with("[truncated]"));
+    assert!(contents[1].len() < large_message.len());
+    assert_eq!(contents[2], agent_settings::COMPACT_THREAD_PROMPT);
+    fake_model.send_last_completion_stream_text_chunk("Recap of the large message");
+    fake_model.end_last_completion_stream();
+    cx.run_until_parked();
+
+    // The request is retried with the recap alone.
+    let completion = fake_model.pending_completions().pop().unwrap();
+    assert_eq!(completion.intent, Some(CompletionIntent::UserPrompt));
+    assert_eq!(
+        completion.messages[1..]
+            .iter()
+            .map(|message| message.string_contents())
+            .collect::<Vec<_>>(),
+        vec![
+            "The conversation so far was compacted to fit in your context window. \
+            Here is a recap of it:\n\nRecap of the large message"
+        ]
+    );
+    fake_model.send_last_completion_stream_text_chunk("Response 2");
+    fake_model.end_last_completion_stream();
+    cx.run_until_parked();
+
+    thread.read_with(cx, |thread, _| {
+        assert!(
+            thread
+                .to_markdown()
+                .ends_with("## Assistant\n\nResponse 2\n")
+        );
+    });
+}
+
+#[gpui::test]
+async fn test_manual_compaction(cx: &mut TestAppContext) {
+    let ThreadTest { model, thread, .. } = setup(cx, TestModel::Fake).await;
+    let fake_model = model.as_fake();
+
+    thread
+        .update(cx, |thread, cx| {
+            thread.send(UserMessageId::new(), ["Message 1"], cx)
+        })
+        .unwrap();
+    cx.run_until_parked();
+    fake_model.send_last_completion_stream_text_chunk("Response 1");
+    fake_model.end_last_completion_stream();
+    cx.run_until_parked();
+
+    let compact_message_id = UserMessageId::new();
+    let events = thread
+        .update(cx, |thread, cx| {
+            thread.compact(compact_message_id.clone(), vec!["/compact".into()], cx)
+        })
+        .unwrap();
+    cx.run_until_parked();
+    let completion = fake_model.pending_completions().pop().unwrap();
+    assert_eq!(
+        completion.intent,
+        Some(CompletionIntent::ThreadContextSummarization)
+    );
+    fake_model.send_last_completion_stream_text_chunk("Recap");
+    fake_model.end_last_completion_stream();
+    let events = events.collect::<Vec<_>>().await;
+    assert!(
+        matches!(
+            events.last(),
+            Some(Ok(ThreadEvent::Stop(acp::StopReason::EndTurn)))
+        ),
+        "unexpected events: {events:?}"
+    );
+
+    // Only the recap is sent along with subsequent messages.
+    thread
+        .update(cx, |thread, cx| {
+            thread.send(UserMessageId::new(), ["Message 2"], cx)
+        })
+        .unwrap();
+    cx.run_until_parked();
+    let completion = fake_model.pending_completions().pop().unwrap();
+    assert_eq!(
+        completion.messages[1..],
+        vec![
+            LanguageModelRequestMessage {
+                role: Role::User,
+                content: vec![
+                    "The conversation so far was compacted to fit in your context window. \
+                    Here is a recap of it:\n\nRecap"
+                        .into()
+                ],
+                cache: false,
+                reasoning_details: None,
+            },
+            LanguageModelRequestMessage {
+                role: Role::User,
+                content: vec!["Message 2".into()],
+                cache: true,
+                reasoning_details: None,
+            }
+        ]
+    );
+
+    // Restoring the checkpoint taken before compacting brings back the whole conversation.
+    thread
+        .update(cx, |thread, cx| thread.truncate(compact_message_id, cx))
+        .unwrap();
+    thread
+        .update(cx, |thread, cx| {
+            thread.send(UserMessageId::new(), ["Message 3"], cx)
+        })
+        .unwrap();
+    cx.run_until_parked();
+    let completion = fake_model.pending_completions().pop().unwrap();
+    assert_eq!(
+        completion.messages[1..],
+        vec![
+            LanguageModelRequestMessage {
+                role: Role::User,
+                content: vec!["Message 1".into()],
+                cache: false,
+                reasoning_details: None,
+            },
+            LanguageModelRequestMessage {
+                role: Role::Assistant,
+                content: vec!["Response 1".into()],
+                cache: false,
+                reasoning_details: None,
+            },
+            LanguageModelRequestMessage {
+                role: Role::User,
+                content: vec!["Message 3".into()],
+                cache: true,
+                reasoning_details: None,
+            }
+        ]
+    );
+}
+
+#[gpui::test]
+#[cfg(not(windows))]
+async fn test_pre_tool_use_hook_blocks_tool(cx: &mut TestAppContext) {
+    let (ThreadTest { model, thread, .. }, _dir) = setup_with_hooks(
+        cx,
+        agent_settings::AgentHooks {
+            pre_tool_use: vec![hook("echo 'Not allowed' >&2; exit 2")],
+            ..Default::default()
+        },
+    )
+    .await;
+    let fake_model = model.as_fake();
+
+    let mut events = thread
+        .update(cx, |thread, cx| {
+            thread.add_tool(EchoTool);
+            thread.send(UserMessageId::new(), ["abc"], cx)
+        })
+        .unwrap();
+    cx.run_until_parked();
+    fake_model.send_last_completion_stream_event(LanguageModelCompletionEvent::ToolUse(
+        LanguageModelToolUse {
+            id: "tool_id_1".into(),
+            name: EchoTool::NAME.into(),
+            raw_input: json!({"text": "hello"}).to_string(),
+            input: json!({"text": "hello"}),
+            is_input_complete: true,
+            thought_signature: None,
+        },
+    ));
+    fake_model.end_last_completion_stream();
+    assert_eq!(
+        next_tool_call_status(&mut events).await,
+        acp::ToolCallStatus::Failed
+    );
+    cx.run_until_parked();
+
+    // The tool doesn't run, and the model gets the hook's stderr instead.
+    let completion = fake_model.pending_completions().pop().unwrap();
+    assert_eq!(
+        completion.messages.last().unwrap().content,
+        vec![MessageContent::ToolResult(LanguageModelToolResult {
+            tool_use_id: "tool_id_1".into(),
+            tool_name: EchoTool::NAME.into(),
+            is_error: true,
+            content: "Blocked by hook: Not allowed".into(),
+            output: None,
+        })]
+    );
+}
+
+#[gpui::test]
+#[cfg(not(windows))]
+async fn test_post_tool_use_hook_feedback(cx: &mut TestAppContext) {
+    let (ThreadTest { model, thread, .. }, _dir) = setup_with_hooks(
+        cx,
+        agent_settings::AgentHooks {
+            post_tool_use: vec![hook(
+                r#"grep -q '"tool_output":"hello"' && echo 'Looks good' >&2 && exit 2"#,
+            )],
+            ..Default::default()
+        },
+    )
+    .await;
+    let fake_model = model.as_fake();
+
+    let mut events = thread
+        .update(cx, |thread, cx| {
+            thread.add_tool(EchoTool);
+            thread.send(UserMessageId::new(), ["abc"], cx)
+        })
+        .unwrap();
+    cx.run_until_parked();
+    fake_model.send_last_completion_stream_event(LanguageModelCompletionEvent::ToolUse(
+        LanguageModelToolUse {
+            id: "tool_id_1".into(),
+            name: EchoTool::NAME.into(),
+            raw_input: json!({"text": "hello"}).to_string(),
+            input: json!({"text": "hello"}),
+            is_input_complete: true,
+            thought_signature: None,
+        },
+    ));
+    fake_model.end_last_completion_stream();
+    assert_eq!(
+        next_tool_call_status(&mut events).await,
+        acp::ToolCallStatus::Completed
+    );
+    cx.run_until_parked();
+
+    // The hook's stderr is appended to the tool's output.
+    let completion = fake_model.pending_completions().pop().unwrap();
+    assert_eq!(
+        completion.messages.last().unwrap().content,
+        vec![MessageContent::ToolResult(LanguageModelToolResult {
+            tool_use_id: "tool_id_1".into(),
+            tool_name: EchoTool::NAME.into(),
+            is_error: false,
+            content: "hello\n\nHook feedback:\nLooks good".into(),
+            output: Some("hello".into()),
+        })]
+    );
+}
+
+#[gpui::test]
+#[cfg(not(windows))]
+async fn test_post_edit_hook_receives_edited_paths(cx: &mut TestAppContext) {
+    let (thread_test, dir) = setup_with_hooks(
+        cx,
+        agent_settings::AgentHooks {
+            post_edit: vec![hook(r#"grep -o '"paths":\[[^]]*]' >&2; exit 2"#)],
+            ..Default::default()
+        },
+    )
+    .await;
+    let ThreadTest {
+        model, thread, fs, ..
+    } = thread_test;
+    let fake_model = model.as_fake();
+    fs.insert_tree(dir.path(), json!({ "notes.txt": "" })).await;
+    cx.run_until_parked();
+
+    let root_name = dir.path().file_name().unwrap().to_str().unwrap();
+    let mut events = thread
+        .update(cx, |thread, cx| {
+            thread.add_tool(AppendToFileTool {
+                project: thread.project().clone(),
+                action_log: thread.action_log().clone(),
+                path: format!("{root_name}/notes.txt"),
+            });
+            thread.send(UserMessageId::new(), ["abc"], cx)
+        })
+        .unwrap();
+    cx.run_until_parked();
+    fake_model.send_last_completion_stream_event(LanguageModelCompletionEvent::ToolUse(
+        LanguageModelToolUse {
+            id: "tool_id_1".into(),
+            name: AppendToFileTool::NAME.into(),
+            raw_input: json!({"text": "hello"}).to_string(),
+            input: json!({"text": "hello"}),
+            is_input_complete: true,
+            thought_signature: None,
+        },
+    ));
+    fake_model.end_last_completion_stream();
+    assert_eq!(
+        next_tool_call_status(&mut events).await,
+        acp::ToolCallStatus::Completed
+    );
+    cx.run_until_parked();
+
+    // The hook gets the files the tool edited, even though its input doesn't name them.
+    let completion = fake_model.pending_completions().pop().unwrap();
+    let paths = json!({ "paths": [dir.path().join("notes.txt")] }).to_string();
+    let paths = paths.trim_start_matches('{').trim_end_matches('}');
+    let output = format!("Appended to {root_name}/notes.txt");
+    assert_eq!(
+        completion.messages.last().unwrap().content,
+        vec![MessageContent::ToolResult(LanguageModelToolResult {
+            tool_use_id: "tool_id_1".into(),
+            tool_name: AppendToFileTool::NAME.into(),
+            is_error: false,
+            content: format!("{output}\n\nHook feedback:\n{paths}").into(),
+            output: Some(output.into()),
+        })]
+    );
+}
+
+#[gpui::test]
+#[cfg(not(windows))]
+async fn test_end_of_turn_hook_continuations(cx: &mut TestAppContext) {
+    let (ThreadTest { model, thread, .. }, _dir) = setup_with_hooks(
+        cx,
+        agent_settings::AgentHooks {
+            end_of_turn: vec![hook("echo 'Tests are failing' >&2; exit 2")],
+            ..Default::default()
+        },
+    )
+    .await;
+    let fake_model = model.as_fake();
+
+    let mut events = thread
+        .update(cx, |thread, cx| {
+            thread.send(UserMessageId::new(), ["Fix the tests"], cx)
+        })
+        .unwrap();
+    cx.run_until_parked();
+
+    // Every time the model ends its turn, the hook's feedback sends it back to work.
+    for attempt in 0..MAX_END_OF_TURN_HOOK_CONTINUATIONS {
+        fake_model.send_last_completion_stream_text_chunk(format!("Attempt {attempt}"));
+        fake_model.send_last_completion_stream_event(LanguageModelCompletionEvent::Stop(
+            StopReason::EndTurn,
+        ));
+        fake_model.end_last_completion_stream();
+        loop {
+            match events.next().await.unwrap().unwrap() {
+                ThreadEvent::UserMessage(_) => break,
+                ThreadEvent::Stop(reason) => panic!("Turn ended early with {reason:?}"),
+                _ => {}
+            }
+        }
+        cx.run_until_parked();
+
+        let completion = fake_model.pending_completions().pop().unwrap();
+        assert_eq!(
+            completion.messages.last().unwrap().content,
+            vec!["The `end_of_turn` hooks reported:\n\nTests are failing".into()]
+        );
+    }
+
+    // Once the continuations are used up, the turn ends without running the hook again.
+    fake_model.send_last_completion_stream_text_chunk("Giving up");
+    fake_model
+        .send_last_completion_stream_event(LanguageModelCompletionEvent::Stop(StopReason::EndTurn));
+    fake_model.end_last_completion_stream();
+    let events = events.collect::<Vec<_>>().await;
+    assert_eq!(stop_events(events), vec![acp::StopReason::EndTurn]);
+    assert!(fake_model.pending_completions().is_empty());
+}
+
+#[cfg(not(windows))]
+fn hook(command: &str) -> agent_settings::AgentHook {
+    agent_settings::AgentHook {
+        command: command.to_string(),
+        tools: None,
+        timeout: agent_settings::AgentHook::DEFAULT_TIMEOUT,
+    }
+}
+
+/// Sets up a thread with the hooks given, in a project at a real directory for them to run in.
+#[cfg(not(windows))]
+async fn setup_with_hooks(
+    cx: &mut TestAppContext,
+    hooks: agent_settings::AgentHooks,
+) -> (ThreadTest, tempfile::TempDir) {
+    let ThreadTest {
+        model,
+        project_context,
+        fs,
+        ..
+    } = setup(cx, TestModel::Fake).await;
+    let dir = tempfile::tempdir().unwrap();
+    fs.insert_tree(dir.path(), json!({})).await;
+    let project = Project::test(fs.clone(), [dir.path()], cx).await;
+    cx.update(|cx| {
+        let mut settings = agent_settings::AgentSettings::get_global(cx).clone();
+        settings.hooks = hooks;
+        agent_settings::AgentSettings::override_global(settings, cx);
+    });
+
+    let context_server_store = project.read_with(cx, |project, _| project.context_server_store());
+    let context_server_registry =
+        cx.new(|cx| ContextServerRegistry::new(context_server_store.clone(), cx));
+    let thread = cx.new(|cx| {
+        Thread::new(
+            project,
+            project_context.clone(),
+            context_server_registry,
+            Templates::new(),
+            Some(model.clone()),
+            cx,
+        )
+    });
+    let thread_test = ThreadTest {
+        model,
+        thread,
+        project_context,
+        context_server_store,
+        fs,
+    };
+    (thread_test, dir)
+}
+
+/// Waits for the tool call to complete or fail, which takes a while when hooks run around it.
+#[cfg(not(windows))]
+async fn next_tool_call_status(
+    events: &mut UnboundedReceiver<Result<ThreadEvent>>,
+) -> acp::ToolCallStatus {
+    loop {
+        let event = events
+            .next()
+            .await
+            .expect("no tool call status received")
+            .unwrap();
+        if let ThreadEvent::ToolCallUpdate(acp_thread::ToolCallUpdate::UpdateFields(update)) = event
+            && let Some(status @ (acp::ToolCallStatus::Completed | acp::ToolCallStatus::Failed)) =
+                update.fields.status
+        {
+            return status;
+        }
+    }
+}
+
 #[gpui::test]
 async fn test_title_generation(cx: &mut TestAppContext) {
     let ThreadTest { model, thread, .. } = setup(cx, TestModel::Fake).await;
@@ -169,6 +169,68 @@ impl AgentTool for EchoTool {
     }
 }
 
+/// A tool that appends its input to a file it was created with, without naming the file in its input.
+#[derive(JsonSchema, Serialize, Deserialize)]
+pub struct AppendToFileToolInput {
+    /// The text to append.
+    pub text: String,
+}
+
+pub struct AppendToFileTool {
+    pub project: Entity<Project>,
+    pub action_log: Entity<action_log::ActionLog>,
+    pub path: String,
+}
+
+impl AgentTool for AppendToFileTool {
+    type Input = AppendToFileToolInput;
+    type Output = String;
+
+    const NAME: &'static str = "append_to_file";
+
+    fn kind() -> acp::ToolKind {
+        acp::ToolKind::Edit
+    }
+
+    fn initial_title(
+        &self,
+        _input: Result<Self::Input, serde_json::Value>,
+        _cx: &mut App,
+    ) -> SharedString {
+        "Append to File".into()
+    }
+
+    fn run(
+        self: Arc<Self>,
+        input: ToolInput<Self::Input>,
+        _event_stream: ToolCallEventStream,
+        cx: &mut App,
+    ) -> Task<Result<String, String>> {
+        cx.spawn(async move |cx| {
+            let input = input
+                .recv()
+                .await
+                .map_err(|e| format!("Failed to receive tool input: {e}"))?;
+            let project_path = self
+                .project
+                .read_with(cx, |project, cx| project.find_project_path(&self.path, cx))
+                .ok_or_else(|| format!("{} isn't in the project", self.path))?;
+            let buffer = self
+                .project
+                .update(cx, |project, cx| project.open_buffer(project_path, cx))
+                .await
+                .map_err(|e| e.to_string())?;
+            buffer.update(cx, |buffer, cx| {
+                let len = buffer.len();
+                buffer.edit([(len..len, input.text)], None, cx);
+            });
+            self.action_log
+                .update(cx, |action_log, cx| action_log.buffer_edited(buffer, cx));
+            Ok(format!("Appended to {}", self.path))
+        })
+    }
+}
+
 /// A tool that waits for a specified delay
 #[derive(JsonSchema, Serialize, Deserialize)]
 pub struct DelayToolInput {
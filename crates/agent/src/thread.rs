@@ -1,10 +1,12 @@
+use crate::hooks::{self, HookEvent, HookEventKind, Hooks};
 use crate::{
-    ContextServerRegistry, CopyPathTool, CreateDirectoryTool, DbLanguageModel, DbThread,
-    DeletePathTool, DiagnosticsTool, EditFileTool, FetchTool, FindPathTool, GrepTool,
-    ListDirectoryTool, MovePathTool, NowTool, OpenTool, ProjectSnapshot, ReadFileTool,
+    ContextServerRegistry, ContextServerRegistryEvent, CopyPathTool, CreateDirectoryTool,
+    DbLanguageModel, DbThread, DeletePathTool, DiagnosticsTool, EditFileTool, FetchTool,
+    FindPathTool, FindReferencesTool, GoToDefinitionTool, GrepTool, HoverTool, ListDirectoryTool,
+    MovePathTool, NowTool, OpenTool, ProjectSnapshot, ReadFileTool, RenameSymbolTool,
     RestoreFileFromDiskTool, SaveFileTool, SpawnAgentTool, StreamingEditFileTool,
     SystemPromptTemplate, Template, Templates, TerminalTool, ToolPermissionDecision, WebSearchTool,
-    decide_permission_from_settings,
+    WorkspaceSymbolsTool, decide_permission_from_settings,
 };
 use acp_thread::{MentionUri, UserMessageId};
 use action_log::ActionLog;
@@ -12,8 +14,8 @@ use feature_flags::{FeatureFlagAppExt as _, StreamingEditFileToolFeatureFlag};
 
 use agent_client_protocol as acp;
 use agent_settings::{
-    AgentProfileId, AgentProfileSettings, AgentSettings, SUMMARIZE_THREAD_DETAILED_PROMPT,
-    SUMMARIZE_THREAD_PROMPT,
+    AgentProfileId, AgentProfileSettings, AgentSettings, COMPACT_THREAD_PROMPT,
+    SUMMARIZE_THREAD_DETAILED_PROMPT, SUMMARIZE_THREAD_PROMPT,
 };
 use anyhow::{Context as _, Result, anyhow};
 use chrono::{DateTime, Utc};
@@ -21,6 +23,7 @@ use client::UserStore;
 use cloud_api_types::Plan;
 use cloud_llm_client::CompletionIntent;
 use collections::{HashMap, HashSet, IndexMap};
+use context_server::ContextServerId;
 use fs::Fs;
 use futures::stream;
 use futures::{
@@ -30,7 +33,8 @@ use futures::{
     stream::FuturesUnordered,
 };
 use gpui::{
-    App, AppContext, AsyncApp, Context, Entity, EventEmitter, SharedString, Task, WeakEntity,
+    App, AppContext, AsyncApp, Context, Entity, EventEmitter, SharedString, Subscription, Task,
+    WeakEntity,
 };
 use heck::ToSnakeCase as _;
 use language_model::{
@@ -51,6 +55,7 @@ use smol::stream::StreamExt;
 use std::{
     collections::BTreeMap,
     marker::PhantomData,
+    mem,
     ops::RangeInclusive,
     path::Path,
     rc::Rc,
@@ -62,6 +67,16 @@ use util::{ResultExt, debug_panic, markdown::MarkdownCodeBlock, paths::PathStyle
 use uuid::Uuid;
 
 const TOOL_CANCELED_MESSAGE: &str = "Tool canceled by user";
+/// The number of most recent messages kept verbatim when a thread is compacted during a turn.
+const AUTO_COMPACTION_RETAINED_MESSAGES: usize = 2;
+/// A conservative estimate of the bytes of text per token, used to fit the messages summarized
+/// when compacting into the model's context window.
+const COMPACTION_BYTES_PER_TOKEN: u64 = 3;
+/// Replaces the oldest messages that didn't fit in the request summarizing the thread.
+const OMITTED_MESSAGES_NOTE: &str = "[The oldest messages of this conversation were omitted because they didn't fit in the context window.]";
+const TRUNCATED_MESSAGE_NOTE: &str = "\n[truncated]";
+/// The number of times `end_of_turn` hooks can send the agent back to work within a single turn.
+pub(crate) const MAX_END_OF_TURN_HOOK_CONTINUATIONS: usize = 3;
 pub const MAX_TOOL_NAME_LENGTH: usize = 64;
 pub const MAX_SUBAGENT_DEPTH: u8 = 1;
 
@@ -113,6 +128,7 @@ pub enum Message {
     User(UserMessage),
     Agent(AgentMessage),
     Resume,
+    Compaction(CompactionMessage),
 }
 
 impl Message {
@@ -139,6 +155,7 @@ impl Message {
                 cache: false,
                 reasoning_details: None,
             }],
+            Message::Compaction(message) => vec![message.to_request()],
         }
     }
 
@@ -147,12 +164,13 @@ impl Message {
             Message::User(message) => message.to_markdown(),
             Message::Agent(message) => message.to_markdown(),
             Message::Resume => "[resume]\n".into(),
+            Message::Compaction(message) => message.to_markdown(),
         }
     }
 
     pub fn role(&self) -> Role {
         match self {
-            Message::User(_) | Message::Resume => Role::User,
+            Message::User(_) | Message::Resume | Message::Compaction(_) => Role::User,
             Message::Agent(_) => Role::Assistant,
         }
     }
@@ -219,6 +237,7 @@ impl UserMessage {
             "<rules>\nThe user has specified the following rules that should be applied:\n";
         const OPEN_DIAGNOSTICS_TAG: &str = "<diagnostics>";
         const OPEN_DIFFS_TAG: &str = "<diffs>";
+        const OPEN_MCP_RESOURCES_TAG: &str = "<mcp_resources>";
 
         let mut file_context = OPEN_FILES_TAG.to_string();
         let mut directory_context = OPEN_DIRECTORIES_TAG.to_string();
@@ -229,6 +248,7 @@ impl UserMessage {
         let mut rules_context = OPEN_RULES_TAG.to_string();
         let mut diagnostics_context = OPEN_DIAGNOSTICS_TAG.to_string();
         let mut diffs_context = OPEN_DIFFS_TAG.to_string();
+        let mut mcp_resources_context = OPEN_MCP_RESOURCES_TAG.to_string();
 
         for chunk in &self.content {
             let chunk = match chunk {
@@ -336,6 +356,19 @@ impl UserMessage {
                             )
                             .ok();
                         }
+                        MentionUri::McpResource { server_id, uri, .. } => {
+                            write!(
+                                &mut mcp_resources_context,
+                                "\nResource {} from {}:\n{}",
+                                uri,
+                                server_id,
+                                MarkdownCodeBlock {
+                                    tag: "",
+                                    text: content
+                                }
+                            )
+                            .ok();
+                        }
                     }
 
                     language_model::MessageContent::Text(uri.as_link().to_string())
@@ -396,6 +429,13 @@ impl UserMessage {
                 .push(language_model::MessageContent::Text(fetch_context));
         }
 
+        if mcp_resources_context.len() > OPEN_MCP_RESOURCES_TAG.len() {
+            mcp_resources_context.push_str("</mcp_resources>\n");
+            message
+                .content
+                .push(language_model::MessageContent::Text(mcp_resources_context));
+        }
+
         if rules_context.len() > OPEN_RULES_TAG.len() {
             rules_context.push_str("</user_rules>\n");
             message
@@ -594,6 +634,37 @@ pub enum AgentMessageContent {
     ToolUse(LanguageModelToolUse),
 }
 
+/// A recap of the messages that precede it in the thread, generated when the thread is compacted.
+///
+/// The summarized messages are kept so that they can still be displayed and restored from a checkpoint,
+/// but requests to the model only include the latest recap and the messages that follow it.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CompactionMessage {
+    pub summary: String,
+}
+
+impl CompactionMessage {
+    pub fn to_markdown(&self) -> String {
+        format!("{}\n", self.summary)
+    }
+
+    fn to_request(&self) -> LanguageModelRequestMessage {
+        LanguageModelRequestMessage {
+            role: Role::User,
+            content: vec![
+                format!(
+                    "The conversation so far was compacted to fit in your context window. \
+                    Here is a recap of it:\n\n{}",
+                    self.summary
+                )
+                .into(),
+            ],
+            cache: false,
+            reasoning_details: None,
+        }
+    }
+}
+
 pub trait TerminalHandle {
     fn id(&self, cx: &AsyncApp) -> Result<acp::TerminalId>;
     fn current_output(&self, cx: &AsyncApp) -> Result<acp::TerminalOutputResponse>;
@@ -876,6 +947,8 @@ pub struct Thread {
     pending_message: Option<AgentMessage>,
     pub(crate) tools: BTreeMap<SharedString, Arc<dyn AnyAgentTool>>,
     request_token_usage: HashMap<UserMessageId, language_model::TokenUsage>,
+    /// The usage reported by the latest request, or `None` if the thread was compacted since.
+    context_token_usage: Option<language_model::TokenUsage>,
     #[allow(unused)]
     cumulative_token_usage: TokenUsage,
     #[allow(unused)]
@@ -902,6 +975,12 @@ pub struct Thread {
     ui_scroll_position: Option<gpui::ListOffset>,
     /// Weak references to running subagent threads for cancellation propagation
     running_subagents: Vec<WeakEntity<Thread>>,
+    /// Fresh contents of mentioned MCP resources that changed since they were last sent,
+    /// attached to the next user message.
+    updated_resources: IndexMap<MentionUri, String>,
+    /// The MCP resources this thread is subscribed to, unsubscribed from when it's released.
+    subscribed_resources: HashSet<(ContextServerId, String)>,
+    _subscriptions: Vec<Subscription>,
 }
 
 impl Thread {
@@ -977,6 +1056,13 @@ impl Thread {
             .and_then(|model| model.effort.clone());
         let (prompt_capabilities_tx, prompt_capabilities_rx) =
             watch::channel(Self::prompt_capabilities(model.as_deref()));
+        let subscriptions = vec![
+            cx.subscribe(
+                &context_server_registry,
+                Self::handle_context_server_registry_event,
+            ),
+            cx.on_release(Self::unsubscribe_from_resources),
+        ];
         Self {
             id: acp::SessionId::new(uuid::Uuid::new_v4().to_string()),
             prompt_id: PromptId::new(),
@@ -992,6 +1078,7 @@ impl Thread {
             pending_message: None,
             tools: BTreeMap::default(),
             request_token_usage: HashMap::default(),
+            context_token_usage: None,
             cumulative_token_usage: TokenUsage::default(),
             initial_project_snapshot: {
                 let project_snapshot = Self::project_snapshot(project.clone(), cx);
@@ -1017,6 +1104,9 @@ impl Thread {
             draft_prompt: None,
             ui_scroll_position: None,
             running_subagents: Vec::new(),
+            updated_resources: IndexMap::default(),
+            subscribed_resources: HashSet::default(),
+            _subscriptions: subscriptions,
         }
     }
 
@@ -1058,6 +1148,7 @@ impl Thread {
                     }
                 }
                 Message::Resume => {}
+                Message::Compaction(message) => stream.send_compaction(message),
             }
         }
         rx
@@ -1193,6 +1284,15 @@ impl Thread {
             watch::channel(Self::prompt_capabilities(model.as_deref()));
 
         let action_log = cx.new(|_| ActionLog::new(project.clone()));
+        let context_token_usage =
+            Self::usage_since_compaction(&db_thread.messages, &db_thread.request_token_usage);
+        let subscriptions = vec![
+            cx.subscribe(
+                &context_server_registry,
+                Self::handle_context_server_registry_event,
+            ),
+            cx.on_release(Self::unsubscribe_from_resources),
+        ];
 
         Self {
             id,
@@ -1212,6 +1312,7 @@ impl Thread {
             pending_message: None,
             tools: BTreeMap::default(),
             request_token_usage: db_thread.request_token_usage.clone(),
+            context_token_usage,
             cumulative_token_usage: db_thread.cumulative_token_usage,
             initial_project_snapshot: Task::ready(db_thread.initial_project_snapshot).shared(),
             context_server_registry,
@@ -1236,6 +1337,9 @@ impl Thread {
                 offset_in_item: gpui::px(sp.offset_in_item),
             }),
             running_subagents: Vec::new(),
+            updated_resources: IndexMap::default(),
+            subscribed_resources: HashSet::default(),
+            _subscriptions: subscriptions,
         }
     }
 
@@ -1457,7 +1561,10 @@ impl Thread {
         ));
         self.add_tool(FetchTool::new(self.project.read(cx).client().http_client()));
         self.add_tool(FindPathTool::new(self.project.clone()));
+        self.add_tool(FindReferencesTool::new(self.project.clone()));
+        self.add_tool(GoToDefinitionTool::new(self.project.clone()));
         self.add_tool(GrepTool::new(self.project.clone()));
+        self.add_tool(HoverTool::new(self.project.clone()));
         self.add_tool(ListDirectoryTool::new(self.project.clone()));
         self.add_tool(MovePathTool::new(self.project.clone()));
         self.add_tool(NowTool);
@@ -1467,10 +1574,16 @@ impl Thread {
             self.action_log.clone(),
             update_agent_location,
         ));
+        self.add_tool(RenameSymbolTool::new(
+            self.project.clone(),
+            cx.weak_entity(),
+            self.action_log.clone(),
+        ));
         self.add_tool(SaveFileTool::new(self.project.clone()));
         self.add_tool(RestoreFileFromDiskTool::new(self.project.clone()));
         self.add_tool(TerminalTool::new(self.project.clone(), environment.clone()));
         self.add_tool(WebSearchTool);
+        self.add_tool(WorkspaceSymbolsTool::new(self.project.clone()));
 
         if self.depth() < MAX_SUBAGENT_DEPTH {
             self.add_tool(SpawnAgentTool::new(environment));
@@ -1552,6 +1665,7 @@ impl Thread {
 
         self.request_token_usage
             .insert(last_user_message.id.clone(), update);
+        self.context_token_usage = Some(update);
         cx.emit(TokenUsageUpdated(self.latest_token_usage()));
         cx.notify();
     }
@@ -1572,9 +1686,11 @@ impl Thread {
                 Message::User(message) => {
                     self.request_token_usage.remove(&message.id);
                 }
-                Message::Agent(_) | Message::Resume => {}
+                Message::Agent(_) | Message::Resume | Message::Compaction(_) => {}
             }
         }
+        self.context_token_usage =
+            Self::usage_since_compaction(&self.messages, &self.request_token_usage);
         self.clear_summary();
         cx.notify();
         Ok(())
@@ -1620,6 +1736,212 @@ impl Thread {
         None
     }
 
+    /// Returns the usage reported for the latest user message, unless the thread was compacted after it,
+    /// in which case the usage no longer reflects the size of the next request.
+    fn usage_since_compaction(
+        messages: &[Message],
+        request_token_usage: &HashMap<UserMessageId, language_model::TokenUsage>,
+    ) -> Option<language_model::TokenUsage> {
+        messages
+            .iter()
+            .rev()
+            .take_while(|message| !matches!(message, Message::Compaction(_)))
+            .find_map(|message| match message {
+                Message::User(message) => request_token_usage.get(&message.id).copied(),
+                _ => None,
+            })
+    }
+
+    /// The messages that are sent to the model: the latest recap, if any, and the messages after it.
+    fn messages_since_compaction(&self) -> &[Message] {
+        let start_ix = self
+            .messages
+            .iter()
+            .rposition(|message| matches!(message, Message::Compaction(_)))
+            .unwrap_or(0);
+        &self.messages[start_ix..]
+    }
+
+    fn should_auto_compact(&self, model: &Arc<dyn LanguageModel>, cx: &App) -> bool {
+        let settings = AgentSettings::get_global(cx);
+        settings.auto_compact
+            && self.context_token_usage.is_some_and(|usage| {
+                usage.total_tokens() as f64
+                    >= model.max_token_count() as f64 * settings.auto_compact_threshold as f64
+            })
+    }
+
+    /// Compacts the whole thread at the user's request, reporting progress like a turn would.
+    ///
+    /// The message containing the request is summarized along with the rest of the thread,
+    /// so that it can be used to restore the checkpoint taken before compacting.
+    pub fn compact(
+        &mut self,
+        id: UserMessageId,
+        content: Vec<UserMessageContent>,
+        cx: &mut Context<Self>,
+    ) -> Result<mpsc::UnboundedReceiver<Result<ThreadEvent>>> {
+        let model = self.model.clone().context("No language model configured")?;
+        self.flush_pending_message(cx);
+        self.cancel(cx).detach();
+
+        self.messages
+            .push(Message::User(UserMessage { id, content }));
+        let compaction = self.compact_messages(model, self.messages.len(), cx);
+        let (events_tx, events_rx) = mpsc::unbounded::<Result<ThreadEvent>>();
+        let event_stream = ThreadEventStream(events_tx);
+        let (cancellation_tx, mut cancellation_rx) = watch::channel(false);
+        self.running_turn = Some(RunningTurn {
+            event_stream: event_stream.clone(),
+            tools: BTreeMap::default(),
+            cancellation_tx,
+            streaming_tool_inputs: HashMap::default(),
+            _task: cx.spawn(async move |this, cx| {
+                futures::select! {
+                    compaction = compaction.fuse() => match compaction {
+                        Ok(Some(message)) => {
+                            event_stream.send_compaction(&message);
+                            event_stream.send_stop(acp::StopReason::EndTurn);
+                        }
+                        Ok(None) => {
+                            event_stream.send_text("There is nothing to compact yet.");
+                            event_stream.send_stop(acp::StopReason::EndTurn);
+                        }
+                        Err(error) => {
+                            log::error!("Failed to compact thread: {:?}", error);
+                            event_stream.send_error(error);
+                        }
+                    },
+                    _ = cancellation_rx.changed().fuse() => {
+                        // `cancel()` already took the running turn.
+                        return;
+                    }
+                }
+                _ = this.update(cx, |this, _| this.running_turn.take());
+            }),
+        });
+        Ok(events_rx)
+    }
+
+    /// Summarizes the messages since the latest compaction, up to `end_ix`, into a recap that is inserted at `end_ix`.
+    ///
+    /// Resolves to `None` if there was nothing to summarize.
+    fn compact_messages(
+        &mut self,
+        model: Arc<dyn LanguageModel>,
+        end_ix: usize,
+        cx: &mut Context<Self>,
+    ) -> Task<Result<Option<CompactionMessage>>> {
+        let start_ix = self.messages[..end_ix]
+            .iter()
+            .rposition(|message| matches!(message, Message::Compaction(_)))
+            .unwrap_or(0);
+        let messages = &self.messages[start_ix..end_ix];
+        if messages
+            .iter()
+            .all(|message| matches!(message, Message::Compaction(_)))
+        {
+            return Task::ready(Ok(None));
+        }
+
+        let prompt = LanguageModelRequestMessage {
+            role: Role::User,
+            content: vec![COMPACT_THREAD_PROMPT.into()],
+            cache: false,
+            reasoning_details: None,
+        };
+        // The thread may have outgrown the context window, so the messages that are summarized
+        // are cut down to fit in it, leaving room for the prompt and the recap.
+        let budget = model
+            .max_token_count()
+            .saturating_sub(model.max_output_tokens().unwrap_or(0))
+            .saturating_sub(estimate_tokens(&prompt));
+        let mut request = LanguageModelRequest {
+            thread_id: Some(self.id.to_string()),
+            prompt_id: Some(self.prompt_id.to_string()),
+            intent: Some(CompletionIntent::ThreadContextSummarization),
+            temperature: AgentSettings::temperature_for_model(&model, cx),
+            messages: fit_in_token_budget(
+                messages
+                    .iter()
+                    .map(|message| message.to_request())
+                    .collect(),
+                budget,
+            ),
+            ..Default::default()
+        };
+        request.messages.push(prompt);
+
+        log::debug!(
+            "Compacting {} messages with model: {}",
+            messages.len(),
+            model.name().0
+        );
+        cx.spawn(async move |this, cx| {
+            let mut summary = String::new();
+            let mut events = model.stream_completion(request, cx).await?;
+            while let Some(event) = events.next().await {
+                if let LanguageModelCompletionEvent::Text(text) = event? {
+                    summary.push_str(&text);
+                }
+            }
+            let summary = summary.trim();
+            anyhow::ensure!(!summary.is_empty(), "The model returned an empty recap");
+
+            let message = CompactionMessage {
+                summary: summary.to_string(),
+            };
+            this.update(cx, |this, cx| {
+                anyhow::ensure!(
+                    end_ix <= this.messages.len(),
+                    "The thread was truncated while it was being compacted"
+                );
+                this.messages
+                    .insert(end_ix, Message::Compaction(message.clone()));
+                this.context_token_usage = None;
+                this.updated_at = Utc::now();
+                this.clear_summary();
+                cx.notify();
+                anyhow::Ok(())
+            })??;
+            Ok(Some(message))
+        })
+    }
+
+    /// Compacts the thread during a turn, keeping its `retained_messages` most recent messages
+    /// verbatim.
+    ///
+    /// Returns whether the thread was compacted. Failing to compact isn't fatal to the turn,
+    /// which proceeds with the thread as it is.
+    async fn auto_compact(
+        this: &WeakEntity<Self>,
+        model: &Arc<dyn LanguageModel>,
+        retained_messages: usize,
+        event_stream: &ThreadEventStream,
+        cancellation_rx: &mut watch::Receiver<bool>,
+        cx: &mut AsyncApp,
+    ) -> Result<bool> {
+        let compaction = this.update(cx, |this, cx| {
+            let end_ix = this.messages.len().saturating_sub(retained_messages);
+            this.compact_messages(model.clone(), end_ix, cx)
+        })?;
+        let compaction = futures::select! {
+            compaction = compaction.fuse() => compaction,
+            _ = cancellation_rx.changed().fuse() => return Ok(false),
+        };
+        match compaction {
+            Ok(Some(message)) => {
+                event_stream.send_compaction(&message);
+                Ok(true)
+            }
+            Ok(None) => Ok(false),
+            Err(error) => {
+                log::error!("Failed to compact thread: {:?}", error);
+                Ok(false)
+            }
+        }
+    }
+
     /// Look up the active profile and resolve its preferred model if one is configured.
     fn resolve_profile_model(
         profile_id: &AgentProfileId,
@@ -1672,9 +1994,22 @@ impl Thread {
     where
         T: Into<UserMessageContent>,
     {
-        let content = content.into_iter().map(Into::into).collect::<Vec<_>>();
+        let mut content = content.into_iter().map(Into::into).collect::<Vec<_>>();
         log::debug!("Thread::send content: {:?}", content);
 
+        self.subscribe_to_mentioned_resources(&content, cx);
+        for (uri, resource_content) in self.updated_resources.drain(..) {
+            let mentioned_again = content.iter().any(|chunk| {
+                matches!(chunk, UserMessageContent::Mention { uri: mentioned, .. } if *mentioned == uri)
+            });
+            if !mentioned_again {
+                content.push(UserMessageContent::Mention {
+                    uri,
+                    content: resource_content,
+                });
+            }
+        }
+
         self.messages
             .push(Message::User(UserMessage { id, content }));
         cx.notify();
@@ -1682,6 +2017,82 @@ impl Thread {
         self.send_existing(cx)
     }
 
+    fn subscribe_to_mentioned_resources(
+        &mut self,
+        content: &[UserMessageContent],
+        cx: &mut Context<Self>,
+    ) {
+        for chunk in content {
+            if let UserMessageContent::Mention {
+                uri: MentionUri::McpResource { server_id, uri, .. },
+                ..
+            } = chunk
+            {
+                let server_id = ContextServerId(server_id.as_str().into());
+                if !self
+                    .subscribed_resources
+                    .insert((server_id.clone(), uri.clone()))
+                {
+                    continue;
+                }
+                self.context_server_registry.update(cx, |registry, cx| {
+                    registry.subscribe_to_resource(&server_id, uri, cx);
+                });
+            }
+        }
+    }
+
+    fn unsubscribe_from_resources(&mut self, cx: &mut App) {
+        let subscribed_resources = mem::take(&mut self.subscribed_resources);
+        self.context_server_registry.update(cx, |registry, cx| {
+            for (server_id, uri) in &subscribed_resources {
+                registry.unsubscribe_from_resource(server_id, uri, cx);
+            }
+        });
+    }
+
+    fn handle_context_server_registry_event(
+        &mut self,
+        _registry: Entity<ContextServerRegistry>,
+        event: &ContextServerRegistryEvent,
+        cx: &mut Context<Self>,
+    ) {
+        let ContextServerRegistryEvent::ResourceUpdated { server_id, uri } = event else {
+            return;
+        };
+        let Some(mention) = self.messages.iter().find_map(|message| {
+            let Message::User(message) = message else {
+                return None;
+            };
+            message.content.iter().find_map(|chunk| match chunk {
+                UserMessageContent::Mention {
+                    uri:
+                        mention @ MentionUri::McpResource {
+                            server_id: mentioned_server_id,
+                            uri: mentioned_uri,
+                            ..
+                        },
+                    ..
+                } if *mentioned_server_id == *server_id.0 && mentioned_uri == uri => {
+                    Some(mention.clone())
+                }
+                _ => None,
+            })
+        }) else {
+            return;
+        };
+
+        let server_store = self.context_server_registry.read(cx).server_store().clone();
+        let read = crate::read_resource(&server_store, server_id, uri, cx);
+        cx.spawn(async move |this, cx| {
+            let content = read.await?;
+            this.update(cx, |this, _cx| {
+                this.updated_resources.insert(mention, content);
+            })
+        })
+        .detach_and_log_err(cx);
+    }
+
     pub fn send_existing(
         &mut self,
         cx: &mut Context<Self>,
@@ -1824,7 +2235,29 @@ impl Thread {
     ) -> Result<()> {
         let mut attempt = 0;
         let mut intent = CompletionIntent::UserPrompt;
+        let mut compacted_after_error = false;
+        let mut end_of_turn_continuations = 0;
+        // Don't retry for every request of the turn if the thread couldn't be compacted.
+        let mut can_auto_compact = true;
         loop {
+            if can_auto_compact
+                && this.read_with(cx, |this, cx| this.should_auto_compact(&model, cx))?
+            {
+                can_auto_compact = Self::auto_compact(
+                    this,
+                    &model,
+                    AUTO_COMPACTION_RETAINED_MESSAGES,
+                    event_stream,
+                    &mut cancellation_rx,
+                    cx,
+                )
+                .await?;
+                if *cancellation_rx.borrow() {
+                    log::debug!("Turn cancelled during compaction, exiting");
+                    return Ok(());
+                }
+            }
+
             let request =
                 this.update(cx, |this, cx| this.build_completion_request(intent, cx))??;
 
@@ -1978,6 +2411,26 @@ impl Thread {
             }
 
             if let Some(error) = error {
+                // When the thread has outgrown the context window, compact it and try again.
+                if matches!(error, LanguageModelCompletionError::PromptTooLarge { .. })
+                    && !compacted_after_error
+                    && cx.update(|cx| AgentSettings::get_global(cx).auto_compact)
+                {
+                    compacted_after_error = true;
+                    // The most recent messages may be what outgrew the context window, so
+                    // they're summarized too.
+                    let compacted =
+                        Self::auto_compact(this, &model, 0, event_stream, &mut cancellation_rx, cx)
+                            .await?;
+                    if *cancellation_rx.borrow() {
+                        log::debug!("Turn cancelled during compaction, exiting");
+                        return Ok(());
+                    }
+                    if compacted {
+                        continue;
+                    }
+                }
+
                 attempt += 1;
                 let retry = this.update(cx, |this, cx| {
                     let user_store = this.user_store.read(cx);
@@ -2003,6 +2456,37 @@ impl Thread {
                     }
                 })?;
             } else if end_turn {
+                if end_of_turn_continuations < MAX_END_OF_TURN_HOOK_CONTINUATIONS
+                    && let Some(hooks) = this.read_with(cx, |this, cx| {
+                        this.hooks(HookEventKind::EndOfTurn, None, cx)
+                    })?
+                {
+                    let end_of_turn = cx.background_spawn(hooks.run(HookEvent::EndOfTurn));
+                    let feedback = futures::select! {
+                        feedback = end_of_turn.fuse() => feedback,
+                        _ = cancelled(cancellation_rx.clone()).fuse() => {
+                            log::debug!("Turn cancelled during end_of_turn hooks, exiting");
+                            return Ok(());
+                        }
+                    };
+                    if let Some(feedback) = feedback {
+                        end_of_turn_continuations += 1;
+                        this.update(cx, |this, _cx| {
+                            let message = UserMessage {
+                                id: UserMessageId::new(),
+                                content: vec![UserMessageContent::Text(format!(
+                                    "The `end_of_turn` hooks reported:\n\n{feedback}"
+                                ))],
+                            };
+                            event_stream.send_user_message(&message);
+                            this.messages.push(Message::User(message));
+                        })?;
+                        intent = CompletionIntent::UserPrompt;
+                        attempt = 0;
+                        compacted_after_error = false;
+                        continue;
+                    }
+                }
                 return Ok(());
             } else {
                 let has_queued = this.update(cx, |this, _| this.has_queued_message())?;
@@ -2012,6 +2496,7 @@ impl Thread {
                 }
                 intent = CompletionIntent::ToolResults;
                 attempt = 0;
+                compacted_after_error = false;
             }
         }
     }
@@ -2245,7 +2730,10 @@ impl Thread {
         };
 
         if !tool_use.is_input_complete {
-            if tool.supports_input_streaming() {
+            // `pre_tool_use` hooks need the complete input before the tool can run.
+            if tool.supports_input_streaming()
+                && !hooks::has_hooks(HookEventKind::PreToolUse, &tool_use.name, cx)
+            {
                 let running_turn = self.running_turn.as_mut()?;
                 if let Some(sender) = running_turn.streaming_tool_inputs.get(&tool_use.id) {
                     sender.send_partial(tool_use.input);
@@ -2284,6 +2772,19 @@ impl Thread {
             return None;
         }
 
+        if let Some(hooks) = self.hooks(HookEventKind::PreToolUse, Some(tool_use.name.as_ref()), cx)
+        {
+            log::debug!("Running pre_tool_use hooks for tool {}", tool_use.name);
+            return Some(self.run_tool_after_hooks(
+                hooks,
+                tool,
+                tool_use,
+                event_stream,
+                cancellation_rx,
+                cx,
+            ));
+        }
+
         log::debug!("Running tool {}", tool_use.name);
         let tool_input = ToolInput::ready(tool_use.input);
         Some(self.run_tool(
@@ -2318,9 +2819,10 @@ impl Thread {
             acp::ToolCallUpdateFields::new().status(acp::ToolCallStatus::InProgress),
         );
         let supports_images = self.model().is_some_and(|model| model.supports_images());
-        let tool_result = tool.run(tool_input, tool_event_stream, cx);
-        cx.foreground_executor().spawn(async move {
-            let (is_error, output) = match tool_result.await {
+        let edit_count = self.action_log.read(cx).agent_edit_count();
+        let tool_result = tool.run(tool_input, tool_event_stream.clone(), cx);
+        cx.spawn(async move |this, cx| {
+            let (is_error, mut output) = match tool_result.await {
                 Ok(mut output) => {
                     if let LanguageModelToolResultContent::Image(_) = &output.llm_output
                         && !supports_images
@@ -2336,6 +2838,33 @@ impl Thread {
                 Err(output) => (true, output),
             };
 
+            let post_tool_use = this
+                .update(cx, |this, cx| {
+                    this.run_post_tool_use_hooks(
+                        &tool_use_id,
+                        &tool_name,
+                        edit_count,
+                        &output.llm_output,
+                        is_error,
+                        cx,
+                    )
+                })
+                .ok()
+                .flatten();
+            if let Some(post_tool_use) = post_tool_use {
+                let feedback = futures::select! {
+                    feedback = post_tool_use.fuse() => feedback,
+                    _ = tool_event_stream.cancelled_by_user().fuse() => None,
+                };
+                if let Some(feedback) = feedback
+                    && let LanguageModelToolResultContent::Text(text) = &output.llm_output
+                {
+                    output.llm_output = LanguageModelToolResultContent::Text(
+                        format!("{text}\n\nHook feedback:\n{feedback}").into(),
+                    );
+                }
+            }
+
             LanguageModelToolResult {
                 tool_use_id,
                 tool_name,
@@ -2346,6 +2875,146 @@ impl Thread {
         })
     }
 
+    /// Runs the tool once its `pre_tool_use` hooks have completed, unless one of them blocked it.
+    fn run_tool_after_hooks(
+        &self,
+        hooks: Hooks,
+        tool: Arc<dyn AnyAgentTool>,
+        tool_use: LanguageModelToolUse,
+        event_stream: &ThreadEventStream,
+        cancellation_rx: watch::Receiver<bool>,
+        cx: &mut Context<Self>,
+    ) -> Task<LanguageModelToolResult> {
+        let LanguageModelToolUse {
+            id: tool_use_id,
+            name: tool_name,
+            input,
+            ..
+        } = tool_use;
+        let pre_tool_use = cx.background_spawn(hooks.run(HookEvent::PreToolUse {
+            tool_name: tool_name.clone(),
+            tool_input: input.clone(),
+        }));
+        let event_stream = event_stream.clone();
+        cx.spawn(async move |this, cx| {
+            let error = |message: String| LanguageModelToolResult {
+                content: LanguageModelToolResultContent::Text(message.into()),
+                tool_use_id: tool_use_id.clone(),
+                tool_name: tool_name.clone(),
+                is_error: true,
+                output: None,
+            };
+
+            let feedback = futures::select! {
+                feedback = pre_tool_use.fuse() => feedback,
+                _ = cancelled(cancellation_rx.clone()).fuse() => {
+                    return error("Tool call cancelled by user".into());
+                }
+            };
+            if let Some(feedback) = feedback {
+                return error(format!("Blocked by hook: {feedback}"));
+            }
+
+            let tool_result = this.update(cx, |this, cx| {
+                this.run_tool(
+                    tool,
+                    ToolInput::ready(input),
+                    tool_use_id.clone(),
+                    tool_name.clone(),
+                    &event_stream,
+                    cancellation_rx,
+                    cx,
+                )
+            });
+            match tool_result {
+                Ok(tool_result) => tool_result.await,
+                Err(err) => error(err.to_string()),
+            }
+        })
+    }
+
+    /// Runs the `post_edit` hooks if the tool edited files, followed by the `post_tool_use` hooks.
+    fn run_post_tool_use_hooks(
+        &self,
+        tool_use_id: &LanguageModelToolUseId,
+        tool_name: &Arc<str>,
+        edit_count: usize,
+        tool_output: &LanguageModelToolResultContent,
+        is_error: bool,
+        cx: &App,
+    ) -> Option<Task<Option<String>>> {
+        let edited_paths = if is_error {
+            Vec::new()
+        } else {
+            self.edited_paths(edit_count, cx)
+        };
+        let post_edit = (!edited_paths.is_empty())
+            .then(|| self.hooks(HookEventKind::PostEdit, Some(tool_name.as_ref()), cx))
+            .flatten();
+        let post_tool_use = self.hooks(HookEventKind::PostToolUse, Some(tool_name.as_ref()), cx);
+        if post_edit.is_none() && post_tool_use.is_none() {
+            return None;
+        }
+
+        // The recorded tool use holds the final input, including for tools whose input was streamed.
+        let tool_input = self
+            .pending_message
+            .as_ref()
+            .and_then(|message| {
+                message.content.iter().find_map(|content| match content {
+                    AgentMessageContent::ToolUse(tool_use) if &tool_use.id == tool_use_id => {
+                        Some(tool_use.input.clone())
+                    }
+                    _ => None,
+                })
+            })
+            .unwrap_or_default();
+        let post_edit = post_edit.map(|hooks| {
+            hooks.run(HookEvent::PostEdit {
+                tool_name: tool_name.clone(),
+                tool_input: tool_input.clone(),
+                paths: edited_paths,
+            })
+        });
+        let post_tool_use = post_tool_use.map(|hooks| {
+            hooks.run(HookEvent::PostToolUse {
+                tool_name: tool_name.clone(),
+                tool_input,
+                tool_output: match tool_output {
+                    LanguageModelToolResultContent::Text(text) => Some(text.to_string()),
+                    LanguageModelToolResultContent::Image(_) => None,
+                },
+                is_error,
+            })
+        });
+
+        Some(cx.background_spawn(async move {
+            let mut feedback = Vec::new();
+            if let Some(post_edit) = post_edit {
+                feedback.extend(post_edit.await);
+            }
+            if let Some(post_tool_use) = post_tool_use {
+                feedback.extend(post_tool_use.await);
+            }
+            (!feedback.is_empty()).then(|| feedback.join("\n\n"))
+        }))
+    }
+
+    /// Returns the absolute paths of the files that the agent created, edited or deleted since
+    /// the action log counted `edit_count` changes.
+    fn edited_paths(&self, edit_count: usize, cx: &App) -> Vec<PathBuf> {
+        self.action_log
+            .read(cx)
+            .buffers_edited_since(edit_count)
+            .into_iter()
+            .filter_map(|buffer| Some(buffer.read(cx).file()?.as_local()?.abs_path(cx)))
+            .collect()
+    }
+
+    fn hooks(&self, event: HookEventKind, tool_name: Option<&str>, cx: &App) -> Option<Hooks> {
+        Hooks::for_event(event, tool_name, &self.id.to_string(), &self.project, cx)
+    }
+
     fn handle_tool_use_json_parse_error_event(
         &mut self,
         tool_use_id: LanguageModelToolUseId,
@@ -2451,7 +3120,7 @@ impl Thread {
             ..Default::default()
         };
 
-        for message in &self.messages {
+        for message in self.messages_since_compaction() {
             request.messages.extend(message.to_request());
         }
 
@@ -2576,6 +3245,7 @@ impl Thread {
                 Message::User(user_message) => Some(user_message),
                 Message::Agent(_) => None,
                 Message::Resume => None,
+                Message::Compaction(_) => None,
             })
     }
 
@@ -2846,7 +3516,7 @@ impl Thread {
             cache: false,
             reasoning_details: None,
         }];
-        for message in &self.messages {
+        for message in self.messages_since_compaction() {
             messages.extend(message.to_request());
         }
 
@@ -2871,6 +3541,7 @@ impl Thread {
                 Message::User(_) => markdown.push_str("## User\n\n"),
                 Message::Agent(_) => markdown.push_str("## Assistant\n\n"),
                 Message::Resume => {}
+                Message::Compaction(_) => markdown.push_str("## Recap\n\n"),
             }
             markdown.push_str(&message.to_markdown());
         }
@@ -3327,6 +3998,19 @@ where
     }
 }
 
+/// Resolves once the turn is cancelled.
+async fn cancelled(mut cancellation_rx: watch::Receiver<bool>) {
+    loop {
+        if *cancellation_rx.borrow() {
+            return;
+        }
+        if cancellation_rx.changed().await.is_err() {
+            // Sender dropped, will never be cancelled
+            std::future::pending::<()>().await;
+        }
+    }
+}
+
 #[derive(Clone)]
 struct ThreadEventStream(mpsc::UnboundedSender<Result<ThreadEvent>>);
 
@@ -3343,6 +4027,13 @@ impl ThreadEventStream {
             .ok();
     }
 
+    fn send_compaction(&self, message: &CompactionMessage) {
+        self.send_text(&format!(
+            "**Compacted the conversation so far into a recap:**\n\n{}\n\n",
+            message.summary
+        ));
+    }
+
     fn send_thinking(&self, text: &str) {
         self.0
             .unbounded_send(Ok(ThreadEvent::AgentThinking(text.to_string())))
@@ -3472,18 +4163,7 @@ impl ToolCallEventStream {
     /// Returns a future that resolves when the user cancels the tool call.
     /// Tools should select on this alongside their main work to detect user cancellation.
     pub fn cancelled_by_user(&self) -> impl std::future::Future<Output = ()> + '_ {
-        let mut rx = self.cancellation_rx.clone();
-        async move {
-            loop {
-                if *rx.borrow() {
-                    return;
-                }
-                if rx.changed().await.is_err() {
-                    // Sender dropped, will never be cancelled
-                    std::future::pending::<()>().await;
-                }
-            }
-        }
+        cancelled(self.cancellation_rx.clone())
     }
 
     /// Returns true if the user has cancelled this tool call.
@@ -3921,6 +4601,117 @@ impl From<UserMessageContent> for acp::ContentBlock {
     }
 }
 
+/// Roughly estimates the number of tokens a request message takes up.
+fn estimate_tokens(message: &LanguageModelRequestMessage) -> u64 {
+    message.content.iter().map(estimate_content_tokens).sum()
+}
+
+fn estimate_content_tokens(content: &language_model::MessageContent) -> u64 {
+    use language_model::MessageContent;
+
+    match content {
+        MessageContent::Image(image)
+        | MessageContent::ToolResult(LanguageModelToolResult {
+            content: LanguageModelToolResultContent::Image(image),
+            ..
+        }) => image.estimate_tokens() as u64,
+        MessageContent::ToolUse(tool_use) => {
+            tool_use.raw_input.len() as u64 / COMPACTION_BYTES_PER_TOKEN
+        }
+        MessageContent::RedactedThinking(data) => data.len() as u64 / COMPACTION_BYTES_PER_TOKEN,
+        content => content
+            .to_str()
+            .map_or(0, |text| text.len() as u64 / COMPACTION_BYTES_PER_TOKEN),
+    }
+}
+
+/// Keeps the most recent messages that fit in `budget` tokens, replacing the older ones with a
+/// note. Each item of `messages` is kept or omitted as a whole, so that tool uses stay with
+/// their results. When even the most recent one doesn't fit, its text is truncated.
+fn fit_in_token_budget(
+    mut messages: Vec<Vec<LanguageModelRequestMessage>>,
+    budget: u64,
+) -> Vec<LanguageModelRequestMessage> {
+    let total_tokens = |messages: &[LanguageModelRequestMessage]| {
+        messages.iter().map(estimate_tokens).sum::<u64>()
+    };
+    if messages
+        .iter()
+        .map(|messages| total_tokens(messages))
+        .sum::<u64>()
+        <= budget
+    {
+        return messages.into_iter().flatten().collect();
+    }
+
+    let note = LanguageModelRequestMessage {
+        role: Role::User,
+        content: vec![OMITTED_MESSAGES_NOTE.into()],
+        cache: false,
+        reasoning_details: None,
+    };
+    let mut remaining = budget.saturating_sub(estimate_tokens(&note));
+    let mut start_ix = messages.len();
+    while start_ix > 0 {
+        let tokens = total_tokens(&messages[start_ix - 1]);
+        if tokens > remaining {
+            break;
+        }
+        remaining -= tokens;
+        start_ix -= 1;
+    }
+    if start_ix == messages.len()
+        && let Some(mut latest) = messages.pop()
+    {
+        for content in latest
+            .iter_mut()
+            .flat_map(|message| message.content.iter_mut())
+        {
+            remaining = truncate_content(content, remaining);
+        }
+        messages.push(latest);
+        start_ix -= 1;
+    }
+
+    let mut fitted = vec![note];
+    fitted.extend(messages.drain(start_ix..).flatten());
+    fitted
+}
+
+/// Truncates the text of `content` to fit in `budget` tokens, returning the tokens left.
+fn truncate_content(content: &mut language_model::MessageContent, budget: u64) -> u64 {
+    use language_model::MessageContent;
+
+    let tokens = estimate_content_tokens(content);
+    if tokens <= budget {
+        return budget - tokens;
+    }
+    let truncate = |text: &str| {
+        let len = text.floor_char_boundary((budget * COMPACTION_BYTES_PER_TOKEN) as usize);
+        format!("{}{TRUNCATED_MESSAGE_NOTE}", &text[..len])
+    };
+    match content {
+        MessageContent::Text(text) | MessageContent::Thinking { text, .. } => {
+            *text = truncate(text);
+        }
+        MessageContent::ToolResult(LanguageModelToolResult {
+            content: LanguageModelToolResultContent::Text(text),
+            ..
+        }) => {
+            *text = truncate(text).into();
+        }
+        MessageContent::ToolResult(LanguageModelToolResult { content, .. }) => {
+            *content = LanguageModelToolResultContent::Text(TRUNCATED_MESSAGE_NOTE.into());
+        }
+        MessageContent::Image(_) | MessageContent::RedactedThinking(_) => {
+            *content = MessageContent::Text(TRUNCATED_MESSAGE_NOTE.into());
+        }
+        // Tool uses are kept whole, since their input has to stay valid.
+        MessageContent::ToolUse(_) => {}
+    }
+    0
+}
+
 fn convert_image(image_content: acp::ImageContent) -> LanguageModelImage {
     LanguageModelImage {
         source: image_content.data.into(),
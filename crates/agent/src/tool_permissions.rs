@@ -560,6 +560,9 @@ mod tests {
             message_editor_min_lines: 1,
             tool_permissions,
             show_turn_stats: false,
+            auto_compact: true,
+            auto_compact_threshold: 0.8,
+            hooks: Default::default(),
         }
     }
 
@@ -21,9 +21,25 @@ pub struct ContextServerPrompt {
     pub prompt: context_server::types::Prompt,
 }
 
+pub struct ContextServerResource {
+    pub server_id: ContextServerId,
+    pub resource: context_server::types::Resource,
+}
+
+pub struct ContextServerResourceTemplate {
+    pub server_id: ContextServerId,
+    pub template: context_server::types::ResourceTemplate,
+}
+
 pub enum ContextServerRegistryEvent {
     ToolsChanged,
     PromptsChanged,
+    ResourcesChanged,
+    /// A resource the registry subscribed to was updated by its server.
+    ResourceUpdated {
+        server_id: ContextServerId,
+        uri: String,
+    },
 }
 
 impl EventEmitter<ContextServerRegistryEvent> for ContextServerRegistry {}
@@ -37,9 +53,15 @@ pub struct ContextServerRegistry {
 struct RegisteredContextServer {
     tools: BTreeMap<SharedString, Arc<dyn AnyAgentTool>>,
     prompts: BTreeMap<SharedString, ContextServerPrompt>,
+    resources: Vec<ContextServerResource>,
+    resource_templates: Vec<ContextServerResourceTemplate>,
+    /// The number of threads subscribed to each resource.
+    subscribed_resources: HashMap<String, usize>,
     load_tools: Task<Result<()>>,
     load_prompts: Task<Result<()>>,
+    load_resources: Task<Result<()>>,
     _tools_updated_subscription: Option<NotificationSubscription>,
+    _resources_subscriptions: Vec<NotificationSubscription>,
 }
 
 impl ContextServerRegistry {
@@ -52,6 +74,7 @@ impl ContextServerRegistry {
         for server in server_store.read(cx).running_servers() {
             this.reload_tools_for_server(server.id(), cx);
             this.reload_prompts_for_server(server.id(), cx);
+            this.reload_resources_for_server(server.id(), cx);
         }
         this
     }
@@ -102,6 +125,110 @@ impl ContextServerRegistry {
         }
     }
 
+    pub fn resources(&self) -> impl Iterator<Item = &ContextServerResource> {
+        self.registered_servers
+            .values()
+            .flat_map(|server| server.resources.iter())
+    }
+
+    pub fn resource_templates(&self) -> impl Iterator<Item = &ContextServerResourceTemplate> {
+        self.registered_servers
+            .values()
+            .flat_map(|server| server.resource_templates.iter())
+    }
+
+    /// Asks the server to notify us when the resource changes, which is reported with
+    /// [`ContextServerRegistryEvent::ResourceUpdated`].
+    pub fn subscribe_to_resource(
+        &mut self,
+        server_id: &ContextServerId,
+        uri: &str,
+        cx: &mut Context<Self>,
+    ) {
+        let Some(client) = self
+            .server_store
+            .read(cx)
+            .get_running_server(server_id)
+            .and_then(|server| server.client())
+        else {
+            return;
+        };
+        let supports_subscriptions = client
+            .initialize
+            .capabilities
+            .resources
+            .as_ref()
+            .is_some_and(|resources| resources.subscribe == Some(true));
+        if !supports_subscriptions {
+            return;
+        }
+        let Some(uri_to_subscribe) = url::Url::parse(uri).log_err() else {
+            return;
+        };
+
+        let registered_server = self.get_or_register_server(server_id, cx);
+        let subscribers = registered_server
+            .subscribed_resources
+            .entry(uri.to_string())
+            .or_default();
+        *subscribers += 1;
+        if *subscribers > 1 {
+            return;
+        }
+        cx.background_spawn(async move {
+            client
+                .request::<context_server::types::requests::ResourcesSubscribe>(
+                    context_server::types::ResourcesSubscribeParams {
+                        uri: uri_to_subscribe,
+                        meta: None,
+                    },
+                )
+                .await
+        })
+        .detach_and_log_err(cx);
+    }
+
+    /// Undoes a [`Self::subscribe_to_resource`], asking the server to stop notifying us
+    /// once nothing else is subscribed to the resource.
+    pub fn unsubscribe_from_resource(
+        &mut self,
+        server_id: &ContextServerId,
+        uri: &str,
+        cx: &mut Context<Self>,
+    ) {
+        let Some(registered_server) = self.registered_servers.get_mut(server_id) else {
+            return;
+        };
+        let Some(subscribers) = registered_server.subscribed_resources.get_mut(uri) else {
+            return;
+        };
+        *subscribers -= 1;
+        if *subscribers > 0 {
+            return;
+        }
+        registered_server.subscribed_resources.remove(uri);
+
+        let Some(client) = self
+            .server_store
+            .read(cx)
+            .get_running_server(server_id)
+            .and_then(|server| server.client())
+        else {
+            return;
+        };
+        let Some(uri) = url::Url::parse(uri).log_err() else {
+            return;
+        };
+        cx.background_spawn(async move {
+            client
+                .request::<context_server::types::requests::ResourcesUnsubscribe>(
+                    context_server::types::ResourcesUnsubscribeParams { uri, meta: None },
+                )
+                .await
+        })
+        .detach_and_log_err(cx);
+    }
+
     pub fn server_store(&self) -> &Entity<ContextServerStore> {
         &self.server_store
     }
@@ -153,12 +280,72 @@ impl ContextServerRegistry {
                 ))
             });
 
+        let resources_subscriptions = server_store
+            .read(cx)
+            .get_running_server(server_id)
+            .and_then(|server| {
+                let client = server.client()?;
+                if !client.capable(context_server::protocol::ServerCapability::Resources) {
+                    return None;
+                }
+
+                let server_id = server.id();
+                let this = cx.entity().downgrade();
+                Some(vec![
+                    client.on_notification(
+                        "notifications/resources/list_changed",
+                        Box::new({
+                            let server_id = server_id.clone();
+                            let this = this.clone();
+                            move |_params, cx: AsyncApp| {
+                                let server_id = server_id.clone();
+                                let this = this.clone();
+                                cx.spawn(async move |cx| {
+                                    this.update(cx, |this, cx| {
+                                        this.reload_resources_for_server(server_id, cx);
+                                    })
+                                })
+                                .detach();
+                            }
+                        }),
+                    ),
+                    client.on_notification(
+                        "notifications/resources/updated",
+                        Box::new(move |params, cx: AsyncApp| {
+                            let Some(params) = serde_json::from_value::<
+                                context_server::types::ResourcesUpdatedParams,
+                            >(params)
+                            .log_err() else {
+                                return;
+                            };
+                            let server_id = server_id.clone();
+                            let this = this.clone();
+                            cx.spawn(async move |cx| {
+                                this.update(cx, |_, cx| {
+                                    cx.emit(ContextServerRegistryEvent::ResourceUpdated {
+                                        server_id,
+                                        uri: params.uri,
+                                    });
+                                })
+                            })
+                            .detach();
+                        }),
+                    ),
+                ])
+            })
+            .unwrap_or_default();
+
         RegisteredContextServer {
             tools: BTreeMap::default(),
             prompts: BTreeMap::default(),
+            resources: Vec::new(),
+            resource_templates: Vec::new(),
+            subscribed_resources: HashMap::default(),
             load_tools: Task::ready(Ok(())),
             load_prompts: Task::ready(Ok(())),
+            load_resources: Task::ready(Ok(())),
             _tools_updated_subscription: tools_updated_subscription,
+            _resources_subscriptions: resources_subscriptions,
         }
     }
 
@@ -244,6 +431,56 @@ impl ContextServerRegistry {
         });
     }
 
+    fn reload_resources_for_server(&mut self, server_id: ContextServerId, cx: &mut Context<Self>) {
+        let Some(server) = self.server_store.read(cx).get_running_server(&server_id) else {
+            return;
+        };
+        let Some(client) = server.client() else {
+            return;
+        };
+        if !client.capable(context_server::protocol::ServerCapability::Resources) {
+            return;
+        }
+
+        let registered_server = self.get_or_register_server(&server_id, cx);
+
+        registered_server.load_resources = cx.spawn(async move |this, cx| {
+            let resources = client.request::<context_server::types::requests::ResourcesList>(());
+            let templates =
+                client.request::<context_server::types::requests::ListResourceTemplates>(());
+            let (resources, templates) = futures::join!(resources, templates);
+
+            this.update(cx, |this, cx| {
+                let Some(registered_server) = this.registered_servers.get_mut(&server_id) else {
+                    return;
+                };
+
+                registered_server.resources = resources
+                    .log_err()
+                    .map(|response| response.resources)
+                    .unwrap_or_default()
+                    .into_iter()
+                    .map(|resource| ContextServerResource {
+                        server_id: server_id.clone(),
+                        resource,
+                    })
+                    .collect();
+                // Templates are optional, so servers may not implement listing them.
+                registered_server.resource_templates = templates
+                    .map(|response| response.resource_templates)
+                    .unwrap_or_default()
+                    .into_iter()
+                    .map(|template| ContextServerResourceTemplate {
+                        server_id: server_id.clone(),
+                        template,
+                    })
+                    .collect();
+                cx.emit(ContextServerRegistryEvent::ResourcesChanged);
+                cx.notify();
+            })
+        });
+    }
+
     fn handle_context_server_store_event(
         &mut self,
         _: Entity<ContextServerStore>,
@@ -257,6 +494,7 @@ impl ContextServerRegistry {
             ContextServerStatus::Running => {
                 self.reload_tools_for_server(server_id.clone(), cx);
                 self.reload_prompts_for_server(server_id.clone(), cx);
+                self.reload_resources_for_server(server_id.clone(), cx);
             }
             ContextServerStatus::Stopped | ContextServerStatus::Error(_) => {
                 if let Some(registered_server) = self.registered_servers.remove(server_id) {
@@ -266,6 +504,11 @@ impl ContextServerRegistry {
                     if !registered_server.prompts.is_empty() {
                         cx.emit(ContextServerRegistryEvent::PromptsChanged);
                     }
+                    if !registered_server.resources.is_empty()
+                        || !registered_server.resource_templates.is_empty()
+                    {
+                        cx.emit(ContextServerRegistryEvent::ResourcesChanged);
+                    }
                 }
                 cx.notify();
             }
@@ -455,6 +698,44 @@ pub fn get_prompt(
     })
 }
 
+/// Reads the text contents of a resource, skipping any binary contents.
+pub fn read_resource(
+    server_store: &Entity<ContextServerStore>,
+    server_id: &ContextServerId,
+    uri: &str,
+    cx: &mut App,
+) -> Task<Result<String>> {
+    let Some(server) = server_store.read(cx).get_running_server(server_id) else {
+        return Task::ready(Err(anyhow::anyhow!("Context server not found")));
+    };
+    let Some(protocol) = server.client() else {
+        return Task::ready(Err(anyhow::anyhow!("Context server not initialized")));
+    };
+    let uri = match url::Url::parse(uri) {
+        Ok(uri) => uri,
+        Err(error) => return Task::ready(Err(error.into())),
+    };
+
+    cx.background_spawn(async move {
+        let response = protocol
+            .request::<context_server::types::requests::ResourcesRead>(
+                context_server::types::ResourcesReadParams { uri, meta: None },
+            )
+            .await?;
+
+        let mut text = String::new();
+        for contents in response.contents {
+            if let context_server::types::ResourceContentsType::Text(contents) = contents {
+                if !text.is_empty() {
+                    text.push('\n');
+                }
+                text.push_str(&contents.text);
+            }
+        }
+        Ok(text)
+    })
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -0,0 +1,265 @@
+use super::symbol_position::{format_location, resolve_symbol_position};
+use crate::{AgentTool, ToolCallEventStream, ToolInput};
+use agent_client_protocol as acp;
+use anyhow::Result;
+use futures::FutureExt as _;
+use gpui::{App, Entity, SharedString, Task};
+use project::Project;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::{cmp, fmt::Write, sync::Arc};
+use util::markdown::MarkdownInlineCode;
+
+/// Finds all references to a symbol across the project, using the language server of the file the symbol appears in.
+///
+/// Prefer this tool to `grep` when looking for usages of a function, type or variable: it only returns real references, not unrelated text that happens to match.
+/// Results are paginated with 100 references per page. Use the optional 'offset' parameter to request subsequent pages.
+///
+/// <example>
+/// To find all usages of the `Config` struct defined on line 10 of `src/config.rs`:
+/// {
+///     "path": "project/src/config.rs",
+///     "line": 10,
+///     "symbol": "Config"
+/// }
+/// </example>
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
+pub struct FindReferencesToolInput {
+    /// The path of the file containing the symbol, either its definition or any usage of it.
+    ///
+    /// This path should never be absolute, and the first component of the path should always be a root directory in a project.
+    pub path: String,
+    /// The 1-based line number where the symbol appears.
+    pub line: u32,
+    /// The name of the symbol, exactly as it is written on that line.
+    pub symbol: String,
+    /// Optional starting position for paginated results (0-based).
+    /// When not provided, starts from the beginning.
+    #[serde(default)]
+    pub offset: usize,
+}
+
+const RESULTS_PER_PAGE: usize = 100;
+
+pub struct FindReferencesTool {
+    project: Entity<Project>,
+}
+
+impl FindReferencesTool {
+    pub fn new(project: Entity<Project>) -> Self {
+        Self { project }
+    }
+}
+
+impl AgentTool for FindReferencesTool {
+    type Input = FindReferencesToolInput;
+    type Output = String;
+
+    const NAME: &'static str = "find_references";
+
+    fn kind() -> acp::ToolKind {
+        acp::ToolKind::Search
+    }
+
+    fn initial_title(
+        &self,
+        input: Result<Self::Input, serde_json::Value>,
+        _cx: &mut App,
+    ) -> SharedString {
+        if let Ok(input) = input {
+            format!("Find references to {}", MarkdownInlineCode(&input.symbol)).into()
+        } else {
+            "Find references".into()
+        }
+    }
+
+    fn run(
+        self: Arc<Self>,
+        input: ToolInput<Self::Input>,
+        event_stream: ToolCallEventStream,
+        cx: &mut App,
+    ) -> Task<Result<Self::Output, Self::Output>> {
+        let project = self.project.clone();
+        cx.spawn(async move |cx| {
+            let input = input
+                .recv()
+                .await
+                .map_err(|e| format!("Failed to receive tool input: {e}"))?;
+
+            let symbol =
+                resolve_symbol_position(&project, &input.path, input.line, &input.symbol, cx)
+                    .await
+                    .map_err(|e| e.to_string())?;
+            let references_task = project.update(cx, |project, cx| {
+                project.references(&symbol.buffer, symbol.position, cx)
+            });
+            let references = futures::select! {
+                result = references_task.fuse() => result.map_err(|e| e.to_string())?,
+                _ = event_stream.cancelled_by_user().fuse() => {
+                    return Err("Finding references cancelled by user".to_string());
+                }
+            };
+
+            let references = references.unwrap_or_default();
+            event_stream.update_fields(acp::ToolCallUpdateFields::new().title(
+                match references.len() {
+                    0 => "No references".to_string(),
+                    1 => "1 reference".to_string(),
+                    len => format!("{len} references"),
+                },
+            ));
+            if references.is_empty() {
+                return Ok(format!("No references found for `{}`.", input.symbol));
+            }
+
+            let page = &references[cmp::min(input.offset, references.len())
+                ..cmp::min(input.offset + RESULTS_PER_PAGE, references.len())];
+            Ok(cx.update(|cx| {
+                let mut output = format!(
+                    "Found {} references to `{}`.\n",
+                    references.len(),
+                    input.symbol
+                );
+                if references.len() > RESULTS_PER_PAGE {
+                    writeln!(
+                        output,
+                        "Showing results {}-{} (provide 'offset' parameter for more results):",
+                        input.offset + 1,
+                        input.offset + page.len()
+                    )
+                    .ok();
+                }
+                for reference in page {
+                    writeln!(
+                        output,
+                        "{}",
+                        format_location(&reference.buffer, &reference.range, cx)
+                    )
+                    .ok();
+                }
+                output
+            }))
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use gpui::TestAppContext;
+    use language::FakeLspAdapter;
+    use project::FakeFs;
+    use serde_json::json;
+    use settings::SettingsStore;
+    use util::path;
+
+    #[gpui::test]
+    async fn test_find_references(cx: &mut TestAppContext) {
+        init_test(cx);
+
+        let fs = FakeFs::new(cx.executor());
+        fs.insert_tree(
+            path!("/root"),
+            json!({
+                "lib.rs": "pub struct Config;\n",
+                "main.rs": "fn main() {\n    let config = lib::Config;\n}\n",
+            }),
+        )
+        .await;
+        let project = Project::test(fs, [path!("/root").as_ref()], cx).await;
+        let language_registry = project.read_with(cx, |project, _| project.languages().clone());
+        language_registry.add(language::rust_lang());
+        // The server isn't started up front: the tool waits for it to start.
+        let _fake_servers = language_registry.register_fake_lsp(
+            "Rust",
+            FakeLspAdapter {
+                capabilities: lsp::ServerCapabilities {
+                    references_provider: Some(lsp::OneOf::Left(true)),
+                    ..Default::default()
+                },
+                initializer: Some(Box::new(|fake_server| {
+                    fake_server.set_request_handler::<lsp::request::References, _, _>(
+                        |params, _| async move {
+                            assert_eq!(
+                                params.text_document_position.position,
+                                lsp::Position::new(1, 22)
+                            );
+                            let location = |path: &str, line, character| {
+                                lsp::Location::new(
+                                    lsp::Uri::from_file_path(path).unwrap(),
+                                    lsp::Range::new(
+                                        lsp::Position::new(line, character),
+                                        lsp::Position::new(line, character + 6),
+                                    ),
+                                )
+                            };
+                            Ok(Some(vec![
+                                location(path!("/root/lib.rs"), 0, 11),
+                                location(path!("/root/main.rs"), 1, 22),
+                            ]))
+                        },
+                    );
+                })),
+                ..Default::default()
+            },
+        );
+
+        let tool = Arc::new(FindReferencesTool::new(project));
+        let output = cx
+            .update(|cx| {
+                tool.run(
+                    ToolInput::resolved(FindReferencesToolInput {
+                        path: "root/main.rs".into(),
+                        line: 2,
+                        symbol: "Config".into(),
+                        offset: 0,
+                    }),
+                    ToolCallEventStream::test().0,
+                    cx,
+                )
+            })
+            .await
+            .unwrap();
+        assert!(
+            output.starts_with("Found 2 references to `Config`.\n"),
+            "unexpected output: {output}"
+        );
+        assert!(output.contains("root/lib.rs:1: pub struct Config;\n"));
+        assert!(output.contains("root/main.rs:2: let config = lib::Config;\n"));
+    }
+
+    #[gpui::test]
+    async fn test_find_references_without_language_server(cx: &mut TestAppContext) {
+        init_test(cx);
+
+        let fs = FakeFs::new(cx.executor());
+        fs.insert_tree(path!("/root"), json!({ "notes.txt": "Config\n" }))
+            .await;
+        let project = Project::test(fs, [path!("/root").as_ref()], cx).await;
+
+        let tool = Arc::new(FindReferencesTool::new(project));
+        let error = cx
+            .update(|cx| {
+                tool.run(
+                    ToolInput::resolved(FindReferencesToolInput {
+                        path: "root/notes.txt".into(),
+                        line: 1,
+                        symbol: "Config".into(),
+                        offset: 0,
+                    }),
+                    ToolCallEventStream::test().0,
+                    cx,
+                )
+            })
+            .await
+            .unwrap_err();
+        assert_eq!(error, "No language server is available for root/notes.txt");
+    }
+
+    fn init_test(cx: &mut TestAppContext) {
+        cx.update(|cx| {
+            let settings_store = SettingsStore::test(cx);
+            cx.set_global(settings_store);
+        });
+    }
+}
@@ -0,0 +1,205 @@
+use super::symbol_position::{format_location, resolve_symbol_position};
+use crate::{AgentTool, ToolCallEventStream, ToolInput};
+use agent_client_protocol as acp;
+use anyhow::Result;
+use futures::FutureExt as _;
+use gpui::{App, Entity, SharedString, Task};
+use project::Project;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::{fmt::Write, sync::Arc};
+use util::markdown::MarkdownInlineCode;
+
+/// Finds where a symbol is defined, using the language server of the file the symbol appears in.
+///
+/// This is more precise than searching the codebase with `grep`, since the language server understands imports, scopes and overloads.
+///
+/// <example>
+/// To find the definition of `parse_config` called on line 42 of `src/main.rs`:
+/// {
+///     "path": "project/src/main.rs",
+///     "line": 42,
+///     "symbol": "parse_config"
+/// }
+/// </example>
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
+pub struct GoToDefinitionToolInput {
+    /// The path of the file containing a usage of the symbol.
+    ///
+    /// This path should never be absolute, and the first component of the path should always be a root directory in a project.
+    pub path: String,
+    /// The 1-based line number where the symbol appears.
+    pub line: u32,
+    /// The name of the symbol, exactly as it is written on that line.
+    pub symbol: String,
+}
+
+pub struct GoToDefinitionTool {
+    project: Entity<Project>,
+}
+
+impl GoToDefinitionTool {
+    pub fn new(project: Entity<Project>) -> Self {
+        Self { project }
+    }
+}
+
+impl AgentTool for GoToDefinitionTool {
+    type Input = GoToDefinitionToolInput;
+    type Output = String;
+
+    const NAME: &'static str = "go_to_definition";
+
+    fn kind() -> acp::ToolKind {
+        acp::ToolKind::Search
+    }
+
+    fn initial_title(
+        &self,
+        input: Result<Self::Input, serde_json::Value>,
+        _cx: &mut App,
+    ) -> SharedString {
+        if let Ok(input) = input {
+            format!("Go to definition of {}", MarkdownInlineCode(&input.symbol)).into()
+        } else {
+            "Go to definition".into()
+        }
+    }
+
+    fn run(
+        self: Arc<Self>,
+        input: ToolInput<Self::Input>,
+        event_stream: ToolCallEventStream,
+        cx: &mut App,
+    ) -> Task<Result<Self::Output, Self::Output>> {
+        let project = self.project.clone();
+        cx.spawn(async move |cx| {
+            let input = input
+                .recv()
+                .await
+                .map_err(|e| format!("Failed to receive tool input: {e}"))?;
+
+            let symbol =
+                resolve_symbol_position(&project, &input.path, input.line, &input.symbol, cx)
+                    .await
+                    .map_err(|e| e.to_string())?;
+            let definitions_task = project.update(cx, |project, cx| {
+                project.definitions(&symbol.buffer, symbol.position, cx)
+            });
+            let definitions = futures::select! {
+                result = definitions_task.fuse() => result.map_err(|e| e.to_string())?,
+                _ = event_stream.cancelled_by_user().fuse() => {
+                    return Err("Go to definition cancelled by user".to_string());
+                }
+            };
+
+            let definitions = definitions.unwrap_or_default();
+            if definitions.is_empty() {
+                return Ok(format!(
+                    "No definition found for `{}`. The language server may still be starting, or the file may not have one.",
+                    input.symbol
+                ));
+            }
+
+            Ok(cx.update(|cx| {
+                let mut output = format!("Definitions of `{}`:\n", input.symbol);
+                for definition in &definitions {
+                    writeln!(
+                        output,
+                        "{}",
+                        format_location(&definition.target.buffer, &definition.target.range, cx)
+                    )
+                    .ok();
+                }
+                output
+            }))
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use futures::StreamExt as _;
+    use gpui::TestAppContext;
+    use language::FakeLspAdapter;
+    use project::FakeFs;
+    use serde_json::json;
+    use settings::SettingsStore;
+    use util::path;
+
+    #[gpui::test]
+    async fn test_go_to_definition(cx: &mut TestAppContext) {
+        init_test(cx);
+
+        let fs = FakeFs::new(cx.executor());
+        fs.insert_tree(
+            path!("/root"),
+            json!({
+                "lib.rs": "pub struct Config;\n",
+                "main.rs": "fn main() {\n    let config = lib::Config;\n}\n",
+            }),
+        )
+        .await;
+        let project = Project::test(fs, [path!("/root").as_ref()], cx).await;
+        let language_registry = project.read_with(cx, |project, _| project.languages().clone());
+        language_registry.add(language::rust_lang());
+        let mut fake_servers = language_registry.register_fake_lsp(
+            "Rust",
+            FakeLspAdapter {
+                capabilities: lsp::ServerCapabilities {
+                    definition_provider: Some(lsp::OneOf::Left(true)),
+                    ..Default::default()
+                },
+                ..Default::default()
+            },
+        );
+
+        let (_buffer, _handle) = project
+            .update(cx, |project, cx| {
+                project.open_local_buffer_with_lsp(path!("/root/main.rs"), cx)
+            })
+            .await
+            .unwrap();
+        let fake_server = fake_servers.next().await.unwrap();
+        fake_server.set_request_handler::<lsp::request::GotoDefinition, _, _>(
+            |params, _| async move {
+                assert_eq!(
+                    params.text_document_position_params.position,
+                    lsp::Position::new(1, 22)
+                );
+                Ok(Some(lsp::GotoDefinitionResponse::Scalar(lsp::Location {
+                    uri: lsp::Uri::from_file_path(path!("/root/lib.rs")).unwrap(),
+                    range: lsp::Range::new(lsp::Position::new(0, 11), lsp::Position::new(0, 17)),
+                })))
+            },
+        );
+
+        let tool = Arc::new(GoToDefinitionTool::new(project));
+        let output = cx
+            .update(|cx| {
+                tool.run(
+                    ToolInput::resolved(GoToDefinitionToolInput {
+                        path: "root/main.rs".into(),
+                        line: 2,
+                        symbol: "Config".into(),
+                    }),
+                    ToolCallEventStream::test().0,
+                    cx,
+                )
+            })
+            .await
+            .unwrap();
+        assert_eq!(
+            output,
+            "Definitions of `Config`:\nroot/lib.rs:1: pub struct Config;\n"
+        );
+    }
+
+    fn init_test(cx: &mut TestAppContext) {
+        cx.update(|cx| {
+            let settings_store = SettingsStore::test(cx);
+            cx.set_global(settings_store);
+        });
+    }
+}
@@ -0,0 +1,193 @@
+use super::symbol_position::resolve_symbol_position;
+use crate::{AgentTool, ToolCallEventStream, ToolInput};
+use agent_client_protocol as acp;
+use anyhow::Result;
+use futures::FutureExt as _;
+use gpui::{App, Entity, SharedString, Task};
+use project::Project;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+use util::markdown::MarkdownInlineCode;
+
+/// Shows what the language server knows about a symbol at a position: usually its type or signature, and its documentation.
+///
+/// Use this to learn the inferred type of a variable or the signature of a function without reading its definition.
+///
+/// <example>
+/// To get the type of the `result` variable on line 27 of `src/lib.rs`:
+/// {
+///     "path": "project/src/lib.rs",
+///     "line": 27,
+///     "symbol": "result"
+/// }
+/// </example>
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
+pub struct HoverToolInput {
+    /// The path of the file containing the symbol.
+    ///
+    /// This path should never be absolute, and the first component of the path should always be a root directory in a project.
+    pub path: String,
+    /// The 1-based line number where the symbol appears.
+    pub line: u32,
+    /// The name of the symbol, exactly as it is written on that line.
+    pub symbol: String,
+}
+
+pub struct HoverTool {
+    project: Entity<Project>,
+}
+
+impl HoverTool {
+    pub fn new(project: Entity<Project>) -> Self {
+        Self { project }
+    }
+}
+
+impl AgentTool for HoverTool {
+    type Input = HoverToolInput;
+    type Output = String;
+
+    const NAME: &'static str = "hover";
+
+    fn kind() -> acp::ToolKind {
+        acp::ToolKind::Read
+    }
+
+    fn initial_title(
+        &self,
+        input: Result<Self::Input, serde_json::Value>,
+        _cx: &mut App,
+    ) -> SharedString {
+        if let Ok(input) = input {
+            format!("Inspect {}", MarkdownInlineCode(&input.symbol)).into()
+        } else {
+            "Inspect symbol".into()
+        }
+    }
+
+    fn run(
+        self: Arc<Self>,
+        input: ToolInput<Self::Input>,
+        event_stream: ToolCallEventStream,
+        cx: &mut App,
+    ) -> Task<Result<Self::Output, Self::Output>> {
+        let project = self.project.clone();
+        cx.spawn(async move |cx| {
+            let input = input
+                .recv()
+                .await
+                .map_err(|e| format!("Failed to receive tool input: {e}"))?;
+
+            let symbol =
+                resolve_symbol_position(&project, &input.path, input.line, &input.symbol, cx)
+                    .await
+                    .map_err(|e| e.to_string())?;
+            let hover_task = project.update(cx, |project, cx| {
+                project.hover(&symbol.buffer, symbol.position, cx)
+            });
+            let hovers = futures::select! {
+                hovers = hover_task.fuse() => hovers.unwrap_or_default(),
+                _ = event_stream.cancelled_by_user().fuse() => {
+                    return Err("Hover cancelled by user".to_string());
+                }
+            };
+
+            let output = hovers
+                .iter()
+                .flat_map(|hover| &hover.contents)
+                .map(|block| block.text.trim())
+                .filter(|text| !text.is_empty())
+                .collect::<Vec<_>>()
+                .join("\n\n");
+            if output.is_empty() {
+                Ok(format!(
+                    "The language server has no information about `{}`.",
+                    input.symbol
+                ))
+            } else {
+                Ok(output)
+            }
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use gpui::TestAppContext;
+    use language::FakeLspAdapter;
+    use project::FakeFs;
+    use serde_json::json;
+    use settings::SettingsStore;
+    use util::path;
+
+    #[gpui::test]
+    async fn test_hover(cx: &mut TestAppContext) {
+        init_test(cx);
+
+        let fs = FakeFs::new(cx.executor());
+        fs.insert_tree(
+            path!("/root"),
+            json!({
+                "main.rs": "fn main() {\n    let config = load_config();\n}\n",
+            }),
+        )
+        .await;
+        let project = Project::test(fs, [path!("/root").as_ref()], cx).await;
+        let language_registry = project.read_with(cx, |project, _| project.languages().clone());
+        language_registry.add(language::rust_lang());
+        // The server isn't started up front: the tool waits for it to start.
+        let _fake_servers = language_registry.register_fake_lsp(
+            "Rust",
+            FakeLspAdapter {
+                capabilities: lsp::ServerCapabilities {
+                    hover_provider: Some(lsp::HoverProviderCapability::Simple(true)),
+                    ..Default::default()
+                },
+                initializer: Some(Box::new(|fake_server| {
+                    fake_server.set_request_handler::<lsp::request::HoverRequest, _, _>(
+                        |params, _| async move {
+                            assert_eq!(
+                                params.text_document_position_params.position,
+                                lsp::Position::new(1, 8)
+                            );
+                            Ok(Some(lsp::Hover {
+                                contents: lsp::HoverContents::Markup(lsp::MarkupContent {
+                                    kind: lsp::MarkupKind::PlainText,
+                                    value: "let config: Config".to_string(),
+                                }),
+                                range: None,
+                            }))
+                        },
+                    );
+                })),
+                ..Default::default()
+            },
+        );
+
+        let tool = Arc::new(HoverTool::new(project));
+        let output = cx
+            .update(|cx| {
+                tool.run(
+                    ToolInput::resolved(HoverToolInput {
+                        path: "root/main.rs".into(),
+                        line: 2,
+                        symbol: "config".into(),
+                    }),
+                    ToolCallEventStream::test().0,
+                    cx,
+                )
+            })
+            .await
+            .unwrap();
+        assert_eq!(output, "let config: Config");
+    }
+
+    fn init_test(cx: &mut TestAppContext) {
+        cx.update(|cx| {
+            let settings_store = SettingsStore::test(cx);
+            cx.set_global(settings_store);
+        });
+    }
+}
@@ -0,0 +1,529 @@
+use super::symbol_position::resolve_symbol_position;
+use super::tool_permissions::authorize_file_edit;
+use crate::{AgentTool, Thread, ToolCallEventStream, ToolInput};
+use action_log::ActionLog;
+use agent_client_protocol as acp;
+use anyhow::{Context as _, Result};
+use collections::HashSet;
+use gpui::{App, Entity, SharedString, Task, WeakEntity};
+use project::Project;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::{fmt::Write, sync::Arc};
+use util::markdown::MarkdownInlineCode;
+
+/// Renames a symbol and updates every reference to it across the project, using the language server of the file the symbol appears in.
+///
+/// Prefer this tool to editing each usage by hand: the language server knows exactly which occurrences refer to the symbol, including ones in other files.
+/// The renamed files are saved unless they had unsaved changes before the rename.
+///
+/// <example>
+/// To rename the `parse_config` function defined on line 12 of `src/config.rs` to `load_config`:
+/// {
+///     "path": "project/src/config.rs",
+///     "line": 12,
+///     "symbol": "parse_config",
+///     "new_name": "load_config"
+/// }
+/// </example>
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
+pub struct RenameSymbolToolInput {
+    /// The path of the file containing the symbol, either its definition or any usage of it.
+    ///
+    /// This path should never be absolute, and the first component of the path should always be a root directory in a project.
+    pub path: String,
+    /// The 1-based line number where the symbol appears.
+    pub line: u32,
+    /// The current name of the symbol, exactly as it is written on that line.
+    pub symbol: String,
+    /// The new name for the symbol.
+    pub new_name: String,
+}
+
+pub struct RenameSymbolTool {
+    project: Entity<Project>,
+    thread: WeakEntity<Thread>,
+    action_log: Entity<ActionLog>,
+}
+
+impl RenameSymbolTool {
+    pub fn new(
+        project: Entity<Project>,
+        thread: WeakEntity<Thread>,
+        action_log: Entity<ActionLog>,
+    ) -> Self {
+        Self {
+            project,
+            thread,
+            action_log,
+        }
+    }
+}
+
+impl AgentTool for RenameSymbolTool {
+    type Input = RenameSymbolToolInput;
+    type Output = String;
+
+    const NAME: &'static str = "rename_symbol";
+
+    fn kind() -> acp::ToolKind {
+        acp::ToolKind::Edit
+    }
+
+    fn initial_title(
+        &self,
+        input: Result<Self::Input, serde_json::Value>,
+        _cx: &mut App,
+    ) -> SharedString {
+        if let Ok(input) = input {
+            format!(
+                "Rename {} to {}",
+                MarkdownInlineCode(&input.symbol),
+                MarkdownInlineCode(&input.new_name)
+            )
+            .into()
+        } else {
+            "Rename symbol".into()
+        }
+    }
+
+    fn run(
+        self: Arc<Self>,
+        input: ToolInput<Self::Input>,
+        event_stream: ToolCallEventStream,
+        cx: &mut App,
+    ) -> Task<Result<Self::Output, Self::Output>> {
+        cx.spawn(async move |cx| {
+            let input = input
+                .recv()
+                .await
+                .map_err(|e| format!("Failed to receive tool input: {e}"))?;
+
+            let result: Result<String> = async {
+                let symbol = resolve_symbol_position(
+                    &self.project,
+                    &input.path,
+                    input.line,
+                    &input.symbol,
+                    cx,
+                )
+                .await?;
+                let action = self
+                    .project
+                    .update(cx, |project, cx| {
+                        project.rename_edit(
+                            symbol.buffer.clone(),
+                            symbol.position,
+                            input.new_name.clone(),
+                            cx,
+                        )
+                    })
+                    .await
+                    .context("The language server failed to rename the symbol")?;
+                let abs_paths = action
+                    .as_ref()
+                    .map(|action| action.lsp_action.workspace_edit_paths())
+                    .unwrap_or_default();
+                let Some(action) = action.filter(|_| !abs_paths.is_empty()) else {
+                    anyhow::bail!(
+                        "The language server did not produce any edits for renaming `{}`",
+                        input.symbol
+                    );
+                };
+
+                // Every file the rename touches is authorized before any of them is changed.
+                let description = format!("Rename `{}` to `{}`", input.symbol, input.new_name);
+                let mut existing_paths = Vec::new();
+                for abs_path in &abs_paths {
+                    let (path, project_path) = self.project.read_with(cx, |project, cx| {
+                        let project_path = project.find_project_path(abs_path, cx);
+                        let path = project_path
+                            .as_ref()
+                            .and_then(|project_path| {
+                                let worktree =
+                                    project.worktree_for_id(project_path.worktree_id, cx)?;
+                                Some(
+                                    worktree
+                                        .read(cx)
+                                        .root_name()
+                                        .join(&project_path.path)
+                                        .as_std_path()
+                                        .to_path_buf(),
+                                )
+                            })
+                            .unwrap_or_else(|| abs_path.clone());
+                        let project_path = project_path.filter(|project_path| {
+                            project.entry_for_path(project_path, cx).is_some()
+                        });
+                        (path, project_path)
+                    });
+                    cx.update(|cx| {
+                        authorize_file_edit(
+                            Self::NAME,
+                            &path,
+                            &description,
+                            &self.thread,
+                            &event_stream,
+                            cx,
+                        )
+                    })
+                    .await?;
+                    existing_paths.extend(project_path);
+                }
+
+                // The files that exist are read into the action log before the rename changes
+                // them, so that the rename can be reviewed like any other agent edit.
+                let mut dirty_buffers = HashSet::default();
+                for project_path in existing_paths {
+                    let buffer = self
+                        .project
+                        .update(cx, |project, cx| project.open_buffer(project_path, cx))
+                        .await?;
+                    cx.update(|cx| {
+                        if buffer.read(cx).is_dirty() {
+                            dirty_buffers.insert(buffer.clone());
+                        }
+                        self.action_log
+                            .update(cx, |log, cx| log.buffer_read(buffer, cx));
+                    });
+                }
+
+                let transaction = self
+                    .project
+                    .update(cx, |project, cx| {
+                        project.apply_code_action(symbol.buffer.clone(), action, true, cx)
+                    })
+                    .await
+                    .context("Failed to apply the rename")?;
+
+                let (buffers_to_save, paths) = cx.update(|cx| {
+                    event_stream.update_fields(
+                        acp::ToolCallUpdateFields::new().locations(
+                            abs_paths
+                                .iter()
+                                .cloned()
+                                .map(acp::ToolCallLocation::new)
+                                .collect::<Vec<_>>(),
+                        ),
+                    );
+                    let buffers_to_save = transaction
+                        .0
+                        .keys()
+                        .filter(|buffer| !dirty_buffers.contains(*buffer))
+                        .cloned()
+                        .collect::<HashSet<_>>();
+                    let mut paths = transaction
+                        .0
+                        .keys()
+                        .map(|buffer| {
+                            self.action_log
+                                .update(cx, |log, cx| log.buffer_edited(buffer.clone(), cx));
+                            let path = buffer
+                                .read(cx)
+                                .file()
+                                .map(|file| file.full_path(cx).to_string_lossy().into_owned())
+                                .unwrap_or_else(|| "untitled".to_string());
+                            (path, buffers_to_save.contains(buffer))
+                        })
+                        .collect::<Vec<_>>();
+                    paths.sort();
+                    (buffers_to_save, paths)
+                });
+
+                self.project
+                    .update(cx, |project, cx| project.save_buffers(buffers_to_save, cx))
+                    .await?;
+
+                let mut output = format!(
+                    "Renamed `{}` to `{}` in {} files:",
+                    input.symbol,
+                    input.new_name,
+                    paths.len()
+                );
+                for (path, saved) in paths {
+                    write!(output, "\n{path}").ok();
+                    if !saved {
+                        output
+                            .push_str(" (left unsaved, it had unsaved changes before the rename)");
+                    }
+                }
+                Ok(output)
+            }
+            .await;
+
+            result.map_err(|e| e.to_string())
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{ContextServerRegistry, Templates};
+    use futures::StreamExt as _;
+    use gpui::TestAppContext;
+    use language::{Buffer, FakeLspAdapter};
+    use language_model::fake_provider::FakeLanguageModel;
+    use project::{FakeFs, lsp_store::OpenLspBufferHandle};
+    use prompt_store::ProjectContext;
+    use serde_json::json;
+    use settings::{Settings as _, SettingsStore};
+    use util::path;
+
+    #[gpui::test]
+    async fn test_rename_symbol_tracks_edits(cx: &mut TestAppContext) {
+        init_test(cx);
+        let test = setup_rename(cx).await;
+        let (fs, action_log) = (test.fs.clone(), test.action_log.clone());
+
+        let output = test.run(cx).await.unwrap();
+        assert!(output.contains("in 2 files"), "unexpected output: {output}");
+        assert_eq!(
+            fs.load(path!("/root/lib.rs").as_ref()).await.unwrap(),
+            "pub fn new_name() {}\n"
+        );
+        assert_eq!(
+            fs.load(path!("/root/main.rs").as_ref()).await.unwrap(),
+            "fn main() {\n    lib::new_name();\n}\n"
+        );
+
+        cx.run_until_parked();
+        let changed_buffers = action_log.read_with(cx, |log, cx| {
+            log.changed_buffers(cx)
+                .into_iter()
+                .map(|(buffer, diff)| {
+                    let path = buffer.read(cx).file().unwrap().full_path(cx);
+                    let hunks = diff
+                        .read(cx)
+                        .snapshot(cx)
+                        .hunks(&buffer.read(cx).snapshot())
+                        .count();
+                    (path, hunks)
+                })
+                .collect::<Vec<_>>()
+        });
+        assert_eq!(
+            changed_buffers.len(),
+            2,
+            "both renamed files should be reviewable, got {changed_buffers:?}"
+        );
+        assert!(changed_buffers.iter().all(|(_, hunks)| *hunks == 1));
+    }
+
+    #[gpui::test]
+    async fn test_rename_symbol_authorizes_every_file(cx: &mut TestAppContext) {
+        init_test(cx);
+        cx.update(|cx| {
+            let mut settings = agent_settings::AgentSettings::get_global(cx).clone();
+            settings.tool_permissions.tools.insert(
+                RenameSymbolTool::NAME.into(),
+                agent_settings::ToolRules {
+                    always_deny: vec![
+                        agent_settings::CompiledRegex::new(r"lib\.rs$", false).unwrap(),
+                    ],
+                    ..Default::default()
+                },
+            );
+            agent_settings::AgentSettings::override_global(settings, cx);
+        });
+        let test = setup_rename(cx).await;
+        let (fs, action_log) = (test.fs.clone(), test.action_log.clone());
+
+        // The symbol is renamed from `main.rs`, but the rename also edits the denied `lib.rs`.
+        assert!(test.run(cx).await.is_err(), "the rename should be denied");
+        assert_eq!(
+            fs.load(path!("/root/lib.rs").as_ref()).await.unwrap(),
+            "pub fn old_name() {}\n"
+        );
+        assert_eq!(
+            fs.load(path!("/root/main.rs").as_ref()).await.unwrap(),
+            "fn main() {\n    lib::old_name();\n}\n"
+        );
+        cx.run_until_parked();
+        assert!(
+            action_log.read_with(cx, |log, cx| log.changed_buffers(cx).is_empty()),
+            "no buffer should be edited when a file is denied"
+        );
+    }
+
+    #[gpui::test]
+    async fn test_rename_symbol_applies_file_operations(cx: &mut TestAppContext) {
+        init_test(cx);
+        let test = setup_rename(cx).await;
+        test._fake_server
+            .set_request_handler::<lsp::request::Rename, _, _>(|_, _| async move {
+                Ok(Some(lsp::WorkspaceEdit {
+                    document_changes: Some(lsp::DocumentChanges::Operations(vec![
+                        lsp::DocumentChangeOperation::Edit(lsp::TextDocumentEdit {
+                            text_document: lsp::OptionalVersionedTextDocumentIdentifier {
+                                uri: lsp::Uri::from_file_path(path!("/root/main.rs")).unwrap(),
+                                version: None,
+                            },
+                            edits: vec![lsp::Edit::Plain(lsp::TextEdit {
+                                range: lsp::Range::new(
+                                    lsp::Position::new(1, 9),
+                                    lsp::Position::new(1, 17),
+                                ),
+                                new_text: "new_name".into(),
+                            })],
+                        }),
+                        lsp::DocumentChangeOperation::Op(lsp::ResourceOp::Rename(
+                            lsp::RenameFile {
+                                old_uri: lsp::Uri::from_file_path(path!("/root/lib.rs")).unwrap(),
+                                new_uri: lsp::Uri::from_file_path(path!("/root/new_lib.rs"))
+                                    .unwrap(),
+                                options: None,
+                                annotation_id: None,
+                            },
+                        )),
+                    ])),
+                    ..Default::default()
+                }))
+            });
+
+        let output = test.run(cx).await.unwrap();
+        assert!(output.contains("in 1 files"), "unexpected output: {output}");
+        let fs = test.fs.clone();
+        assert_eq!(
+            fs.load(path!("/root/main.rs").as_ref()).await.unwrap(),
+            "fn main() {\n    lib::new_name();\n}\n"
+        );
+        assert!(!fs.is_file(path!("/root/lib.rs").as_ref()).await);
+        assert_eq!(
+            fs.load(path!("/root/new_lib.rs").as_ref()).await.unwrap(),
+            "pub fn old_name() {}\n"
+        );
+    }
+
+    struct RenameTest {
+        fs: Arc<FakeFs>,
+        action_log: Entity<ActionLog>,
+        tool: Arc<RenameSymbolTool>,
+        _thread: Entity<Thread>,
+        _buffer: Entity<Buffer>,
+        _lsp_handle: OpenLspBufferHandle,
+        _fake_server: lsp::FakeLanguageServer,
+    }
+
+    impl RenameTest {
+        /// Renames `old_name` to `new_name`, from its usage in `main.rs`.
+        fn run(&self, cx: &mut TestAppContext) -> Task<Result<String, String>> {
+            cx.update(|cx| {
+                self.tool.clone().run(
+                    ToolInput::resolved(RenameSymbolToolInput {
+                        path: "root/main.rs".into(),
+                        line: 2,
+                        symbol: "old_name".into(),
+                        new_name: "new_name".into(),
+                    }),
+                    ToolCallEventStream::test().0,
+                    cx,
+                )
+            })
+        }
+    }
+
+    async fn setup_rename(cx: &mut TestAppContext) -> RenameTest {
+        let fs = FakeFs::new(cx.executor());
+        fs.insert_tree(
+            path!("/root"),
+            json!({
+                "lib.rs": "pub fn old_name() {}\n",
+                "main.rs": "fn main() {\n    lib::old_name();\n}\n",
+            }),
+        )
+        .await;
+        let project = Project::test(fs.clone(), [path!("/root").as_ref()], cx).await;
+        let language_registry = project.read_with(cx, |project, _| project.languages().clone());
+        language_registry.add(language::rust_lang());
+        let mut fake_servers = language_registry.register_fake_lsp(
+            "Rust",
+            FakeLspAdapter {
+                capabilities: lsp::ServerCapabilities {
+                    rename_provider: Some(lsp::OneOf::Left(true)),
+                    ..Default::default()
+                },
+                ..Default::default()
+            },
+        );
+
+        let context_server_registry =
+            cx.new(|cx| ContextServerRegistry::new(project.read(cx).context_server_store(), cx));
+        let thread = cx.new(|cx| {
+            Thread::new(
+                project.clone(),
+                cx.new(|_cx| ProjectContext::default()),
+                context_server_registry,
+                Templates::new(),
+                Some(Arc::new(FakeLanguageModel::default())),
+                cx,
+            )
+        });
+        let action_log = thread.read_with(cx, |thread, _| thread.action_log().clone());
+        let tool = Arc::new(RenameSymbolTool::new(
+            project.clone(),
+            thread.downgrade(),
+            action_log.clone(),
+        ));
+
+        // Start the language server up front, so that tests can change how it answers renames.
+        let (buffer, lsp_handle) = project
+            .update(cx, |project, cx| {
+                project.open_local_buffer_with_lsp(path!("/root/main.rs"), cx)
+            })
+            .await
+            .unwrap();
+        let fake_server = fake_servers.next().await.unwrap();
+        fake_server.set_request_handler::<lsp::request::Rename, _, _>(|params, _| async move {
+            assert_eq!(
+                params.text_document_position.position,
+                lsp::Position::new(1, 9)
+            );
+            assert_eq!(params.new_name, "new_name");
+            let edit = |line, character| lsp::TextEdit {
+                range: lsp::Range::new(
+                    lsp::Position::new(line, character),
+                    lsp::Position::new(line, character + 8),
+                ),
+                new_text: "new_name".into(),
+            };
+            Ok(Some(lsp::WorkspaceEdit {
+                changes: Some(
+                    [
+                        (
+                            lsp::Uri::from_file_path(path!("/root/lib.rs")).unwrap(),
+                            vec![edit(0, 7)],
+                        ),
+                        (
+                            lsp::Uri::from_file_path(path!("/root/main.rs")).unwrap(),
+                            vec![edit(1, 9)],
+                        ),
+                    ]
+                    .into_iter()
+                    .collect(),
+                ),
+                ..Default::default()
+            }))
+        });
+
+        RenameTest {
+            fs,
+            action_log,
+            tool,
+            _thread: thread,
+            _buffer: buffer,
+            _lsp_handle: lsp_handle,
+            _fake_server: fake_server,
+        }
+    }
+
+    fn init_test(cx: &mut TestAppContext) {
+        cx.update(|cx| {
+            let settings_store = SettingsStore::test(cx);
+            cx.set_global(settings_store);
+            let mut settings = agent_settings::AgentSettings::get_global(cx).clone();
+            settings.tool_permissions.default = settings::ToolPermissionMode::Allow;
+            agent_settings::AgentSettings::override_global(settings, cx);
+        });
+    }
+}
@@ -0,0 +1,187 @@
+use anyhow::{Context as _, Result};
+use collections::HashSet;
+use futures::{FutureExt as _, StreamExt as _, channel::mpsc};
+use gpui::{App, AsyncApp, Entity};
+use language::{Anchor, Buffer, BufferEvent, Point, ToPoint as _};
+use project::{Project, lsp_store::OpenLspBufferHandle};
+use std::{ops::Range, time::Duration};
+
+/// How long to wait for a language server to start for the file being queried.
+const LANGUAGE_SERVER_START_TIMEOUT: Duration = Duration::from_secs(60);
+
+/// A buffer opened for a language server request, along with the position of the symbol to query.
+pub(crate) struct SymbolPosition {
+    pub buffer: Entity<Buffer>,
+    pub position: Point,
+    /// Keeps the buffer registered with its language servers while the request is in flight.
+    _lsp_handle: OpenLspBufferHandle,
+}
+
+/// Opens the buffer at `path`, waits for one of its language servers to be running, and finds
+/// `symbol` on the given 1-based `line`.
+///
+/// Models are unreliable at counting columns, so the symbol's text is used to locate it on the line.
+/// Whole-word occurrences are preferred over occurrences inside a longer identifier.
+pub(crate) async fn resolve_symbol_position(
+    project: &Entity<Project>,
+    path: &str,
+    line: u32,
+    symbol: &str,
+    cx: &mut AsyncApp,
+) -> Result<SymbolPosition> {
+    anyhow::ensure!(!symbol.is_empty(), "The symbol must not be empty");
+    let open_buffer = project.update(cx, |project, cx| {
+        let project_path = project
+            .find_project_path(path, cx)
+            .with_context(|| format!("Could not find path {path} in project"))?;
+        anyhow::Ok(project.open_buffer(project_path, cx))
+    })?;
+    let buffer = open_buffer.await?;
+    let lsp_handle = project.update(cx, |project, cx| {
+        project.register_buffer_with_language_servers(&buffer, cx)
+    });
+    wait_for_language_server(project, &buffer, path, cx).await?;
+
+    let position = buffer.read_with(cx, |buffer, _| {
+        let snapshot = buffer.snapshot();
+        let max_row = snapshot.max_point().row;
+        let row = line
+            .checked_sub(1)
+            .filter(|row| *row <= max_row)
+            .with_context(|| {
+                format!(
+                    "Line {line} is out of range, {path} has {} lines",
+                    max_row + 1
+                )
+            })?;
+        let line_text = snapshot
+            .text_for_range(Point::new(row, 0)..Point::new(row, snapshot.line_len(row)))
+            .collect::<String>();
+        let column = find_symbol_in_line(&line_text, symbol)
+            .with_context(|| format!("Could not find `{symbol}` on line {line} of {path}"))?;
+        anyhow::Ok(Point::new(row, column as u32))
+    })?;
+
+    Ok(SymbolPosition {
+        buffer,
+        position,
+        _lsp_handle: lsp_handle,
+    })
+}
+
+/// Waits until a language server is running for the buffer's language, as requests sent before
+/// then get no answer. Fails right away when no language server handles the buffer's language.
+async fn wait_for_language_server(
+    project: &Entity<Project>,
+    buffer: &Entity<Buffer>,
+    path: &str,
+    cx: &mut AsyncApp,
+) -> Result<()> {
+    let (tx, mut rx) = mpsc::unbounded();
+    let _subscriptions = cx.update(|cx| {
+        [
+            cx.subscribe(project, {
+                let tx = tx.clone();
+                move |_, event, _| {
+                    if let project::Event::LanguageServerAdded(..) = event {
+                        tx.unbounded_send(()).ok();
+                    }
+                }
+            }),
+            cx.subscribe(buffer, move |_, event, _| {
+                if let BufferEvent::LanguageChanged(_) = event {
+                    tx.unbounded_send(()).ok();
+                }
+            }),
+        ]
+    });
+    let mut timeout = cx
+        .background_executor()
+        .timer(LANGUAGE_SERVER_START_TIMEOUT)
+        .fuse();
+    loop {
+        let running = project.read_with(cx, |project, cx| {
+            language_server_running(project, buffer.read(cx), path, cx)
+        })?;
+        if running {
+            return Ok(());
+        }
+        futures::select_biased! {
+            _ = rx.next() => {}
+            _ = timeout => anyhow::bail!("Timed out waiting for a language server to start for {path}"),
+        }
+    }
+}
+
+fn language_server_running(
+    project: &Project,
+    buffer: &Buffer,
+    path: &str,
+    cx: &App,
+) -> Result<bool> {
+    let Some(language) = buffer.language() else {
+        // The language may still be loading, unless no language matches the file.
+        anyhow::ensure!(
+            buffer.file().is_none_or(|file| project
+                .languages()
+                .language_for_file(file, None, cx)
+                .is_some()),
+            "No language server is available for {path}"
+        );
+        return Ok(false);
+    };
+    let server_names = project
+        .languages()
+        .lsp_adapters(&language.name())
+        .into_iter()
+        .map(|adapter| adapter.name())
+        .collect::<HashSet<_>>();
+    anyhow::ensure!(
+        !server_names.is_empty(),
+        "No language server is available for {path}"
+    );
+    Ok(project
+        .language_server_statuses(cx)
+        .any(|(_, status)| server_names.contains(&status.name)))
+}
+
+fn find_symbol_in_line(line: &str, symbol: &str) -> Option<usize> {
+    let is_identifier_char = |c: char| c.is_alphanumeric() || c == '_';
+    let mut occurrences = line.match_indices(symbol).map(|(ix, _)| ix).peekable();
+    let first = *occurrences.peek()?;
+    occurrences
+        .find(|&ix| {
+            let before = line[..ix].chars().next_back();
+            let after = line[ix + symbol.len()..].chars().next();
+            !before.is_some_and(is_identifier_char) && !after.is_some_and(is_identifier_char)
+        })
+        .or(Some(first))
+}
+
+/// Formats a location as `path:line: text`, with the path in the form the other tools accept.
+pub(crate) fn format_location(buffer: &Entity<Buffer>, range: &Range<Anchor>, cx: &App) -> String {
+    let buffer = buffer.read(cx);
+    let snapshot = buffer.snapshot();
+    let row = range.start.to_point(&snapshot).row;
+    let path = buffer
+        .file()
+        .map(|file| file.full_path(cx).to_string_lossy().into_owned())
+        .unwrap_or_else(|| "untitled".to_string());
+    let line_text = snapshot
+        .text_for_range(Point::new(row, 0)..Point::new(row, snapshot.line_len(row)))
+        .collect::<String>();
+    format!("{path}:{}: {}", row + 1, line_text.trim())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_find_symbol_in_line() {
+        assert_eq!(find_symbol_in_line("let value = val;", "val"), Some(12));
+        assert_eq!(find_symbol_in_line("let value = 1;", "val"), Some(4));
+        assert_eq!(find_symbol_in_line("fn main() {}", "main"), Some(3));
+        assert_eq!(find_symbol_in_line("fn main() {}", "other"), None);
+    }
+}
@@ -0,0 +1,257 @@
+use crate::{AgentTool, ToolCallEventStream, ToolInput};
+use agent_client_protocol as acp;
+use anyhow::Result;
+use futures::FutureExt as _;
+use gpui::{App, Entity, SharedString, Task};
+use project::{Project, Symbol, lsp_store::SymbolLocation};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::{fmt::Write, sync::Arc};
+use util::markdown::MarkdownInlineCode;
+
+/// Searches the project for symbols (functions, types, constants, …) whose name matches a query, using the running language servers.
+///
+/// Use this to find where something is declared when you know (part of) its name but not the file it lives in.
+/// Each result includes the symbol's kind, its name and the location of its declaration, which can be passed on to the `go_to_definition`, `find_references` and `hover` tools.
+///
+/// <example>
+/// To find types and functions related to parsing:
+/// {
+///     "query": "parse"
+/// }
+/// </example>
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
+pub struct WorkspaceSymbolsToolInput {
+    /// The text to match against symbol names. Language servers usually match it fuzzily.
+    pub query: String,
+}
+
+const MAX_RESULTS: usize = 100;
+
+pub struct WorkspaceSymbolsTool {
+    project: Entity<Project>,
+}
+
+impl WorkspaceSymbolsTool {
+    pub fn new(project: Entity<Project>) -> Self {
+        Self { project }
+    }
+}
+
+impl AgentTool for WorkspaceSymbolsTool {
+    type Input = WorkspaceSymbolsToolInput;
+    type Output = String;
+
+    const NAME: &'static str = "workspace_symbols";
+
+    fn kind() -> acp::ToolKind {
+        acp::ToolKind::Search
+    }
+
+    fn initial_title(
+        &self,
+        input: Result<Self::Input, serde_json::Value>,
+        _cx: &mut App,
+    ) -> SharedString {
+        if let Ok(input) = input {
+            format!(
+                "Search symbols matching {}",
+                MarkdownInlineCode(&input.query)
+            )
+            .into()
+        } else {
+            "Search symbols".into()
+        }
+    }
+
+    fn run(
+        self: Arc<Self>,
+        input: ToolInput<Self::Input>,
+        event_stream: ToolCallEventStream,
+        cx: &mut App,
+    ) -> Task<Result<Self::Output, Self::Output>> {
+        let project = self.project.clone();
+        cx.spawn(async move |cx| {
+            let input = input
+                .recv()
+                .await
+                .map_err(|e| format!("Failed to receive tool input: {e}"))?;
+
+            let symbols_task = project.update(cx, |project, cx| project.symbols(&input.query, cx));
+            let symbols = futures::select! {
+                result = symbols_task.fuse() => result.map_err(|e| e.to_string())?,
+                _ = event_stream.cancelled_by_user().fuse() => {
+                    return Err("Symbol search cancelled by user".to_string());
+                }
+            };
+
+            event_stream.update_fields(acp::ToolCallUpdateFields::new().title(
+                match symbols.len() {
+                    0 => "No symbols".to_string(),
+                    1 => "1 symbol".to_string(),
+                    len => format!("{len} symbols"),
+                },
+            ));
+            if symbols.is_empty() {
+                return Ok(format!(
+                    "No symbols found matching `{}`. Language servers only report symbols for the languages they support, and may still be indexing the project.",
+                    input.query
+                ));
+            }
+
+            Ok(project.read_with(cx, |project, cx| {
+                let mut output = format!("Found {} symbols.", symbols.len());
+                if symbols.len() > MAX_RESULTS {
+                    write!(
+                        output,
+                        " Showing the first {MAX_RESULTS}, refine the query to narrow them down."
+                    )
+                    .ok();
+                }
+                for symbol in symbols.iter().take(MAX_RESULTS) {
+                    write!(
+                        output,
+                        "\n{:?} {} at {}:{}",
+                        symbol.kind,
+                        symbol_name(symbol),
+                        symbol_path(project, symbol, cx),
+                        symbol.range.start.0.row + 1
+                    )
+                    .ok();
+                }
+                output
+            }))
+        })
+    }
+}
+
+fn symbol_name(symbol: &Symbol) -> String {
+    match &symbol.container_name {
+        Some(container_name) if !container_name.is_empty() => {
+            format!("{} (in {container_name})", symbol.name)
+        }
+        _ => symbol.name.clone(),
+    }
+}
+
+fn symbol_path(project: &Project, symbol: &Symbol, cx: &App) -> String {
+    match &symbol.path {
+        SymbolLocation::InProject(project_path) => project
+            .worktree_for_id(project_path.worktree_id, cx)
+            .map(|worktree| {
+                worktree
+                    .read(cx)
+                    .root_name()
+                    .join(&project_path.path)
+                    .display(project.path_style(cx))
+                    .to_string()
+            })
+            .unwrap_or_else(|| {
+                project_path
+                    .path
+                    .display(project.path_style(cx))
+                    .to_string()
+            }),
+        SymbolLocation::OutsideProject { abs_path, .. } => abs_path.display().to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use futures::StreamExt as _;
+    use gpui::TestAppContext;
+    use language::FakeLspAdapter;
+    use project::FakeFs;
+    use serde_json::json;
+    use settings::SettingsStore;
+    use util::path;
+
+    #[gpui::test]
+    async fn test_workspace_symbols(cx: &mut TestAppContext) {
+        init_test(cx);
+
+        let fs = FakeFs::new(cx.executor());
+        fs.insert_tree(
+            path!("/root"),
+            json!({
+                "lib.rs": "pub struct Config;\n\npub fn load_config() -> Config {\n    Config\n}\n",
+            }),
+        )
+        .await;
+        let project = Project::test(fs, [path!("/root").as_ref()], cx).await;
+        let language_registry = project.read_with(cx, |project, _| project.languages().clone());
+        language_registry.add(language::rust_lang());
+        let mut fake_servers = language_registry.register_fake_lsp(
+            "Rust",
+            FakeLspAdapter {
+                capabilities: lsp::ServerCapabilities {
+                    workspace_symbol_provider: Some(lsp::OneOf::Left(true)),
+                    ..Default::default()
+                },
+                ..Default::default()
+            },
+        );
+
+        let (_buffer, _handle) = project
+            .update(cx, |project, cx| {
+                project.open_local_buffer_with_lsp(path!("/root/lib.rs"), cx)
+            })
+            .await
+            .unwrap();
+        let fake_server = fake_servers.next().await.unwrap();
+        fake_server.set_request_handler::<lsp::WorkspaceSymbolRequest, _, _>(
+            |params, _| async move {
+                assert_eq!(params.query, "config");
+                let symbol = |name: &str, kind, line| {
+                    #[allow(deprecated)]
+                    lsp::SymbolInformation {
+                        name: name.to_string(),
+                        kind,
+                        tags: None,
+                        deprecated: None,
+                        container_name: Some("lib".to_string()),
+                        location: lsp::Location::new(
+                            lsp::Uri::from_file_path(path!("/root/lib.rs")).unwrap(),
+                            lsp::Range::new(
+                                lsp::Position::new(line, 0),
+                                lsp::Position::new(line, 1),
+                            ),
+                        ),
+                    }
+                };
+                Ok(Some(lsp::WorkspaceSymbolResponse::Flat(vec![
+                    symbol("Config", lsp::SymbolKind::STRUCT, 0),
+                    symbol("load_config", lsp::SymbolKind::FUNCTION, 2),
+                ])))
+            },
+        );
+
+        let tool = Arc::new(WorkspaceSymbolsTool::new(project));
+        let output = cx
+            .update(|cx| {
+                tool.run(
+                    ToolInput::resolved(WorkspaceSymbolsToolInput {
+                        query: "config".into(),
+                    }),
+                    ToolCallEventStream::test().0,
+                    cx,
+                )
+            })
+            .await
+            .unwrap();
+        assert!(
+            output.starts_with("Found 2 symbols."),
+            "unexpected output: {output}"
+        );
+        assert!(output.contains(" Config (in lib) at root/lib.rs:1"));
+        assert!(output.contains(" load_config (in lib) at root/lib.rs:3"));
+    }
+
+    fn init_test(cx: &mut TestAppContext) {
+        cx.update(|cx| {
+            let settings_store = SettingsStore::test(cx);
+            cx.set_global(settings_store);
+        });
+    }
+}
@@ -2,6 +2,7 @@ mod agent_profile;
 
 use std::path::{Component, Path};
 use std::sync::{Arc, LazyLock};
+use std::time::Duration;
 
 use agent_client_protocol::ModelId;
 use collections::{HashSet, IndexMap};
@@ -20,6 +21,7 @@ pub use crate::agent_profile::*;
 pub const SUMMARIZE_THREAD_PROMPT: &str = include_str!("prompts/summarize_thread_prompt.txt");
 pub const SUMMARIZE_THREAD_DETAILED_PROMPT: &str =
     include_str!("prompts/summarize_thread_detailed_prompt.txt");
+pub const COMPACT_THREAD_PROMPT: &str = include_str!("prompts/compact_thread_prompt.txt");
 
 #[derive(Clone, Debug, RegisterSetting)]
 pub struct AgentSettings {
@@ -50,7 +52,10 @@ pub struct AgentSettings {
     pub use_modifier_to_send: bool,
     pub message_editor_min_lines: usize,
     pub show_turn_stats: bool,
+    pub auto_compact: bool,
+    pub auto_compact_threshold: f32,
     pub tool_permissions: ToolPermissions,
+    pub hooks: AgentHooks,
 }
 
 impl AgentSettings {
@@ -191,6 +196,33 @@ impl CompiledRegex {
     }
 }
 
+/// Shell commands the user configured to run at points of the agent's turns.
+#[derive(Clone, Debug, Default)]
+pub struct AgentHooks {
+    pub pre_tool_use: Vec<AgentHook>,
+    pub post_tool_use: Vec<AgentHook>,
+    pub post_edit: Vec<AgentHook>,
+    pub end_of_turn: Vec<AgentHook>,
+}
+
+#[derive(Clone, Debug)]
+pub struct AgentHook {
+    pub command: String,
+    /// The tools the hook runs for, or `None` to run it for every tool.
+    pub tools: Option<CompiledRegex>,
+    pub timeout: Duration,
+}
+
+impl AgentHook {
+    pub const DEFAULT_TIMEOUT: Duration = Duration::from_secs(60);
+
+    pub fn matches_tool(&self, tool_name: &str) -> bool {
+        self.tools
+            .as_ref()
+            .is_none_or(|tools| tools.is_match(tool_name))
+    }
+}
+
 pub const HARDCODED_SECURITY_DENIAL_MESSAGE: &str = "Blocked by built-in security rule. This operation is considered too \
      harmful to be allowed, and cannot be overridden by settings.";
 
@@ -437,7 +469,10 @@ impl Settings for AgentSettings {
             use_modifier_to_send: agent.use_modifier_to_send.unwrap(),
             message_editor_min_lines: agent.message_editor_min_lines.unwrap(),
             show_turn_stats: agent.show_turn_stats.unwrap(),
+            auto_compact: agent.auto_compact.unwrap(),
+            auto_compact_threshold: agent.auto_compact_threshold.unwrap().clamp(0.0, 1.0),
             tool_permissions: compile_tool_permissions(agent.tool_permissions),
+            hooks: compile_hooks(agent.hooks),
         }
     }
 }
@@ -504,6 +539,42 @@ fn compile_tool_permissions(content: Option<settings::ToolPermissionsContent>) -
     }
 }
 
+fn compile_hooks(content: Option<settings::AgentHooksContent>) -> AgentHooks {
+    let Some(content) = content else {
+        return AgentHooks::default();
+    };
+
+    let compile = |hooks: Option<Vec<settings::AgentHookContent>>| {
+        hooks
+            .unwrap_or_default()
+            .into_iter()
+            .filter(|hook| !hook.command.trim().is_empty())
+            .map(|hook| AgentHook {
+                // A hook with an invalid pattern runs for every tool rather than being skipped,
+                // since it may be enforcing a policy.
+                tools: hook.tools.and_then(|pattern| {
+                    CompiledRegex::try_new(&pattern, true)
+                        .inspect_err(|error| {
+                            log::error!("Invalid tools pattern for agent hook `{pattern}`: {error}")
+                        })
+                        .ok()
+                }),
+                timeout: hook
+                    .timeout
+                    .map_or(AgentHook::DEFAULT_TIMEOUT, Duration::from_secs),
+                command: hook.command,
+            })
+            .collect()
+    };
+
+    AgentHooks {
+        pre_tool_use: compile(content.pre_tool_use),
+        post_tool_use: compile(content.post_tool_use),
+        post_edit: compile(content.post_edit),
+        end_of_turn: compile(content.end_of_turn),
+    }
+}
+
 fn compile_regex_rules(
     rules: Vec<settings::ToolRegexRule>,
     rule_type: &str,
@@ -975,6 +1046,36 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_hooks_parsing() {
+        let json = json!({
+            "pre_tool_use": [
+                { "command": "./check-policy.sh", "tools": "^(terminal|fetch)$" },
+                { "command": "   " }
+            ],
+            "post_edit": [
+                { "command": "cargo fmt", "timeout": 5 },
+                { "command": "./lint.sh", "tools": "[invalid" }
+            ]
+        });
+
+        let content: settings::AgentHooksContent = serde_json::from_value(json).unwrap();
+        let hooks = compile_hooks(Some(content));
+
+        assert_eq!(hooks.pre_tool_use.len(), 1);
+        assert!(hooks.pre_tool_use[0].matches_tool("terminal"));
+        assert!(!hooks.pre_tool_use[0].matches_tool("edit_file"));
+        assert_eq!(hooks.pre_tool_use[0].timeout, AgentHook::DEFAULT_TIMEOUT);
+
+        assert_eq!(hooks.post_edit.len(), 2);
+        assert_eq!(hooks.post_edit[0].timeout, Duration::from_secs(5));
+        assert!(hooks.post_edit[0].matches_tool("edit_file"));
+        assert!(hooks.post_edit[1].matches_tool("edit_file"));
+
+        assert!(hooks.post_tool_use.is_empty());
+        assert!(hooks.end_of_turn.is_empty());
+    }
+
     #[test]
     fn test_default_json_tool_permissions_parse() {
         let default_json = include_str!("../../../assets/settings/default.json");
@@ -10,6 +10,7 @@ mod config_options;
 pub(crate) mod connection_view;
 mod context;
 mod context_server_configuration;
+mod context_server_requests;
 mod entry_view_state;
 mod external_source_prompt;
 mod favorite_models;
@@ -313,6 +314,7 @@ pub fn init(
     assistant_slash_command::init(cx);
     agent_panel::init(cx);
     context_server_configuration::init(language_registry.clone(), fs.clone(), cx);
+    context_server_requests::init(cx);
     TextThreadEditor::init(cx);
 
     register_slash_commands(cx);
@@ -592,6 +594,9 @@ mod tests {
             message_editor_min_lines: 1,
             tool_permissions: Default::default(),
             show_turn_stats: false,
+            auto_compact: true,
+            auto_compact_threshold: 0.8,
+            hooks: Default::default(),
         };
 
         cx.update(|cx| {
@@ -6,6 +6,7 @@ use std::sync::atomic::AtomicBool;
 
 use crate::ThreadHistory;
 use acp_thread::MentionUri;
+use agent::ContextServerRegistry;
 use agent_client_protocol as acp;
 use anyhow::Result;
 use editor::{
@@ -64,6 +65,7 @@ pub(crate) enum PromptContextType {
     Thread,
     Rules,
     Diagnostics,
+    McpResource,
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -102,6 +104,7 @@ impl TryFrom<&str> for PromptContextType {
             "thread" => Ok(Self::Thread),
             "rule" => Ok(Self::Rules),
             "diagnostics" => Ok(Self::Diagnostics),
+            "resource" => Ok(Self::McpResource),
             _ => Err(format!("Invalid context picker mode: {}", value)),
         }
     }
@@ -116,6 +119,7 @@ impl PromptContextType {
             Self::Thread => "thread",
             Self::Rules => "rule",
             Self::Diagnostics => "diagnostics",
+            Self::McpResource => "resource",
         }
     }
 
@@ -127,6 +131,7 @@ impl PromptContextType {
             Self::Thread => "Threads",
             Self::Rules => "Rules",
             Self::Diagnostics => "Diagnostics",
+            Self::McpResource => "MCP Resources",
         }
     }
 
@@ -138,6 +143,7 @@ impl PromptContextType {
             Self::Thread => IconName::Thread,
             Self::Rules => IconName::Reader,
             Self::Diagnostics => IconName::Warning,
+            Self::McpResource => IconName::Server,
         }
     }
 }
@@ -149,6 +155,8 @@ pub(crate) enum Match {
     RecentThread(SessionMatch),
     Fetch(SharedString),
     Rules(RulesContextEntry),
+    McpResource(McpResourceMatch),
+    McpResourceTemplate(McpResourceTemplateMatch),
     Entry(EntryMatch),
 }
 
@@ -162,6 +170,8 @@ impl Match {
             Match::Symbol(_) => 1.,
             Match::Rules(_) => 1.,
             Match::Fetch(_) => 1.,
+            Match::McpResource(_) => 1.,
+            Match::McpResourceTemplate(_) => 1.,
         }
     }
 }
@@ -189,6 +199,19 @@ pub struct RulesContextEntry {
     pub title: SharedString,
 }
 
+#[derive(Debug, Clone)]
+pub struct McpResourceMatch {
+    server_id: String,
+    uri: String,
+    name: SharedString,
+}
+
+#[derive(Debug, Clone)]
+pub struct McpResourceTemplateMatch {
+    name: SharedString,
+    uri_template: String,
+}
+
 #[derive(Debug, Clone)]
 pub struct AvailableCommand {
     pub name: Arc<str>,
@@ -529,6 +552,83 @@ impl<T: PromptCompletionProviderDelegate> PromptCompletionProvider<T> {
         })
     }
 
+    fn completion_for_mcp_resource(
+        resource: McpResourceMatch,
+        source_range: Range<Anchor>,
+        source: Arc<T>,
+        editor: WeakEntity<Editor>,
+        mention_set: WeakEntity<MentionSet>,
+        workspace: Entity<Workspace>,
+        label_max_chars: usize,
+        cx: &mut App,
+    ) -> Completion {
+        let label = build_code_label_for_path(
+            &resource.name,
+            Some(&resource.uri),
+            None,
+            label_max_chars,
+            cx,
+        );
+        let uri = MentionUri::McpResource {
+            server_id: resource.server_id,
+            uri: resource.uri,
+            name: resource.name.to_string(),
+        };
+        let new_text = format!("{} ", uri.as_link());
+        let new_text_len = new_text.len();
+        let icon_path = uri.icon_path(cx);
+        Completion {
+            replace_range: source_range.clone(),
+            new_text,
+            label,
+            documentation: None,
+            insert_text_mode: None,
+            source: project::CompletionSource::Custom,
+            match_start: None,
+            snippet_deduplication_key: None,
+            icon_path: Some(icon_path),
+            confirm: Some(confirm_completion_callback(
+                resource.name,
+                source_range.start,
+                new_text_len - 1,
+                uri,
+                source,
+                editor,
+                mention_set,
+                workspace,
+            )),
+        }
+    }
+
+    fn completion_for_mcp_resource_template(
+        template: McpResourceTemplateMatch,
+        source_range: Range<Anchor>,
+        label_max_chars: usize,
+        cx: &mut App,
+    ) -> Completion {
+        // Insert the part of the template preceding its first variable, so the
+        // user can fill in the rest and pick the resulting URI.
+        let prefix = template.uri_template.split('{').next().unwrap_or_default();
+        Completion {
+            replace_range: source_range,
+            new_text: format!("@{} {}", PromptContextType::McpResource.keyword(), prefix),
+            label: build_code_label_for_path(
+                &template.name,
+                Some(&template.uri_template),
+                None,
+                label_max_chars,
+                cx,
+            ),
+            documentation: None,
+            insert_text_mode: None,
+            source: project::CompletionSource::Custom,
+            match_start: None,
+            snippet_deduplication_key: None,
+            icon_path: Some(IconName::Server.path().into()),
+            confirm: Some(Arc::new(|_, _, _| true)),
+        }
+    }
+
     pub(crate) fn completion_for_action(
         action: PromptContextAction,
         source_range: Range<Anchor>,
@@ -892,6 +992,14 @@ impl<T: PromptCompletionProviderDelegate> PromptCompletionProvider<T> {
 
             Some(PromptContextType::Diagnostics) => Task::ready(Vec::new()),
 
+            Some(PromptContextType::McpResource) => {
+                if let Some(registry) = context_server_registry(&workspace, cx) {
+                    search_mcp_resources(query, cancellation_flag, &registry, cx)
+                } else {
+                    Task::ready(Vec::new())
+                }
+            }
+
             None if query.is_empty() => {
                 let recent_task = self.recent_context_picker_entries(&workspace, cx);
                 let entries = self
@@ -1107,6 +1215,18 @@ impl<T: PromptCompletionProviderDelegate> PromptCompletionProvider<T> {
             }
         }
 
+        if self
+            .source
+            .supports_context(PromptContextType::McpResource, cx)
+            && context_server_registry(workspace, cx).is_some_and(|registry| {
+                let registry = registry.read(cx);
+                registry.resources().next().is_some()
+                    || registry.resource_templates().next().is_some()
+            })
+        {
+            entries.push(PromptContextEntry::Mode(PromptContextType::McpResource));
+        }
+
         entries
     }
 }
@@ -1354,6 +1474,26 @@ impl<T: PromptCompletionProviderDelegate> CompletionProvider for PromptCompletio
                                     workspace.clone(),
                                     cx,
                                 ),
+                                Match::McpResource(resource) => {
+                                    Some(Self::completion_for_mcp_resource(
+                                        resource,
+                                        source_range.clone(),
+                                        source.clone(),
+                                        editor.clone(),
+                                        mention_set.clone(),
+                                        workspace.clone(),
+                                        label_max_chars,
+                                        cx,
+                                    ))
+                                }
+                                Match::McpResourceTemplate(template) => {
+                                    Some(Self::completion_for_mcp_resource_template(
+                                        template,
+                                        source_range.clone(),
+                                        label_max_chars,
+                                        cx,
+                                    ))
+                                }
                                 Match::Entry(EntryMatch { entry, .. }) => {
                                     Self::completion_for_entry(
                                         entry,
@@ -1967,6 +2107,121 @@ pub(crate) fn search_rules(
     })
 }
 
+fn context_server_registry(
+    workspace: &Entity<Workspace>,
+    cx: &App,
+) -> Option<Entity<ContextServerRegistry>> {
+    let panel = workspace.read(cx).panel::<AgentPanel>(cx)?;
+    Some(panel.read(cx).context_server_registry().clone())
+}
+
+fn search_mcp_resources(
+    query: String,
+    cancellation_flag: Arc<AtomicBool>,
+    registry: &Entity<ContextServerRegistry>,
+    cx: &mut App,
+) -> Task<Vec<Match>> {
+    let registry = registry.read(cx);
+    let resources = registry
+        .resources()
+        .map(|resource| McpResourceMatch {
+            server_id: resource.server_id.0.to_string(),
+            uri: resource.resource.uri.to_string(),
+            name: resource.resource.name.clone().into(),
+        })
+        .collect::<Vec<_>>();
+
+    // A query that fits a template names a concrete resource, which is offered directly.
+    let mut expanded = Vec::new();
+    let mut templates = Vec::new();
+    for template in registry.resource_templates() {
+        if matches_resource_template(&template.template.uri_template, &query) {
+            expanded.push(Match::McpResource(McpResourceMatch {
+                server_id: template.server_id.0.to_string(),
+                uri: query.clone(),
+                name: query.clone().into(),
+            }));
+        } else {
+            templates.push(McpResourceTemplateMatch {
+                name: template.template.name.clone().into(),
+                uri_template: template.template.uri_template.clone(),
+            });
+        }
+    }
+
+    if query.is_empty() {
+        return Task::ready(
+            resources
+                .into_iter()
+                .map(Match::McpResource)
+                .chain(templates.into_iter().map(Match::McpResourceTemplate))
+                .collect(),
+        );
+    }
+
+    let executor = cx.background_executor().clone();
+    cx.background_spawn(async move {
+        let candidates = resources
+            .iter()
+            .map(|resource| format!("{} {}", resource.name, resource.uri))
+            .chain(
+                templates
+                    .iter()
+                    .map(|template| format!("{} {}", template.name, template.uri_template)),
+            )
+            .enumerate()
+            .map(|(id, text)| StringMatchCandidate::new(id, &text))
+            .collect::<Vec<_>>();
+        let matches = fuzzy::match_strings(
+            &candidates,
+            &query,
+            false,
+            true,
+            100,
+            &cancellation_flag,
+            executor,
+        )
+        .await;
+
+        expanded.extend(matches.into_iter().map(|mat| {
+            if let Some(resource) = resources.get(mat.candidate_id) {
+                Match::McpResource(resource.clone())
+            } else {
+                Match::McpResourceTemplate(templates[mat.candidate_id - resources.len()].clone())
+            }
+        }));
+        expanded
+    })
+}
+
+/// Returns whether `uri` can be produced by expanding the variables of an RFC 6570
+/// `template`. Variables match any non-empty text, excluding `/` unless they use
+/// reserved (`{+var}`) or fragment (`{#var}`) expansion.
+fn matches_resource_template(template: &str, uri: &str) -> bool {
+    let Some(start) = template.find('{') else {
+        return template == uri;
+    };
+    let Some(end) = template[start..].find('}').map(|end| start + end) else {
+        return template == uri;
+    };
+    let Some(uri) = uri.strip_prefix(&template[..start]) else {
+        return false;
+    };
+    let rest_of_template = &template[end + 1..];
+    let allows_slash = template[start + 1..end].starts_with(['+', '#']);
+    let max_len = if allows_slash {
+        uri.len()
+    } else {
+        uri.find('/').unwrap_or(uri.len())
+    };
+    uri[..max_len]
+        .char_indices()
+        .skip(1)
+        .map(|(ix, _)| ix)
+        .chain((max_len > 0).then_some(max_len))
+        .any(|len| matches_resource_template(rest_of_template, &uri[len..]))
+}
+
 pub struct SymbolMatch {
     pub symbol: Symbol,
 }
@@ -2358,6 +2613,31 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_matches_resource_template() {
+        assert!(matches_resource_template("tickets://{id}", "tickets://123"));
+        assert!(matches_resource_template(
+            "repo://{owner}/{name}/readme",
+            "repo://zed-industries/zed/readme"
+        ));
+        assert!(matches_resource_template(
+            "file:///{+path}",
+            "file:///src/main.rs"
+        ));
+        assert!(matches_resource_template("status://", "status://"));
+
+        assert!(!matches_resource_template("tickets://{id}", "tickets://"));
+        assert!(!matches_resource_template("tickets://{id}", "issues://123"));
+        assert!(!matches_resource_template(
+            "tickets://{id}",
+            "tickets://123/comments"
+        ));
+        assert!(!matches_resource_template(
+            "repo://{owner}/{name}/readme",
+            "repo://zed-industries/readme"
+        ));
+    }
+
     #[gpui::test]
     async fn test_filter_sessions_by_query(cx: &mut TestAppContext) {
         let alpha = SessionMatch {
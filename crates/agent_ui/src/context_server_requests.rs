@@ -0,0 +1,591 @@
+use std::rc::Rc;
+
+use anyhow::Result;
+use collections::HashMap;
+use context_server::ContextServerId;
+use context_server::types::{
+    CreateMessageRequest, CreateMessageResult, ElicitationAction, ElicitationCreateParams,
+    ElicitationCreateResponse, ElicitationPropertySchema, MessageContent, Role, SamplingMessage,
+};
+use futures::{StreamExt as _, channel::oneshot};
+use gpui::{
+    AnyWindowHandle, DismissEvent, Entity, EventEmitter, FocusHandle, Focusable, PromptLevel,
+    ScrollHandle, Task, WeakEntity,
+};
+use language_model::{
+    LanguageModelCompletionEvent, LanguageModelRegistry, LanguageModelRequest,
+    LanguageModelRequestMessage, StopReason,
+};
+use project::context_server_store::ContextServerRequestHandler;
+use ui::{Banner, Checkbox, KeyBinding, Modal, ModalFooter, ModalHeader, Section, prelude::*};
+use ui_input::InputField;
+use util::truncate_and_trailoff;
+use workspace::{ModalView, Workspace};
+
+/// A rough estimate of the bytes of text per token, to bound the length of sampled messages.
+const BYTES_PER_TOKEN: u64 = 3;
+/// The most messages of a sampling request shown when asking the user, counting from the last.
+const MAX_PROMPT_DETAIL_MESSAGES: usize = 3;
+/// The most characters of each message shown when asking the user.
+const MAX_PROMPT_DETAIL_CHARS: usize = 300;
+
+/// Answers the sampling and elicitation requests of the project's context servers in the workspace's window.
+pub(crate) fn init(cx: &mut App) {
+    cx.observe_new(|workspace: &mut Workspace, window, cx| {
+        let Some(window) = window else {
+            return;
+        };
+        let handler = Rc::new(WorkspaceRequestHandler {
+            workspace: cx.weak_entity(),
+            window: window.window_handle(),
+        });
+        let context_server_store = workspace.project().read(cx).context_server_store();
+        context_server_store.update(cx, |store, _| store.set_request_handler(handler));
+    })
+    .detach();
+}
+
+struct WorkspaceRequestHandler {
+    workspace: WeakEntity<Workspace>,
+    window: AnyWindowHandle,
+}
+
+impl ContextServerRequestHandler for WorkspaceRequestHandler {
+    fn create_message(
+        &self,
+        server_id: ContextServerId,
+        params: CreateMessageRequest,
+        cx: &mut App,
+    ) -> Task<Result<CreateMessageResult>> {
+        let Some(model) = LanguageModelRegistry::read_global(cx).default_model() else {
+            return Task::ready(Err(anyhow::anyhow!("No language model is configured")));
+        };
+
+        // Servers can only use the user's model after the user has seen what they're asking.
+        let answer = self.window.update(cx, |_, window, cx| {
+            window.prompt(
+                PromptLevel::Info,
+                &format!(
+                    "Allow the {} MCP server to use {}?",
+                    server_id.0,
+                    model.model.name().0
+                ),
+                Some(&sampling_prompt_detail(&params)),
+                &["Allow", "Deny"],
+                cx,
+            )
+        });
+
+        cx.spawn(async move |cx| {
+            if answer?.await? != 0 {
+                anyhow::bail!("The user denied the request");
+            }
+
+            let mut messages = Vec::new();
+            if let Some(system_prompt) = params.system_prompt {
+                messages.push(LanguageModelRequestMessage {
+                    role: language_model::Role::System,
+                    content: vec![system_prompt.into()],
+                    cache: false,
+                    reasoning_details: None,
+                });
+            }
+            for message in params.messages {
+                let MessageContent::Text { text, .. } = message.content else {
+                    anyhow::bail!("Only text messages are supported");
+                };
+                messages.push(LanguageModelRequestMessage {
+                    role: match message.role {
+                        Role::User => language_model::Role::User,
+                        Role::Assistant => language_model::Role::Assistant,
+                    },
+                    content: vec![text.into()],
+                    cache: false,
+                    reasoning_details: None,
+                });
+            }
+            let request = LanguageModelRequest {
+                thread_id: None,
+                prompt_id: None,
+                intent: None,
+                messages,
+                tools: Vec::new(),
+                tool_choice: None,
+                stop: params.stop_sequences.unwrap_or_default(),
+                temperature: params.temperature.map(|temperature| temperature as f32),
+                thinking_allowed: false,
+                thinking_effort: None,
+                speed: None,
+            };
+
+            let max_tokens = u64::from(params.max_tokens);
+            let mut events = model.model.stream_completion(request, cx).await?;
+            let mut text = String::new();
+            let mut output_tokens = 0;
+            let mut stop_reason = None;
+            while let Some(event) = events.next().await {
+                match event? {
+                    LanguageModelCompletionEvent::Text(chunk) => text.push_str(&chunk),
+                    LanguageModelCompletionEvent::UsageUpdate(usage) => {
+                        output_tokens = usage.output_tokens;
+                    }
+                    LanguageModelCompletionEvent::Stop(reason) => {
+                        stop_reason = Some(match reason {
+                            StopReason::EndTurn => "endTurn",
+                            StopReason::MaxTokens => "maxTokens",
+                            StopReason::ToolUse => "toolUse",
+                            StopReason::Refusal => "refusal",
+                        });
+                    }
+                    _ => {}
+                }
+
+                // Not every provider reports usage as it streams, so the length of the text
+                // bounds the tokens too. Dropping the stream cancels the completion.
+                let max_len = (max_tokens * BYTES_PER_TOKEN) as usize;
+                if output_tokens >= max_tokens || text.len() > max_len {
+                    text.truncate(text.floor_char_boundary(max_len));
+                    stop_reason = Some("maxTokens");
+                    break;
+                }
+            }
+
+            Ok(CreateMessageResult {
+                role: Role::Assistant,
+                content: MessageContent::Text {
+                    text,
+                    annotations: None,
+                },
+                model: model.model.id().0.to_string(),
+                stop_reason: stop_reason.map(Into::into),
+            })
+        })
+    }
+
+    fn elicit(
+        &self,
+        server_id: ContextServerId,
+        params: ElicitationCreateParams,
+        cx: &mut App,
+    ) -> Task<Result<ElicitationCreateResponse>> {
+        let (response_tx, response_rx) = oneshot::channel();
+        let workspace = self.workspace.clone();
+        let shown = self.window.update(cx, |_, window, cx| {
+            workspace.update(cx, |workspace, cx| {
+                if workspace.active_modal::<ElicitationModal>(cx).is_some() {
+                    anyhow::bail!("Another request is awaiting the user's response");
+                }
+                workspace.toggle_modal(window, cx, |window, cx| {
+                    ElicitationModal::new(server_id, params, response_tx, window, cx)
+                });
+                anyhow::Ok(())
+            })
+        });
+        if let Err(error) = shown.flatten().flatten() {
+            return Task::ready(Err(error));
+        }
+
+        cx.background_spawn(async move {
+            // Dismissing the modal without answering cancels the request.
+            Ok(response_rx.await.unwrap_or(ElicitationCreateResponse {
+                action: ElicitationAction::Cancel,
+                content: None,
+                meta: None,
+            }))
+        })
+    }
+}
+
+fn sampling_prompt_detail(params: &CreateMessageRequest) -> String {
+    let mut detail = String::new();
+    if let Some(system_prompt) = &params.system_prompt {
+        let system_prompt = truncate_and_trailoff(system_prompt, MAX_PROMPT_DETAIL_CHARS);
+        detail.push_str(&format!("System: {system_prompt}\n\n"));
+    }
+    let skipped = params
+        .messages
+        .len()
+        .saturating_sub(MAX_PROMPT_DETAIL_MESSAGES);
+    if skipped > 0 {
+        detail.push_str(&format!("…{skipped} earlier messages\n\n"));
+    }
+    for SamplingMessage { role, content } in &params.messages[skipped..] {
+        let role = match role {
+            Role::User => "User",
+            Role::Assistant => "Assistant",
+        };
+        let text = match content {
+            MessageContent::Text { text, .. } => {
+                truncate_and_trailoff(text, MAX_PROMPT_DETAIL_CHARS)
+            }
+            MessageContent::Image { .. } => "[image]".into(),
+            MessageContent::Audio { .. } => "[audio]".into(),
+            MessageContent::Resource { .. } => "[resource]".into(),
+        };
+        detail.push_str(&format!("{role}: {text}\n\n"));
+    }
+    detail.push_str(&format!("Up to {} tokens", params.max_tokens));
+    detail
+}
+
+enum FieldInput {
+    Text(Entity<InputField>),
+    Boolean(ToggleState),
+    Choice {
+        values: Vec<String>,
+        labels: Vec<SharedString>,
+        selected: Option<usize>,
+    },
+}
+
+struct ElicitationField {
+    name: String,
+    schema: ElicitationPropertySchema,
+    required: bool,
+    input: FieldInput,
+}
+
+impl ElicitationField {
+    fn label(&self) -> SharedString {
+        let label = self.schema.title().unwrap_or(&self.name);
+        if self.required {
+            format!("{label} *").into()
+        } else {
+            label.to_string().into()
+        }
+    }
+
+    /// Returns the value to send to the server, or `None` if an optional field was left empty.
+    fn value(&self, cx: &App) -> Result<Option<serde_json::Value>, SharedString> {
+        let label = self.schema.title().unwrap_or(&self.name);
+        let value = match &self.input {
+            FieldInput::Boolean(state) => Some(serde_json::Value::Bool(state.selected())),
+            FieldInput::Choice {
+                values, selected, ..
+            } => selected.map(|ix| serde_json::Value::String(values[ix].clone())),
+            FieldInput::Text(input) => {
+                let text = input.read(cx).text(cx);
+                let text = text.trim();
+                if text.is_empty() {
+                    None
+                } else {
+                    Some(match &self.schema {
+                        ElicitationPropertySchema::Number { .. } => text
+                            .parse::<f64>()
+                            .ok()
+                            .and_then(serde_json::Number::from_f64)
+                            .map(serde_json::Value::Number)
+                            .ok_or_else(|| {
+                                SharedString::from(format!("{label} must be a number"))
+                            })?,
+                        ElicitationPropertySchema::Integer { .. } => text
+                            .parse::<i64>()
+                            .map(serde_json::Value::from)
+                            .map_err(|_| {
+                                SharedString::from(format!("{label} must be an integer"))
+                            })?,
+                        _ => serde_json::Value::String(text.to_string()),
+                    })
+                }
+            }
+        };
+        if value.is_none() && self.required {
+            return Err(format!("{label} is required").into());
+        }
+        Ok(value)
+    }
+}
+
+/// Asks the user for the information a context server requested.
+pub struct ElicitationModal {
+    server_id: ContextServerId,
+    message: SharedString,
+    fields: Vec<ElicitationField>,
+    response_tx: Option<oneshot::Sender<ElicitationCreateResponse>>,
+    last_error: Option<SharedString>,
+    focus_handle: FocusHandle,
+    scroll_handle: ScrollHandle,
+}
+
+impl ElicitationModal {
+    fn new(
+        server_id: ContextServerId,
+        params: ElicitationCreateParams,
+        response_tx: oneshot::Sender<ElicitationCreateResponse>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Self {
+        let required = params.requested_schema.required;
+        let fields = params
+            .requested_schema
+            .properties
+            .into_iter()
+            .enumerate()
+            .map(|(ix, (name, schema))| {
+                let input = match &schema {
+                    ElicitationPropertySchema::Boolean { default, .. } => {
+                        FieldInput::Boolean(default.unwrap_or(false).into())
+                    }
+                    ElicitationPropertySchema::String {
+                        enum_values: Some(values),
+                        enum_names,
+                        default,
+                        ..
+                    } => FieldInput::Choice {
+                        labels: values
+                            .iter()
+                            .enumerate()
+                            .map(|(ix, value)| {
+                                enum_names
+                                    .as_ref()
+                                    .and_then(|names| names.get(ix))
+                                    .unwrap_or(value)
+                                    .clone()
+                                    .into()
+                            })
+                            .collect(),
+                        selected: default
+                            .as_ref()
+                            .and_then(|default| values.iter().position(|value| value == default)),
+                        values: values.clone(),
+                    },
+                    _ => {
+                        let default = match &schema {
+                            ElicitationPropertySchema::String { default, .. } => default.clone(),
+                            ElicitationPropertySchema::Number { default, .. } => {
+                                default.map(|default| default.to_string())
+                            }
+                            ElicitationPropertySchema::Integer { default, .. } => {
+                                default.map(|default| default.to_string())
+                            }
+                            ElicitationPropertySchema::Boolean { .. } => None,
+                        };
+                        let placeholder = schema.description().unwrap_or_default().to_string();
+                        FieldInput::Text(cx.new(|cx| {
+                            let input = InputField::new(window, cx, &placeholder)
+                                .tab_index(ix as isize)
+                                .tab_stop(true);
+                            if let Some(default) = default {
+                                input.set_text(&default, window, cx);
+                            }
+                            input
+                        }))
+                    }
+                };
+                ElicitationField {
+                    required: required.contains(&name),
+                    name,
+                    schema,
+                    input,
+                }
+            })
+            .collect();
+
+        Self {
+            server_id,
+            message: params.message.into(),
+            fields,
+            response_tx: Some(response_tx),
+            last_error: None,
+            focus_handle: cx.focus_handle(),
+            scroll_handle: ScrollHandle::new(),
+        }
+    }
+
+    fn respond(
+        &mut self,
+        action: ElicitationAction,
+        content: Option<HashMap<String, serde_json::Value>>,
+        cx: &mut Context<Self>,
+    ) {
+        if let Some(response_tx) = self.response_tx.take() {
+            response_tx
+                .send(ElicitationCreateResponse {
+                    action,
+                    content,
+                    meta: None,
+                })
+                .ok();
+        }
+        cx.emit(DismissEvent);
+    }
+
+    fn confirm(&mut self, _: &menu::Confirm, _: &mut Window, cx: &mut Context<Self>) {
+        let mut content = HashMap::default();
+        for field in &self.fields {
+            match field.value(cx) {
+                Ok(Some(value)) => {
+                    content.insert(field.name.clone(), value);
+                }
+                Ok(None) => {}
+                Err(error) => {
+                    self.last_error = Some(error);
+                    cx.notify();
+                    return;
+                }
+            }
+        }
+        self.respond(ElicitationAction::Accept, Some(content), cx);
+    }
+
+    fn decline(&mut self, cx: &mut Context<Self>) {
+        self.respond(ElicitationAction::Decline, None, cx);
+    }
+
+    fn cancel(&mut self, _: &menu::Cancel, _: &mut Window, cx: &mut Context<Self>) {
+        self.respond(ElicitationAction::Cancel, None, cx);
+    }
+
+    fn render_field(&self, ix: usize, cx: &mut Context<Self>) -> impl IntoElement + use<> {
+        let field = &self.fields[ix];
+        let input = match &field.input {
+            FieldInput::Text(input) => input.clone().into_any_element(),
+            FieldInput::Boolean(state) => Checkbox::new(("elicitation-boolean", ix), *state)
+                .label(field.label())
+                .on_click(cx.listener(move |this, checked, _window, cx| {
+                    if let FieldInput::Boolean(state) = &mut this.fields[ix].input {
+                        *state = *checked;
+                    }
+                    cx.notify();
+                }))
+                .into_any_element(),
+            FieldInput::Choice {
+                labels, selected, ..
+            } => h_flex()
+                .flex_wrap()
+                .gap_1()
+                .children(labels.iter().enumerate().map(|(choice_ix, label)| {
+                    Button::new(
+                        SharedString::from(format!("elicitation-choice-{ix}-{choice_ix}")),
+                        label.clone(),
+                    )
+                    .style(ButtonStyle::Outlined)
+                    .label_size(LabelSize::Small)
+                    .toggle_state(*selected == Some(choice_ix))
+                    .on_click(cx.listener(move |this, _, _window, cx| {
+                        if let FieldInput::Choice { selected, .. } = &mut this.fields[ix].input {
+                            *selected = Some(choice_ix);
+                        }
+                        cx.notify();
+                    }))
+                }))
+                .into_any_element(),
+        };
+
+        v_flex()
+            .gap_1()
+            .when(!matches!(field.input, FieldInput::Boolean(_)), |this| {
+                this.child(Label::new(field.label()).size(LabelSize::Small))
+            })
+            .child(input)
+            .when(!matches!(field.input, FieldInput::Text(_)), |this| {
+                this.when_some(field.schema.description(), |this, description| {
+                    this.child(
+                        Label::new(description.to_string())
+                            .size(LabelSize::Small)
+                            .color(Color::Muted),
+                    )
+                })
+            })
+    }
+}
+
+impl ModalView for ElicitationModal {}
+
+impl Focusable for ElicitationModal {
+    fn focus_handle(&self, _cx: &App) -> FocusHandle {
+        self.focus_handle.clone()
+    }
+}
+
+impl EventEmitter<DismissEvent> for ElicitationModal {}
+
+impl Render for ElicitationModal {
+    fn render(&mut self, window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        let focus_handle = self.focus_handle(cx);
+
+        v_flex()
+            .id("context-server-elicitation-modal")
+            .key_context("ContextServerElicitationModal")
+            .w(rems(34.))
+            .elevation_3(cx)
+            .on_action(cx.listener(Self::cancel))
+            .on_action(cx.listener(Self::confirm))
+            .track_focus(&self.focus_handle)
+            .child(
+                Modal::new("context-server-elicitation", None)
+                    .header(
+                        ModalHeader::new()
+                            .headline(format!("{} is asking for input", self.server_id.0))
+                            .description(self.message.clone()),
+                    )
+                    .when_some(self.last_error.clone(), |this, error| {
+                        this.section(
+                            Section::new().child(
+                                Banner::new()
+                                    .severity(Severity::Warning)
+                                    .child(div().text_xs().child(error)),
+                            ),
+                        )
+                    })
+                    .child(
+                        div()
+                            .size_full()
+                            .vertical_scrollbar_for(&self.scroll_handle, window, cx)
+                            .child(
+                                v_flex()
+                                    .id("modal_content")
+                                    .size_full()
+                                    .tab_group()
+                                    .max_h_128()
+                                    .pl_3()
+                                    .pr_4()
+                                    .pb_2()
+                                    .gap_2()
+                                    .overflow_y_scroll()
+                                    .track_scroll(&self.scroll_handle)
+                                    .children(
+                                        (0..self.fields.len()).map(|ix| self.render_field(ix, cx)),
+                                    ),
+                            ),
+                    )
+                    .footer(
+                        ModalFooter::new().end_slot(
+                            h_flex()
+                                .gap_1()
+                                .child(
+                                    Button::new("cancel", "Cancel")
+                                        .key_binding(
+                                            KeyBinding::for_action_in(
+                                                &menu::Cancel,
+                                                &focus_handle,
+                                                cx,
+                                            )
+                                            .map(|kb| kb.size(rems_from_px(12.))),
+                                        )
+                                        .on_click(cx.listener(|this, _event, window, cx| {
+                                            this.cancel(&menu::Cancel, window, cx)
+                                        })),
+                                )
+                                .child(Button::new("decline", "Decline").on_click(
+                                    cx.listener(|this, _event, _window, cx| this.decline(cx)),
+                                ))
+                                .child(
+                                    Button::new("accept", "Submit")
+                                        .key_binding(
+                                            KeyBinding::for_action_in(
+                                                &menu::Confirm,
+                                                &focus_handle,
+                                                cx,
+                                            )
+                                            .map(|kb| kb.size(rems_from_px(12.))),
+                                        )
+                                        .on_click(cx.listener(|this, _event, window, cx| {
+                                            this.confirm(&menu::Confirm, window, cx)
+                                        })),
+                                ),
+                        ),
+                    ),
+            )
+    }
+}
@@ -883,6 +883,8 @@ impl Database {
                         remote_upstream_url: db_repository_entry.remote_upstream_url.clone(),
                         remote_origin_url: db_repository_entry.remote_origin_url.clone(),
                         original_repo_abs_path: Some(db_repository_entry.abs_path),
+                        bisect: None,
+                        submodules: Vec::new(),
                     });
                 }
             }
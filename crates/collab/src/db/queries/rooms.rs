@@ -799,6 +799,8 @@ impl Database {
                             remote_upstream_url: db_repository.remote_upstream_url.clone(),
                             remote_origin_url: db_repository.remote_origin_url.clone(),
                             original_repo_abs_path: Some(db_repository.abs_path),
+                            bisect: None,
+                            submodules: Vec::new(),
                         });
                     }
                 }
@@ -5444,6 +5444,113 @@ async fn test_document_highlights(
     });
 }
 
+#[gpui::test(iterations = 10)]
+async fn test_type_hierarchy(
+    executor: BackgroundExecutor,
+    cx_a: &mut TestAppContext,
+    cx_b: &mut TestAppContext,
+) {
+    let mut server = TestServer::start(executor.clone()).await;
+    let client_a = server.create_client(cx_a, "user_a").await;
+    let client_b = server.create_client(cx_b, "user_b").await;
+    server
+        .create_room(&mut [(&client_a, cx_a), (&client_b, cx_b)])
+        .await;
+    let active_call_a = cx_a.read(ActiveCall::global);
+
+    client_a
+        .fs()
+        .insert_tree(
+            path!("/root-1"),
+            json!({
+                "main.rs": "trait A {}\nstruct B;\nimpl A for B {}",
+            }),
+        )
+        .await;
+
+    client_a.language_registry().add(rust_lang());
+    let mut fake_language_servers = client_a
+        .language_registry()
+        .register_fake_lsp("Rust", FakeLspAdapter::default());
+    client_b.language_registry().add(rust_lang());
+    client_b
+        .language_registry()
+        .register_fake_lsp_adapter("Rust", FakeLspAdapter::default());
+
+    let (project_a, worktree_id) = client_a.build_local_project(path!("/root-1"), cx_a).await;
+    let project_id = active_call_a
+        .update(cx_a, |call, cx| call.share_project(project_a.clone(), cx))
+        .await
+        .unwrap();
+    let project_b = client_b.join_remote_project(project_id, cx_b).await;
+
+    let (buffer_b, _handle) = project_b
+        .update(cx_b, |p, cx| {
+            p.open_buffer_with_lsp((worktree_id, rel_path("main.rs")), cx)
+        })
+        .await
+        .unwrap();
+
+    fn lsp_item(name: &str, line: u32, start: u32) -> lsp::TypeHierarchyItem {
+        lsp::TypeHierarchyItem {
+            name: name.to_string(),
+            kind: lsp::SymbolKind::STRUCT,
+            tags: None,
+            detail: None,
+            uri: lsp::Uri::from_file_path(path!("/root-1/main.rs")).unwrap(),
+            range: lsp::Range::new(lsp::Position::new(line, 0), lsp::Position::new(line, 10)),
+            selection_range: lsp::Range::new(
+                lsp::Position::new(line, start),
+                lsp::Position::new(line, start + 1),
+            ),
+            data: Some(json!({ "id": name })),
+        }
+    }
+
+    let fake_language_server = fake_language_servers.next().await.unwrap();
+    fake_language_server.set_request_handler::<lsp::request::TypeHierarchyPrepare, _, _>(
+        |params, _| async move {
+            assert_eq!(
+                params.text_document_position_params.position,
+                lsp::Position::new(1, 7)
+            );
+            Ok(Some(vec![lsp_item("B", 1, 7)]))
+        },
+    );
+    fake_language_server.set_request_handler::<lsp::request::TypeHierarchySupertypes, _, _>(
+        |params, _| async move {
+            // The item makes it back to the server as the server returned it.
+            assert_eq!(params.item, lsp_item("B", 1, 7));
+            Ok(Some(vec![lsp_item("A", 0, 6)]))
+        },
+    );
+    cx_a.run_until_parked();
+    cx_b.run_until_parked();
+
+    // Ask for the type hierarchy as the guest.
+    let items = project_b
+        .update(cx_b, |p, cx| p.prepare_type_hierarchy(&buffer_b, 18, cx))
+        .await
+        .unwrap();
+    assert_eq!(items.len(), 1);
+    let b = items[0].clone();
+    assert_eq!(b.name, "B");
+    assert_eq!(b.location.buffer, buffer_b);
+    buffer_b.read_with(cx_b, |buffer, _| {
+        assert_eq!(b.selection_range.to_offset(buffer), 18..19);
+    });
+
+    let supertypes = project_b
+        .update(cx_b, |p, cx| p.supertypes(&b, cx))
+        .await
+        .unwrap();
+    assert_eq!(supertypes.len(), 1);
+    assert_eq!(supertypes[0].name, "A");
+    buffer_b.read_with(cx_b, |buffer, _| {
+        assert_eq!(supertypes[0].selection_range.to_offset(buffer), 6..7);
+    });
+}
+
 #[gpui::test(iterations = 10)]
 async fn test_lsp_hover(
     executor: BackgroundExecutor,
@@ -53,13 +53,13 @@ pub(crate) struct Client {
     name: Arc<str>,
     subscription_set: Arc<Mutex<NotificationSubscriptionSet>>,
     response_handlers: Arc<Mutex<Option<HashMap<RequestId, ResponseHandler>>>>,
+    request_handlers: Arc<Mutex<HashMap<&'static str, RequestHandler>>>,
     #[allow(clippy::type_complexity)]
     #[allow(dead_code)]
     io_tasks: Mutex<Option<(Task<Option<()>>, Task<Option<()>>)>>,
     #[allow(dead_code)]
     output_done_rx: Mutex<Option<barrier::Receiver>>,
     executor: BackgroundExecutor,
-    #[allow(dead_code)]
     transport: Arc<dyn Transport>,
     request_timeout: Option<Duration>,
 }
@@ -202,12 +202,14 @@ impl Client {
             let response_handlers = response_handlers.clone();
             let request_handlers = request_handlers.clone();
             let transport = transport.clone();
+            let outbound_tx = outbound_tx.clone();
             async move |cx| {
                 Self::handle_input(
                     transport,
                     subscription_set,
                     request_handlers,
                     response_handlers,
+                    outbound_tx,
                     cx,
                 )
                 .log_err()
@@ -238,6 +240,7 @@ impl Client {
             server_id,
             subscription_set,
             response_handlers,
+            request_handlers,
             name: server_name,
             next_id: Default::default(),
             outbound_tx,
@@ -254,12 +257,14 @@ impl Client {
     /// This function continuously reads lines from the provided stdout stream,
     /// parses them as JSON-RPC responses or notifications, and dispatches them
     /// to the appropriate handlers. It processes both responses (which are matched
-    /// to pending requests) and notifications (which trigger registered handlers).
+    /// to pending requests), requests from the server (which are answered by registered
+    /// handlers) and notifications (which trigger registered handlers).
     async fn handle_input(
         transport: Arc<dyn Transport>,
         subscription_set: Arc<Mutex<NotificationSubscriptionSet>>,
         request_handlers: Arc<Mutex<HashMap<&'static str, RequestHandler>>>,
         response_handlers: Arc<Mutex<Option<HashMap<RequestId, ResponseHandler>>>>,
+        outbound_tx: channel::Sender<String>,
         cx: &mut AsyncApp,
     ) -> anyhow::Result<()> {
         let mut receiver = transport.receive();
@@ -274,6 +279,15 @@ impl Client {
                         request.params.unwrap_or(RawValue::NULL),
                         cx.clone(),
                     );
+                } else {
+                    log::debug!("Unhandled request from context server: {}", request.method);
+                    outbound_tx
+                        .try_send(Self::error_response(
+                            request.id,
+                            METHOD_NOT_FOUND,
+                            format!("Method not found: {}", request.method),
+                        ))
+                        .log_err();
                 }
             } else if let Ok(response) = serde_json::from_str::<AnyResponse>(&message) {
                 if let Some(handlers) = response_handlers.lock().as_mut()
@@ -452,6 +466,69 @@ impl Client {
         Ok(())
     }
 
+    /// Passes the protocol version negotiated with the server on to the transport.
+    pub(crate) fn set_protocol_version(&self, protocol_version: &str) {
+        self.transport.set_protocol_version(protocol_version);
+    }
+
+    /// Registers a handler for the requests the server sends to the client.
+    ///
+    /// The handler's result, or its error, is sent back to the server as the response to the request.
+    pub fn on_request<P, R>(
+        &self,
+        method: &'static str,
+        mut f: impl 'static + Send + FnMut(P, AsyncApp) -> Task<Result<R>>,
+    ) where
+        P: DeserializeOwned,
+        R: Serialize + 'static,
+    {
+        let outbound_tx = self.outbound_tx.clone();
+        self.request_handlers.lock().insert(
+            method,
+            Box::new(move |id, params, cx| {
+                let params = match serde_json::from_str(params.get()) {
+                    Ok(params) => params,
+                    Err(error) => {
+                        outbound_tx
+                            .try_send(Self::error_response(
+                                id,
+                                INVALID_PARAMS,
+                                format!("Invalid params: {error}"),
+                            ))
+                            .log_err();
+                        return;
+                    }
+                };
+                let response = f(params, cx.clone());
+                let outbound_tx = outbound_tx.clone();
+                cx.spawn(async move |_| {
+                    let response = match response.await {
+                        Ok(result) => serde_json::to_string(&Response {
+                            jsonrpc: JSON_RPC_VERSION,
+                            id,
+                            value: CspResult::Ok(Some(result)),
+                        })
+                        .unwrap(),
+                        Err(error) => {
+                            Self::error_response(id, INTERNAL_ERROR, format!("{error:#}"))
+                        }
+                    };
+                    outbound_tx.try_send(response).log_err();
+                })
+                .detach();
+            }),
+        );
+    }
+
+    fn error_response(id: RequestId, code: i32, message: String) -> String {
+        serde_json::to_string(&Response::<()> {
+            jsonrpc: JSON_RPC_VERSION,
+            id,
+            value: CspResult::Error(Some(Error { message, code })),
+        })
+        .unwrap()
+    }
+
     #[must_use]
     pub fn on_notification(
         &self,
@@ -15,7 +15,7 @@ use std::{fmt::Display, path::PathBuf};
 
 use anyhow::Result;
 use client::Client;
-use gpui::AsyncApp;
+use gpui::{AsyncApp, Task};
 use parking_lot::RwLock;
 pub use settings::ContextServerCommand;
 use url::Url;
@@ -31,6 +31,26 @@ impl Display for ContextServerId {
     }
 }
 
+/// Fulfils the requests a context server sends to Zed.
+pub trait ContextServerDelegate: 'static + Send + Sync {
+    /// Returns the directories the server is allowed to operate in.
+    fn roots(&self, cx: &mut AsyncApp) -> Result<Vec<types::Root>>;
+
+    /// Generates a message with one of the user's language models, on behalf of the server.
+    fn create_message(
+        &self,
+        params: types::CreateMessageRequest,
+        cx: &mut AsyncApp,
+    ) -> Task<Result<types::CreateMessageResult>>;
+
+    /// Asks the user for the information the server requested.
+    fn elicit(
+        &self,
+        params: types::ElicitationCreateParams,
+        cx: &mut AsyncApp,
+    ) -> Task<Result<types::ElicitationCreateResponse>>;
+}
+
 enum ContextServerTransport {
     Stdio(ContextServerCommand, Option<PathBuf>),
     Custom(Arc<dyn crate::transport::Transport>),
@@ -105,8 +125,12 @@ impl ContextServer {
         self.client.read().clone()
     }
 
-    pub async fn start(&self, cx: &AsyncApp) -> Result<()> {
-        self.initialize(self.new_client(cx)?).await
+    pub async fn start(
+        &self,
+        delegate: Option<Arc<dyn ContextServerDelegate>>,
+        cx: &AsyncApp,
+    ) -> Result<()> {
+        self.initialize(self.new_client(cx)?, delegate).await
     }
 
     fn new_client(&self, cx: &AsyncApp) -> Result<Client> {
@@ -132,14 +156,18 @@ impl ContextServer {
         })
     }
 
-    async fn initialize(&self, client: Client) -> Result<()> {
+    async fn initialize(
+        &self,
+        client: Client,
+        delegate: Option<Arc<dyn ContextServerDelegate>>,
+    ) -> Result<()> {
         log::debug!("starting context server {}", self.id);
         let protocol = crate::protocol::ModelContextProtocol::new(client);
         let client_info = types::Implementation {
             name: "Zed".to_string(),
             version: env!("CARGO_PKG_VERSION").to_string(),
         };
-        let initialized_protocol = protocol.initialize(client_info).await?;
+        let initialized_protocol = protocol.initialize(client_info, delegate).await?;
 
         log::debug!(
             "context server {} initialized: {:?}",
@@ -5,13 +5,15 @@
 //! read/write messages and the types from types.rs for serialization/deserialization
 //! of messages.
 
+use std::sync::Arc;
 use std::time::Duration;
 
 use anyhow::Result;
 use futures::channel::oneshot;
-use gpui::AsyncApp;
+use gpui::{AsyncApp, Task};
 use serde_json::Value;
 
+use crate::ContextServerDelegate;
 use crate::client::{Client, NotificationSubscription};
 use crate::types::{self, Notification, Request};
 
@@ -27,25 +29,38 @@ impl ModelContextProtocol {
     fn supported_protocols() -> Vec<types::ProtocolVersion> {
         vec![
             types::ProtocolVersion(types::LATEST_PROTOCOL_VERSION.to_string()),
+            types::ProtocolVersion(types::VERSION_2025_03_26.to_string()),
             types::ProtocolVersion(types::VERSION_2024_11_05.to_string()),
         ]
     }
 
+    /// Initializes the server, advertising roots, sampling and elicitation support if a delegate
+    /// is given to fulfil the corresponding requests.
     pub async fn initialize(
         self,
         client_info: types::Implementation,
+        delegate: Option<Arc<dyn ContextServerDelegate>>,
     ) -> Result<InitializedContextServerProtocol> {
+        let supports_requests = delegate.is_some();
         let params = types::InitializeParams {
             protocol_version: types::ProtocolVersion(types::LATEST_PROTOCOL_VERSION.to_string()),
             capabilities: types::ClientCapabilities {
                 experimental: None,
-                sampling: None,
-                roots: None,
+                sampling: supports_requests.then(|| serde_json::json!({})),
+                roots: supports_requests.then_some(types::RootsCapabilities {
+                    list_changed: Some(true),
+                }),
+                elicitation: supports_requests.then(|| serde_json::json!({})),
             },
             meta: None,
             client_info,
         };
 
+        // Servers can send requests as soon as they're initialized.
+        if let Some(delegate) = delegate {
+            self.handle_requests(delegate);
+        }
+
         let response: types::InitializeResponse = self
             .inner
             .request(types::requests::Initialize::METHOD, params)
@@ -58,6 +73,8 @@ impl ModelContextProtocol {
         );
 
         log::trace!("mcp server info {:?}", response.server_info);
+        self.inner
+            .set_protocol_version(&response.protocol_version.0);
 
         let initialized_protocol = InitializedContextServerProtocol {
             inner: self.inner,
@@ -68,6 +85,28 @@ impl ModelContextProtocol {
 
         Ok(initialized_protocol)
     }
+
+    fn handle_requests(&self, delegate: Arc<dyn ContextServerDelegate>) {
+        self.inner.on_request(types::requests::ListRoots::METHOD, {
+            let delegate = delegate.clone();
+            move |_: Option<Value>, mut cx| {
+                Task::ready(
+                    delegate
+                        .roots(&mut cx)
+                        .map(|roots| types::ListRootsResponse { roots, meta: None }),
+                )
+            }
+        });
+        self.inner
+            .on_request(types::requests::CreateMessage::METHOD, {
+                let delegate = delegate.clone();
+                move |params, mut cx| delegate.create_message(params, &mut cx)
+            });
+        self.inner.on_request(
+            types::requests::ElicitationCreate::METHOD,
+            move |params, mut cx| delegate.elicit(params, &mut cx),
+        );
+    }
 }
 
 pub struct InitializedContextServerProtocol {
@@ -1,8 +1,16 @@
 use anyhow::Context as _;
 use collections::HashMap;
-use futures::{FutureExt, Stream, StreamExt as _, future::BoxFuture, lock::Mutex};
+use futures::{
+    FutureExt, Stream, StreamExt as _, channel::oneshot, future::BoxFuture, lock::Mutex,
+};
 use gpui::BackgroundExecutor;
-use std::{pin::Pin, sync::Arc};
+use std::{
+    pin::Pin,
+    sync::{
+        Arc,
+        atomic::{AtomicUsize, Ordering::SeqCst},
+    },
+};
 
 use crate::{
     transport::Transport,
@@ -41,6 +49,9 @@ pub struct FakeTransport {
     >,
     tx: futures::channel::mpsc::UnboundedSender<String>,
     rx: Arc<Mutex<futures::channel::mpsc::UnboundedReceiver<String>>>,
+    client_response_handlers:
+        parking_lot::Mutex<HashMap<String, oneshot::Sender<serde_json::Value>>>,
+    next_client_request_id: AtomicUsize,
     executor: BackgroundExecutor,
 }
 
@@ -51,10 +62,52 @@ impl FakeTransport {
             request_handlers: Default::default(),
             tx,
             rx: Arc::new(Mutex::new(rx)),
+            client_response_handlers: Default::default(),
+            next_client_request_id: Default::default(),
             executor,
         }
     }
 
+    /// Sends a request to the client, as the server would, and resolves to the client's response.
+    pub fn request_client<T: crate::types::Request>(
+        &self,
+        params: T::Params,
+    ) -> impl Future<Output = anyhow::Result<T::Response>> + use<T> {
+        let id = format!(
+            "server-{}",
+            self.next_client_request_id.fetch_add(1, SeqCst)
+        );
+        let (response_tx, response_rx) = oneshot::channel();
+        self.client_response_handlers
+            .lock()
+            .insert(id.clone(), response_tx);
+        let request = serde_json::json!({
+            "jsonrpc": "2.0",
+            "id": id,
+            "method": T::METHOD,
+            "params": params,
+        });
+        let sent = self.tx.unbounded_send(request.to_string());
+        async move {
+            sent.context("sending a request to the client")?;
+            let mut response = response_rx.await?;
+            if let Some(error) = response.get("error") {
+                anyhow::bail!("client responded with an error: {error}");
+            }
+            Ok(serde_json::from_value(response["result"].take())?)
+        }
+    }
+
+    /// Sends a notification to the client, as the server would.
+    pub fn notify_client<T: crate::types::Notification>(&self, params: T::Params) {
+        let notification = serde_json::json!({
+            "jsonrpc": "2.0",
+            "method": T::METHOD,
+            "params": params,
+        });
+        self.tx.unbounded_send(notification.to_string()).ok();
+    }
+
     pub fn on_request<T, Fut>(
         mut self,
         handler: impl 'static + Send + Sync + Fn(T::Params) -> Fut,
@@ -101,6 +154,10 @@ impl Transport for FakeTransport {
                 } else {
                     log::debug!("No handler registered for MCP request '{method}'");
                 }
+            } else if let Some(id) = msg.get("id").and_then(|id| id.as_str())
+                && let Some(response_tx) = self.client_response_handlers.lock().remove(id)
+            {
+                response_tx.send(msg).ok();
             }
         }
         Ok(())
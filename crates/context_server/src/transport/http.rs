@@ -12,6 +12,7 @@ use crate::transport::Transport;
 
 // Constants from MCP spec
 const HEADER_SESSION_ID: &str = "Mcp-Session-Id";
+const HEADER_PROTOCOL_VERSION: &str = "MCP-Protocol-Version";
 const EVENT_STREAM_MIME_TYPE: &str = "text/event-stream";
 const JSON_MIME_TYPE: &str = "application/json";
 
@@ -20,6 +21,8 @@ pub struct HttpTransport {
     http_client: Arc<dyn HttpClient>,
     endpoint: String,
     session_id: Arc<SyncMutex<Option<String>>>,
+    /// The protocol version negotiated during initialization, sent with every later request.
+    protocol_version: SyncMutex<Option<String>>,
     executor: BackgroundExecutor,
     response_tx: channel::Sender<String>,
     response_rx: channel::Receiver<String>,
@@ -44,6 +47,7 @@ impl HttpTransport {
             executor,
             endpoint,
             session_id: Arc::new(SyncMutex::new(None)),
+            protocol_version: SyncMutex::new(None),
             response_tx,
             response_rx,
             error_tx,
@@ -74,6 +78,10 @@ impl HttpTransport {
         if let Some(ref session_id) = *self.session_id.lock() {
             request_builder = request_builder.header(HEADER_SESSION_ID, session_id.as_str());
         }
+        if let Some(ref protocol_version) = *self.protocol_version.lock() {
+            request_builder =
+                request_builder.header(HEADER_PROTOCOL_VERSION, protocol_version.as_str());
+        }
 
         let request = request_builder.body(AsyncBody::from(message.into_bytes()))?;
         let mut response = self.http_client.send(request).await?;
@@ -224,6 +232,10 @@ impl Transport for HttpTransport {
     fn receive_err(&self) -> Pin<Box<dyn Stream<Item = String> + Send>> {
         Box::pin(self.error_rx.clone())
     }
+
+    fn set_protocol_version(&self, protocol_version: &str) {
+        *self.protocol_version.lock() = Some(protocol_version.to_string());
+    }
 }
 
 impl Drop for HttpTransport {
@@ -232,6 +244,7 @@ impl Drop for HttpTransport {
         let http_client = self.http_client.clone();
         let endpoint = self.endpoint.clone();
         let session_id = self.session_id.lock().clone();
+        let protocol_version = self.protocol_version.lock().clone();
         let headers = self.headers.clone();
 
         if let Some(session_id) = session_id {
@@ -241,6 +254,10 @@ impl Drop for HttpTransport {
                         .method(Method::DELETE)
                         .uri(&endpoint)
                         .header(HEADER_SESSION_ID, &session_id);
+                    if let Some(protocol_version) = protocol_version {
+                        request_builder =
+                            request_builder.header(HEADER_PROTOCOL_VERSION, protocol_version);
+                    }
 
                     // Add authentication headers if present
                     for (key, value) in headers {
@@ -47,6 +47,14 @@ pub trait DapDelegate: Send + Sync + 'static {
     async fn read_text_file(&self, path: &RelPath) -> Result<String>;
     async fn shell_env(&self) -> collections::HashMap<String, String>;
     fn is_headless(&self) -> bool;
+    /// Sends a `workspace/executeCommand` request to the language server with the given name,
+    /// if one is running for this worktree.
+    async fn execute_language_server_command(
+        &self,
+        server_name: &str,
+        command: &str,
+        arguments: Vec<serde_json::Value>,
+    ) -> Result<serde_json::Value>;
 }
 
 #[derive(
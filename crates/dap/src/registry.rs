@@ -9,7 +9,10 @@ use task::{
 };
 
 use crate::adapters::{DebugAdapter, DebugAdapterName};
-use std::{collections::BTreeMap, sync::Arc};
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    sync::Arc,
+};
 
 /// Given a user build configuration, locator creates a fill-in debug target ([DebugScenario]) on behalf of the user.
 #[async_trait]
@@ -34,6 +37,10 @@ pub trait DapLocator: Send + Sync {
 struct DapRegistryState {
     adapters: BTreeMap<DebugAdapterName, Arc<dyn DebugAdapter>>,
     locators: FxHashMap<SharedString, Arc<dyn DapLocator>>,
+    /// The names of the adapters provided by extensions.
+    extension_adapters: BTreeSet<DebugAdapterName>,
+    /// Built-in adapters that extension adapters with the same name took the place of.
+    shadowed_adapters: BTreeMap<DebugAdapterName, Arc<dyn DebugAdapter>>,
 }
 
 #[derive(Clone, Default)]
@@ -59,6 +66,32 @@ impl DapRegistry {
         self.0.write().adapters.remove(name);
     }
 
+    /// Adds an adapter provided by an extension. It takes the place of the built-in adapter with
+    /// the same name, if there's one, until it's removed.
+    pub fn add_extension_adapter(&self, adapter: Arc<dyn DebugAdapter>) {
+        let name = adapter.name();
+        let mut state = self.0.write();
+        if let Some(previous) = state.adapters.insert(name.clone(), adapter)
+            && !state.extension_adapters.contains(&name)
+        {
+            state.shadowed_adapters.insert(name.clone(), previous);
+        }
+        state.extension_adapters.insert(name);
+    }
+
+    /// Removes an adapter provided by an extension, restoring the built-in adapter it took the
+    /// place of.
+    pub fn remove_extension_adapter(&self, name: &str) {
+        let mut state = self.0.write();
+        if !state.extension_adapters.remove(name) {
+            return;
+        }
+        state.adapters.remove(name);
+        if let Some(built_in) = state.shadowed_adapters.remove(name) {
+            state.adapters.insert(built_in.name(), built_in);
+        }
+    }
+
     pub fn remove_locator(&self, locator: &str) {
         self.0.write().locators.remove(locator);
     }
@@ -95,3 +128,45 @@ impl DapRegistry {
         self.0.read().adapters.keys().cloned().collect()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::FakeAdapter;
+
+    #[test]
+    fn test_extension_adapter_replaces_built_in_adapter() {
+        let registry = DapRegistry::default();
+        let built_in: Arc<dyn DebugAdapter> = Arc::new(FakeAdapter::new());
+        registry.add_adapter(built_in.clone());
+
+        let extension: Arc<dyn DebugAdapter> = Arc::new(FakeAdapter::new());
+        registry.add_extension_adapter(extension.clone());
+        let adapter = registry.adapter(FakeAdapter::ADAPTER_NAME).unwrap();
+        assert!(Arc::ptr_eq(&adapter, &extension));
+
+        // Reloading the extension doesn't lose the built-in adapter.
+        let reloaded: Arc<dyn DebugAdapter> = Arc::new(FakeAdapter::new());
+        registry.add_extension_adapter(reloaded.clone());
+        let adapter = registry.adapter(FakeAdapter::ADAPTER_NAME).unwrap();
+        assert!(Arc::ptr_eq(&adapter, &reloaded));
+
+        registry.remove_extension_adapter(FakeAdapter::ADAPTER_NAME);
+        let adapter = registry.adapter(FakeAdapter::ADAPTER_NAME).unwrap();
+        assert!(Arc::ptr_eq(&adapter, &built_in));
+
+        // Built-in adapters aren't removed as extension adapters.
+        registry.remove_extension_adapter(FakeAdapter::ADAPTER_NAME);
+        assert!(registry.adapter(FakeAdapter::ADAPTER_NAME).is_some());
+    }
+
+    #[test]
+    fn test_remove_extension_adapter_without_built_in_adapter() {
+        let registry = DapRegistry::default();
+        registry.add_extension_adapter(Arc::new(FakeAdapter::new()));
+        assert!(registry.adapter(FakeAdapter::ADAPTER_NAME).is_some());
+
+        registry.remove_extension_adapter(FakeAdapter::ADAPTER_NAME);
+        assert!(registry.adapter(FakeAdapter::ADAPTER_NAME).is_none());
+    }
+}
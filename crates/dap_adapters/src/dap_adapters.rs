@@ -1,8 +1,12 @@
 mod codelldb;
 mod gdb;
 mod go;
+mod java;
 mod javascript;
+mod netcoredbg;
+mod php;
 mod python;
+mod ruby;
 
 #[cfg(test)]
 use std::path::PathBuf;
@@ -21,8 +25,12 @@ use dap::{
 use gdb::GdbDebugAdapter;
 use go::GoDebugAdapter;
 use gpui::{App, BorrowAppContext};
+use java::JavaDebugAdapter;
 use javascript::JsDebugAdapter;
+use netcoredbg::NetCoreDbgDebugAdapter;
+use php::PhpDebugAdapter;
 use python::PythonDebugAdapter;
+use ruby::RubyDebugAdapter;
 use serde_json::json;
 use task::{DebugScenario, ZedDebugConfig};
 
@@ -33,6 +41,10 @@ pub fn init(cx: &mut App) {
         registry.add_adapter(Arc::from(JsDebugAdapter::default()));
         registry.add_adapter(Arc::from(GoDebugAdapter::default()));
         registry.add_adapter(Arc::from(GdbDebugAdapter));
+        registry.add_adapter(Arc::from(JavaDebugAdapter));
+        registry.add_adapter(Arc::from(NetCoreDbgDebugAdapter::default()));
+        registry.add_adapter(Arc::from(RubyDebugAdapter));
+        registry.add_adapter(Arc::from(PhpDebugAdapter::default()));
 
         #[cfg(any(test, feature = "test-support"))]
         {
@@ -100,5 +112,14 @@ mod test_mocks {
         fn is_headless(&self) -> bool {
             false
         }
+
+        async fn execute_language_server_command(
+            &self,
+            _server_name: &str,
+            _command: &str,
+            _arguments: Vec<serde_json::Value>,
+        ) -> Result<serde_json::Value> {
+            unimplemented!("Not needed for tests")
+        }
     }
 }
@@ -0,0 +1,310 @@
+use anyhow::{Context as _, bail};
+use collections::HashMap;
+use dap::{
+    StartDebuggingRequestArguments, StartDebuggingRequestArgumentsRequest,
+    adapters::{DebugTaskDefinition, TcpArguments},
+};
+use gpui::{AsyncApp, SharedString};
+use language::LanguageName;
+use serde_json::Value;
+use std::{net::Ipv4Addr, path::PathBuf};
+
+use crate::*;
+
+/// Debugs Java programs through [java-debug](https://github.com/microsoft/java-debug), which runs
+/// as a plugin inside of jdtls rather than as a standalone process.
+#[derive(Default, Debug)]
+pub(crate) struct JavaDebugAdapter;
+
+impl JavaDebugAdapter {
+    const ADAPTER_NAME: &'static str = "Java";
+    const LANGUAGE_SERVER_NAME: &'static str = "jdtls";
+
+    /// Asks jdtls to start a java-debug server and returns the port it listens on.
+    async fn start_debug_session(delegate: &Arc<dyn DapDelegate>) -> Result<u16> {
+        let port = delegate
+            .execute_language_server_command(
+                Self::LANGUAGE_SERVER_NAME,
+                "vscode.java.startDebugSession",
+                Vec::new(),
+            )
+            .await
+            .context(
+                "Failed to start java-debug. Make sure jdtls is running and was started with the java-debug plugin in its `bundles`",
+            )?;
+        port.as_u64()
+            .and_then(|port| u16::try_from(port).ok())
+            .with_context(|| format!("jdtls returned an invalid java-debug port: {port}"))
+    }
+
+    /// Fills in the class and module paths of a launch configuration from the jdtls project model,
+    /// unless they were specified by the user.
+    async fn resolve_classpath(
+        delegate: &Arc<dyn DapDelegate>,
+        configuration: &mut serde_json::Map<String, Value>,
+    ) -> Result<()> {
+        if configuration.contains_key("classPaths") || configuration.contains_key("modulePaths") {
+            return Ok(());
+        }
+        let main_class = configuration
+            .get("mainClass")
+            .and_then(Value::as_str)
+            .context("Java launch configurations require a `mainClass`")?
+            .to_owned();
+        let project_name = configuration
+            .get("projectName")
+            .cloned()
+            .unwrap_or(Value::Null);
+
+        let response = delegate
+            .execute_language_server_command(
+                Self::LANGUAGE_SERVER_NAME,
+                "vscode.java.resolveClasspath",
+                vec![main_class.clone().into(), project_name],
+            )
+            .await
+            .with_context(|| format!("resolving the classpath of {main_class}"))?;
+        let Some([module_paths, class_paths]) = response
+            .as_array()
+            .and_then(|paths| <&[Value; 2]>::try_from(paths.as_slice()).ok())
+        else {
+            bail!("jdtls returned an invalid classpath for {main_class}: {response}");
+        };
+        configuration.insert("modulePaths".into(), module_paths.clone());
+        configuration.insert("classPaths".into(), class_paths.clone());
+        Ok(())
+    }
+}
+
+#[async_trait(?Send)]
+impl DebugAdapter for JavaDebugAdapter {
+    fn name(&self) -> DebugAdapterName {
+        DebugAdapterName(Self::ADAPTER_NAME.into())
+    }
+
+    fn adapter_language_name(&self) -> Option<LanguageName> {
+        Some(SharedString::new_static("Java").into())
+    }
+
+    async fn config_from_zed_format(&self, zed_scenario: ZedDebugConfig) -> Result<DebugScenario> {
+        let mut config = match &zed_scenario.request {
+            dap::DebugRequest::Attach(_) => {
+                bail!(
+                    "java-debug can't attach to a process by its id. Start the JVM with a JDWP agent and use an `attach` configuration with `hostName` and `port` instead"
+                );
+            }
+            dap::DebugRequest::Launch(launch) => {
+                json!({
+                    "request": "launch",
+                    "mainClass": launch.program,
+                    "args": launch.args,
+                    "env": launch.env_json(),
+                })
+            }
+        };
+
+        let map = config.as_object_mut().unwrap();
+        if let dap::DebugRequest::Launch(launch) = &zed_scenario.request
+            && let Some(cwd) = launch.cwd.as_ref()
+        {
+            map.insert("cwd".into(), cwd.to_string_lossy().into_owned().into());
+        }
+        if let Some(stop_on_entry) = zed_scenario.stop_on_entry {
+            map.insert("stopOnEntry".into(), stop_on_entry.into());
+        }
+
+        Ok(DebugScenario {
+            adapter: zed_scenario.adapter,
+            label: zed_scenario.label,
+            build: None,
+            config,
+            tcp_connection: None,
+        })
+    }
+
+    fn dap_schema(&self) -> serde_json::Value {
+        json!({
+            "oneOf": [
+                {
+                    "allOf": [
+                        {
+                            "type": "object",
+                            "required": ["request"],
+                            "properties": {
+                                "request": {
+                                    "type": "string",
+                                    "enum": ["launch"],
+                                    "description": "Request to launch a new JVM"
+                                }
+                            }
+                        },
+                        {
+                            "type": "object",
+                            "properties": {
+                                "mainClass": {
+                                    "type": "string",
+                                    "description": "The fully qualified name of the class containing the main method. May be prefixed with the module name, e.g. `module/com.example.Main`."
+                                },
+                                "projectName": {
+                                    "type": "string",
+                                    "description": "The project the main class belongs to, used to resolve its classpath when there are several classes with the same name."
+                                },
+                                "args": {
+                                    "type": ["array", "string"],
+                                    "items": {
+                                        "type": "string"
+                                    },
+                                    "description": "Command line arguments passed to the program.",
+                                    "default": []
+                                },
+                                "vmArgs": {
+                                    "type": ["array", "string"],
+                                    "items": {
+                                        "type": "string"
+                                    },
+                                    "description": "Extra options passed to the JVM, e.g. `-Xmx1G`.",
+                                    "default": []
+                                },
+                                "classPaths": {
+                                    "type": "array",
+                                    "items": {
+                                        "type": "string"
+                                    },
+                                    "description": "The classpath of the program. Resolved by jdtls when omitted."
+                                },
+                                "modulePaths": {
+                                    "type": "array",
+                                    "items": {
+                                        "type": "string"
+                                    },
+                                    "description": "The module path of the program. Resolved by jdtls when omitted."
+                                },
+                                "cwd": {
+                                    "type": "string",
+                                    "description": "Working directory of the program.",
+                                    "default": "${ZED_WORKTREE_ROOT}"
+                                },
+                                "env": {
+                                    "type": "object",
+                                    "description": "Environment variables for the program.",
+                                    "default": {}
+                                },
+                                "javaExec": {
+                                    "type": "string",
+                                    "description": "Path to the java executable used to launch the program. Defaults to the runtime that jdtls uses for the project."
+                                },
+                                "stopOnEntry": {
+                                    "type": "boolean",
+                                    "description": "Automatically pause the program after launching it.",
+                                    "default": false
+                                },
+                                "console": {
+                                    "type": "string",
+                                    "enum": ["internalConsole", "integratedTerminal", "externalTerminal"],
+                                    "description": "Where to launch the program.",
+                                    "default": "internalConsole"
+                                },
+                                "shortenCommandLine": {
+                                    "type": "string",
+                                    "enum": ["none", "jarmanifest", "argfile", "auto"],
+                                    "description": "How to shorten the command line when it exceeds the operating system's limit.",
+                                    "default": "auto"
+                                }
+                            },
+                            "required": ["mainClass"]
+                        }
+                    ]
+                },
+                {
+                    "allOf": [
+                        {
+                            "type": "object",
+                            "required": ["request"],
+                            "properties": {
+                                "request": {
+                                    "type": "string",
+                                    "enum": ["attach"],
+                                    "description": "Request to attach to a JVM that's listening for JDWP connections"
+                                }
+                            }
+                        },
+                        {
+                            "type": "object",
+                            "properties": {
+                                "hostName": {
+                                    "type": "string",
+                                    "description": "The host of the JVM's JDWP agent.",
+                                    "default": "localhost"
+                                },
+                                "port": {
+                                    "type": "number",
+                                    "description": "The port of the JVM's JDWP agent.",
+                                    "default": 5005
+                                },
+                                "projectName": {
+                                    "type": "string",
+                                    "description": "The project whose sources should be used to resolve stack frames."
+                                },
+                                "timeout": {
+                                    "type": "number",
+                                    "description": "Time in milliseconds to wait for the JVM before giving up.",
+                                    "default": 30000
+                                }
+                            },
+                            "required": ["hostName", "port"]
+                        }
+                    ]
+                }
+            ]
+        })
+    }
+
+    async fn get_binary(
+        &self,
+        delegate: &Arc<dyn DapDelegate>,
+        task_definition: &DebugTaskDefinition,
+        _user_installed_path: Option<PathBuf>,
+        _user_args: Option<Vec<String>>,
+        user_env: Option<HashMap<String, String>>,
+        _: &mut AsyncApp,
+    ) -> Result<DebugAdapterBinary> {
+        let request = self.request_kind(&task_definition.config).await?;
+
+        let mut configuration = task_definition.config.clone();
+        let obj = configuration
+            .as_object_mut()
+            .context("Java debug configuration is not a valid json object")?;
+        obj.entry("cwd")
+            .or_insert(delegate.worktree_root_path().to_string_lossy().into());
+        if matches!(request, StartDebuggingRequestArgumentsRequest::Launch) {
+            Self::resolve_classpath(delegate, obj).await?;
+        }
+
+        let connection = if let Some(tcp_connection) = task_definition.tcp_connection.clone() {
+            let (host, port, timeout) = crate::configure_tcp_connection(tcp_connection).await?;
+            TcpArguments {
+                host,
+                port,
+                timeout,
+            }
+        } else {
+            TcpArguments {
+                host: Ipv4Addr::LOCALHOST,
+                port: Self::start_debug_session(delegate).await?,
+                timeout: None,
+            }
+        };
+
+        Ok(DebugAdapterBinary {
+            command: None,
+            arguments: Vec::new(),
+            envs: user_env.unwrap_or_default(),
+            cwd: Some(delegate.worktree_root_path().to_path_buf()),
+            connection: Some(connection),
+            request_args: StartDebuggingRequestArguments {
+                request,
+                configuration,
+            },
+        })
+    }
+}
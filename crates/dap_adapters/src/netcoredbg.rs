@@ -0,0 +1,285 @@
+use anyhow::{Context as _, bail};
+use collections::HashMap;
+use dap::{
+    StartDebuggingRequestArguments,
+    adapters::{
+        DebugTaskDefinition, DownloadedFileType, download_adapter_from_github,
+        latest_github_release,
+    },
+};
+use gpui::{AsyncApp, SharedString};
+use language::LanguageName;
+use serde_json::{Map, Value};
+use std::{env::consts, ffi::OsStr, path::PathBuf, sync::OnceLock};
+
+use crate::*;
+
+#[derive(Default, Debug)]
+pub(crate) struct NetCoreDbgDebugAdapter {
+    path_to_netcoredbg: OnceLock<PathBuf>,
+}
+
+impl NetCoreDbgDebugAdapter {
+    const ADAPTER_NAME: &'static str = "netcoredbg";
+
+    async fn fetch_latest_adapter_version(
+        delegate: &Arc<dyn DapDelegate>,
+    ) -> Result<AdapterVersion> {
+        let release =
+            latest_github_release("Samsung/netcoredbg", true, false, delegate.http_client())
+                .await?;
+
+        let asset_name = match (consts::OS, consts::ARCH) {
+            ("linux", "x86_64") => "netcoredbg-linux-amd64.tar.gz",
+            ("linux", "aarch64") => "netcoredbg-linux-arm64.tar.gz",
+            ("macos", "x86_64") => "netcoredbg-osx-amd64.tar.gz",
+            ("windows", "x86_64") => "netcoredbg-win64.zip",
+            (os, arch) => bail!("netcoredbg doesn't provide builds for {os} on {arch}"),
+        };
+        let asset = release
+            .assets
+            .iter()
+            .find(|asset| asset.name == asset_name)
+            .with_context(|| format!("no asset found matching `{asset_name:?}`"))?;
+
+        Ok(AdapterVersion {
+            tag_name: release.tag_name,
+            url: asset.browser_download_url.clone(),
+        })
+    }
+
+    async fn install(&self, delegate: &Arc<dyn DapDelegate>) -> Result<PathBuf> {
+        if let Some(path) = self.path_to_netcoredbg.get().cloned() {
+            return Ok(path);
+        }
+
+        delegate.output_to_console(format!("Checking latest version of {}...", self.name()));
+        let version = Self::fetch_latest_adapter_version(delegate).await?;
+        let file_type = if consts::OS == "windows" {
+            DownloadedFileType::Zip
+        } else {
+            DownloadedFileType::GzipTar
+        };
+        let version_path =
+            download_adapter_from_github(self.name(), version, file_type, delegate.as_ref())
+                .await?;
+
+        let path = version_path
+            .join("netcoredbg")
+            .join(format!("netcoredbg{}", consts::EXE_SUFFIX));
+        self.path_to_netcoredbg.set(path.clone()).ok();
+        Ok(path)
+    }
+}
+
+#[async_trait(?Send)]
+impl DebugAdapter for NetCoreDbgDebugAdapter {
+    fn name(&self) -> DebugAdapterName {
+        DebugAdapterName(Self::ADAPTER_NAME.into())
+    }
+
+    fn adapter_language_name(&self) -> Option<LanguageName> {
+        Some(SharedString::new_static("C#").into())
+    }
+
+    async fn config_from_zed_format(&self, zed_scenario: ZedDebugConfig) -> Result<DebugScenario> {
+        let mut config = match &zed_scenario.request {
+            dap::DebugRequest::Attach(attach) => {
+                json!({
+                    "request": "attach",
+                    "processId": attach.process_id,
+                })
+            }
+            dap::DebugRequest::Launch(launch) => {
+                json!({
+                    "request": "launch",
+                    "program": launch.program,
+                    "args": launch.args,
+                    "env": launch.env_json(),
+                })
+            }
+        };
+
+        let map = config.as_object_mut().unwrap();
+        if let dap::DebugRequest::Launch(launch) = &zed_scenario.request
+            && let Some(cwd) = launch.cwd.as_ref()
+        {
+            map.insert("cwd".into(), cwd.to_string_lossy().into_owned().into());
+        }
+        if let Some(stop_on_entry) = zed_scenario.stop_on_entry {
+            map.insert("stopAtEntry".into(), stop_on_entry.into());
+        }
+
+        Ok(DebugScenario {
+            adapter: zed_scenario.adapter,
+            label: zed_scenario.label,
+            build: None,
+            config,
+            tcp_connection: None,
+        })
+    }
+
+    fn dap_schema(&self) -> serde_json::Value {
+        json!({
+            "oneOf": [
+                {
+                    "allOf": [
+                        {
+                            "type": "object",
+                            "required": ["request"],
+                            "properties": {
+                                "request": {
+                                    "type": "string",
+                                    "enum": ["launch"],
+                                    "description": "Request to launch a new process"
+                                }
+                            }
+                        },
+                        {
+                            "type": "object",
+                            "properties": {
+                                "program": {
+                                    "type": "string",
+                                    "description": "Path to the program to debug. Assemblies (`.dll` files) are run through `dotnet`."
+                                },
+                                "args": {
+                                    "type": "array",
+                                    "items": {
+                                        "type": "string"
+                                    },
+                                    "description": "Command line arguments passed to the program.",
+                                    "default": []
+                                },
+                                "cwd": {
+                                    "type": "string",
+                                    "description": "Working directory of the program.",
+                                    "default": "${ZED_WORKTREE_ROOT}"
+                                },
+                                "env": {
+                                    "type": "object",
+                                    "description": "Environment variables for the program.",
+                                    "default": {}
+                                },
+                                "stopAtEntry": {
+                                    "type": "boolean",
+                                    "description": "Automatically pause the program after launching it.",
+                                    "default": false
+                                },
+                                "justMyCode": {
+                                    "type": "boolean",
+                                    "description": "Only step through and break in user code.",
+                                    "default": true
+                                },
+                                "enableStepFiltering": {
+                                    "type": "boolean",
+                                    "description": "Step over properties and operators.",
+                                    "default": true
+                                }
+                            },
+                            "required": ["program"]
+                        }
+                    ]
+                },
+                {
+                    "allOf": [
+                        {
+                            "type": "object",
+                            "required": ["request"],
+                            "properties": {
+                                "request": {
+                                    "type": "string",
+                                    "enum": ["attach"],
+                                    "description": "Request to attach to an existing process"
+                                }
+                            }
+                        },
+                        {
+                            "type": "object",
+                            "properties": {
+                                "processId": {
+                                    "type": ["number", "string"],
+                                    "description": "The id of the .NET process to attach to."
+                                },
+                                "justMyCode": {
+                                    "type": "boolean",
+                                    "description": "Only step through and break in user code.",
+                                    "default": true
+                                }
+                            },
+                            "required": ["processId"]
+                        }
+                    ]
+                }
+            ]
+        })
+    }
+
+    async fn get_binary(
+        &self,
+        delegate: &Arc<dyn DapDelegate>,
+        task_definition: &DebugTaskDefinition,
+        user_installed_path: Option<PathBuf>,
+        user_args: Option<Vec<String>>,
+        user_env: Option<HashMap<String, String>>,
+        _: &mut AsyncApp,
+    ) -> Result<DebugAdapterBinary> {
+        let netcoredbg_path = if let Some(path) = user_installed_path {
+            path
+        } else if let Some(path) = delegate.which(OsStr::new("netcoredbg")).await {
+            path
+        } else {
+            self.install(delegate).await?
+        };
+
+        let mut configuration = task_definition.config.clone();
+        if let Some(configuration) = configuration.as_object_mut() {
+            configuration
+                .entry("cwd")
+                .or_insert_with(|| delegate.worktree_root_path().to_string_lossy().into());
+            run_assembly_through_dotnet_host(configuration);
+        }
+
+        let arguments = if let Some(args) = user_args {
+            args
+        } else {
+            vec!["--interpreter=vscode".into()]
+        };
+
+        Ok(DebugAdapterBinary {
+            command: Some(netcoredbg_path.to_string_lossy().into_owned()),
+            arguments,
+            envs: user_env.unwrap_or_default(),
+            cwd: Some(delegate.worktree_root_path().to_path_buf()),
+            connection: None,
+            request_args: StartDebuggingRequestArguments {
+                request: self.request_kind(&task_definition.config).await?,
+                configuration,
+            },
+        })
+    }
+}
+
+/// Framework-dependent assemblies can't be executed directly, so launch them with `dotnet`.
+fn run_assembly_through_dotnet_host(configuration: &mut Map<String, Value>) {
+    let Some(assembly) = configuration
+        .get("program")
+        .and_then(Value::as_str)
+        .filter(|program| program.ends_with(".dll"))
+        .map(str::to_owned)
+    else {
+        return;
+    };
+    let args = Some(Value::from(assembly))
+        .into_iter()
+        .chain(
+            configuration
+                .get("args")
+                .and_then(Value::as_array)
+                .into_iter()
+                .flatten()
+                .cloned(),
+        )
+        .collect::<Vec<_>>();
+    configuration.insert("program".into(), "dotnet".into());
+    configuration.insert("args".into(), args.into());
+}
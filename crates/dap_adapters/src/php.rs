@@ -0,0 +1,256 @@
+use adapters::latest_github_release;
+use anyhow::{Context as _, bail};
+use collections::HashMap;
+use dap::{StartDebuggingRequestArguments, adapters::DebugTaskDefinition};
+use gpui::{AsyncApp, SharedString};
+use language::LanguageName;
+use std::{path::PathBuf, sync::OnceLock};
+use util::ResultExt;
+
+use crate::*;
+
+/// Debugs PHP programs with [vscode-php-debug](https://github.com/xdebug/vscode-php-debug), which
+/// listens for connections from the Xdebug extension of the PHP runtime.
+#[derive(Debug, Default)]
+pub(crate) struct PhpDebugAdapter {
+    checked: OnceLock<()>,
+}
+
+impl PhpDebugAdapter {
+    const ADAPTER_NAME: &'static str = "Xdebug";
+    const ADAPTER_PATH: &'static str = "extension/out/phpDebug.js";
+
+    async fn fetch_latest_adapter_version(
+        &self,
+        delegate: &Arc<dyn DapDelegate>,
+    ) -> Result<AdapterVersion> {
+        let release = latest_github_release(
+            "xdebug/vscode-php-debug",
+            true,
+            false,
+            delegate.http_client(),
+        )
+        .await?;
+
+        let asset_name = format!(
+            "php-debug-{}.vsix",
+            release.tag_name.trim_start_matches('v')
+        );
+
+        Ok(AdapterVersion {
+            tag_name: release.tag_name,
+            url: release
+                .assets
+                .iter()
+                .find(|asset| asset.name == asset_name)
+                .with_context(|| format!("no asset found matching {asset_name:?}"))?
+                .browser_download_url
+                .clone(),
+        })
+    }
+}
+
+#[async_trait(?Send)]
+impl DebugAdapter for PhpDebugAdapter {
+    fn name(&self) -> DebugAdapterName {
+        DebugAdapterName(Self::ADAPTER_NAME.into())
+    }
+
+    fn adapter_language_name(&self) -> Option<LanguageName> {
+        Some(SharedString::new_static("PHP").into())
+    }
+
+    async fn config_from_zed_format(&self, zed_scenario: ZedDebugConfig) -> Result<DebugScenario> {
+        let mut config = match &zed_scenario.request {
+            dap::DebugRequest::Attach(_) => {
+                bail!(
+                    "Xdebug can't attach to a process by its id. Use a `launch` configuration without a `program` to listen for incoming Xdebug connections instead"
+                );
+            }
+            dap::DebugRequest::Launch(launch) => {
+                json!({
+                    "request": "launch",
+                    "program": launch.program,
+                    "args": launch.args,
+                    "env": launch.env_json(),
+                })
+            }
+        };
+
+        let map = config.as_object_mut().unwrap();
+        if let dap::DebugRequest::Launch(launch) = &zed_scenario.request
+            && let Some(cwd) = launch.cwd.as_ref()
+        {
+            map.insert("cwd".into(), cwd.to_string_lossy().into_owned().into());
+        }
+        if let Some(stop_on_entry) = zed_scenario.stop_on_entry {
+            map.insert("stopOnEntry".into(), stop_on_entry.into());
+        }
+
+        Ok(DebugScenario {
+            adapter: zed_scenario.adapter,
+            label: zed_scenario.label,
+            build: None,
+            config,
+            tcp_connection: None,
+        })
+    }
+
+    fn dap_schema(&self) -> serde_json::Value {
+        json!({
+            "properties": {
+                "request": {
+                    "type": "string",
+                    "enum": ["launch"],
+                    "description": "Launch a PHP script, or listen for Xdebug connections when no `program` is given"
+                },
+                "program": {
+                    "type": "string",
+                    "description": "Path to the PHP script to debug. When omitted, the adapter waits for Xdebug to connect, e.g. from a web server."
+                },
+                "args": {
+                    "type": "array",
+                    "items": {
+                        "type": "string"
+                    },
+                    "description": "Command line arguments passed to the script.",
+                    "default": []
+                },
+                "cwd": {
+                    "type": "string",
+                    "description": "Working directory of the script.",
+                    "default": "${ZED_WORKTREE_ROOT}"
+                },
+                "env": {
+                    "type": "object",
+                    "description": "Environment variables for the script.",
+                    "default": {}
+                },
+                "runtimeExecutable": {
+                    "type": "string",
+                    "description": "Path to the PHP binary used to launch the script.",
+                    "default": "php"
+                },
+                "runtimeArgs": {
+                    "type": "array",
+                    "items": {
+                        "type": "string"
+                    },
+                    "description": "Extra arguments passed to the PHP binary.",
+                    "default": []
+                },
+                "port": {
+                    "type": "number",
+                    "description": "The port on which to listen for Xdebug connections. Use 0 to pick a random port.",
+                    "default": 9003
+                },
+                "hostname": {
+                    "type": "string",
+                    "description": "The address to bind to when listening for Xdebug connections.",
+                    "default": "::"
+                },
+                "pathMappings": {
+                    "type": "object",
+                    "description": "Maps server paths to local paths, for when the code runs on a remote server or in a container."
+                },
+                "stopOnEntry": {
+                    "type": "boolean",
+                    "description": "Pause on the first line of the script.",
+                    "default": false
+                },
+                "xdebugSettings": {
+                    "type": "object",
+                    "description": "Xdebug settings to apply, e.g. `max_data` or `max_depth`."
+                },
+                "log": {
+                    "type": "boolean",
+                    "description": "Log the communication between the adapter and Xdebug.",
+                    "default": false
+                }
+            },
+            "required": ["request"]
+        })
+    }
+
+    async fn get_binary(
+        &self,
+        delegate: &Arc<dyn DapDelegate>,
+        task_definition: &DebugTaskDefinition,
+        user_installed_path: Option<PathBuf>,
+        user_args: Option<Vec<String>>,
+        user_env: Option<HashMap<String, String>>,
+        _: &mut AsyncApp,
+    ) -> Result<DebugAdapterBinary> {
+        if user_installed_path.is_none() && self.checked.set(()).is_ok() {
+            delegate.output_to_console(format!("Checking latest version of {}...", self.name()));
+            if let Some(version) = self.fetch_latest_adapter_version(delegate).await.log_err() {
+                adapters::download_adapter_from_github(
+                    self.name(),
+                    version,
+                    adapters::DownloadedFileType::Vsix,
+                    delegate.as_ref(),
+                )
+                .await?;
+            } else {
+                delegate.output_to_console(format!("{} debug adapter is up to date", self.name()));
+            }
+        }
+
+        let adapter_path = if let Some(user_installed_path) = user_installed_path {
+            user_installed_path
+        } else {
+            let adapter_path = paths::debug_adapters_dir().join(self.name().as_ref());
+            let file_name_prefix = format!("{}_", self.name());
+
+            util::fs::find_file_name_in_dir(adapter_path.as_path(), |file_name| {
+                file_name.starts_with(&file_name_prefix)
+            })
+            .await
+            .context("Couldn't find Xdebug dap directory")?
+            .join(Self::ADAPTER_PATH)
+        };
+
+        let tcp_connection = task_definition.tcp_connection.clone().unwrap_or_default();
+        let (host, port, timeout) = crate::configure_tcp_connection(tcp_connection).await?;
+
+        let mut configuration = task_definition.config.clone();
+        if let Some(configuration) = configuration.as_object_mut() {
+            configuration
+                .entry("cwd")
+                .or_insert(delegate.worktree_root_path().to_string_lossy().into());
+        }
+
+        let arguments = if let Some(mut args) = user_args {
+            args.insert(0, adapter_path.to_string_lossy().into_owned());
+            args
+        } else {
+            vec![
+                adapter_path.to_string_lossy().into_owned(),
+                format!("--server={port}"),
+            ]
+        };
+
+        Ok(DebugAdapterBinary {
+            command: Some(
+                delegate
+                    .node_runtime()
+                    .binary_path()
+                    .await?
+                    .to_string_lossy()
+                    .into_owned(),
+            ),
+            arguments,
+            cwd: Some(delegate.worktree_root_path().to_path_buf()),
+            envs: user_env.unwrap_or_default(),
+            connection: Some(adapters::TcpArguments {
+                host,
+                port,
+                timeout,
+            }),
+            request_args: StartDebuggingRequestArguments {
+                configuration,
+                request: self.request_kind(&task_definition.config).await?,
+            },
+        })
+    }
+}
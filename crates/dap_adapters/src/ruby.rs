@@ -0,0 +1,326 @@
+use anyhow::{Context as _, bail};
+use collections::HashMap;
+use dap::{
+    StartDebuggingRequestArguments, StartDebuggingRequestArgumentsRequest,
+    adapters::{DebugTaskDefinition, TcpArguments},
+};
+use gpui::{AsyncApp, SharedString};
+use language::LanguageName;
+use serde_json::Value;
+use std::{ffi::OsStr, path::PathBuf};
+use task::TcpArgumentsTemplate;
+
+use crate::*;
+
+/// Debugs Ruby programs with `rdbg` from the [debug](https://github.com/ruby/debug) gem.
+///
+/// `rdbg` runs the debuggee in its own process and opens a DAP server in it, so the adapter binary
+/// is the program being debugged.
+#[derive(Default, Debug)]
+pub(crate) struct RubyDebugAdapter;
+
+impl RubyDebugAdapter {
+    const ADAPTER_NAME: &'static str = "rdbg";
+
+    /// Builds the `rdbg` arguments that run the configured script or command.
+    fn rdbg_arguments(
+        configuration: &serde_json::Map<String, Value>,
+        host: &str,
+        port: u16,
+    ) -> Result<Vec<String>> {
+        let mut arguments = vec![
+            "--open".to_owned(),
+            "--port".to_owned(),
+            port.to_string(),
+            "--host".to_owned(),
+            host.to_owned(),
+        ];
+        if !configuration
+            .get("stopOnEntry")
+            .and_then(Value::as_bool)
+            .unwrap_or(false)
+        {
+            arguments.push("--nonstop".to_owned());
+        }
+
+        let command = configuration.get("command").and_then(Value::as_str);
+        let script = configuration.get("script").and_then(Value::as_str);
+        if command.is_none() && script.is_none() {
+            bail!("rdbg launch configurations require a `script` or a `command`");
+        }
+        if command.is_some() {
+            arguments.push("--command".to_owned());
+        }
+        arguments.push("--".to_owned());
+        arguments.extend(command.map(str::to_owned));
+        arguments.extend(script.map(str::to_owned));
+        match configuration.get("args") {
+            Some(Value::Array(args)) => {
+                arguments.extend(args.iter().filter_map(Value::as_str).map(str::to_owned))
+            }
+            Some(Value::String(args)) => arguments.extend(
+                args.split_whitespace()
+                    .map(str::to_owned)
+                    .collect::<Vec<_>>(),
+            ),
+            _ => {}
+        }
+        Ok(arguments)
+    }
+}
+
+#[async_trait(?Send)]
+impl DebugAdapter for RubyDebugAdapter {
+    fn name(&self) -> DebugAdapterName {
+        DebugAdapterName(Self::ADAPTER_NAME.into())
+    }
+
+    fn adapter_language_name(&self) -> Option<LanguageName> {
+        Some(SharedString::new_static("Ruby").into())
+    }
+
+    async fn config_from_zed_format(&self, zed_scenario: ZedDebugConfig) -> Result<DebugScenario> {
+        let mut config = match &zed_scenario.request {
+            dap::DebugRequest::Attach(_) => {
+                bail!(
+                    "rdbg can't attach to a process by its id. Run the program with `rdbg --open` and connect to it with an `attach` configuration instead"
+                );
+            }
+            dap::DebugRequest::Launch(launch) => {
+                let mut config = json!({
+                    "request": "launch",
+                    "args": launch.args,
+                    "env": launch.env_json(),
+                });
+                let key = if launch.program.ends_with(".rb") {
+                    "script"
+                } else {
+                    "command"
+                };
+                config[key] = launch.program.clone().into();
+                config
+            }
+        };
+
+        let map = config.as_object_mut().unwrap();
+        if let dap::DebugRequest::Launch(launch) = &zed_scenario.request
+            && let Some(cwd) = launch.cwd.as_ref()
+        {
+            map.insert("cwd".into(), cwd.to_string_lossy().into_owned().into());
+        }
+        if let Some(stop_on_entry) = zed_scenario.stop_on_entry {
+            map.insert("stopOnEntry".into(), stop_on_entry.into());
+        }
+
+        Ok(DebugScenario {
+            adapter: zed_scenario.adapter,
+            label: zed_scenario.label,
+            build: None,
+            config,
+            tcp_connection: None,
+        })
+    }
+
+    fn dap_schema(&self) -> serde_json::Value {
+        json!({
+            "oneOf": [
+                {
+                    "allOf": [
+                        {
+                            "type": "object",
+                            "required": ["request"],
+                            "properties": {
+                                "request": {
+                                    "type": "string",
+                                    "enum": ["launch"],
+                                    "description": "Request to launch a new process"
+                                }
+                            }
+                        },
+                        {
+                            "type": "object",
+                            "properties": {
+                                "script": {
+                                    "type": "string",
+                                    "description": "The Ruby script to debug."
+                                },
+                                "command": {
+                                    "type": "string",
+                                    "description": "An executable to debug instead of a script, e.g. `rspec` or `rails`. Use `bundle` with `exec` as the first argument to run it through Bundler."
+                                },
+                                "args": {
+                                    "type": ["array", "string"],
+                                    "items": {
+                                        "type": "string"
+                                    },
+                                    "description": "Command line arguments passed to the script or command.",
+                                    "default": []
+                                },
+                                "cwd": {
+                                    "type": "string",
+                                    "description": "Working directory of the program.",
+                                    "default": "${ZED_WORKTREE_ROOT}"
+                                },
+                                "env": {
+                                    "type": "object",
+                                    "description": "Environment variables for the program.",
+                                    "default": {}
+                                },
+                                "stopOnEntry": {
+                                    "type": "boolean",
+                                    "description": "Automatically pause the program after launching it.",
+                                    "default": false
+                                }
+                            }
+                        }
+                    ]
+                },
+                {
+                    "allOf": [
+                        {
+                            "type": "object",
+                            "required": ["request"],
+                            "properties": {
+                                "request": {
+                                    "type": "string",
+                                    "enum": ["attach"],
+                                    "description": "Request to attach to a program started with `rdbg --open`. Set `tcp_connection` to the host and port it listens on."
+                                }
+                            }
+                        }
+                    ]
+                }
+            ]
+        })
+    }
+
+    async fn get_binary(
+        &self,
+        delegate: &Arc<dyn DapDelegate>,
+        task_definition: &DebugTaskDefinition,
+        user_installed_path: Option<PathBuf>,
+        user_args: Option<Vec<String>>,
+        user_env: Option<HashMap<String, String>>,
+        _: &mut AsyncApp,
+    ) -> Result<DebugAdapterBinary> {
+        let request = self.request_kind(&task_definition.config).await?;
+        let (host, port, timeout) =
+            crate::configure_tcp_connection(task_definition.tcp_connection.clone().unwrap_or_else(
+                || TcpArgumentsTemplate {
+                    // rdbg takes a moment to boot the program before it starts listening.
+                    timeout: Some(10_000),
+                    ..Default::default()
+                },
+            ))
+            .await?;
+
+        let mut configuration = task_definition.config.clone();
+        let obj = configuration
+            .as_object_mut()
+            .context("rdbg configuration is not a valid json object")?;
+        let cwd = obj
+            .get("cwd")
+            .and_then(Value::as_str)
+            .map(PathBuf::from)
+            .unwrap_or_else(|| delegate.worktree_root_path().to_path_buf());
+
+        let mut envs = delegate.shell_env().await;
+        envs.extend(user_env.unwrap_or_default());
+        if let Some(env) = obj.get("env").cloned()
+            && let Ok(env) = serde_json::from_value::<HashMap<String, String>>(env)
+        {
+            envs.extend(env);
+        }
+
+        // When connecting to an existing `rdbg --open` process there's nothing to spawn.
+        let command = if matches!(request, StartDebuggingRequestArgumentsRequest::Attach) {
+            None
+        } else if let Some(path) = user_installed_path {
+            Some(path.to_string_lossy().into_owned())
+        } else {
+            let path = delegate.which(OsStr::new("rdbg")).await.context(
+                "rdbg not found in path. Install the debug gem with `gem install debug` or add it to your Gemfile",
+            )?;
+            Some(path.to_string_lossy().into_owned())
+        };
+        let arguments = match (&command, user_args) {
+            (None, _) => Vec::new(),
+            (Some(_), Some(args)) => args,
+            (Some(_), None) => Self::rdbg_arguments(obj, &host.to_string(), port)?,
+        };
+
+        Ok(DebugAdapterBinary {
+            command,
+            arguments,
+            envs,
+            cwd: Some(cwd),
+            connection: Some(TcpArguments {
+                host,
+                port,
+                timeout,
+            }),
+            request_args: StartDebuggingRequestArguments {
+                request,
+                configuration,
+            },
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_rdbg_arguments() {
+        let configuration = json!({
+            "request": "launch",
+            "script": "app.rb",
+            "args": ["--verbose"],
+        });
+        assert_eq!(
+            RubyDebugAdapter::rdbg_arguments(configuration.as_object().unwrap(), "127.0.0.1", 1234)
+                .unwrap(),
+            vec![
+                "--open",
+                "--port",
+                "1234",
+                "--host",
+                "127.0.0.1",
+                "--nonstop",
+                "--",
+                "app.rb",
+                "--verbose",
+            ]
+        );
+
+        let configuration = json!({
+            "request": "launch",
+            "command": "bundle",
+            "args": ["exec", "rspec"],
+            "stopOnEntry": true,
+        });
+        assert_eq!(
+            RubyDebugAdapter::rdbg_arguments(configuration.as_object().unwrap(), "127.0.0.1", 1234)
+                .unwrap(),
+            vec![
+                "--open",
+                "--port",
+                "1234",
+                "--host",
+                "127.0.0.1",
+                "--command",
+                "--",
+                "bundle",
+                "exec",
+                "rspec",
+            ]
+        );
+
+        let configuration = json!({ "request": "launch" });
+        assert!(
+            RubyDebugAdapter::rdbg_arguments(configuration.as_object().unwrap(), "127.0.0.1", 1234)
+                .is_err()
+        );
+    }
+}
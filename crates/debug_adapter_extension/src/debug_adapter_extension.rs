@@ -39,7 +39,8 @@ impl ExtensionDebugAdapterProviderProxy for DebugAdapterRegistryProxy {
         if let Some(adapter) =
             ExtensionDapAdapter::new(extension, debug_adapter_name, schema_path).log_err()
         {
-            self.debug_adapter_registry.add_adapter(Arc::new(adapter));
+            self.debug_adapter_registry
+                .add_extension_adapter(Arc::new(adapter));
         }
     }
 
@@ -57,7 +58,7 @@ impl ExtensionDebugAdapterProviderProxy for DebugAdapterRegistryProxy {
 
     fn unregister_debug_adapter(&self, debug_adapter_name: Arc<str>) {
         self.debug_adapter_registry
-            .remove_adapter(&debug_adapter_name);
+            .remove_extension_adapter(&debug_adapter_name);
     }
 
     fn unregister_debug_locator(&self, locator_name: Arc<str>) {
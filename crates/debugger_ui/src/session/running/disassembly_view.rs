@@ -0,0 +1,642 @@
+use std::{ops::Range, path::Path, sync::Arc};
+
+use collections::HashMap;
+use dap::{DisassembledInstruction, DisassembledInstructionPresentationHint};
+use gpui::{
+    AnyElement, Entity, FocusHandle, Focusable, ListHorizontalSizingBehavior, ScrollStrategy,
+    Subscription, UniformListScrollHandle, WeakEntity, uniform_list,
+};
+use language::{BufferSnapshot, Point};
+use project::{
+    ProjectPath,
+    debugger::session::{Session, SessionEvent, ThreadStatus},
+};
+use ui::{Divider, Tooltip, VisibleOnHover, WithScrollbar, prelude::*};
+use workspace::Workspace;
+
+use crate::{
+    StepIntoInstruction, StepOverInstruction,
+    session::running::{
+        RunningState,
+        stack_frame_list::{StackFrameList, StackFrameListEvent},
+    },
+};
+
+/// How many instructions before the instruction pointer are fetched when a stack frame is opened.
+const INSTRUCTIONS_BEFORE_POINTER: i64 = 64;
+/// How many instructions are fetched and displayed at once.
+const INSTRUCTION_COUNT: u64 = 256;
+
+#[derive(Debug, Clone)]
+enum DisassemblyEntry {
+    /// A source line that the instructions that follow it were generated from.
+    Source {
+        path: Option<Arc<Path>>,
+        name: SharedString,
+        line: u64,
+    },
+    Instruction(DisassembledInstruction),
+}
+
+pub(crate) struct DisassemblyView {
+    session: Entity<Session>,
+    workspace: WeakEntity<Workspace>,
+    stack_frame_list: Entity<StackFrameList>,
+    running_state: WeakEntity<RunningState>,
+    focus_handle: FocusHandle,
+    scroll_handle: UniformListScrollHandle,
+    /// Memory reference of the instruction that the opened stack frame is executing.
+    instruction_pointer: Option<String>,
+    /// Offset of the first displayed instruction, relative to the instruction pointer.
+    instruction_offset: i64,
+    scroll_to_instruction_pointer: bool,
+    entries: Vec<DisassemblyEntry>,
+    sources: HashMap<Arc<Path>, Option<BufferSnapshot>>,
+    selected_ix: Option<usize>,
+    _subscriptions: Vec<Subscription>,
+}
+
+impl DisassemblyView {
+    pub(crate) fn new(
+        session: Entity<Session>,
+        workspace: WeakEntity<Workspace>,
+        stack_frame_list: Entity<StackFrameList>,
+        running_state: WeakEntity<RunningState>,
+        cx: &mut Context<Self>,
+    ) -> Self {
+        let _subscriptions = vec![
+            cx.subscribe(&session, |this, _, event, cx| match event {
+                SessionEvent::Stopped(_)
+                | SessionEvent::HistoricSnapshotSelected
+                | SessionEvent::Disassembly => this.refresh(cx),
+                _ => {}
+            }),
+            cx.subscribe(
+                &stack_frame_list,
+                |this, _, event: &StackFrameListEvent, cx| match event {
+                    StackFrameListEvent::SelectedStackFrameChanged(_)
+                    | StackFrameListEvent::BuiltEntries => this.refresh(cx),
+                },
+            ),
+        ];
+
+        let mut this = Self {
+            session,
+            workspace,
+            stack_frame_list,
+            running_state,
+            focus_handle: cx.focus_handle(),
+            scroll_handle: UniformListScrollHandle::new(),
+            instruction_pointer: None,
+            instruction_offset: -INSTRUCTIONS_BEFORE_POINTER,
+            scroll_to_instruction_pointer: true,
+            entries: Vec::new(),
+            sources: HashMap::default(),
+            selected_ix: None,
+            _subscriptions,
+        };
+        this.refresh(cx);
+        this
+    }
+
+    fn refresh(&mut self, cx: &mut Context<Self>) {
+        let instruction_pointer = self
+            .stack_frame_list
+            .read(cx)
+            .opened_stack_frame()
+            .and_then(|stack_frame| stack_frame.instruction_pointer_reference.clone());
+        if instruction_pointer != self.instruction_pointer {
+            self.instruction_pointer = instruction_pointer;
+            self.instruction_offset = -INSTRUCTIONS_BEFORE_POINTER;
+            self.scroll_to_instruction_pointer = true;
+            self.selected_ix = None;
+        }
+
+        let Some(instruction_pointer) = self.instruction_pointer.clone() else {
+            self.entries.clear();
+            cx.notify();
+            return;
+        };
+        let Some(instructions) = self.session.update(cx, |session, cx| {
+            session.disassemble(
+                &instruction_pointer,
+                self.instruction_offset,
+                INSTRUCTION_COUNT,
+                cx,
+            )
+        }) else {
+            return;
+        };
+
+        self.entries = disassembly_entries(&instructions);
+        for entry in &self.entries {
+            if let DisassemblyEntry::Source {
+                path: Some(path), ..
+            } = entry
+            {
+                self.load_source(path.clone(), cx);
+            }
+        }
+
+        if self.scroll_to_instruction_pointer
+            && let Some(ix) = self.instruction_pointer_ix()
+        {
+            self.scroll_to_instruction_pointer = false;
+            self.scroll_handle
+                .scroll_to_item(ix, ScrollStrategy::Center);
+        }
+        cx.notify();
+    }
+
+    fn instruction_pointer_ix(&self) -> Option<usize> {
+        let instruction_pointer = parse_address(self.instruction_pointer.as_deref()?)?;
+        self.entries.iter().position(|entry| match entry {
+            DisassemblyEntry::Instruction(instruction) => {
+                parse_address(&instruction.address) == Some(instruction_pointer)
+            }
+            DisassemblyEntry::Source { .. } => false,
+        })
+    }
+
+    fn load_source(&mut self, abs_path: Arc<Path>, cx: &mut Context<Self>) {
+        if self.sources.contains_key(&abs_path) {
+            return;
+        }
+        self.sources.insert(abs_path.clone(), None);
+
+        cx.spawn(async move |this, cx| {
+            let (worktree, relative_path) = this
+                .update(cx, |this, cx| {
+                    this.workspace.update(cx, |workspace, cx| {
+                        workspace.project().update(cx, |project, cx| {
+                            project.find_or_create_worktree(&abs_path, false, cx)
+                        })
+                    })
+                })??
+                .await?;
+            let buffer = this
+                .update(cx, |this, cx| {
+                    this.workspace.update(cx, |workspace, cx| {
+                        workspace.project().update(cx, |project, cx| {
+                            let worktree_id = worktree.read(cx).id();
+                            project.open_buffer(
+                                ProjectPath {
+                                    worktree_id,
+                                    path: relative_path,
+                                },
+                                cx,
+                            )
+                        })
+                    })
+                })??
+                .await?;
+            this.update(cx, |this, cx| {
+                this.sources
+                    .insert(abs_path, Some(buffer.read(cx).snapshot()));
+                cx.notify();
+            })
+        })
+        .detach_and_log_err(cx);
+    }
+
+    fn source_line_text(&self, path: Option<&Arc<Path>>, line: u64) -> Option<SharedString> {
+        let snapshot = self.sources.get(path?)?.as_ref()?;
+        let row = u32::try_from(line.checked_sub(1)?).ok()?;
+        if row > snapshot.max_point().row {
+            return None;
+        }
+        let text = snapshot
+            .text_for_range(Point::new(row, 0)..Point::new(row, snapshot.line_len(row)))
+            .collect::<String>();
+        Some(text.trim().to_owned().into())
+    }
+
+    fn show_earlier_instructions(&mut self, cx: &mut Context<Self>) {
+        self.instruction_offset -= INSTRUCTION_COUNT as i64 / 2;
+        self.selected_ix = None;
+        self.refresh(cx);
+    }
+
+    fn show_later_instructions(&mut self, cx: &mut Context<Self>) {
+        self.instruction_offset += INSTRUCTION_COUNT as i64 / 2;
+        self.selected_ix = None;
+        self.refresh(cx);
+    }
+
+    fn go_to_instruction_pointer(&mut self, cx: &mut Context<Self>) {
+        self.instruction_offset = -INSTRUCTIONS_BEFORE_POINTER;
+        self.scroll_to_instruction_pointer = true;
+        self.refresh(cx);
+    }
+
+    fn toggle_instruction_breakpoint(&mut self, address: String, cx: &mut Context<Self>) {
+        self.session.update(cx, |session, cx| {
+            session.toggle_instruction_breakpoint(address, cx);
+        });
+        cx.notify();
+    }
+
+    fn supports_instruction_breakpoints(&self, cx: &App) -> bool {
+        self.session
+            .read(cx)
+            .capabilities()
+            .supports_instruction_breakpoints
+            .unwrap_or_default()
+    }
+
+    fn select_ix(&mut self, ix: Option<usize>, cx: &mut Context<Self>) {
+        self.selected_ix = ix;
+        if let Some(ix) = ix {
+            self.scroll_handle
+                .scroll_to_item(ix, ScrollStrategy::Center);
+        }
+        cx.notify();
+    }
+
+    fn select_next(&mut self, _: &menu::SelectNext, _window: &mut Window, cx: &mut Context<Self>) {
+        let ix = match self.selected_ix {
+            _ if self.entries.is_empty() => None,
+            None => self.instruction_pointer_ix().or(Some(0)),
+            Some(ix) => Some((ix + 1).min(self.entries.len() - 1)),
+        };
+        self.select_ix(ix, cx);
+    }
+
+    fn select_previous(
+        &mut self,
+        _: &menu::SelectPrevious,
+        _window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let ix = match self.selected_ix {
+            _ if self.entries.is_empty() => None,
+            None => self.instruction_pointer_ix().or(Some(0)),
+            Some(ix) => Some(ix.saturating_sub(1)),
+        };
+        self.select_ix(ix, cx);
+    }
+
+    fn confirm(&mut self, _: &menu::Confirm, _window: &mut Window, cx: &mut Context<Self>) {
+        if !self.supports_instruction_breakpoints(cx) {
+            return;
+        }
+        let Some(DisassemblyEntry::Instruction(instruction)) =
+            self.selected_ix.and_then(|ix| self.entries.get(ix))
+        else {
+            return;
+        };
+        let address = instruction.address.clone();
+        self.toggle_instruction_breakpoint(address, cx);
+    }
+
+    fn page_up(&mut self, _: &menu::SelectFirst, _window: &mut Window, cx: &mut Context<Self>) {
+        self.show_earlier_instructions(cx);
+    }
+
+    fn page_down(&mut self, _: &menu::SelectLast, _window: &mut Window, cx: &mut Context<Self>) {
+        self.show_later_instructions(cx);
+    }
+
+    fn render_entry(&self, ix: usize, cx: &mut Context<Self>) -> AnyElement {
+        match &self.entries[ix] {
+            DisassemblyEntry::Source { path, name, line } => {
+                let text = self.source_line_text(path.as_ref(), *line);
+                h_flex()
+                    .id(("disassembly-source", ix))
+                    .w_full()
+                    .px_1()
+                    .gap_2()
+                    .child(
+                        Label::new(format!("{name}:{line}"))
+                            .size(LabelSize::Small)
+                            .color(Color::Accent),
+                    )
+                    .when_some(text, |this, text| {
+                        this.child(
+                            Label::new(text)
+                                .buffer_font(cx)
+                                .size(LabelSize::Small)
+                                .color(Color::Muted)
+                                .truncate(),
+                        )
+                    })
+                    .into_any_element()
+            }
+            DisassemblyEntry::Instruction(instruction) => {
+                self.render_instruction(ix, instruction, cx)
+            }
+        }
+    }
+
+    fn render_instruction(
+        &self,
+        ix: usize,
+        instruction: &DisassembledInstruction,
+        cx: &mut Context<Self>,
+    ) -> AnyElement {
+        let is_invalid =
+            instruction.presentation_hint == Some(DisassembledInstructionPresentationHint::Invalid);
+        let is_instruction_pointer = self
+            .instruction_pointer
+            .as_deref()
+            .and_then(parse_address)
+            .is_some_and(|pointer| parse_address(&instruction.address) == Some(pointer));
+        let has_breakpoint = self
+            .session
+            .read(cx)
+            .has_instruction_breakpoint(&instruction.address);
+        let supports_instruction_breakpoints = self.supports_instruction_breakpoints(cx);
+        let address = instruction.address.clone();
+
+        h_flex()
+            .id(("disassembly-instruction", ix))
+            .group("disassembly-instruction")
+            .w_full()
+            .gap_2()
+            .px_1()
+            .when(is_instruction_pointer, |this| {
+                this.bg(cx.theme().colors().editor_debugger_active_line_background)
+            })
+            .when(Some(ix) == self.selected_ix, |this| {
+                this.bg(cx.theme().colors().element_selected)
+            })
+            .hover(|style| style.bg(cx.theme().colors().element_hover))
+            .on_click(cx.listener(move |this, _, _, cx| {
+                this.selected_ix = Some(ix);
+                cx.notify();
+            }))
+            .child(
+                div()
+                    .id(("disassembly-breakpoint", ix))
+                    .w_4()
+                    .flex_none()
+                    .when(has_breakpoint, |this| {
+                        this.child(
+                            Icon::new(IconName::DebugBreakpoint)
+                                .size(IconSize::XSmall)
+                                .color(Color::Debugger),
+                        )
+                    })
+                    .when(
+                        !has_breakpoint && supports_instruction_breakpoints,
+                        |this| {
+                            this.child(
+                                div().visible_on_hover("disassembly-instruction").child(
+                                    Icon::new(IconName::DebugBreakpoint)
+                                        .size(IconSize::XSmall)
+                                        .color(Color::Hint),
+                                ),
+                            )
+                        },
+                    )
+                    .when(supports_instruction_breakpoints && !is_invalid, |this| {
+                        this.cursor_pointer()
+                            .tooltip(Tooltip::text(if has_breakpoint {
+                                "Remove Instruction Breakpoint"
+                            } else {
+                                "Set Instruction Breakpoint"
+                            }))
+                            .on_click(cx.listener(move |this, _, _, cx| {
+                                this.toggle_instruction_breakpoint(address.clone(), cx);
+                                cx.stop_propagation();
+                            }))
+                    }),
+            )
+            .child(
+                div()
+                    .w_4()
+                    .flex_none()
+                    .when(is_instruction_pointer, |this| {
+                        this.child(
+                            Icon::new(IconName::ArrowRight)
+                                .size(IconSize::XSmall)
+                                .color(Color::Warning),
+                        )
+                    }),
+            )
+            .child(
+                Label::new(instruction.address.clone())
+                    .buffer_font(cx)
+                    .size(LabelSize::Small)
+                    .color(Color::Muted),
+            )
+            .when_some(instruction.instruction_bytes.clone(), |this, bytes| {
+                this.child(
+                    div().w_40().flex_none().child(
+                        Label::new(bytes)
+                            .buffer_font(cx)
+                            .size(LabelSize::Small)
+                            .color(Color::Muted)
+                            .truncate(),
+                    ),
+                )
+            })
+            .child(
+                Label::new(instruction.instruction.clone())
+                    .buffer_font(cx)
+                    .size(LabelSize::Small)
+                    .when(is_invalid, |this| this.color(Color::Muted)),
+            )
+            .when_some(instruction.symbol.clone(), |this, symbol| {
+                this.child(
+                    Label::new(format!("<{symbol}>"))
+                        .buffer_font(cx)
+                        .size(LabelSize::Small)
+                        .color(Color::Muted),
+                )
+            })
+            .into_any_element()
+    }
+
+    fn render_toolbar(&self, cx: &mut Context<Self>) -> impl IntoElement {
+        let is_stopped = self
+            .running_state
+            .read_with(cx, |state, cx| state.thread_status(cx))
+            .ok()
+            .flatten()
+            == Some(ThreadStatus::Stopped);
+        let supports_stepping_granularity = self
+            .session
+            .read(cx)
+            .capabilities()
+            .supports_stepping_granularity
+            .unwrap_or_default();
+        let can_step = is_stopped && supports_stepping_granularity;
+        let has_instructions = self.instruction_pointer.is_some();
+        let focus_handle = self.focus_handle.clone();
+
+        h_flex()
+            .w_full()
+            .gap_1()
+            .p_1()
+            .child(
+                IconButton::new("disassembly-step-over", IconName::DebugStepOver)
+                    .icon_size(IconSize::Small)
+                    .disabled(!can_step)
+                    .on_click(cx.listener(|this, _, _, cx| {
+                        this.running_state
+                            .update(cx, |state, cx| state.step_over_instruction(cx))
+                            .ok();
+                    }))
+                    .tooltip({
+                        let focus_handle = focus_handle.clone();
+                        move |_window, cx| {
+                            Tooltip::for_action_in(
+                                "Step Over Instruction",
+                                &StepOverInstruction,
+                                &focus_handle,
+                                cx,
+                            )
+                        }
+                    }),
+            )
+            .child(
+                IconButton::new("disassembly-step-into", IconName::DebugStepInto)
+                    .icon_size(IconSize::Small)
+                    .disabled(!can_step)
+                    .on_click(cx.listener(|this, _, _, cx| {
+                        this.running_state
+                            .update(cx, |state, cx| state.step_in_instruction(cx))
+                            .ok();
+                    }))
+                    .tooltip({
+                        let focus_handle = focus_handle.clone();
+                        move |_window, cx| {
+                            Tooltip::for_action_in(
+                                "Step Into Instruction",
+                                &StepIntoInstruction,
+                                &focus_handle,
+                                cx,
+                            )
+                        }
+                    }),
+            )
+            .child(Divider::vertical())
+            .child(
+                IconButton::new("disassembly-earlier", IconName::ArrowUp)
+                    .icon_size(IconSize::Small)
+                    .disabled(!has_instructions)
+                    .tooltip(Tooltip::text("Show Earlier Instructions"))
+                    .on_click(cx.listener(|this, _, _, cx| this.show_earlier_instructions(cx))),
+            )
+            .child(
+                IconButton::new("disassembly-later", IconName::ArrowDown)
+                    .icon_size(IconSize::Small)
+                    .disabled(!has_instructions)
+                    .tooltip(Tooltip::text("Show Later Instructions"))
+                    .on_click(cx.listener(|this, _, _, cx| this.show_later_instructions(cx))),
+            )
+            .child(
+                IconButton::new("disassembly-instruction-pointer", IconName::Crosshair)
+                    .icon_size(IconSize::Small)
+                    .disabled(!has_instructions)
+                    .tooltip(Tooltip::text("Go to Instruction Pointer"))
+                    .on_click(cx.listener(|this, _, _, cx| this.go_to_instruction_pointer(cx))),
+            )
+    }
+
+    fn render_list(&mut self, cx: &mut Context<Self>) -> impl IntoElement {
+        uniform_list(
+            "disassembly-view",
+            self.entries.len(),
+            cx.processor(|this, range: Range<usize>, _window, cx| {
+                range.map(|ix| this.render_entry(ix, cx)).collect()
+            }),
+        )
+        .track_scroll(&self.scroll_handle)
+        .with_horizontal_sizing_behavior(ListHorizontalSizingBehavior::Unconstrained)
+        .size_full()
+    }
+
+    #[cfg(test)]
+    pub(crate) fn rendered_entries(&self) -> Vec<String> {
+        self.entries
+            .iter()
+            .map(|entry| match entry {
+                DisassemblyEntry::Source { name, line, .. } => format!("{name}:{line}"),
+                DisassemblyEntry::Instruction(instruction) => {
+                    format!("{} {}", instruction.address, instruction.instruction)
+                }
+            })
+            .collect()
+    }
+}
+
+/// Interleaves the instructions with the source lines they were generated from.
+fn disassembly_entries(instructions: &[DisassembledInstruction]) -> Vec<DisassemblyEntry> {
+    let mut entries = Vec::with_capacity(instructions.len());
+    let mut current_source = None;
+    let mut current_line = None;
+    for instruction in instructions {
+        // Adapters may omit the location when it's the same as the previous instruction's.
+        if let Some(source) = &instruction.location {
+            current_source = Some(source);
+        }
+        if instruction.line.is_some() && instruction.line != current_line {
+            current_line = instruction.line;
+            if let Some((source, line)) = current_source.zip(current_line) {
+                let path = StackFrameList::abs_path_from_source(source);
+                let name = source
+                    .name
+                    .clone()
+                    .or_else(|| {
+                        path.as_ref()
+                            .and_then(|path| path.file_name())
+                            .map(|name| name.to_string_lossy().into_owned())
+                    })
+                    .unwrap_or_default();
+                entries.push(DisassemblyEntry::Source {
+                    path,
+                    name: name.into(),
+                    line,
+                });
+            }
+        }
+        entries.push(DisassemblyEntry::Instruction(instruction.clone()));
+    }
+    entries
+}
+
+fn parse_address(address: &str) -> Option<u64> {
+    parse_int::parse::<u64>(address).ok()
+}
+
+impl Focusable for DisassemblyView {
+    fn focus_handle(&self, _: &App) -> FocusHandle {
+        self.focus_handle.clone()
+    }
+}
+
+impl Render for DisassemblyView {
+    fn render(&mut self, window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        let content = if self.instruction_pointer.is_none() {
+            h_flex()
+                .size_full()
+                .justify_center()
+                .child(
+                    Label::new("The selected stack frame has no instruction pointer")
+                        .color(Color::Muted),
+                )
+                .into_any_element()
+        } else {
+            div()
+                .size_full()
+                .child(self.render_list(cx))
+                .vertical_scrollbar_for(&self.scroll_handle, window, cx)
+                .into_any_element()
+        };
+
+        v_flex()
+            .id("disassembly-view")
+            .track_focus(&self.focus_handle)
+            .on_action(cx.listener(Self::select_next))
+            .on_action(cx.listener(Self::select_previous))
+            .on_action(cx.listener(Self::page_up))
+            .on_action(cx.listener(Self::page_down))
+            .on_action(cx.listener(Self::confirm))
+            .size_full()
+            .child(self.render_toolbar(cx))
+            .child(Divider::horizontal())
+            .child(content)
+    }
+}
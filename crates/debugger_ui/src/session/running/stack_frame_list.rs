@@ -15,7 +15,7 @@ use util::{
     paths::{PathStyle, is_absolute},
 };
 
-use crate::ToggleUserFrames;
+use crate::{ToggleUserFrames, persistence::DebuggerPaneItem};
 use language::PointUtf16;
 use project::debugger::breakpoint_store::ActiveStackFrame;
 use project::debugger::session::{Session, SessionEvent, StackFrame, ThreadStatus};
@@ -219,6 +219,18 @@ impl StackFrameList {
         self.opened_stack_frame_id
     }
 
+    pub(crate) fn opened_stack_frame(&self) -> Option<&dap::StackFrame> {
+        let stack_frame_id = self.opened_stack_frame_id?;
+        self.entries
+            .iter()
+            .flat_map(|entry| match entry {
+                StackFrameEntry::Label(stack_frame) => std::slice::from_ref(stack_frame),
+                StackFrameEntry::Normal(stack_frame) => std::slice::from_ref(stack_frame),
+                StackFrameEntry::Collapsed(stack_frames) => stack_frames.as_slice(),
+            })
+            .find(|stack_frame| stack_frame.id == stack_frame_id)
+    }
+
     pub(super) fn schedule_refresh(
         &mut self,
         select_first: bool,
@@ -394,13 +406,31 @@ impl StackFrameList {
     ) -> Task<Result<()>> {
         let stack_frame_id = stack_frame.id;
         self.opened_stack_frame_id = Some(stack_frame_id);
+        cx.emit(StackFrameListEvent::SelectedStackFrameChanged(
+            stack_frame_id,
+        ));
         let Some(abs_path) = Self::abs_path_from_stack_frame(&stack_frame) else {
+            // Frames without source, e.g. in system libraries, can still be stepped through
+            // instruction by instruction.
+            let supports_disassemble = self
+                .session
+                .read(cx)
+                .capabilities()
+                .supports_disassemble_request
+                .unwrap_or_default();
+            if supports_disassemble && stack_frame.instruction_pointer_reference.is_some() {
+                let state = self.state.clone();
+                window.defer(cx, move |window, cx| {
+                    state
+                        .update(cx, |state, cx| {
+                            state.activate_item(DebuggerPaneItem::Disassembly, window, cx);
+                        })
+                        .ok();
+                });
+            }
             return Task::ready(Err(anyhow!("Project path not found")));
         };
         let row = stack_frame.line.saturating_sub(1) as u32;
-        cx.emit(StackFrameListEvent::SelectedStackFrameChanged(
-            stack_frame_id,
-        ));
         cx.spawn_in(window, async move |this, cx| {
             let (worktree, relative_path) = this
                 .update(cx, |this, cx| {
@@ -515,16 +545,22 @@ impl StackFrameList {
     }
 
     pub(crate) fn abs_path_from_stack_frame(stack_frame: &dap::StackFrame) -> Option<Arc<Path>> {
-        stack_frame.source.as_ref().and_then(|s| {
-            s.path
-                .as_deref()
-                .filter(|path| {
-                    // Since we do not know if we are debugging on the host or (a remote/WSL) target,
-                    // we need to check if either the path is absolute as Posix or Windows.
-                    is_absolute(path, PathStyle::Posix) || is_absolute(path, PathStyle::Windows)
-                })
-                .map(|path| Arc::<Path>::from(Path::new(path)))
-        })
+        stack_frame
+            .source
+            .as_ref()
+            .and_then(Self::abs_path_from_source)
+    }
+
+    pub(crate) fn abs_path_from_source(source: &dap::Source) -> Option<Arc<Path>> {
+        source
+            .path
+            .as_deref()
+            .filter(|path| {
+                // Since we do not know if we are debugging on the host or (a remote/WSL) target,
+                // we need to check if either the path is absolute as Posix or Windows.
+                is_absolute(path, PathStyle::Posix) || is_absolute(path, PathStyle::Windows)
+            })
+            .map(|path| Arc::<Path>::from(Path::new(path)))
     }
 
     pub fn restart_stack_frame(&mut self, stack_frame_id: u64, cx: &mut Context<Self>) {
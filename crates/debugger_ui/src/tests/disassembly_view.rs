@@ -0,0 +1,171 @@
+use crate::{
+    debugger_panel::DebugPanel,
+    tests::{active_debug_session_panel, init_test, init_test_workspace, start_debug_session},
+};
+use dap::{
+    StackFrame, StoppedEvent,
+    requests::{Disassemble, Initialize, Scopes, SetInstructionBreakpoints, StackTrace, Threads},
+};
+use gpui::{BackgroundExecutor, TestAppContext, VisualTestContext};
+use project::{FakeFs, Project};
+use serde_json::json;
+use std::sync::{Arc, Mutex};
+use util::path;
+
+fn instruction(
+    address: &str,
+    instruction: &str,
+    line: Option<u64>,
+) -> dap::DisassembledInstruction {
+    serde_json::from_value(json!({
+        "address": address,
+        "instruction": instruction,
+        "location": line.map(|_| json!({ "name": "main.c", "path": path!("/project/main.c") })),
+        "line": line,
+    }))
+    .unwrap()
+}
+
+#[gpui::test]
+async fn test_disassembly_view(executor: BackgroundExecutor, cx: &mut TestAppContext) {
+    init_test(cx);
+
+    let fs = FakeFs::new(executor.clone());
+    fs.insert_tree(
+        path!("/project"),
+        json!({
+            "main.c": "int main() {\n    return 0;\n}\n",
+        }),
+    )
+    .await;
+
+    let project = Project::test(fs, [path!("/project").as_ref()], cx).await;
+    let workspace = init_test_workspace(&project, cx).await;
+    workspace
+        .update(cx, |workspace, window, cx| {
+            workspace.focus_panel::<DebugPanel>(window, cx);
+        })
+        .unwrap();
+    let cx = &mut VisualTestContext::from_window(*workspace, cx);
+
+    let session = start_debug_session(&workspace, cx, |client| {
+        client.on_request::<Initialize, _>(move |_, _| {
+            Ok(dap::Capabilities {
+                supports_disassemble_request: Some(true),
+                supports_instruction_breakpoints: Some(true),
+                supports_stepping_granularity: Some(true),
+                ..Default::default()
+            })
+        });
+    })
+    .unwrap();
+
+    let client = session.update(cx, |session, _| session.adapter_client().unwrap());
+    client.on_request::<Scopes, _>(move |_, _| Ok(dap::ScopesResponse { scopes: vec![] }));
+    client.on_request::<Threads, _>(move |_, _| {
+        Ok(dap::ThreadsResponse {
+            threads: vec![dap::Thread {
+                id: 1,
+                name: "Thread 1".into(),
+            }],
+        })
+    });
+    client.on_request::<StackTrace, _>(move |_, _| {
+        Ok(dap::StackTraceResponse {
+            stack_frames: vec![StackFrame {
+                id: 1,
+                name: "main".into(),
+                source: None,
+                line: 0,
+                column: 0,
+                end_line: None,
+                end_column: None,
+                can_restart: None,
+                instruction_pointer_reference: Some("0x1004".into()),
+                module_id: None,
+                presentation_hint: None,
+            }],
+            total_frames: None,
+        })
+    });
+    client.on_request::<Disassemble, _>(move |_, args| {
+        assert_eq!(args.memory_reference, "0x1004");
+        assert_eq!(args.resolve_symbols, Some(true));
+        Ok(dap::DisassembleResponse {
+            instructions: vec![
+                instruction("0x1000", "push rbp", Some(1)),
+                instruction("0x1004", "mov eax, 0", Some(2)),
+                instruction("0x1008", "pop rbp", None),
+                instruction("0x100c", "ret", Some(3)),
+            ],
+        })
+    });
+
+    let instruction_breakpoints = Arc::new(Mutex::new(Vec::new()));
+    client.on_request::<SetInstructionBreakpoints, _>({
+        let instruction_breakpoints = instruction_breakpoints.clone();
+        move |_, args| {
+            *instruction_breakpoints.lock().unwrap() = args
+                .breakpoints
+                .iter()
+                .map(|breakpoint| breakpoint.instruction_reference.clone())
+                .collect::<Vec<_>>();
+            Ok(dap::SetInstructionBreakpointsResponse {
+                breakpoints: Vec::new(),
+            })
+        }
+    });
+
+    client
+        .fake_event(dap::messages::Events::Stopped(StoppedEvent {
+            reason: dap::StoppedEventReason::Pause,
+            description: None,
+            thread_id: Some(1),
+            preserve_focus_hint: None,
+            text: None,
+            all_threads_stopped: None,
+            hit_breakpoint_ids: None,
+        }))
+        .await;
+
+    cx.run_until_parked();
+
+    let running_state = active_debug_session_panel(workspace, cx)
+        .update(cx, |item, _| item.running_state().clone());
+
+    running_state.update(cx, |state, cx| {
+        let entries = state.disassembly_view().read(cx).rendered_entries();
+        assert_eq!(
+            entries,
+            vec![
+                "main.c:1",
+                "0x1000 push rbp",
+                "main.c:2",
+                "0x1004 mov eax, 0",
+                "0x1008 pop rbp",
+                "main.c:3",
+                "0x100c ret",
+            ]
+        );
+    });
+
+    session.update(cx, |session, cx| {
+        session.toggle_instruction_breakpoint("0x1008".into(), cx);
+    });
+    cx.run_until_parked();
+
+    assert_eq!(*instruction_breakpoints.lock().unwrap(), vec!["0x1008"]);
+    session.update(cx, |session, _| {
+        assert!(session.has_instruction_breakpoint("0x1008"));
+    });
+
+    session.update(cx, |session, cx| {
+        session.toggle_instruction_breakpoint("0x1008".into(), cx);
+    });
+    cx.run_until_parked();
+
+    assert!(instruction_breakpoints.lock().unwrap().is_empty());
+    session.update(cx, |session, _| {
+        assert!(!session.has_instruction_breakpoint("0x1008"));
+    });
+}
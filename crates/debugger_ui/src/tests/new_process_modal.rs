@@ -366,6 +366,10 @@ async fn test_dap_adapter_config_conversion_and_validation(cx: &mut TestAppConte
         "JavaScript",
         "Delve",
         "GDB",
+        "Java",
+        "netcoredbg",
+        "rdbg",
+        "Xdebug",
         "fake-adapter",
     ];
 
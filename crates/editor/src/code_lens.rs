@@ -0,0 +1,914 @@
+use std::{ops::Range, sync::Arc};
+
+use anyhow::Context as _;
+use collections::{HashMap, HashSet};
+use dap::TelemetrySpawnLocation;
+use futures::future::{join_all, try_join_all};
+use gpui::{App, Entity, SharedString, Task, WeakEntity};
+use itertools::Itertools;
+use language::{Buffer, language_settings::language_settings};
+use lsp::LanguageServerId;
+use multi_buffer::Anchor;
+use project::{CodeAction, LspAction, TaskSourceKind, lsp_store::lsp_ext_command::Runnable};
+use settings::Settings as _;
+use task::TaskTemplate;
+use text::{BufferId, OffsetRangeExt as _, Point, ToOffset as _, ToPoint as _};
+use ui::{Context, Window, prelude::*};
+use workspace::item::PreviewTabsSettings;
+
+use crate::{
+    Editor, LSP_REQUEST_DEBOUNCE_TIMEOUT, MultibufferSelectionMode, SelectionEffects,
+    actions::FindAllReferences,
+    display_map::{
+        BlockContext, BlockPlacement, BlockProperties, BlockStyle, CustomBlockId, RenderBlock,
+    },
+    lsp_ext::lsp_task_context,
+};
+
+/// Commands that ask the client to list locations. Their arguments are the document, the
+/// position and the locations to show.
+const SHOW_REFERENCES_COMMANDS: &[&str] = &[
+    "editor.action.showReferences",
+    "rust-analyzer.showReferences",
+];
+
+/// rust-analyzer's commands to run or debug the runnable in their first argument. Clients
+/// implement them, so the server doesn't list them among the commands it executes.
+const RUST_ANALYZER_RUN_SINGLE: &str = "rust-analyzer.runSingle";
+const RUST_ANALYZER_DEBUG_SINGLE: &str = "rust-analyzer.debugSingle";
+
+/// The code lenses of a line, shown in a block above it.
+pub(super) struct CodeLensBlock {
+    block_id: CustomBlockId,
+    buffer: Entity<Buffer>,
+    /// The start of the line's text, which the lenses are aligned with.
+    anchor: Anchor,
+    lenses: Vec<CodeAction>,
+    resolve_task: Option<Task<()>>,
+}
+
+enum CodeLensLabel {
+    Resolving,
+    Runnable { lens_ix: usize, title: SharedString },
+    Text(SharedString),
+}
+
+impl Editor {
+    pub(super) fn refresh_code_lenses(
+        &mut self,
+        for_buffer: Option<BufferId>,
+        _window: &Window,
+        cx: &mut Context<Self>,
+    ) {
+        if !self.mode().is_full() {
+            return;
+        }
+        let Some(project) = self.project.clone() else {
+            return;
+        };
+
+        let buffers_to_query = self
+            .visible_excerpts(true, cx)
+            .into_values()
+            .map(|(buffer, ..)| buffer)
+            .chain(for_buffer.and_then(|id| self.buffer.read(cx).buffer(id)))
+            .filter(|buffer| {
+                let id = buffer.read(cx).remote_id();
+                (for_buffer.is_none_or(|target| target == id))
+                    && self.registered_buffers.contains_key(&id)
+                    && code_lens_enabled(buffer.read(cx), cx)
+            })
+            .unique_by(|buffer| buffer.read(cx).remote_id())
+            .collect::<Vec<_>>();
+
+        self.refresh_code_lenses_task = cx.spawn(async move |editor, cx| {
+            cx.background_executor()
+                .timer(LSP_REQUEST_DEBOUNCE_TIMEOUT)
+                .await;
+
+            let Some(tasks) = editor
+                .update(cx, |_, cx| {
+                    project.read(cx).lsp_store().update(cx, |lsp_store, cx| {
+                        buffers_to_query
+                            .into_iter()
+                            .map(|buffer| {
+                                let task = lsp_store.code_lens_actions(&buffer, cx);
+                                async move { (buffer, task.await) }
+                            })
+                            .collect::<Vec<_>>()
+                    })
+                })
+                .ok()
+            else {
+                return;
+            };
+
+            let results = join_all(tasks).await;
+            if results.is_empty() {
+                return;
+            }
+
+            editor
+                .update(cx, |editor, cx| {
+                    for (buffer, lenses) in results {
+                        match lenses {
+                            Ok(lenses) => {
+                                editor.set_code_lenses(buffer, lenses.unwrap_or_default(), cx)
+                            }
+                            Err(e) => log::error!("Failed to fetch code lenses: {e:#}"),
+                        }
+                    }
+                    editor.resolve_visible_code_lenses(cx);
+                })
+                .ok();
+        });
+    }
+
+    /// Fetches the lenses again after a server asked for it, resolving them anew as the
+    /// commands of the old ones may be out of date.
+    pub(super) fn invalidate_code_lenses(&mut self, window: &Window, cx: &mut Context<Self>) {
+        for lens in self
+            .code_lens_blocks
+            .values_mut()
+            .flatten()
+            .flat_map(|block| &mut block.lenses)
+        {
+            lens.resolved = false;
+        }
+        self.refresh_code_lenses(None, window, cx);
+    }
+
+    fn set_code_lenses(
+        &mut self,
+        buffer: Entity<Buffer>,
+        mut lenses: Vec<CodeAction>,
+        cx: &mut Context<Self>,
+    ) {
+        let buffer_snapshot = buffer.read(cx).snapshot();
+        let buffer_id = buffer_snapshot.remote_id();
+        let old_blocks = self.remove_code_lenses(buffer_id, cx);
+
+        // Lenses that were resolved already keep their commands, so that the blocks don't go
+        // back to loading after each edit.
+        let old_lenses = old_blocks
+            .iter()
+            .flat_map(|block| &block.lenses)
+            .filter(|lens| lens.resolved)
+            .collect::<Vec<_>>();
+        for lens in &mut lenses {
+            if lens.resolved {
+                continue;
+            }
+            let LspAction::CodeLens(lsp_lens) = &lens.lsp_action else {
+                continue;
+            };
+            let resolved = old_lenses.iter().find(|old_lens| {
+                old_lens.server_id == lens.server_id
+                    && matches!(
+                        &old_lens.lsp_action,
+                        LspAction::CodeLens(old_lsp_lens)
+                            if old_lsp_lens.range == lsp_lens.range
+                                && old_lsp_lens.data == lsp_lens.data
+                    )
+            });
+            if let Some(resolved) = resolved {
+                lens.lsp_action = resolved.lsp_action.clone();
+                lens.resolved = true;
+            }
+        }
+        lenses.sort_by(|a, b| a.range.start.cmp(&b.range.start, &buffer_snapshot));
+
+        let multi_buffer_snapshot = self.buffer.read(cx).snapshot(cx);
+        let excerpts = multi_buffer_snapshot
+            .excerpts()
+            .filter(|(_, snapshot, _)| snapshot.remote_id() == buffer_id)
+            .map(|(excerpt_id, _, range)| (excerpt_id, range.context.to_point(&buffer_snapshot)))
+            .collect::<Vec<_>>();
+
+        let mut lines = Vec::new();
+        for (row, line_lenses) in &lenses
+            .into_iter()
+            .chunk_by(|lens| lens.range.start.to_point(&buffer_snapshot).row)
+        {
+            let position = Point::new(row, buffer_snapshot.indent_size_for_line(row).len);
+            let Some((excerpt_id, _)) = excerpts
+                .iter()
+                .find(|(_, range)| range.start <= position && position <= range.end)
+            else {
+                continue;
+            };
+            let Some(anchor) = multi_buffer_snapshot
+                .anchor_in_excerpt(*excerpt_id, buffer_snapshot.anchor_before(position))
+            else {
+                continue;
+            };
+            lines.push((anchor, line_lenses.collect::<Vec<_>>()));
+        }
+        if lines.is_empty() {
+            return;
+        }
+
+        let block_ids = self.insert_blocks(
+            lines.iter().map(|(anchor, _)| BlockProperties {
+                placement: BlockPlacement::Above(*anchor),
+                height: Some(1),
+                style: BlockStyle::Flex,
+                render: Arc::new(|_| gpui::Empty.into_any_element()),
+                priority: 0,
+            }),
+            None,
+            cx,
+        );
+        let mut renderers = HashMap::default();
+        let mut blocks = Vec::with_capacity(lines.len());
+        for (block_id, (anchor, lenses)) in block_ids.into_iter().zip(lines) {
+            renderers.insert(
+                block_id,
+                self.code_lens_renderer(buffer_id, block_id, &lenses, cx),
+            );
+            blocks.push(CodeLensBlock {
+                block_id,
+                buffer: buffer.clone(),
+                anchor,
+                lenses,
+                resolve_task: None,
+            });
+        }
+        self.replace_blocks(renderers, None, cx);
+        self.code_lens_blocks.insert(buffer_id, blocks);
+    }
+
+    /// Resolves the commands of the lenses in the visible part of the editor, which the
+    /// servers may send without them to answer faster.
+    pub(super) fn resolve_visible_code_lenses(&mut self, cx: &mut Context<Self>) {
+        if self.code_lens_blocks.is_empty() {
+            return;
+        }
+        let Some(project) = self.project.clone() else {
+            return;
+        };
+
+        for (buffer, _, visible_range) in self.visible_excerpts(true, cx).into_values() {
+            let buffer_id = buffer.read(cx).remote_id();
+            let Some(blocks) = self.code_lens_blocks.get_mut(&buffer_id) else {
+                continue;
+            };
+            let buffer_snapshot = buffer.read(cx).snapshot();
+            for block in blocks {
+                if block.resolve_task.is_some() || block.lenses.iter().all(|lens| lens.resolved) {
+                    continue;
+                }
+                let offset = block.anchor.text_anchor.to_offset(&buffer_snapshot);
+                if !(visible_range.start..=visible_range.end).contains(&offset) {
+                    continue;
+                }
+
+                let resolve_tasks = project.update(cx, |project, cx| {
+                    block
+                        .lenses
+                        .iter()
+                        .map(|lens| project.resolve_code_lens(&buffer, lens.clone(), cx))
+                        .collect::<Vec<_>>()
+                });
+                let block_id = block.block_id;
+                block.resolve_task = Some(cx.spawn(async move |editor, cx| {
+                    let resolved = join_all(resolve_tasks).await;
+                    editor
+                        .update(cx, |editor, cx| {
+                            editor.code_lenses_resolved(buffer_id, block_id, resolved, cx);
+                        })
+                        .ok();
+                }));
+            }
+        }
+    }
+
+    fn code_lenses_resolved(
+        &mut self,
+        buffer_id: BufferId,
+        block_id: CustomBlockId,
+        resolved: Vec<anyhow::Result<CodeAction>>,
+        cx: &mut Context<Self>,
+    ) {
+        let Some(block) = self
+            .code_lens_blocks
+            .get_mut(&buffer_id)
+            .and_then(|blocks| blocks.iter_mut().find(|block| block.block_id == block_id))
+        else {
+            return;
+        };
+        for (lens, resolved) in block.lenses.iter_mut().zip(resolved) {
+            match resolved {
+                Ok(resolved) => *lens = resolved,
+                Err(e) => {
+                    log::error!("Failed to resolve code lens: {e:#}");
+                    lens.resolved = true;
+                }
+            }
+        }
+        let lenses = block.lenses.clone();
+        let renderer = self.code_lens_renderer(buffer_id, block_id, &lenses, cx);
+        self.replace_blocks(HashMap::from_iter([(block_id, renderer)]), None, cx);
+    }
+
+    /// Removes the code lenses of the buffers where they've been turned off, and fetches them
+    /// for the buffers where they've been turned on.
+    pub(super) fn clear_disabled_code_lenses(&mut self, window: &Window, cx: &mut Context<Self>) {
+        let buffers_to_clear = self
+            .code_lens_blocks
+            .values()
+            .filter_map(|blocks| blocks.first())
+            .filter(|block| !code_lens_enabled(block.buffer.read(cx), cx))
+            .map(|block| block.buffer.read(cx).remote_id())
+            .collect::<Vec<_>>();
+        for buffer_id in buffers_to_clear {
+            self.remove_code_lenses(buffer_id, cx);
+        }
+        self.refresh_code_lenses(None, window, cx);
+    }
+
+    pub(super) fn remove_code_lenses(
+        &mut self,
+        buffer_id: BufferId,
+        cx: &mut Context<Self>,
+    ) -> Vec<CodeLensBlock> {
+        let blocks = self.code_lens_blocks.remove(&buffer_id).unwrap_or_default();
+        if !blocks.is_empty() {
+            self.remove_blocks(
+                blocks
+                    .iter()
+                    .map(|block| block.block_id)
+                    .collect::<HashSet<_>>(),
+                None,
+                cx,
+            );
+        }
+        blocks
+    }
+
+    fn code_lens_renderer(
+        &self,
+        buffer_id: BufferId,
+        block_id: CustomBlockId,
+        lenses: &[CodeAction],
+        cx: &Context<Self>,
+    ) -> RenderBlock {
+        let labels = lenses
+            .iter()
+            .enumerate()
+            .filter_map(|(lens_ix, lens)| {
+                let LspAction::CodeLens(lsp_lens) = &lens.lsp_action else {
+                    return None;
+                };
+                match &lsp_lens.command {
+                    Some(command)
+                        if self.can_run_code_lens(lens.server_id, &command.command, cx) =>
+                    {
+                        Some(CodeLensLabel::Runnable {
+                            lens_ix,
+                            title: command.title.clone().into(),
+                        })
+                    }
+                    Some(command) => Some(CodeLensLabel::Text(command.title.clone().into())),
+                    None if lens.resolved => None,
+                    None => Some(CodeLensLabel::Resolving),
+                }
+            })
+            .collect::<Arc<[_]>>();
+        render_code_lenses(cx.entity().downgrade(), buffer_id, block_id, labels)
+    }
+
+    fn can_run_code_lens(&self, server_id: LanguageServerId, command: &str, cx: &App) -> bool {
+        if SHOW_REFERENCES_COMMANDS.contains(&command)
+            || command == RUST_ANALYZER_RUN_SINGLE
+            || command == RUST_ANALYZER_DEBUG_SINGLE
+        {
+            return true;
+        }
+        self.project.as_ref().is_some_and(|project| {
+            project
+                .read(cx)
+                .lsp_store()
+                .read(cx)
+                .lsp_server_capabilities
+                .get(&server_id)
+                .and_then(|capabilities| capabilities.execute_command_provider.as_ref())
+                .is_some_and(|provider| provider.commands.iter().any(|c| c == command))
+        })
+    }
+
+    fn run_code_lens(
+        &mut self,
+        buffer_id: BufferId,
+        block_id: CustomBlockId,
+        lens_ix: usize,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let Some(block) = self
+            .code_lens_blocks
+            .get(&buffer_id)
+            .and_then(|blocks| blocks.iter().find(|block| block.block_id == block_id))
+        else {
+            return;
+        };
+        let Some(lens) = block.lenses.get(lens_ix).cloned() else {
+            return;
+        };
+        let LspAction::CodeLens(lsp::CodeLens {
+            command: Some(command),
+            ..
+        }) = lens.lsp_action.clone()
+        else {
+            return;
+        };
+        let excerpt_id = block.anchor.excerpt_id;
+        let buffer = block.buffer.clone();
+
+        if SHOW_REFERENCES_COMMANDS.contains(&command.command.as_str()) {
+            let locations = command
+                .arguments
+                .as_ref()
+                .and_then(|arguments| arguments.get(2))
+                .and_then(|locations| {
+                    serde_json::from_value::<Vec<lsp::Location>>(locations.clone()).ok()
+                });
+            if let Some(locations) = locations {
+                self.open_code_lens_locations(lens.server_id, locations, command.title, window, cx);
+            } else {
+                // Without the locations, look the references up from the lens' position.
+                let Some(position) = self
+                    .buffer
+                    .read(cx)
+                    .snapshot(cx)
+                    .anchor_in_excerpt(excerpt_id, lens.range.start)
+                else {
+                    return;
+                };
+                self.change_selections(SelectionEffects::no_scroll(), window, cx, |s| {
+                    s.select_anchor_ranges([position..position])
+                });
+                if let Some(task) =
+                    self.find_all_references(&FindAllReferences::default(), window, cx)
+                {
+                    task.detach_and_log_err(cx);
+                }
+            }
+        } else if command.command == RUST_ANALYZER_RUN_SINGLE
+            || command.command == RUST_ANALYZER_DEBUG_SINGLE
+        {
+            let runnable = command
+                .arguments
+                .as_ref()
+                .and_then(|arguments| arguments.first())
+                .and_then(|runnable| serde_json::from_value::<Runnable>(runnable.clone()).ok());
+            let Some(runnable) = runnable else {
+                log::error!("Code lens `{}` has no runnable to run", command.title);
+                return;
+            };
+            self.spawn_code_lens_task(
+                lens.server_id,
+                buffer,
+                runnable.into_task_template(),
+                command.command == RUST_ANALYZER_DEBUG_SINGLE,
+                window,
+                cx,
+            );
+        } else if self.can_run_code_lens(lens.server_id, &command.command, cx) {
+            let Some(project) = self.project.clone() else {
+                return;
+            };
+            let Some(workspace) = self.workspace() else {
+                return;
+            };
+            let apply = project.update(cx, |project, cx| {
+                project.apply_code_action(buffer, lens, true, cx)
+            });
+            let workspace = workspace.downgrade();
+            cx.spawn_in(window, async move |editor, cx| {
+                let project_transaction = apply.await?;
+                Self::open_project_transaction(
+                    &editor,
+                    workspace,
+                    project_transaction,
+                    command.title,
+                    cx,
+                )
+                .await
+            })
+            .detach_and_log_err(cx);
+        }
+    }
+
+    /// Runs the task of a lens in the terminal, or debugs it with the language's debugger.
+    fn spawn_code_lens_task(
+        &mut self,
+        server_id: LanguageServerId,
+        buffer: Entity<Buffer>,
+        task_template: TaskTemplate,
+        debug: bool,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let Some(project) = self.project.clone() else {
+            return;
+        };
+        let Some(workspace) = self.workspace() else {
+            return;
+        };
+        let Some(language) = buffer.read(cx).language().cloned() else {
+            return;
+        };
+        let task_source_kind = TaskSourceKind::Lsp {
+            language_name: language.name().into(),
+            server: server_id,
+        };
+        let debug_adapter = language_settings(language.name().into(), buffer.read(cx).file(), cx)
+            .debuggers
+            .first()
+            .map(SharedString::from)
+            .or_else(|| language.config().debuggers.first().map(SharedString::from));
+        let workspace = workspace.downgrade();
+        cx.spawn_in(window, async move |_, cx| {
+            let task_context = lsp_task_context(&project, &buffer, cx)
+                .await
+                .unwrap_or_default();
+            if debug {
+                let debug_adapter = debug_adapter.with_context(|| {
+                    format!("No debugger is configured for {}", language.name())
+                })?;
+                let label = SharedString::from(task_template.label.clone());
+                let scenario = project
+                    .update(cx, |project, cx| {
+                        project.dap_store().update(cx, |dap_store, cx| {
+                            dap_store.debug_scenario_for_build_task(
+                                task_template,
+                                debug_adapter.into(),
+                                label.clone(),
+                                cx,
+                            )
+                        })
+                    })
+                    .await
+                    .with_context(|| format!("Cannot debug `{label}`"))?;
+                workspace.update_in(cx, |workspace, window, cx| {
+                    dap::send_telemetry(&scenario, TelemetrySpawnLocation::Gutter, cx);
+                    workspace.start_debug_session(
+                        scenario,
+                        task_context.into(),
+                        Some(buffer),
+                        None,
+                        window,
+                        cx,
+                    );
+                })
+            } else {
+                let resolved_task = task_template
+                    .resolve_task(&task_source_kind.to_id_base(), &task_context)
+                    .with_context(|| format!("Cannot run `{}`", task_template.label))?;
+                workspace.update_in(cx, |workspace, window, cx| {
+                    workspace.schedule_resolved_task(
+                        task_source_kind,
+                        resolved_task,
+                        false,
+                        window,
+                        cx,
+                    );
+                })
+            }
+        })
+        .detach_and_log_err(cx);
+    }
+
+    fn open_code_lens_locations(
+        &mut self,
+        server_id: LanguageServerId,
+        locations: Vec<lsp::Location>,
+        title: String,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        if locations.is_empty() {
+            return;
+        }
+        let Some(workspace) = self.workspace() else {
+            return;
+        };
+        let location_tasks = locations
+            .into_iter()
+            .map(|location| self.compute_target_location(location, server_id, window, cx))
+            .collect::<Vec<_>>();
+        cx.spawn_in(window, async move |_, cx| {
+            let locations = try_join_all(location_tasks).await?;
+            workspace.update_in(cx, |workspace, window, cx| {
+                let mut locations_by_buffer =
+                    std::collections::HashMap::<Entity<Buffer>, Vec<Range<Point>>>::default();
+                for location in locations.into_iter().flatten() {
+                    let buffer = location.buffer.read(cx);
+                    let range =
+                        location.range.start.to_point(buffer)..location.range.end.to_point(buffer);
+                    locations_by_buffer
+                        .entry(location.buffer)
+                        .or_default()
+                        .push(range);
+                }
+                if locations_by_buffer.is_empty() {
+                    return;
+                }
+                let allow_preview = PreviewTabsSettings::get_global(cx)
+                    .enable_preview_multibuffer_from_code_navigation;
+                Self::open_locations_in_multibuffer(
+                    workspace,
+                    locations_by_buffer,
+                    title,
+                    false,
+                    allow_preview,
+                    MultibufferSelectionMode::First,
+                    window,
+                    cx,
+                );
+            })
+        })
+        .detach_and_log_err(cx);
+    }
+}
+
+fn code_lens_enabled(buffer: &Buffer, cx: &App) -> bool {
+    language_settings(buffer.language().map(|l| l.name()), buffer.file(), cx).code_lens
+}
+
+fn render_code_lenses(
+    editor: WeakEntity<Editor>,
+    buffer_id: BufferId,
+    block_id: CustomBlockId,
+    labels: Arc<[CodeLensLabel]>,
+) -> RenderBlock {
+    Arc::new(move |cx: &mut BlockContext| {
+        let colors = cx.app.theme().colors();
+        let hover_color = colors.text;
+        let mut row = h_flex()
+            .id(block_id)
+            .h(cx.line_height)
+            .pl(cx.anchor_x)
+            .gap_1p5()
+            .text_ui_xs(cx.app)
+            .text_color(colors.text_muted);
+        for (ix, label) in labels.iter().enumerate() {
+            if ix > 0 {
+                row = row.child("|");
+            }
+            row = row.child(match label {
+                CodeLensLabel::Resolving => div().child("…").into_any_element(),
+                CodeLensLabel::Text(title) => div().child(title.clone()).into_any_element(),
+                CodeLensLabel::Runnable { lens_ix, title } => {
+                    let editor = editor.clone();
+                    let lens_ix = *lens_ix;
+                    div()
+                        .id(lens_ix)
+                        .cursor_pointer()
+                        .block_mouse_except_scroll()
+                        .hover(move |style| style.text_color(hover_color))
+                        .child(title.clone())
+                        .on_click(move |_, window, cx| {
+                            editor
+                                .update(cx, |editor, cx| {
+                                    editor.run_code_lens(buffer_id, block_id, lens_ix, window, cx)
+                                })
+                                .ok();
+                        })
+                        .into_any_element()
+                }
+            });
+        }
+        row.into_any_element()
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::{
+        Arc,
+        atomic::{AtomicUsize, Ordering},
+    };
+
+    use futures::StreamExt as _;
+    use gpui::TestAppContext;
+    use itertools::Itertools as _;
+    use lsp::DEFAULT_LSP_REQUEST_TIMEOUT;
+    use multi_buffer::ToPoint as _;
+    use pretty_assertions::assert_eq;
+    use project::{LspAction, TaskSourceKind};
+
+    use crate::{
+        Editor,
+        editor_tests::{init_test, update_test_language_settings},
+        test::editor_lsp_test_context::EditorLspTestContext,
+    };
+
+    fn code_lens_titles(editor: &Editor, cx: &gpui::App) -> Vec<(u32, Vec<Option<String>>)> {
+        let snapshot = editor.buffer.read(cx).snapshot(cx);
+        let mut titles = editor
+            .code_lens_blocks
+            .values()
+            .flatten()
+            .map(|block| {
+                let titles = block
+                    .lenses
+                    .iter()
+                    .map(|lens| match &lens.lsp_action {
+                        LspAction::CodeLens(lens) => {
+                            lens.command.as_ref().map(|command| command.title.clone())
+                        }
+                        _ => None,
+                    })
+                    .collect();
+                (block.anchor.to_point(&snapshot).row, titles)
+            })
+            .collect::<Vec<_>>();
+        titles.sort();
+        titles
+    }
+
+    #[gpui::test]
+    async fn test_code_lenses_are_resolved_and_refreshed(cx: &mut TestAppContext) {
+        init_test(cx, |_| {});
+        update_test_language_settings(cx, &|settings| {
+            settings.defaults.code_lens = Some(true);
+        });
+
+        let mut cx = EditorLspTestContext::new_rust(
+            lsp::ServerCapabilities {
+                code_lens_provider: Some(lsp::CodeLensOptions {
+                    resolve_provider: Some(true),
+                }),
+                ..lsp::ServerCapabilities::default()
+            },
+            cx,
+        )
+        .await;
+
+        let mut lens_requests = cx.set_request_handler::<lsp::request::CodeLensRequest, _, _>(
+            move |_, _, _| async move {
+                let lens = |line, start, end| lsp::CodeLens {
+                    range: lsp::Range::new(
+                        lsp::Position::new(line, start),
+                        lsp::Position::new(line, end),
+                    ),
+                    command: None,
+                    data: Some(serde_json::json!(line)),
+                };
+                Ok(Some(vec![lens(0, 3, 7), lens(4, 7, 12)]))
+            },
+        );
+        let references = Arc::new(AtomicUsize::new(1));
+        let resolves = Arc::new(AtomicUsize::new(0));
+        cx.set_request_handler::<lsp::request::CodeLensResolve, _, _>({
+            let references = references.clone();
+            let resolves = resolves.clone();
+            move |lens, _, _| {
+                let references = references.load(Ordering::Acquire);
+                resolves.fetch_add(1, Ordering::Release);
+                async move {
+                    Ok(lsp::CodeLens {
+                        command: Some(lsp::Command {
+                            title: format!("{references} references"),
+                            command: "rust-analyzer.showReferences".to_string(),
+                            arguments: None,
+                        }),
+                        ..lens
+                    })
+                }
+            }
+        });
+
+        cx.set_state("ˇfn main() {\n    other();\n}\n\n    fn other() {}\n");
+        assert!(lens_requests.next().await.is_some());
+        cx.run_until_parked();
+        cx.update_editor(|editor, _, cx| {
+            assert_eq!(
+                code_lens_titles(editor, cx),
+                [
+                    (0, vec![Some("1 references".to_string())]),
+                    (4, vec![Some("1 references".to_string())]),
+                ],
+            );
+        });
+        assert_eq!(resolves.load(Ordering::Acquire), 2);
+
+        // Edits keep the lenses that were resolved already.
+        cx.update_editor(|editor, window, cx| editor.handle_input("x", window, cx));
+        assert!(lens_requests.next().await.is_some());
+        cx.run_until_parked();
+        assert_eq!(resolves.load(Ordering::Acquire), 2);
+
+        references.store(2, Ordering::Release);
+        cx.lsp
+            .request::<lsp::request::CodeLensRefresh>((), DEFAULT_LSP_REQUEST_TIMEOUT)
+            .await
+            .into_response()
+            .expect("code lens refresh request failed");
+        assert!(lens_requests.next().await.is_some());
+        cx.run_until_parked();
+        cx.update_editor(|editor, _, cx| {
+            assert_eq!(
+                code_lens_titles(editor, cx),
+                [
+                    (0, vec![Some("2 references".to_string())]),
+                    (4, vec![Some("2 references".to_string())]),
+                ],
+                "Refreshed lenses should be resolved again"
+            );
+        });
+        assert_eq!(resolves.load(Ordering::Acquire), 4);
+
+        update_test_language_settings(&mut cx, &|settings| {
+            settings.defaults.code_lens = Some(false);
+        });
+        cx.run_until_parked();
+        cx.update_editor(|editor, _, cx| {
+            assert_eq!(code_lens_titles(editor, cx), []);
+        });
+    }
+
+    #[gpui::test]
+    async fn test_rust_analyzer_run_lens_schedules_task(cx: &mut TestAppContext) {
+        init_test(cx, |_| {});
+        update_test_language_settings(cx, &|settings| {
+            settings.defaults.code_lens = Some(true);
+        });
+
+        let mut cx = EditorLspTestContext::new_rust(
+            lsp::ServerCapabilities {
+                code_lens_provider: Some(lsp::CodeLensOptions {
+                    resolve_provider: Some(false),
+                }),
+                ..lsp::ServerCapabilities::default()
+            },
+            cx,
+        )
+        .await;
+
+        let mut lens_requests = cx.set_request_handler::<lsp::request::CodeLensRequest, _, _>(
+            move |_, _, _| async move {
+                Ok(Some(vec![lsp::CodeLens {
+                    range: lsp::Range::new(lsp::Position::new(0, 3), lsp::Position::new(0, 7)),
+                    command: Some(lsp::Command {
+                        title: "▶\u{fe0e} Run".to_string(),
+                        command: "rust-analyzer.runSingle".to_string(),
+                        arguments: Some(vec![serde_json::json!({
+                            "label": "run main",
+                            "kind": "cargo",
+                            "args": {
+                                "cwd": "/root",
+                                "cargoArgs": ["run", "--bin", "main"],
+                                "executableArgs": [],
+                            },
+                        })]),
+                    }),
+                    data: None,
+                }]))
+            },
+        );
+
+        cx.set_state("ˇfn main() {}\n");
+        assert!(lens_requests.next().await.is_some());
+        cx.run_until_parked();
+        cx.update_editor(|editor, window, cx| {
+            let (buffer_id, block_id) = editor
+                .code_lens_blocks
+                .iter()
+                .flat_map(|(buffer_id, blocks)| {
+                    blocks.iter().map(|block| (*buffer_id, block.block_id))
+                })
+                .exactly_one()
+                .ok()
+                .expect("a single code lens block");
+            assert!(editor.can_run_code_lens(
+                editor.code_lens_blocks[&buffer_id][0].lenses[0].server_id,
+                "rust-analyzer.runSingle",
+                cx
+            ));
+            editor.run_code_lens(buffer_id, block_id, 0, window, cx);
+        });
+        cx.run_until_parked();
+
+        let (task_source_kind, task) = cx.update_editor(|editor, _, cx| {
+            editor
+                .project
+                .as_ref()
+                .unwrap()
+                .read(cx)
+                .task_store()
+                .read(cx)
+                .task_inventory()
+                .unwrap()
+                .read(cx)
+                .last_scheduled_task(None)
+                .expect("the lens should schedule a task")
+        });
+        assert!(matches!(task_source_kind, TaskSourceKind::Lsp { .. }));
+        assert_eq!(task.resolved_label, "run main");
+        assert_eq!(task.resolved.command.as_deref(), Some("cargo"));
+        assert_eq!(task.resolved.args, ["run", "--bin", "main"]);
+    }
+}
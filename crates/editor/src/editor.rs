@@ -16,6 +16,7 @@ pub mod blink_manager;
 mod bracket_colorization;
 mod clangd_ext;
 pub mod code_context_menus;
+mod code_lens;
 pub mod display_map;
 mod document_colors;
 mod document_symbols;
@@ -1339,6 +1340,8 @@ pub struct Editor {
     refresh_colors_task: Task<()>,
     use_document_folding_ranges: bool,
     refresh_folding_ranges_task: Task<()>,
+    code_lens_blocks: HashMap<BufferId, Vec<code_lens::CodeLensBlock>>,
+    refresh_code_lenses_task: Task<()>,
     inlay_hints: Option<LspInlayHintData>,
     folding_newlines: Task<()>,
     select_next_is_case_sensitive: Option<bool>,
@@ -2141,7 +2144,7 @@ impl Editor {
                 window,
                 |editor, _, event, window, cx| match event {
                     project::Event::RefreshCodeLens => {
-                        // we always query lens with actions, without storing them, always refreshing them
+                        editor.invalidate_code_lenses(window, cx);
                     }
                     project::Event::RefreshInlayHints {
                         server_id,
@@ -2570,6 +2573,8 @@ impl Editor {
             refresh_colors_task: Task::ready(()),
             use_document_folding_ranges: false,
             refresh_folding_ranges_task: Task::ready(()),
+            code_lens_blocks: HashMap::default(),
+            refresh_code_lenses_task: Task::ready(()),
             inlay_hints: None,
             next_color_inlay_id: 0,
             post_scroll_update: Task::ready(()),
@@ -2668,6 +2673,7 @@ impl Editor {
                                     if !editor.buffer().read(cx).is_singleton() {
                                         editor.update_lsp_data(None, window, cx);
                                     }
+                                    editor.resolve_visible_code_lenses(cx);
                                 })
                                 .ok();
                         });
@@ -18200,7 +18206,8 @@ impl Editor {
             .into_iter()
             .filter_map(|def| match def {
                 HoverLink::Text(link) => Some(Task::ready(anyhow::Ok(Some(link.target)))),
-                HoverLink::InlayHint(lsp_location, server_id) => {
+                HoverLink::InlayHint(lsp_location, server_id)
+                | HoverLink::DocumentLink(lsp_location, server_id) => {
                     let computation =
                         self.compute_target_location(lsp_location, server_id, window, cx);
                     Some(cx.background_spawn(computation))
@@ -24185,6 +24192,7 @@ impl Editor {
                         display_map.invalidate_semantic_highlights(*buffer_id);
                         display_map.clear_lsp_folding_ranges(*buffer_id, cx);
                     });
+                    self.remove_code_lenses(*buffer_id, cx);
                 }
 
                 self.display_map.update(cx, |display_map, cx| {
@@ -24437,6 +24445,7 @@ impl Editor {
 
             if language_settings_changed {
                 self.clear_disabled_lsp_folding_ranges(window, cx);
+                self.clear_disabled_code_lenses(window, cx);
                 self.refresh_document_symbols(None, cx);
             }
 
@@ -25605,6 +25614,7 @@ impl Editor {
         self.refresh_document_colors(for_buffer, window, cx);
         self.refresh_folding_ranges(for_buffer, window, cx);
         self.refresh_document_symbols(for_buffer, cx);
+        self.refresh_code_lenses(for_buffer, window, cx);
     }
 
     fn register_visible_buffers(&mut self, cx: &mut Context<Self>) {
@@ -11,9 +11,10 @@ use lsp::LanguageServerId;
 use project::{InlayId, LocationLink, Project, ResolvedPath};
 use regex::Regex;
 use settings::Settings;
-use std::{ops::Range, sync::LazyLock};
+use std::{ops::Range, str::FromStr as _, sync::LazyLock};
 use text::OffsetRangeExt;
 use theme::ActiveTheme as _;
+use url::Url;
 use util::{ResultExt, TryFutureExt as _, maybe};
 
 #[derive(Debug)]
@@ -66,6 +67,7 @@ pub enum HoverLink {
     File(ResolvedPath),
     Text(LocationLink),
     InlayHint(lsp::Location, LanguageServerId),
+    DocumentLink(lsp::Location, LanguageServerId),
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -336,7 +338,7 @@ pub fn show_link_definition(
         || hovered_link_state
             .links
             .first()
-            .is_some_and(|d| matches!(d, HoverLink::Url(_)));
+            .is_some_and(|d| matches!(d, HoverLink::Url(_) | HoverLink::DocumentLink(..)));
 
     if same_kind {
         if is_cached && (hovered_link_state.last_trigger_point == trigger_point)
@@ -361,7 +363,17 @@ pub fn show_link_definition(
         async move {
             let result = match &trigger_point {
                 TriggerPoint::Text(_) => {
-                    if let Some((url_range, url)) = find_url(&buffer, text_anchor, cx.clone()) {
+                    if let Some((link_range, link)) =
+                        find_document_link(&buffer, project.clone(), text_anchor, cx).await
+                    {
+                        let range = maybe!({
+                            let range = snapshot.anchor_range_in_excerpt(excerpt_id, link_range)?;
+                            Some(RangeInEditor::Text(range))
+                        });
+                        Some((range, vec![link]))
+                    } else if let Some((url_range, url)) =
+                        find_url(&buffer, text_anchor, cx.clone())
+                    {
                         this.read_with(cx, |_, _| {
                             let range = maybe!({
                                 let range =
@@ -603,6 +615,64 @@ pub(crate) fn find_url_from_range(
     None
 }
 
+/// Looks up the LSP document link under `position`, resolving its target if the server
+/// sent it without one.
+pub(crate) async fn find_document_link(
+    buffer: &Entity<language::Buffer>,
+    project: Option<Entity<Project>>,
+    position: text::Anchor,
+    cx: &mut AsyncWindowContext,
+) -> Option<(Range<text::Anchor>, HoverLink)> {
+    let project = project?;
+    let lsp_store = project.read_with(cx, |project, _| project.lsp_store());
+    let links = lsp_store
+        .update(cx, |lsp_store, cx| lsp_store.document_links(buffer, cx))
+        .await;
+    let snapshot = buffer.read_with(cx, |buffer, _| buffer.snapshot());
+    let offset = position.to_offset(&snapshot);
+    let link = links
+        .into_iter()
+        .find(|link| link.range.to_offset(&snapshot).contains(&offset))?;
+    let link = lsp_store
+        .update(cx, |lsp_store, cx| {
+            lsp_store.resolve_document_link(buffer, link, cx)
+        })
+        .await
+        .log_err()?;
+    let target = link.target()?.to_string();
+    let hover_link = match Url::parse(&target) {
+        Ok(mut url) if url.scheme() == "file" => {
+            let position = url
+                .fragment()
+                .and_then(document_link_fragment_position)
+                .unwrap_or_default();
+            url.set_fragment(None);
+            HoverLink::DocumentLink(
+                lsp::Location {
+                    uri: lsp::Uri::from_str(url.as_str()).log_err()?,
+                    range: lsp::Range::new(position, position),
+                },
+                link.server_id,
+            )
+        }
+        _ => HoverLink::Url(target),
+    };
+    Some((link.range, hover_link))
+}
+
+/// Parses a `L<line>` or `L<line>,<column>` file URI fragment, as used by `rust-analyzer`,
+/// `clangd` and others to point into the linked file. Both numbers are 1-based.
+fn document_link_fragment_position(fragment: &str) -> Option<lsp::Position> {
+    let mut parts = fragment.strip_prefix('L')?.splitn(2, [',', ':']);
+    let line = parts.next()?.parse::<u32>().ok()?.checked_sub(1)?;
+    let character = parts
+        .next()
+        .and_then(|column| column.parse::<u32>().ok())
+        .and_then(|column| column.checked_sub(1))
+        .unwrap_or(0);
+    Some(lsp::Position::new(line, character))
+}
+
 pub(crate) async fn find_file(
     buffer: &Entity<language::Buffer>,
     project: Option<Entity<Project>>,
@@ -1362,6 +1432,111 @@ mod tests {
         );
     }
 
+    #[gpui::test]
+    async fn test_document_links(cx: &mut gpui::TestAppContext) {
+        init_test(cx, |_| {});
+        let mut cx = EditorLspTestContext::new_rust(
+            lsp::ServerCapabilities {
+                document_link_provider: Some(lsp::DocumentLinkOptions {
+                    resolve_provider: Some(true),
+                    work_done_progress_options: Default::default(),
+                }),
+                ..Default::default()
+            },
+            cx,
+        )
+        .await;
+
+        cx.set_state(indoc! {"
+            extern crate serde;
+            mod other;
+            ˇfn main() {}
+        "});
+        let crate_range = cx.lsp_range(indoc! {"
+            extern crate «serde»;
+            mod other;
+            fn main() {}
+        "});
+        let module_range = cx.lsp_range(indoc! {"
+            extern crate serde;
+            mod «other»;
+            fn main() {}
+        "});
+        let module_target = lsp::Uri::from_str(&format!("{}#L3,4", cx.buffer_lsp_url)).unwrap();
+        let mut link_requests =
+            cx.set_request_handler::<lsp::request::DocumentLinkRequest, _, _>(move |_, _, _| {
+                let module_target = module_target.clone();
+                async move {
+                    Ok(Some(vec![
+                        lsp::DocumentLink {
+                            range: crate_range,
+                            target: None,
+                            tooltip: None,
+                            data: Some(serde_json::json!("serde")),
+                        },
+                        lsp::DocumentLink {
+                            range: module_range,
+                            target: Some(module_target),
+                            tooltip: None,
+                            data: None,
+                        },
+                    ]))
+                }
+            });
+        let mut resolve_requests = cx
+            .set_request_handler::<lsp::request::DocumentLinkResolve, _, _>(
+                |link, _, _| async move {
+                    Ok(lsp::DocumentLink {
+                        target: Some(lsp::Uri::from_str("https://docs.rs/serde").unwrap()),
+                        ..link
+                    })
+                },
+            );
+
+        let screen_coord = cx.pixel_position(indoc! {"
+            extern crate serˇde;
+            mod other;
+            fn main() {}
+        "});
+        cx.simulate_mouse_move(screen_coord, None, Modifiers::secondary_key());
+        assert!(link_requests.next().await.is_some());
+        assert!(resolve_requests.next().await.is_some());
+        cx.run_until_parked();
+        cx.assert_editor_text_highlights(
+            HighlightKey::HoveredLinkState,
+            indoc! {"
+            extern crate «serdeˇ»;
+            mod other;
+            fn main() {}
+        "},
+        );
+        cx.simulate_click(screen_coord, Modifiers::secondary_key());
+        assert_eq!(cx.opened_url(), Some("https://docs.rs/serde".into()));
+
+        let screen_coord = cx.pixel_position(indoc! {"
+            extern crate serde;
+            mod otˇher;
+            fn main() {}
+        "});
+        cx.simulate_mouse_move(screen_coord, None, Modifiers::secondary_key());
+        cx.run_until_parked();
+        cx.assert_editor_text_highlights(
+            HighlightKey::HoveredLinkState,
+            indoc! {"
+            extern crate serde;
+            mod «otherˇ»;
+            fn main() {}
+        "},
+        );
+        cx.simulate_click(screen_coord, Modifiers::secondary_key());
+        cx.run_until_parked();
+        cx.assert_editor_state(indoc! {"
+            extern crate serde;
+            mod other;
+            fn ˇmain() {}
+        "});
+    }
+
     #[gpui::test]
     async fn test_hover_preconditions(cx: &mut gpui::TestAppContext) {
         init_test(cx, |_| {});
@@ -55,7 +55,7 @@ where
         })
 }
 
-async fn lsp_task_context(
+pub(crate) async fn lsp_task_context(
     project: &Entity<Project>,
     buffer: &Entity<Buffer>,
     cx: &mut AsyncApp,
@@ -6,9 +6,10 @@ use git::{
     Oid, RunHook,
     blame::Blame,
     repository::{
-        AskPassDelegate, Branch, CommitDataReader, CommitDetails, CommitOptions, FetchOptions,
-        GRAPH_CHUNK_SIZE, GitRepository, GitRepositoryCheckpoint, InitialGraphCommitData, LogOrder,
-        LogSource, PushOptions, Remote, RepoPath, ResetMode, Worktree,
+        AskPassDelegate, BisectLog, BisectMark, Branch, CommitDataReader, CommitDetails,
+        CommitOptions, FetchOptions, GRAPH_CHUNK_SIZE, GitRepository, GitRepositoryCheckpoint,
+        InitialGraphCommitData, LogOrder, LogSource, PushOptions, RebaseTodoEntry, ReflogEntry,
+        Remote, RepoPath, ResetMode, Submodule, Tag, Worktree,
     },
     status::{
         DiffTreeType, FileStatus, GitStatus, StatusCode, TrackedStatus, TreeDiff, TreeDiffStatus,
@@ -47,6 +48,8 @@ pub struct FakeGitRepositoryState {
     pub blames: HashMap<RepoPath, Blame>,
     pub current_branch_name: Option<String>,
     pub branches: HashSet<String>,
+    /// Tags, most recently created first
+    pub tags: Vec<Tag>,
     /// List of remotes, keys are names and values are URLs
     pub remotes: HashMap<String, String>,
     pub simulated_index_write_error_message: Option<String>,
@@ -66,6 +69,7 @@ impl FakeGitRepositoryState {
             blames: Default::default(),
             current_branch_name: Default::default(),
             branches: Default::default(),
+            tags: Vec::new(),
             simulated_index_write_error_message: Default::default(),
             simulated_create_worktree_error: Default::default(),
             refs: HashMap::from_iter([("HEAD".into(), "abc".into())]),
@@ -232,6 +236,73 @@ impl GitRepository for FakeGitRepository {
         unimplemented!()
     }
 
+    fn is_ancestor(&self, _ancestor: String, _descendant: String) -> BoxFuture<'_, Result<bool>> {
+        unimplemented!()
+    }
+
+    fn rebase_todo(&self, _base: String) -> BoxFuture<'_, Result<Vec<RebaseTodoEntry>>> {
+        unimplemented!()
+    }
+
+    fn rebase(
+        &self,
+        _base: String,
+        _todo: Vec<RebaseTodoEntry>,
+        _env: Arc<HashMap<String, String>>,
+    ) -> BoxFuture<'_, Result<()>> {
+        unimplemented!()
+    }
+
+    fn cherry_pick(
+        &self,
+        _commits: Vec<String>,
+        _env: Arc<HashMap<String, String>>,
+    ) -> BoxFuture<'_, Result<()>> {
+        unimplemented!()
+    }
+
+    fn revert(
+        &self,
+        _commits: Vec<String>,
+        _env: Arc<HashMap<String, String>>,
+    ) -> BoxFuture<'_, Result<()>> {
+        unimplemented!()
+    }
+
+    fn continue_operation(&self, _env: Arc<HashMap<String, String>>) -> BoxFuture<'_, Result<()>> {
+        unimplemented!()
+    }
+
+    fn abort_operation(&self, _env: Arc<HashMap<String, String>>) -> BoxFuture<'_, Result<()>> {
+        unimplemented!()
+    }
+
+    fn bisect_start(
+        &self,
+        _bad: Option<String>,
+        _good: Vec<String>,
+        _env: Arc<HashMap<String, String>>,
+    ) -> BoxFuture<'_, Result<()>> {
+        unimplemented!()
+    }
+
+    fn bisect_mark(
+        &self,
+        _mark: BisectMark,
+        _commit: Option<String>,
+        _env: Arc<HashMap<String, String>>,
+    ) -> BoxFuture<'_, Result<()>> {
+        unimplemented!()
+    }
+
+    fn bisect_reset(&self, _env: Arc<HashMap<String, String>>) -> BoxFuture<'_, Result<()>> {
+        unimplemented!()
+    }
+
+    fn bisect_log(&self) -> BoxFuture<'_, Result<Option<BisectLog>>> {
+        future::ready(Ok(None)).boxed()
+    }
+
     fn path(&self) -> PathBuf {
         self.repository_dir_path.clone()
     }
@@ -596,6 +667,79 @@ impl GitRepository for FakeGitRepository {
         })
     }
 
+    fn tags(&self) -> BoxFuture<'_, Result<Vec<Tag>>> {
+        self.with_state_async(false, move |state| Ok(state.tags.clone()))
+    }
+
+    fn create_tag(
+        &self,
+        name: String,
+        target: Option<String>,
+        message: Option<String>,
+        _env: Arc<HashMap<String, String>>,
+    ) -> BoxFuture<'_, Result<()>> {
+        self.with_state_async(true, move |state| {
+            if state.tags.iter().any(|tag| tag.name == name) {
+                bail!("tag '{name}' already exists");
+            }
+            let target = target.unwrap_or_else(|| "HEAD".into());
+            let sha = state.refs.get(&target).cloned().unwrap_or(target);
+            state.tags.insert(
+                0,
+                Tag {
+                    name: name.into(),
+                    sha: sha.into(),
+                    annotation: message
+                        .and_then(|message| Some(message.lines().next()?.to_string().into())),
+                },
+            );
+            Ok(())
+        })
+    }
+
+    fn delete_tag(
+        &self,
+        name: String,
+        _env: Arc<HashMap<String, String>>,
+    ) -> BoxFuture<'_, Result<()>> {
+        self.with_state_async(true, move |state| {
+            let ix = state
+                .tags
+                .iter()
+                .position(|tag| tag.name == name)
+                .with_context(|| format!("tag '{name}' not found"))?;
+            state.tags.remove(ix);
+            Ok(())
+        })
+    }
+
+    fn reflog(&self, _ref_name: String, _limit: usize) -> BoxFuture<'_, Result<Vec<ReflogEntry>>> {
+        future::ready(Ok(Vec::new())).boxed()
+    }
+
+    fn submodules(&self) -> BoxFuture<'_, Result<Vec<Submodule>>> {
+        future::ready(Ok(Vec::new())).boxed()
+    }
+
+    fn update_submodules(
+        &self,
+        _paths: Vec<RepoPath>,
+        _init: bool,
+        _askpass: AskPassDelegate,
+        _env: Arc<HashMap<String, String>>,
+        _cx: AsyncApp,
+    ) -> BoxFuture<'_, Result<git::repository::RemoteCommandOutput>> {
+        unimplemented!()
+    }
+
+    fn sync_submodules(
+        &self,
+        _paths: Vec<RepoPath>,
+        _env: Arc<HashMap<String, String>>,
+    ) -> BoxFuture<'_, Result<()>> {
+        unimplemented!()
+    }
+
     fn blame(
         &self,
         path: RepoPath,
@@ -67,6 +67,30 @@ actions!(
         TrashUntrackedFiles,
         /// Undoes the last commit, keeping changes in the working directory.
         Uncommit,
+        /// Undoes the last commit, amend, reset or rebase of the current branch, using its reflog.
+        UndoLastOperation,
+        /// Continues the rebase, cherry-pick or revert stopped on a conflict.
+        ContinueOperation,
+        /// Aborts the rebase, cherry-pick or revert in progress.
+        AbortOperation,
+        /// Starts a bisect, with the current commit marked as bad.
+        BisectStart,
+        /// Marks the commit being bisected as good.
+        BisectGood,
+        /// Marks the commit being bisected as bad.
+        BisectBad,
+        /// Skips the commit being bisected, when it can't be tested.
+        BisectSkip,
+        /// Ends the bisect, checking out the commit it started from.
+        BisectReset,
+        /// Runs a task on each commit being bisected, marking it by the task's exit code.
+        BisectRun,
+        /// Clones and checks out the submodules that aren't initialized yet.
+        InitSubmodules,
+        /// Checks out the commits recorded for the submodules.
+        UpdateSubmodules,
+        /// Copies the submodule URLs from .gitmodules into the repository's config.
+        SyncSubmodules,
         /// Pushes commits to the remote repository.
         Push,
         /// Pushes commits to a specific remote branch.
@@ -36,6 +36,7 @@ use thiserror::Error;
 use util::command::{Stdio, new_command};
 use util::paths::PathStyle;
 use util::rel_path::RelPath;
+use util::shell::ShellKind;
 use util::{ResultExt, normalize_path, paths};
 use uuid::Uuid;
 
@@ -356,6 +357,53 @@ pub fn parse_worktrees_from_str<T: AsRef<str>>(raw_worktrees: T) -> Vec<Worktree
     worktrees
 }
 
+#[derive(Clone, Debug, Hash, PartialEq, Eq)]
+pub struct Tag {
+    pub name: SharedString,
+    /// The commit the tag points to, with annotated tags peeled.
+    pub sha: SharedString,
+    /// The subject of the tag message, for annotated tags.
+    pub annotation: Option<SharedString>,
+}
+
+/// An entry of a reflog, recording a movement of HEAD or a branch.
+#[derive(Clone, Debug, Hash, PartialEq, Eq)]
+pub struct ReflogEntry {
+    /// The selector of the entry, such as `HEAD@{2}`.
+    pub selector: SharedString,
+    /// The commit the ref pointed to after the movement.
+    pub sha: SharedString,
+    /// What moved the ref, such as `commit (amend): Fix typo` or `reset: moving to HEAD~1`.
+    pub message: SharedString,
+    /// When the ref moved, as a unix timestamp.
+    pub timestamp: i64,
+}
+
+impl ReflogEntry {
+    /// The git command that moved the ref, such as `commit (amend)` or `rebase (finish)`.
+    pub fn command(&self) -> &str {
+        self.message
+            .split_once(": ")
+            .map_or(self.message.as_ref(), |(command, _)| command)
+    }
+}
+
+/// A submodule of the repository, as recorded in its index.
+#[derive(Clone, Debug, Hash, PartialEq, Eq)]
+pub struct Submodule {
+    pub path: RepoPath,
+    /// The commit recorded for the submodule in the index.
+    pub sha: SharedString,
+    /// Whether the submodule has been cloned into the working tree.
+    pub is_initialized: bool,
+    /// Whether the submodule has a commit checked out other than the recorded one.
+    pub has_new_commits: bool,
+    /// Whether the submodule has uncommitted changes to its tracked files.
+    pub has_modified_content: bool,
+    /// Whether the submodule has untracked files.
+    pub has_untracked_content: bool,
+}
+
 #[derive(Clone, Debug, Hash, PartialEq, Eq)]
 pub struct Upstream {
     pub ref_name: SharedString,
@@ -525,6 +573,103 @@ pub enum ResetMode {
     /// Reset the branch pointer and index, leave worktree unchanged (this makes it look as though things that were
     /// committed are now unstaged).
     Mixed,
+    /// Reset the branch pointer, index and worktree, but keep uncommitted changes (this fails rather than overwrite
+    /// a file with uncommitted changes).
+    Keep,
+}
+
+/// What an interactive rebase does with a single commit.
+#[derive(Debug, Clone, Copy, Hash, PartialEq, Eq)]
+pub enum RebaseTodoAction {
+    /// Keep the commit as is.
+    Pick,
+    /// Keep the commit, replacing its message.
+    Reword,
+    /// Meld the commit into the previous one, combining their messages.
+    Squash,
+    /// Meld the commit into the previous one, keeping the previous message.
+    Fixup,
+    /// Remove the commit.
+    Drop,
+}
+
+impl RebaseTodoAction {
+    /// Whether the action melds the commit into the previous one, which the first commit can't do.
+    pub fn melds_into_previous(self) -> bool {
+        matches!(self, Self::Squash | Self::Fixup)
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            RebaseTodoAction::Pick => "pick",
+            RebaseTodoAction::Reword => "reword",
+            RebaseTodoAction::Squash => "squash",
+            RebaseTodoAction::Fixup => "fixup",
+            RebaseTodoAction::Drop => "drop",
+        }
+    }
+}
+
+/// A line of an interactive rebase todo list.
+#[derive(Debug, Clone, Hash, PartialEq, Eq)]
+pub struct RebaseTodoEntry {
+    pub action: RebaseTodoAction,
+    pub sha: SharedString,
+    pub subject: SharedString,
+    /// The new message of the commit, used by [`RebaseTodoAction::Reword`].
+    pub message: Option<SharedString>,
+}
+
+/// The verdict given to a commit while bisecting.
+#[derive(Debug, Clone, Copy, Hash, PartialEq, Eq)]
+pub enum BisectMark {
+    /// The commit doesn't have the regression.
+    Good,
+    /// The commit has the regression.
+    Bad,
+    /// The commit can't be tested.
+    Skip,
+}
+
+impl BisectMark {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            BisectMark::Good => "good",
+            BisectMark::Bad => "bad",
+            BisectMark::Skip => "skip",
+        }
+    }
+}
+
+/// The state of a bisect in progress, as recorded in `BISECT_LOG`.
+#[derive(Debug, Clone, Default, Hash, PartialEq, Eq)]
+pub struct BisectLog {
+    /// The most recent commit marked bad, bounding the range from above.
+    pub bad: Option<SharedString>,
+    /// The commits marked good, bounding the range from below.
+    pub good: Vec<SharedString>,
+    pub skipped: Vec<SharedString>,
+    /// The commit that introduced the regression, once the bisect has found it.
+    pub first_bad: Option<SharedString>,
+    /// The number of commits left to test, once both a good and a bad commit are known.
+    pub remaining: Option<u32>,
+    /// The estimated number of steps left, once both a good and a bad commit are known.
+    pub steps: Option<u32>,
+}
+
+impl BisectLog {
+    /// Returns how the commit with the given SHA was marked, if it was.
+    pub fn mark_of(&self, sha: &str) -> Option<BisectMark> {
+        if self.bad.as_ref().is_some_and(|bad| bad == sha) {
+            Some(BisectMark::Bad)
+        } else if self.good.iter().any(|good| good == sha) {
+            Some(BisectMark::Good)
+        } else if self.skipped.iter().any(|skipped| skipped == sha) {
+            Some(BisectMark::Skip)
+        } else {
+            None
+        }
+    }
 }
 
 #[derive(Debug, Clone, Hash, PartialEq, Eq)]
@@ -765,6 +910,50 @@ pub trait GitRepository: Send + Sync {
 
     fn delete_branch(&self, name: String) -> BoxFuture<'_, Result<()>>;
 
+    /// Returns the tags of the repository, most recently created first.
+    fn tags(&self) -> BoxFuture<'_, Result<Vec<Tag>>>;
+
+    /// Creates a tag pointing at `target`, or HEAD if none is given.
+    /// The tag is annotated when a `message` is given, and lightweight otherwise.
+    fn create_tag(
+        &self,
+        name: String,
+        target: Option<String>,
+        message: Option<String>,
+        env: Arc<HashMap<String, String>>,
+    ) -> BoxFuture<'_, Result<()>>;
+
+    fn delete_tag(
+        &self,
+        name: String,
+        env: Arc<HashMap<String, String>>,
+    ) -> BoxFuture<'_, Result<()>>;
+
+    /// Returns the most recent `limit` entries of the reflog of `ref_name`, newest first.
+    fn reflog(&self, ref_name: String, limit: usize) -> BoxFuture<'_, Result<Vec<ReflogEntry>>>;
+
+    /// Returns the submodules declared in `.gitmodules` and recorded in the index.
+    fn submodules(&self) -> BoxFuture<'_, Result<Vec<Submodule>>>;
+
+    /// Checks out the recorded commit of the submodules at the given paths, or of all
+    /// submodules if no paths are given. With `init`, uninitialized submodules get cloned first.
+    fn update_submodules(
+        &self,
+        paths: Vec<RepoPath>,
+        init: bool,
+        askpass: AskPassDelegate,
+        env: Arc<HashMap<String, String>>,
+        cx: AsyncApp,
+    ) -> BoxFuture<'_, Result<RemoteCommandOutput>>;
+
+    /// Updates the remote URLs of the submodules at the given paths, or of all submodules
+    /// if no paths are given, to the ones in `.gitmodules`.
+    fn sync_submodules(
+        &self,
+        paths: Vec<RepoPath>,
+        env: Arc<HashMap<String, String>>,
+    ) -> BoxFuture<'_, Result<()>>;
+
     fn worktrees(&self) -> BoxFuture<'_, Result<Vec<Worktree>>>;
 
     fn create_worktree(
@@ -792,6 +981,65 @@ pub trait GitRepository: Send + Sync {
         env: Arc<HashMap<String, String>>,
     ) -> BoxFuture<'_, Result<()>>;
 
+    /// Whether `ancestor` is `descendant` or one of its ancestors, as `git merge-base --is-ancestor`
+    /// reports it.
+    fn is_ancestor(&self, ancestor: String, descendant: String) -> BoxFuture<'_, Result<bool>>;
+
+    /// Returns the todo list of an interactive rebase of the current branch onto `base`,
+    /// with every commit picked, oldest first.
+    fn rebase_todo(&self, base: String) -> BoxFuture<'_, Result<Vec<RebaseTodoEntry>>>;
+
+    /// Rebases the current branch onto `base`, applying the `todo` list given.
+    fn rebase(
+        &self,
+        base: String,
+        todo: Vec<RebaseTodoEntry>,
+        env: Arc<HashMap<String, String>>,
+    ) -> BoxFuture<'_, Result<()>>;
+
+    /// Applies the changes introduced by the given commits on top of HEAD, in order.
+    fn cherry_pick(
+        &self,
+        commits: Vec<String>,
+        env: Arc<HashMap<String, String>>,
+    ) -> BoxFuture<'_, Result<()>>;
+
+    /// Creates commits reverting the given commits, in order.
+    fn revert(
+        &self,
+        commits: Vec<String>,
+        env: Arc<HashMap<String, String>>,
+    ) -> BoxFuture<'_, Result<()>>;
+
+    /// Continues the rebase, cherry-pick or revert stopped on a conflict.
+    fn continue_operation(&self, env: Arc<HashMap<String, String>>) -> BoxFuture<'_, Result<()>>;
+
+    /// Aborts the rebase, cherry-pick or revert in progress, restoring the original branch.
+    fn abort_operation(&self, env: Arc<HashMap<String, String>>) -> BoxFuture<'_, Result<()>>;
+
+    /// Starts bisecting between the `bad` commit, or HEAD if none is given, and the `good` ones.
+    /// With no commits given, they're left to be marked afterwards.
+    fn bisect_start(
+        &self,
+        bad: Option<String>,
+        good: Vec<String>,
+        env: Arc<HashMap<String, String>>,
+    ) -> BoxFuture<'_, Result<()>>;
+
+    /// Marks `commit`, or HEAD if none is given, and checks out the next commit to test.
+    fn bisect_mark(
+        &self,
+        mark: BisectMark,
+        commit: Option<String>,
+        env: Arc<HashMap<String, String>>,
+    ) -> BoxFuture<'_, Result<()>>;
+
+    /// Ends the bisect, checking out the commit HEAD was on when it started.
+    fn bisect_reset(&self, env: Arc<HashMap<String, String>>) -> BoxFuture<'_, Result<()>>;
+
+    /// Returns the state of the bisect in progress, if any.
+    fn bisect_log(&self) -> BoxFuture<'_, Result<Option<BisectLog>>>;
+
     fn show(&self, commit: String) -> BoxFuture<'_, Result<CommitDetails>>;
 
     fn load_commit(&self, commit: String, cx: AsyncApp) -> BoxFuture<'_, Result<CommitDiff>>;
@@ -1291,6 +1539,7 @@ impl GitRepository for RealGitRepository {
             let mode_flag = match mode {
                 ResetMode::Mixed => "--mixed",
                 ResetMode::Soft => "--soft",
+                ResetMode::Keep => "--keep",
             };
 
             let git = git_binary?;
@@ -1338,6 +1587,317 @@ impl GitRepository for RealGitRepository {
         .boxed()
     }
 
+    fn is_ancestor(&self, ancestor: String, descendant: String) -> BoxFuture<'_, Result<bool>> {
+        let git_binary = self.git_binary();
+        self.executor
+            .spawn(async move { is_ancestor(&git_binary?, &ancestor, &descendant).await })
+            .boxed()
+    }
+
+    fn rebase_todo(&self, base: String) -> BoxFuture<'_, Result<Vec<RebaseTodoEntry>>> {
+        let git_binary = self.git_binary();
+        self.executor
+            .spawn(async move {
+                let git = git_binary?;
+                // The parent of a root commit doesn't resolve, so there is nothing to rebase onto.
+                git.run(&[
+                    "rev-parse",
+                    "--verify",
+                    "--quiet",
+                    &format!("{base}^{{commit}}"),
+                ])
+                .await
+                .with_context(|| {
+                    format!("{base} is not a commit to rebase onto, root commits have no parent")
+                })?;
+                anyhow::ensure!(
+                    is_ancestor(&git, &base, "HEAD").await?,
+                    "{base} is not an ancestor of the current branch"
+                );
+
+                let output = git
+                    .run(&[
+                        "log",
+                        "--reverse",
+                        "--no-merges",
+                        "--format=%H%x00%s",
+                        &format!("{base}..HEAD"),
+                    ])
+                    .await?;
+                output
+                    .lines()
+                    .filter(|line| !line.is_empty())
+                    .map(|line| {
+                        let (sha, subject) = line
+                            .split_once('\0')
+                            .with_context(|| format!("unexpected git log output: {line:?}"))?;
+                        Ok(RebaseTodoEntry {
+                            action: RebaseTodoAction::Pick,
+                            sha: sha.to_string().into(),
+                            subject: subject.to_string().into(),
+                            message: None,
+                        })
+                    })
+                    .collect()
+            })
+            .boxed()
+    }
+
+    fn rebase(
+        &self,
+        base: String,
+        todo: Vec<RebaseTodoEntry>,
+        env: Arc<HashMap<String, String>>,
+    ) -> BoxFuture<'_, Result<()>> {
+        let git_binary = self.git_binary();
+        let git_dir = self.path();
+        let state_dir = git_dir.join(REBASE_STATE_DIR);
+        self.executor
+            .spawn(async move {
+                let git = git_binary?;
+                anyhow::ensure!(!todo.is_empty(), "Nothing to rebase");
+
+                // Messages of reworded commits have to outlive the command, in case the rebase
+                // stops on a conflict and gets continued later.
+                smol::fs::remove_dir_all(&state_dir).await.ok();
+                smol::fs::create_dir_all(&state_dir).await?;
+                let mut script = String::new();
+                for (ix, entry) in todo.iter().enumerate() {
+                    match (entry.action, &entry.message) {
+                        (RebaseTodoAction::Reword, Some(message)) => {
+                            let message_path = state_dir.join(format!("message-{ix}"));
+                            smol::fs::write(&message_path, message.as_bytes()).await?;
+                            script.push_str(&format!("pick {} {}\n", entry.sha, entry.subject));
+                            script.push_str(&format!(
+                                "exec git commit --amend --only --allow-empty --no-verify -F {}\n",
+                                quote_for_git_shell(&message_path)?
+                            ));
+                        }
+                        (action, _) => {
+                            script.push_str(&format!(
+                                "{} {} {}\n",
+                                action.as_str(),
+                                entry.sha,
+                                entry.subject
+                            ));
+                        }
+                    }
+                }
+                let todo_path = state_dir.join("git-rebase-todo");
+                smol::fs::write(&todo_path, script).await?;
+
+                let output = git
+                    .build_command(["rebase", "--interactive", &base])
+                    .envs(env.iter())
+                    .env(
+                        "GIT_SEQUENCE_EDITOR",
+                        format!("cp {}", quote_for_git_shell(&todo_path)?),
+                    )
+                    .env("GIT_EDITOR", "true")
+                    .output()
+                    .await?;
+                remove_rebase_state_unless_in_progress(&git_dir).await;
+                anyhow::ensure!(
+                    output.status.success(),
+                    "Failed to rebase:\n{}",
+                    String::from_utf8_lossy(&output.stderr),
+                );
+                Ok(())
+            })
+            .boxed()
+    }
+
+    fn cherry_pick(
+        &self,
+        commits: Vec<String>,
+        env: Arc<HashMap<String, String>>,
+    ) -> BoxFuture<'_, Result<()>> {
+        let git_binary = self.git_binary();
+        self.executor
+            .spawn(async move {
+                let output = git_binary?
+                    .build_command(["cherry-pick"])
+                    .args(&commits)
+                    .envs(env.iter())
+                    .output()
+                    .await?;
+                anyhow::ensure!(
+                    output.status.success(),
+                    "Failed to cherry-pick:\n{}",
+                    String::from_utf8_lossy(&output.stderr),
+                );
+                Ok(())
+            })
+            .boxed()
+    }
+
+    fn revert(
+        &self,
+        commits: Vec<String>,
+        env: Arc<HashMap<String, String>>,
+    ) -> BoxFuture<'_, Result<()>> {
+        let git_binary = self.git_binary();
+        self.executor
+            .spawn(async move {
+                let output = git_binary?
+                    .build_command(["revert", "--no-edit"])
+                    .args(&commits)
+                    .envs(env.iter())
+                    .output()
+                    .await?;
+                anyhow::ensure!(
+                    output.status.success(),
+                    "Failed to revert:\n{}",
+                    String::from_utf8_lossy(&output.stderr),
+                );
+                Ok(())
+            })
+            .boxed()
+    }
+
+    fn continue_operation(&self, env: Arc<HashMap<String, String>>) -> BoxFuture<'_, Result<()>> {
+        let git_binary = self.git_binary();
+        let git_dir = self.path();
+        self.executor
+            .spawn(async move {
+                let operation = SequencerOperation::in_progress(&git_dir)
+                    .context("No rebase, cherry-pick or revert in progress")?;
+                let output = git_binary?
+                    .build_command([operation.command(), "--continue"])
+                    .envs(env.iter())
+                    .env("GIT_EDITOR", "true")
+                    .output()
+                    .await?;
+                remove_rebase_state_unless_in_progress(&git_dir).await;
+                anyhow::ensure!(
+                    output.status.success(),
+                    "Failed to continue {}:\n{}",
+                    operation.command(),
+                    String::from_utf8_lossy(&output.stderr),
+                );
+                Ok(())
+            })
+            .boxed()
+    }
+
+    fn abort_operation(&self, env: Arc<HashMap<String, String>>) -> BoxFuture<'_, Result<()>> {
+        let git_binary = self.git_binary();
+        let git_dir = self.path();
+        self.executor
+            .spawn(async move {
+                let operation = SequencerOperation::in_progress(&git_dir)
+                    .context("No rebase, cherry-pick or revert in progress")?;
+                let output = git_binary?
+                    .build_command([operation.command(), "--abort"])
+                    .envs(env.iter())
+                    .output()
+                    .await?;
+                remove_rebase_state_unless_in_progress(&git_dir).await;
+                anyhow::ensure!(
+                    output.status.success(),
+                    "Failed to abort {}:\n{}",
+                    operation.command(),
+                    String::from_utf8_lossy(&output.stderr),
+                );
+                Ok(())
+            })
+            .boxed()
+    }
+
+    fn bisect_start(
+        &self,
+        bad: Option<String>,
+        good: Vec<String>,
+        env: Arc<HashMap<String, String>>,
+    ) -> BoxFuture<'_, Result<()>> {
+        let git_binary = self.git_binary();
+        self.executor
+            .spawn(async move {
+                let mut command = git_binary?.build_command(["bisect", "start"]);
+                if bad.is_some() || !good.is_empty() {
+                    command.arg(bad.as_deref().unwrap_or("HEAD")).args(&good);
+                }
+                let output = command.arg("--").envs(env.iter()).output().await?;
+                anyhow::ensure!(
+                    output.status.success(),
+                    "Failed to start bisect:\n{}",
+                    String::from_utf8_lossy(&output.stderr),
+                );
+                Ok(())
+            })
+            .boxed()
+    }
+
+    fn bisect_mark(
+        &self,
+        mark: BisectMark,
+        commit: Option<String>,
+        env: Arc<HashMap<String, String>>,
+    ) -> BoxFuture<'_, Result<()>> {
+        let git_binary = self.git_binary();
+        self.executor
+            .spawn(async move {
+                let output = git_binary?
+                    .build_command(["bisect", mark.as_str()])
+                    .args(commit)
+                    .envs(env.iter())
+                    .output()
+                    .await?;
+                anyhow::ensure!(
+                    output.status.success(),
+                    "Failed to mark commit as {}:\n{}",
+                    mark.as_str(),
+                    String::from_utf8_lossy(&output.stderr),
+                );
+                Ok(())
+            })
+            .boxed()
+    }
+
+    fn bisect_reset(&self, env: Arc<HashMap<String, String>>) -> BoxFuture<'_, Result<()>> {
+        let git_binary = self.git_binary();
+        self.executor
+            .spawn(async move {
+                let output = git_binary?
+                    .build_command(["bisect", "reset"])
+                    .envs(env.iter())
+                    .output()
+                    .await?;
+                anyhow::ensure!(
+                    output.status.success(),
+                    "Failed to reset bisect:\n{}",
+                    String::from_utf8_lossy(&output.stderr),
+                );
+                Ok(())
+            })
+            .boxed()
+    }
+
+    fn bisect_log(&self) -> BoxFuture<'_, Result<Option<BisectLog>>> {
+        let git_binary = self.git_binary();
+        let git_dir = self.path();
+        self.executor
+            .spawn(async move {
+                let log = match std::fs::read_to_string(git_dir.join("BISECT_LOG")) {
+                    Ok(log) => log,
+                    Err(error) if error.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+                    Err(error) => return Err(error.into()),
+                };
+                let mut bisect = parse_bisect_log(&log);
+                if let Some(bad) = &bisect.bad
+                    && !bisect.good.is_empty()
+                    && bisect.first_bad.is_none()
+                {
+                    let mut args = vec!["rev-list", "--bisect-vars", bad.as_ref(), "--not"];
+                    args.extend(bisect.good.iter().map(|sha| sha.as_ref()));
+                    let output = git_binary?.run(&args).await?;
+                    (bisect.remaining, bisect.steps) = parse_bisect_vars(&output);
+                }
+                Ok(Some(bisect))
+            })
+            .boxed()
+    }
+
     fn load_index_text(&self, path: RepoPath) -> BoxFuture<'_, Option<String>> {
         // https://git-scm.com/book/en/v2/Git-Internals-Git-Objects
         const GIT_MODE_SYMLINK: u32 = 0o120000;
@@ -1649,33 +2209,198 @@ impl GitRepository for RealGitRepository {
 
                 anyhow::ensure!(
                     output.status.success(),
-                    "Failed to git git branches:\n{}",
-                    String::from_utf8_lossy(&output.stderr)
+                    "Failed to git git branches:\n{}",
+                    String::from_utf8_lossy(&output.stderr)
+                );
+
+                let input = String::from_utf8_lossy(&output.stdout);
+
+                let mut branches = parse_branch_input(&input)?;
+                if branches.is_empty() {
+                    let args = vec!["symbolic-ref", "--quiet", "HEAD"];
+
+                    let output = git.build_command(args).output().await?;
+
+                    // git symbolic-ref returns a non-0 exit code if HEAD points
+                    // to something other than a branch
+                    if output.status.success() {
+                        let name = String::from_utf8_lossy(&output.stdout).trim().to_string();
+
+                        branches.push(Branch {
+                            ref_name: name.into(),
+                            is_head: true,
+                            upstream: None,
+                            most_recent_commit: None,
+                        });
+                    }
+                }
+
+                Ok(branches)
+            })
+            .boxed()
+    }
+
+    fn reflog(&self, ref_name: String, limit: usize) -> BoxFuture<'_, Result<Vec<ReflogEntry>>> {
+        let git_binary = self.git_binary();
+        self.executor
+            .spawn(async move {
+                // With `--date`, the selector holds the time of the entry instead of its index.
+                let output = git_binary?
+                    .build_command(["reflog", "show", "--date=unix", "--format=%gd%x00%H%x00%gs"])
+                    .arg(format!("--max-count={limit}"))
+                    .arg(&ref_name)
+                    .arg("--")
+                    .output()
+                    .await?;
+                anyhow::ensure!(
+                    output.status.success(),
+                    "Failed to read the reflog of {ref_name}:\n{}",
+                    String::from_utf8_lossy(&output.stderr),
+                );
+                parse_reflog(&String::from_utf8_lossy(&output.stdout))
+            })
+            .boxed()
+    }
+
+    fn submodules(&self) -> BoxFuture<'_, Result<Vec<Submodule>>> {
+        let git_binary = self.git_binary();
+        let working_directory = self.working_directory();
+        self.executor
+            .spawn(async move {
+                let working_directory = working_directory?;
+                if !working_directory.join(".gitmodules").exists() {
+                    return Ok(Vec::new());
+                }
+                let git = git_binary?;
+
+                // `git config` exits with 1 when no key matches.
+                let output = git
+                    .build_command([
+                        "config",
+                        "--file",
+                        ".gitmodules",
+                        "--null",
+                        "--get-regexp",
+                        r"^submodule\..*\.path$",
+                    ])
+                    .output()
+                    .await?;
+                anyhow::ensure!(
+                    output.status.success() || output.status.code() == Some(1),
+                    "Failed to read .gitmodules:\n{}",
+                    String::from_utf8_lossy(&output.stderr),
+                );
+                let paths = String::from_utf8_lossy(&output.stdout)
+                    .split('\0')
+                    .filter_map(|entry| Some(entry.split_once('\n')?.1.to_string()))
+                    .collect::<Vec<_>>();
+                if paths.is_empty() {
+                    return Ok(Vec::new());
+                }
+
+                let index = git
+                    .build_command(["ls-files", "--stage", "-z", "--"])
+                    .args(&paths)
+                    .output()
+                    .await?;
+                anyhow::ensure!(
+                    index.status.success(),
+                    "Failed to list submodules:\n{}",
+                    String::from_utf8_lossy(&index.stderr),
+                );
+                let mut submodules =
+                    parse_submodule_index(&String::from_utf8_lossy(&index.stdout))?;
+                for submodule in &mut submodules {
+                    submodule.is_initialized = working_directory
+                        .join(submodule.path.as_std_path())
+                        .join(".git")
+                        .exists();
+                }
+
+                let status = git
+                    .build_command([
+                        "--no-optional-locks",
+                        "status",
+                        "--porcelain=v2",
+                        "--untracked-files=normal",
+                        "--no-renames",
+                        "-z",
+                        "--",
+                    ])
+                    .args(&paths)
+                    .output()
+                    .await?;
+                anyhow::ensure!(
+                    status.status.success(),
+                    "Failed to get submodule status:\n{}",
+                    String::from_utf8_lossy(&status.stderr),
+                );
+                apply_submodule_status(&String::from_utf8_lossy(&status.stdout), &mut submodules);
+                Ok(submodules)
+            })
+            .boxed()
+    }
+
+    fn update_submodules(
+        &self,
+        paths: Vec<RepoPath>,
+        init: bool,
+        ask_pass: AskPassDelegate,
+        env: Arc<HashMap<String, String>>,
+        cx: AsyncApp,
+    ) -> BoxFuture<'_, Result<RemoteCommandOutput>> {
+        let working_directory = self.working_directory();
+        let git_binary_path = self.system_git_binary_path.clone();
+        let executor = cx.background_executor().clone();
+        let is_trusted = self.is_trusted();
+        // Note: Do not spawn this command on the background thread, cloning a submodule
+        // might pop open the credential helper which we want to block on.
+        async move {
+            let git_binary_path =
+                git_binary_path.context("git not found on $PATH, can't update submodules")?;
+            let working_directory = working_directory?;
+            let git = GitBinary::new(
+                git_binary_path,
+                working_directory,
+                executor.clone(),
+                is_trusted,
+            );
+            let mut command = git.build_command(["submodule", "update"]);
+            if init {
+                command.arg("--init");
+            }
+            command
+                .arg("--")
+                .args(paths.iter().map(|path| path.as_unix_str()))
+                .envs(env.iter())
+                .stdout(Stdio::piped())
+                .stderr(Stdio::piped());
+
+            run_git_command(env, ask_pass, command, executor).await
+        }
+        .boxed()
+    }
+
+    fn sync_submodules(
+        &self,
+        paths: Vec<RepoPath>,
+        env: Arc<HashMap<String, String>>,
+    ) -> BoxFuture<'_, Result<()>> {
+        let git_binary = self.git_binary();
+        self.executor
+            .spawn(async move {
+                let output = git_binary?
+                    .build_command(["submodule", "sync", "--"])
+                    .args(paths.iter().map(|path| path.as_unix_str()))
+                    .envs(env.iter())
+                    .output()
+                    .await?;
+                anyhow::ensure!(
+                    output.status.success(),
+                    "Failed to sync submodules:\n{}",
+                    String::from_utf8_lossy(&output.stderr),
                 );
-
-                let input = String::from_utf8_lossy(&output.stdout);
-
-                let mut branches = parse_branch_input(&input)?;
-                if branches.is_empty() {
-                    let args = vec!["symbolic-ref", "--quiet", "HEAD"];
-
-                    let output = git.build_command(args).output().await?;
-
-                    // git symbolic-ref returns a non-0 exit code if HEAD points
-                    // to something other than a branch
-                    if output.status.success() {
-                        let name = String::from_utf8_lossy(&output.stdout).trim().to_string();
-
-                        branches.push(Branch {
-                            ref_name: name.into(),
-                            is_head: true,
-                            upstream: None,
-                            most_recent_commit: None,
-                        });
-                    }
-                }
-
-                Ok(branches)
+                Ok(())
             })
             .boxed()
     }
@@ -1867,6 +2592,81 @@ impl GitRepository for RealGitRepository {
             .boxed()
     }
 
+    fn tags(&self) -> BoxFuture<'_, Result<Vec<Tag>>> {
+        let git_binary = self.git_binary();
+        self.executor
+            .spawn(async move {
+                let fields = [
+                    "%(refname:strip=2)",
+                    "%(objecttype)",
+                    "%(objectname)",
+                    "%(*objectname)",
+                    "%(contents:subject)",
+                ]
+                .join("%00");
+                let output = git_binary?
+                    .run(&[
+                        "for-each-ref",
+                        "--sort=-creatordate",
+                        "--format",
+                        &fields,
+                        "refs/tags",
+                    ])
+                    .await?;
+                parse_tag_input(&output)
+            })
+            .boxed()
+    }
+
+    fn create_tag(
+        &self,
+        name: String,
+        target: Option<String>,
+        message: Option<String>,
+        env: Arc<HashMap<String, String>>,
+    ) -> BoxFuture<'_, Result<()>> {
+        let git_binary = self.git_binary();
+        self.executor
+            .spawn(async move {
+                let mut command = git_binary?.build_command(["tag"]);
+                if let Some(message) = &message {
+                    command.args(["--annotate", "--message", message.as_str()]);
+                }
+                command.arg("--").arg(&name).args(target);
+                let output = command.envs(env.iter()).output().await?;
+                anyhow::ensure!(
+                    output.status.success(),
+                    "Failed to create tag:\n{}",
+                    String::from_utf8_lossy(&output.stderr),
+                );
+                Ok(())
+            })
+            .boxed()
+    }
+
+    fn delete_tag(
+        &self,
+        name: String,
+        env: Arc<HashMap<String, String>>,
+    ) -> BoxFuture<'_, Result<()>> {
+        let git_binary = self.git_binary();
+        self.executor
+            .spawn(async move {
+                let output = git_binary?
+                    .build_command(["tag", "--delete", "--", &name])
+                    .envs(env.iter())
+                    .output()
+                    .await?;
+                anyhow::ensure!(
+                    output.status.success(),
+                    "Failed to delete tag:\n{}",
+                    String::from_utf8_lossy(&output.stderr),
+                );
+                Ok(())
+            })
+            .boxed()
+    }
+
     fn blame(
         &self,
         path: RepoPath,
@@ -2977,6 +3777,80 @@ async fn exclude_files(git: &GitBinary) -> Result<GitExcludeOverride> {
     Ok(excludes)
 }
 
+async fn is_ancestor(git: &GitBinary, ancestor: &str, descendant: &str) -> Result<bool> {
+    let output = git
+        .build_command(["merge-base", "--is-ancestor", ancestor, descendant])
+        .output()
+        .await?;
+    match output.status.code() {
+        Some(0) => Ok(true),
+        Some(1) => Ok(false),
+        _ => anyhow::bail!(
+            "Failed to check whether {ancestor} is an ancestor of {descendant}:\n{}",
+            String::from_utf8_lossy(&output.stderr),
+        ),
+    }
+}
+
+/// The directory inside `.git`, holding the files of the last interactive rebase.
+const REBASE_STATE_DIR: &str = "zed-rebase";
+
+/// Removes the files of the last interactive rebase, once it is no longer in progress:
+/// either completed, aborted or failed without stopping on a conflict.
+async fn remove_rebase_state_unless_in_progress(git_dir: &Path) {
+    if SequencerOperation::in_progress(git_dir) != Some(SequencerOperation::Rebase) {
+        smol::fs::remove_dir_all(git_dir.join(REBASE_STATE_DIR))
+            .await
+            .ok();
+    }
+}
+
+/// A git command that applies a sequence of commits and may stop on a conflict.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum SequencerOperation {
+    Rebase,
+    CherryPick,
+    Revert,
+}
+
+impl SequencerOperation {
+    fn in_progress(git_dir: &Path) -> Option<Self> {
+        if git_dir.join("rebase-merge").exists() || git_dir.join("rebase-apply").exists() {
+            Some(Self::Rebase)
+        } else if git_dir.join("CHERRY_PICK_HEAD").exists() {
+            Some(Self::CherryPick)
+        } else if git_dir.join("REVERT_HEAD").exists() {
+            Some(Self::Revert)
+        } else {
+            // A multi-commit cherry-pick or revert stays in progress after its conflict
+            // gets committed manually, until the remaining commits are applied.
+            let todo = std::fs::read_to_string(git_dir.join("sequencer").join("todo")).ok()?;
+            match todo.split_whitespace().next()? {
+                "pick" | "p" => Some(Self::CherryPick),
+                "revert" => Some(Self::Revert),
+                _ => None,
+            }
+        }
+    }
+
+    fn command(&self) -> &'static str {
+        match self {
+            Self::Rebase => "rebase",
+            Self::CherryPick => "cherry-pick",
+            Self::Revert => "revert",
+        }
+    }
+}
+
+/// Quotes a path for the POSIX shell git runs editors and `exec` lines of a rebase with.
+fn quote_for_git_shell(path: &Path) -> Result<String> {
+    let path = path.to_str().context("path is not valid UTF-8")?;
+    Ok(ShellKind::Posix
+        .try_quote(path)
+        .with_context(|| format!("failed to quote {path:?}"))?
+        .into_owned())
+}
+
 pub(crate) struct GitBinary {
     git_binary_path: PathBuf,
     working_directory: PathBuf,
@@ -3273,6 +4147,150 @@ impl MapSeekTarget<RepoPath> for RepoPathDescendants<'_> {
     }
 }
 
+/// Parses the comment lines git writes to `BISECT_LOG`, such as `# good: [<sha>] <subject>`.
+fn parse_bisect_log(input: &str) -> BisectLog {
+    let mut bisect = BisectLog::default();
+    for line in input.lines() {
+        let Some((kind, rest)) = line
+            .strip_prefix("# ")
+            .and_then(|line| line.split_once(": ["))
+        else {
+            continue;
+        };
+        let Some((sha, _subject)) = rest.split_once(']') else {
+            continue;
+        };
+        let sha = SharedString::from(sha.to_string());
+        match kind {
+            "bad" => bisect.bad = Some(sha),
+            "good" => bisect.good.push(sha),
+            "skip" => bisect.skipped.push(sha),
+            "first bad commit" => bisect.first_bad = Some(sha),
+            _ => {}
+        }
+    }
+    bisect
+}
+
+/// Parses the number of commits left and steps left from `git rev-list --bisect-vars`.
+fn parse_bisect_vars(input: &str) -> (Option<u32>, Option<u32>) {
+    let mut remaining = None;
+    let mut steps = None;
+    for line in input.lines() {
+        match line.split_once('=') {
+            Some(("bisect_all", value)) => remaining = value.parse().ok(),
+            Some(("bisect_steps", value)) => steps = value.parse().ok(),
+            _ => {}
+        }
+    }
+    (remaining, steps)
+}
+
+fn parse_reflog(input: &str) -> Result<Vec<ReflogEntry>> {
+    input
+        .lines()
+        .filter(|line| !line.is_empty())
+        .enumerate()
+        .map(|(ix, line)| {
+            let mut fields = line.split('\0');
+            let (Some(selector), Some(sha), Some(message)) =
+                (fields.next(), fields.next(), fields.next())
+            else {
+                anyhow::bail!("unexpected git reflog output: {line:?}");
+            };
+            let (ref_name, timestamp) = selector
+                .strip_suffix('}')
+                .and_then(|selector| selector.rsplit_once("@{"))
+                .with_context(|| format!("unexpected reflog selector: {selector:?}"))?;
+            Ok(ReflogEntry {
+                selector: format!("{ref_name}@{{{ix}}}").into(),
+                sha: sha.to_string().into(),
+                message: message.to_string().into(),
+                timestamp: timestamp.parse()?,
+            })
+        })
+        .collect()
+}
+
+/// Parses the gitlinks out of `git ls-files --stage -z`, skipping the other entries.
+fn parse_submodule_index(input: &str) -> Result<Vec<Submodule>> {
+    let mut submodules = Vec::<Submodule>::new();
+    for entry in input.split('\0').filter(|entry| !entry.is_empty()) {
+        let Some((info, path)) = entry.split_once('\t') else {
+            anyhow::bail!("unexpected git ls-files output: {entry:?}");
+        };
+        let mut info = info.split(' ');
+        let (Some(mode), Some(sha)) = (info.next(), info.next()) else {
+            anyhow::bail!("unexpected git ls-files output: {entry:?}");
+        };
+        if mode != "160000" {
+            continue;
+        }
+        let path = RepoPath::new(path)?;
+        // A conflicted submodule has an entry per stage.
+        if submodules.last().is_some_and(|last| last.path == path) {
+            continue;
+        }
+        submodules.push(Submodule {
+            path,
+            sha: sha.to_string().into(),
+            is_initialized: false,
+            has_new_commits: false,
+            has_modified_content: false,
+            has_untracked_content: false,
+        });
+    }
+    Ok(submodules)
+}
+
+/// Applies the submodule states in the `<sub>` field of `git status --porcelain=v2 -z`
+/// entries, such as `SC.U` for a submodule with new commits and untracked content.
+fn apply_submodule_status(input: &str, submodules: &mut [Submodule]) {
+    for entry in input.split('\0') {
+        let mut fields = entry.splitn(9, ' ');
+        if fields.next() != Some("1") {
+            continue;
+        }
+        let (Some(state), Some(path)) = (fields.nth(1), fields.nth(5)) else {
+            continue;
+        };
+        let Some(state) = state.strip_prefix('S') else {
+            continue;
+        };
+        let Some(submodule) = submodules
+            .iter_mut()
+            .find(|submodule| submodule.path.as_unix_str() == path)
+        else {
+            continue;
+        };
+        let state = state.as_bytes();
+        submodule.has_new_commits = state.first() == Some(&b'C');
+        submodule.has_modified_content = state.get(1) == Some(&b'M');
+        submodule.has_untracked_content = state.get(2) == Some(&b'U');
+    }
+}
+
+fn parse_tag_input(input: &str) -> Result<Vec<Tag>> {
+    input
+        .lines()
+        .filter(|line| !line.is_empty())
+        .map(|line| {
+            let fields = line.split('\x00').collect::<Vec<_>>();
+            let [name, object_type, sha, peeled_sha, subject] = fields.as_slice() else {
+                anyhow::bail!("unexpected git for-each-ref output: {line:?}");
+            };
+            let is_annotated = *object_type == "tag";
+            let sha = if is_annotated { peeled_sha } else { sha };
+            Ok(Tag {
+                name: name.to_string().into(),
+                sha: sha.to_string().into(),
+                annotation: (is_annotated && !subject.is_empty())
+                    .then(|| subject.to_string().into()),
+            })
+        })
+        .collect()
+}
+
 fn parse_branch_input(input: &str) -> Result<Vec<Branch>> {
     let mut branches = Vec::new();
     for line in input.split('\n') {
@@ -3720,10 +4738,110 @@ mod tests {
         );
     }
 
+    #[gpui::test]
+    async fn test_interactive_rebase(cx: &mut TestAppContext) {
+        disable_git_global_config();
+
+        cx.executor().allow_parking();
+
+        let repo_dir = tempfile::tempdir().unwrap();
+        git2::Repository::init(repo_dir.path()).unwrap();
+        let repo = RealGitRepository::new(
+            &repo_dir.path().join(".git"),
+            None,
+            Some("git".into()),
+            cx.executor(),
+        )
+        .unwrap();
+        let env = Arc::new(checkpoint_author_envs());
+
+        let mut base = None;
+        for name in ["init", "a", "b", "c"] {
+            smol::fs::write(repo_dir.path().join(name), name)
+                .await
+                .unwrap();
+            repo.stage_paths(vec![repo_path(name)], env.clone())
+                .await
+                .unwrap();
+            repo.commit(
+                format!("Add {name}").into(),
+                None,
+                CommitOptions::default(),
+                AskPassDelegate::new(&mut cx.to_async(), |_, _, _| {}),
+                env.clone(),
+            )
+            .await
+            .unwrap();
+            base.get_or_insert(repo.head_sha().await.unwrap());
+        }
+        let base = base.unwrap();
+
+        let todo = repo.rebase_todo(base.clone()).await.unwrap();
+        assert_eq!(
+            todo.iter()
+                .map(|entry| (entry.action, entry.subject.as_ref()))
+                .collect::<Vec<_>>(),
+            [
+                (RebaseTodoAction::Pick, "Add a"),
+                (RebaseTodoAction::Pick, "Add b"),
+                (RebaseTodoAction::Pick, "Add c"),
+            ]
+        );
+
+        let [a, b, c] = <[RebaseTodoEntry; 3]>::try_from(todo).unwrap();
+        let dropped_sha = b.sha.to_string();
+        let todo = vec![
+            RebaseTodoEntry {
+                action: RebaseTodoAction::Reword,
+                message: Some("Add c, reworded\n\nWith a body".into()),
+                ..c
+            },
+            a,
+            RebaseTodoEntry {
+                action: RebaseTodoAction::Drop,
+                ..b
+            },
+        ];
+        repo.rebase(base.clone(), todo, env.clone()).await.unwrap();
+
+        assert!(
+            !repo_dir.path().join(".git").join(REBASE_STATE_DIR).exists(),
+            "the rebase files should be removed once it completes"
+        );
+
+        let rebased = repo.rebase_todo(base.clone()).await.unwrap();
+        assert_eq!(
+            rebased
+                .iter()
+                .map(|entry| entry.subject.as_ref())
+                .collect::<Vec<_>>(),
+            ["Add c, reworded", "Add a"]
+        );
+        assert_eq!(
+            repo.show(rebased[0].sha.to_string())
+                .await
+                .unwrap()
+                .message
+                .trim_end(),
+            "Add c, reworded\n\nWith a body"
+        );
+        assert!(!repo_dir.path().join("b").exists());
+
+        // Neither a dropped commit nor the parent of the root commit can be rebased onto.
+        assert!(repo.is_ancestor(base.clone(), "HEAD".into()).await.unwrap());
+        assert!(
+            !repo
+                .is_ancestor(dropped_sha.clone(), "HEAD".into())
+                .await
+                .unwrap()
+        );
+        assert!(repo.rebase_todo(dropped_sha).await.is_err());
+        assert!(repo.rebase_todo(format!("{base}^")).await.is_err());
+    }
+
     #[test]
     fn test_branches_parsing() {
         // suppress "help: octal escapes are not supported, `\0` is always null"
-        #[allow(clippy::octal_escapes)]
         let input = "*\0060964da10574cd9bf06463a53bf6e0769c5c45e\0\0refs/heads/zed-patches\0refs/remotes/origin/zed-patches\0\01733187470\0John Doe\0generated protobuf\n";
         assert_eq!(
             parse_branch_input(input).unwrap(),
@@ -3748,9 +4866,126 @@ mod tests {
         )
     }
 
+    #[test]
+    fn test_tags_parsing() {
+        let input = "v1.1\0tag\0a9c4bc61a0e1b5b8b4e1ee2ee3f39db1c5a2d2ef\0060964da10574cd9bf06463a53bf6e0769c5c45e\0Release 1.1\n\
+                     v1.0\0commit\0f1b2e6d3c7a8e9f0d1c2b3a4e5f6a7b8c9d0e1f2\0\0Fix the build\n";
+        assert_eq!(
+            parse_tag_input(input).unwrap(),
+            vec![
+                Tag {
+                    name: "v1.1".into(),
+                    sha: "060964da10574cd9bf06463a53bf6e0769c5c45e".into(),
+                    annotation: Some("Release 1.1".into()),
+                },
+                Tag {
+                    name: "v1.0".into(),
+                    sha: "f1b2e6d3c7a8e9f0d1c2b3a4e5f6a7b8c9d0e1f2".into(),
+                    annotation: None,
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_reflog_parsing() {
+        let input = "HEAD@{1792197897}\0b8339ad334c1f97f075d23921c91bba303d948af\0commit (amend): Fix typo\n\
+                     HEAD@{1792197800}\0f2c3eb6d06943d7f5760af2e721519c348cbfda9\0reset: moving to HEAD~1\n";
+        let entries = parse_reflog(input).unwrap();
+        assert_eq!(
+            entries,
+            vec![
+                ReflogEntry {
+                    selector: "HEAD@{0}".into(),
+                    sha: "b8339ad334c1f97f075d23921c91bba303d948af".into(),
+                    message: "commit (amend): Fix typo".into(),
+                    timestamp: 1792197897,
+                },
+                ReflogEntry {
+                    selector: "HEAD@{1}".into(),
+                    sha: "f2c3eb6d06943d7f5760af2e721519c348cbfda9".into(),
+                    message: "reset: moving to HEAD~1".into(),
+                    timestamp: 1792197800,
+                },
+            ]
+        );
+        assert_eq!(entries[0].command(), "commit (amend)");
+        assert_eq!(entries[1].command(), "reset");
+    }
+
+    #[test]
+    fn test_submodules_parsing() {
+        let index = "100644 72ea43907c8c5d33169b1eab4b5b6e6d34f64140 0\t.gitmodules\0\
+                     160000 be79fd1f3846dcbf9411281c2de86968fd5ff063 0\tother\0\
+                     160000 be79fd1f3846dcbf9411281c2de86968fd5ff063 0\tvendor/lib\0";
+        let mut submodules = parse_submodule_index(index).unwrap();
+        let status = "1 .M S.M. 160000 160000 160000 be79fd1f3846dcbf9411281c2de86968fd5ff063 be79fd1f3846dcbf9411281c2de86968fd5ff063 other\0\
+                      1 .M SC.U 160000 160000 160000 be79fd1f3846dcbf9411281c2de86968fd5ff063 be79fd1f3846dcbf9411281c2de86968fd5ff063 vendor/lib\0";
+        apply_submodule_status(status, &mut submodules);
+        assert_eq!(
+            submodules,
+            vec![
+                Submodule {
+                    path: RepoPath::new("other").unwrap(),
+                    sha: "be79fd1f3846dcbf9411281c2de86968fd5ff063".into(),
+                    is_initialized: false,
+                    has_new_commits: false,
+                    has_modified_content: true,
+                    has_untracked_content: false,
+                },
+                Submodule {
+                    path: RepoPath::new("vendor/lib").unwrap(),
+                    sha: "be79fd1f3846dcbf9411281c2de86968fd5ff063".into(),
+                    is_initialized: false,
+                    has_new_commits: true,
+                    has_modified_content: false,
+                    has_untracked_content: true,
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_bisect_log_parsing() {
+        let log = "\
+            git bisect start\n\
+            # status: waiting for both good and bad commits\n\
+            # bad: [4d0278c8f6b6ec66bd6193ebe0202424c6ffa9b3] c8\n\
+            # good: [3a2201517a63b9967270c17cf3c072f6eb7d1c10] c2\n\
+            git bisect start 'HEAD' 'HEAD~6'\n\
+            # skip: [34a251433b38f61aa0bd5dcb68398dd3f5b28c9c] c5\n\
+            git bisect skip 34a251433b38f61aa0bd5dcb68398dd3f5b28c9c\n\
+            # good: [e92933607264cbf72d491eb36554ad3904a761ff] c6\n\
+            git bisect good e92933607264cbf72d491eb36554ad3904a761ff\n\
+            # bad: [be48a1f09a62fdcd465627b3fe5b13c533c2567c] c7\n\
+            git bisect bad be48a1f09a62fdcd465627b3fe5b13c533c2567c\n\
+            # first bad commit: [be48a1f09a62fdcd465627b3fe5b13c533c2567c] c7\n";
+        assert_eq!(
+            parse_bisect_log(log),
+            BisectLog {
+                bad: Some("be48a1f09a62fdcd465627b3fe5b13c533c2567c".into()),
+                good: vec![
+                    "3a2201517a63b9967270c17cf3c072f6eb7d1c10".into(),
+                    "e92933607264cbf72d491eb36554ad3904a761ff".into(),
+                ],
+                skipped: vec!["34a251433b38f61aa0bd5dcb68398dd3f5b28c9c".into()],
+                first_bad: Some("be48a1f09a62fdcd465627b3fe5b13c533c2567c".into()),
+                remaining: None,
+                steps: None,
+            }
+        );
+
+        let vars = "bisect_rev='d5049a8f5e7d1405f8f9580a8c900a9973e4d45b'\n\
+                    bisect_nr=2\n\
+                    bisect_good=2\n\
+                    bisect_bad=2\n\
+                    bisect_all=6\n\
+                    bisect_steps=2\n";
+        assert_eq!(parse_bisect_vars(vars), (Some(6), Some(2)));
+    }
+
     #[test]
     fn test_branches_parsing_containing_refs_with_missing_fields() {
-        #[allow(clippy::octal_escapes)]
         let input = " \090012116c03db04344ab10d50348553aa94f1ea0\0refs/heads/broken\n \0eb0cae33272689bd11030822939dd2701c52f81e\0895951d681e5561478c0acdd6905e8aacdfd2249\0refs/heads/dev\0\0\01762948725\0Zed\0Add feature\n*\0895951d681e5561478c0acdd6905e8aacdfd2249\0\0refs/heads/main\0\0\01762948695\0Zed\0Initial commit\n";
 
         let branches = parse_branch_input(input).unwrap();
@@ -3,16 +3,21 @@ use feature_flags::{FeatureFlagAppExt as _, GitGraphFeatureFlag};
 use git::{
     BuildCommitPermalinkParams, GitHostingProviderRegistry, GitRemote, Oid, ParsedGitRemote,
     parse_git_remote_url,
-    repository::{CommitDiff, CommitFile, InitialGraphCommitData, LogOrder, LogSource, RepoPath},
+    repository::{
+        BisectMark, CommitDiff, CommitFile, InitialGraphCommitData, LogOrder, LogSource, RepoPath,
+    },
     status::{FileStatus, StatusCode, TrackedStatus},
 };
-use git_ui::{commit_tooltip::CommitAvatar, commit_view::CommitView, git_status_icon};
+use git_ui::{
+    bisect, commit_tooltip::CommitAvatar, commit_view::CommitView, git_status_icon,
+    interactive_rebase::InteractiveRebaseModal, tag_picker::CreateTagModal,
+};
 use gpui::{
-    AnyElement, App, Bounds, ClickEvent, ClipboardItem, Corner, DefiniteLength, DragMoveEvent,
-    ElementId, Empty, Entity, EventEmitter, FocusHandle, Focusable, Hsla, PathBuilder, Pixels,
-    Point, ScrollStrategy, ScrollWheelEvent, SharedString, Subscription, Task,
-    UniformListScrollHandle, WeakEntity, Window, actions, anchored, deferred, point, prelude::*,
-    px, uniform_list,
+    AnyElement, App, Bounds, ClickEvent, ClipboardItem, Corner, DefiniteLength, DismissEvent,
+    DragMoveEvent, ElementId, Empty, Entity, EventEmitter, FocusHandle, Focusable, Hsla,
+    MouseButton, MouseDownEvent, PathBuilder, Pixels, Point, ScrollStrategy, ScrollWheelEvent,
+    SharedString, Subscription, Task, UniformListScrollHandle, WeakEntity, Window, actions,
+    anchored, deferred, point, prelude::*, px, uniform_list,
 };
 use language::line_diff;
 use menu::{Cancel, SelectNext, SelectPrevious};
@@ -36,13 +41,14 @@ use std::{
 use theme::{AccentColors, ThemeSettings};
 use time::{OffsetDateTime, UtcOffset, format_description::BorrowedFormatItem};
 use ui::{
-    ButtonLike, Chip, CommonAnimationExt as _, ContextMenu, DiffStat, Divider, ScrollableHandle,
-    Table, TableColumnWidths, TableInteractionState, TableResizeBehavior, Tooltip, WithScrollbar,
-    prelude::*,
+    ButtonLike, Chip, CommonAnimationExt as _, ContextMenu, ContextMenuEntry, DiffStat, Divider,
+    ScrollableHandle, Table, TableColumnWidths, TableInteractionState, TableResizeBehavior,
+    Tooltip, WithScrollbar, prelude::*,
 };
 use workspace::{
     Workspace,
     item::{Item, ItemEvent, SerializableItem, TabTooltipContent},
+    notifications::DetachAndPromptErr,
 };
 
 const COMMIT_CIRCLE_RADIUS: Pixels = px(3.5);
@@ -1034,6 +1040,7 @@ impl GitGraph {
                     cx.notify();
                 }
             }
+            RepositoryEvent::BisectChanged => cx.notify(),
             RepositoryEvent::GraphEvent(_, _) => {}
             _ => {}
         }
@@ -1057,11 +1064,41 @@ impl GitGraph {
             .and_then(|repo_id| project.repositories(cx).get(&repo_id).cloned())
     }
 
-    fn render_chip(&self, name: &SharedString, accent_color: gpui::Hsla) -> impl IntoElement {
-        Chip::new(name.clone())
+    fn render_chip(
+        &self,
+        name: &SharedString,
+        accent_color: gpui::Hsla,
+        cx: &App,
+    ) -> impl IntoElement {
+        // Tags come prefixed in the ref names, draw them apart from the branches.
+        let tag_name = name.strip_prefix("tag: ");
+        let (label, accent_color) = match tag_name {
+            Some(tag_name) => (
+                SharedString::from(format!("# {tag_name}")),
+                cx.theme().status().info,
+            ),
+            None => (name.clone(), accent_color),
+        };
+        Chip::new(label)
             .label_size(LabelSize::Small)
             .bg_color(accent_color.opacity(0.1))
             .border_color(accent_color.opacity(0.5))
+            .when_some(tag_name, |chip, tag_name| {
+                chip.tooltip(Tooltip::text(format!("Tag {tag_name}")))
+            })
+    }
+
+    fn render_bisect_chip(&self, mark: BisectMark, cx: &App) -> impl IntoElement {
+        let (label, color) = match mark {
+            BisectMark::Good => ("good", cx.theme().status().success),
+            BisectMark::Bad => ("bad", cx.theme().status().error),
+            BisectMark::Skip => ("skipped", cx.theme().status().ignored),
+        };
+        Chip::new(label)
+            .label_size(LabelSize::Small)
+            .bg_color(color.opacity(0.1))
+            .border_color(color.opacity(0.5))
+            .tooltip(Tooltip::text(format!("Bisect: marked as {label}")))
     }
 
     fn render_table_rows(
@@ -1073,6 +1110,9 @@ impl GitGraph {
         let repository = self.get_selected_repository(cx);
 
         let row_height = self.row_height;
+        let bisect = repository
+            .as_ref()
+            .and_then(|repository| repository.read(cx).bisect.clone());
 
         // We fetch data outside the visible viewport to avoid loading entries when
         // users scroll through the git graph
@@ -1107,6 +1147,9 @@ impl GitGraph {
                 });
 
                 let short_sha = commit.data.sha.display_short();
+                let bisect_mark = bisect
+                    .as_ref()
+                    .and_then(|bisect| bisect.mark_of(&commit.data.sha.to_string()));
                 let mut formatted_time = String::new();
                 let subject: SharedString;
                 let author_name: SharedString;
@@ -1144,13 +1187,14 @@ impl GitGraph {
                             h_flex()
                                 .gap_2()
                                 .overflow_hidden()
+                                .children(bisect_mark.map(|mark| self.render_bisect_chip(mark, cx)))
                                 .children((!commit.data.ref_names.is_empty()).then(|| {
                                     h_flex().gap_1().children(
                                         commit
                                             .data
                                             .ref_names
                                             .iter()
-                                            .map(|name| self.render_chip(name, accent_color)),
+                                            .map(|name| self.render_chip(name, accent_color, cx)),
                                     )
                                 }))
                                 .child(column_label(subject)),
@@ -1289,6 +1333,216 @@ impl GitGraph {
         );
     }
 
+    fn deploy_context_menu(
+        &mut self,
+        position: Point<Pixels>,
+        entry_index: usize,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let Some(commit) = self.graph_data.commits.get(entry_index) else {
+            return;
+        };
+        let sha = commit.data.sha.to_string();
+        // Root commits have no parent to rebase onto. Whether the commit is on the current
+        // branch is checked by git once it's picked.
+        let can_rebase_from = !commit.data.parents.is_empty();
+        let is_read_only = self.project.read(cx).is_read_only(cx);
+        let is_bisecting = self
+            .get_selected_repository(cx)
+            .is_some_and(|repository| repository.read(cx).bisect.is_some());
+        self.select_entry(entry_index, cx);
+
+        let this = cx.weak_entity();
+        let context_menu = ContextMenu::build(window, cx, |context_menu, _, _| {
+            context_menu
+                .context(self.focus_handle.clone())
+                .action("Open Commit", OpenCommitView.boxed_clone())
+                .entry("Copy SHA", None, {
+                    let sha = sha.clone();
+                    move |_, cx| cx.write_to_clipboard(ClipboardItem::new_string(sha.clone()))
+                })
+                .when(!is_read_only, |context_menu| {
+                    context_menu
+                        .separator()
+                        .entry("Cherry-Pick", None, {
+                            let this = this.clone();
+                            let sha = sha.clone();
+                            move |window, cx| {
+                                this.update(cx, |this, cx| {
+                                    this.cherry_pick(sha.clone(), window, cx)
+                                })
+                                .ok();
+                            }
+                        })
+                        .entry("Revert", None, {
+                            let this = this.clone();
+                            let sha = sha.clone();
+                            move |window, cx| {
+                                this.update(cx, |this, cx| this.revert(sha.clone(), window, cx))
+                                    .ok();
+                            }
+                        })
+                        .entry("Create Tag…", None, {
+                            let this = this.clone();
+                            let sha = sha.clone();
+                            move |window, cx| {
+                                this.update(cx, |this, cx| {
+                                    this.create_tag(sha.clone(), window, cx)
+                                })
+                                .ok();
+                            }
+                        })
+                        .item(
+                            ContextMenuEntry::new("Interactive Rebase from Here")
+                                .disabled(!can_rebase_from)
+                                .handler({
+                                    let this = this.clone();
+                                    let sha = sha.clone();
+                                    move |window, cx| {
+                                        this.update(cx, |this, cx| {
+                                            this.interactive_rebase(sha.clone(), window, cx)
+                                        })
+                                        .ok();
+                                    }
+                                }),
+                        )
+                        .separator()
+                        .map(|context_menu| {
+                            if is_bisecting {
+                                [
+                                    ("Mark as Good", BisectMark::Good),
+                                    ("Mark as Bad", BisectMark::Bad),
+                                    ("Skip in Bisect", BisectMark::Skip),
+                                ]
+                                .into_iter()
+                                .fold(
+                                    context_menu,
+                                    |context_menu, (label, mark)| {
+                                        context_menu.entry(label, None, {
+                                            let this = this.clone();
+                                            let sha = sha.clone();
+                                            move |window, cx| {
+                                                this.update(cx, |this, cx| {
+                                                    this.bisect_mark(mark, sha.clone(), window, cx)
+                                                })
+                                                .ok();
+                                            }
+                                        })
+                                    },
+                                )
+                            } else {
+                                context_menu.entry("Start Bisect from Here", None, {
+                                    let this = this.clone();
+                                    let sha = sha.clone();
+                                    move |window, cx| {
+                                        this.update(cx, |this, cx| {
+                                            this.bisect_start(sha.clone(), window, cx)
+                                        })
+                                        .ok();
+                                    }
+                                })
+                            }
+                        })
+                })
+        });
+
+        let subscription = cx.subscribe_in(
+            &context_menu,
+            window,
+            |this, _, _: &DismissEvent, window, cx| {
+                if this
+                    .context_menu
+                    .as_ref()
+                    .is_some_and(|(context_menu, ..)| {
+                        context_menu.focus_handle(cx).contains_focused(window, cx)
+                    })
+                {
+                    cx.focus_self(window);
+                }
+                this.context_menu.take();
+                cx.notify();
+            },
+        );
+        self.context_menu = Some((context_menu, position, subscription));
+        cx.notify();
+    }
+
+    fn cherry_pick(&mut self, sha: String, window: &mut Window, cx: &mut Context<Self>) {
+        let Some(repository) = self.get_selected_repository(cx) else {
+            return;
+        };
+        let cherry_pick = repository.update(cx, |repository, _| repository.cherry_pick(vec![sha]));
+        cx.spawn(async move |_, _| cherry_pick.await?)
+            .detach_and_prompt_err("Failed to cherry-pick", window, cx, |_, _, _| None);
+    }
+
+    fn revert(&mut self, sha: String, window: &mut Window, cx: &mut Context<Self>) {
+        let Some(repository) = self.get_selected_repository(cx) else {
+            return;
+        };
+        let revert = repository.update(cx, |repository, _| repository.revert(vec![sha]));
+        cx.spawn(async move |_, _| revert.await?)
+            .detach_and_prompt_err("Failed to revert", window, cx, |_, _, _| None);
+    }
+
+    fn create_tag(&mut self, sha: String, window: &mut Window, cx: &mut Context<Self>) {
+        let Some(repository) = self.get_selected_repository(cx) else {
+            return;
+        };
+        self.workspace
+            .update(cx, |workspace, cx| {
+                CreateTagModal::toggle(workspace, repository, Some(sha), None, window, cx);
+            })
+            .ok();
+    }
+
+    /// Starts bisecting with the given commit as the first bad one.
+    fn bisect_start(&mut self, sha: String, window: &mut Window, cx: &mut Context<Self>) {
+        if let Some(repository) = self.get_selected_repository(cx) {
+            bisect::start(repository, Some(sha), window, cx);
+        }
+    }
+
+    fn bisect_mark(
+        &mut self,
+        mark: BisectMark,
+        sha: String,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        if let Some(repository) = self.get_selected_repository(cx) {
+            bisect::mark(repository, mark, Some(sha), window, cx);
+        }
+    }
+
+    /// Rebases the commits since the given one, including it, after editing them in a modal.
+    fn interactive_rebase(&mut self, sha: String, window: &mut Window, cx: &mut Context<Self>) {
+        let Some(repository) = self.get_selected_repository(cx) else {
+            return;
+        };
+        let is_ancestor = repository.update(cx, |repository, _| {
+            repository.is_ancestor(sha.clone(), "HEAD".to_string())
+        });
+        let workspace = self.workspace.clone();
+        cx.spawn_in(window, async move |_, cx| {
+            anyhow::ensure!(
+                is_ancestor.await??,
+                "{} is not on the current branch",
+                sha.get(..git::SHORT_SHA_LENGTH).unwrap_or(&sha)
+            );
+            cx.update(|window, cx| {
+                InteractiveRebaseModal::open(workspace, repository, format!("{sha}^"), window, cx)
+            })
+        })
+        .detach_and_prompt_err(
+            "Failed to start interactive rebase",
+            window,
+            cx,
+            |_, _, _| None,
+        );
+    }
+
     fn get_remote(
         &self,
         repository: &Repository,
@@ -1455,7 +1709,7 @@ impl GitGraph {
                         h_flex().gap_1().flex_wrap().justify_center().children(
                             ref_names
                                 .iter()
-                                .map(|name| self.render_chip(name, accent_color)),
+                                .map(|name| self.render_chip(name, accent_color, cx)),
                         )
                     }))
                     .child(
@@ -1973,6 +2227,17 @@ impl GitGraph {
         }
     }
 
+    fn handle_graph_secondary_mouse_down(
+        &mut self,
+        event: &MouseDownEvent,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        if let Some(row) = self.row_at_position(event.position.y, cx) {
+            self.deploy_context_menu(event.position, row, window, cx);
+        }
+    }
+
     fn handle_graph_scroll(
         &mut self,
         event: &ScrollWheelEvent,
@@ -2137,6 +2402,10 @@ impl Render for GitGraph {
                                 .on_scroll_wheel(cx.listener(Self::handle_graph_scroll))
                                 .on_mouse_move(cx.listener(Self::handle_graph_mouse_move))
                                 .on_click(cx.listener(Self::handle_graph_click))
+                                .on_mouse_down(
+                                    MouseButton::Right,
+                                    cx.listener(Self::handle_graph_secondary_mouse_down),
+                                )
                                 .on_hover(cx.listener(|this, &is_hovered: &bool, _, cx| {
                                     if !is_hovered && this.hovered_entry_idx.is_some() {
                                         this.hovered_entry_idx = None;
@@ -2189,6 +2458,7 @@ impl Render for GitGraph {
                                 let is_focused = focus_handle.is_focused(window);
                                 let weak = weak_self.clone();
                                 let weak_for_hover = weak.clone();
+                                let weak_for_context_menu = weak.clone();
 
                                 let hover_bg = cx.theme().colors().element_hover.opacity(0.6);
                                 let selected_bg = if is_focused {
@@ -2226,6 +2496,18 @@ impl Render for GitGraph {
                                         })
                                         .ok();
                                     })
+                                    .on_mouse_down(MouseButton::Right, move |event, window, cx| {
+                                        weak_for_context_menu
+                                            .update(cx, |this, cx| {
+                                                this.deploy_context_menu(
+                                                    event.position,
+                                                    index,
+                                                    window,
+                                                    cx,
+                                                );
+                                            })
+                                            .ok();
+                                    })
                                     .into_any_element()
                             })
                             .uniform_list(
@@ -0,0 +1,175 @@
+use std::process::ExitStatus;
+
+use anyhow::{Result, anyhow};
+use git::repository::BisectMark;
+use gpui::{App, AppContext as _, Context, Entity, Window};
+use project::git_store::Repository;
+use task::TaskContext;
+use workspace::{Workspace, notifications::DetachAndPromptErr};
+
+use crate::{picker_prompt, resolve_active_repository};
+
+/// The exit code `git bisect run` reserves for commits that can't be tested.
+const SKIP_EXIT_CODE: i32 = 125;
+
+pub(crate) fn register(workspace: &mut Workspace) {
+    workspace.register_action(|workspace, _: &git::BisectStart, window, cx| {
+        if let Some(repository) = resolve_active_repository(workspace, cx) {
+            start(repository, Some("HEAD".to_string()), window, cx);
+        }
+    });
+    workspace.register_action(|workspace, _: &git::BisectGood, window, cx| {
+        if let Some(repository) = resolve_active_repository(workspace, cx) {
+            mark(repository, BisectMark::Good, None, window, cx);
+        }
+    });
+    workspace.register_action(|workspace, _: &git::BisectBad, window, cx| {
+        if let Some(repository) = resolve_active_repository(workspace, cx) {
+            mark(repository, BisectMark::Bad, None, window, cx);
+        }
+    });
+    workspace.register_action(|workspace, _: &git::BisectSkip, window, cx| {
+        if let Some(repository) = resolve_active_repository(workspace, cx) {
+            mark(repository, BisectMark::Skip, None, window, cx);
+        }
+    });
+    workspace.register_action(|workspace, _: &git::BisectReset, window, cx| {
+        if let Some(repository) = resolve_active_repository(workspace, cx) {
+            reset(repository, window, cx);
+        }
+    });
+    workspace.register_action(|workspace, _: &git::BisectRun, window, cx| {
+        run(workspace, window, cx);
+    });
+}
+
+/// Starts bisecting with `bad` as the first bad commit.
+pub fn start(
+    repository: Entity<Repository>,
+    bad: Option<String>,
+    window: &mut Window,
+    cx: &mut App,
+) {
+    let started = repository.update(cx, |repository, _| repository.bisect_start(bad, Vec::new()));
+    cx.spawn(async move |_| started.await?)
+        .detach_and_prompt_err("Failed to start bisect", window, cx, |_, _, _| None);
+}
+
+/// Marks `commit`, or the commit being bisected if none is given.
+pub fn mark(
+    repository: Entity<Repository>,
+    mark: BisectMark,
+    commit: Option<String>,
+    window: &mut Window,
+    cx: &mut App,
+) {
+    let marked = repository.update(cx, |repository, _| repository.bisect_mark(mark, commit));
+    cx.spawn(async move |_| marked.await?.map(|_| ()))
+        .detach_and_prompt_err("Failed to mark commit", window, cx, |_, _, _| None);
+}
+
+pub fn reset(repository: Entity<Repository>, window: &mut Window, cx: &mut App) {
+    let reset = repository.update(cx, |repository, _| repository.bisect_reset());
+    cx.spawn(async move |_| reset.await?).detach_and_prompt_err(
+        "Failed to reset bisect",
+        window,
+        cx,
+        |_, _, _| None,
+    );
+}
+
+/// Prompts for a task and runs it on each commit being bisected, marking the commit
+/// by its exit code the way `git bisect run` does, until the first bad commit is found.
+///
+/// Only tasks that run a single command without prompting for inputs are offered,
+/// since the exit code of that command decides the mark.
+pub fn run(workspace: &mut Workspace, window: &mut Window, cx: &mut Context<Workspace>) {
+    let Some(repository) = resolve_active_repository(workspace, cx) else {
+        return;
+    };
+    let project = workspace.project().read(cx);
+    let Some(inventory) = project.task_store().read(cx).task_inventory().cloned() else {
+        return;
+    };
+    let work_directory = repository.read(cx).work_directory_abs_path.clone();
+    let worktree_id = project
+        .find_worktree(&work_directory, cx)
+        .map(|(worktree, _)| worktree.read(cx).id());
+    let templates = inventory.read(cx).list_tasks(None, None, worktree_id, cx);
+    let task_context = TaskContext {
+        cwd: Some(work_directory.to_path_buf()),
+        ..TaskContext::default()
+    };
+
+    cx.spawn_in(window, async move |workspace, cx| {
+        let is_bounded = repository.read_with(cx, |repository, _| {
+            repository
+                .bisect
+                .as_ref()
+                .is_some_and(|bisect| bisect.bad.is_some() && !bisect.good.is_empty())
+        });
+        anyhow::ensure!(
+            is_bounded,
+            "Mark a good and a bad commit before running the bisect"
+        );
+
+        let tasks = templates
+            .await
+            .into_iter()
+            .filter(|(_, template)| template.depends_on.is_empty())
+            .filter_map(|(kind, template)| template.resolve_task(&kind.to_id_base(), &task_context))
+            .filter(|task| task.pending_inputs().is_empty())
+            .collect::<Vec<_>>();
+        anyhow::ensure!(!tasks.is_empty(), "No tasks to test the commits with");
+
+        let labels = tasks
+            .iter()
+            .map(|task| task.display_label().to_string().into())
+            .collect();
+        let Some(ix) = cx
+            .update(|window, cx| {
+                picker_prompt::prompt(
+                    "Select a task to test each commit with",
+                    labels,
+                    workspace.clone(),
+                    window,
+                    cx,
+                )
+            })?
+            .await
+        else {
+            return Ok(());
+        };
+        let spawn_in_terminal = tasks[ix].resolved.clone();
+
+        loop {
+            let Some(status) = workspace
+                .update_in(cx, |workspace, window, cx| {
+                    workspace.spawn_in_terminal(spawn_in_terminal.clone(), window, cx)
+                })?
+                .await
+            else {
+                return Ok(());
+            };
+            let mark = mark_for_exit_status(status?)?;
+            let bisect = repository
+                .update(cx, |repository, _| repository.bisect_mark(mark, None))
+                .await??;
+            if bisect.is_none_or(|bisect| bisect.first_bad.is_some()) {
+                return Ok(());
+            }
+        }
+    })
+    .detach_and_prompt_err("Failed to run bisect", window, cx, |_, _, _| None);
+}
+
+fn mark_for_exit_status(status: ExitStatus) -> Result<BisectMark> {
+    match status.code() {
+        Some(0) => Ok(BisectMark::Good),
+        Some(SKIP_EXIT_CODE) => Ok(BisectMark::Skip),
+        Some(1..=127) => Ok(BisectMark::Bad),
+        _ => Err(anyhow!(
+            "The task exited with {status}, stopping the bisect"
+        )),
+    }
+}
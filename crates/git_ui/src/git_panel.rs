@@ -24,15 +24,15 @@ use futures::StreamExt as _;
 use git::commit::ParsedCommitMessage;
 use git::repository::{
     Branch, CommitDetails, CommitOptions, CommitSummary, DiffType, FetchOptions, GitCommitter,
-    PushOptions, Remote, RemoteCommandOutput, ResetMode, Upstream, UpstreamTracking,
+    PushOptions, Remote, RemoteCommandOutput, ResetMode, Submodule, Upstream, UpstreamTracking,
     UpstreamTrackingStatus, get_git_committer,
 };
 use git::stash::GitStash;
 use git::status::{DiffStat, StageStatus};
 use git::{Amend, Signoff, ToggleStaged, repository::RepoPath, status::FileStatus};
 use git::{
-    ExpandCommitEditor, GitHostingProviderRegistry, RestoreTrackedFiles, StageAll, StashAll,
-    StashApply, StashPop, TrashUntrackedFiles, UnstageAll,
+    ExpandCommitEditor, GitHostingProviderRegistry, RestoreTrackedFiles, SHORT_SHA_LENGTH,
+    StageAll, StashAll, StashApply, StashPop, TrashUntrackedFiles, UnstageAll,
 };
 use gpui::{
     Action, AsyncApp, AsyncWindowContext, Bounds, ClickEvent, Corner, DismissEvent, Empty, Entity,
@@ -202,6 +202,9 @@ fn git_panel_context_menu(
                 TrashUntrackedFiles.boxed_clone(),
             )
             .separator()
+            .action("Undo Last Operation", git::UndoLastOperation.boxed_clone())
+            .action("View Reflog", zed_actions::git::ViewReflog.boxed_clone())
+            .separator()
             .entry(
                 if state.tree_view {
                     "Flat View"
@@ -268,6 +271,7 @@ struct SerializedGitPanel {
 enum Section {
     Conflict,
     Tracked,
+    Submodules,
     New,
 }
 
@@ -284,14 +288,20 @@ impl GitHeaderEntry {
             Section::Conflict => {
                 repo.had_conflict_on_last_merge_head_change(&status_entry.repo_path)
             }
-            Section::Tracked => !status.is_created(),
-            Section::New => status.is_created(),
+            Section::Tracked => {
+                !status.is_created() && repo.submodule(&status_entry.repo_path).is_none()
+            }
+            Section::Submodules => repo.submodule(&status_entry.repo_path).is_some(),
+            Section::New => {
+                status.is_created() && repo.submodule(&status_entry.repo_path).is_none()
+            }
         }
     }
     pub fn title(&self) -> &'static str {
         match self.header {
             Section::Conflict => "Conflicts",
             Section::Tracked => "Tracked",
+            Section::Submodules => "Submodules",
             Section::New => "Untracked",
         }
     }
@@ -643,6 +653,8 @@ pub struct GitPanel {
     marked_entries: Vec<usize>,
     tracked_count: usize,
     tracked_staged_count: usize,
+    submodule_count: usize,
+    submodule_staged_count: usize,
     update_visible_entries_task: Task<()>,
     width: Option<Pixels>,
     pub(crate) workspace: WeakEntity<Workspace>,
@@ -765,7 +777,9 @@ impl GitPanel {
                 move |this, _git_store, event, window, cx| match event {
                     GitStoreEvent::RepositoryUpdated(
                         _,
-                        RepositoryEvent::StatusesChanged | RepositoryEvent::BranchChanged,
+                        RepositoryEvent::StatusesChanged
+                        | RepositoryEvent::BranchChanged
+                        | RepositoryEvent::SubmodulesChanged,
                         true,
                     )
                     | GitStoreEvent::RepositoryAdded
@@ -773,6 +787,9 @@ impl GitPanel {
                     | GitStoreEvent::ActiveRepositoryChanged(_) => {
                         this.schedule_update(window, cx);
                     }
+                    GitStoreEvent::RepositoryUpdated(_, RepositoryEvent::BisectChanged, true) => {
+                        cx.notify();
+                    }
                     GitStoreEvent::IndexWriteError(error) => {
                         this.workspace
                             .update(cx, |workspace, cx| {
@@ -815,6 +832,8 @@ impl GitPanel {
                 marked_entries: Vec::new(),
                 tracked_count: 0,
                 tracked_staged_count: 0,
+                submodule_count: 0,
+                submodule_staged_count: 0,
                 update_visible_entries_task: Task::ready(()),
                 width: None,
                 show_placeholders: false,
@@ -860,6 +879,8 @@ impl GitPanel {
                 .map(|status| {
                     if repo.had_conflict_on_last_merge_head_change(&repo_path) {
                         Section::Conflict
+                    } else if repo.submodule(&repo_path).is_some() {
+                        Section::Submodules
                     } else if status.is_created() {
                         Section::New
                     } else {
@@ -2884,6 +2905,67 @@ impl GitPanel {
             .detach_and_log_err(cx);
     }
 
+    /// Checks out the recorded commits of the submodules at `paths`, or of all
+    /// submodules if `paths` is empty, cloning them first when `init` is set.
+    pub(crate) fn update_submodules(
+        &mut self,
+        paths: Vec<RepoPath>,
+        init: bool,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        if !self.can_push_and_pull(cx) {
+            return;
+        }
+
+        let Some(repo) = self.active_repository.clone() else {
+            return;
+        };
+        let operation = if init {
+            "git submodule update --init"
+        } else {
+            "git submodule update"
+        };
+        let askpass = self.askpass_delegate(operation, window, cx);
+        let this = cx.weak_entity();
+
+        window
+            .spawn(cx, async move |cx| {
+                let update =
+                    repo.update(cx, |repo, _| repo.update_submodules(paths, init, askpass));
+
+                let result = update.await?;
+                this.update(cx, |this, cx| {
+                    if let Err(e) = result {
+                        log::error!("Error while updating submodules {:?}", e);
+                        this.show_error_toast("submodule update", e, cx)
+                    }
+                })
+                .ok();
+                anyhow::Ok(())
+            })
+            .detach_and_log_err(cx);
+    }
+
+    pub(crate) fn sync_submodules(&mut self, paths: Vec<RepoPath>, cx: &mut Context<Self>) {
+        let Some(repo) = self.active_repository.clone() else {
+            return;
+        };
+        let sync = repo.update(cx, |repo, _| repo.sync_submodules(paths));
+
+        cx.spawn(async move |this, cx| {
+            let result = sync.await?;
+            this.update(cx, |this, cx| {
+                if let Err(e) = result {
+                    this.show_error_toast("submodule sync", e, cx)
+                }
+            })
+            .ok();
+            anyhow::Ok(())
+        })
+        .detach_and_log_err(cx);
+    }
+
     pub(crate) fn git_clone(&mut self, repo: String, window: &mut Window, cx: &mut Context<Self>) {
         let workspace = self.workspace.clone();
 
@@ -3120,6 +3202,56 @@ impl GitPanel {
         .detach_and_log_err(cx);
     }
 
+    pub(crate) fn push_tag(
+        &mut self,
+        repo: Entity<Repository>,
+        tag: SharedString,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        if !self.can_push_and_pull(cx) {
+            return;
+        }
+        let remote = self.get_remote(true, true, window, cx);
+
+        cx.spawn_in(window, async move |this, cx| {
+            let remote = match remote.await {
+                Ok(Some(remote)) => remote,
+                Ok(None) => {
+                    return Ok(());
+                }
+                Err(e) => {
+                    log::error!("Failed to get current remote: {}", e);
+                    this.update(cx, |this, cx| this.show_error_toast("push", e, cx))
+                        .ok();
+                    return Ok(());
+                }
+            };
+
+            let askpass_delegate = this.update_in(cx, |this, window, cx| {
+                this.askpass_delegate(format!("git push {}", remote.name), window, cx)
+            })?;
+
+            let push = repo.update(cx, |repo, cx| {
+                repo.push_tag(tag.clone(), remote.name.clone(), askpass_delegate, cx)
+            });
+
+            let remote_output = push.await?;
+
+            let action = RemoteAction::Push(tag, remote);
+            this.update(cx, |this, cx| match remote_output {
+                Ok(remote_message) => this.show_remote_output(action, remote_message, cx),
+                Err(e) => {
+                    log::error!("Error while pushing tag {:?}", e);
+                    this.show_error_toast(action.name(), e, cx)
+                }
+            })?;
+
+            anyhow::Ok(())
+        })
+        .detach_and_log_err(cx);
+    }
+
     pub fn create_pull_request(&self, window: &mut Window, cx: &mut Context<Self>) {
         let result = (|| -> anyhow::Result<()> {
             let repo = self
@@ -3496,6 +3628,8 @@ impl GitPanel {
         self.tracked_count = 0;
         self.new_staged_count = 0;
         self.tracked_staged_count = 0;
+        self.submodule_count = 0;
+        self.submodule_staged_count = 0;
         self.entry_count = 0;
         self.max_width_item_index = None;
 
@@ -3506,6 +3640,7 @@ impl GitPanel {
         let mut changed_entries = Vec::new();
         let mut new_entries = Vec::new();
         let mut conflict_entries = Vec::new();
+        let mut submodule_entries = Vec::new();
         let mut single_staged_entry = None;
         let mut staged_count = 0;
         let mut seen_directories = HashSet::default();
@@ -3526,6 +3661,7 @@ impl GitPanel {
             self.changes_count += 1;
             let is_conflict = repo.had_conflict_on_last_merge_head_change(&entry.repo_path);
             let is_new = entry.status.is_created();
+            let is_submodule = repo.submodule(&entry.repo_path).is_some();
             let staging = entry.status.staging();
 
             if let Some(pending) = repo.pending_ops_for_path(&entry.repo_path)
@@ -3551,6 +3687,8 @@ impl GitPanel {
 
             if group_by_status && is_conflict {
                 conflict_entries.push(entry);
+            } else if group_by_status && is_submodule {
+                submodule_entries.push(entry);
             } else if group_by_status && is_new {
                 new_entries.push(entry);
             } else {
@@ -3618,6 +3756,7 @@ impl GitPanel {
                 [
                     (Section::Conflict, std::mem::take(&mut conflict_entries)),
                     (Section::Tracked, std::mem::take(&mut changed_entries)),
+                    (Section::Submodules, std::mem::take(&mut submodule_entries)),
                     (Section::New, std::mem::take(&mut new_entries)),
                 ]
             };
@@ -3718,6 +3857,7 @@ impl GitPanel {
         let (staged_count, count) = match header_type {
             Section::New => (self.new_staged_count, self.new_count),
             Section::Tracked => (self.tracked_staged_count, self.tracked_count),
+            Section::Submodules => (self.submodule_staged_count, self.submodule_count),
             Section::Conflict => (self.conflicted_staged_count, self.conflicted_count),
         };
         if staged_count == 0 {
@@ -3737,6 +3877,8 @@ impl GitPanel {
         self.tracked_count = 0;
         self.new_staged_count = 0;
         self.tracked_staged_count = 0;
+        self.submodule_count = 0;
+        self.submodule_staged_count = 0;
         self.entry_count = 0;
 
         for status_entry in self.entries.iter().filter_map(|entry| entry.status_entry()) {
@@ -3750,6 +3892,11 @@ impl GitPanel {
                 if is_staging_or_staged {
                     self.conflicted_staged_count += 1;
                 }
+            } else if repo.submodule(&status_entry.repo_path).is_some() {
+                self.submodule_count += 1;
+                if is_staging_or_staged {
+                    self.submodule_staged_count += 1;
+                }
             } else if status_entry.status.is_created() {
                 self.new_count += 1;
                 if is_staging_or_staged {
@@ -3768,12 +3915,14 @@ impl GitPanel {
         self.tracked_staged_count > 0
             || self.new_staged_count > 0
             || self.conflicted_staged_count > 0
+            || self.submodule_staged_count > 0
     }
 
     pub(crate) fn has_unstaged_changes(&self) -> bool {
         self.tracked_count > self.tracked_staged_count
             || self.new_count > self.new_staged_count
             || self.conflicted_count > self.conflicted_staged_count
+            || self.submodule_count > self.submodule_staged_count
     }
 
     fn has_tracked_changes(&self) -> bool {
@@ -4468,6 +4617,141 @@ impl GitPanel {
             ))
     }
 
+    fn render_uninitialized_submodules(&self, cx: &mut Context<Self>) -> Option<impl IntoElement> {
+        if !self.can_push_and_pull(cx) {
+            return None;
+        }
+        let active_repository = self.active_repository.as_ref()?;
+        let uninitialized_count = active_repository
+            .read(cx)
+            .submodules
+            .iter()
+            .filter(|submodule| !submodule.is_initialized)
+            .count();
+        if uninitialized_count == 0 {
+            return None;
+        }
+
+        let message: SharedString = if uninitialized_count == 1 {
+            "1 submodule isn't initialized".into()
+        } else {
+            format!("{uninitialized_count} submodules aren't initialized").into()
+        };
+
+        Some(
+            h_flex()
+                .py_1p5()
+                .px_2()
+                .gap_1p5()
+                .justify_between()
+                .border_t_1()
+                .border_color(cx.theme().colors().border.opacity(0.8))
+                .child(
+                    div()
+                        .flex_grow()
+                        .overflow_hidden()
+                        .child(Label::new(message).size(LabelSize::Small).truncate()),
+                )
+                .child(
+                    panel_button("Initialize")
+                        .size(ButtonSize::Default)
+                        .tooltip(Tooltip::for_action_title(
+                            "Initialize Submodules",
+                            &git::InitSubmodules,
+                        ))
+                        .on_click(|_, window, cx| {
+                            window.dispatch_action(Box::new(git::InitSubmodules), cx)
+                        }),
+                ),
+        )
+    }
+
+    fn render_bisect(&self, cx: &mut Context<Self>) -> Option<impl IntoElement> {
+        let active_repository = self.active_repository.as_ref()?;
+        let bisect = active_repository.read(cx).bisect.clone()?;
+        let short_sha = |sha: &SharedString| sha.chars().take(SHORT_SHA_LENGTH).collect::<String>();
+
+        let status: SharedString = if let Some(first_bad) = &bisect.first_bad {
+            format!("{} is the first bad commit", short_sha(first_bad)).into()
+        } else if bisect.bad.is_none() {
+            "Bisecting: mark a bad commit".into()
+        } else if bisect.good.is_empty() {
+            "Bisecting: mark a good commit".into()
+        } else {
+            match (bisect.remaining, bisect.steps) {
+                (Some(remaining), Some(steps)) => {
+                    format!("Bisecting: {remaining} commits left, roughly {steps} steps").into()
+                }
+                _ => "Bisecting".into(),
+            }
+        };
+        let bisect_button =
+            |label: &'static str, tooltip: &'static str, action: Box<dyn Action>| {
+                panel_button(label)
+                    .size(ButtonSize::Default)
+                    .tooltip(Tooltip::for_action_title(tooltip, action.as_ref()))
+                    .on_click(move |_, window, cx| window.dispatch_action(action.boxed_clone(), cx))
+            };
+
+        Some(
+            h_flex()
+                .py_1p5()
+                .px_2()
+                .gap_1p5()
+                .justify_between()
+                .border_t_1()
+                .border_color(cx.theme().colors().border.opacity(0.8))
+                .child(
+                    div()
+                        .flex_grow()
+                        .overflow_hidden()
+                        .child(Label::new(status).size(LabelSize::Small).truncate()),
+                )
+                .child(
+                    h_flex()
+                        .gap_0p5()
+                        .when(bisect.first_bad.is_none(), |this| {
+                            this.child(bisect_button(
+                                "Good",
+                                "Mark as Good",
+                                Box::new(git::BisectGood),
+                            ))
+                            .child(bisect_button(
+                                "Bad",
+                                "Mark as Bad",
+                                Box::new(git::BisectBad),
+                            ))
+                            .child(bisect_button(
+                                "Skip",
+                                "Skip Commit",
+                                Box::new(git::BisectSkip),
+                            ))
+                            .when(
+                                bisect.bad.is_some() && !bisect.good.is_empty(),
+                                |this| {
+                                    this.child(
+                                        panel_icon_button("bisect-run", IconName::PlayOutlined)
+                                            .icon_size(IconSize::Small)
+                                            .tooltip(Tooltip::for_action_title(
+                                                "Run Task on Each Commit",
+                                                &git::BisectRun,
+                                            ))
+                                            .on_click(|_, window, cx| {
+                                                window.dispatch_action(Box::new(git::BisectRun), cx)
+                                            }),
+                                    )
+                                },
+                            )
+                        })
+                        .child(bisect_button(
+                            "Reset",
+                            "End Bisect",
+                            Box::new(git::BisectReset),
+                        )),
+                ),
+        )
+    }
+
     fn render_pending_amend(&self, cx: &mut Context<Self>) -> impl IntoElement {
         h_flex()
             .py_1p5()
@@ -4941,10 +5225,44 @@ impl GitPanel {
         } else {
             "Discard Changes"
         };
+        let submodule_path = self.active_repository.as_ref().and_then(|repo| {
+            repo.read(cx)
+                .submodule(&entry.repo_path)
+                .map(|submodule| submodule.path.clone())
+        });
+        let can_update_submodule = self.can_push_and_pull(cx);
+        let this = cx.weak_entity();
         let context_menu = ContextMenu::build(window, cx, |context_menu, _, _| {
             let is_created = entry.status.is_created();
             context_menu
                 .context(self.focus_handle.clone())
+                .when_some(submodule_path, |context_menu, path| {
+                    context_menu
+                        .when(can_update_submodule, |context_menu| {
+                            context_menu.entry("Update Submodule", None, {
+                                let this = this.clone();
+                                let path = path.clone();
+                                move |window, cx| {
+                                    this.update(cx, |this, cx| {
+                                        this.update_submodules(
+                                            vec![path.clone()],
+                                            false,
+                                            window,
+                                            cx,
+                                        )
+                                    })
+                                    .ok();
+                                }
+                            })
+                        })
+                        .entry("Sync Submodule URL", None, move |_, cx| {
+                            this.update(cx, |this, cx| {
+                                this.sync_submodules(vec![path.clone()], cx)
+                            })
+                            .ok();
+                        })
+                        .separator()
+                })
                 .action(stage_title, ToggleStaged.boxed_clone())
                 .action(restore_title, git::RestoreFile::default().boxed_clone())
                 .action_disabled_when(
@@ -5100,6 +5418,10 @@ impl GitPanel {
             )
         };
 
+        let submodule_state = repo
+            .submodule(&entry.repo_path)
+            .and_then(submodule_state_label);
+
         let name_row = h_flex()
             .min_w_0()
             .flex_1()
@@ -5123,6 +5445,14 @@ impl GitPanel {
                         status.is_deleted(),
                     ))
                 }
+            })
+            .when_some(submodule_state, |this, state| {
+                this.child(
+                    Label::new(state)
+                        .size(LabelSize::Small)
+                        .color(Color::Muted)
+                        .truncate(),
+                )
             });
 
         let id_for_diff_stat = id.clone();
@@ -5636,6 +5966,8 @@ impl Render for GitPanel {
                             this.child(self.render_empty_state(cx).into_any_element())
                         }
                     })
+                    .children(self.render_uninitialized_submodules(cx))
+                    .children(self.render_bisect(cx))
                     .children(self.render_footer(window, cx))
                     .when(self.amend_pending, |this| {
                         this.child(self.render_pending_amend(cx))
@@ -6343,6 +6675,20 @@ fn open_output(
     workspace.add_item_to_center(Box::new(editor), window, cx);
 }
 
+/// Describes how a submodule's checkout differs from the commit recorded for it,
+/// the way `git status` does.
+fn submodule_state_label(submodule: &Submodule) -> Option<SharedString> {
+    let states = [
+        (submodule.has_new_commits, "new commits"),
+        (submodule.has_modified_content, "modified content"),
+        (submodule.has_untracked_content, "untracked content"),
+    ]
+    .into_iter()
+    .filter_map(|(is_set, state)| is_set.then_some(state))
+    .collect::<Vec<_>>();
+    (!states.is_empty()).then(|| states.join(", ").into())
+}
+
 pub(crate) fn show_error_toast(
     workspace: Entity<Workspace>,
     action: impl Into<SharedString>,
@@ -13,14 +13,22 @@ use ui::{
 use workspace::{ModalView, Workspace, pane};
 
 use crate::branch_picker::{self, BranchList, DeleteBranch, FilterRemotes};
+use crate::reflog_picker::{self, CreateBranchFromEntry, ReflogList, ResetToEntry};
 use crate::stash_picker::{self, DropStashItem, ShowStashItem, StashList};
+use crate::tag_picker::{self, DeleteTag, PushTag, TagList};
 use crate::worktree_picker::{
     self, DeleteWorktree, WorktreeFromDefault, WorktreeFromDefaultOnWindow, WorktreeList,
 };
 
 actions!(
     git_picker,
-    [ActivateBranchesTab, ActivateWorktreesTab, ActivateStashTab,]
+    [
+        ActivateBranchesTab,
+        ActivateWorktreesTab,
+        ActivateStashTab,
+        ActivateTagsTab,
+        ActivateReflogTab,
+    ]
 );
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
@@ -28,6 +36,8 @@ pub enum GitPickerTab {
     Branches,
     Worktrees,
     Stash,
+    Tags,
+    Reflog,
 }
 
 impl Display for GitPickerTab {
@@ -36,6 +46,8 @@ impl Display for GitPickerTab {
             GitPickerTab::Branches => "Branches",
             GitPickerTab::Worktrees => "Worktrees",
             GitPickerTab::Stash => "Stash",
+            GitPickerTab::Tags => "Tags",
+            GitPickerTab::Reflog => "Reflog",
         };
         write!(f, "{}", label)
     }
@@ -49,6 +61,8 @@ pub struct GitPicker {
     branch_list: Option<Entity<BranchList>>,
     worktree_list: Option<Entity<WorktreeList>>,
     stash_list: Option<Entity<StashList>>,
+    tag_list: Option<Entity<TagList>>,
+    reflog_list: Option<Entity<ReflogList>>,
     _subscriptions: Vec<Subscription>,
     popover_style: bool,
 }
@@ -82,6 +96,8 @@ impl GitPicker {
             branch_list: None,
             worktree_list: None,
             stash_list: None,
+            tag_list: None,
+            reflog_list: None,
             _subscriptions: Vec::new(),
             popover_style,
         };
@@ -101,6 +117,12 @@ impl GitPicker {
             GitPickerTab::Stash => {
                 self.ensure_stash_list(window, cx);
             }
+            GitPickerTab::Tags => {
+                self.ensure_tag_list(window, cx);
+            }
+            GitPickerTab::Reflog => {
+                self.ensure_reflog_list(window, cx);
+            }
         }
     }
 
@@ -188,11 +210,65 @@ impl GitPicker {
         self.stash_list.clone().unwrap()
     }
 
+    fn ensure_tag_list(&mut self, window: &mut Window, cx: &mut Context<Self>) -> Entity<TagList> {
+        if self.tag_list.is_none() {
+            let tag_list = cx.new(|cx| {
+                tag_picker::create_embedded(
+                    self.repository.clone(),
+                    self.workspace.clone(),
+                    self.width,
+                    window,
+                    cx,
+                )
+            });
+
+            let subscription = cx.subscribe(&tag_list, |this, _, _: &DismissEvent, cx| {
+                if this.tab == GitPickerTab::Tags {
+                    cx.emit(DismissEvent);
+                }
+            });
+
+            self._subscriptions.push(subscription);
+            self.tag_list = Some(tag_list);
+        }
+        self.tag_list.clone().unwrap()
+    }
+
+    fn ensure_reflog_list(
+        &mut self,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Entity<ReflogList> {
+        if self.reflog_list.is_none() {
+            let reflog_list = cx.new(|cx| {
+                reflog_picker::create_embedded(
+                    self.repository.clone(),
+                    self.workspace.clone(),
+                    self.width,
+                    window,
+                    cx,
+                )
+            });
+
+            let subscription = cx.subscribe(&reflog_list, |this, _, _: &DismissEvent, cx| {
+                if this.tab == GitPickerTab::Reflog {
+                    cx.emit(DismissEvent);
+                }
+            });
+
+            self._subscriptions.push(subscription);
+            self.reflog_list = Some(reflog_list);
+        }
+        self.reflog_list.clone().unwrap()
+    }
+
     fn activate_next_tab(&mut self, window: &mut Window, cx: &mut Context<Self>) {
         self.tab = match self.tab {
             GitPickerTab::Branches => GitPickerTab::Worktrees,
             GitPickerTab::Worktrees => GitPickerTab::Stash,
-            GitPickerTab::Stash => GitPickerTab::Branches,
+            GitPickerTab::Stash => GitPickerTab::Tags,
+            GitPickerTab::Tags => GitPickerTab::Reflog,
+            GitPickerTab::Reflog => GitPickerTab::Branches,
         };
         self.ensure_active_picker(window, cx);
         self.focus_active_picker(window, cx);
@@ -201,9 +277,11 @@ impl GitPicker {
 
     fn activate_previous_tab(&mut self, window: &mut Window, cx: &mut Context<Self>) {
         self.tab = match self.tab {
-            GitPickerTab::Branches => GitPickerTab::Stash,
+            GitPickerTab::Branches => GitPickerTab::Reflog,
             GitPickerTab::Worktrees => GitPickerTab::Branches,
             GitPickerTab::Stash => GitPickerTab::Worktrees,
+            GitPickerTab::Tags => GitPickerTab::Stash,
+            GitPickerTab::Reflog => GitPickerTab::Tags,
         };
         self.ensure_active_picker(window, cx);
         self.focus_active_picker(window, cx);
@@ -227,6 +305,16 @@ impl GitPicker {
                     stash_list.focus_handle(cx).focus(window, cx);
                 }
             }
+            GitPickerTab::Tags => {
+                if let Some(tag_list) = &self.tag_list {
+                    tag_list.focus_handle(cx).focus(window, cx);
+                }
+            }
+            GitPickerTab::Reflog => {
+                if let Some(reflog_list) = &self.reflog_list {
+                    reflog_list.focus_handle(cx).focus(window, cx);
+                }
+            }
         }
     }
 
@@ -234,7 +322,9 @@ impl GitPicker {
         let focus_handle = self.focus_handle(cx);
         let branches_focus_handle = focus_handle.clone();
         let worktrees_focus_handle = focus_handle.clone();
-        let stash_focus_handle = focus_handle;
+        let stash_focus_handle = focus_handle.clone();
+        let tags_focus_handle = focus_handle.clone();
+        let reflog_focus_handle = focus_handle;
 
         h_flex().p_2().pb_0p5().w_full().child(
             ToggleButtonGroup::single_row(
@@ -291,6 +381,40 @@ impl GitPicker {
                             cx,
                         )
                     }),
+                    ToggleButtonSimple::new(
+                        GitPickerTab::Tags.to_string(),
+                        cx.listener(|this, _, window, cx| {
+                            this.tab = GitPickerTab::Tags;
+                            this.ensure_active_picker(window, cx);
+                            this.focus_active_picker(window, cx);
+                            cx.notify();
+                        }),
+                    )
+                    .tooltip(move |_, cx| {
+                        Tooltip::for_action_in(
+                            "Toggle Tag Picker",
+                            &ActivateTagsTab,
+                            &tags_focus_handle,
+                            cx,
+                        )
+                    }),
+                    ToggleButtonSimple::new(
+                        GitPickerTab::Reflog.to_string(),
+                        cx.listener(|this, _, window, cx| {
+                            this.tab = GitPickerTab::Reflog;
+                            this.ensure_active_picker(window, cx);
+                            this.focus_active_picker(window, cx);
+                            cx.notify();
+                        }),
+                    )
+                    .tooltip(move |_, cx| {
+                        Tooltip::for_action_in(
+                            "Toggle Reflog",
+                            &ActivateReflogTab,
+                            &reflog_focus_handle,
+                            cx,
+                        )
+                    }),
                 ],
             )
             .label_size(LabelSize::Default)
@@ -300,6 +424,8 @@ impl GitPicker {
                 GitPickerTab::Branches => 0,
                 GitPickerTab::Worktrees => 1,
                 GitPickerTab::Stash => 2,
+                GitPickerTab::Tags => 3,
+                GitPickerTab::Reflog => 4,
             }),
         )
     }
@@ -322,6 +448,14 @@ impl GitPicker {
                 let stash_list = self.ensure_stash_list(window, cx);
                 stash_list.into_any_element()
             }
+            GitPickerTab::Tags => {
+                let tag_list = self.ensure_tag_list(window, cx);
+                tag_list.into_any_element()
+            }
+            GitPickerTab::Reflog => {
+                let reflog_list = self.ensure_reflog_list(window, cx);
+                reflog_list.into_any_element()
+            }
         }
     }
 
@@ -353,6 +487,7 @@ impl GitPicker {
                     });
                 }
             }
+            GitPickerTab::Tags | GitPickerTab::Reflog => {}
         }
     }
 
@@ -446,6 +581,48 @@ impl GitPicker {
             });
         }
     }
+
+    fn handle_delete_tag(&mut self, _: &DeleteTag, window: &mut Window, cx: &mut Context<Self>) {
+        if let Some(tag_list) = &self.tag_list {
+            tag_list.update(cx, |list, cx| {
+                list.handle_delete_tag(&DeleteTag, window, cx);
+            });
+        }
+    }
+
+    fn handle_push_tag(&mut self, _: &PushTag, window: &mut Window, cx: &mut Context<Self>) {
+        if let Some(tag_list) = &self.tag_list {
+            tag_list.update(cx, |list, cx| {
+                list.handle_push_tag(&PushTag, window, cx);
+            });
+        }
+    }
+
+    fn handle_reset_to_entry(
+        &mut self,
+        _: &ResetToEntry,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        if let Some(reflog_list) = &self.reflog_list {
+            reflog_list.update(cx, |list, cx| {
+                list.handle_reset_to_entry(&ResetToEntry, window, cx);
+            });
+        }
+    }
+
+    fn handle_create_branch_from_entry(
+        &mut self,
+        _: &CreateBranchFromEntry,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        if let Some(reflog_list) = &self.reflog_list {
+            reflog_list.update(cx, |list, cx| {
+                list.handle_create_branch_from_entry(&CreateBranchFromEntry, window, cx);
+            });
+        }
+    }
 }
 
 impl ModalView for GitPicker {}
@@ -469,6 +646,16 @@ impl Focusable for GitPicker {
                     return stash_list.focus_handle(cx);
                 }
             }
+            GitPickerTab::Tags => {
+                if let Some(tag_list) = &self.tag_list {
+                    return tag_list.focus_handle(cx);
+                }
+            }
+            GitPickerTab::Reflog => {
+                if let Some(reflog_list) = &self.reflog_list {
+                    return reflog_list.focus_handle(cx);
+                }
+            }
         }
         cx.focus_handle()
     }
@@ -494,6 +681,8 @@ impl Render for GitPicker {
                     GitPickerTab::Branches => key_context.add("GitBranchSelector"),
                     GitPickerTab::Worktrees => key_context.add("GitWorktreeSelector"),
                     GitPickerTab::Stash => key_context.add("StashList"),
+                    GitPickerTab::Tags => key_context.add("TagList"),
+                    GitPickerTab::Reflog => key_context.add("ReflogList"),
                 }
                 key_context
             })
@@ -529,6 +718,18 @@ impl Render for GitPicker {
                 this.focus_active_picker(window, cx);
                 cx.notify();
             }))
+            .on_action(cx.listener(|this, _: &ActivateTagsTab, window, cx| {
+                this.tab = GitPickerTab::Tags;
+                this.ensure_active_picker(window, cx);
+                this.focus_active_picker(window, cx);
+                cx.notify();
+            }))
+            .on_action(cx.listener(|this, _: &ActivateReflogTab, window, cx| {
+                this.tab = GitPickerTab::Reflog;
+                this.ensure_active_picker(window, cx);
+                this.focus_active_picker(window, cx);
+                cx.notify();
+            }))
             .on_modifiers_changed(cx.listener(Self::handle_modifiers_changed))
             .when(self.tab == GitPickerTab::Branches, |el| {
                 el.on_action(cx.listener(Self::handle_delete_branch))
@@ -543,6 +744,14 @@ impl Render for GitPicker {
                 el.on_action(cx.listener(Self::handle_drop_stash))
                     .on_action(cx.listener(Self::handle_show_stash))
             })
+            .when(self.tab == GitPickerTab::Tags, |el| {
+                el.on_action(cx.listener(Self::handle_delete_tag))
+                    .on_action(cx.listener(Self::handle_push_tag))
+            })
+            .when(self.tab == GitPickerTab::Reflog, |el| {
+                el.on_action(cx.listener(Self::handle_reset_to_entry))
+                    .on_action(cx.listener(Self::handle_create_branch_from_entry))
+            })
             .child(self.render_tab_bar(cx))
             .child(self.render_active_picker(window, cx))
     }
@@ -575,6 +784,24 @@ pub fn open_stash(
     open_with_tab(workspace, GitPickerTab::Stash, window, cx);
 }
 
+pub fn open_tags(
+    workspace: &mut Workspace,
+    _: &zed_actions::git::ViewTags,
+    window: &mut Window,
+    cx: &mut Context<Workspace>,
+) {
+    open_with_tab(workspace, GitPickerTab::Tags, window, cx);
+}
+
+pub fn open_reflog(
+    workspace: &mut Workspace,
+    _: &zed_actions::git::ViewReflog,
+    window: &mut Window,
+    cx: &mut Context<Workspace>,
+) {
+    open_with_tab(workspace, GitPickerTab::Reflog, window, cx);
+}
+
 fn open_with_tab(
     workspace: &mut Workspace,
     tab: GitPickerTab,
@@ -623,4 +850,10 @@ pub fn register(workspace: &mut Workspace) {
     workspace.register_action(|workspace, _: &zed_actions::git::ViewStash, window, cx| {
         open_with_tab(workspace, GitPickerTab::Stash, window, cx);
     });
+    workspace.register_action(|workspace, _: &zed_actions::git::ViewTags, window, cx| {
+        open_with_tab(workspace, GitPickerTab::Tags, window, cx);
+    });
+    workspace.register_action(|workspace, _: &zed_actions::git::ViewReflog, window, cx| {
+        open_with_tab(workspace, GitPickerTab::Reflog, window, cx);
+    });
 }
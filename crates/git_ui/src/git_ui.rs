@@ -28,6 +28,7 @@ use zed_actions;
 use crate::{git_panel::GitPanel, text_diff_view::TextDiffView};
 
 mod askpass_modal;
+pub mod bisect;
 pub mod branch_picker;
 mod commit_modal;
 pub mod commit_tooltip;
@@ -38,12 +39,15 @@ pub mod file_history_view;
 pub mod git_panel;
 mod git_panel_settings;
 pub mod git_picker;
+pub mod interactive_rebase;
 pub mod multi_diff_view;
 pub mod picker_prompt;
 pub mod project_diff;
+pub mod reflog_picker;
 pub(crate) mod remote_output;
 pub mod repository_selector;
 pub mod stash_picker;
+pub mod tag_picker;
 pub mod text_diff_view;
 pub mod worktree_picker;
 
@@ -133,6 +137,22 @@ pub fn init(cx: &mut App) {
                     panel.pull(true, window, cx);
                 });
             });
+            workspace.register_action(|workspace, _: &git::InitSubmodules, window, cx| {
+                let Some(panel) = workspace.panel::<git_panel::GitPanel>(cx) else {
+                    return;
+                };
+                panel.update(cx, |panel, cx| {
+                    panel.update_submodules(Vec::new(), true, window, cx);
+                });
+            });
+            workspace.register_action(|workspace, _: &git::UpdateSubmodules, window, cx| {
+                let Some(panel) = workspace.panel::<git_panel::GitPanel>(cx) else {
+                    return;
+                };
+                panel.update(cx, |panel, cx| {
+                    panel.update_submodules(Vec::new(), false, window, cx);
+                });
+            });
         }
         workspace.register_action(|workspace, action: &git::StashAll, window, cx| {
             let Some(panel) = workspace.panel::<git_panel::GitPanel>(cx) else {
@@ -182,6 +202,36 @@ pub fn init(cx: &mut App) {
                 panel.uncommit(window, cx);
             })
         });
+        workspace.register_action(|workspace, _: &git::ContinueOperation, window, cx| {
+            let Some(repo) = resolve_active_repository(workspace, cx) else {
+                return;
+            };
+            let continued = repo.update(cx, |repo, _| repo.continue_operation());
+            cx.spawn(async move |_, _| continued.await?)
+                .detach_and_prompt_err("Failed to continue", window, cx, |_, _, _| None);
+        });
+        workspace.register_action(|workspace, _: &git::AbortOperation, window, cx| {
+            let Some(repo) = resolve_active_repository(workspace, cx) else {
+                return;
+            };
+            let aborted = repo.update(cx, |repo, _| repo.abort_operation());
+            cx.spawn(async move |_, _| aborted.await?)
+                .detach_and_prompt_err("Failed to abort", window, cx, |_, _, _| None);
+        });
+        workspace.register_action(|workspace, _: &git::UndoLastOperation, window, cx| {
+            if let Some(repo) = resolve_active_repository(workspace, cx) {
+                reflog_picker::undo_last_operation(repo, window, cx);
+            }
+        });
+        bisect::register(workspace);
+        workspace.register_action(|workspace, _: &git::SyncSubmodules, _, cx| {
+            let Some(panel) = workspace.panel::<git_panel::GitPanel>(cx) else {
+                return;
+            };
+            panel.update(cx, |panel, cx| {
+                panel.sync_submodules(Vec::new(), cx);
+            });
+        });
         workspace.register_action(|workspace, _action: &git::Init, window, cx| {
             let Some(panel) = workspace.panel::<git_panel::GitPanel>(cx) else {
                 return;
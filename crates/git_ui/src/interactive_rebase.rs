@@ -0,0 +1,463 @@
+use anyhow::anyhow;
+use editor::Editor;
+use git::repository::{RebaseTodoAction, RebaseTodoEntry};
+use gpui::{
+    App, DismissEvent, Entity, EventEmitter, FocusHandle, Focusable, ScrollStrategy, Task,
+    UniformListScrollHandle, WeakEntity, Window, actions, uniform_list,
+};
+use menu::{Cancel, Confirm, SelectNext, SelectPrevious};
+use project::git_store::Repository;
+use ui::{
+    Chip, Divider, Headline, HeadlineSize, KeyBinding, ListItem, ListItemSpacing, Tooltip,
+    prelude::*,
+};
+use workspace::{ModalView, Workspace, notifications::DetachAndPromptErr};
+
+use crate::git_panel::show_error_toast;
+
+actions!(
+    interactive_rebase,
+    [
+        /// Moves the selected commit one line up in the rebase todo list.
+        MoveUp,
+        /// Moves the selected commit one line down in the rebase todo list.
+        MoveDown,
+        /// Keeps the selected commit as is.
+        PickCommit,
+        /// Keeps the selected commit, editing its message.
+        RewordCommit,
+        /// Melds the selected commit into the previous one, combining their messages.
+        SquashCommit,
+        /// Melds the selected commit into the previous one, discarding its message.
+        FixupCommit,
+        /// Removes the selected commit.
+        DropCommit,
+    ]
+);
+
+/// A modal to edit the todo list of an interactive rebase before running it.
+pub struct InteractiveRebaseModal {
+    repository: Entity<Repository>,
+    workspace: WeakEntity<Workspace>,
+    base: String,
+    entries: Vec<RebaseTodoEntry>,
+    selected_index: usize,
+    /// The entry, whose message is being edited in the message editor.
+    editing_index: Option<usize>,
+    message_editor: Entity<Editor>,
+    focus_handle: FocusHandle,
+    scroll_handle: UniformListScrollHandle,
+    _load_message: Option<Task<()>>,
+}
+
+impl InteractiveRebaseModal {
+    /// Loads the commits between `base` and HEAD and shows them in the modal.
+    pub fn open(
+        workspace: WeakEntity<Workspace>,
+        repository: Entity<Repository>,
+        base: String,
+        window: &mut Window,
+        cx: &mut App,
+    ) {
+        let todo = repository.update(cx, |repository, _| repository.rebase_todo(base.clone()));
+        window
+            .spawn(cx, async move |cx| {
+                let entries = todo.await??;
+                if entries.is_empty() {
+                    return Err(anyhow!("No commits to rebase onto {base}"));
+                }
+                workspace.update_in(cx, |workspace, window, cx| {
+                    let workspace_handle = workspace.weak_handle();
+                    workspace.toggle_modal(window, cx, |window, cx| {
+                        Self::new(workspace_handle, repository, base, entries, window, cx)
+                    });
+                })
+            })
+            .detach_and_prompt_err(
+                "Failed to start interactive rebase",
+                window,
+                cx,
+                |_, _, _| None,
+            );
+    }
+
+    fn new(
+        workspace: WeakEntity<Workspace>,
+        repository: Entity<Repository>,
+        base: String,
+        entries: Vec<RebaseTodoEntry>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Self {
+        let message_editor = cx.new(|cx| {
+            let mut editor = Editor::auto_height(3, 10, window, cx);
+            editor.set_placeholder_text("Commit message", window, cx);
+            editor
+        });
+        Self {
+            repository,
+            workspace,
+            base,
+            entries,
+            selected_index: 0,
+            editing_index: None,
+            message_editor,
+            focus_handle: cx.focus_handle(),
+            scroll_handle: UniformListScrollHandle::new(),
+            _load_message: None,
+        }
+    }
+
+    fn select_index(&mut self, ix: usize, cx: &mut Context<Self>) {
+        self.selected_index = ix.min(self.entries.len().saturating_sub(1));
+        self.scroll_handle
+            .scroll_to_item(self.selected_index, ScrollStrategy::Nearest);
+        cx.notify();
+    }
+
+    fn select_previous(&mut self, _: &SelectPrevious, _: &mut Window, cx: &mut Context<Self>) {
+        self.select_index(self.selected_index.saturating_sub(1), cx);
+    }
+
+    fn select_next(&mut self, _: &SelectNext, _: &mut Window, cx: &mut Context<Self>) {
+        self.select_index(self.selected_index + 1, cx);
+    }
+
+    fn move_up(&mut self, _: &MoveUp, window: &mut Window, cx: &mut Context<Self>) {
+        if self.selected_index == 0 {
+            return;
+        }
+        self.finish_editing_message(window, cx);
+        self.entries
+            .swap(self.selected_index, self.selected_index - 1);
+        self.select_index(self.selected_index - 1, cx);
+    }
+
+    fn move_down(&mut self, _: &MoveDown, window: &mut Window, cx: &mut Context<Self>) {
+        if self.selected_index + 1 >= self.entries.len() {
+            return;
+        }
+        self.finish_editing_message(window, cx);
+        self.entries
+            .swap(self.selected_index, self.selected_index + 1);
+        self.select_index(self.selected_index + 1, cx);
+    }
+
+    fn set_action(
+        &mut self,
+        action: RebaseTodoAction,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let ix = self.selected_index;
+        if action.melds_into_previous() && !self.has_previous_commit(ix) {
+            return;
+        }
+        let Some(entry) = self.entries.get_mut(ix) else {
+            return;
+        };
+        entry.action = action;
+        if action == RebaseTodoAction::Reword {
+            self.start_editing_message(ix, window, cx);
+        } else if self.editing_index == Some(ix) {
+            self.editing_index = None;
+            window.focus(&self.focus_handle, cx);
+        }
+        cx.notify();
+    }
+
+    /// Whether a commit is kept before the entry at `ix`, for it to be squashed or fixed up into.
+    fn has_previous_commit(&self, ix: usize) -> bool {
+        self.entries[..ix.min(self.entries.len())]
+            .iter()
+            .any(|entry| entry.action != RebaseTodoAction::Drop)
+    }
+
+    /// The first entry melding into a previous commit when there is none, which git rejects.
+    /// Moving or dropping entries can leave one in that position.
+    fn entry_without_previous_commit(&self) -> Option<usize> {
+        let ix = self
+            .entries
+            .iter()
+            .position(|entry| entry.action != RebaseTodoAction::Drop)?;
+        self.entries[ix].action.melds_into_previous().then_some(ix)
+    }
+
+    fn pick_commit(&mut self, _: &PickCommit, window: &mut Window, cx: &mut Context<Self>) {
+        self.set_action(RebaseTodoAction::Pick, window, cx);
+    }
+
+    fn reword_commit(&mut self, _: &RewordCommit, window: &mut Window, cx: &mut Context<Self>) {
+        self.set_action(RebaseTodoAction::Reword, window, cx);
+    }
+
+    fn squash_commit(&mut self, _: &SquashCommit, window: &mut Window, cx: &mut Context<Self>) {
+        self.set_action(RebaseTodoAction::Squash, window, cx);
+    }
+
+    fn fixup_commit(&mut self, _: &FixupCommit, window: &mut Window, cx: &mut Context<Self>) {
+        self.set_action(RebaseTodoAction::Fixup, window, cx);
+    }
+
+    fn drop_commit(&mut self, _: &DropCommit, window: &mut Window, cx: &mut Context<Self>) {
+        self.set_action(RebaseTodoAction::Drop, window, cx);
+    }
+
+    fn start_editing_message(&mut self, ix: usize, window: &mut Window, cx: &mut Context<Self>) {
+        self.finish_editing_message(window, cx);
+        let Some(entry) = self.entries.get(ix).cloned() else {
+            return;
+        };
+        self.editing_index = Some(ix);
+        let message = entry
+            .message
+            .clone()
+            .unwrap_or_else(|| entry.subject.clone());
+        self.message_editor.update(cx, |editor, cx| {
+            editor.set_text(message.as_ref(), window, cx);
+        });
+        window.focus(&self.message_editor.focus_handle(cx), cx);
+
+        // The todo list only has commit subjects, load the full message to edit.
+        if entry.message.is_none() {
+            let details = self
+                .repository
+                .update(cx, |repository, _| repository.show(entry.sha.to_string()));
+            self._load_message = Some(cx.spawn_in(window, async move |this, cx| {
+                let Ok(Ok(details)) = details.await else {
+                    return;
+                };
+                this.update_in(cx, |this, window, cx| {
+                    if this.editing_index != Some(ix) {
+                        return;
+                    }
+                    this.message_editor.update(cx, |editor, cx| {
+                        if editor.text(cx).trim() == details.message.lines().next().unwrap_or("") {
+                            editor.set_text(details.message.trim_end(), window, cx);
+                        }
+                    });
+                })
+                .ok();
+            }));
+        }
+    }
+
+    fn finish_editing_message(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        let Some(ix) = self.editing_index.take() else {
+            return;
+        };
+        let message = self.message_editor.read(cx).text(cx);
+        if let Some(entry) = self.entries.get_mut(ix) {
+            entry.message = Some(message.trim_end().to_string().into());
+        }
+        window.focus(&self.focus_handle, cx);
+        cx.notify();
+    }
+
+    fn cancel(&mut self, _: &Cancel, window: &mut Window, cx: &mut Context<Self>) {
+        if self.editing_index.is_some() {
+            self.finish_editing_message(window, cx);
+        } else {
+            cx.emit(DismissEvent);
+        }
+    }
+
+    fn confirm(&mut self, _: &Confirm, window: &mut Window, cx: &mut Context<Self>) {
+        self.start_rebase(window, cx);
+    }
+
+    fn start_rebase(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        self.finish_editing_message(window, cx);
+        if let Some(ix) = self.entry_without_previous_commit() {
+            self.select_index(ix, cx);
+            return;
+        }
+        let base = self.base.clone();
+        let todo = self.entries.clone();
+        let workspace = self.workspace.clone();
+        let rebase = self
+            .repository
+            .update(cx, |repository, _| repository.rebase(base, todo));
+        cx.spawn(async move |_, cx| {
+            let result = match rebase.await {
+                Ok(result) => result,
+                Err(_) => Err(anyhow!("Operation was canceled")),
+            };
+            if let Err(error) = result {
+                cx.update(|cx| {
+                    if let Some(workspace) = workspace.upgrade() {
+                        show_error_toast(workspace, "rebase", error, cx);
+                    }
+                });
+            }
+        })
+        .detach();
+        cx.emit(DismissEvent);
+    }
+
+    fn render_entry(&self, ix: usize, cx: &mut Context<Self>) -> ListItem {
+        let entry = &self.entries[ix];
+        let action_color = match entry.action {
+            RebaseTodoAction::Pick => Color::Default,
+            RebaseTodoAction::Reword => Color::Accent,
+            RebaseTodoAction::Squash | RebaseTodoAction::Fixup => Color::Warning,
+            RebaseTodoAction::Drop => Color::Error,
+        };
+        let subject = entry
+            .message
+            .as_ref()
+            .filter(|_| entry.action == RebaseTodoAction::Reword)
+            .and_then(|message| message.lines().next())
+            .map(|line| SharedString::from(line.to_string()))
+            .unwrap_or_else(|| entry.subject.clone());
+        let short_sha = entry
+            .sha
+            .get(..git::SHORT_SHA_LENGTH)
+            .unwrap_or(&entry.sha)
+            .to_string();
+
+        ListItem::new(("rebase-todo-entry", ix))
+            .inset(true)
+            .spacing(ListItemSpacing::Sparse)
+            .toggle_state(ix == self.selected_index)
+            .on_click(cx.listener(move |this, _, _, cx| this.select_index(ix, cx)))
+            .child(
+                h_flex()
+                    .w_full()
+                    .gap_2()
+                    .child(
+                        div().w(rems(4.)).child(
+                            Label::new(entry.action.as_str())
+                                .size(LabelSize::Small)
+                                .color(action_color),
+                        ),
+                    )
+                    .child(Chip::new(short_sha).label_size(LabelSize::Small))
+                    .child(
+                        Label::new(subject)
+                            .truncate()
+                            .when(entry.action == RebaseTodoAction::Drop, |label| {
+                                label.strikethrough().color(Color::Muted)
+                            }),
+                    ),
+            )
+    }
+
+    fn render_footer(&self, cx: &mut Context<Self>) -> impl IntoElement {
+        let focus_handle = self.focus_handle.clone();
+        let can_meld = self.has_previous_commit(self.selected_index);
+        let invalid_entry = self.entry_without_previous_commit();
+        let action_button = |id: &'static str, label: &'static str, action: &dyn gpui::Action| {
+            Button::new(id, label)
+                .key_binding(
+                    KeyBinding::for_action_in(action, &focus_handle, cx)
+                        .map(|kb| kb.size(rems_from_px(12.))),
+                )
+                .on_click({
+                    let action = action.boxed_clone();
+                    move |_, window, cx| window.dispatch_action(action.boxed_clone(), cx)
+                })
+        };
+
+        h_flex()
+            .w_full()
+            .p_1p5()
+            .gap_0p5()
+            .justify_end()
+            .flex_wrap()
+            .border_t_1()
+            .border_color(cx.theme().colors().border_variant)
+            .child(action_button("rebase-pick", "Pick", &PickCommit))
+            .child(action_button("rebase-reword", "Reword", &RewordCommit))
+            .child(action_button("rebase-squash", "Squash", &SquashCommit).disabled(!can_meld))
+            .child(action_button("rebase-fixup", "Fixup", &FixupCommit).disabled(!can_meld))
+            .child(action_button("rebase-drop", "Drop", &DropCommit))
+            .child(
+                Button::new("start-rebase", "Rebase")
+                    .key_binding(
+                        KeyBinding::for_action_in(&Confirm, &self.focus_handle, cx)
+                            .map(|kb| kb.size(rems_from_px(12.))),
+                    )
+                    .disabled(invalid_entry.is_some())
+                    .when(invalid_entry.is_some(), |button| {
+                        button.tooltip(Tooltip::text(
+                            "The first commit can't be squashed or fixed up",
+                        ))
+                    })
+                    .on_click(cx.listener(|this, _, window, cx| this.start_rebase(window, cx))),
+            )
+    }
+}
+
+impl EventEmitter<DismissEvent> for InteractiveRebaseModal {}
+
+impl ModalView for InteractiveRebaseModal {}
+
+impl Focusable for InteractiveRebaseModal {
+    fn focus_handle(&self, _: &App) -> FocusHandle {
+        self.focus_handle.clone()
+    }
+}
+
+impl Render for InteractiveRebaseModal {
+    fn render(&mut self, _: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        let entry_count = self.entries.len();
+        v_flex()
+            .key_context("InteractiveRebaseModal")
+            .on_action(cx.listener(Self::cancel))
+            .elevation_2(cx)
+            .w(rems(40.))
+            .child(
+                h_flex()
+                    .px_3()
+                    .pt_2()
+                    .pb_1()
+                    .w_full()
+                    .gap_1p5()
+                    .child(Icon::new(IconName::GitBranch).size(IconSize::XSmall))
+                    .child(
+                        Headline::new(format!("Interactive Rebase onto {}", self.base))
+                            .size(HeadlineSize::XSmall),
+                    ),
+            )
+            .child(
+                v_flex()
+                    .key_context("InteractiveRebaseList")
+                    .track_focus(&self.focus_handle)
+                    .on_action(cx.listener(Self::select_previous))
+                    .on_action(cx.listener(Self::select_next))
+                    .on_action(cx.listener(Self::move_up))
+                    .on_action(cx.listener(Self::move_down))
+                    .on_action(cx.listener(Self::pick_commit))
+                    .on_action(cx.listener(Self::reword_commit))
+                    .on_action(cx.listener(Self::squash_commit))
+                    .on_action(cx.listener(Self::fixup_commit))
+                    .on_action(cx.listener(Self::drop_commit))
+                    .on_action(cx.listener(Self::confirm))
+                    .py_1()
+                    .h(rems(20.))
+                    .child(
+                        uniform_list(
+                            "rebase-todo-entries",
+                            entry_count,
+                            cx.processor(|this, range: std::ops::Range<usize>, _, cx| {
+                                range.map(|ix| this.render_entry(ix, cx)).collect()
+                            }),
+                        )
+                        .track_scroll(&self.scroll_handle)
+                        .size_full(),
+                    ),
+            )
+            .when(self.editing_index.is_some(), |this| {
+                this.child(Divider::horizontal()).child(
+                    div()
+                        .key_context("InteractiveRebaseMessage")
+                        .px_3()
+                        .py_2()
+                        .w_full()
+                        .child(self.message_editor.clone()),
+                )
+            })
+            .child(self.render_footer(cx))
+    }
+}
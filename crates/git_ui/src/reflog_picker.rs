@@ -0,0 +1,673 @@
+use anyhow::{Context as _, anyhow};
+use editor::Editor;
+use fuzzy::StringMatchCandidate;
+use git::repository::{ReflogEntry, ResetMode};
+use gpui::{
+    Action, AnyElement, App, Context, DismissEvent, Entity, EventEmitter, FocusHandle, Focusable,
+    InteractiveElement, IntoElement, ParentElement, PromptLevel, Render, SharedString, Styled,
+    Subscription, Task, WeakEntity, Window, actions,
+};
+use menu::{Cancel, Confirm};
+use picker::{Picker, PickerDelegate};
+use project::git_store::{Repository, RepositoryEvent};
+use std::sync::Arc;
+use time::{OffsetDateTime, UtcOffset};
+use ui::{
+    Headline, HeadlineSize, HighlightedLabel, KeyBinding, ListItem, ListItemSpacing, Tooltip,
+    prelude::*,
+};
+use util::ResultExt;
+use workspace::notifications::DetachAndPromptErr;
+use workspace::{ModalView, Workspace};
+
+use crate::commit_view::CommitView;
+use crate::reflog_picker;
+
+actions!(
+    reflog_picker,
+    [
+        /// Resets the current branch to the selected reflog entry, keeping uncommitted changes.
+        ResetToEntry,
+        /// Creates a branch at the selected reflog entry.
+        CreateBranchFromEntry,
+    ]
+);
+
+/// How many reflog entries to list.
+const REFLOG_LIMIT: usize = 500;
+
+/// Undoes the last operation that moved the current branch, or HEAD when it's detached,
+/// by resetting it to where it was before.
+///
+/// Commits and amends are undone with their changes left staged. Other operations, such
+/// as resets and rebases, are undone along with their changes to the working tree.
+pub fn undo_last_operation(repository: Entity<Repository>, window: &mut Window, cx: &mut App) {
+    let branch = repository
+        .read(cx)
+        .branch
+        .as_ref()
+        .map(|branch| branch.ref_name.to_string());
+    let is_on_branch = branch.is_some();
+    let ref_name = branch.unwrap_or_else(|| "HEAD".to_string());
+    let reflog = repository.update(cx, |repository, _| repository.reflog(ref_name.clone(), 2));
+
+    window
+        .spawn(cx, async move |cx| {
+            let entries = reflog.await??;
+            let [last, previous] = entries.as_slice() else {
+                anyhow::bail!("There is no operation to undo");
+            };
+            let mode = undo_mode(last, is_on_branch)
+                .with_context(|| format!("\"{}\" can't be undone", last.message))?;
+
+            let short_sha = previous
+                .sha
+                .get(..git::SHORT_SHA_LENGTH)
+                .unwrap_or(&previous.sha);
+            let ref_name = ref_name.strip_prefix("refs/heads/").unwrap_or(&ref_name);
+            let detail = match mode {
+                ResetMode::Soft => {
+                    format!("{ref_name} will move back to {short_sha}, with the commit's changes staged.")
+                }
+                _ => format!("{ref_name} will move back to {short_sha}, keeping your changes."),
+            };
+            let answer = cx.update(|window, cx| {
+                window.prompt(
+                    PromptLevel::Info,
+                    &format!("Undo \"{}\"?", last.message),
+                    Some(&detail),
+                    &["Undo", "Cancel"],
+                    cx,
+                )
+            })?;
+            if answer.await? != 0 {
+                return Ok(());
+            }
+
+            let sha = previous.sha.to_string();
+            repository
+                .update(cx, |repository, cx| repository.reset(sha, mode, cx))
+                .await??;
+            anyhow::Ok(())
+        })
+        .detach_and_prompt_err("Failed to undo", window, cx, |e, _, _| Some(e.to_string()));
+}
+
+/// How to undo the operation recorded in a reflog entry, if it can be undone.
+fn undo_mode(entry: &ReflogEntry, is_on_branch: bool) -> Option<ResetMode> {
+    match entry.command() {
+        "commit" | "commit (amend)" => Some(ResetMode::Soft),
+        "reset" | "commit (merge)" | "cherry-pick" | "revert" => Some(ResetMode::Keep),
+        // A detached HEAD records each step of these operations, so its previous entry
+        // isn't where it was before the operation.
+        command
+            if is_on_branch
+                && (command.starts_with("rebase")
+                    || command.starts_with("merge")
+                    || command.starts_with("pull")) =>
+        {
+            Some(ResetMode::Keep)
+        }
+        _ => None,
+    }
+}
+
+pub fn create_embedded(
+    repository: Option<Entity<Repository>>,
+    workspace: WeakEntity<Workspace>,
+    width: Rems,
+    window: &mut Window,
+    cx: &mut Context<ReflogList>,
+) -> ReflogList {
+    ReflogList::new(repository, workspace, width, window, cx)
+}
+
+pub struct ReflogList {
+    width: Rems,
+    pub picker: Entity<Picker<ReflogListDelegate>>,
+    picker_focus_handle: FocusHandle,
+    _subscriptions: Vec<Subscription>,
+}
+
+impl ReflogList {
+    fn new(
+        repository: Option<Entity<Repository>>,
+        workspace: WeakEntity<Workspace>,
+        width: Rems,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Self {
+        let delegate = ReflogListDelegate::new(repository.clone(), workspace, cx);
+        let picker = cx.new(|cx| {
+            Picker::uniform_list(delegate, window, cx)
+                .show_scrollbar(true)
+                .modal(false)
+        });
+        let picker_focus_handle = picker.focus_handle(cx);
+        picker.update(cx, |picker, _| {
+            picker.delegate.focus_handle = picker_focus_handle.clone();
+        });
+
+        let mut _subscriptions = vec![cx.subscribe(&picker, |_, _, _, cx| {
+            cx.emit(DismissEvent);
+        })];
+        if let Some(repository) = repository {
+            _subscriptions.push(cx.subscribe_in(
+                &repository,
+                window,
+                |this, _, event: &RepositoryEvent, window, cx| {
+                    if matches!(event, RepositoryEvent::BranchChanged) {
+                        this.reload_entries(window, cx);
+                    }
+                },
+            ));
+        }
+
+        let mut this = Self {
+            width,
+            picker,
+            picker_focus_handle,
+            _subscriptions,
+        };
+        this.reload_entries(window, cx);
+        this
+    }
+
+    fn reload_entries(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        let Some(repository) = self.picker.read(cx).delegate.repo.clone() else {
+            return;
+        };
+        let entries = repository.update(cx, |repository, _| {
+            repository.reflog("HEAD".to_string(), REFLOG_LIMIT)
+        });
+        cx.spawn_in(window, async move |this, cx| {
+            let entries = entries.await??;
+            this.update_in(cx, |this, window, cx| {
+                this.picker.update(cx, |picker, cx| {
+                    picker.delegate.all_entries = Some(entries);
+                    picker.refresh(window, cx);
+                })
+            })?;
+            anyhow::Ok(())
+        })
+        .detach_and_log_err(cx);
+    }
+
+    pub fn handle_reset_to_entry(
+        &mut self,
+        _: &ResetToEntry,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        self.picker.update(cx, |picker, cx| {
+            picker
+                .delegate
+                .reset_to_entry_at(picker.delegate.selected_index(), window, cx);
+        });
+    }
+
+    pub fn handle_create_branch_from_entry(
+        &mut self,
+        _: &CreateBranchFromEntry,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        self.picker.update(cx, |picker, cx| {
+            picker
+                .delegate
+                .create_branch_at(picker.delegate.selected_index(), window, cx);
+        });
+    }
+}
+
+impl EventEmitter<DismissEvent> for ReflogList {}
+
+impl Focusable for ReflogList {
+    fn focus_handle(&self, _: &App) -> FocusHandle {
+        self.picker_focus_handle.clone()
+    }
+}
+
+impl Render for ReflogList {
+    fn render(&mut self, _: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        v_flex()
+            .key_context("ReflogList")
+            .w(self.width)
+            .on_action(cx.listener(Self::handle_reset_to_entry))
+            .on_action(cx.listener(Self::handle_create_branch_from_entry))
+            .child(self.picker.clone())
+    }
+}
+
+#[derive(Debug, Clone)]
+struct ReflogEntryMatch {
+    entry: ReflogEntry,
+    positions: Vec<usize>,
+    formatted_timestamp: String,
+}
+
+pub struct ReflogListDelegate {
+    matches: Vec<ReflogEntryMatch>,
+    all_entries: Option<Vec<ReflogEntry>>,
+    repo: Option<Entity<Repository>>,
+    workspace: WeakEntity<Workspace>,
+    selected_index: usize,
+    focus_handle: FocusHandle,
+    timezone: UtcOffset,
+}
+
+impl ReflogListDelegate {
+    fn new(
+        repo: Option<Entity<Repository>>,
+        workspace: WeakEntity<Workspace>,
+        cx: &mut Context<ReflogList>,
+    ) -> Self {
+        Self {
+            matches: Vec::new(),
+            all_entries: None,
+            repo,
+            workspace,
+            selected_index: 0,
+            focus_handle: cx.focus_handle(),
+            timezone: UtcOffset::current_local_offset().unwrap_or(UtcOffset::UTC),
+        }
+    }
+
+    fn format_timestamp(timestamp: i64, timezone: UtcOffset) -> String {
+        let timestamp =
+            OffsetDateTime::from_unix_timestamp(timestamp).unwrap_or(OffsetDateTime::now_utc());
+        time_format::format_localized_timestamp(
+            timestamp,
+            OffsetDateTime::now_utc(),
+            timezone,
+            time_format::TimestampFormat::EnhancedAbsolute,
+        )
+    }
+
+    fn entry_at(&self, ix: usize) -> Option<&ReflogEntry> {
+        self.matches.get(ix).map(|entry_match| &entry_match.entry)
+    }
+
+    fn reset_to_entry_at(&self, ix: usize, window: &mut Window, cx: &mut Context<Picker<Self>>) {
+        let Some(entry) = self.entry_at(ix) else {
+            return;
+        };
+        let Some(repo) = self.repo.clone() else {
+            return;
+        };
+        let sha = entry.sha.to_string();
+
+        cx.spawn(async move |_, cx| {
+            repo.update(cx, |repo, cx| repo.reset(sha, ResetMode::Keep, cx))
+                .await??;
+            Ok(())
+        })
+        .detach_and_prompt_err("Failed to reset", window, cx, |e, _, _| Some(e.to_string()));
+        cx.emit(DismissEvent);
+    }
+
+    fn create_branch_at(&self, ix: usize, window: &mut Window, cx: &mut Context<Picker<Self>>) {
+        let Some(entry) = self.entry_at(ix) else {
+            return;
+        };
+        let Some(repo) = self.repo.clone() else {
+            return;
+        };
+        let sha = entry.sha.clone();
+        let workspace = self.workspace.clone();
+        window.defer(cx, move |window, cx| {
+            workspace
+                .update(cx, |workspace, cx| {
+                    CreateBranchModal::toggle(workspace, repo, sha, window, cx);
+                })
+                .ok();
+        });
+    }
+
+    fn show_entry_at(&self, ix: usize, window: &mut Window, cx: &mut Context<Picker<Self>>) {
+        let Some(entry) = self.entry_at(ix) else {
+            return;
+        };
+        let Some(repo) = self.repo.clone() else {
+            return;
+        };
+        CommitView::open(
+            entry.sha.to_string(),
+            repo.downgrade(),
+            self.workspace.clone(),
+            None,
+            None,
+            window,
+            cx,
+        );
+        cx.emit(DismissEvent);
+    }
+}
+
+impl PickerDelegate for ReflogListDelegate {
+    type ListItem = ListItem;
+
+    fn placeholder_text(&self, _window: &mut Window, _cx: &mut App) -> Arc<str> {
+        "Search the reflog…".into()
+    }
+
+    fn match_count(&self) -> usize {
+        self.matches.len()
+    }
+
+    fn selected_index(&self) -> usize {
+        self.selected_index
+    }
+
+    fn set_selected_index(
+        &mut self,
+        ix: usize,
+        _window: &mut Window,
+        _: &mut Context<Picker<Self>>,
+    ) {
+        self.selected_index = ix;
+    }
+
+    fn update_matches(
+        &mut self,
+        query: String,
+        window: &mut Window,
+        cx: &mut Context<Picker<Self>>,
+    ) -> Task<()> {
+        let Some(all_entries) = self.all_entries.clone() else {
+            return Task::ready(());
+        };
+        let timezone = self.timezone;
+
+        cx.spawn_in(window, async move |picker, cx| {
+            let matches: Vec<ReflogEntryMatch> = if query.is_empty() {
+                all_entries
+                    .into_iter()
+                    .map(|entry| ReflogEntryMatch {
+                        formatted_timestamp: Self::format_timestamp(entry.timestamp, timezone),
+                        entry,
+                        positions: Vec::new(),
+                    })
+                    .collect()
+            } else {
+                let candidates = all_entries
+                    .iter()
+                    .enumerate()
+                    .map(|(ix, entry)| StringMatchCandidate::new(ix, &entry.message))
+                    .collect::<Vec<StringMatchCandidate>>();
+                fuzzy::match_strings(
+                    &candidates,
+                    &query,
+                    false,
+                    true,
+                    10000,
+                    &Default::default(),
+                    cx.background_executor().clone(),
+                )
+                .await
+                .into_iter()
+                .map(|candidate| {
+                    let entry = all_entries[candidate.candidate_id].clone();
+                    ReflogEntryMatch {
+                        formatted_timestamp: Self::format_timestamp(entry.timestamp, timezone),
+                        entry,
+                        positions: candidate.positions,
+                    }
+                })
+                .collect()
+            };
+
+            picker
+                .update(cx, |picker, _| {
+                    let delegate = &mut picker.delegate;
+                    delegate.matches = matches;
+                    if delegate.matches.is_empty() {
+                        delegate.selected_index = 0;
+                    } else {
+                        delegate.selected_index =
+                            core::cmp::min(delegate.selected_index, delegate.matches.len() - 1);
+                    }
+                })
+                .log_err();
+        })
+    }
+
+    fn confirm(&mut self, _secondary: bool, window: &mut Window, cx: &mut Context<Picker<Self>>) {
+        self.show_entry_at(self.selected_index(), window, cx);
+    }
+
+    fn dismissed(&mut self, _: &mut Window, cx: &mut Context<Picker<Self>>) {
+        cx.emit(DismissEvent);
+    }
+
+    fn render_match(
+        &self,
+        ix: usize,
+        selected: bool,
+        _window: &mut Window,
+        _cx: &mut Context<Picker<Self>>,
+    ) -> Option<Self::ListItem> {
+        let entry_match = self.matches.get(ix)?;
+        let entry = &entry_match.entry;
+
+        let short_sha = entry
+            .sha
+            .get(..git::SHORT_SHA_LENGTH)
+            .unwrap_or(&entry.sha)
+            .to_string();
+        let details = h_flex()
+            .gap_1p5()
+            .w_full()
+            .child(
+                Label::new(entry.selector.clone())
+                    .color(Color::Muted)
+                    .size(LabelSize::Small),
+            )
+            .child(
+                Label::new("•")
+                    .alpha(0.5)
+                    .color(Color::Muted)
+                    .size(LabelSize::Small),
+            )
+            .child(
+                Label::new(short_sha)
+                    .color(Color::Muted)
+                    .size(LabelSize::Small),
+            )
+            .child(
+                Label::new("•")
+                    .alpha(0.5)
+                    .color(Color::Muted)
+                    .size(LabelSize::Small),
+            )
+            .child(
+                Label::new(entry_match.formatted_timestamp.clone())
+                    .color(Color::Muted)
+                    .size(LabelSize::Small),
+            );
+
+        Some(
+            ListItem::new(format!("reflog-{ix}"))
+                .inset(true)
+                .spacing(ListItemSpacing::Sparse)
+                .toggle_state(selected)
+                .child(
+                    v_flex()
+                        .w_full()
+                        .child(
+                            HighlightedLabel::new(
+                                entry.message.clone(),
+                                entry_match.positions.clone(),
+                            )
+                            .truncate(),
+                        )
+                        .child(details),
+                )
+                .tooltip(Tooltip::text(entry.sha.clone())),
+        )
+    }
+
+    fn no_matches_text(&self, _window: &mut Window, _cx: &mut App) -> Option<SharedString> {
+        Some("No reflog entries found".into())
+    }
+
+    fn render_footer(&self, _: &mut Window, cx: &mut Context<Picker<Self>>) -> Option<AnyElement> {
+        if self.matches.is_empty() {
+            return None;
+        }
+        let focus_handle = self.focus_handle.clone();
+
+        Some(
+            h_flex()
+                .w_full()
+                .p_1p5()
+                .gap_0p5()
+                .justify_end()
+                .flex_wrap()
+                .border_t_1()
+                .border_color(cx.theme().colors().border_variant)
+                .child(
+                    Button::new("create-branch-from-entry", "Create Branch")
+                        .key_binding(
+                            KeyBinding::for_action_in(
+                                &reflog_picker::CreateBranchFromEntry,
+                                &focus_handle,
+                                cx,
+                            )
+                            .map(|kb| kb.size(rems_from_px(12.))),
+                        )
+                        .on_click(|_, window, cx| {
+                            window.dispatch_action(
+                                reflog_picker::CreateBranchFromEntry.boxed_clone(),
+                                cx,
+                            )
+                        }),
+                )
+                .child(
+                    Button::new("reset-to-entry", "Reset")
+                        .key_binding(
+                            KeyBinding::for_action_in(
+                                &reflog_picker::ResetToEntry,
+                                &focus_handle,
+                                cx,
+                            )
+                            .map(|kb| kb.size(rems_from_px(12.))),
+                        )
+                        .on_click(|_, window, cx| {
+                            window.dispatch_action(reflog_picker::ResetToEntry.boxed_clone(), cx)
+                        }),
+                )
+                .child(
+                    Button::new("view-entry", "View")
+                        .key_binding(
+                            KeyBinding::for_action_in(&menu::Confirm, &focus_handle, cx)
+                                .map(|kb| kb.size(rems_from_px(12.))),
+                        )
+                        .on_click(|_, window, cx| {
+                            window.dispatch_action(menu::Confirm.boxed_clone(), cx)
+                        }),
+                )
+                .into_any(),
+        )
+    }
+}
+
+/// A modal to create and switch to a branch starting at a given commit.
+pub struct CreateBranchModal {
+    repository: Entity<Repository>,
+    target: SharedString,
+    name_editor: Entity<Editor>,
+}
+
+impl CreateBranchModal {
+    pub fn toggle(
+        workspace: &mut Workspace,
+        repository: Entity<Repository>,
+        target: SharedString,
+        window: &mut Window,
+        cx: &mut Context<Workspace>,
+    ) {
+        workspace.toggle_modal(window, cx, |window, cx| {
+            Self::new(repository, target, window, cx)
+        });
+    }
+
+    fn new(
+        repository: Entity<Repository>,
+        target: SharedString,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Self {
+        let name_editor = cx.new(|cx| {
+            let mut editor = Editor::single_line(window, cx);
+            editor.set_placeholder_text("Branch name", window, cx);
+            editor
+        });
+        Self {
+            repository,
+            target,
+            name_editor,
+        }
+    }
+
+    fn cancel(&mut self, _: &Cancel, _: &mut Window, cx: &mut Context<Self>) {
+        cx.emit(DismissEvent);
+    }
+
+    fn confirm(&mut self, _: &Confirm, window: &mut Window, cx: &mut Context<Self>) {
+        let name = self.name_editor.read(cx).text(cx).trim().replace(' ', "-");
+        if name.is_empty() {
+            return;
+        }
+        let target = self.target.to_string();
+
+        let create_branch = self.repository.update(cx, |repository, _| {
+            repository.create_branch(name, Some(target))
+        });
+        cx.spawn(async move |_, _| match create_branch.await {
+            Ok(result) => result,
+            Err(_) => Err(anyhow!("Operation was canceled")),
+        })
+        .detach_and_prompt_err("Failed to create branch", window, cx, |_, _, _| None);
+        cx.emit(DismissEvent);
+    }
+}
+
+impl EventEmitter<DismissEvent> for CreateBranchModal {}
+
+impl ModalView for CreateBranchModal {}
+
+impl Focusable for CreateBranchModal {
+    fn focus_handle(&self, cx: &App) -> FocusHandle {
+        self.name_editor.focus_handle(cx)
+    }
+}
+
+impl Render for CreateBranchModal {
+    fn render(&mut self, _: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        let title = format!(
+            "Create Branch at {}",
+            self.target
+                .get(..git::SHORT_SHA_LENGTH)
+                .unwrap_or(&self.target)
+        );
+
+        v_flex()
+            .key_context("CreateBranchModal")
+            .on_action(cx.listener(Self::cancel))
+            .on_action(cx.listener(Self::confirm))
+            .elevation_2(cx)
+            .w(rems(34.))
+            .child(
+                h_flex()
+                    .px_3()
+                    .pt_2()
+                    .pb_1()
+                    .w_full()
+                    .gap_1p5()
+                    .child(Icon::new(IconName::GitBranch).size(IconSize::XSmall))
+                    .child(Headline::new(title).size(HeadlineSize::XSmall)),
+            )
+            .child(div().px_3().pb_3().w_full().child(self.name_editor.clone()))
+    }
+}
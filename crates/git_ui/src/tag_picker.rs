@@ -0,0 +1,631 @@
+use anyhow::anyhow;
+use editor::Editor;
+use fuzzy::StringMatchCandidate;
+use git::repository::Tag;
+use gpui::{
+    Action, AnyElement, App, Context, DismissEvent, Entity, EventEmitter, FocusHandle, Focusable,
+    InteractiveElement, IntoElement, ParentElement, Render, SharedString, Styled, Subscription,
+    Task, WeakEntity, Window, actions,
+};
+use menu::{Cancel, Confirm};
+use picker::{Picker, PickerDelegate};
+use project::git_store::{Repository, RepositoryEvent};
+use std::sync::Arc;
+use ui::{
+    Headline, HeadlineSize, HighlightedLabel, KeyBinding, ListItem, ListItemSpacing, Tooltip,
+    prelude::*,
+};
+use util::ResultExt;
+use workspace::notifications::DetachAndPromptErr;
+use workspace::{ModalView, Workspace};
+
+use crate::commit_view::CommitView;
+use crate::git_panel::GitPanel;
+use crate::tag_picker;
+
+actions!(
+    tag_picker,
+    [
+        /// Deletes the selected tag.
+        DeleteTag,
+        /// Pushes the selected tag to a remote.
+        PushTag,
+    ]
+);
+
+pub fn create_embedded(
+    repository: Option<Entity<Repository>>,
+    workspace: WeakEntity<Workspace>,
+    width: Rems,
+    window: &mut Window,
+    cx: &mut Context<TagList>,
+) -> TagList {
+    TagList::new(repository, workspace, width, window, cx)
+}
+
+pub struct TagList {
+    width: Rems,
+    pub picker: Entity<Picker<TagListDelegate>>,
+    picker_focus_handle: FocusHandle,
+    _subscriptions: Vec<Subscription>,
+}
+
+impl TagList {
+    fn new(
+        repository: Option<Entity<Repository>>,
+        workspace: WeakEntity<Workspace>,
+        width: Rems,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Self {
+        let delegate = TagListDelegate::new(repository.clone(), workspace, cx);
+        let picker = cx.new(|cx| {
+            Picker::uniform_list(delegate, window, cx)
+                .show_scrollbar(true)
+                .modal(false)
+        });
+        let picker_focus_handle = picker.focus_handle(cx);
+        picker.update(cx, |picker, _| {
+            picker.delegate.focus_handle = picker_focus_handle.clone();
+        });
+
+        let mut _subscriptions = vec![cx.subscribe(&picker, |_, _, _, cx| {
+            cx.emit(DismissEvent);
+        })];
+        if let Some(repository) = repository {
+            // Tag changes are reported as branch changes, as both update the refs of the repository.
+            _subscriptions.push(cx.subscribe_in(
+                &repository,
+                window,
+                |this, _, event: &RepositoryEvent, window, cx| {
+                    if matches!(event, RepositoryEvent::BranchChanged) {
+                        this.reload_tags(window, cx);
+                    }
+                },
+            ));
+        }
+
+        let mut this = Self {
+            width,
+            picker,
+            picker_focus_handle,
+            _subscriptions,
+        };
+        this.reload_tags(window, cx);
+        this
+    }
+
+    fn reload_tags(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        let Some(repository) = self.picker.read(cx).delegate.repo.clone() else {
+            return;
+        };
+        let tags = repository.update(cx, |repository, _| repository.tags());
+        cx.spawn_in(window, async move |this, cx| {
+            let tags = tags.await??;
+            this.update_in(cx, |this, window, cx| {
+                this.picker.update(cx, |picker, cx| {
+                    picker.delegate.all_tags = Some(tags);
+                    picker.refresh(window, cx);
+                })
+            })?;
+            anyhow::Ok(())
+        })
+        .detach_and_log_err(cx);
+    }
+
+    pub fn handle_delete_tag(
+        &mut self,
+        _: &DeleteTag,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        self.picker.update(cx, |picker, cx| {
+            picker
+                .delegate
+                .delete_tag_at(picker.delegate.selected_index(), window, cx);
+        });
+    }
+
+    pub fn handle_push_tag(&mut self, _: &PushTag, window: &mut Window, cx: &mut Context<Self>) {
+        self.picker.update(cx, |picker, cx| {
+            picker
+                .delegate
+                .push_tag_at(picker.delegate.selected_index(), window, cx);
+        });
+    }
+}
+
+impl EventEmitter<DismissEvent> for TagList {}
+
+impl Focusable for TagList {
+    fn focus_handle(&self, _: &App) -> FocusHandle {
+        self.picker_focus_handle.clone()
+    }
+}
+
+impl Render for TagList {
+    fn render(&mut self, _: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        v_flex()
+            .key_context("TagList")
+            .w(self.width)
+            .on_action(cx.listener(Self::handle_delete_tag))
+            .on_action(cx.listener(Self::handle_push_tag))
+            .child(self.picker.clone())
+    }
+}
+
+#[derive(Debug, Clone)]
+enum Entry {
+    Tag { tag: Tag, positions: Vec<usize> },
+    NewTag { name: String },
+}
+
+pub struct TagListDelegate {
+    matches: Vec<Entry>,
+    all_tags: Option<Vec<Tag>>,
+    repo: Option<Entity<Repository>>,
+    workspace: WeakEntity<Workspace>,
+    selected_index: usize,
+    focus_handle: FocusHandle,
+}
+
+impl TagListDelegate {
+    fn new(
+        repo: Option<Entity<Repository>>,
+        workspace: WeakEntity<Workspace>,
+        cx: &mut Context<TagList>,
+    ) -> Self {
+        Self {
+            matches: Vec::new(),
+            all_tags: None,
+            repo,
+            workspace,
+            selected_index: 0,
+            focus_handle: cx.focus_handle(),
+        }
+    }
+
+    fn tag_at(&self, ix: usize) -> Option<&Tag> {
+        match self.matches.get(ix)? {
+            Entry::Tag { tag, .. } => Some(tag),
+            Entry::NewTag { .. } => None,
+        }
+    }
+
+    fn delete_tag_at(&self, ix: usize, window: &mut Window, cx: &mut Context<Picker<Self>>) {
+        let Some(tag) = self.tag_at(ix) else {
+            return;
+        };
+        let Some(repo) = self.repo.clone() else {
+            return;
+        };
+        let name = tag.name.to_string();
+
+        cx.spawn(async move |_, cx| {
+            repo.update(cx, |repo, cx| repo.delete_tag(name, cx))
+                .await??;
+            Ok(())
+        })
+        .detach_and_prompt_err("Failed to delete tag", window, cx, |e, _, _| {
+            Some(e.to_string())
+        });
+    }
+
+    fn push_tag_at(&self, ix: usize, window: &mut Window, cx: &mut Context<Picker<Self>>) {
+        let Some(tag) = self.tag_at(ix) else {
+            return;
+        };
+        let Some(repo) = self.repo.clone() else {
+            return;
+        };
+        let Some(git_panel) = self
+            .workspace
+            .upgrade()
+            .and_then(|workspace| workspace.read(cx).panel::<GitPanel>(cx))
+        else {
+            return;
+        };
+        let name = tag.name.clone();
+
+        // Dismiss first, so that the remote and credential prompts can take the modal layer over.
+        cx.emit(DismissEvent);
+        git_panel.update(cx, |git_panel, cx| {
+            git_panel.push_tag(repo, name, window, cx);
+        });
+    }
+
+    fn show_tag_at(&self, ix: usize, window: &mut Window, cx: &mut Context<Picker<Self>>) {
+        let Some(tag) = self.tag_at(ix) else {
+            return;
+        };
+        let Some(repo) = self.repo.clone() else {
+            return;
+        };
+        CommitView::open(
+            tag.sha.to_string(),
+            repo.downgrade(),
+            self.workspace.clone(),
+            None,
+            None,
+            window,
+            cx,
+        );
+        cx.emit(DismissEvent);
+    }
+
+    fn create_tag(&self, name: String, window: &mut Window, cx: &mut Context<Picker<Self>>) {
+        let Some(repo) = self.repo.clone() else {
+            return;
+        };
+        let workspace = self.workspace.clone();
+        window.defer(cx, move |window, cx| {
+            workspace
+                .update(cx, |workspace, cx| {
+                    CreateTagModal::toggle(workspace, repo, None, Some(name), window, cx);
+                })
+                .ok();
+        });
+    }
+}
+
+impl PickerDelegate for TagListDelegate {
+    type ListItem = ListItem;
+
+    fn placeholder_text(&self, _window: &mut Window, _cx: &mut App) -> Arc<str> {
+        "Select a tag or type a new tag name…".into()
+    }
+
+    fn match_count(&self) -> usize {
+        self.matches.len()
+    }
+
+    fn selected_index(&self) -> usize {
+        self.selected_index
+    }
+
+    fn set_selected_index(
+        &mut self,
+        ix: usize,
+        _window: &mut Window,
+        _: &mut Context<Picker<Self>>,
+    ) {
+        self.selected_index = ix;
+    }
+
+    fn update_matches(
+        &mut self,
+        query: String,
+        window: &mut Window,
+        cx: &mut Context<Picker<Self>>,
+    ) -> Task<()> {
+        let Some(all_tags) = self.all_tags.clone() else {
+            return Task::ready(());
+        };
+
+        cx.spawn_in(window, async move |picker, cx| {
+            let mut matches: Vec<Entry> = if query.is_empty() {
+                all_tags
+                    .into_iter()
+                    .map(|tag| Entry::Tag {
+                        tag,
+                        positions: Vec::new(),
+                    })
+                    .collect()
+            } else {
+                let candidates = all_tags
+                    .iter()
+                    .enumerate()
+                    .map(|(ix, tag)| StringMatchCandidate::new(ix, &tag.name))
+                    .collect::<Vec<StringMatchCandidate>>();
+                fuzzy::match_strings(
+                    &candidates,
+                    &query,
+                    true,
+                    true,
+                    10000,
+                    &Default::default(),
+                    cx.background_executor().clone(),
+                )
+                .await
+                .into_iter()
+                .map(|candidate| Entry::Tag {
+                    tag: all_tags[candidate.candidate_id].clone(),
+                    positions: candidate.positions,
+                })
+                .collect()
+            };
+
+            let name = query.trim().replace(' ', "-");
+            let exists = matches
+                .iter()
+                .any(|entry| matches!(entry, Entry::Tag { tag, .. } if tag.name.as_ref() == name));
+            if !name.is_empty() && !exists {
+                matches.push(Entry::NewTag { name });
+            }
+
+            picker
+                .update(cx, |picker, _| {
+                    let delegate = &mut picker.delegate;
+                    delegate.matches = matches;
+                    if delegate.matches.is_empty() {
+                        delegate.selected_index = 0;
+                    } else {
+                        delegate.selected_index =
+                            core::cmp::min(delegate.selected_index, delegate.matches.len() - 1);
+                    }
+                })
+                .log_err();
+        })
+    }
+
+    fn confirm(&mut self, _secondary: bool, window: &mut Window, cx: &mut Context<Picker<Self>>) {
+        match self.matches.get(self.selected_index()) {
+            Some(Entry::Tag { .. }) => self.show_tag_at(self.selected_index(), window, cx),
+            Some(Entry::NewTag { name }) => self.create_tag(name.clone(), window, cx),
+            None => {}
+        }
+    }
+
+    fn dismissed(&mut self, _: &mut Window, cx: &mut Context<Picker<Self>>) {
+        cx.emit(DismissEvent);
+    }
+
+    fn render_match(
+        &self,
+        ix: usize,
+        selected: bool,
+        _window: &mut Window,
+        _cx: &mut Context<Picker<Self>>,
+    ) -> Option<Self::ListItem> {
+        let entry = self.matches.get(ix)?;
+
+        let (icon, title, details, tooltip) = match entry {
+            Entry::NewTag { name } => (
+                IconName::Plus,
+                Label::new(format!("Create Tag: \"{name}\"…"))
+                    .truncate()
+                    .into_any_element(),
+                None,
+                None,
+            ),
+            Entry::Tag { tag, positions } => {
+                let short_sha = tag
+                    .sha
+                    .get(..git::SHORT_SHA_LENGTH)
+                    .unwrap_or(&tag.sha)
+                    .to_string();
+                let details = h_flex()
+                    .gap_1p5()
+                    .w_full()
+                    .child(
+                        Label::new(short_sha)
+                            .color(Color::Muted)
+                            .size(LabelSize::Small),
+                    )
+                    .when_some(tag.annotation.clone(), |this, annotation| {
+                        this.child(
+                            Label::new("•")
+                                .alpha(0.5)
+                                .color(Color::Muted)
+                                .size(LabelSize::Small),
+                        )
+                        .child(
+                            Label::new(annotation)
+                                .truncate()
+                                .color(Color::Muted)
+                                .size(LabelSize::Small),
+                        )
+                    });
+                (
+                    IconName::Hash,
+                    HighlightedLabel::new(tag.name.clone(), positions.clone())
+                        .truncate()
+                        .into_any_element(),
+                    Some(details),
+                    Some(tag.sha.clone()),
+                )
+            }
+        };
+
+        Some(
+            ListItem::new(format!("tag-{ix}"))
+                .inset(true)
+                .spacing(ListItemSpacing::Sparse)
+                .toggle_state(selected)
+                .child(
+                    h_flex()
+                        .w_full()
+                        .gap_3()
+                        .child(Icon::new(icon).color(Color::Muted))
+                        .child(v_flex().w_full().child(title).children(details)),
+                )
+                .when_some(tooltip, |this, sha| this.tooltip(Tooltip::text(sha))),
+        )
+    }
+
+    fn no_matches_text(&self, _window: &mut Window, _cx: &mut App) -> Option<SharedString> {
+        Some("No tags found".into())
+    }
+
+    fn render_footer(&self, _: &mut Window, cx: &mut Context<Picker<Self>>) -> Option<AnyElement> {
+        let focus_handle = self.focus_handle.clone();
+        let is_tag_selected = self.tag_at(self.selected_index).is_some();
+
+        Some(
+            h_flex()
+                .w_full()
+                .p_1p5()
+                .gap_0p5()
+                .justify_end()
+                .flex_wrap()
+                .border_t_1()
+                .border_color(cx.theme().colors().border_variant)
+                .when(is_tag_selected, |this| {
+                    this.child(
+                        Button::new("delete-tag", "Delete")
+                            .key_binding(
+                                KeyBinding::for_action_in(
+                                    &tag_picker::DeleteTag,
+                                    &focus_handle,
+                                    cx,
+                                )
+                                .map(|kb| kb.size(rems_from_px(12.))),
+                            )
+                            .on_click(|_, window, cx| {
+                                window.dispatch_action(tag_picker::DeleteTag.boxed_clone(), cx)
+                            }),
+                    )
+                    .child(
+                        Button::new("push-tag", "Push")
+                            .key_binding(
+                                KeyBinding::for_action_in(&tag_picker::PushTag, &focus_handle, cx)
+                                    .map(|kb| kb.size(rems_from_px(12.))),
+                            )
+                            .on_click(|_, window, cx| {
+                                window.dispatch_action(tag_picker::PushTag.boxed_clone(), cx)
+                            }),
+                    )
+                })
+                .child(
+                    Button::new(
+                        "confirm-tag",
+                        if is_tag_selected { "View" } else { "Create" },
+                    )
+                    .key_binding(
+                        KeyBinding::for_action_in(&menu::Confirm, &focus_handle, cx)
+                            .map(|kb| kb.size(rems_from_px(12.))),
+                    )
+                    .on_click(|_, window, cx| {
+                        window.dispatch_action(menu::Confirm.boxed_clone(), cx)
+                    }),
+                )
+                .into_any(),
+        )
+    }
+}
+
+/// A modal to create a tag, which is annotated when a message is given.
+pub struct CreateTagModal {
+    repository: Entity<Repository>,
+    /// The commit to tag, HEAD when `None`.
+    target: Option<String>,
+    name_editor: Entity<Editor>,
+    message_editor: Entity<Editor>,
+}
+
+impl CreateTagModal {
+    pub fn toggle(
+        workspace: &mut Workspace,
+        repository: Entity<Repository>,
+        target: Option<String>,
+        name: Option<String>,
+        window: &mut Window,
+        cx: &mut Context<Workspace>,
+    ) {
+        workspace.toggle_modal(window, cx, |window, cx| {
+            Self::new(repository, target, name, window, cx)
+        });
+    }
+
+    fn new(
+        repository: Entity<Repository>,
+        target: Option<String>,
+        name: Option<String>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Self {
+        let name_editor = cx.new(|cx| {
+            let mut editor = Editor::single_line(window, cx);
+            editor.set_placeholder_text("Tag name", window, cx);
+            if let Some(name) = name {
+                editor.set_text(name, window, cx);
+            }
+            editor
+        });
+        let message_editor = cx.new(|cx| {
+            let mut editor = Editor::auto_height(3, 10, window, cx);
+            editor.set_placeholder_text("Message, to create an annotated tag", window, cx);
+            editor
+        });
+        Self {
+            repository,
+            target,
+            name_editor,
+            message_editor,
+        }
+    }
+
+    fn cancel(&mut self, _: &Cancel, _: &mut Window, cx: &mut Context<Self>) {
+        cx.emit(DismissEvent);
+    }
+
+    fn confirm(&mut self, _: &Confirm, window: &mut Window, cx: &mut Context<Self>) {
+        let name = self.name_editor.read(cx).text(cx).trim().to_string();
+        if name.is_empty() {
+            return;
+        }
+        let message = self.message_editor.read(cx).text(cx).trim().to_string();
+        let message = (!message.is_empty()).then_some(message);
+        let target = self.target.clone();
+
+        let create_tag = self.repository.update(cx, |repository, cx| {
+            repository.create_tag(name, target, message, cx)
+        });
+        cx.spawn(async move |_, _| match create_tag.await {
+            Ok(result) => result,
+            Err(_) => Err(anyhow!("Operation was canceled")),
+        })
+        .detach_and_prompt_err("Failed to create tag", window, cx, |_, _, _| None);
+        cx.emit(DismissEvent);
+    }
+}
+
+impl EventEmitter<DismissEvent> for CreateTagModal {}
+
+impl ModalView for CreateTagModal {}
+
+impl Focusable for CreateTagModal {
+    fn focus_handle(&self, cx: &App) -> FocusHandle {
+        self.name_editor.focus_handle(cx)
+    }
+}
+
+impl Render for CreateTagModal {
+    fn render(&mut self, _: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        let title = match &self.target {
+            Some(target) => format!(
+                "Create Tag on {}",
+                target.get(..git::SHORT_SHA_LENGTH).unwrap_or(target)
+            ),
+            None => "Create Tag".to_string(),
+        };
+
+        v_flex()
+            .key_context("CreateTagModal")
+            .on_action(cx.listener(Self::cancel))
+            .on_action(cx.listener(Self::confirm))
+            .elevation_2(cx)
+            .w(rems(34.))
+            .child(
+                h_flex()
+                    .px_3()
+                    .pt_2()
+                    .pb_1()
+                    .w_full()
+                    .gap_1p5()
+                    .child(Icon::new(IconName::Hash).size(IconSize::XSmall))
+                    .child(Headline::new(title).size(HeadlineSize::XSmall)),
+            )
+            .child(div().px_3().pb_2().w_full().child(self.name_editor.clone()))
+            .child(
+                div()
+                    .mx_3()
+                    .mb_3()
+                    .p_1()
+                    .border_1()
+                    .rounded_sm()
+                    .border_color(cx.theme().colors().border_variant)
+                    .child(self.message_editor.clone()),
+            )
+    }
+}
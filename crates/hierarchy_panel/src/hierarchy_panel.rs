@@ -0,0 +1,942 @@
+mod hierarchy_panel_settings;
+
+use std::{ops::Range, sync::Arc};
+
+use anyhow::Result;
+use editor::{Editor, SelectionEffects, scroll::Autoscroll};
+use gpui::{
+    Action, AnyElement, App, AsyncWindowContext, ClickEvent, Context, Entity, EventEmitter,
+    FocusHandle, Focusable, KeyContext, Pixels, Render, ScrollStrategy, Task,
+    UniformListScrollHandle, WeakEntity, Window, actions, px, uniform_list,
+};
+use hierarchy_panel_settings::{DockSide, HierarchyPanelSettings};
+use language::{Anchor, Buffer, ToPoint as _};
+use menu::{
+    Confirm, SecondaryConfirm, SelectChild, SelectFirst, SelectLast, SelectNext, SelectParent,
+    SelectPrevious,
+};
+use project::{CallHierarchyItem, Fs, Location, Project, TypeHierarchyItem};
+use settings::Settings;
+use ui::{IconButton, ListItem, Tab, Tooltip, prelude::*};
+use workspace::{
+    Workspace,
+    dock::{DockPosition, Panel, PanelEvent},
+};
+
+actions!(
+    hierarchy_panel,
+    [
+        /// Shows the functions that call the function at the cursor.
+        ShowIncomingCalls,
+        /// Shows the functions called by the function at the cursor.
+        ShowOutgoingCalls,
+        /// Shows the types the type at the cursor inherits from.
+        ShowSupertypes,
+        /// Shows the types that inherit from the type at the cursor.
+        ShowSubtypes,
+        /// Switches between incoming and outgoing calls, or between supertypes and subtypes.
+        ToggleDirection,
+        /// Toggles focus on the hierarchy panel.
+        ToggleFocus,
+    ]
+);
+
+const HIERARCHY_PANEL_KEY: &str = "HierarchyPanel";
+
+pub fn init(cx: &mut App) {
+    cx.observe_new(|workspace: &mut Workspace, _, _| {
+        workspace.register_action(|workspace, _: &ToggleFocus, window, cx| {
+            workspace.toggle_panel_focus::<HierarchyPanel>(window, cx);
+        });
+        workspace.register_action(|workspace, _: &ShowIncomingCalls, window, cx| {
+            show_hierarchy(workspace, Direction::IncomingCalls, window, cx);
+        });
+        workspace.register_action(|workspace, _: &ShowOutgoingCalls, window, cx| {
+            show_hierarchy(workspace, Direction::OutgoingCalls, window, cx);
+        });
+        workspace.register_action(|workspace, _: &ShowSupertypes, window, cx| {
+            show_hierarchy(workspace, Direction::Supertypes, window, cx);
+        });
+        workspace.register_action(|workspace, _: &ShowSubtypes, window, cx| {
+            show_hierarchy(workspace, Direction::Subtypes, window, cx);
+        });
+    })
+    .detach();
+}
+
+fn show_hierarchy(
+    workspace: &mut Workspace,
+    direction: Direction,
+    window: &mut Window,
+    cx: &mut Context<Workspace>,
+) {
+    let Some(editor) = workspace.active_item_as::<Editor>(cx) else {
+        return;
+    };
+    let Some((buffer, position)) = editor.read_with(cx, |editor, cx| {
+        let head = editor.selections.newest_anchor().head();
+        editor.buffer().read(cx).text_anchor_for_position(head, cx)
+    }) else {
+        return;
+    };
+    if let Some(panel) = workspace.focus_panel::<HierarchyPanel>(window, cx) {
+        panel.update(cx, |panel, cx| {
+            panel.show(direction, buffer, position, window, cx);
+        });
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Direction {
+    IncomingCalls,
+    OutgoingCalls,
+    Supertypes,
+    Subtypes,
+}
+
+impl Direction {
+    fn reversed(self) -> Self {
+        match self {
+            Self::IncomingCalls => Self::OutgoingCalls,
+            Self::OutgoingCalls => Self::IncomingCalls,
+            Self::Supertypes => Self::Subtypes,
+            Self::Subtypes => Self::Supertypes,
+        }
+    }
+
+    fn is_call_hierarchy(self) -> bool {
+        matches!(self, Self::IncomingCalls | Self::OutgoingCalls)
+    }
+
+    fn title(self) -> &'static str {
+        match self {
+            Self::IncomingCalls => "Incoming Calls",
+            Self::OutgoingCalls => "Outgoing Calls",
+            Self::Supertypes => "Supertypes",
+            Self::Subtypes => "Subtypes",
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+enum Item {
+    Call(CallHierarchyItem),
+    Type(TypeHierarchyItem),
+}
+
+impl Item {
+    fn name(&self) -> &str {
+        match self {
+            Self::Call(item) => &item.name,
+            Self::Type(item) => &item.name,
+        }
+    }
+
+    fn detail(&self) -> Option<&str> {
+        match self {
+            Self::Call(item) => item.detail.as_deref(),
+            Self::Type(item) => item.detail.as_deref(),
+        }
+    }
+
+    fn buffer(&self) -> &Entity<Buffer> {
+        match self {
+            Self::Call(item) => &item.location.buffer,
+            Self::Type(item) => &item.location.buffer,
+        }
+    }
+
+    fn selection_range(&self) -> &Range<Anchor> {
+        match self {
+            Self::Call(item) => &item.selection_range,
+            Self::Type(item) => &item.selection_range,
+        }
+    }
+}
+
+struct Node {
+    item: Item,
+    /// Where the item calls, or is called by, its parent in the tree.
+    call_sites: Vec<Location>,
+    depth: usize,
+    parent: Option<usize>,
+    expanded: bool,
+    children: Children,
+}
+
+enum Children {
+    NotLoaded,
+    Loading { _task: Task<()> },
+    Loaded(Vec<usize>),
+}
+
+/// A tree of the callers or callees of a function, or of the supertypes or subtypes of a
+/// type, whose branches are requested from the language server as they're expanded.
+pub struct HierarchyPanel {
+    workspace: WeakEntity<Workspace>,
+    project: Entity<Project>,
+    fs: Arc<dyn Fs>,
+    focus_handle: FocusHandle,
+    width: Option<Pixels>,
+    direction: Direction,
+    /// The items the hierarchy was shown for, which stay at the root of the tree when its
+    /// direction is reversed.
+    roots: Vec<Item>,
+    nodes: Vec<Node>,
+    visible_entries: Vec<usize>,
+    selected_node: Option<usize>,
+    scroll_handle: UniformListScrollHandle,
+    _prepare_task: Task<()>,
+    message: Option<SharedString>,
+}
+
+impl HierarchyPanel {
+    pub async fn load(
+        workspace: WeakEntity<Workspace>,
+        mut cx: AsyncWindowContext,
+    ) -> Result<Entity<Self>> {
+        workspace.update_in(&mut cx, |workspace, _, cx| Self::new(workspace, cx))
+    }
+
+    fn new(workspace: &Workspace, cx: &mut Context<Workspace>) -> Entity<Self> {
+        let project = workspace.project().clone();
+        let fs = workspace.app_state().fs.clone();
+        let workspace = workspace.weak_handle();
+        cx.new(|cx| Self {
+            workspace,
+            project,
+            fs,
+            focus_handle: cx.focus_handle(),
+            width: None,
+            direction: Direction::IncomingCalls,
+            roots: Vec::new(),
+            nodes: Vec::new(),
+            visible_entries: Vec::new(),
+            selected_node: None,
+            scroll_handle: UniformListScrollHandle::new(),
+            _prepare_task: Task::ready(()),
+            message: None,
+        })
+    }
+
+    /// Shows the hierarchy of the function or type at the given position.
+    pub fn show(
+        &mut self,
+        direction: Direction,
+        buffer: Entity<Buffer>,
+        position: Anchor,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        self.direction = direction;
+        self.roots.clear();
+        self.message = Some("Loading…".into());
+        self.rebuild(cx);
+
+        let project = self.project.clone();
+        self._prepare_task = cx.spawn_in(window, async move |this, cx| {
+            let roots = if direction.is_call_hierarchy() {
+                let items = project.update(cx, |project, cx| {
+                    project.prepare_call_hierarchy(&buffer, position, cx)
+                });
+                items
+                    .await
+                    .map(|items| items.into_iter().map(Item::Call).collect::<Vec<_>>())
+            } else {
+                let items = project.update(cx, |project, cx| {
+                    project.prepare_type_hierarchy(&buffer, position, cx)
+                });
+                items
+                    .await
+                    .map(|items| items.into_iter().map(Item::Type).collect::<Vec<_>>())
+            };
+            this.update(cx, |this, cx| {
+                match roots {
+                    Ok(roots) if roots.is_empty() => {
+                        this.message = Some(if direction.is_call_hierarchy() {
+                            "No function found at the cursor".into()
+                        } else {
+                            "No type found at the cursor".into()
+                        });
+                    }
+                    Ok(roots) => {
+                        this.roots = roots;
+                        this.message = None;
+                    }
+                    Err(error) => {
+                        this.message = Some(format!("{error:#}").into());
+                    }
+                }
+                this.rebuild(cx);
+            })
+            .ok();
+        });
+    }
+
+    fn toggle_direction(&mut self, _: &ToggleDirection, _: &mut Window, cx: &mut Context<Self>) {
+        self.direction = self.direction.reversed();
+        self.rebuild(cx);
+    }
+
+    /// Starts the tree over from the roots, for the current direction.
+    fn rebuild(&mut self, cx: &mut Context<Self>) {
+        self.nodes = self
+            .roots
+            .iter()
+            .map(|item| Node {
+                item: item.clone(),
+                call_sites: Vec::new(),
+                depth: 0,
+                parent: None,
+                expanded: false,
+                children: Children::NotLoaded,
+            })
+            .collect();
+        for ix in 0..self.nodes.len() {
+            self.expand(ix, cx);
+        }
+        self.selected_node = (!self.nodes.is_empty()).then_some(0);
+        self.update_visible_entries(cx);
+    }
+
+    fn expand(&mut self, ix: usize, cx: &mut Context<Self>) {
+        self.nodes[ix].expanded = true;
+        if matches!(self.nodes[ix].children, Children::NotLoaded) {
+            self.nodes[ix].children = Children::Loading {
+                _task: self.load_children(ix, cx),
+            };
+        }
+    }
+
+    fn load_children(&mut self, ix: usize, cx: &mut Context<Self>) -> Task<()> {
+        let direction = self.direction;
+        let item = self.nodes[ix].item.clone();
+        let children = self
+            .project
+            .update(cx, |project, cx| match (&item, direction) {
+                (Item::Call(item), Direction::IncomingCalls) => {
+                    let calls = project.incoming_calls(item, cx);
+                    cx.background_spawn(async move { calls_to_children(calls.await?) })
+                }
+                (Item::Call(item), Direction::OutgoingCalls) => {
+                    let calls = project.outgoing_calls(item, cx);
+                    cx.background_spawn(async move { calls_to_children(calls.await?) })
+                }
+                (Item::Type(item), Direction::Supertypes) => {
+                    let types = project.supertypes(item, cx);
+                    cx.background_spawn(async move { types_to_children(types.await?) })
+                }
+                (Item::Type(item), Direction::Subtypes) => {
+                    let types = project.subtypes(item, cx);
+                    cx.background_spawn(async move { types_to_children(types.await?) })
+                }
+                _ => Task::ready(Ok(Vec::new())),
+            });
+
+        cx.spawn(async move |this, cx| {
+            let children = children.await.unwrap_or_else(|error| {
+                log::error!(
+                    "failed to load the {} of {}: {error:#}",
+                    direction.title().to_lowercase(),
+                    item.name()
+                );
+                Vec::new()
+            });
+            this.update(cx, |this, cx| {
+                let depth = this.nodes[ix].depth + 1;
+                let mut child_ids = Vec::with_capacity(children.len());
+                for (item, call_sites) in children {
+                    child_ids.push(this.nodes.len());
+                    this.nodes.push(Node {
+                        item,
+                        call_sites,
+                        depth,
+                        parent: Some(ix),
+                        expanded: false,
+                        children: Children::NotLoaded,
+                    });
+                }
+                this.nodes[ix].children = Children::Loaded(child_ids);
+                this.update_visible_entries(cx);
+            })
+            .ok();
+        })
+    }
+
+    fn update_visible_entries(&mut self, cx: &mut Context<Self>) {
+        self.visible_entries.clear();
+        let mut stack = (0..self.roots.len().min(self.nodes.len()))
+            .rev()
+            .collect::<Vec<_>>();
+        while let Some(ix) = stack.pop() {
+            self.visible_entries.push(ix);
+            let node = &self.nodes[ix];
+            if node.expanded
+                && let Children::Loaded(children) = &node.children
+            {
+                stack.extend(children.iter().rev());
+            }
+        }
+        cx.notify();
+    }
+
+    fn selected_entry(&self) -> Option<usize> {
+        let selected_node = self.selected_node?;
+        self.visible_entries
+            .iter()
+            .position(|&ix| ix == selected_node)
+    }
+
+    fn select_entry(&mut self, entry: usize, cx: &mut Context<Self>) {
+        if let Some(&ix) = self.visible_entries.get(entry) {
+            self.selected_node = Some(ix);
+            self.scroll_handle
+                .scroll_to_item(entry, ScrollStrategy::Center);
+            cx.notify();
+        }
+    }
+
+    fn select_next(&mut self, _: &SelectNext, _: &mut Window, cx: &mut Context<Self>) {
+        let entry = self.selected_entry().map_or(0, |entry| entry + 1);
+        if entry < self.visible_entries.len() {
+            self.select_entry(entry, cx);
+        }
+    }
+
+    fn select_previous(&mut self, _: &SelectPrevious, _: &mut Window, cx: &mut Context<Self>) {
+        if let Some(entry) = self.selected_entry().and_then(|entry| entry.checked_sub(1)) {
+            self.select_entry(entry, cx);
+        }
+    }
+
+    fn select_first(&mut self, _: &SelectFirst, _: &mut Window, cx: &mut Context<Self>) {
+        self.select_entry(0, cx);
+    }
+
+    fn select_last(&mut self, _: &SelectLast, _: &mut Window, cx: &mut Context<Self>) {
+        if let Some(entry) = self.visible_entries.len().checked_sub(1) {
+            self.select_entry(entry, cx);
+        }
+    }
+
+    /// Expands the selected entry, or selects its first child if it's already expanded.
+    fn select_child(&mut self, _: &SelectChild, _: &mut Window, cx: &mut Context<Self>) {
+        let Some(ix) = self.selected_node else {
+            return;
+        };
+        if self.nodes[ix].expanded {
+            if let Children::Loaded(children) = &self.nodes[ix].children
+                && let Some(&child) = children.first()
+            {
+                self.selected_node = Some(child);
+                if let Some(entry) = self.selected_entry() {
+                    self.select_entry(entry, cx);
+                }
+            }
+        } else {
+            self.expand(ix, cx);
+            self.update_visible_entries(cx);
+        }
+    }
+
+    /// Collapses the selected entry, or selects its parent if it's already collapsed.
+    fn select_parent(&mut self, _: &SelectParent, _: &mut Window, cx: &mut Context<Self>) {
+        let Some(ix) = self.selected_node else {
+            return;
+        };
+        if self.nodes[ix].expanded {
+            self.nodes[ix].expanded = false;
+            self.update_visible_entries(cx);
+        } else if let Some(parent) = self.nodes[ix].parent {
+            self.selected_node = Some(parent);
+            if let Some(entry) = self.selected_entry() {
+                self.select_entry(entry, cx);
+            }
+        }
+    }
+
+    fn toggle_expanded(&mut self, ix: usize, cx: &mut Context<Self>) {
+        if self.nodes[ix].expanded {
+            self.nodes[ix].expanded = false;
+        } else {
+            self.expand(ix, cx);
+        }
+        self.update_visible_entries(cx);
+    }
+
+    fn confirm(&mut self, _: &Confirm, window: &mut Window, cx: &mut Context<Self>) {
+        if let Some(ix) = self.selected_node {
+            self.open_node(ix, false, true, window, cx);
+        }
+    }
+
+    fn secondary_confirm(
+        &mut self,
+        _: &SecondaryConfirm,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        if let Some(ix) = self.selected_node {
+            self.open_node(ix, true, true, window, cx);
+        }
+    }
+
+    /// Opens the node's item in an editor. Callers are opened where they make the call, so
+    /// that walking up the incoming calls follows the path the calls take.
+    fn open_node(
+        &mut self,
+        ix: usize,
+        split: bool,
+        focus: bool,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let node = &self.nodes[ix];
+        let (buffer, position) = match node.call_sites.first() {
+            Some(call_site) if self.direction == Direction::IncomingCalls => {
+                (call_site.buffer.clone(), call_site.range.start)
+            }
+            _ => (
+                node.item.buffer().clone(),
+                node.item.selection_range().start,
+            ),
+        };
+        let Some(workspace) = self.workspace.upgrade() else {
+            return;
+        };
+        workspace.update(cx, |workspace, cx| {
+            let position = position.to_point(&buffer.read(cx));
+            let pane = if split {
+                workspace.adjacent_pane(window, cx)
+            } else {
+                workspace.active_pane().clone()
+            };
+            let editor = workspace
+                .open_project_item::<Editor>(pane, buffer, true, focus, true, true, window, cx);
+            editor.update(cx, |editor, cx| {
+                editor.change_selections(
+                    SelectionEffects::scroll(Autoscroll::center()),
+                    window,
+                    cx,
+                    |s| s.select_ranges([position..position]),
+                );
+            });
+        });
+    }
+
+    fn dispatch_context(&self) -> KeyContext {
+        let mut dispatch_context = KeyContext::new_with_defaults();
+        dispatch_context.add("HierarchyPanel");
+        dispatch_context.add("menu");
+        dispatch_context
+    }
+
+    fn render_entry(&self, entry: usize, cx: &mut Context<Self>) -> AnyElement {
+        let ix = self.visible_entries[entry];
+        let node = &self.nodes[ix];
+        let toggle = match &node.children {
+            Children::Loaded(children) if children.is_empty() => None,
+            _ => Some(node.expanded),
+        };
+        let file_name = node
+            .item
+            .buffer()
+            .read(cx)
+            .file()
+            .map(|file| SharedString::from(file.file_name(cx).to_string()));
+
+        ListItem::new(ix)
+            .indent_level(node.depth)
+            .indent_step_size(px(12.))
+            .toggle(toggle)
+            .on_toggle(cx.listener(move |this, _: &ClickEvent, _, cx| {
+                this.toggle_expanded(ix, cx);
+            }))
+            .toggle_state(self.selected_node == Some(ix))
+            .child(
+                h_flex()
+                    .gap_1()
+                    .min_w_0()
+                    .child(Label::new(node.item.name().to_string()).truncate())
+                    .when_some(node.item.detail(), |this, detail| {
+                        this.child(
+                            Label::new(detail.to_string())
+                                .size(LabelSize::Small)
+                                .color(Color::Muted)
+                                .truncate(),
+                        )
+                    }),
+            )
+            .end_slot::<Label>(file_name.map(|file_name| {
+                Label::new(file_name)
+                    .size(LabelSize::Small)
+                    .color(Color::Muted)
+            }))
+            .on_click(cx.listener(move |this, event: &ClickEvent, window, cx| {
+                this.selected_node = Some(ix);
+                this.open_node(ix, false, event.click_count() > 1, window, cx);
+                cx.notify();
+            }))
+            .into_any_element()
+    }
+
+    fn render_header(&self, cx: &mut Context<Self>) -> impl IntoElement {
+        let tooltip = format!("Show {}", self.direction.reversed().title());
+        h_flex()
+            .h(Tab::container_height(cx))
+            .px_2()
+            .justify_between()
+            .border_b_1()
+            .border_color(cx.theme().colors().border_variant)
+            .child(Label::new(self.direction.title()))
+            .child(
+                IconButton::new("toggle-direction", IconName::ArrowRightLeft)
+                    .icon_size(IconSize::Small)
+                    .tooltip(Tooltip::for_action_title_in(
+                        tooltip,
+                        &ToggleDirection,
+                        &self.focus_handle,
+                    ))
+                    .on_click(|_, window, cx| {
+                        window.dispatch_action(ToggleDirection.boxed_clone(), cx)
+                    }),
+            )
+    }
+
+    #[cfg(test)]
+    fn visible_entries_for_test(&self) -> Vec<String> {
+        self.visible_entries
+            .iter()
+            .map(|&ix| {
+                let node = &self.nodes[ix];
+                let marker = match (&node.children, node.expanded) {
+                    (Children::Loaded(children), _) if children.is_empty() => " ",
+                    (_, true) => "v",
+                    (_, false) => ">",
+                };
+                format!("{}{marker} {}", "  ".repeat(node.depth), node.item.name())
+            })
+            .collect()
+    }
+}
+
+fn calls_to_children(calls: Vec<project::CallHierarchyCall>) -> Result<Vec<(Item, Vec<Location>)>> {
+    Ok(calls
+        .into_iter()
+        .map(|call| (Item::Call(call.item), call.call_sites))
+        .collect())
+}
+
+fn types_to_children(types: Vec<TypeHierarchyItem>) -> Result<Vec<(Item, Vec<Location>)>> {
+    Ok(types
+        .into_iter()
+        .map(|item| (Item::Type(item), Vec::new()))
+        .collect())
+}
+
+impl Render for HierarchyPanel {
+    fn render(&mut self, _: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        v_flex()
+            .id("hierarchy-panel")
+            .size_full()
+            .overflow_hidden()
+            .key_context(self.dispatch_context())
+            .track_focus(&self.focus_handle)
+            .on_action(cx.listener(Self::select_next))
+            .on_action(cx.listener(Self::select_previous))
+            .on_action(cx.listener(Self::select_first))
+            .on_action(cx.listener(Self::select_last))
+            .on_action(cx.listener(Self::select_child))
+            .on_action(cx.listener(Self::select_parent))
+            .on_action(cx.listener(Self::confirm))
+            .on_action(cx.listener(Self::secondary_confirm))
+            .on_action(cx.listener(Self::toggle_direction))
+            .child(self.render_header(cx))
+            .map(|this| {
+                if let Some(message) = self.message.clone() {
+                    this.child(
+                        v_flex()
+                            .flex_1()
+                            .p_4()
+                            .items_center()
+                            .child(Label::new(message).color(Color::Muted)),
+                    )
+                } else if self.roots.is_empty() {
+                    this.child(
+                        v_flex().flex_1().p_4().items_center().child(
+                            Label::new(
+                                "Show the call or type hierarchy of a symbol to see it here",
+                            )
+                            .color(Color::Muted),
+                        ),
+                    )
+                } else {
+                    this.child(
+                        uniform_list(
+                            "hierarchy-entries",
+                            self.visible_entries.len(),
+                            cx.processor(|this, range: Range<usize>, _, cx| {
+                                range.map(|entry| this.render_entry(entry, cx)).collect()
+                            }),
+                        )
+                        .size_full()
+                        .track_scroll(&self.scroll_handle),
+                    )
+                }
+            })
+    }
+}
+
+impl Focusable for HierarchyPanel {
+    fn focus_handle(&self, _: &App) -> FocusHandle {
+        self.focus_handle.clone()
+    }
+}
+
+impl EventEmitter<PanelEvent> for HierarchyPanel {}
+
+impl Panel for HierarchyPanel {
+    fn persistent_name() -> &'static str {
+        "Hierarchy Panel"
+    }
+
+    fn panel_key() -> &'static str {
+        HIERARCHY_PANEL_KEY
+    }
+
+    fn position(&self, _: &Window, cx: &App) -> DockPosition {
+        match HierarchyPanelSettings::get_global(cx).dock {
+            DockSide::Left => DockPosition::Left,
+            DockSide::Right => DockPosition::Right,
+        }
+    }
+
+    fn position_is_valid(&self, position: DockPosition) -> bool {
+        matches!(position, DockPosition::Left | DockPosition::Right)
+    }
+
+    fn set_position(&mut self, position: DockPosition, _: &mut Window, cx: &mut Context<Self>) {
+        settings::update_settings_file(self.fs.clone(), cx, move |settings, _| {
+            let dock = match position {
+                DockPosition::Left | DockPosition::Bottom => DockSide::Left,
+                DockPosition::Right => DockSide::Right,
+            };
+            settings.hierarchy_panel.get_or_insert_default().dock = Some(dock);
+        });
+    }
+
+    fn size(&self, _: &Window, cx: &App) -> Pixels {
+        self.width
+            .unwrap_or_else(|| HierarchyPanelSettings::get_global(cx).default_width)
+    }
+
+    fn set_size(&mut self, size: Option<Pixels>, _: &mut Window, cx: &mut Context<Self>) {
+        self.width = size;
+        cx.notify();
+    }
+
+    // The panel is opened by showing a hierarchy, so there's no point in a button for it
+    // until there's a hierarchy to go back to.
+    fn icon(&self, _: &Window, cx: &App) -> Option<IconName> {
+        (HierarchyPanelSettings::get_global(cx).button && !self.roots.is_empty())
+            .then_some(IconName::ArrowRightLeft)
+    }
+
+    fn icon_tooltip(&self, _window: &Window, _: &App) -> Option<&'static str> {
+        Some("Hierarchy Panel")
+    }
+
+    fn toggle_action(&self) -> Box<dyn Action> {
+        Box::new(ToggleFocus)
+    }
+
+    fn activation_priority(&self) -> u32 {
+        4
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use editor::MultiBufferOffset;
+    use futures::StreamExt;
+    use gpui::TestAppContext;
+    use language::{FakeLspAdapter, Language, LanguageConfig, LanguageMatcher};
+    use project::FakeFs;
+    use serde_json::json;
+    use settings::SettingsStore;
+    use util::path;
+    use workspace::MultiWorkspace;
+
+    #[gpui::test]
+    async fn test_call_hierarchy_panel(cx: &mut TestAppContext) {
+        init_test(cx);
+
+        let fs = FakeFs::new(cx.executor());
+        fs.insert_tree(
+            path!("/dir"),
+            json!({
+                "a.rs": "fn a() { b() }\nfn b() { c() }\nfn c() {}",
+            }),
+        )
+        .await;
+
+        let project = Project::test(fs.clone(), [path!("/dir").as_ref()], cx).await;
+        let language_registry = project.read_with(cx, |project, _| project.languages().clone());
+        language_registry.add(Arc::new(Language::new(
+            LanguageConfig {
+                name: "Rust".into(),
+                matcher: LanguageMatcher {
+                    path_suffixes: vec!["rs".to_string()],
+                    ..Default::default()
+                },
+                ..Default::default()
+            },
+            None,
+        )));
+        let mut fake_servers = language_registry.register_fake_lsp(
+            "Rust",
+            FakeLspAdapter {
+                capabilities: lsp::ServerCapabilities {
+                    call_hierarchy_provider: Some(lsp::CallHierarchyServerCapability::Simple(true)),
+                    ..Default::default()
+                },
+                ..Default::default()
+            },
+        );
+
+        let (buffer, _handle) = project
+            .update(cx, |project, cx| {
+                project.open_local_buffer_with_lsp(path!("/dir/a.rs"), cx)
+            })
+            .await
+            .unwrap();
+        let fake_server = fake_servers.next().await.unwrap();
+        cx.executor().run_until_parked();
+
+        // `a` calls `b`, which calls `c`.
+        fn item(name: &str) -> lsp::CallHierarchyItem {
+            let line = match name {
+                "a" => 0,
+                "b" => 1,
+                _ => 2,
+            };
+            lsp::CallHierarchyItem {
+                name: name.to_string(),
+                kind: lsp::SymbolKind::FUNCTION,
+                tags: None,
+                detail: None,
+                uri: lsp::Uri::from_file_path(path!("/dir/a.rs")).unwrap(),
+                range: lsp::Range::new(lsp::Position::new(line, 0), lsp::Position::new(line, 9)),
+                selection_range: lsp::Range::new(
+                    lsp::Position::new(line, 3),
+                    lsp::Position::new(line, 4),
+                ),
+                data: None,
+            }
+        }
+        fn call_site(line: u32) -> lsp::Range {
+            lsp::Range::new(lsp::Position::new(line, 9), lsp::Position::new(line, 10))
+        }
+
+        fake_server.set_request_handler::<lsp::request::CallHierarchyPrepare, _, _>(
+            |_, _| async move { Ok(Some(vec![item("b")])) },
+        );
+        fake_server.set_request_handler::<lsp::request::CallHierarchyIncomingCalls, _, _>(
+            |params, _| async move {
+                Ok(Some(match params.item.name.as_str() {
+                    "b" => vec![lsp::CallHierarchyIncomingCall {
+                        from: item("a"),
+                        from_ranges: vec![call_site(0)],
+                    }],
+                    "c" => vec![lsp::CallHierarchyIncomingCall {
+                        from: item("b"),
+                        from_ranges: vec![call_site(1)],
+                    }],
+                    _ => Vec::new(),
+                }))
+            },
+        );
+        fake_server.set_request_handler::<lsp::request::CallHierarchyOutgoingCalls, _, _>(
+            |params, _| async move {
+                Ok(Some(match params.item.name.as_str() {
+                    "a" => vec![lsp::CallHierarchyOutgoingCall {
+                        to: item("b"),
+                        from_ranges: vec![call_site(0)],
+                    }],
+                    "b" => vec![lsp::CallHierarchyOutgoingCall {
+                        to: item("c"),
+                        from_ranges: vec![call_site(1)],
+                    }],
+                    _ => Vec::new(),
+                }))
+            },
+        );
+
+        let (multi_workspace, cx) =
+            cx.add_window_view(|window, cx| MultiWorkspace::test_new(project.clone(), window, cx));
+        let workspace = multi_workspace.read_with(cx, |mw, _| mw.workspace().clone());
+        let panel = workspace.update(cx, |workspace, cx| HierarchyPanel::new(workspace, cx));
+
+        let position = buffer.read_with(cx, |buffer, _| buffer.anchor_before(18));
+        panel.update_in(cx, |panel, window, cx| {
+            panel.show(
+                Direction::IncomingCalls,
+                buffer.clone(),
+                position,
+                window,
+                cx,
+            );
+        });
+        cx.run_until_parked();
+        panel.read_with(cx, |panel, _| {
+            assert_eq!(panel.visible_entries_for_test(), ["v b", "  > a"]);
+        });
+
+        // Expanding `a` finds that nothing calls it.
+        panel.update_in(cx, |panel, window, cx| {
+            panel.select_next(&SelectNext, window, cx);
+            panel.select_child(&SelectChild, window, cx);
+        });
+        cx.run_until_parked();
+        panel.read_with(cx, |panel, _| {
+            assert_eq!(panel.visible_entries_for_test(), ["v b", "    a"]);
+        });
+
+        // Callers are opened where they make the call.
+        panel.update_in(cx, |panel, window, cx| {
+            panel.confirm(&Confirm, window, cx);
+        });
+        let editor = workspace.read_with(cx, |workspace, cx| {
+            workspace.active_item_as::<Editor>(cx).unwrap()
+        });
+        editor.update(cx, |editor, cx| {
+            assert_eq!(
+                editor
+                    .selections
+                    .newest::<MultiBufferOffset>(&editor.display_snapshot(cx))
+                    .head(),
+                MultiBufferOffset(9)
+            );
+        });
+
+        // Reversing the direction keeps `b` at the root.
+        panel.update_in(cx, |panel, window, cx| {
+            panel.toggle_direction(&ToggleDirection, window, cx);
+        });
+        cx.run_until_parked();
+        panel.read_with(cx, |panel, _| {
+            assert_eq!(panel.visible_entries_for_test(), ["v b", "  > c"]);
+        });
+    }
+
+    fn init_test(cx: &mut TestAppContext) {
+        cx.update(|cx| {
+            let store = SettingsStore::test(cx);
+            cx.set_global(store);
+            theme::init(theme::LoadThemes::JustBase, cx);
+            release_channel::init(semver::Version::new(0, 0, 0), cx);
+            editor::init(cx);
+        });
+    }
+}
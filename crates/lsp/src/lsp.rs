@@ -298,6 +298,26 @@ struct Error {
     data: Option<serde_json::Value>,
 }
 
+/// An error that a language server answered a request with.
+#[derive(Debug)]
+pub struct ResponseError {
+    pub code: i64,
+    pub message: String,
+}
+
+impl ResponseError {
+    /// The code of the error that servers answer requests for methods they don't implement with.
+    pub const METHOD_NOT_FOUND: i64 = -32601;
+}
+
+impl fmt::Display for ResponseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.message)
+    }
+}
+
+impl std::error::Error for ResponseError {}
+
 pub trait LspRequestFuture<O>: Future<Output = ConnectionResult<O>> {
     fn id(&self) -> i32;
 }
@@ -502,7 +522,7 @@ impl LanguageServer {
                             jsonrpc: JSON_RPC_VERSION,
                             id: message_id,
                             error: Some(Error {
-                                code: -32601,
+                                code: ResponseError::METHOD_NOT_FOUND,
                                 message: format!("Unrecognized method `{}`", msg.method),
                                 data: None,
                             }),
@@ -987,6 +1007,14 @@ impl LanguageServer {
                     code_lens: Some(CodeLensClientCapabilities {
                         dynamic_registration: Some(true),
                     }),
+                    call_hierarchy: Some(DynamicRegistrationClientCapabilities {
+                        dynamic_registration: Some(true),
+                    }),
+                    // `ServerCapabilities` has no field for type hierarchies, so there's nowhere
+                    // to record their dynamic registration.
+                    type_hierarchy: Some(DynamicRegistrationClientCapabilities {
+                        dynamic_registration: Some(false),
+                    }),
                     document_symbol: Some(DocumentSymbolClientCapabilities {
                         hierarchical_document_symbol_support: Some(true),
                         dynamic_registration: Some(true),
@@ -1249,7 +1277,10 @@ impl LanguageServer {
                                             jsonrpc: JSON_RPC_VERSION,
                                             id: id.clone(),
                                             value: LspResult::Error(Some(Error {
-                                                code: lsp_types::error_codes::REQUEST_FAILED,
+                                                code: error.downcast_ref::<ResponseError>().map_or(
+                                                    lsp_types::error_codes::REQUEST_FAILED,
+                                                    |error| error.code,
+                                                ),
                                                 message: error.to_string(),
                                                 data: None,
                                             })),
@@ -1439,7 +1470,11 @@ impl LanguageServer {
                                             Err(error).context("failed to deserialize response")
                                         }
                                     }
-                                    Err(error) => Err(anyhow!("{}", error.message)),
+                                    Err(error) => Err(ResponseError {
+                                        code: error.code,
+                                        message: error.message,
+                                    }
+                                    .into()),
                                 };
                                 tx.send(response).ok();
                             })
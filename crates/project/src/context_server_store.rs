@@ -2,12 +2,17 @@ pub mod extension;
 pub mod registry;
 
 use std::path::Path;
+use std::rc::Rc;
 use std::sync::Arc;
 use std::time::Duration;
 
 use anyhow::{Context as _, Result};
 use collections::{HashMap, HashSet};
-use context_server::{ContextServer, ContextServerCommand, ContextServerId};
+use context_server::types::{
+    self, CreateMessageRequest, CreateMessageResult, ElicitationCreateParams,
+    ElicitationCreateResponse,
+};
+use context_server::{ContextServer, ContextServerCommand, ContextServerDelegate, ContextServerId};
 use futures::{FutureExt as _, future::Either, future::join_all};
 use gpui::{App, AsyncApp, Context, Entity, EventEmitter, Subscription, Task, WeakEntity, actions};
 use itertools::Itertools;
@@ -20,7 +25,7 @@ use util::{ResultExt as _, rel_path::RelPath};
 use crate::{
     DisableAiSettings, Project,
     project_settings::{ContextServerSettings, ProjectSettings},
-    worktree_store::WorktreeStore,
+    worktree_store::{WorktreeStore, WorktreeStoreEvent},
 };
 
 /// Maximum timeout for context server requests
@@ -197,6 +202,23 @@ impl ContextServerConfiguration {
     }
 }
 
+/// Fulfils the requests from context servers that need a language model or the user's input.
+pub trait ContextServerRequestHandler {
+    fn create_message(
+        &self,
+        server_id: ContextServerId,
+        params: CreateMessageRequest,
+        cx: &mut App,
+    ) -> Task<Result<CreateMessageResult>>;
+
+    fn elicit(
+        &self,
+        server_id: ContextServerId,
+        params: ElicitationCreateParams,
+        cx: &mut App,
+    ) -> Task<Result<ElicitationCreateResponse>>;
+}
+
 pub type ContextServerFactory =
     Box<dyn Fn(ContextServerId, Arc<ContextServerConfiguration>) -> Arc<ContextServer>>;
 
@@ -221,6 +243,7 @@ pub struct ContextServerStore {
     registry: Entity<ContextServerDescriptorRegistry>,
     update_servers_task: Option<Task<Result<()>>>,
     context_server_factory: Option<ContextServerFactory>,
+    request_handler: Option<Rc<dyn ContextServerRequestHandler>>,
     needs_server_update: bool,
     ai_disabled: bool,
     _subscriptions: Vec<Subscription>,
@@ -233,6 +256,46 @@ pub struct ServerStatusChangedEvent {
 
 impl EventEmitter<ServerStatusChangedEvent> for ContextServerStore {}
 
+/// Answers a server's requests on behalf of the store it was started by.
+struct ContextServerStoreDelegate {
+    server_id: ContextServerId,
+    store: WeakEntity<ContextServerStore>,
+}
+
+impl ContextServerDelegate for ContextServerStoreDelegate {
+    fn roots(&self, cx: &mut AsyncApp) -> Result<Vec<types::Root>> {
+        self.store.read_with(cx, |store, cx| store.roots(cx))
+    }
+
+    fn create_message(
+        &self,
+        params: CreateMessageRequest,
+        cx: &mut AsyncApp,
+    ) -> Task<Result<CreateMessageResult>> {
+        let server_id = self.server_id.clone();
+        self.store
+            .update(cx, |store, cx| match store.request_handler.clone() {
+                Some(handler) => handler.create_message(server_id, params, cx),
+                None => Task::ready(Err(anyhow::anyhow!("Sampling is not available"))),
+            })
+            .unwrap_or_else(|error| Task::ready(Err(error)))
+    }
+
+    fn elicit(
+        &self,
+        params: ElicitationCreateParams,
+        cx: &mut AsyncApp,
+    ) -> Task<Result<ElicitationCreateResponse>> {
+        let server_id = self.server_id.clone();
+        self.store
+            .update(cx, |store, cx| match store.request_handler.clone() {
+                Some(handler) => handler.elicit(server_id, params, cx),
+                None => Task::ready(Err(anyhow::anyhow!("Elicitation is not available"))),
+            })
+            .unwrap_or_else(|error| Task::ready(Err(error)))
+    }
+}
+
 impl ContextServerStore {
     pub fn local(
         worktree_store: Entity<WorktreeStore>,
@@ -354,6 +417,11 @@ impl ContextServerStore {
         &self.registry
     }
 
+    /// Sets the handler for the sampling and elicitation requests of this store's servers.
+    pub fn set_request_handler(&mut self, handler: Rc<dyn ContextServerRequestHandler>) {
+        self.request_handler = Some(handler);
+    }
+
     #[cfg(feature = "test-support")]
     pub fn test_start_server(&mut self, server: Arc<ContextServer>, cx: &mut Context<Self>) {
         let configuration = Arc::new(ContextServerConfiguration::Custom {
@@ -405,6 +473,15 @@ impl ContextServerStore {
             }
         })];
 
+        subscriptions.push(
+            cx.subscribe(&worktree_store, |this, _, event, _| match event {
+                WorktreeStoreEvent::WorktreeAdded(_)
+                | WorktreeStoreEvent::WorktreeRemoved(..)
+                | WorktreeStoreEvent::WorktreeOrderChanged => this.roots_changed(),
+                _ => {}
+            }),
+        );
+
         if maintain_server_loop {
             subscriptions.push(cx.observe(&registry, |this, _registry, cx| {
                 if !DisableAiSettings::get_global(cx).disable_ai {
@@ -429,6 +506,7 @@ impl ContextServerStore {
             server_ids: Default::default(),
             update_servers_task: None,
             context_server_factory,
+            request_handler: None,
         };
         if maintain_server_loop && !DisableAiSettings::get_global(cx).disable_ai {
             this.available_context_servers_changed(cx);
@@ -501,6 +579,31 @@ impl ContextServerStore {
             .collect();
     }
 
+    /// The project's visible worktrees, which servers are allowed to operate in.
+    fn roots(&self, cx: &App) -> Vec<types::Root> {
+        self.worktree_store
+            .read(cx)
+            .visible_worktrees(cx)
+            .filter_map(|worktree| {
+                let worktree = worktree.read(cx);
+                Some(types::Root {
+                    uri: url::Url::from_file_path(worktree.abs_path()).ok()?,
+                    name: Some(worktree.root_name_str().to_string()),
+                })
+            })
+            .collect()
+    }
+
+    fn roots_changed(&self) {
+        for server in self.running_servers() {
+            if let Some(client) = server.client() {
+                client
+                    .notify::<types::notifications::RootsListChanged>(())
+                    .log_err();
+            }
+        }
+    }
+
     pub fn running_servers(&self) -> Vec<Arc<ContextServer>> {
         self.servers
             .values()
@@ -594,13 +697,17 @@ impl ContextServerStore {
         ) {
             self.stop_server(&id, cx).log_err();
         }
+        let delegate: Arc<dyn ContextServerDelegate> = Arc::new(ContextServerStoreDelegate {
+            server_id: id.clone(),
+            store: cx.weak_entity(),
+        });
         let task = cx.spawn({
             let id = server.id();
             let server = server.clone();
             let configuration = configuration.clone();
 
             async move |this, cx| {
-                match server.clone().start(cx).await {
+                match server.clone().start(Some(delegate), cx).await {
                     Ok(_) => {
                         debug_assert!(server.client().is_some());
 
@@ -7,7 +7,7 @@ use super::{
 use remote::Interactive;
 
 use crate::{
-    InlayHint, InlayHintLabel, ProjectEnvironment, ResolveState,
+    InlayHint, InlayHintLabel, LspStore, ProjectEnvironment, ResolveState,
     debugger::session::SessionQuirks,
     project_settings::{DapBinary, ProjectSettings},
     worktree_store::WorktreeStore,
@@ -30,9 +30,12 @@ use futures::{
     channel::mpsc::{self, UnboundedSender},
     future::{Shared, join_all},
 };
-use gpui::{App, AppContext, AsyncApp, Context, Entity, EventEmitter, SharedString, Task};
+use gpui::{
+    App, AppContext, AsyncApp, Context, Entity, EventEmitter, SharedString, Task, WeakEntity,
+};
 use http_client::HttpClient;
 use language::{Buffer, LanguageToolchainStore};
+use lsp::LanguageServer;
 use node_runtime::NodeRuntime;
 use settings::InlayHintKind;
 
@@ -50,6 +53,7 @@ use std::{
     net::Ipv4Addr,
     path::{Path, PathBuf},
     sync::{Arc, Once},
+    time::Duration,
 };
 use task::{DebugScenario, SharedTaskContext, SpawnInTerminal, TaskTemplate};
 use util::{ResultExt as _, rel_path::RelPath};
@@ -80,6 +84,7 @@ pub struct LocalDapStore {
     http_client: Arc<dyn HttpClient>,
     environment: Entity<ProjectEnvironment>,
     toolchain_store: Arc<dyn LanguageToolchainStore>,
+    lsp_store: Option<WeakEntity<LspStore>>,
     is_headless: bool,
 }
 
@@ -124,6 +129,10 @@ impl DapStore {
             registry.add_locator(Arc::new(locators::go::GoLocator {}));
             registry.add_locator(Arc::new(locators::node::NodeLocator));
             registry.add_locator(Arc::new(locators::python::PythonLocator));
+            registry.add_locator(Arc::new(locators::java::JavaLocator));
+            registry.add_locator(Arc::new(locators::dotnet::DotnetLocator));
+            registry.add_locator(Arc::new(locators::ruby::RubyLocator));
+            registry.add_locator(Arc::new(locators::php::PhpLocator));
         });
         client.add_entity_request_handler(Self::handle_run_debug_locator);
         client.add_entity_request_handler(Self::handle_get_debug_adapter_binary);
@@ -148,12 +157,21 @@ impl DapStore {
             http_client,
             node_runtime,
             toolchain_store,
+            lsp_store: None,
             is_headless,
         });
 
         Self::new(mode, breakpoint_store, worktree_store, fs, cx)
     }
 
+    /// Lets debug adapters talk to the language servers of the project, e.g. to have jdtls start
+    /// a Java debug session.
+    pub fn set_lsp_store(&mut self, lsp_store: WeakEntity<LspStore>) {
+        if let DapStoreMode::Local(local_store) = &mut self.mode {
+            local_store.lsp_store = Some(lsp_store);
+        }
+    }
+
     pub fn new_remote(
         project_id: u64,
         remote_client: Entity<RemoteClient>,
@@ -603,6 +621,18 @@ impl DapStore {
             unimplemented!("Starting session on remote side");
         };
 
+        let worktree_id = worktree.read(cx).id();
+        let language_servers = local_store
+            .lsp_store
+            .as_ref()
+            .and_then(|lsp_store| lsp_store.upgrade())
+            .map(|lsp_store| {
+                lsp_store
+                    .read(cx)
+                    .running_language_servers_for_worktree(worktree_id)
+            })
+            .unwrap_or_default();
+
         Arc::new(DapAdapterDelegate::new(
             local_store.fs.clone(),
             worktree.read(cx).snapshot(),
@@ -613,6 +643,10 @@ impl DapStore {
             local_store
                 .environment
                 .update(cx, |env, cx| env.worktree_environment(worktree.clone(), cx)),
+            language_servers,
+            ProjectSettings::get_global(cx)
+                .global_lsp_settings
+                .get_request_timeout(),
             local_store.is_headless,
         ))
     }
@@ -944,6 +978,8 @@ pub struct DapAdapterDelegate {
     http_client: Arc<dyn HttpClient>,
     toolchain_store: Arc<dyn LanguageToolchainStore>,
     load_shell_env_task: Shared<Task<Option<HashMap<String, String>>>>,
+    language_servers: Vec<Arc<LanguageServer>>,
+    lsp_request_timeout: Duration,
     is_headless: bool,
 }
 
@@ -956,6 +992,8 @@ impl DapAdapterDelegate {
         http_client: Arc<dyn HttpClient>,
         toolchain_store: Arc<dyn LanguageToolchainStore>,
         load_shell_env_task: Shared<Task<Option<HashMap<String, String>>>>,
+        language_servers: Vec<Arc<LanguageServer>>,
+        lsp_request_timeout: Duration,
         is_headless: bool,
     ) -> Self {
         Self {
@@ -966,6 +1004,8 @@ impl DapAdapterDelegate {
             node_runtime,
             toolchain_store,
             load_shell_env_task,
+            language_servers,
+            lsp_request_timeout,
             is_headless,
         }
     }
@@ -1034,4 +1074,30 @@ impl dap::adapters::DapDelegate for DapAdapterDelegate {
     fn is_headless(&self) -> bool {
         self.is_headless
     }
+
+    async fn execute_language_server_command(
+        &self,
+        server_name: &str,
+        command: &str,
+        arguments: Vec<serde_json::Value>,
+    ) -> Result<serde_json::Value> {
+        let language_server = self
+            .language_servers
+            .iter()
+            .find(|server| server.name().0 == server_name)
+            .with_context(|| format!("{server_name} is not running for this worktree"))?;
+        let response = language_server
+            .request::<lsp::request::ExecuteCommand>(
+                lsp::ExecuteCommandParams {
+                    command: command.to_owned(),
+                    arguments,
+                    ..Default::default()
+                },
+                self.lsp_request_timeout,
+            )
+            .await
+            .into_response()
+            .with_context(|| format!("executing {command} on {server_name}"))?;
+        Ok(response.unwrap_or_default())
+    }
 }
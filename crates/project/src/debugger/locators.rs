@@ -1,4 +1,8 @@
 pub(crate) mod cargo;
+pub mod dotnet;
 pub mod go;
+pub mod java;
 pub(crate) mod node;
+pub mod php;
 pub mod python;
+pub mod ruby;
@@ -0,0 +1,114 @@
+use anyhow::{Context as _, Result};
+use async_trait::async_trait;
+use dap::{DapLocator, DebugRequest, adapters::DebugAdapterName};
+use gpui::{BackgroundExecutor, SharedString};
+use serde_json::json;
+use smol::{io::AsyncReadExt, process::Stdio as SmolStdio};
+use task::{BuildTaskDefinition, DebugScenario, ShellBuilder, SpawnInTerminal, TaskTemplate};
+
+pub struct DotnetLocator;
+
+#[async_trait]
+impl DapLocator for DotnetLocator {
+    fn name(&self) -> SharedString {
+        SharedString::new_static("dotnet-locator")
+    }
+
+    async fn create_scenario(
+        &self,
+        build_config: &TaskTemplate,
+        resolved_label: &str,
+        adapter: &DebugAdapterName,
+    ) -> Option<DebugScenario> {
+        if adapter.0.as_ref() != "netcoredbg" || build_config.command != "dotnet" {
+            return None;
+        }
+        if build_config.args.first()? != "run" {
+            return None;
+        }
+
+        let delimiter = build_config
+            .args
+            .iter()
+            .position(|arg| arg == "--")
+            .unwrap_or(build_config.args.len());
+        let program_args = build_config.args.get(delimiter + 1..).unwrap_or_default();
+
+        // Turn `dotnet run` into `dotnet build`, dropping the options that only `run` understands.
+        let mut build_args = vec!["build".to_owned()];
+        let mut run_args = build_config.args[1..delimiter].iter();
+        while let Some(arg) = run_args.next() {
+            match arg.as_str() {
+                "--project" => build_args.extend(run_args.next().cloned()),
+                "--launch-profile" | "-lp" => {
+                    run_args.next();
+                }
+                "--no-launch-profile" | "--no-build" => {}
+                _ => build_args.push(arg.clone()),
+            }
+        }
+
+        let mut task_template = build_config.clone();
+        task_template.args = build_args;
+
+        Some(DebugScenario {
+            adapter: adapter.0.clone(),
+            label: resolved_label.to_string().into(),
+            build: Some(BuildTaskDefinition::Template {
+                task_template,
+                locator_name: Some(self.name()),
+            }),
+            config: json!({
+                "args": program_args,
+            }),
+            tcp_connection: None,
+        })
+    }
+
+    async fn run(
+        &self,
+        build_config: SpawnInTerminal,
+        _: BackgroundExecutor,
+    ) -> Result<DebugRequest> {
+        let cwd = build_config
+            .cwd
+            .clone()
+            .context("Couldn't get cwd from debug config which is needed for locators")?;
+        let builder = ShellBuilder::new(&build_config.shell, cfg!(windows)).non_interactive();
+        let mut child = builder
+            .build_smol_command(
+                Some("dotnet".into()),
+                &build_config
+                    .args
+                    .iter()
+                    .cloned()
+                    .chain(Some("--getProperty:TargetPath".to_owned()))
+                    .collect::<Vec<_>>(),
+            )
+            .envs(build_config.env.iter().map(|(k, v)| (k.clone(), v.clone())))
+            .current_dir(cwd)
+            .stdout(SmolStdio::piped())
+            .spawn()?;
+
+        let mut output = String::new();
+        if let Some(mut stdout) = child.stdout.take() {
+            stdout.read_to_string(&mut output).await?;
+        }
+
+        let status = child.status().await?;
+        anyhow::ensure!(status.success(), "dotnet build failed");
+
+        let target_path = output
+            .lines()
+            .map(str::trim)
+            .rfind(|line| !line.is_empty())
+            .context("Couldn't get the target path of the dotnet project")?;
+
+        Ok(DebugRequest::Launch(task::LaunchRequest {
+            program: target_path.to_owned(),
+            cwd: build_config.cwd,
+            args: Vec::new(),
+            env: build_config.env.into_iter().collect(),
+        }))
+    }
+}
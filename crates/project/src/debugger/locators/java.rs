@@ -0,0 +1,117 @@
+use std::path::Path;
+
+use anyhow::{Result, bail};
+use async_trait::async_trait;
+use dap::{DapLocator, DebugRequest, adapters::DebugAdapterName};
+use gpui::{BackgroundExecutor, SharedString};
+use serde_json::json;
+
+use task::{DebugScenario, SpawnInTerminal, TaskTemplate};
+
+pub struct JavaLocator;
+
+/// JVM options whose value is passed as a separate argument.
+const OPTIONS_WITH_VALUES: &[&str] = &[
+    "--add-exports",
+    "--add-modules",
+    "--add-opens",
+    "--add-reads",
+    "--limit-modules",
+    "--patch-module",
+    "--upgrade-module-path",
+];
+
+#[async_trait]
+impl DapLocator for JavaLocator {
+    fn name(&self) -> SharedString {
+        SharedString::new_static("java-locator")
+    }
+
+    /// Determines whether this locator can generate debug target for given task.
+    async fn create_scenario(
+        &self,
+        build_config: &TaskTemplate,
+        resolved_label: &str,
+        adapter: &DebugAdapterName,
+    ) -> Option<DebugScenario> {
+        if adapter.0.as_ref() != "Java" {
+            return None;
+        }
+        let is_java = Path::new(&build_config.command)
+            .file_stem()
+            .is_some_and(|name| name == "java");
+        if !is_java {
+            return None;
+        }
+
+        let mut vm_args = Vec::new();
+        let mut class_paths = None;
+        let mut module_paths = None;
+        let mut args = build_config.args.iter();
+        let main_class = loop {
+            let arg = args.next()?;
+            match arg.as_str() {
+                "-cp" | "-classpath" | "--class-path" => {
+                    class_paths = Some(split_path_list(args.next()?));
+                }
+                "-p" | "--module-path" => {
+                    module_paths = Some(split_path_list(args.next()?));
+                }
+                "-m" | "--module" => break args.next()?.clone(),
+                // java-debug has to know the main class, which it can't get out of a jar.
+                "-jar" => return None,
+                option if OPTIONS_WITH_VALUES.contains(&option) => {
+                    vm_args.push(arg.clone());
+                    vm_args.push(args.next()?.clone());
+                }
+                option if option.starts_with('-') => vm_args.push(arg.clone()),
+                _ => break arg.clone(),
+            }
+        };
+        if main_class.ends_with(".java") {
+            // Source-file mode compiles the file in memory, so there are no classes to debug.
+            return None;
+        }
+
+        let mut config = json!({
+            "request": "launch",
+            "mainClass": main_class,
+            "args": args.cloned().collect::<Vec<_>>(),
+            "vmArgs": vm_args,
+            "cwd": build_config.cwd.clone(),
+            "env": build_config.env.clone(),
+        });
+        if let Some(config) = config.as_object_mut() {
+            if let Some(class_paths) = class_paths {
+                config.insert("classPaths".into(), class_paths.into());
+            }
+            if let Some(module_paths) = module_paths {
+                config.insert("modulePaths".into(), module_paths.into());
+            }
+            if build_config.command != "java" {
+                config.insert("javaExec".into(), build_config.command.clone().into());
+            }
+        }
+
+        Some(DebugScenario {
+            adapter: adapter.0.clone(),
+            label: resolved_label.to_string().into(),
+            build: None,
+            config,
+            tcp_connection: None,
+        })
+    }
+
+    async fn run(&self, _: SpawnInTerminal, _executor: BackgroundExecutor) -> Result<DebugRequest> {
+        bail!("Java locator should not require DapLocator::run to be ran");
+    }
+}
+
+fn split_path_list(paths: &str) -> Vec<String> {
+    let separator = if cfg!(windows) { ';' } else { ':' };
+    paths
+        .split(separator)
+        .filter(|path| !path.is_empty())
+        .map(str::to_owned)
+        .collect()
+}
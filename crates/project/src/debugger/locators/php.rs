@@ -0,0 +1,82 @@
+use std::path::Path;
+
+use anyhow::{Result, bail};
+use async_trait::async_trait;
+use dap::{DapLocator, DebugRequest, adapters::DebugAdapterName};
+use gpui::{BackgroundExecutor, SharedString};
+use serde_json::json;
+
+use task::{DebugScenario, SpawnInTerminal, TaskTemplate};
+
+pub struct PhpLocator;
+
+/// PHP scripts that are commonly run as tasks on their own, e.g. `vendor/bin/phpunit`.
+const PHP_SCRIPTS: &[&str] = &["phpunit", "pest", "artisan"];
+
+#[async_trait]
+impl DapLocator for PhpLocator {
+    fn name(&self) -> SharedString {
+        SharedString::new_static("php-locator")
+    }
+
+    /// Determines whether this locator can generate debug target for given task.
+    async fn create_scenario(
+        &self,
+        build_config: &TaskTemplate,
+        resolved_label: &str,
+        adapter: &DebugAdapterName,
+    ) -> Option<DebugScenario> {
+        if adapter.0.as_ref() != "Xdebug" {
+            return None;
+        }
+        let command_name = Path::new(&build_config.command).file_stem()?.to_str()?;
+
+        let (runtime_executable, runtime_args, program, args) = if command_name == "php" {
+            if build_config.args.iter().any(|arg| arg == "-r") {
+                // We cannot debug inline code.
+                return None;
+            }
+            let program_position = build_config
+                .args
+                .iter()
+                .position(|arg| !arg.starts_with('-'))?;
+            (
+                build_config.command.clone(),
+                build_config.args[..program_position].to_vec(),
+                build_config.args[program_position].clone(),
+                build_config.args[program_position + 1..].to_vec(),
+            )
+        } else if PHP_SCRIPTS.contains(&command_name) {
+            (
+                "php".to_owned(),
+                Vec::new(),
+                build_config.command.clone(),
+                build_config.args.clone(),
+            )
+        } else {
+            return None;
+        };
+
+        let config = json!({
+            "request": "launch",
+            "program": program,
+            "args": args,
+            "runtimeExecutable": runtime_executable,
+            "runtimeArgs": runtime_args,
+            "cwd": build_config.cwd.clone(),
+            "env": build_config.env.clone(),
+        });
+
+        Some(DebugScenario {
+            adapter: adapter.0.clone(),
+            label: resolved_label.to_string().into(),
+            build: None,
+            config,
+            tcp_connection: None,
+        })
+    }
+
+    async fn run(&self, _: SpawnInTerminal, _executor: BackgroundExecutor) -> Result<DebugRequest> {
+        bail!("PHP locator should not require DapLocator::run to be ran");
+    }
+}
@@ -0,0 +1,72 @@
+use std::path::Path;
+
+use anyhow::{Result, bail};
+use async_trait::async_trait;
+use dap::{DapLocator, DebugRequest, adapters::DebugAdapterName};
+use gpui::{BackgroundExecutor, SharedString};
+use serde_json::json;
+
+use task::{DebugScenario, SpawnInTerminal, TaskTemplate};
+
+pub struct RubyLocator;
+
+/// Executables that rdbg can run in its command mode.
+const RUBY_COMMANDS: &[&str] = &["ruby", "bundle", "rails", "rake", "rspec"];
+
+#[async_trait]
+impl DapLocator for RubyLocator {
+    fn name(&self) -> SharedString {
+        SharedString::new_static("ruby-locator")
+    }
+
+    /// Determines whether this locator can generate debug target for given task.
+    async fn create_scenario(
+        &self,
+        build_config: &TaskTemplate,
+        resolved_label: &str,
+        adapter: &DebugAdapterName,
+    ) -> Option<DebugScenario> {
+        if adapter.0.as_ref() != "rdbg" {
+            return None;
+        }
+        let command_name = Path::new(&build_config.command).file_stem()?.to_str()?;
+        if !RUBY_COMMANDS.contains(&command_name) {
+            return None;
+        }
+        if command_name == "ruby" && build_config.args.iter().any(|arg| arg == "-e") {
+            // We cannot debug inline scripts.
+            return None;
+        }
+
+        let mut config = json!({
+            "request": "launch",
+            "cwd": build_config.cwd.clone(),
+            "env": build_config.env.clone(),
+        });
+        if let Some(config) = config.as_object_mut() {
+            match build_config.args.split_first() {
+                // A plain `ruby script.rb` doesn't need rdbg's command mode.
+                Some((script, args)) if command_name == "ruby" && !script.starts_with('-') => {
+                    config.insert("script".into(), script.clone().into());
+                    config.insert("args".into(), args.to_vec().into());
+                }
+                _ => {
+                    config.insert("command".into(), build_config.command.clone().into());
+                    config.insert("args".into(), build_config.args.clone().into());
+                }
+            }
+        }
+
+        Some(DebugScenario {
+            adapter: adapter.0.clone(),
+            label: resolved_label.to_string().into(),
+            build: None,
+            config,
+            tcp_connection: None,
+        })
+    }
+
+    async fn run(&self, _: SpawnInTerminal, _executor: BackgroundExecutor) -> Result<DebugRequest> {
+        bail!("Ruby locator should not require DapLocator::run to be ran");
+    }
+}
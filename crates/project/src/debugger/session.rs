@@ -2,12 +2,13 @@ use super::breakpoint_store::{
     BreakpointStore, BreakpointStoreEvent, BreakpointUpdatedReason, SourceBreakpoint,
 };
 use super::dap_command::{
-    self, Attach, ConfigurationDone, ContinueCommand, DataBreakpointInfoCommand, DisconnectCommand,
-    EvaluateCommand, Initialize, Launch, LoadedSourcesCommand, LocalDapCommand, LocationsCommand,
-    ModulesCommand, NextCommand, PauseCommand, RestartCommand, RestartStackFrameCommand,
-    ScopesCommand, SetDataBreakpointsCommand, SetExceptionBreakpoints, SetVariableValueCommand,
-    StackTraceCommand, StepBackCommand, StepCommand, StepInCommand, StepOutCommand,
-    TerminateCommand, TerminateThreadsCommand, ThreadsCommand, VariablesCommand,
+    self, Attach, ConfigurationDone, ContinueCommand, DataBreakpointInfoCommand,
+    DisassembleCommand, DisconnectCommand, EvaluateCommand, Initialize, Launch,
+    LoadedSourcesCommand, LocalDapCommand, LocationsCommand, ModulesCommand, NextCommand,
+    PauseCommand, RestartCommand, RestartStackFrameCommand, ScopesCommand,
+    SetDataBreakpointsCommand, SetExceptionBreakpoints, SetInstructionBreakpointsCommand,
+    SetVariableValueCommand, StackTraceCommand, StepBackCommand, StepCommand, StepInCommand,
+    StepOutCommand, TerminateCommand, TerminateThreadsCommand, ThreadsCommand, VariablesCommand,
 };
 use super::dap_store::DapStore;
 use crate::debugger::breakpoint_store::BreakpointSessionState;
@@ -152,6 +153,12 @@ pub struct DataBreakpointState {
     pub context: Arc<DataBreakpointContext>,
 }
 
+#[derive(Debug, Clone, PartialEq)]
+pub struct InstructionBreakpointState {
+    pub dap: dap::InstructionBreakpoint,
+    pub is_enabled: bool,
+}
+
 pub enum SessionState {
     /// Represents a session that is building/initializing
     /// even if a session doesn't have a pre build task this state
@@ -680,6 +687,7 @@ pub struct SessionSnapshot {
     variables: HashMap<VariableReference, Vec<dap::Variable>>,
     stack_frames: IndexMap<StackFrameId, StackFrame>,
     locations: HashMap<u64, dap::LocationsResponse>,
+    disassembly: HashMap<DisassembleCommand, Arc<[dap::DisassembledInstruction]>>,
     modules: Vec<dap::Module>,
     loaded_sources: Vec<dap::Source>,
 }
@@ -709,6 +717,7 @@ pub struct Session {
     ignore_breakpoints: bool,
     exception_breakpoints: BTreeMap<String, (ExceptionBreakpointsFilter, IsEnabled)>,
     data_breakpoints: BTreeMap<String, DataBreakpointState>,
+    instruction_breakpoints: BTreeMap<String, InstructionBreakpointState>,
     background_tasks: Vec<Task<()>>,
     restart_task: Option<Task<()>>,
     task_context: SharedTaskContext,
@@ -806,6 +815,7 @@ pub enum SessionEvent {
         sender: mpsc::Sender<Result<u32>>,
     },
     DataBreakpointInfo,
+    Disassembly,
     ConsoleOutput,
     HistoricSnapshotSelected,
 }
@@ -883,6 +893,7 @@ impl Session {
                 ignore_breakpoints: false,
                 breakpoint_store,
                 data_breakpoints: Default::default(),
+                instruction_breakpoints: Default::default(),
                 exception_breakpoints: Default::default(),
                 label,
                 adapter,
@@ -1771,6 +1782,7 @@ impl Session {
         self.invalidate_command_type::<ThreadsCommand>();
         self.invalidate_command_type::<DataBreakpointInfoCommand>();
         self.invalidate_command_type::<ReadMemory>();
+        self.invalidate_command_type::<DisassembleCommand>();
         let executor = self.as_running().map(|running| running.executor.clone());
         if let Some(executor) = executor {
             self.memory.clear(&executor);
@@ -1980,6 +1992,40 @@ impl Session {
         );
     }
 
+    /// Returns `instruction_count` disassembled instructions, starting `instruction_offset`
+    /// instructions away from `memory_reference`, once the debug adapter has sent them.
+    pub fn disassemble(
+        &mut self,
+        memory_reference: &str,
+        instruction_offset: i64,
+        instruction_count: u64,
+        cx: &mut Context<Self>,
+    ) -> Option<Arc<[dap::DisassembledInstruction]>> {
+        let command = DisassembleCommand {
+            memory_reference: memory_reference.to_owned(),
+            instruction_offset,
+            instruction_count,
+        };
+        self.fetch(
+            command.clone(),
+            {
+                let command = command.clone();
+                move |this, response, cx| {
+                    let Some(instructions) = response.log_err() else {
+                        return;
+                    };
+                    this.active_snapshot
+                        .disassembly
+                        .insert(command, instructions);
+                    cx.emit(SessionEvent::Disassembly);
+                    cx.notify();
+                }
+            },
+            cx,
+        );
+        self.session_state().disassembly.get(&command).cloned()
+    }
+
     pub fn ignore_breakpoints(&self) -> bool {
         self.ignore_breakpoints
     }
@@ -2086,6 +2132,56 @@ impl Session {
         self.send_data_breakpoints(cx);
     }
 
+    pub fn instruction_breakpoints(&self) -> impl Iterator<Item = &InstructionBreakpointState> {
+        self.instruction_breakpoints.values()
+    }
+
+    pub fn has_instruction_breakpoint(&self, instruction_reference: &str) -> bool {
+        self.instruction_breakpoints
+            .contains_key(instruction_reference)
+    }
+
+    /// Adds a breakpoint on the instruction at the given address, or removes the existing one.
+    pub fn toggle_instruction_breakpoint(
+        &mut self,
+        instruction_reference: String,
+        cx: &mut Context<Self>,
+    ) {
+        if self
+            .instruction_breakpoints
+            .remove(&instruction_reference)
+            .is_none()
+        {
+            self.instruction_breakpoints.insert(
+                instruction_reference.clone(),
+                InstructionBreakpointState {
+                    dap: dap::InstructionBreakpoint {
+                        instruction_reference,
+                        offset: None,
+                        condition: None,
+                        hit_condition: None,
+                        mode: None,
+                    },
+                    is_enabled: true,
+                },
+            );
+        }
+        self.send_instruction_breakpoints(cx);
+        cx.notify();
+    }
+
+    fn send_instruction_breakpoints(&mut self, cx: &mut Context<Self>) {
+        if let Some(mode) = self.as_running() {
+            let breakpoints = self
+                .instruction_breakpoints
+                .values()
+                .filter_map(|state| state.is_enabled.then(|| state.dap.clone()))
+                .collect();
+            let command = SetInstructionBreakpointsCommand { breakpoints };
+            mode.request(command).detach_and_log_err(cx);
+        }
+    }
+
     pub fn breakpoints_enabled(&self) -> bool {
         self.ignore_breakpoints
     }
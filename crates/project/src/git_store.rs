@@ -32,10 +32,11 @@ use git::{
     blame::Blame,
     parse_git_remote_url,
     repository::{
-        Branch, CommitDetails, CommitDiff, CommitFile, CommitOptions, DiffType, FetchOptions,
-        GitRepository, GitRepositoryCheckpoint, GraphCommitData, InitialGraphCommitData, LogOrder,
-        LogSource, PushOptions, Remote, RemoteCommandOutput, RepoPath, ResetMode,
-        UpstreamTrackingStatus, Worktree as GitWorktree,
+        BisectLog, BisectMark, Branch, CommitDetails, CommitDiff, CommitFile, CommitOptions,
+        DiffType, FetchOptions, GitRepository, GitRepositoryCheckpoint, GraphCommitData,
+        InitialGraphCommitData, LogOrder, LogSource, PushOptions, RebaseTodoAction,
+        RebaseTodoEntry, ReflogEntry, Remote, RemoteCommandOutput, RepoPath, ResetMode, Submodule,
+        Tag, UpstreamTrackingStatus, Worktree as GitWorktree,
     },
     stash::{GitStash, StashEntry},
     status::{
@@ -293,6 +294,8 @@ pub struct RepositorySnapshot {
     pub remote_origin_url: Option<String>,
     pub remote_upstream_url: Option<String>,
     pub stash_entries: GitStash,
+    pub bisect: Option<BisectLog>,
+    pub submodules: Arc<[Submodule]>,
 }
 
 type JobId = u64;
@@ -429,6 +432,8 @@ pub enum RepositoryEvent {
     StatusesChanged,
     BranchChanged,
     StashEntriesChanged,
+    BisectChanged,
+    SubmodulesChanged,
     PendingOpsChanged { pending_ops: SumTree<PendingOps> },
     GraphEvent((LogSource, LogOrder), GitGraphEvent),
 }
@@ -557,6 +562,22 @@ impl GitStore {
         client.add_entity_request_handler(Self::handle_commit);
         client.add_entity_request_handler(Self::handle_run_hook);
         client.add_entity_request_handler(Self::handle_reset);
+        client.add_entity_request_handler(Self::handle_rebase_todo);
+        client.add_entity_request_handler(Self::handle_is_ancestor);
+        client.add_entity_request_handler(Self::handle_rebase);
+        client.add_entity_request_handler(Self::handle_cherry_pick);
+        client.add_entity_request_handler(Self::handle_revert);
+        client.add_entity_request_handler(Self::handle_continue_operation);
+        client.add_entity_request_handler(Self::handle_abort_operation);
+        client.add_entity_request_handler(Self::handle_bisect_start);
+        client.add_entity_request_handler(Self::handle_bisect_mark);
+        client.add_entity_request_handler(Self::handle_bisect_reset);
+        client.add_entity_request_handler(Self::handle_update_submodules);
+        client.add_entity_request_handler(Self::handle_sync_submodules);
+        client.add_entity_request_handler(Self::handle_get_tags);
+        client.add_entity_request_handler(Self::handle_get_reflog);
+        client.add_entity_request_handler(Self::handle_create_tag);
+        client.add_entity_request_handler(Self::handle_delete_tag);
         client.add_entity_request_handler(Self::handle_show);
         client.add_entity_request_handler(Self::handle_load_commit_diff);
         client.add_entity_request_handler(Self::handle_file_history);
@@ -2428,10 +2449,11 @@ impl GitStore {
         let repository_id = RepositoryId::from_proto(envelope.payload.repository_id);
         let repository_handle = Self::repository_for_request(&this, repository_id, &mut cx)?;
         let branch_name = envelope.payload.branch_name;
+        let base_branch = envelope.payload.base_branch;
 
         repository_handle
             .update(&mut cx, |repository_handle, _| {
-                repository_handle.create_branch(branch_name, None)
+                repository_handle.create_branch(branch_name, base_branch)
             })
             .await??;
 
@@ -2512,6 +2534,81 @@ impl GitStore {
         Ok(proto::Ack {})
     }
 
+    async fn handle_get_tags(
+        this: Entity<Self>,
+        envelope: TypedEnvelope<proto::GitGetTags>,
+        mut cx: AsyncApp,
+    ) -> Result<proto::GitTagsResponse> {
+        let repository_id = RepositoryId::from_proto(envelope.payload.repository_id);
+        let repository_handle = Self::repository_for_request(&this, repository_id, &mut cx)?;
+
+        let tags = repository_handle
+            .update(&mut cx, |repository_handle, _| repository_handle.tags())
+            .await??;
+
+        Ok(proto::GitTagsResponse {
+            tags: tags.iter().map(tag_to_proto).collect(),
+        })
+    }
+
+    async fn handle_get_reflog(
+        this: Entity<Self>,
+        envelope: TypedEnvelope<proto::GitGetReflog>,
+        mut cx: AsyncApp,
+    ) -> Result<proto::GitReflogResponse> {
+        let repository_id = RepositoryId::from_proto(envelope.payload.repository_id);
+        let repository_handle = Self::repository_for_request(&this, repository_id, &mut cx)?;
+
+        let entries = repository_handle
+            .update(&mut cx, |repository_handle, _| {
+                repository_handle.reflog(envelope.payload.ref_name, envelope.payload.limit as usize)
+            })
+            .await??;
+
+        Ok(proto::GitReflogResponse {
+            entries: entries.iter().map(reflog_entry_to_proto).collect(),
+        })
+    }
+
+    async fn handle_create_tag(
+        this: Entity<Self>,
+        envelope: TypedEnvelope<proto::GitCreateTag>,
+        mut cx: AsyncApp,
+    ) -> Result<proto::Ack> {
+        let repository_id = RepositoryId::from_proto(envelope.payload.repository_id);
+        let repository_handle = Self::repository_for_request(&this, repository_id, &mut cx)?;
+
+        repository_handle
+            .update(&mut cx, |repository_handle, cx| {
+                repository_handle.create_tag(
+                    envelope.payload.name,
+                    envelope.payload.target,
+                    envelope.payload.message,
+                    cx,
+                )
+            })
+            .await??;
+
+        Ok(proto::Ack {})
+    }
+
+    async fn handle_delete_tag(
+        this: Entity<Self>,
+        envelope: TypedEnvelope<proto::GitDeleteTag>,
+        mut cx: AsyncApp,
+    ) -> Result<proto::Ack> {
+        let repository_id = RepositoryId::from_proto(envelope.payload.repository_id);
+        let repository_handle = Self::repository_for_request(&this, repository_id, &mut cx)?;
+
+        repository_handle
+            .update(&mut cx, |repository_handle, cx| {
+                repository_handle.delete_tag(envelope.payload.name, cx)
+            })
+            .await??;
+
+        Ok(proto::Ack {})
+    }
+
     async fn handle_remove_remote(
         this: Entity<Self>,
         envelope: TypedEnvelope<proto::GitRemoveRemote>,
@@ -2624,6 +2721,7 @@ impl GitStore {
         let mode = match envelope.payload.mode() {
             git_reset::ResetMode::Soft => ResetMode::Soft,
             git_reset::ResetMode::Mixed => ResetMode::Mixed,
+            git_reset::ResetMode::Keep => ResetMode::Keep,
         };
 
         repository_handle
@@ -2634,6 +2732,240 @@ impl GitStore {
         Ok(proto::Ack {})
     }
 
+    async fn handle_rebase_todo(
+        this: Entity<Self>,
+        envelope: TypedEnvelope<proto::GitRebaseTodo>,
+        mut cx: AsyncApp,
+    ) -> Result<proto::GitRebaseTodoResponse> {
+        let repository_id = RepositoryId::from_proto(envelope.payload.repository_id);
+        let repository_handle = Self::repository_for_request(&this, repository_id, &mut cx)?;
+
+        let entries = repository_handle
+            .update(&mut cx, |repository_handle, _| {
+                repository_handle.rebase_todo(envelope.payload.base)
+            })
+            .await??;
+        Ok(proto::GitRebaseTodoResponse {
+            entries: entries.iter().map(rebase_todo_entry_to_proto).collect(),
+        })
+    }
+
+    async fn handle_is_ancestor(
+        this: Entity<Self>,
+        envelope: TypedEnvelope<proto::GitIsAncestor>,
+        mut cx: AsyncApp,
+    ) -> Result<proto::GitIsAncestorResponse> {
+        let repository_id = RepositoryId::from_proto(envelope.payload.repository_id);
+        let repository_handle = Self::repository_for_request(&this, repository_id, &mut cx)?;
+
+        let is_ancestor = repository_handle
+            .update(&mut cx, |repository_handle, _| {
+                repository_handle
+                    .is_ancestor(envelope.payload.ancestor, envelope.payload.descendant)
+            })
+            .await??;
+        Ok(proto::GitIsAncestorResponse { is_ancestor })
+    }
+
+    async fn handle_rebase(
+        this: Entity<Self>,
+        envelope: TypedEnvelope<proto::GitRebase>,
+        mut cx: AsyncApp,
+    ) -> Result<proto::Ack> {
+        let repository_id = RepositoryId::from_proto(envelope.payload.repository_id);
+        let repository_handle = Self::repository_for_request(&this, repository_id, &mut cx)?;
+        let todo = envelope
+            .payload
+            .todo
+            .iter()
+            .map(proto_to_rebase_todo_entry)
+            .collect();
+
+        repository_handle
+            .update(&mut cx, |repository_handle, _| {
+                repository_handle.rebase(envelope.payload.base, todo)
+            })
+            .await??;
+        Ok(proto::Ack {})
+    }
+
+    async fn handle_cherry_pick(
+        this: Entity<Self>,
+        envelope: TypedEnvelope<proto::GitCherryPick>,
+        mut cx: AsyncApp,
+    ) -> Result<proto::Ack> {
+        let repository_id = RepositoryId::from_proto(envelope.payload.repository_id);
+        let repository_handle = Self::repository_for_request(&this, repository_id, &mut cx)?;
+
+        repository_handle
+            .update(&mut cx, |repository_handle, _| {
+                repository_handle.cherry_pick(envelope.payload.commits)
+            })
+            .await??;
+        Ok(proto::Ack {})
+    }
+
+    async fn handle_revert(
+        this: Entity<Self>,
+        envelope: TypedEnvelope<proto::GitRevert>,
+        mut cx: AsyncApp,
+    ) -> Result<proto::Ack> {
+        let repository_id = RepositoryId::from_proto(envelope.payload.repository_id);
+        let repository_handle = Self::repository_for_request(&this, repository_id, &mut cx)?;
+
+        repository_handle
+            .update(&mut cx, |repository_handle, _| {
+                repository_handle.revert(envelope.payload.commits)
+            })
+            .await??;
+        Ok(proto::Ack {})
+    }
+
+    async fn handle_continue_operation(
+        this: Entity<Self>,
+        envelope: TypedEnvelope<proto::GitContinueOperation>,
+        mut cx: AsyncApp,
+    ) -> Result<proto::Ack> {
+        let repository_id = RepositoryId::from_proto(envelope.payload.repository_id);
+        let repository_handle = Self::repository_for_request(&this, repository_id, &mut cx)?;
+
+        repository_handle
+            .update(&mut cx, |repository_handle, _| {
+                repository_handle.continue_operation()
+            })
+            .await??;
+        Ok(proto::Ack {})
+    }
+
+    async fn handle_abort_operation(
+        this: Entity<Self>,
+        envelope: TypedEnvelope<proto::GitAbortOperation>,
+        mut cx: AsyncApp,
+    ) -> Result<proto::Ack> {
+        let repository_id = RepositoryId::from_proto(envelope.payload.repository_id);
+        let repository_handle = Self::repository_for_request(&this, repository_id, &mut cx)?;
+
+        repository_handle
+            .update(&mut cx, |repository_handle, _| {
+                repository_handle.abort_operation()
+            })
+            .await??;
+        Ok(proto::Ack {})
+    }
+
+    async fn handle_bisect_start(
+        this: Entity<Self>,
+        envelope: TypedEnvelope<proto::GitBisectStart>,
+        mut cx: AsyncApp,
+    ) -> Result<proto::Ack> {
+        let repository_id = RepositoryId::from_proto(envelope.payload.repository_id);
+        let repository_handle = Self::repository_for_request(&this, repository_id, &mut cx)?;
+
+        repository_handle
+            .update(&mut cx, |repository_handle, _| {
+                repository_handle.bisect_start(envelope.payload.bad, envelope.payload.good)
+            })
+            .await??;
+        Ok(proto::Ack {})
+    }
+
+    async fn handle_bisect_mark(
+        this: Entity<Self>,
+        envelope: TypedEnvelope<proto::GitBisectMark>,
+        mut cx: AsyncApp,
+    ) -> Result<proto::GitBisectMarkResponse> {
+        let repository_id = RepositoryId::from_proto(envelope.payload.repository_id);
+        let repository_handle = Self::repository_for_request(&this, repository_id, &mut cx)?;
+
+        let mark = match envelope.payload.mark() {
+            proto::git_bisect_mark::Mark::Good => BisectMark::Good,
+            proto::git_bisect_mark::Mark::Bad => BisectMark::Bad,
+            proto::git_bisect_mark::Mark::Skip => BisectMark::Skip,
+        };
+
+        let bisect = repository_handle
+            .update(&mut cx, |repository_handle, _| {
+                repository_handle.bisect_mark(mark, envelope.payload.commit)
+            })
+            .await??;
+        Ok(proto::GitBisectMarkResponse {
+            bisect: bisect.as_ref().map(bisect_to_proto),
+        })
+    }
+
+    async fn handle_bisect_reset(
+        this: Entity<Self>,
+        envelope: TypedEnvelope<proto::GitBisectReset>,
+        mut cx: AsyncApp,
+    ) -> Result<proto::Ack> {
+        let repository_id = RepositoryId::from_proto(envelope.payload.repository_id);
+        let repository_handle = Self::repository_for_request(&this, repository_id, &mut cx)?;
+
+        repository_handle
+            .update(&mut cx, |repository_handle, _| {
+                repository_handle.bisect_reset()
+            })
+            .await??;
+        Ok(proto::Ack {})
+    }
+
+    async fn handle_update_submodules(
+        this: Entity<Self>,
+        envelope: TypedEnvelope<proto::GitUpdateSubmodules>,
+        mut cx: AsyncApp,
+    ) -> Result<proto::RemoteMessageResponse> {
+        let repository_id = RepositoryId::from_proto(envelope.payload.repository_id);
+        let repository_handle = Self::repository_for_request(&this, repository_id, &mut cx)?;
+        let paths = envelope
+            .payload
+            .paths
+            .iter()
+            .map(|s| RepoPath::from_proto(s))
+            .collect::<Result<Vec<_>>>()?;
+        let askpass_id = envelope.payload.askpass_id;
+
+        let askpass = make_remote_delegate(
+            this,
+            envelope.payload.project_id,
+            repository_id,
+            askpass_id,
+            &mut cx,
+        );
+
+        let remote_output = repository_handle
+            .update(&mut cx, |repository_handle, _| {
+                repository_handle.update_submodules(paths, envelope.payload.init, askpass)
+            })
+            .await??;
+
+        Ok(proto::RemoteMessageResponse {
+            stdout: remote_output.stdout,
+            stderr: remote_output.stderr,
+        })
+    }
+
+    async fn handle_sync_submodules(
+        this: Entity<Self>,
+        envelope: TypedEnvelope<proto::GitSyncSubmodules>,
+        mut cx: AsyncApp,
+    ) -> Result<proto::Ack> {
+        let repository_id = RepositoryId::from_proto(envelope.payload.repository_id);
+        let repository_handle = Self::repository_for_request(&this, repository_id, &mut cx)?;
+        let paths = envelope
+            .payload
+            .paths
+            .iter()
+            .map(|s| RepoPath::from_proto(s))
+            .collect::<Result<Vec<_>>>()?;
+
+        repository_handle
+            .update(&mut cx, |repository_handle, _| {
+                repository_handle.sync_submodules(paths)
+            })
+            .await??;
+        Ok(proto::Ack {})
+    }
+
     async fn handle_checkout_files(
         this: Entity<Self>,
         envelope: TypedEnvelope<proto::GitCheckoutFiles>,
@@ -3535,6 +3867,8 @@ impl RepositorySnapshot {
             remote_origin_url: None,
             remote_upstream_url: None,
             stash_entries: Default::default(),
+            bisect: None,
+            submodules: Arc::default(),
             path_style,
         }
     }
@@ -3573,6 +3907,8 @@ impl RepositorySnapshot {
             original_repo_abs_path: Some(
                 self.original_repo_abs_path.to_string_lossy().into_owned(),
             ),
+            bisect: self.bisect.as_ref().map(bisect_to_proto),
+            submodules: self.submodules.iter().map(submodule_to_proto).collect(),
         }
     }
 
@@ -3649,6 +3985,8 @@ impl RepositorySnapshot {
             original_repo_abs_path: Some(
                 self.original_repo_abs_path.to_string_lossy().into_owned(),
             ),
+            bisect: self.bisect.as_ref().map(bisect_to_proto),
+            submodules: self.submodules.iter().map(submodule_to_proto).collect(),
         }
     }
 
@@ -3660,6 +3998,13 @@ impl RepositorySnapshot {
         self.statuses_by_path.summary().item_summary
     }
 
+    /// Returns the submodule checked out at `path`, if there is one.
+    pub fn submodule(&self, path: &RepoPath) -> Option<&Submodule> {
+        self.submodules
+            .iter()
+            .find(|submodule| &submodule.path == path)
+    }
+
     pub fn status_for_path(&self, path: &RepoPath) -> Option<StatusEntry> {
         self.statuses_by_path
             .get(&PathKey(path.as_ref().clone()), ())
@@ -4329,51 +4674,417 @@ impl Repository {
                                         })
                                         .await?;
 
-                                    Ok(())
-                                }
-                            }
-                        },
-                    )
-                })?
-                .await?
+                                    Ok(())
+                                }
+                            }
+                        },
+                    )
+                })?
+                .await?
+            },
+        )
+    }
+
+    pub fn reset(
+        &mut self,
+        commit: String,
+        reset_mode: ResetMode,
+        _cx: &mut App,
+    ) -> oneshot::Receiver<Result<()>> {
+        let id = self.id;
+
+        self.send_job(None, move |git_repo, _| async move {
+            match git_repo {
+                RepositoryState::Local(LocalRepositoryState {
+                    backend,
+                    environment,
+                    ..
+                }) => backend.reset(commit, reset_mode, environment).await,
+                RepositoryState::Remote(RemoteRepositoryState { project_id, client }) => {
+                    client
+                        .request(proto::GitReset {
+                            project_id: project_id.0,
+                            repository_id: id.to_proto(),
+                            commit,
+                            mode: match reset_mode {
+                                ResetMode::Soft => git_reset::ResetMode::Soft.into(),
+                                ResetMode::Mixed => git_reset::ResetMode::Mixed.into(),
+                                ResetMode::Keep => git_reset::ResetMode::Keep.into(),
+                            },
+                        })
+                        .await?;
+
+                    Ok(())
+                }
+            }
+        })
+    }
+
+    pub fn rebase_todo(&mut self, base: String) -> oneshot::Receiver<Result<Vec<RebaseTodoEntry>>> {
+        let id = self.id;
+        self.send_job(None, move |git_repo, _| async move {
+            match git_repo {
+                RepositoryState::Local(LocalRepositoryState { backend, .. }) => {
+                    backend.rebase_todo(base).await
+                }
+                RepositoryState::Remote(RemoteRepositoryState { project_id, client }) => {
+                    let response = client
+                        .request(proto::GitRebaseTodo {
+                            project_id: project_id.0,
+                            repository_id: id.to_proto(),
+                            base,
+                        })
+                        .await?;
+
+                    Ok(response
+                        .entries
+                        .iter()
+                        .map(proto_to_rebase_todo_entry)
+                        .collect())
+                }
+            }
+        })
+    }
+
+    /// Whether `ancestor` is `descendant` or one of its ancestors.
+    pub fn is_ancestor(
+        &mut self,
+        ancestor: String,
+        descendant: String,
+    ) -> oneshot::Receiver<Result<bool>> {
+        let id = self.id;
+        self.send_job(None, move |git_repo, _| async move {
+            match git_repo {
+                RepositoryState::Local(LocalRepositoryState { backend, .. }) => {
+                    backend.is_ancestor(ancestor, descendant).await
+                }
+                RepositoryState::Remote(RemoteRepositoryState { project_id, client }) => {
+                    let response = client
+                        .request(proto::GitIsAncestor {
+                            project_id: project_id.0,
+                            repository_id: id.to_proto(),
+                            ancestor,
+                            descendant,
+                        })
+                        .await?;
+                    Ok(response.is_ancestor)
+                }
+            }
+        })
+    }
+
+    pub fn rebase(
+        &mut self,
+        base: String,
+        todo: Vec<RebaseTodoEntry>,
+    ) -> oneshot::Receiver<Result<()>> {
+        let id = self.id;
+        self.send_job(
+            Some(format!("git rebase --interactive {base}").into()),
+            move |git_repo, _| async move {
+                match git_repo {
+                    RepositoryState::Local(LocalRepositoryState {
+                        backend,
+                        environment,
+                        ..
+                    }) => backend.rebase(base, todo, environment).await,
+                    RepositoryState::Remote(RemoteRepositoryState { project_id, client }) => {
+                        client
+                            .request(proto::GitRebase {
+                                project_id: project_id.0,
+                                repository_id: id.to_proto(),
+                                base,
+                                todo: todo.iter().map(rebase_todo_entry_to_proto).collect(),
+                            })
+                            .await?;
+
+                        Ok(())
+                    }
+                }
+            },
+        )
+    }
+
+    pub fn cherry_pick(&mut self, commits: Vec<String>) -> oneshot::Receiver<Result<()>> {
+        let id = self.id;
+        self.send_job(
+            Some("git cherry-pick".into()),
+            move |git_repo, _| async move {
+                match git_repo {
+                    RepositoryState::Local(LocalRepositoryState {
+                        backend,
+                        environment,
+                        ..
+                    }) => backend.cherry_pick(commits, environment).await,
+                    RepositoryState::Remote(RemoteRepositoryState { project_id, client }) => {
+                        client
+                            .request(proto::GitCherryPick {
+                                project_id: project_id.0,
+                                repository_id: id.to_proto(),
+                                commits,
+                            })
+                            .await?;
+
+                        Ok(())
+                    }
+                }
+            },
+        )
+    }
+
+    pub fn revert(&mut self, commits: Vec<String>) -> oneshot::Receiver<Result<()>> {
+        let id = self.id;
+        self.send_job(Some("git revert".into()), move |git_repo, _| async move {
+            match git_repo {
+                RepositoryState::Local(LocalRepositoryState {
+                    backend,
+                    environment,
+                    ..
+                }) => backend.revert(commits, environment).await,
+                RepositoryState::Remote(RemoteRepositoryState { project_id, client }) => {
+                    client
+                        .request(proto::GitRevert {
+                            project_id: project_id.0,
+                            repository_id: id.to_proto(),
+                            commits,
+                        })
+                        .await?;
+
+                    Ok(())
+                }
+            }
+        })
+    }
+
+    /// Continues the rebase, cherry-pick or revert that stopped on a conflict.
+    pub fn continue_operation(&mut self) -> oneshot::Receiver<Result<()>> {
+        let id = self.id;
+        self.send_job(None, move |git_repo, _| async move {
+            match git_repo {
+                RepositoryState::Local(LocalRepositoryState {
+                    backend,
+                    environment,
+                    ..
+                }) => backend.continue_operation(environment).await,
+                RepositoryState::Remote(RemoteRepositoryState { project_id, client }) => {
+                    client
+                        .request(proto::GitContinueOperation {
+                            project_id: project_id.0,
+                            repository_id: id.to_proto(),
+                        })
+                        .await?;
+
+                    Ok(())
+                }
+            }
+        })
+    }
+
+    /// Aborts the rebase, cherry-pick or revert in progress.
+    pub fn abort_operation(&mut self) -> oneshot::Receiver<Result<()>> {
+        let id = self.id;
+        self.send_job(None, move |git_repo, _| async move {
+            match git_repo {
+                RepositoryState::Local(LocalRepositoryState {
+                    backend,
+                    environment,
+                    ..
+                }) => backend.abort_operation(environment).await,
+                RepositoryState::Remote(RemoteRepositoryState { project_id, client }) => {
+                    client
+                        .request(proto::GitAbortOperation {
+                            project_id: project_id.0,
+                            repository_id: id.to_proto(),
+                        })
+                        .await?;
+
+                    Ok(())
+                }
+            }
+        })
+    }
+
+    /// Starts bisecting between the `bad` commit, or HEAD if none is given, and the `good` ones.
+    pub fn bisect_start(
+        &mut self,
+        bad: Option<String>,
+        good: Vec<String>,
+    ) -> oneshot::Receiver<Result<()>> {
+        let id = self.id;
+        self.send_job(
+            Some("git bisect start".into()),
+            move |git_repo, _| async move {
+                match git_repo {
+                    RepositoryState::Local(LocalRepositoryState {
+                        backend,
+                        environment,
+                        ..
+                    }) => backend.bisect_start(bad, good, environment).await,
+                    RepositoryState::Remote(RemoteRepositoryState { project_id, client }) => {
+                        client
+                            .request(proto::GitBisectStart {
+                                project_id: project_id.0,
+                                repository_id: id.to_proto(),
+                                bad,
+                                good,
+                            })
+                            .await?;
+
+                        Ok(())
+                    }
+                }
             },
         )
     }
 
-    pub fn reset(
+    /// Marks `commit`, or HEAD if none is given, and checks out the next commit to test.
+    /// Returns the state of the bisect afterwards, which has the first bad commit once it's found.
+    pub fn bisect_mark(
         &mut self,
-        commit: String,
-        reset_mode: ResetMode,
-        _cx: &mut App,
-    ) -> oneshot::Receiver<Result<()>> {
+        mark: BisectMark,
+        commit: Option<String>,
+    ) -> oneshot::Receiver<Result<Option<BisectLog>>> {
         let id = self.id;
-
-        self.send_job(None, move |git_repo, _| async move {
+        let job_message = format!("git bisect {}", mark.as_str());
+        self.send_job(Some(job_message.into()), move |git_repo, _| async move {
             match git_repo {
                 RepositoryState::Local(LocalRepositoryState {
                     backend,
                     environment,
                     ..
-                }) => backend.reset(commit, reset_mode, environment).await,
+                }) => {
+                    backend.bisect_mark(mark, commit, environment).await?;
+                    backend.bisect_log().await
+                }
                 RepositoryState::Remote(RemoteRepositoryState { project_id, client }) => {
-                    client
-                        .request(proto::GitReset {
+                    let response = client
+                        .request(proto::GitBisectMark {
                             project_id: project_id.0,
                             repository_id: id.to_proto(),
-                            commit,
-                            mode: match reset_mode {
-                                ResetMode::Soft => git_reset::ResetMode::Soft.into(),
-                                ResetMode::Mixed => git_reset::ResetMode::Mixed.into(),
+                            mark: match mark {
+                                BisectMark::Good => proto::git_bisect_mark::Mark::Good.into(),
+                                BisectMark::Bad => proto::git_bisect_mark::Mark::Bad.into(),
+                                BisectMark::Skip => proto::git_bisect_mark::Mark::Skip.into(),
                             },
+                            commit,
                         })
                         .await?;
 
-                    Ok(())
+                    Ok(response.bisect.as_ref().map(proto_to_bisect))
+                }
+            }
+        })
+    }
+
+    /// Ends the bisect in progress, checking out the commit it started from.
+    pub fn bisect_reset(&mut self) -> oneshot::Receiver<Result<()>> {
+        let id = self.id;
+        self.send_job(
+            Some("git bisect reset".into()),
+            move |git_repo, _| async move {
+                match git_repo {
+                    RepositoryState::Local(LocalRepositoryState {
+                        backend,
+                        environment,
+                        ..
+                    }) => backend.bisect_reset(environment).await,
+                    RepositoryState::Remote(RemoteRepositoryState { project_id, client }) => {
+                        client
+                            .request(proto::GitBisectReset {
+                                project_id: project_id.0,
+                                repository_id: id.to_proto(),
+                            })
+                            .await?;
+
+                        Ok(())
+                    }
+                }
+            },
+        )
+    }
+
+    /// Checks out the recorded commit of the submodules at `paths`, or of all submodules
+    /// if `paths` is empty. With `init`, uninitialized submodules get cloned first.
+    pub fn update_submodules(
+        &mut self,
+        paths: Vec<RepoPath>,
+        init: bool,
+        askpass: AskPassDelegate,
+    ) -> oneshot::Receiver<Result<RemoteCommandOutput>> {
+        let askpass_delegates = self.askpass_delegates.clone();
+        let askpass_id = util::post_inc(&mut self.latest_askpass_id);
+        let id = self.id;
+        let job_message = if init {
+            "git submodule update --init"
+        } else {
+            "git submodule update"
+        };
+
+        self.send_job(Some(job_message.into()), move |git_repo, cx| async move {
+            match git_repo {
+                RepositoryState::Local(LocalRepositoryState {
+                    backend,
+                    environment,
+                    ..
+                }) => {
+                    backend
+                        .update_submodules(paths, init, askpass, environment, cx)
+                        .await
+                }
+                RepositoryState::Remote(RemoteRepositoryState { project_id, client }) => {
+                    askpass_delegates.lock().insert(askpass_id, askpass);
+                    let _defer = util::defer(|| {
+                        let askpass_delegate = askpass_delegates.lock().remove(&askpass_id);
+                        debug_assert!(askpass_delegate.is_some());
+                    });
+
+                    let response = client
+                        .request(proto::GitUpdateSubmodules {
+                            project_id: project_id.0,
+                            repository_id: id.to_proto(),
+                            paths: paths.iter().map(|path| path.to_proto()).collect(),
+                            init,
+                            askpass_id,
+                        })
+                        .await?;
+
+                    Ok(RemoteCommandOutput {
+                        stdout: response.stdout,
+                        stderr: response.stderr,
+                    })
                 }
             }
         })
     }
 
+    /// Copies the submodule URLs from `.gitmodules` into the repository's config.
+    pub fn sync_submodules(&mut self, paths: Vec<RepoPath>) -> oneshot::Receiver<Result<()>> {
+        let id = self.id;
+        self.send_job(
+            Some("git submodule sync".into()),
+            move |git_repo, _| async move {
+                match git_repo {
+                    RepositoryState::Local(LocalRepositoryState {
+                        backend,
+                        environment,
+                        ..
+                    }) => backend.sync_submodules(paths, environment).await,
+                    RepositoryState::Remote(RemoteRepositoryState { project_id, client }) => {
+                        client
+                            .request(proto::GitSyncSubmodules {
+                                project_id: project_id.0,
+                                repository_id: id.to_proto(),
+                                paths: paths.iter().map(|path| path.to_proto()).collect(),
+                            })
+                            .await?;
+
+                        Ok(())
+                    }
+                }
+            },
+        )
+    }
+
     pub fn show(&mut self, commit: String) -> oneshot::Receiver<Result<CommitDetails>> {
         let id = self.id;
         self.send_job(None, move |git_repo, _cx| async move {
@@ -5889,6 +6600,7 @@ impl Repository {
                             project_id: project_id.0,
                             repository_id: id.to_proto(),
                             branch_name,
+                            base_branch,
                         })
                         .await?;
 
@@ -5946,6 +6658,139 @@ impl Repository {
         )
     }
 
+    pub fn tags(&mut self) -> oneshot::Receiver<Result<Vec<Tag>>> {
+        let id = self.id;
+        self.send_job(None, move |repo, _| async move {
+            match repo {
+                RepositoryState::Local(LocalRepositoryState { backend, .. }) => {
+                    backend.tags().await
+                }
+                RepositoryState::Remote(RemoteRepositoryState { project_id, client }) => {
+                    let response = client
+                        .request(proto::GitGetTags {
+                            project_id: project_id.0,
+                            repository_id: id.to_proto(),
+                        })
+                        .await?;
+
+                    Ok(response.tags.iter().map(proto_to_tag).collect())
+                }
+            }
+        })
+    }
+
+    /// Returns the most recent `limit` entries of the reflog of `ref_name`, newest first.
+    pub fn reflog(
+        &mut self,
+        ref_name: String,
+        limit: usize,
+    ) -> oneshot::Receiver<Result<Vec<ReflogEntry>>> {
+        let id = self.id;
+        self.send_job(None, move |repo, _| async move {
+            match repo {
+                RepositoryState::Local(LocalRepositoryState { backend, .. }) => {
+                    backend.reflog(ref_name, limit).await
+                }
+                RepositoryState::Remote(RemoteRepositoryState { project_id, client }) => {
+                    let response = client
+                        .request(proto::GitGetReflog {
+                            project_id: project_id.0,
+                            repository_id: id.to_proto(),
+                            ref_name,
+                            limit: limit as u64,
+                        })
+                        .await?;
+
+                    Ok(response.entries.iter().map(proto_to_reflog_entry).collect())
+                }
+            }
+        })
+    }
+
+    /// Creates a tag pointing at `target`, or HEAD if none is given.
+    /// The tag is annotated when a `message` is given, and lightweight otherwise.
+    pub fn create_tag(
+        &mut self,
+        name: String,
+        target: Option<String>,
+        message: Option<String>,
+        cx: &mut Context<Self>,
+    ) -> oneshot::Receiver<Result<()>> {
+        let id = self.id;
+        let this = cx.weak_entity();
+        self.send_job(
+            Some(format!("git tag {name}").into()),
+            move |repo, mut cx| async move {
+                match repo {
+                    RepositoryState::Local(LocalRepositoryState {
+                        backend,
+                        environment,
+                        ..
+                    }) => {
+                        backend
+                            .create_tag(name, target, message, environment)
+                            .await?
+                    }
+                    RepositoryState::Remote(RemoteRepositoryState { project_id, client }) => {
+                        client
+                            .request(proto::GitCreateTag {
+                                project_id: project_id.0,
+                                repository_id: id.to_proto(),
+                                name,
+                                target,
+                                message,
+                            })
+                            .await?;
+                    }
+                }
+                // Tags are not part of the repository snapshot, have the ref labels reloaded.
+                this.update(&mut cx, |_, cx| cx.emit(RepositoryEvent::BranchChanged))
+            },
+        )
+    }
+
+    pub fn delete_tag(
+        &mut self,
+        name: String,
+        cx: &mut Context<Self>,
+    ) -> oneshot::Receiver<Result<()>> {
+        let id = self.id;
+        let this = cx.weak_entity();
+        self.send_job(
+            Some(format!("git tag --delete {name}").into()),
+            move |repo, mut cx| async move {
+                match repo {
+                    RepositoryState::Local(LocalRepositoryState {
+                        backend,
+                        environment,
+                        ..
+                    }) => backend.delete_tag(name, environment).await?,
+                    RepositoryState::Remote(RemoteRepositoryState { project_id, client }) => {
+                        client
+                            .request(proto::GitDeleteTag {
+                                project_id: project_id.0,
+                                repository_id: id.to_proto(),
+                                name,
+                            })
+                            .await?;
+                    }
+                }
+                this.update(&mut cx, |_, cx| cx.emit(RepositoryEvent::BranchChanged))
+            },
+        )
+    }
+
+    pub fn push_tag(
+        &mut self,
+        name: SharedString,
+        remote: SharedString,
+        askpass: AskPassDelegate,
+        cx: &mut Context<Self>,
+    ) -> oneshot::Receiver<Result<RemoteCommandOutput>> {
+        let tag_ref = SharedString::from(format!("refs/tags/{name}"));
+        self.push(tag_ref.clone(), tag_ref, remote, None, askpass, cx)
+    }
+
     pub fn rename_branch(
         &mut self,
         branch: String,
@@ -6067,6 +6912,20 @@ impl Repository {
             cx.emit(RepositoryEvent::StashEntriesChanged)
         }
         self.snapshot.stash_entries = new_stash_entries;
+        let new_bisect = update.bisect.as_ref().map(proto_to_bisect);
+        if self.snapshot.bisect != new_bisect {
+            cx.emit(RepositoryEvent::BisectChanged)
+        }
+        self.snapshot.bisect = new_bisect;
+        let new_submodules = update
+            .submodules
+            .iter()
+            .filter_map(|submodule| proto_to_submodule(submodule).log_err())
+            .collect::<Arc<[_]>>();
+        if self.snapshot.submodules != new_submodules {
+            cx.emit(RepositoryEvent::SubmodulesChanged)
+        }
+        self.snapshot.submodules = new_submodules;
         self.snapshot.remote_upstream_url = update.remote_upstream_url;
         self.snapshot.remote_origin_url = update.remote_origin_url;
 
@@ -6747,6 +7606,116 @@ fn proto_to_worktree(proto: &proto::Worktree) -> git::repository::Worktree {
     }
 }
 
+fn tag_to_proto(tag: &Tag) -> proto::Tag {
+    proto::Tag {
+        name: tag.name.to_string(),
+        sha: tag.sha.to_string(),
+        annotation: tag.annotation.as_ref().map(ToString::to_string),
+    }
+}
+
+fn proto_to_tag(proto: &proto::Tag) -> Tag {
+    Tag {
+        name: proto.name.clone().into(),
+        sha: proto.sha.clone().into(),
+        annotation: proto.annotation.clone().map(Into::into),
+    }
+}
+
+fn reflog_entry_to_proto(entry: &ReflogEntry) -> proto::ReflogEntry {
+    proto::ReflogEntry {
+        selector: entry.selector.to_string(),
+        sha: entry.sha.to_string(),
+        message: entry.message.to_string(),
+        timestamp: entry.timestamp,
+    }
+}
+
+fn proto_to_reflog_entry(proto: &proto::ReflogEntry) -> ReflogEntry {
+    ReflogEntry {
+        selector: proto.selector.clone().into(),
+        sha: proto.sha.clone().into(),
+        message: proto.message.clone().into(),
+        timestamp: proto.timestamp,
+    }
+}
+
+fn bisect_to_proto(bisect: &BisectLog) -> proto::BisectLog {
+    proto::BisectLog {
+        bad: bisect.bad.as_ref().map(ToString::to_string),
+        good: bisect.good.iter().map(ToString::to_string).collect(),
+        skipped: bisect.skipped.iter().map(ToString::to_string).collect(),
+        first_bad: bisect.first_bad.as_ref().map(ToString::to_string),
+        remaining: bisect.remaining,
+        steps: bisect.steps,
+    }
+}
+
+fn proto_to_bisect(proto: &proto::BisectLog) -> BisectLog {
+    BisectLog {
+        bad: proto.bad.clone().map(Into::into),
+        good: proto.good.iter().cloned().map(Into::into).collect(),
+        skipped: proto.skipped.iter().cloned().map(Into::into).collect(),
+        first_bad: proto.first_bad.clone().map(Into::into),
+        remaining: proto.remaining,
+        steps: proto.steps,
+    }
+}
+
+fn submodule_to_proto(submodule: &Submodule) -> proto::Submodule {
+    proto::Submodule {
+        path: submodule.path.to_proto(),
+        sha: submodule.sha.to_string(),
+        is_initialized: submodule.is_initialized,
+        has_new_commits: submodule.has_new_commits,
+        has_modified_content: submodule.has_modified_content,
+        has_untracked_content: submodule.has_untracked_content,
+    }
+}
+
+fn proto_to_submodule(proto: &proto::Submodule) -> Result<Submodule> {
+    Ok(Submodule {
+        path: RepoPath::from_proto(&proto.path)?,
+        sha: proto.sha.clone().into(),
+        is_initialized: proto.is_initialized,
+        has_new_commits: proto.has_new_commits,
+        has_modified_content: proto.has_modified_content,
+        has_untracked_content: proto.has_untracked_content,
+    })
+}
+
+fn rebase_todo_entry_to_proto(entry: &RebaseTodoEntry) -> proto::RebaseTodoEntry {
+    let action = match entry.action {
+        RebaseTodoAction::Pick => proto::rebase_todo_entry::Action::Pick,
+        RebaseTodoAction::Reword => proto::rebase_todo_entry::Action::Reword,
+        RebaseTodoAction::Squash => proto::rebase_todo_entry::Action::Squash,
+        RebaseTodoAction::Fixup => proto::rebase_todo_entry::Action::Fixup,
+        RebaseTodoAction::Drop => proto::rebase_todo_entry::Action::Drop,
+    };
+    proto::RebaseTodoEntry {
+        action: action.into(),
+        sha: entry.sha.to_string(),
+        subject: entry.subject.to_string(),
+        message: entry.message.as_ref().map(ToString::to_string),
+    }
+}
+
+fn proto_to_rebase_todo_entry(proto: &proto::RebaseTodoEntry) -> RebaseTodoEntry {
+    let action = match proto.action() {
+        proto::rebase_todo_entry::Action::Pick => RebaseTodoAction::Pick,
+        proto::rebase_todo_entry::Action::Reword => RebaseTodoAction::Reword,
+        proto::rebase_todo_entry::Action::Squash => RebaseTodoAction::Squash,
+        proto::rebase_todo_entry::Action::Fixup => RebaseTodoAction::Fixup,
+        proto::rebase_todo_entry::Action::Drop => RebaseTodoAction::Drop,
+    };
+    RebaseTodoEntry {
+        action,
+        sha: proto.sha.clone().into(),
+        subject: proto.subject.clone().into(),
+        message: proto.message.clone().map(Into::into),
+    }
+}
+
 fn proto_to_branch(proto: &proto::Branch) -> git::repository::Branch {
     git::repository::Branch {
         is_head: proto.is_head,
@@ -6860,6 +7829,21 @@ async fn compute_snapshot(
         events.push(RepositoryEvent::BranchChanged);
     }
 
+    let bisect = backend.bisect_log().await.log_err().flatten();
+    if bisect != prev_snapshot.bisect {
+        events.push(RepositoryEvent::BisectChanged);
+    }
+
+    let submodules: Arc<[Submodule]> = backend
+        .submodules()
+        .await
+        .log_err()
+        .unwrap_or_default()
+        .into();
+    if submodules != prev_snapshot.submodules {
+        events.push(RepositoryEvent::SubmodulesChanged);
+    }
+
     let remote_origin_url = backend.remote_url("origin").await;
     let remote_upstream_url = backend.remote_url("upstream").await;
 
@@ -6876,6 +7860,8 @@ async fn compute_snapshot(
         remote_origin_url,
         remote_upstream_url,
         stash_entries,
+        bisect,
+        submodules,
     };
 
     Ok((snapshot, events))
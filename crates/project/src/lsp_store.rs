@@ -12,6 +12,7 @@
 pub mod clangd_ext;
 mod code_lens;
 mod document_colors;
+mod document_links;
 mod document_symbols;
 mod folding_ranges;
 mod inlay_hints;
@@ -20,17 +21,20 @@ pub mod log_store;
 pub mod lsp_ext_command;
 pub mod rust_analyzer_ext;
 mod semantic_tokens;
+mod task_diagnostics;
 pub mod vue_language_server_ext;
 
 use self::code_lens::CodeLensData;
 use self::document_colors::DocumentColorData;
+use self::document_links::DocumentLinksData;
 use self::document_symbols::DocumentSymbolsData;
 use self::inlay_hints::BufferInlayHints;
+use self::task_diagnostics::TaskDiagnostics;
 use crate::{
     CodeAction, Completion, CompletionDisplayOptions, CompletionResponse, CompletionSource,
     CoreCompletion, Hover, InlayHint, InlayId, LocationLink, LspAction, LspPullDiagnostics,
     ManifestProvidersStore, Project, ProjectItem, ProjectPath, ProjectTransaction,
-    PulledDiagnostics, ResolveState, Symbol,
+    PulledDiagnostics, ResolveState, Symbol, TypeHierarchyItem,
     buffer_store::{BufferStore, BufferStoreEvent},
     environment::ProjectEnvironment,
     lsp_command::{self, *},
@@ -133,6 +137,7 @@ use std::{
     vec,
 };
 use sum_tree::Dimensions;
+use task::TaskId;
 use text::{Anchor, BufferId, LineEnding, OffsetRangeExt, ToPoint as _};
 
 use util::{
@@ -144,6 +149,7 @@ use util::{
 };
 
 pub use document_colors::DocumentColors;
+pub use document_links::DocumentLink;
 pub use folding_ranges::LspFoldingRange;
 pub use fs::*;
 pub use language::Location;
@@ -283,6 +289,29 @@ struct DynamicRegistrations {
     diagnostics: HashMap<Option<String>, DiagnosticServerCapabilities>,
 }
 
+/// The operations of a workspace edit, in the order they're applied.
+pub(crate) fn workspace_edit_operations(
+    edit: lsp::WorkspaceEdit,
+) -> Vec<lsp::DocumentChangeOperation> {
+    let mut operations = Vec::new();
+    if let Some(document_changes) = edit.document_changes {
+        match document_changes {
+            lsp::DocumentChanges::Edits(edits) => {
+                operations.extend(edits.into_iter().map(lsp::DocumentChangeOperation::Edit))
+            }
+            lsp::DocumentChanges::Operations(ops) => operations = ops,
+        }
+    } else if let Some(changes) = edit.changes {
+        operations.extend(changes.into_iter().map(|(uri, edits)| {
+            lsp::DocumentChangeOperation::Edit(lsp::TextDocumentEdit {
+                text_document: lsp::OptionalVersionedTextDocumentIdentifier { uri, version: None },
+                edits: edits.into_iter().map(Edit::Plain).collect(),
+            })
+        }));
+    }
+    operations
+}
+
 pub struct LocalLspStore {
     weak: WeakEntity<LspStore>,
     pub worktree_store: Entity<WorktreeStore>,
@@ -329,6 +358,7 @@ pub struct LocalLspStore {
         HashMap<Option<SharedString>, HashMap<PathBuf, Option<SharedString>>>,
     >,
     restricted_worktrees_tasks: HashMap<WorktreeId, (Subscription, watch::Receiver<bool>)>,
+    task_diagnostics: HashMap<TaskId, TaskDiagnostics>,
 
     buffers_to_refresh_hash_set: HashSet<BufferId>,
     buffers_to_refresh_queue: VecDeque<BufferId>,
@@ -1092,6 +1122,7 @@ impl LocalLspStore {
                     let mut cx = cx.clone();
                     async move {
                         this.update(&mut cx, |this, cx| {
+                            this.invalidate_code_lens();
                             cx.emit(LspStoreEvent::RefreshCodeLens);
                             this.downstream_client.as_ref().map(|(client, project_id)| {
                                 client.send(proto::RefreshCodeLens {
@@ -3240,28 +3271,8 @@ impl LocalLspStore {
     ) -> Result<ProjectTransaction> {
         let fs = this.read_with(cx, |this, _| this.as_local().unwrap().fs.clone());
 
-        let mut operations = Vec::new();
-        if let Some(document_changes) = edit.document_changes {
-            match document_changes {
-                lsp::DocumentChanges::Edits(edits) => {
-                    operations.extend(edits.into_iter().map(lsp::DocumentChangeOperation::Edit))
-                }
-                lsp::DocumentChanges::Operations(ops) => operations = ops,
-            }
-        } else if let Some(changes) = edit.changes {
-            operations.extend(changes.into_iter().map(|(uri, edits)| {
-                lsp::DocumentChangeOperation::Edit(lsp::TextDocumentEdit {
-                    text_document: lsp::OptionalVersionedTextDocumentIdentifier {
-                        uri,
-                        version: None,
-                    },
-                    edits: edits.into_iter().map(Edit::Plain).collect(),
-                })
-            }));
-        }
-
         let mut project_transaction = ProjectTransaction::default();
-        for operation in operations {
+        for operation in workspace_edit_operations(edit) {
             match operation {
                 lsp::DocumentChangeOperation::Op(lsp::ResourceOp::Create(op)) => {
                     let abs_path = op
@@ -3925,6 +3936,7 @@ pub struct BufferLspData {
     semantic_tokens: Option<SemanticTokensData>,
     folding_ranges: Option<FoldingRangeData>,
     document_symbols: Option<DocumentSymbolsData>,
+    document_links: Option<DocumentLinksData>,
     inlay_hints: BufferInlayHints,
     lsp_requests: HashMap<LspKey, HashMap<LspRequestId, Task<()>>>,
     chunk_lsp_requests: HashMap<LspKey, HashMap<RowChunk, LspRequestId>>,
@@ -3945,6 +3957,7 @@ impl BufferLspData {
             semantic_tokens: None,
             folding_ranges: None,
             document_symbols: None,
+            document_links: None,
             inlay_hints: BufferInlayHints::new(buffer, cx),
             lsp_requests: HashMap::default(),
             chunk_lsp_requests: HashMap::default(),
@@ -3976,6 +3989,10 @@ impl BufferLspData {
         if let Some(document_symbols) = &mut self.document_symbols {
             document_symbols.remove_server_data(for_server);
         }
+
+        if let Some(document_links) = &mut self.document_links {
+            document_links.links.remove(&for_server);
+        }
     }
 
     #[cfg(any(test, feature = "test-support"))]
@@ -4093,6 +4110,8 @@ impl LspStore {
         client.add_entity_request_handler(Self::handle_refresh_inlay_hints);
         client.add_entity_request_handler(Self::handle_refresh_semantic_tokens);
         client.add_entity_request_handler(Self::handle_refresh_code_lens);
+        client.add_entity_request_handler(Self::handle_resolve_code_lens);
+        client.add_entity_request_handler(Self::handle_resolve_document_link);
         client.add_entity_request_handler(Self::handle_on_type_formatting);
         client.add_entity_request_handler(Self::handle_apply_additional_edits_for_completion);
         client.add_entity_request_handler(Self::handle_register_buffer_with_language_servers);
@@ -4101,8 +4120,15 @@ impl LspStore {
         client.add_entity_request_handler(Self::handle_lsp_get_completions);
         client.add_entity_request_handler(Self::handle_lsp_command::<GetDocumentHighlights>);
         client.add_entity_request_handler(Self::handle_lsp_command::<GetDocumentSymbols>);
+        client.add_entity_request_handler(Self::handle_lsp_command::<PrepareCallHierarchy>);
+        client.add_entity_request_handler(Self::handle_lsp_command::<GetIncomingCalls>);
+        client.add_entity_request_handler(Self::handle_lsp_command::<GetOutgoingCalls>);
+        client.add_entity_request_handler(Self::handle_prepare_type_hierarchy);
+        client.add_entity_request_handler(Self::handle_lsp_command::<GetSupertypes>);
+        client.add_entity_request_handler(Self::handle_lsp_command::<GetSubtypes>);
         client.add_entity_request_handler(Self::handle_lsp_command::<PrepareRename>);
         client.add_entity_request_handler(Self::handle_lsp_command::<PerformRename>);
+        client.add_entity_request_handler(Self::handle_lsp_command::<GetRenameEdit>);
         client.add_entity_request_handler(Self::handle_lsp_command::<LinkedEditingRange>);
 
         client.add_entity_request_handler(Self::handle_lsp_ext_cancel_flycheck);
@@ -4229,6 +4255,7 @@ impl LspStore {
                 buffer_pull_diagnostics_result_ids: HashMap::default(),
                 workspace_pull_diagnostics_result_ids: HashMap::default(),
                 restricted_worktrees_tasks: HashMap::default(),
+                task_diagnostics: HashMap::default(),
                 watched_manifest_filenames: ManifestProvidersStore::global(cx)
                     .manifest_file_names(),
             }),
@@ -5067,7 +5094,14 @@ impl LspStore {
                 if !message.ends_with("content modified") {
                     log::warn!("{message}");
                 }
-                anyhow::anyhow!(message)
+                // Keep the server's error code, for callers to tell what went wrong.
+                match err.downcast::<lsp::ResponseError>() {
+                    Ok(error) => anyhow::Error::new(lsp::ResponseError {
+                        code: error.code,
+                        message,
+                    }),
+                    Err(_) => anyhow::anyhow!(message),
+                }
             })?;
 
             request
@@ -7512,6 +7546,60 @@ impl LspStore {
         }
     }
 
+    /// Prepares a type hierarchy at `position` with each of the buffer's language servers.
+    ///
+    /// Servers don't report whether they support type hierarchies in a way that
+    /// `lsp::ServerCapabilities` can tell, so those that answer that the method isn't found are
+    /// skipped instead.
+    pub fn prepare_type_hierarchy(
+        &mut self,
+        buffer: &Entity<Buffer>,
+        position: PointUtf16,
+        cx: &mut Context<Self>,
+    ) -> Task<Result<Vec<TypeHierarchyItem>>> {
+        if self.upstream_client().is_some() {
+            return self.request_lsp(
+                buffer.clone(),
+                LanguageServerToQuery::FirstCapable,
+                PrepareTypeHierarchy { position },
+                cx,
+            );
+        }
+
+        let server_ids = buffer.update(cx, |buffer, cx| {
+            self.as_local()
+                .map(|local| {
+                    local
+                        .language_servers_for_buffer(buffer, cx)
+                        .map(|(_, server)| server.server_id())
+                        .collect::<Vec<_>>()
+                })
+                .unwrap_or_default()
+        });
+        let requests = server_ids
+            .into_iter()
+            .map(|server_id| {
+                self.request_lsp(
+                    buffer.clone(),
+                    LanguageServerToQuery::Other(server_id),
+                    PrepareTypeHierarchy { position },
+                    cx,
+                )
+            })
+            .collect::<Vec<_>>();
+        cx.spawn(async move |_, _| {
+            let mut items = Vec::new();
+            for result in join_all(requests).await {
+                match result {
+                    Ok(server_items) => items.extend(server_items),
+                    Err(error) if is_method_not_found(&error) => {}
+                    Err(error) => return Err(error),
+                }
+            }
+            Ok(items)
+        })
+    }
+
     pub fn symbols(&self, query: &str, cx: &mut Context<Self>) -> Task<Result<Vec<Symbol>>> {
         let language_registry = self.languages.clone();
 
@@ -7938,6 +8026,8 @@ impl LspStore {
         });
         for language_server_id in language_servers {
             self.simulate_disk_based_diagnostics_events_if_needed(language_server_id, cx);
+            // Saving may change what other, unopened files report, so re-pull diagnostics for the whole workspace.
+            self.pull_workspace_diagnostics(language_server_id);
         }
 
         None
@@ -8771,7 +8861,7 @@ impl LspStore {
             .update(&mut cx, |this, cx| {
                 this.request_lsp(
                     buffer_handle.clone(),
-                    LanguageServerToQuery::FirstCapable,
+                    request.server_to_query(),
                     request,
                     cx,
                 )
@@ -8788,6 +8878,39 @@ impl LspStore {
         })
     }
 
+    async fn handle_prepare_type_hierarchy(
+        this: Entity<Self>,
+        envelope: TypedEnvelope<proto::PrepareTypeHierarchy>,
+        mut cx: AsyncApp,
+    ) -> Result<proto::PrepareTypeHierarchyResponse> {
+        let sender_id = envelope.original_sender_id().unwrap_or_default();
+        let buffer_id = PrepareTypeHierarchy::buffer_id_from_proto(&envelope.payload)?;
+        let buffer = this.update(&mut cx, |this, cx| {
+            this.buffer_store.read(cx).get_existing(buffer_id)
+        })?;
+        let PrepareTypeHierarchy { position } = PrepareTypeHierarchy::from_proto(
+            envelope.payload,
+            this.clone(),
+            buffer.clone(),
+            cx.clone(),
+        )
+        .await?;
+        let items = this
+            .update(&mut cx, |this, cx| {
+                this.prepare_type_hierarchy(&buffer, position, cx)
+            })
+            .await?;
+        this.update(&mut cx, |this, cx| {
+            Ok(PrepareTypeHierarchy::response_to_proto(
+                items,
+                this,
+                sender_id,
+                &buffer.read(cx).version(),
+                cx,
+            ))
+        })
+    }
+
     async fn handle_lsp_query(
         lsp_store: Entity<Self>,
         envelope: TypedEnvelope<proto::LspQuery>,
@@ -8848,6 +8971,18 @@ impl LspStore {
                 )
                 .await?;
             }
+            Request::GetDocumentLinks(get_document_links) => {
+                Self::query_lsp_locally::<GetDocumentLinks>(
+                    lsp_store,
+                    server_id,
+                    sender_id,
+                    lsp_request_id,
+                    get_document_links,
+                    None,
+                    &mut cx,
+                )
+                .await?;
+            }
             Request::GetHover(get_hover) => {
                 let position = get_hover.position.clone().and_then(deserialize_anchor);
                 Self::query_lsp_locally::<GetHover>(
@@ -9850,6 +9985,20 @@ impl LspStore {
         self.as_local()?.language_server_for_id(id)
     }
 
+    pub(crate) fn running_language_servers_for_worktree(
+        &self,
+        worktree_id: WorktreeId,
+    ) -> Vec<Arc<LanguageServer>> {
+        self.as_local()
+            .map(|local| {
+                local
+                    .language_servers_for_worktree(worktree_id)
+                    .cloned()
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+
     fn on_lsp_progress(
         &mut self,
         progress_params: lsp::ProgressParams,
@@ -12570,6 +12719,17 @@ impl LspStore {
                     });
                     notify_server_capabilities_updated(&server, cx);
                 }
+                "textDocument/prepareCallHierarchy" => {
+                    let options = parse_register_capabilities(reg)?;
+                    let provider = match options {
+                        OneOf::Left(value) => lsp::CallHierarchyServerCapability::Simple(value),
+                        OneOf::Right(caps) => caps,
+                    };
+                    server.update_capabilities(|capabilities| {
+                        capabilities.call_hierarchy_provider = Some(provider);
+                    });
+                    notify_server_capabilities_updated(&server, cx);
+                }
                 _ => log::warn!("unhandled capability registration: {reg:?}"),
             }
         }
@@ -12773,6 +12933,12 @@ impl LspStore {
                     });
                     notify_server_capabilities_updated(&server, cx);
                 }
+                "textDocument/prepareCallHierarchy" => {
+                    server.update_capabilities(|capabilities| {
+                        capabilities.call_hierarchy_provider = None;
+                    });
+                    notify_server_capabilities_updated(&server, cx);
+                }
                 _ => log::warn!("unhandled capability unregistration: {unreg:?}"),
             }
         }
@@ -13359,6 +13525,13 @@ fn remove_empty_hover_blocks(mut hover: Hover) -> Option<Hover> {
     }
 }
 
+/// Returns whether a language server answered a request by saying that it doesn't implement it.
+fn is_method_not_found(error: &anyhow::Error) -> bool {
+    error
+        .downcast_ref::<lsp::ResponseError>()
+        .is_some_and(|error| error.code == lsp::ResponseError::METHOD_NOT_FOUND)
+}
+
 async fn populate_labels_for_completions(
     new_completions: Vec<CoreCompletion>,
     language: Option<Arc<Language>>,
@@ -13,10 +13,12 @@ use lsp::LanguageServerId;
 use rpc::{TypedEnvelope, proto};
 use settings::Settings as _;
 use std::time::Duration;
+use text::BufferId;
 
 use crate::{
-    CodeAction, LspStore, LspStoreEvent,
+    CodeAction, LspAction, LspStore, LspStoreEvent,
     lsp_command::{GetCodeLens, LspCommand as _},
+    lsp_store::LocalLspStore,
     project_settings::ProjectSettings,
 };
 
@@ -202,6 +204,68 @@ impl LspStore {
         }
     }
 
+    /// Resolves the command of a code lens that the server sent without one.
+    pub fn resolve_code_lens(
+        &self,
+        buffer: &Entity<Buffer>,
+        mut lens: CodeAction,
+        cx: &mut Context<Self>,
+    ) -> Task<Result<CodeAction>> {
+        if lens.resolved {
+            return Task::ready(Ok(lens));
+        }
+        if let Some((upstream_client, project_id)) = self.upstream_client() {
+            if !self.check_if_capable_for_proto_request(buffer, GetCodeLens::can_resolve_lens, cx) {
+                lens.resolved = true;
+                return Task::ready(Ok(lens));
+            }
+            let request = proto::ResolveCodeLens {
+                project_id,
+                buffer_id: buffer.read(cx).remote_id().into(),
+                lens: Some(Self::serialize_code_action(&lens)),
+            };
+            cx.background_spawn(async move {
+                let response = upstream_client
+                    .request(request)
+                    .await
+                    .context("code lens proto resolve request")?;
+                match response.lens {
+                    Some(resolved_lens) => Self::deserialize_code_action(resolved_lens),
+                    None => Ok(lens),
+                }
+            })
+        } else {
+            let Some(lang_server) = buffer.update(cx, |buffer, cx| {
+                self.language_server_for_local_buffer(buffer, lens.server_id, cx)
+                    .map(|(_, server)| server.clone())
+            }) else {
+                return Task::ready(Ok(lens));
+            };
+            if !matches!(&lens.lsp_action, LspAction::CodeLens(code_lens) if code_lens.command.is_none())
+            {
+                lens.resolved = true;
+                return Task::ready(Ok(lens));
+            }
+            let request_timeout = ProjectSettings::get_global(cx)
+                .global_lsp_settings
+                .get_request_timeout();
+            cx.background_spawn(async move {
+                LocalLspStore::try_resolve_code_action(&lang_server, &mut lens, request_timeout)
+                    .await
+                    .context("code lens resolve LSP request")?;
+                Ok(lens)
+            })
+        }
+    }
+
+    /// Drops the cached lenses of all buffers, so that they are queried again after a server
+    /// asks for a refresh.
+    pub(super) fn invalidate_code_lens(&mut self) {
+        for lsp_data in self.lsp_data.values_mut() {
+            lsp_data.code_lens = None;
+        }
+    }
+
     #[cfg(any(test, feature = "test-support"))]
     pub fn forget_code_lens_task(&mut self, buffer_id: text::BufferId) -> Option<CodeLensTask> {
         Some(
@@ -220,9 +284,35 @@ impl LspStore {
         _: TypedEnvelope<proto::RefreshCodeLens>,
         mut cx: AsyncApp,
     ) -> Result<proto::Ack> {
-        this.update(&mut cx, |_, cx| {
+        this.update(&mut cx, |this, cx| {
+            this.invalidate_code_lens();
             cx.emit(LspStoreEvent::RefreshCodeLens);
         });
         Ok(proto::Ack {})
     }
+
+    pub(super) async fn handle_resolve_code_lens(
+        lsp_store: Entity<Self>,
+        envelope: TypedEnvelope<proto::ResolveCodeLens>,
+        mut cx: AsyncApp,
+    ) -> Result<proto::ResolveCodeLensResponse> {
+        let lens = Self::deserialize_code_action(
+            envelope
+                .payload
+                .lens
+                .context("missing code lens in the resolve request")?,
+        )?;
+        let buffer = lsp_store.update(&mut cx, |lsp_store, cx| {
+            let buffer_id = BufferId::new(envelope.payload.buffer_id)?;
+            lsp_store.buffer_store.read(cx).get_existing(buffer_id)
+        })?;
+        let lens = lsp_store
+            .update(&mut cx, |lsp_store, cx| {
+                lsp_store.resolve_code_lens(&buffer, lens, cx)
+            })
+            .await?;
+        Ok(proto::ResolveCodeLensResponse {
+            lens: Some(Self::serialize_code_action(&lens)),
+        })
+    }
 }
@@ -0,0 +1,379 @@
+use std::ops::Range;
+use std::sync::Arc;
+use std::time::Duration;
+
+use anyhow::{Context as _, Result};
+use clock::Global;
+use collections::HashMap;
+use futures::FutureExt as _;
+use futures::future::{Shared, join_all};
+use gpui::{AppContext as _, AsyncApp, Context, Entity, Task};
+use itertools::Itertools;
+use language::{
+    Buffer,
+    proto::{deserialize_anchor_range, serialize_anchor_range},
+};
+use lsp::LanguageServerId;
+use rpc::{TypedEnvelope, proto};
+use settings::Settings as _;
+use text::{Anchor, BufferId};
+
+use crate::lsp_command::{GetDocumentLinks, LspCommand as _};
+use crate::lsp_store::LspStore;
+use crate::project_settings::ProjectSettings;
+
+/// A range of a document that links to another document or to a URL.
+#[derive(Clone, Debug)]
+pub struct DocumentLink {
+    pub range: Range<Anchor>,
+    pub server_id: LanguageServerId,
+    pub lsp_link: lsp::DocumentLink,
+    /// Whether the link's target was resolved, or the server can't resolve it.
+    pub resolved: bool,
+}
+
+impl DocumentLink {
+    pub fn target(&self) -> Option<&lsp::Uri> {
+        self.lsp_link.target.as_ref()
+    }
+
+    pub fn tooltip(&self) -> Option<&str> {
+        self.lsp_link.tooltip.as_deref()
+    }
+}
+
+pub(super) type DocumentLinksTask =
+    Shared<Task<std::result::Result<Vec<DocumentLink>, Arc<anyhow::Error>>>>;
+
+#[derive(Debug, Default)]
+pub(super) struct DocumentLinksData {
+    pub(super) links: HashMap<LanguageServerId, Vec<DocumentLink>>,
+    links_update: Option<(Global, DocumentLinksTask)>,
+}
+
+impl LspStore {
+    /// Returns a task that resolves to the document links of the given buffer, sorted by
+    /// their start.
+    ///
+    /// Caches results per buffer version so repeated calls for the same version
+    /// return immediately. Deduplicates concurrent in-flight requests.
+    pub fn document_links(
+        &mut self,
+        buffer: &Entity<Buffer>,
+        cx: &mut Context<Self>,
+    ) -> Task<Vec<DocumentLink>> {
+        let version_queried_for = buffer.read(cx).version();
+        let buffer_id = buffer.read(cx).remote_id();
+
+        let current_language_servers = self.as_local().map(|local| {
+            local
+                .buffers_opened_in_servers
+                .get(&buffer_id)
+                .cloned()
+                .unwrap_or_default()
+        });
+
+        if let Some(lsp_data) = self.current_lsp_data(buffer_id) {
+            if let Some(cached) = &lsp_data.document_links {
+                if !version_queried_for.changed_since(&lsp_data.buffer_version) {
+                    let has_different_servers =
+                        current_language_servers.is_some_and(|current_language_servers| {
+                            current_language_servers != cached.links.keys().copied().collect()
+                        });
+                    if !has_different_servers {
+                        let snapshot = buffer.read(cx).snapshot();
+                        return Task::ready(
+                            cached
+                                .links
+                                .values()
+                                .flatten()
+                                .cloned()
+                                .sorted_by(|a, b| a.range.start.cmp(&b.range.start, &snapshot))
+                                .collect(),
+                        );
+                    }
+                }
+            }
+        }
+
+        let links_lsp_data = self
+            .latest_lsp_data(buffer, cx)
+            .document_links
+            .get_or_insert_default();
+        if let Some((updating_for, running_update)) = &links_lsp_data.links_update {
+            if !version_queried_for.changed_since(updating_for) {
+                let running = running_update.clone();
+                return cx.background_spawn(async move { running.await.unwrap_or_default() });
+            }
+        }
+
+        let buffer = buffer.clone();
+        let query_version = version_queried_for.clone();
+        let new_task = cx
+            .spawn(async move |lsp_store, cx| {
+                cx.background_executor()
+                    .timer(Duration::from_millis(30))
+                    .await;
+
+                let fetched = lsp_store
+                    .update(cx, |lsp_store, cx| {
+                        lsp_store.fetch_document_links_for_buffer(&buffer, cx)
+                    })
+                    .map_err(Arc::new)?
+                    .await
+                    .context("fetching document links")
+                    .map_err(Arc::new);
+
+                let fetched = match fetched {
+                    Ok(fetched) => fetched,
+                    Err(e) => {
+                        lsp_store
+                            .update(cx, |lsp_store, _| {
+                                if let Some(lsp_data) = lsp_store.lsp_data.get_mut(&buffer_id) {
+                                    if let Some(document_links) = &mut lsp_data.document_links {
+                                        document_links.links_update = None;
+                                    }
+                                }
+                            })
+                            .ok();
+                        return Err(e);
+                    }
+                };
+
+                lsp_store
+                    .update(cx, |lsp_store, cx| {
+                        let lsp_data = lsp_store.latest_lsp_data(&buffer, cx);
+                        let document_links = lsp_data.document_links.get_or_insert_default();
+
+                        if let Some(fetched_links) = fetched {
+                            if lsp_data.buffer_version == query_version {
+                                document_links.links.extend(fetched_links);
+                            } else if !lsp_data.buffer_version.changed_since(&query_version) {
+                                lsp_data.buffer_version = query_version;
+                                document_links.links = fetched_links;
+                            }
+                        }
+                        document_links.links_update = None;
+                        let snapshot = buffer.read(cx).snapshot();
+                        document_links
+                            .links
+                            .values()
+                            .flatten()
+                            .cloned()
+                            .sorted_by(|a, b| a.range.start.cmp(&b.range.start, &snapshot))
+                            .collect()
+                    })
+                    .map_err(Arc::new)
+            })
+            .shared();
+
+        links_lsp_data.links_update = Some((version_queried_for, new_task.clone()));
+
+        cx.background_spawn(async move { new_task.await.unwrap_or_default() })
+    }
+
+    fn fetch_document_links_for_buffer(
+        &mut self,
+        buffer: &Entity<Buffer>,
+        cx: &mut Context<Self>,
+    ) -> Task<Result<Option<HashMap<LanguageServerId, Vec<DocumentLink>>>>> {
+        if let Some((client, project_id)) = self.upstream_client() {
+            let request = GetDocumentLinks;
+            if !self.is_capable_for_proto_request(buffer, &request, cx) {
+                return Task::ready(Ok(None));
+            }
+
+            let request_timeout = ProjectSettings::get_global(cx)
+                .global_lsp_settings
+                .get_request_timeout();
+            let request_task = client.request_lsp(
+                project_id,
+                None,
+                request_timeout,
+                cx.background_executor().clone(),
+                request.to_proto(project_id, buffer.read(cx)),
+            );
+            let buffer = buffer.clone();
+            cx.spawn(async move |weak_lsp_store, cx| {
+                let Some(lsp_store) = weak_lsp_store.upgrade() else {
+                    return Ok(None);
+                };
+                let Some(responses) = request_task.await? else {
+                    return Ok(None);
+                };
+
+                let document_links = join_all(responses.payload.into_iter().map(|response| {
+                    let lsp_store = lsp_store.clone();
+                    let buffer = buffer.clone();
+                    let cx = cx.clone();
+                    async move {
+                        (
+                            LanguageServerId::from_proto(response.server_id),
+                            GetDocumentLinks
+                                .response_from_proto(response.response, lsp_store, buffer, cx)
+                                .await,
+                        )
+                    }
+                }))
+                .await;
+
+                let mut has_errors = false;
+                let result = document_links
+                    .into_iter()
+                    .filter_map(|(server_id, links)| match links {
+                        Ok(links) => Some((server_id, links)),
+                        Err(e) => {
+                            has_errors = true;
+                            log::error!("Failed to fetch document links: {e:#}");
+                            None
+                        }
+                    })
+                    .collect::<HashMap<_, _>>();
+                anyhow::ensure!(
+                    !has_errors || !result.is_empty(),
+                    "Failed to fetch document links"
+                );
+                Ok(Some(result))
+            })
+        } else {
+            let links_task =
+                self.request_multiple_lsp_locally(buffer, None::<usize>, GetDocumentLinks, cx);
+            cx.background_spawn(async move { Ok(Some(links_task.await.into_iter().collect())) })
+        }
+    }
+
+    /// Resolves the target of a document link that the server sent without one.
+    pub fn resolve_document_link(
+        &self,
+        buffer: &Entity<Buffer>,
+        mut link: DocumentLink,
+        cx: &mut Context<Self>,
+    ) -> Task<Result<DocumentLink>> {
+        if link.resolved {
+            return Task::ready(Ok(link));
+        }
+        let buffer_id = buffer.read(cx).remote_id();
+        let resolve_task = if let Some((upstream_client, project_id)) = self.upstream_client() {
+            if !self.check_if_capable_for_proto_request(
+                buffer,
+                GetDocumentLinks::can_resolve_links,
+                cx,
+            ) {
+                link.resolved = true;
+                return Task::ready(Ok(link));
+            }
+            let request = proto::ResolveDocumentLink {
+                project_id,
+                buffer_id: buffer_id.to_proto(),
+                link: Some(Self::serialize_document_link(&link)),
+            };
+            cx.background_spawn(async move {
+                let response = upstream_client
+                    .request(request)
+                    .await
+                    .context("document link proto resolve request")?;
+                match response.link {
+                    Some(resolved_link) => Self::deserialize_document_link(resolved_link),
+                    None => Ok(link),
+                }
+            })
+        } else {
+            let Some(lang_server) = buffer.update(cx, |buffer, cx| {
+                self.language_server_for_local_buffer(buffer, link.server_id, cx)
+                    .map(|(_, server)| server.clone())
+            }) else {
+                return Task::ready(Ok(link));
+            };
+            if !GetDocumentLinks::can_resolve_links(&lang_server.capabilities()) {
+                link.resolved = true;
+                return Task::ready(Ok(link));
+            }
+            let request_timeout = ProjectSettings::get_global(cx)
+                .global_lsp_settings
+                .get_request_timeout();
+            cx.background_spawn(async move {
+                link.lsp_link = lang_server
+                    .request::<lsp::request::DocumentLinkResolve>(
+                        link.lsp_link.clone(),
+                        request_timeout,
+                    )
+                    .await
+                    .into_response()
+                    .context("document link resolve LSP request")?;
+                link.resolved = true;
+                Ok(link)
+            })
+        };
+
+        cx.spawn(async move |lsp_store, cx| {
+            let resolved_link = resolve_task.await?;
+            lsp_store
+                .update(cx, |lsp_store, _| {
+                    lsp_store.cache_resolved_document_link(buffer_id, &resolved_link)
+                })
+                .ok();
+            Ok(resolved_link)
+        })
+    }
+
+    fn cache_resolved_document_link(&mut self, buffer_id: BufferId, resolved_link: &DocumentLink) {
+        let Some(cached_link) = self
+            .lsp_data
+            .get_mut(&buffer_id)
+            .and_then(|lsp_data| lsp_data.document_links.as_mut())
+            .and_then(|document_links| document_links.links.get_mut(&resolved_link.server_id))
+            .and_then(|links| {
+                links.iter_mut().find(|link| {
+                    link.range == resolved_link.range
+                        && link.lsp_link.data == resolved_link.lsp_link.data
+                })
+            })
+        else {
+            return;
+        };
+        *cached_link = resolved_link.clone();
+    }
+
+    pub(crate) fn serialize_document_link(link: &DocumentLink) -> proto::DocumentLink {
+        proto::DocumentLink {
+            range: Some(serialize_anchor_range(link.range.clone())),
+            server_id: link.server_id.to_proto(),
+            lsp_link: serde_json::to_vec(&link.lsp_link).unwrap(),
+            resolved: link.resolved,
+        }
+    }
+
+    pub(crate) fn deserialize_document_link(link: proto::DocumentLink) -> Result<DocumentLink> {
+        Ok(DocumentLink {
+            range: deserialize_anchor_range(link.range.context("invalid document link range")?)?,
+            server_id: LanguageServerId::from_proto(link.server_id),
+            lsp_link: serde_json::from_slice(&link.lsp_link)?,
+            resolved: link.resolved,
+        })
+    }
+
+    pub(super) async fn handle_resolve_document_link(
+        lsp_store: Entity<Self>,
+        envelope: TypedEnvelope<proto::ResolveDocumentLink>,
+        mut cx: AsyncApp,
+    ) -> Result<proto::ResolveDocumentLinkResponse> {
+        let link = Self::deserialize_document_link(
+            envelope
+                .payload
+                .link
+                .context("missing document link in the resolve request")?,
+        )?;
+        let buffer = lsp_store.update(&mut cx, |lsp_store, cx| {
+            let buffer_id = BufferId::new(envelope.payload.buffer_id)?;
+            lsp_store.buffer_store.read(cx).get_existing(buffer_id)
+        })?;
+        let link = lsp_store
+            .update(&mut cx, |lsp_store, cx| {
+                lsp_store.resolve_document_link(&buffer, link, cx)
+            })
+            .await?;
+        Ok(proto::ResolveDocumentLinkResponse {
+            link: Some(Self::serialize_document_link(&link)),
+        })
+    }
+}
@@ -0,0 +1,161 @@
+use std::{
+    borrow::Cow,
+    path::{Path, PathBuf},
+};
+
+use anyhow::{Context as _, Result};
+use collections::{HashMap, HashSet};
+use gpui::Context;
+use language::{Diagnostic, DiagnosticEntry, DiagnosticSourceKind, PointUtf16, Unclipped};
+use lsp::{DiagnosticSeverity, LanguageServerId, NumberOrString};
+use task::{ProblemSeverity, TaskId, TaskProblem};
+use util::post_inc;
+
+use crate::{
+    LspStore,
+    lsp_store::{DocumentDiagnostics, DocumentDiagnosticsUpdate},
+};
+
+/// Diagnostics, reported by the problem matchers of a single task.
+/// Dropped once the task has no diagnostics left.
+#[derive(Debug)]
+pub(super) struct TaskDiagnostics {
+    /// A language server id, allocated from the language registry without starting a server,
+    /// used to store the task's diagnostics separately from others.
+    server_id: LanguageServerId,
+    /// Paths with diagnostics from the last run of the task, to clear on the next one.
+    abs_paths: HashSet<PathBuf>,
+}
+
+impl LspStore {
+    /// Replaces the diagnostics from the previous run of the task with the problems matched in its latest output.
+    /// Relative paths of the problems are resolved against `cwd`, problems outside of the project are ignored.
+    pub fn update_task_diagnostics(
+        &mut self,
+        task_id: &TaskId,
+        cwd: Option<&Path>,
+        problems: Vec<TaskProblem>,
+        cx: &mut Context<Self>,
+    ) -> Result<()> {
+        let local = self
+            .as_local_mut()
+            .context("cannot update task diagnostics on a remote LspStore")?;
+        if problems.is_empty() && !local.task_diagnostics.contains_key(task_id) {
+            return Ok(());
+        }
+        let languages = local.languages.clone();
+        let task_diagnostics = local
+            .task_diagnostics
+            .entry(task_id.clone())
+            .or_insert_with(|| TaskDiagnostics {
+                server_id: languages.next_language_server_id(),
+                abs_paths: HashSet::default(),
+            });
+        let server_id = task_diagnostics.server_id;
+        let previous_paths = std::mem::take(&mut task_diagnostics.abs_paths);
+
+        let mut diagnostics_by_path =
+            HashMap::<PathBuf, Vec<DiagnosticEntry<Unclipped<PointUtf16>>>>::default();
+        for problem in problems {
+            let path = Path::new(&problem.path);
+            let abs_path = if path.is_absolute() {
+                path.to_path_buf()
+            } else if let Some(cwd) = cwd {
+                cwd.join(path)
+            } else {
+                continue;
+            };
+            let group_id = post_inc(&mut local.next_diagnostic_group_id);
+            let start = PointUtf16::new(
+                problem.line.saturating_sub(1),
+                problem.column.saturating_sub(1),
+            );
+            let end = problem
+                .end
+                .map(|(end_line, end_column)| {
+                    PointUtf16::new(end_line.saturating_sub(1), end_column.saturating_sub(1))
+                })
+                .filter(|end| *end > start)
+                .unwrap_or(start);
+            diagnostics_by_path
+                .entry(abs_path)
+                .or_default()
+                .push(DiagnosticEntry {
+                    range: Unclipped(start)..Unclipped(end),
+                    diagnostic: Diagnostic {
+                        source: Some(problem.source),
+                        source_kind: DiagnosticSourceKind::Other,
+                        code: problem.code.map(NumberOrString::String),
+                        severity: match problem.severity {
+                            ProblemSeverity::Error => DiagnosticSeverity::ERROR,
+                            ProblemSeverity::Warning => DiagnosticSeverity::WARNING,
+                            ProblemSeverity::Info => DiagnosticSeverity::INFORMATION,
+                            ProblemSeverity::Hint => DiagnosticSeverity::HINT,
+                        },
+                        message: problem.message,
+                        group_id,
+                        is_primary: true,
+                        is_disk_based: true,
+                        ..Diagnostic::default()
+                    },
+                });
+        }
+
+        let worktree_store = self.worktree_store.read(cx);
+        diagnostics_by_path
+            .retain(|abs_path, _| worktree_store.find_worktree(abs_path, cx).is_some());
+        let cleared_paths = previous_paths
+            .into_iter()
+            .filter(|abs_path| !diagnostics_by_path.contains_key(abs_path))
+            .collect::<Vec<_>>();
+        if let Some(local) = self.as_local_mut() {
+            if diagnostics_by_path.is_empty() {
+                // The server id isn't referenced anymore once the cleared paths are merged below.
+                local.task_diagnostics.remove(task_id);
+            } else if let Some(task_diagnostics) = local.task_diagnostics.get_mut(task_id) {
+                task_diagnostics.abs_paths = diagnostics_by_path.keys().cloned().collect();
+            }
+        }
+
+        let updates = diagnostics_by_path
+            .into_iter()
+            .chain(
+                cleared_paths
+                    .into_iter()
+                    .map(|abs_path| (abs_path, Vec::new())),
+            )
+            .map(
+                |(document_abs_path, diagnostics)| DocumentDiagnosticsUpdate {
+                    diagnostics: DocumentDiagnostics {
+                        diagnostics,
+                        document_abs_path,
+                        version: None,
+                    },
+                    result_id: None,
+                    registration_id: None,
+                    server_id,
+                    disk_based_sources: Cow::Borrowed(&[]),
+                },
+            )
+            .collect::<Vec<_>>();
+        if updates.is_empty() {
+            return Ok(());
+        }
+        self.merge_diagnostic_entries(updates, |_, _, _| false, cx)
+    }
+
+    /// Removes all diagnostics, reported by the problem matchers of the task given.
+    pub fn clear_task_diagnostics(
+        &mut self,
+        task_id: &TaskId,
+        cx: &mut Context<Self>,
+    ) -> Result<()> {
+        let has_diagnostics = self
+            .as_local()
+            .is_some_and(|local| local.task_diagnostics.contains_key(task_id));
+        if !has_diagnostics {
+            return Ok(());
+        }
+        self.update_task_diagnostics(task_id, None, Vec::new(), cx)
+    }
+}
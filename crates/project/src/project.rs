@@ -150,15 +150,16 @@ pub use language::Location;
 #[cfg(any(test, feature = "test-support"))]
 pub use prettier::FORMAT_SUFFIX as TEST_PRETTIER_FORMAT_SUFFIX;
 pub use task_inventory::{
-    BasicContextProvider, ContextProviderWithTasks, DebugScenarioContext, Inventory, TaskContexts,
-    TaskSourceKind,
+    BasicContextProvider, ContextProviderWithTasks, DebugScenarioContext, Inventory, TaskChain,
+    TaskContexts, TaskSourceKind,
 };
 
 pub use buffer_store::ProjectTransaction;
 pub use lsp_store::{
-    DiagnosticSummary, InvalidationStrategy, LanguageServerLogType, LanguageServerProgress,
-    LanguageServerPromptRequest, LanguageServerStatus, LanguageServerToQuery, LspStore,
-    LspStoreEvent, ProgressToken, SERVER_PROGRESS_THROTTLE_TIMEOUT,
+    DiagnosticSummary, DocumentLink, InvalidationStrategy, LanguageServerLogType,
+    LanguageServerProgress, LanguageServerPromptRequest, LanguageServerStatus,
+    LanguageServerToQuery, LspStore, LspStoreEvent, ProgressToken,
+    SERVER_PROGRESS_THROTTLE_TIMEOUT,
 };
 pub use toolchain_store::{ToolchainStore, Toolchains};
 const MAX_PROJECT_SEARCH_HISTORY_SIZE: usize = 500;
@@ -756,6 +757,31 @@ impl LspAction {
         }
     }
 
+    /// The absolute paths of the files the action's workspace edit changes, creates, renames or
+    /// deletes.
+    pub fn workspace_edit_paths(&self) -> Vec<PathBuf> {
+        let Some(edit) = self.edit() else {
+            return Vec::new();
+        };
+        let mut paths = Vec::new();
+        for operation in lsp_store::workspace_edit_operations(edit.clone()) {
+            let uris = match operation {
+                lsp::DocumentChangeOperation::Edit(edit) => vec![edit.text_document.uri],
+                lsp::DocumentChangeOperation::Op(lsp::ResourceOp::Create(op)) => vec![op.uri],
+                lsp::DocumentChangeOperation::Op(lsp::ResourceOp::Rename(op)) => {
+                    vec![op.old_uri, op.new_uri]
+                }
+                lsp::DocumentChangeOperation::Op(lsp::ResourceOp::Delete(op)) => vec![op.uri],
+            };
+            for path in uris.into_iter().filter_map(|uri| uri.to_file_path().ok()) {
+                if !paths.contains(&path) {
+                    paths.push(path);
+                }
+            }
+        }
+        paths
+    }
+
     fn command(&self) -> Option<&lsp::Command> {
         match self {
             Self::Action(action) => action.command.as_ref(),
@@ -845,6 +871,34 @@ pub struct DocumentSymbol {
     pub children: Vec<DocumentSymbol>,
 }
 
+/// A function in a call hierarchy, or a type in a type hierarchy.
+#[derive(Clone, Debug)]
+pub struct HierarchyItem<T> {
+    pub name: String,
+    pub kind: lsp::SymbolKind,
+    pub detail: Option<String>,
+    /// The whole definition of the item, including its body.
+    pub location: Location,
+    /// The part of the definition to reveal when navigating to the item, usually its name.
+    pub selection_range: Range<Anchor>,
+    pub server_id: LanguageServerId,
+    /// The item as the language server returned it, which is sent back to the server to
+    /// ask for the item's calls or types.
+    pub lsp_item: T,
+}
+
+pub type CallHierarchyItem = HierarchyItem<lsp::CallHierarchyItem>;
+pub type TypeHierarchyItem = HierarchyItem<lsp::TypeHierarchyItem>;
+
+/// The calls between a function and one of its callers or callees.
+#[derive(Clone, Debug)]
+pub struct CallHierarchyCall {
+    /// The caller, for incoming calls, or the callee, for outgoing calls.
+    pub item: CallHierarchyItem,
+    /// Where the calls are made, which is in the caller either way.
+    pub call_sites: Vec<Location>,
+}
+
 #[derive(Clone, Debug, PartialEq)]
 pub struct HoverBlock {
     pub text: String,
@@ -1266,6 +1320,9 @@ impl Project {
                     cx,
                 )
             });
+            dap_store.update(cx, |dap_store, _| {
+                dap_store.set_lsp_store(lsp_store.downgrade());
+            });
 
             let git_store = cx.new(|cx| {
                 GitStore::local(
@@ -4104,6 +4161,118 @@ impl Project {
         )
     }
 
+    pub fn prepare_call_hierarchy<T: ToPointUtf16>(
+        &mut self,
+        buffer: &Entity<Buffer>,
+        position: T,
+        cx: &mut Context<Self>,
+    ) -> Task<Result<Vec<CallHierarchyItem>>> {
+        let position = position.to_point_utf16(buffer.read(cx));
+        self.request_lsp(
+            buffer.clone(),
+            LanguageServerToQuery::FirstCapable,
+            PrepareCallHierarchy { position },
+            cx,
+        )
+    }
+
+    /// Returns the functions that call the item, which comes from [`Self::prepare_call_hierarchy`]
+    /// or from an earlier call to this method or [`Self::outgoing_calls`].
+    pub fn incoming_calls(
+        &mut self,
+        item: &CallHierarchyItem,
+        cx: &mut Context<Self>,
+    ) -> Task<Result<Vec<CallHierarchyCall>>> {
+        let lsp_item = match serde_json::to_vec(&item.lsp_item) {
+            Ok(lsp_item) => lsp_item,
+            Err(error) => return Task::ready(Err(error.into())),
+        };
+        self.request_lsp(
+            item.location.buffer.clone(),
+            LanguageServerToQuery::Other(item.server_id),
+            GetIncomingCalls {
+                lsp_item,
+                server_id: item.server_id,
+            },
+            cx,
+        )
+    }
+
+    /// Returns the functions the item calls.
+    pub fn outgoing_calls(
+        &mut self,
+        item: &CallHierarchyItem,
+        cx: &mut Context<Self>,
+    ) -> Task<Result<Vec<CallHierarchyCall>>> {
+        let lsp_item = match serde_json::to_vec(&item.lsp_item) {
+            Ok(lsp_item) => lsp_item,
+            Err(error) => return Task::ready(Err(error.into())),
+        };
+        self.request_lsp(
+            item.location.buffer.clone(),
+            LanguageServerToQuery::Other(item.server_id),
+            GetOutgoingCalls {
+                lsp_item,
+                server_id: item.server_id,
+            },
+            cx,
+        )
+    }
+
+    /// Returns the types at `position`, from each of the buffer's language servers that
+    /// supports type hierarchies.
+    pub fn prepare_type_hierarchy<T: ToPointUtf16>(
+        &mut self,
+        buffer: &Entity<Buffer>,
+        position: T,
+        cx: &mut Context<Self>,
+    ) -> Task<Result<Vec<TypeHierarchyItem>>> {
+        let position = position.to_point_utf16(buffer.read(cx));
+        self.lsp_store.update(cx, |lsp_store, cx| {
+            lsp_store.prepare_type_hierarchy(buffer, position, cx)
+        })
+    }
+
+    pub fn supertypes(
+        &mut self,
+        item: &TypeHierarchyItem,
+        cx: &mut Context<Self>,
+    ) -> Task<Result<Vec<TypeHierarchyItem>>> {
+        let lsp_item = match serde_json::to_vec(&item.lsp_item) {
+            Ok(lsp_item) => lsp_item,
+            Err(error) => return Task::ready(Err(error.into())),
+        };
+        self.request_lsp(
+            item.location.buffer.clone(),
+            LanguageServerToQuery::Other(item.server_id),
+            GetSupertypes {
+                lsp_item,
+                server_id: item.server_id,
+            },
+            cx,
+        )
+    }
+
+    pub fn subtypes(
+        &mut self,
+        item: &TypeHierarchyItem,
+        cx: &mut Context<Self>,
+    ) -> Task<Result<Vec<TypeHierarchyItem>>> {
+        let lsp_item = match serde_json::to_vec(&item.lsp_item) {
+            Ok(lsp_item) => lsp_item,
+            Err(error) => return Task::ready(Err(error.into())),
+        };
+        self.request_lsp(
+            item.location.buffer.clone(),
+            LanguageServerToQuery::Other(item.server_id),
+            GetSubtypes {
+                lsp_item,
+                server_id: item.server_id,
+            },
+            cx,
+        )
+    }
+
     pub fn symbols(&self, query: &str, cx: &mut Context<Self>) -> Task<Result<Vec<Symbol>>> {
         self.lsp_store
             .update(cx, |lsp_store, cx| lsp_store.symbols(query, cx))
@@ -4249,6 +4418,17 @@ impl Project {
         })
     }
 
+    pub fn resolve_code_lens(
+        &self,
+        buffer: &Entity<Buffer>,
+        lens: CodeAction,
+        cx: &mut Context<Self>,
+    ) -> Task<Result<CodeAction>> {
+        self.lsp_store.update(cx, |lsp_store, cx| {
+            lsp_store.resolve_code_lens(buffer, lens, cx)
+        })
+    }
+
     pub fn apply_code_action(
         &self,
         buffer_handle: Entity<Buffer>,
@@ -4309,6 +4489,25 @@ impl Project {
         )
     }
 
+    /// Returns the edit renaming the symbol at `position` as a code action, without applying it,
+    /// so that the files it changes can be inspected first. It's applied with
+    /// [`Project::apply_code_action`].
+    pub fn rename_edit<T: ToPointUtf16>(
+        &mut self,
+        buffer: Entity<Buffer>,
+        position: T,
+        new_name: String,
+        cx: &mut Context<Self>,
+    ) -> Task<Result<Option<CodeAction>>> {
+        let position = position.to_point_utf16(buffer.read(cx));
+        self.request_lsp(
+            buffer,
+            LanguageServerToQuery::FirstCapable,
+            GetRenameEdit { position, new_name },
+            cx,
+        )
+    }
+
     pub fn on_type_format<T: ToPointUtf16>(
         &mut self,
         buffer: Entity<Buffer>,
@@ -8,7 +8,7 @@ use std::{
     sync::Arc,
 };
 
-use anyhow::Result;
+use anyhow::{Context as _, Result, bail};
 use collections::{HashMap, HashSet, VecDeque};
 use dap::DapRegistry;
 use gpui::{App, AppContext as _, Context, Entity, SharedString, Task, WeakEntity};
@@ -21,8 +21,8 @@ use lsp::{LanguageServerId, LanguageServerName};
 use paths::{debug_task_file_name, task_file_name};
 use settings::{InvalidSettingsError, parse_json_with_comments};
 use task::{
-    DebugScenario, ResolvedTask, SharedTaskContext, TaskContext, TaskId, TaskTemplate,
-    TaskTemplates, TaskVariables, VariableName,
+    DebugScenario, DependsOrder, ResolvedTask, SharedTaskContext, TaskContext, TaskId,
+    TaskTemplate, TaskTemplates, TaskVariables, VariableName,
 };
 use text::{BufferId, Point, ToPoint};
 use util::{NumericPrefixWithSuffix, ResultExt as _, post_inc, rel_path::RelPath};
@@ -155,6 +155,84 @@ pub enum TaskSourceKind {
     },
 }
 
+/// A resolved task, together with all tasks it depends on, resolved recursively.
+/// Dependencies have to finish successfully before the task itself is spawned.
+///
+/// A task that several tasks of the chain depend on is resolved once, and shared between them,
+/// so it only runs once per chain.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TaskChain {
+    pub task_source_kind: TaskSourceKind,
+    pub task: ResolvedTask,
+    pub depends_order: DependsOrder,
+    pub dependencies: Vec<Arc<TaskChain>>,
+}
+
+impl TaskChain {
+    fn resolve(
+        task_source_kind: TaskSourceKind,
+        task: ResolvedTask,
+        available_templates: &[(TaskSourceKind, TaskTemplate)],
+        dependents: &mut Vec<String>,
+        resolved: &mut HashMap<String, Arc<TaskChain>>,
+    ) -> Result<Self> {
+        let template = task.original_task();
+        let depends_order = template.depends_order;
+        let dependency_labels = template.depends_on.clone();
+        dependents.push(template.label.clone());
+
+        let mut dependencies = Vec::with_capacity(dependency_labels.len());
+        for label in dependency_labels {
+            if dependents.contains(&label) {
+                bail!(
+                    "Task dependency cycle detected: {} -> {label}",
+                    dependents.join(" -> ")
+                );
+            }
+            if let Some(dependency) = resolved.get(&label) {
+                dependencies.push(dependency.clone());
+                continue;
+            }
+            let (kind, template) = available_templates
+                .iter()
+                .find(|(_, template)| template.label == label)
+                .with_context(|| {
+                    format!(
+                        "Task `{}` depends on an unknown task `{label}`",
+                        task.original_task().label
+                    )
+                })?;
+            let dependency = template
+                .resolve_task(&kind.to_id_base(), task.task_context())
+                .with_context(|| format!("Failed to resolve task dependency `{label}`"))?;
+            if let Some(input) = dependency.pending_inputs().first() {
+                bail!(
+                    "Task dependency `{label}` needs the input `{}`, not provided by the task `{}`",
+                    input.id,
+                    task.original_task().label
+                );
+            }
+            let dependency = Arc::new(Self::resolve(
+                kind.clone(),
+                dependency,
+                available_templates,
+                dependents,
+                resolved,
+            )?);
+            resolved.insert(label, dependency.clone());
+            dependencies.push(dependency);
+        }
+
+        dependents.pop();
+        Ok(Self {
+            task_source_kind,
+            task,
+            depends_order,
+            dependencies,
+        })
+    }
+}
+
 /// A collection of task contexts, derived from the current state of the workspace.
 /// Only contains worktrees that are visible and with their root being a directory.
 #[derive(Debug, Default)]
@@ -575,6 +653,44 @@ impl Inventory {
         })
     }
 
+    /// Resolves all dependencies of the task given (and their dependencies) into a [`TaskChain`].
+    /// Dependencies are looked up by their template labels among the templates from the same worktree first,
+    /// then among the global ones, and are resolved with the same [`TaskContext`] as the task itself.
+    /// Each label is resolved at most once per chain.
+    ///
+    /// Fails if any of the dependencies cannot be found or resolved, or if there is a dependency cycle.
+    pub fn resolve_task_chain(
+        &self,
+        task_source_kind: TaskSourceKind,
+        resolved_task: ResolvedTask,
+        cx: &App,
+    ) -> Task<Result<TaskChain>> {
+        if resolved_task.original_task().depends_on.is_empty() {
+            return Task::ready(Ok(TaskChain {
+                task_source_kind,
+                depends_order: resolved_task.original_task().depends_order,
+                task: resolved_task,
+                dependencies: Vec::new(),
+            }));
+        }
+
+        let worktree = match &task_source_kind {
+            TaskSourceKind::Worktree { id, .. } => Some(*id),
+            _ => None,
+        };
+        let available_templates = self.list_tasks(None, None, worktree, cx);
+        cx.background_spawn(async move {
+            let available_templates = available_templates.await;
+            TaskChain::resolve(
+                task_source_kind,
+                resolved_task,
+                &available_templates,
+                &mut Vec::new(),
+                &mut HashMap::default(),
+            )
+        })
+    }
+
     /// Returns the last scheduled task by task_id if provided.
     /// Otherwise, returns the last scheduled task.
     pub fn last_scheduled_task(
@@ -591,6 +707,29 @@ impl Inventory {
         }
     }
 
+    /// Returns the input values the task template given was last scheduled with, keyed by the input ids.
+    pub fn last_input_values(&self, template: &TaskTemplate) -> HashMap<String, String> {
+        let Some((_, last_task)) = self
+            .last_scheduled_tasks
+            .iter()
+            .rev()
+            .find(|(_, task)| task.original_task() == template)
+        else {
+            return HashMap::default();
+        };
+        template
+            .inputs
+            .iter()
+            .filter_map(|input| {
+                let value = last_task
+                    .task_context()
+                    .task_variables
+                    .get(&input.variable_name())?;
+                Some((input.id.clone(), value.to_owned()))
+            })
+            .collect()
+    }
+
     /// Registers task "usage" as being scheduled – to be used for LRU sorting when listing all tasks.
     pub fn task_scheduled(
         &mut self,
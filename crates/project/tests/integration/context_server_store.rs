@@ -1,7 +1,10 @@
 use anyhow::Result;
 use context_server::test::create_fake_transport;
+use context_server::types::{self, requests};
 use context_server::{ContextServer, ContextServerId};
-use gpui::{AppContext, AsyncApp, Entity, Subscription, Task, TestAppContext, UpdateGlobal as _};
+use gpui::{
+    App, AppContext, AsyncApp, Entity, Subscription, Task, TestAppContext, UpdateGlobal as _,
+};
 use http_client::{FakeHttpClient, Response};
 use project::context_server_store::registry::ContextServerDescriptorRegistry;
 use project::context_server_store::*;
@@ -982,6 +985,119 @@ async fn test_context_server_stdio_timeout(cx: &mut TestAppContext) {
     );
 }
 
+#[gpui::test]
+async fn test_context_server_requests(cx: &mut TestAppContext) {
+    const SERVER_ID: &str = "mcp-requests";
+
+    let (_fs, project) = setup_context_server_test(cx, json!({"code.rs": ""}), vec![]).await;
+
+    let registry = cx.new(|_| ContextServerDescriptorRegistry::new());
+    let store = cx.new(|cx| {
+        ContextServerStore::test(
+            registry.clone(),
+            project.read(cx).worktree_store(),
+            Some(project.downgrade()),
+            cx,
+        )
+    });
+
+    let transport = Arc::new(create_fake_transport(SERVER_ID, cx.executor()));
+    let server = Arc::new(ContextServer::new(
+        ContextServerId(SERVER_ID.into()),
+        transport.clone(),
+    ));
+    store.update(cx, |store, cx| store.test_start_server(server, cx));
+    cx.run_until_parked();
+
+    // Servers may only operate in the project's worktrees.
+    let roots = transport.request_client::<requests::ListRoots>(());
+    cx.run_until_parked();
+    assert_eq!(
+        roots.await.unwrap().roots,
+        vec![types::Root {
+            uri: url::Url::from_file_path(path!("/test")).unwrap(),
+            name: Some("test".into()),
+        }]
+    );
+
+    // Without a handler, sampling and elicitation requests are refused.
+    let elicitation =
+        transport.request_client::<requests::ElicitationCreate>(types::ElicitationCreateParams {
+            message: "Which environment?".into(),
+            requested_schema: types::ElicitationSchema {
+                properties: Default::default(),
+                required: Vec::new(),
+            },
+            meta: None,
+        });
+    cx.run_until_parked();
+    assert!(elicitation.await.is_err());
+
+    struct FakeRequestHandler;
+
+    impl ContextServerRequestHandler for FakeRequestHandler {
+        fn create_message(
+            &self,
+            server_id: ContextServerId,
+            params: types::CreateMessageRequest,
+            _cx: &mut App,
+        ) -> Task<Result<types::CreateMessageResult>> {
+            let prompt = match &params.messages[0].content {
+                types::MessageContent::Text { text, .. } => text.clone(),
+                _ => String::new(),
+            };
+            Task::ready(Ok(types::CreateMessageResult {
+                role: types::Role::Assistant,
+                content: types::MessageContent::Text {
+                    text: format!("{}: {prompt}", server_id.0),
+                    annotations: None,
+                },
+                model: "fake".into(),
+                stop_reason: Some("endTurn".into()),
+            }))
+        }
+
+        fn elicit(
+            &self,
+            _server_id: ContextServerId,
+            _params: types::ElicitationCreateParams,
+            _cx: &mut App,
+        ) -> Task<Result<types::ElicitationCreateResponse>> {
+            Task::ready(Ok(types::ElicitationCreateResponse {
+                action: types::ElicitationAction::Decline,
+                content: None,
+                meta: None,
+            }))
+        }
+    }
+
+    store.update(cx, |store, _| {
+        store.set_request_handler(Rc::new(FakeRequestHandler))
+    });
+    let message =
+        transport.request_client::<requests::CreateMessage>(types::CreateMessageRequest {
+            messages: vec![types::SamplingMessage {
+                role: types::Role::User,
+                content: types::MessageContent::Text {
+                    text: "Hello".into(),
+                    annotations: None,
+                },
+            }],
+            model_preferences: None,
+            system_prompt: None,
+            include_context: None,
+            temperature: None,
+            max_tokens: 100,
+            stop_sequences: None,
+            metadata: None,
+        });
+    cx.run_until_parked();
+    match message.await.unwrap().content {
+        types::MessageContent::Text { text, .. } => assert_eq!(text, "mcp-requests: Hello"),
+        content => panic!("unexpected content: {content:?}"),
+    }
+}
+
 fn assert_server_events(
     store: &Entity<ContextServerStore>,
     expected_events: Vec<(ContextServerId, ContextServerStatus)>,
@@ -3,7 +3,7 @@ mod go_locator {
     use dap::{DapLocator, adapters::DebugAdapterName};
     use gpui::TestAppContext;
     use project::debugger::locators::go::{DelveLaunchRequest, GoLocator};
-    use task::{HideStrategy, RevealStrategy, RevealTarget, Shell, TaskTemplate};
+    use task::{DependsOrder, HideStrategy, RevealStrategy, RevealTarget, Shell, TaskTemplate};
     #[gpui::test]
     async fn test_create_scenario_for_go_build(_: &mut TestAppContext) {
         let locator = GoLocator;
@@ -22,6 +22,10 @@ mod go_locator {
             tags: vec![],
             show_summary: true,
             show_command: true,
+            depends_on: vec![],
+            depends_order: DependsOrder::Sequence,
+            problem_matchers: vec![],
+            inputs: vec![],
         };
 
         let scenario = locator
@@ -49,6 +53,10 @@ mod go_locator {
             tags: vec![],
             show_summary: true,
             show_command: true,
+            depends_on: vec![],
+            depends_order: DependsOrder::Sequence,
+            problem_matchers: vec![],
+            inputs: vec![],
         };
 
         let scenario = locator
@@ -187,6 +195,10 @@ mod go_locator {
             tags: vec![],
             show_summary: true,
             show_command: true,
+            depends_on: vec![],
+            depends_order: DependsOrder::Sequence,
+            problem_matchers: vec![],
+            inputs: vec![],
         };
 
         let scenario = locator
@@ -221,6 +233,10 @@ mod python_locator {
             shell: task::Shell::System,
             show_summary: false,
             show_command: false,
+            depends_on: vec![],
+            depends_order: task::DependsOrder::Sequence,
+            problem_matchers: vec![],
+            inputs: vec![],
         };
 
         let expected_scenario = DebugScenario {
@@ -247,6 +263,209 @@ mod python_locator {
     }
 }
 
+mod java_locator {
+    use dap::{DapLocator, adapters::DebugAdapterName};
+    use serde_json::json;
+
+    use project::debugger::locators::java::*;
+    use task::TaskTemplate;
+
+    #[gpui::test]
+    async fn test_java_locator() {
+        let adapter = DebugAdapterName("Java".into());
+        let build_task = TaskTemplate {
+            label: "run Main".into(),
+            command: "java".into(),
+            args: vec![
+                "-Xmx1G".into(),
+                "--add-opens".into(),
+                "java.base/java.lang=ALL-UNNAMED".into(),
+                "-cp".into(),
+                "target/classes".into(),
+                "com.example.Main".into(),
+                "--verbose".into(),
+            ],
+            cwd: Some("$ZED_WORKTREE_ROOT".into()),
+            ..Default::default()
+        };
+
+        let scenario = JavaLocator
+            .create_scenario(&build_task, "run Main", &adapter)
+            .await
+            .expect("Failed to create a scenario");
+        assert_eq!(
+            scenario.config,
+            json!({
+                "request": "launch",
+                "mainClass": "com.example.Main",
+                "args": ["--verbose"],
+                "vmArgs": ["-Xmx1G", "--add-opens", "java.base/java.lang=ALL-UNNAMED"],
+                "classPaths": ["target/classes"],
+                "cwd": "$ZED_WORKTREE_ROOT",
+                "env": {},
+            })
+        );
+
+        let jar_task = TaskTemplate {
+            command: "java".into(),
+            args: vec!["-jar".into(), "app.jar".into()],
+            ..Default::default()
+        };
+        assert!(
+            JavaLocator
+                .create_scenario(&jar_task, "run jar", &adapter)
+                .await
+                .is_none()
+        );
+    }
+}
+
+mod dotnet_locator {
+    use dap::{DapLocator, adapters::DebugAdapterName};
+    use serde_json::json;
+
+    use project::debugger::locators::dotnet::*;
+    use task::{BuildTaskDefinition, TaskTemplate};
+
+    #[gpui::test]
+    async fn test_dotnet_locator() {
+        let adapter = DebugAdapterName("netcoredbg".into());
+        let build_task = TaskTemplate {
+            label: "dotnet run".into(),
+            command: "dotnet".into(),
+            args: vec![
+                "run".into(),
+                "--project".into(),
+                "src/App".into(),
+                "--launch-profile".into(),
+                "Development".into(),
+                "-c".into(),
+                "Debug".into(),
+                "--".into(),
+                "--port".into(),
+                "8080".into(),
+            ],
+            ..Default::default()
+        };
+
+        let scenario = DotnetLocator
+            .create_scenario(&build_task, "dotnet run", &adapter)
+            .await
+            .expect("Failed to create a scenario");
+        assert_eq!(scenario.config, json!({ "args": ["--port", "8080"] }));
+        let Some(BuildTaskDefinition::Template { task_template, .. }) = scenario.build else {
+            panic!("Expected a build task template");
+        };
+        assert_eq!(task_template.args, ["build", "src/App", "-c", "Debug"]);
+
+        let test_task = TaskTemplate {
+            command: "dotnet".into(),
+            args: vec!["test".into()],
+            ..Default::default()
+        };
+        assert!(
+            DotnetLocator
+                .create_scenario(&test_task, "dotnet test", &adapter)
+                .await
+                .is_none()
+        );
+    }
+}
+
+mod ruby_locator {
+    use dap::{DapLocator, adapters::DebugAdapterName};
+    use serde_json::json;
+
+    use project::debugger::locators::ruby::*;
+    use task::TaskTemplate;
+
+    #[gpui::test]
+    async fn test_ruby_locator() {
+        let adapter = DebugAdapterName("rdbg".into());
+        let script_task = TaskTemplate {
+            command: "ruby".into(),
+            args: vec!["$ZED_FILE".into(), "--verbose".into()],
+            cwd: Some("$ZED_WORKTREE_ROOT".into()),
+            ..Default::default()
+        };
+        let scenario = RubyLocator
+            .create_scenario(&script_task, "run script", &adapter)
+            .await
+            .expect("Failed to create a scenario");
+        assert_eq!(
+            scenario.config,
+            json!({
+                "request": "launch",
+                "script": "$ZED_FILE",
+                "args": ["--verbose"],
+                "cwd": "$ZED_WORKTREE_ROOT",
+                "env": {},
+            })
+        );
+
+        let rspec_task = TaskTemplate {
+            command: "bundle".into(),
+            args: vec!["exec".into(), "rspec".into(), "spec/app_spec.rb".into()],
+            ..Default::default()
+        };
+        let scenario = RubyLocator
+            .create_scenario(&rspec_task, "run specs", &adapter)
+            .await
+            .expect("Failed to create a scenario");
+        assert_eq!(scenario.config["command"], json!("bundle"));
+        assert_eq!(
+            scenario.config["args"],
+            json!(["exec", "rspec", "spec/app_spec.rb"])
+        );
+    }
+}
+
+mod php_locator {
+    use dap::{DapLocator, adapters::DebugAdapterName};
+    use serde_json::json;
+
+    use project::debugger::locators::php::*;
+    use task::TaskTemplate;
+
+    #[gpui::test]
+    async fn test_php_locator() {
+        let adapter = DebugAdapterName("Xdebug".into());
+        let script_task = TaskTemplate {
+            command: "php".into(),
+            args: vec!["-dmemory_limit=1G".into(), "$ZED_FILE".into(), "42".into()],
+            ..Default::default()
+        };
+        let scenario = PhpLocator
+            .create_scenario(&script_task, "run script", &adapter)
+            .await
+            .expect("Failed to create a scenario");
+        assert_eq!(
+            scenario.config,
+            json!({
+                "request": "launch",
+                "program": "$ZED_FILE",
+                "args": ["42"],
+                "runtimeExecutable": "php",
+                "runtimeArgs": ["-dmemory_limit=1G"],
+                "cwd": null,
+                "env": {},
+            })
+        );
+
+        let phpunit_task = TaskTemplate {
+            command: "vendor/bin/phpunit".into(),
+            args: vec!["--filter".into(), "testAddition".into()],
+            ..Default::default()
+        };
+        let scenario = PhpLocator
+            .create_scenario(&phpunit_task, "run tests", &adapter)
+            .await
+            .expect("Failed to create a scenario");
+        assert_eq!(scenario.config["program"], json!("vendor/bin/phpunit"));
+        assert_eq!(scenario.config["runtimeExecutable"], json!("php"));
+    }
+}
+
 mod memory {
     use project::debugger::{
         MemoryCell,
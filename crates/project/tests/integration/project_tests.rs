@@ -2989,6 +2989,168 @@ async fn test_restarted_server_reporting_invalid_buffer_version(cx: &mut gpui::T
     assert_eq!(notification.version, 0);
 }
 
+#[gpui::test]
+async fn test_pulling_workspace_diagnostics(cx: &mut gpui::TestAppContext) {
+    init_test(cx);
+
+    let fs = FakeFs::new(cx.executor());
+    fs.insert_tree(
+        path!("/dir"),
+        json!({ "a.rs": "fn a() {}", "b.rs": "fn b() { c() }" }),
+    )
+    .await;
+
+    let project = Project::test(fs, [path!("/dir").as_ref()], cx).await;
+    let worktree_id = project.update(cx, |project, cx| {
+        project.worktrees(cx).next().unwrap().read(cx).id()
+    });
+    let language_registry = project.read_with(cx, |project, _| project.languages().clone());
+    language_registry.add(rust_lang());
+
+    let workspace_requests = Arc::new(Mutex::new(Vec::<Vec<String>>::new()));
+    let mut fake_servers =
+        language_registry.register_fake_lsp(
+            "Rust",
+            FakeLspAdapter {
+                capabilities: lsp::ServerCapabilities {
+                    diagnostic_provider: Some(lsp::DiagnosticServerCapabilities::Options(
+                        lsp::DiagnosticOptions {
+                            identifier: None,
+                            inter_file_dependencies: true,
+                            workspace_diagnostics: true,
+                            work_done_progress_options: Default::default(),
+                        },
+                    )),
+                    ..Default::default()
+                },
+                initializer: Some(Box::new({
+                    let workspace_requests = workspace_requests.clone();
+                    move |fake_server| {
+                        fake_server
+                            .set_request_handler::<lsp::request::DocumentDiagnosticRequest, _, _>(
+                                |_, _| async move {
+                                    Ok(lsp::DocumentDiagnosticReportResult::Report(
+                                        lsp::DocumentDiagnosticReport::Full(
+                                            lsp::RelatedFullDocumentDiagnosticReport {
+                                                related_documents: None,
+                                                full_document_diagnostic_report:
+                                                    lsp::FullDocumentDiagnosticReport {
+                                                        result_id: None,
+                                                        items: Vec::new(),
+                                                    },
+                                            },
+                                        ),
+                                    ))
+                                },
+                            );
+                        let workspace_requests = workspace_requests.clone();
+                        fake_server
+                            .set_request_handler::<lsp::request::WorkspaceDiagnosticRequest, _, _>(
+                                move |params, _| {
+                                    let request_count = {
+                                        let mut workspace_requests = workspace_requests.lock();
+                                        workspace_requests.push(
+                                            params
+                                                .previous_result_ids
+                                                .into_iter()
+                                                .map(|id| id.value)
+                                                .collect(),
+                                        );
+                                        workspace_requests.len()
+                                    };
+                                    async move {
+                                        Ok(lsp::WorkspaceDiagnosticReportResult::Report(
+                                    lsp::WorkspaceDiagnosticReport {
+                                        items: vec![lsp::WorkspaceDocumentDiagnosticReport::Full(
+                                            lsp::WorkspaceFullDocumentDiagnosticReport {
+                                                uri: Uri::from_file_path(path!("/dir/b.rs"))
+                                                    .unwrap(),
+                                                version: None,
+                                                full_document_diagnostic_report:
+                                                    lsp::FullDocumentDiagnosticReport {
+                                                        result_id: Some(format!(
+                                                            "workspace-{request_count}"
+                                                        )),
+                                                        items: vec![lsp::Diagnostic {
+                                                            range: lsp::Range::new(
+                                                                lsp::Position::new(0, 9),
+                                                                lsp::Position::new(0, 10),
+                                                            ),
+                                                            severity: Some(
+                                                                lsp::DiagnosticSeverity::ERROR,
+                                                            ),
+                                                            message: "cannot find function `c`"
+                                                                .to_string(),
+                                                            ..Default::default()
+                                                        }],
+                                                    },
+                                            },
+                                        )],
+                                    },
+                                ))
+                                    }
+                                },
+                            );
+                    }
+                })),
+                ..Default::default()
+            },
+        );
+
+    let (buffer, _handle) = project
+        .update(cx, |project, cx| {
+            project.open_local_buffer_with_lsp(path!("/dir/a.rs"), cx)
+        })
+        .await
+        .unwrap();
+    let fake_server = fake_servers.next().await.unwrap();
+    let server_id = fake_server.server.server_id();
+    cx.executor().advance_clock(Duration::from_millis(100));
+    cx.executor().run_until_parked();
+
+    assert_eq!(
+        workspace_requests.lock().len(),
+        1,
+        "Starting the server should pull workspace diagnostics"
+    );
+    project.update(cx, |project, cx| {
+        assert_eq!(
+            project.diagnostic_summaries(false, cx).collect::<Vec<_>>(),
+            vec![(
+                ProjectPath {
+                    worktree_id,
+                    path: rel_path("b.rs").into(),
+                },
+                server_id,
+                DiagnosticSummary {
+                    error_count: 1,
+                    warning_count: 0,
+                }
+            )],
+            "Diagnostics of files that were never opened should be reported"
+        );
+    });
+
+    project
+        .update(cx, |project, cx| project.save_buffer(buffer.clone(), cx))
+        .await
+        .unwrap();
+    cx.executor().advance_clock(Duration::from_millis(100));
+    cx.executor().run_until_parked();
+
+    let workspace_requests = workspace_requests.lock();
+    assert_eq!(
+        workspace_requests.len(),
+        2,
+        "Saving a buffer should pull workspace diagnostics again"
+    );
+    assert!(
+        workspace_requests[1].contains(&"workspace-1".to_string()),
+        "Repeated pulls should send the previous result ids, got {:?}",
+        workspace_requests[1]
+    );
+}
+
 #[gpui::test]
 async fn test_cancel_language_server_work(cx: &mut gpui::TestAppContext) {
     init_test(cx);
@@ -4158,6 +4320,285 @@ async fn test_definition(cx: &mut gpui::TestAppContext) {
     }
 }
 
+#[gpui::test]
+async fn test_call_hierarchy(cx: &mut gpui::TestAppContext) {
+    init_test(cx);
+
+    let fs = FakeFs::new(cx.executor());
+    fs.insert_tree(
+        path!("/dir"),
+        json!({
+            "a.rs": "fn a() { b() }\nfn b() {}",
+        }),
+    )
+    .await;
+
+    let project = Project::test(fs, [path!("/dir").as_ref()], cx).await;
+
+    let language_registry = project.read_with(cx, |project, _| project.languages().clone());
+    language_registry.add(rust_lang());
+    let mut fake_servers = language_registry.register_fake_lsp(
+        "Rust",
+        FakeLspAdapter {
+            capabilities: lsp::ServerCapabilities {
+                call_hierarchy_provider: Some(lsp::CallHierarchyServerCapability::Simple(true)),
+                ..lsp::ServerCapabilities::default()
+            },
+            ..FakeLspAdapter::default()
+        },
+    );
+
+    let (buffer, _handle) = project
+        .update(cx, |project, cx| {
+            project.open_local_buffer_with_lsp(path!("/dir/a.rs"), cx)
+        })
+        .await
+        .unwrap();
+
+    let fake_server = fake_servers.next().await.unwrap();
+    cx.executor().run_until_parked();
+
+    fn lsp_item(name: &str, line: u32, end: u32) -> lsp::CallHierarchyItem {
+        lsp::CallHierarchyItem {
+            name: name.to_string(),
+            kind: lsp::SymbolKind::FUNCTION,
+            tags: None,
+            detail: Some(format!("fn {name}()")),
+            uri: lsp::Uri::from_file_path(path!("/dir/a.rs")).unwrap(),
+            range: lsp::Range::new(lsp::Position::new(line, 0), lsp::Position::new(line, end)),
+            selection_range: lsp::Range::new(
+                lsp::Position::new(line, 3),
+                lsp::Position::new(line, 4),
+            ),
+            data: None,
+        }
+    }
+
+    fake_server.set_request_handler::<lsp::request::CallHierarchyPrepare, _, _>(
+        |params, _| async move {
+            assert_eq!(
+                params.text_document_position_params.position,
+                lsp::Position::new(1, 3)
+            );
+            Ok(Some(vec![lsp_item("b", 1, 9)]))
+        },
+    );
+    fake_server.set_request_handler::<lsp::request::CallHierarchyIncomingCalls, _, _>(
+        |params, _| async move {
+            assert_eq!(params.item.name, "b");
+            Ok(Some(vec![lsp::CallHierarchyIncomingCall {
+                from: lsp_item("a", 0, 14),
+                from_ranges: vec![lsp::Range::new(
+                    lsp::Position::new(0, 9),
+                    lsp::Position::new(0, 10),
+                )],
+            }]))
+        },
+    );
+    fake_server.set_request_handler::<lsp::request::CallHierarchyOutgoingCalls, _, _>(
+        |params, _| async move {
+            assert_eq!(params.item.name, "a");
+            Ok(Some(vec![lsp::CallHierarchyOutgoingCall {
+                to: lsp_item("b", 1, 9),
+                from_ranges: vec![lsp::Range::new(
+                    lsp::Position::new(0, 9),
+                    lsp::Position::new(0, 10),
+                )],
+            }]))
+        },
+    );
+
+    let items = project
+        .update(cx, |project, cx| {
+            project.prepare_call_hierarchy(&buffer, 18, cx)
+        })
+        .await
+        .unwrap();
+    assert_eq!(items.len(), 1);
+    let b = items[0].clone();
+    assert_eq!(b.name, "b");
+    assert_eq!(b.detail.as_deref(), Some("fn b()"));
+    assert_eq!(b.location.buffer, buffer);
+    buffer.read_with(cx, |buffer, _| {
+        assert_eq!(b.location.range.to_offset(buffer), 15..24);
+        assert_eq!(b.selection_range.to_offset(buffer), 18..19);
+    });
+
+    let incoming = project
+        .update(cx, |project, cx| project.incoming_calls(&b, cx))
+        .await
+        .unwrap();
+    assert_eq!(incoming.len(), 1);
+    let a = incoming[0].item.clone();
+    assert_eq!(a.name, "a");
+    buffer.read_with(cx, |buffer, _| {
+        assert_eq!(a.selection_range.to_offset(buffer), 3..4);
+        assert_eq!(
+            incoming[0]
+                .call_sites
+                .iter()
+                .map(|call_site| call_site.range.to_offset(buffer))
+                .collect::<Vec<_>>(),
+            [9..10]
+        );
+    });
+
+    let outgoing = project
+        .update(cx, |project, cx| project.outgoing_calls(&a, cx))
+        .await
+        .unwrap();
+    assert_eq!(outgoing.len(), 1);
+    assert_eq!(outgoing[0].item.name, "b");
+    assert_eq!(outgoing[0].call_sites[0].buffer, buffer);
+    buffer.read_with(cx, |buffer, _| {
+        assert_eq!(outgoing[0].call_sites[0].range.to_offset(buffer), 9..10);
+    });
+}
+
+#[gpui::test]
+async fn test_type_hierarchy(cx: &mut gpui::TestAppContext) {
+    init_test(cx);
+
+    let fs = FakeFs::new(cx.executor());
+    fs.insert_tree(
+        path!("/dir"),
+        json!({
+            "a.rs": "trait A {}\nstruct B;\nimpl A for B {}",
+        }),
+    )
+    .await;
+
+    let project = Project::test(fs, [path!("/dir").as_ref()], cx).await;
+
+    let language_registry = project.read_with(cx, |project, _| project.languages().clone());
+    language_registry.add(rust_lang());
+    let mut fake_servers = language_registry.register_fake_lsp(
+        "Rust",
+        FakeLspAdapter {
+            name: "type-hierarchy-server",
+            ..FakeLspAdapter::default()
+        },
+    );
+    let mut other_fake_servers = language_registry.register_fake_lsp(
+        "Rust",
+        FakeLspAdapter {
+            name: "other-server",
+            ..FakeLspAdapter::default()
+        },
+    );
+
+    let (buffer, _handle) = project
+        .update(cx, |project, cx| {
+            project.open_local_buffer_with_lsp(path!("/dir/a.rs"), cx)
+        })
+        .await
+        .unwrap();
+
+    let fake_server = fake_servers.next().await.unwrap();
+    let other_fake_server = other_fake_servers.next().await.unwrap();
+    cx.executor().run_until_parked();
+
+    fn lsp_item(
+        name: &str,
+        kind: lsp::SymbolKind,
+        line: u32,
+        start: u32,
+    ) -> lsp::TypeHierarchyItem {
+        lsp::TypeHierarchyItem {
+            name: name.to_string(),
+            kind,
+            tags: None,
+            detail: None,
+            uri: lsp::Uri::from_file_path(path!("/dir/a.rs")).unwrap(),
+            range: lsp::Range::new(lsp::Position::new(line, 0), lsp::Position::new(line, 10)),
+            selection_range: lsp::Range::new(
+                lsp::Position::new(line, start),
+                lsp::Position::new(line, start + 1),
+            ),
+            data: None,
+        }
+    }
+
+    // Servers that don't support type hierarchies are skipped.
+    other_fake_server.set_request_handler::<lsp::request::TypeHierarchyPrepare, _, _>(
+        |_, _| async move {
+            Err(lsp::ResponseError {
+                code: lsp::ResponseError::METHOD_NOT_FOUND,
+                message: "Unhandled method textDocument/prepareTypeHierarchy".into(),
+            }
+            .into())
+        },
+    );
+    fake_server.set_request_handler::<lsp::request::TypeHierarchyPrepare, _, _>(
+        |params, _| async move {
+            assert_eq!(
+                params.text_document_position_params.position,
+                lsp::Position::new(1, 7)
+            );
+            Ok(Some(vec![lsp_item("B", lsp::SymbolKind::STRUCT, 1, 7)]))
+        },
+    );
+    fake_server.set_request_handler::<lsp::request::TypeHierarchySupertypes, _, _>(
+        |params, _| async move {
+            assert_eq!(params.item.name, "B");
+            Ok(Some(vec![lsp_item("A", lsp::SymbolKind::INTERFACE, 0, 6)]))
+        },
+    );
+    fake_server.set_request_handler::<lsp::request::TypeHierarchySubtypes, _, _>(
+        |params, _| async move {
+            assert_eq!(params.item.name, "A");
+            Ok(Some(vec![lsp_item("B", lsp::SymbolKind::STRUCT, 1, 7)]))
+        },
+    );
+
+    let items = project
+        .update(cx, |project, cx| {
+            project.prepare_type_hierarchy(&buffer, 18, cx)
+        })
+        .await
+        .unwrap();
+    assert_eq!(items.len(), 1);
+    let b = items[0].clone();
+    assert_eq!(b.name, "B");
+    assert_eq!(b.kind, lsp::SymbolKind::STRUCT);
+    assert_eq!(b.location.buffer, buffer);
+    buffer.read_with(cx, |buffer, _| {
+        assert_eq!(b.selection_range.to_offset(buffer), 18..19);
+    });
+
+    let supertypes = project
+        .update(cx, |project, cx| project.supertypes(&b, cx))
+        .await
+        .unwrap();
+    assert_eq!(supertypes.len(), 1);
+    let a = supertypes[0].clone();
+    assert_eq!(a.name, "A");
+    buffer.read_with(cx, |buffer, _| {
+        assert_eq!(a.selection_range.to_offset(buffer), 6..7);
+    });
+
+    let subtypes = project
+        .update(cx, |project, cx| project.subtypes(&a, cx))
+        .await
+        .unwrap();
+    assert_eq!(
+        subtypes.iter().map(|item| &item.name).collect::<Vec<_>>(),
+        ["B"]
+    );
+
+    // Other errors are reported.
+    other_fake_server.set_request_handler::<lsp::request::TypeHierarchyPrepare, _, _>(
+        |_, _| async move { Err(anyhow::anyhow!("the server crashed")) },
+    );
+    let error = project
+        .update(cx, |project, cx| {
+            project.prepare_type_hierarchy(&buffer, 18, cx)
+        })
+        .await
+        .unwrap_err();
+    assert!(error.to_string().contains("the server crashed"), "{error}");
+}
+
 #[gpui::test]
 async fn test_completions_with_text_edit(cx: &mut gpui::TestAppContext) {
     init_test(cx);
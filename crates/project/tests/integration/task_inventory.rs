@@ -11,6 +11,7 @@ use util::rel_path::rel_path;
 use project::task_store::{TaskSettingsLocation, TaskStore};
 
 use project::{WorktreeId, task_inventory::*};
+use task::{DependsOrder, TaskContext};
 use test_inventory::*;
 
 mod test_inventory {
@@ -560,6 +561,97 @@ async fn test_inventory_static_task_filters(cx: &mut TestAppContext) {
     );
 }
 
+#[gpui::test]
+async fn test_resolving_task_chains(cx: &mut TestAppContext) {
+    init_test(cx);
+    let inventory = cx.update(|cx| Inventory::new(cx));
+    inventory.update(cx, |inventory, _| {
+        inventory
+            .update_file_based_tasks(
+                TaskSettingsLocation::Global(tasks_file()),
+                Some(
+                    &json!([
+                        { "label": "build", "command": "cargo", "args": ["build"] },
+                        { "label": "lint", "command": "cargo", "args": ["clippy"], "depends_on": ["build"] },
+                        {
+                            "label": "check",
+                            "depends_on": ["lint", "build"],
+                            "depends_order": "parallel"
+                        },
+                        { "label": "first", "command": "echo", "depends_on": ["second"] },
+                        { "label": "second", "command": "echo", "depends_on": ["first"] },
+                        { "label": "broken", "command": "echo", "depends_on": ["missing"] },
+                    ])
+                    .to_string(),
+                ),
+            )
+            .unwrap();
+    });
+
+    let resolve_chain = |label: &'static str, cx: &mut TestAppContext| {
+        let tasks = inventory.update(cx, |inventory, cx| {
+            inventory.list_tasks(None, None, None, cx)
+        });
+        let inventory = inventory.clone();
+        cx.spawn(|cx| async move {
+            let (kind, template) = tasks
+                .await
+                .into_iter()
+                .find(|(_, template)| template.label == label)
+                .unwrap();
+            let resolved_task = template
+                .resolve_task(&kind.to_id_base(), &TaskContext::default())
+                .unwrap();
+            inventory
+                .read_with(&cx, |inventory, cx| {
+                    inventory.resolve_task_chain(kind, resolved_task, cx)
+                })
+                .await
+        })
+    };
+
+    /// Labels of the tasks in the chain, in the order they finish when run in sequence.
+    fn labels(chain: &TaskChain) -> Vec<String> {
+        let mut unique_labels = Vec::new();
+        for label in chain
+            .dependencies
+            .iter()
+            .flat_map(|dependency| labels(dependency))
+            .chain(Some(chain.task.resolved_label.clone()))
+        {
+            if !unique_labels.contains(&label) {
+                unique_labels.push(label);
+            }
+        }
+        unique_labels
+    }
+
+    let chain = resolve_chain("check", cx).await.unwrap();
+    assert!(chain.task.is_dependencies_only());
+    assert_eq!(chain.depends_order, DependsOrder::Parallel);
+    assert_eq!(chain.dependencies[0].depends_order, DependsOrder::Sequence);
+    assert_eq!(labels(&chain), vec!["build", "lint", "check"]);
+    // `build` is reached both directly and through `lint`, but is resolved once and shared.
+    assert!(Arc::ptr_eq(
+        &chain.dependencies[0].dependencies[0],
+        &chain.dependencies[1]
+    ));
+
+    let chain = resolve_chain("build", cx).await.unwrap();
+    assert!(chain.dependencies.is_empty());
+
+    let error = resolve_chain("first", cx).await.unwrap_err();
+    assert!(
+        error.to_string().contains("cycle"),
+        "Unexpected error: {error}"
+    );
+    let error = resolve_chain("broken", cx).await.unwrap_err();
+    assert!(
+        error.to_string().contains("unknown task `missing`"),
+        "Unexpected error: {error}"
+    );
+}
+
 fn init_test(_cx: &mut TestAppContext) {
     zlog::init_test();
     TaskStore::init(None);
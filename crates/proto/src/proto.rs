@@ -117,12 +117,26 @@ messages!(
     (GetProjectSymbolsResponse, Background),
     (GetReferences, Background),
     (GetReferencesResponse, Background),
+    (GetRenameEdit, Background),
+    (GetRenameEditResponse, Background),
     (GetSignatureHelp, Background),
     (GetSignatureHelpResponse, Background),
     (GetTypeDefinition, Background),
     (GetTypeDefinitionResponse, Background),
     (GetImplementation, Background),
     (GetImplementationResponse, Background),
+    (PrepareCallHierarchy, Background),
+    (PrepareCallHierarchyResponse, Background),
+    (GetIncomingCalls, Background),
+    (GetIncomingCallsResponse, Background),
+    (GetOutgoingCalls, Background),
+    (GetOutgoingCallsResponse, Background),
+    (PrepareTypeHierarchy, Background),
+    (PrepareTypeHierarchyResponse, Background),
+    (GetSupertypes, Background),
+    (GetSupertypesResponse, Background),
+    (GetSubtypes, Background),
+    (GetSubtypesResponse, Background),
     (OpenUnstagedDiff, Foreground),
     (OpenUnstagedDiffResponse, Foreground),
     (OpenUncommittedDiff, Foreground),
@@ -231,7 +245,13 @@ messages!(
     (GetColorPresentationResponse, Background),
     (GetFoldingRanges, Background),
     (GetFoldingRangesResponse, Background),
+    (GetDocumentLinks, Background),
+    (GetDocumentLinksResponse, Background),
+    (ResolveDocumentLink, Background),
+    (ResolveDocumentLinkResponse, Background),
     (RefreshCodeLens, Background),
+    (ResolveCodeLens, Background),
+    (ResolveCodeLensResponse, Background),
     (GetCodeLens, Background),
     (GetCodeLensResponse, Background),
     (RespondToChannelInvite, Foreground),
@@ -298,6 +318,27 @@ messages!(
     (GitReset, Background),
     (GitDeleteBranch, Background),
     (GitCheckoutFiles, Background),
+    (GitRebaseTodo, Background),
+    (GitRebaseTodoResponse, Background),
+    (GitIsAncestor, Background),
+    (GitIsAncestorResponse, Background),
+    (GitRebase, Background),
+    (GitCherryPick, Background),
+    (GitRevert, Background),
+    (GitContinueOperation, Background),
+    (GitAbortOperation, Background),
+    (GitGetTags, Background),
+    (GitTagsResponse, Background),
+    (GitCreateTag, Background),
+    (GitDeleteTag, Background),
+    (GitBisectStart, Background),
+    (GitBisectMark, Background),
+    (GitBisectMarkResponse, Background),
+    (GitBisectReset, Background),
+    (GitUpdateSubmodules, Background),
+    (GitSyncSubmodules, Background),
+    (GitGetReflog, Background),
+    (GitReflogResponse, Background),
     (GitShow, Background),
     (GitCommitDetails, Background),
     (GitFileHistory, Background),
@@ -399,12 +440,19 @@ request_messages!(
     (GetDefinition, GetDefinitionResponse),
     (GetDeclaration, GetDeclarationResponse),
     (GetImplementation, GetImplementationResponse),
+    (PrepareCallHierarchy, PrepareCallHierarchyResponse),
+    (GetIncomingCalls, GetIncomingCallsResponse),
+    (GetOutgoingCalls, GetOutgoingCallsResponse),
+    (PrepareTypeHierarchy, PrepareTypeHierarchyResponse),
+    (GetSupertypes, GetSupertypesResponse),
+    (GetSubtypes, GetSubtypesResponse),
     (GetDocumentHighlights, GetDocumentHighlightsResponse),
     (GetDocumentSymbols, GetDocumentSymbolsResponse),
     (GetHover, GetHoverResponse),
     (GetNotifications, GetNotificationsResponse),
     (GetProjectSymbols, GetProjectSymbolsResponse),
     (GetReferences, GetReferencesResponse),
+    (GetRenameEdit, GetRenameEditResponse),
     (GetSignatureHelp, GetSignatureHelpResponse),
     (OpenUnstagedDiff, OpenUnstagedDiffResponse),
     (OpenUncommittedDiff, OpenUncommittedDiffResponse),
@@ -440,6 +488,7 @@ request_messages!(
     (RefreshInlayHints, Ack),
     (RefreshSemanticTokens, Ack),
     (RefreshCodeLens, Ack),
+    (ResolveCodeLens, ResolveCodeLensResponse),
     (RejoinChannelBuffers, RejoinChannelBuffersResponse),
     (RejoinRoom, RejoinRoomResponse),
     (ReloadBuffers, ReloadBuffersResponse),
@@ -458,6 +507,8 @@ request_messages!(
     (ResolveInlayHint, ResolveInlayHintResponse),
     (GetDocumentColor, GetDocumentColorResponse),
     (GetFoldingRanges, GetFoldingRangesResponse),
+    (GetDocumentLinks, GetDocumentLinksResponse),
+    (ResolveDocumentLink, ResolveDocumentLinkResponse),
     (GetColorPresentation, GetColorPresentationResponse),
     (RespondToChannelInvite, Ack),
     (RespondToContactRequest, Ack),
@@ -526,6 +577,22 @@ request_messages!(
     (GitReset, Ack),
     (GitDeleteBranch, Ack),
     (GitCheckoutFiles, Ack),
+    (GitRebaseTodo, GitRebaseTodoResponse),
+    (GitIsAncestor, GitIsAncestorResponse),
+    (GitRebase, Ack),
+    (GitCherryPick, Ack),
+    (GitRevert, Ack),
+    (GitContinueOperation, Ack),
+    (GitAbortOperation, Ack),
+    (GitGetTags, GitTagsResponse),
+    (GitCreateTag, Ack),
+    (GitDeleteTag, Ack),
+    (GitBisectStart, Ack),
+    (GitBisectMark, GitBisectMarkResponse),
+    (GitBisectReset, Ack),
+    (GitUpdateSubmodules, RemoteMessageResponse),
+    (GitSyncSubmodules, Ack),
+    (GitGetReflog, GitReflogResponse),
     (SetIndexText, Ack),
     (Push, RemoteMessageResponse),
     (Fetch, RemoteMessageResponse),
@@ -569,6 +636,7 @@ lsp_messages!(
     (GetReferences, GetReferencesResponse, true),
     (GetDocumentColor, GetDocumentColorResponse, true),
     (GetFoldingRanges, GetFoldingRangesResponse, true),
+    (GetDocumentLinks, GetDocumentLinksResponse, true),
     (GetDocumentSymbols, GetDocumentSymbolsResponse, true),
     (GetHover, GetHoverResponse, true),
     (GetCodeActions, GetCodeActionsResponse, true),
@@ -604,6 +672,7 @@ entity_messages!(
     CreateProjectEntry,
     GetDocumentColor,
     GetFoldingRanges,
+    GetDocumentLinks,
     DeleteProjectEntry,
     ExpandProjectEntry,
     ExpandAllForProjectEntry,
@@ -616,11 +685,18 @@ entity_messages!(
     GetDefinition,
     GetDeclaration,
     GetImplementation,
+    PrepareCallHierarchy,
+    GetIncomingCalls,
+    GetOutgoingCalls,
+    PrepareTypeHierarchy,
+    GetSupertypes,
+    GetSubtypes,
     GetDocumentHighlights,
     GetDocumentSymbols,
     GetHover,
     GetProjectSymbols,
     GetReferences,
+    GetRenameEdit,
     GetSignatureHelp,
     OpenUnstagedDiff,
     OpenUncommittedDiff,
@@ -652,7 +728,9 @@ entity_messages!(
     ReloadBuffers,
     RemoveProjectCollaborator,
     RenameProjectEntry,
+    ResolveCodeLens,
     ResolveCompletionDocumentation,
+    ResolveDocumentLink,
     ResolveInlayHint,
     SaveBuffer,
     Stage,
@@ -711,6 +789,22 @@ entity_messages!(
     GitReset,
     GitDeleteBranch,
     GitCheckoutFiles,
+    GitRebaseTodo,
+    GitIsAncestor,
+    GitRebase,
+    GitCherryPick,
+    GitRevert,
+    GitContinueOperation,
+    GitAbortOperation,
+    GitGetTags,
+    GitCreateTag,
+    GitDeleteTag,
+    GitBisectStart,
+    GitBisectMark,
+    GitBisectReset,
+    GitUpdateSubmodules,
+    GitSyncSubmodules,
+    GitGetReflog,
     SetIndexText,
     ToggleLspLogs,
     GetDirectoryEnvironment,
@@ -939,6 +1033,7 @@ impl LspQuery {
             Some(lsp_query::Request::GetDocumentColor(_)) => ("GetDocumentColor", false),
             Some(lsp_query::Request::GetFoldingRanges(_)) => ("GetFoldingRanges", false),
             Some(lsp_query::Request::GetDocumentSymbols(_)) => ("GetDocumentSymbols", false),
+            Some(lsp_query::Request::GetDocumentLinks(_)) => ("GetDocumentLinks", false),
             Some(lsp_query::Request::InlayHints(_)) => ("InlayHints", false),
             Some(lsp_query::Request::SemanticTokens(_)) => ("SemanticTokens", false),
             None => ("<unknown>", true),
@@ -229,6 +229,9 @@ impl HeadlessProject {
             lsp_store.shared(REMOTE_SERVER_PROJECT_ID, session.clone(), cx);
             lsp_store
         });
+        dap_store.update(cx, |dap_store, _| {
+            dap_store.set_lsp_store(lsp_store.downgrade());
+        });
 
         AgentRegistryStore::init_global(cx, fs.clone(), http_client.clone());
 
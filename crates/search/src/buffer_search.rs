@@ -1129,6 +1129,18 @@ impl BufferSearchBar {
         self.query_editor.read(cx).text(cx)
     }
 
+    /// Fills the search history with `queries`, oldest first, unless it already has entries.
+    pub fn seed_search_history(&mut self, queries: impl IntoIterator<Item = String>) {
+        if self.search_history.len() > 0 {
+            return;
+        }
+        for query in queries {
+            self.search_history
+                .add(&mut self.search_history_cursor, query);
+        }
+        self.search_history_cursor.reset();
+    }
+
     pub fn replacement(&self, cx: &mut App) -> String {
         self.replacement_editor.read(cx).text(cx)
     }
@@ -698,6 +698,17 @@ impl ProjectSearchView {
         self.entity.read(cx).match_ranges.clone()
     }
 
+    /// Returns the buffer and position of each match's start, in the order they're shown.
+    pub fn match_locations(&self, cx: &App) -> Vec<(Entity<Buffer>, language::Anchor)> {
+        let search = self.entity.read(cx);
+        let excerpts = search.excerpts.read(cx);
+        search
+            .match_ranges
+            .iter()
+            .filter_map(|range| excerpts.text_anchor_for_position(range.start, cx))
+            .collect()
+    }
+
     fn toggle_filters(&mut self, cx: &mut Context<Self>) {
         self.filters_enabled = !self.filters_enabled;
         ActiveSettings::update_global(cx, |settings, cx| {
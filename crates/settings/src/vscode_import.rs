@@ -191,6 +191,7 @@ impl VsCodeSettings {
                 ..GlobalLspSettingsContent::default()
             }),
             helix_mode: None,
+            hierarchy_panel: None,
             image_viewer: None,
             journal: None,
             language_models: None,
@@ -563,6 +564,7 @@ impl VsCodeSettings {
                     }
                 }),
             document_folding_ranges: None,
+            code_lens: self.read_bool("editor.codeLens"),
             document_symbols: None,
             linked_edits: self.read_bool("editor.linkedEditing"),
             preferred_line_length: self.read_u32("editor.wordWrapColumn"),
@@ -869,6 +871,8 @@ impl VsCodeSettings {
             minimum_contrast: None,
             option_as_meta: self.read_bool("terminal.integrated.macOptionIsMeta"),
             project: self.project_terminal_settings_content(),
+            restored_scrollback_lines: self
+                .read_usize("terminal.integrated.persistentSessionScrollback"),
             scrollbar: None,
             scroll_multiplier: None,
             toolbar: None,
@@ -111,6 +111,16 @@ pub struct AgentSettingsContent {
     ///
     /// Default: false
     pub show_turn_stats: Option<bool>,
+    /// Whether to automatically compact a thread once it uses a large part of the model's context window,
+    /// by summarizing its older messages into a recap.
+    ///
+    /// Default: true
+    pub auto_compact: Option<bool>,
+    /// The fraction of the model's context window a thread can use before it's automatically compacted.
+    ///
+    /// Default: 0.8
+    #[serde(serialize_with = "crate::serialize_optional_f32_with_two_decimal_places")]
+    pub auto_compact_threshold: Option<f32>,
     /// Per-tool permission rules for granular control over which tool actions
     /// require confirmation.
     ///
@@ -122,6 +132,13 @@ pub struct AgentSettingsContent {
     /// `always_confirm`) match against the tool's text input (command, path,
     /// URL, etc.).
     pub tool_permissions: Option<ToolPermissionsContent>,
+    /// Shell commands to run around the agent's tool calls and at the end of its turns,
+    /// e.g. to run formatters, linters and policy checks.
+    ///
+    /// Each command receives a JSON description of the event on stdin.
+    /// Exiting with status 2 blocks the tool call about to run, or otherwise
+    /// sends the command's stderr back to the agent as feedback.
+    pub hooks: Option<AgentHooksContent>,
 }
 
 impl AgentSettingsContent {
@@ -462,6 +479,50 @@ pub enum CustomAgentServerSettings {
     },
 }
 
+#[with_fallible_options]
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize, JsonSchema, MergeFrom)]
+pub struct AgentHooksContent {
+    /// Commands to run before the agent uses a tool.
+    /// A command can prevent the tool from running by exiting with status 2,
+    /// in which case its stderr is reported to the agent as the reason.
+    /// A command that exits with any other status or times out prevents it too.
+    ///
+    /// Default: []
+    pub pre_tool_use: Option<Vec<AgentHookContent>>,
+    /// Commands to run after the agent used a tool.
+    /// If a command exits with status 2, its stderr is added to the tool's result.
+    ///
+    /// Default: []
+    pub post_tool_use: Option<Vec<AgentHookContent>>,
+    /// Commands to run after the agent successfully created, edited or deleted files with a tool.
+    /// If a command exits with status 2, its stderr is added to the tool's result.
+    ///
+    /// Default: []
+    pub post_edit: Option<Vec<AgentHookContent>>,
+    /// Commands to run when the agent is about to end its turn.
+    /// If a command exits with status 2, its stderr is sent to the agent, which continues its turn.
+    ///
+    /// Default: []
+    pub end_of_turn: Option<Vec<AgentHookContent>>,
+}
+
+#[with_fallible_options]
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize, JsonSchema, MergeFrom)]
+pub struct AgentHookContent {
+    /// The command to run with the system shell, in the project's first worktree.
+    #[serde(default)]
+    pub command: String,
+    /// A regex matched against tool names, to only run the command for some tools.
+    /// Ignored by end-of-turn hooks.
+    ///
+    /// Default: all tools
+    pub tools: Option<String>,
+    /// How long the command can run, in seconds, before it's stopped.
+    ///
+    /// Default: 60
+    pub timeout: Option<u64>,
+}
+
 #[with_fallible_options]
 #[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize, JsonSchema, MergeFrom)]
 pub struct ToolPermissionsContent {
@@ -517,6 +517,11 @@ pub struct LanguageSettingsContent {
     ///
     /// Default: "off"
     pub document_folding_ranges: Option<DocumentFoldingRanges>,
+    /// Whether to show code lenses from language servers above the lines they refer to,
+    /// such as reference counts or "Run test" links.
+    ///
+    /// Default: false
+    pub code_lens: Option<bool>,
     /// Controls the source of document symbols used for outlines and breadcrumbs.
     ///
     /// Options:
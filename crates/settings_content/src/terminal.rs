@@ -147,6 +147,13 @@ pub struct TerminalSettingsContent {
     ///
     /// Default: 10_000
     pub max_scroll_history_lines: Option<usize>,
+    /// The maximum number of lines of each terminal's scrollback to save, and restore above
+    /// the shell's prompt when the terminal is reopened after a restart.
+    /// The scrollback is stored unencrypted in Zed's database, so it's off by default.
+    /// 0 disables restoring the scrollback.
+    ///
+    /// Default: 0
+    pub restored_scrollback_lines: Option<usize>,
     /// The multiplier for scrolling with the mouse wheel.
     ///
     /// Default: 1.0
@@ -6245,7 +6245,7 @@ fn terminal_page() -> SettingsPage {
         ]
     }
 
-    fn advanced_settings_section() -> [SettingsPageItem; 3] {
+    fn advanced_settings_section() -> [SettingsPageItem; 4] {
         [
             SettingsPageItem::SectionHeader("Advanced Settings"),
             SettingsPageItem::SettingItem(SettingItem {
@@ -6270,6 +6270,28 @@ fn terminal_page() -> SettingsPage {
                 metadata: None,
                 files: USER,
             }),
+            SettingsPageItem::SettingItem(SettingItem {
+                title: "Restored Scrollback Lines",
+                description: "Maximum number of lines of scrollback to save unencrypted and restore when a terminal is reopened after a restart (0 disables it).",
+                field: Box::new(SettingField {
+                    json_path: Some("terminal.restored_scrollback_lines"),
+                    pick: |settings_content| {
+                        settings_content
+                            .terminal
+                            .as_ref()?
+                            .restored_scrollback_lines
+                            .as_ref()
+                    },
+                    write: |settings_content, value| {
+                        settings_content
+                            .terminal
+                            .get_or_insert_default()
+                            .restored_scrollback_lines = value;
+                    },
+                }),
+                metadata: None,
+                files: USER,
+            }),
             SettingsPageItem::SettingItem(SettingItem {
                 title: "Scroll Multiplier",
                 description: "The multiplier for scrolling in the terminal with the mouse wheel",
@@ -6972,7 +6994,7 @@ fn ai_page() -> SettingsPage {
         ]
     }
 
-    fn agent_configuration_section() -> [SettingsPageItem; 12] {
+    fn agent_configuration_section() -> [SettingsPageItem; 14] {
         [
             SettingsPageItem::SectionHeader("Agent Configuration"),
             SettingsPageItem::SubPageLink(SubPageLink {
@@ -7188,6 +7210,41 @@ fn ai_page() -> SettingsPage {
                 metadata: None,
                 files: USER,
             }),
+            SettingsPageItem::SettingItem(SettingItem {
+                title: "Auto Compact",
+                description: "Whether to automatically summarize the older messages of a thread once it uses a large part of the model's context window.",
+                field: Box::new(SettingField {
+                    json_path: Some("agent.auto_compact"),
+                    pick: |settings_content| settings_content.agent.as_ref()?.auto_compact.as_ref(),
+                    write: |settings_content, value| {
+                        settings_content.agent.get_or_insert_default().auto_compact = value;
+                    },
+                }),
+                metadata: None,
+                files: USER,
+            }),
+            SettingsPageItem::SettingItem(SettingItem {
+                title: "Auto Compact Threshold",
+                description: "The fraction of the model's context window a thread can use before it's automatically compacted.",
+                field: Box::new(SettingField {
+                    json_path: Some("agent.auto_compact_threshold"),
+                    pick: |settings_content| {
+                        settings_content
+                            .agent
+                            .as_ref()?
+                            .auto_compact_threshold
+                            .as_ref()
+                    },
+                    write: |settings_content, value| {
+                        settings_content
+                            .agent
+                            .get_or_insert_default()
+                            .auto_compact_threshold = value;
+                    },
+                }),
+                metadata: None,
+                files: USER,
+            }),
         ]
     }
 
@@ -8578,7 +8635,7 @@ fn language_settings_data() -> Box<[SettingsPageItem]> {
 /// LanguageSettings items that should be included in the "Languages & Tools" page
 /// not the "Editor" page
 fn non_editor_language_settings_data() -> Box<[SettingsPageItem]> {
-    fn lsp_section() -> [SettingsPageItem; 8] {
+    fn lsp_section() -> [SettingsPageItem; 9] {
         [
             SettingsPageItem::SectionHeader("LSP"),
             SettingsPageItem::SettingItem(SettingItem {
@@ -8733,6 +8790,25 @@ fn non_editor_language_settings_data() -> Box<[SettingsPageItem]> {
                 metadata: None,
                 files: USER | PROJECT,
             }),
+            SettingsPageItem::SettingItem(SettingItem {
+                title: "Code Lenses",
+                description: "Whether to show code lenses from the language server above the lines they refer to.",
+                field: Box::new(SettingField {
+                    json_path: Some("languages.$(language).code_lens"),
+                    pick: |settings_content| {
+                        language_settings_field(settings_content, |language| {
+                            language.code_lens.as_ref()
+                        })
+                    },
+                    write: |settings_content, value| {
+                        language_settings_field_mut(settings_content, value, |language, value| {
+                            language.code_lens = value;
+                        })
+                    },
+                }),
+                metadata: None,
+                files: USER | PROJECT,
+            }),
         ]
     }
 
@@ -80,6 +80,12 @@ const TOOLS: &[ToolInfo] = &[
         description: "Discards unsaved changes by reloading from disk",
         regex_explanation: "Patterns are matched against the file path being restored.",
     },
+    ToolInfo {
+        id: "rename_symbol",
+        name: "Rename Symbol",
+        description: "Language server renames across the project",
+        regex_explanation: "Patterns are matched against the path of the file containing the symbol being renamed.",
+    },
 ];
 
 pub(crate) struct ToolInfo {
@@ -307,6 +313,7 @@ fn get_tool_render_fn(
         "fetch" => render_fetch_tool_config,
         "web_search" => render_web_search_tool_config,
         "restore_file_from_disk" => render_restore_file_from_disk_tool_config,
+        "rename_symbol" => render_rename_symbol_tool_config,
         _ => render_terminal_tool_config, // fallback
     }
 }
@@ -1386,6 +1393,7 @@ tool_config_page_fn!(
     render_restore_file_from_disk_tool_config,
     "restore_file_from_disk"
 );
+tool_config_page_fn!(render_rename_symbol_tool_config, "rename_symbol");
 
 #[cfg(test)]
 mod tests {
@@ -1401,12 +1409,16 @@ mod tests {
             // Read-only / low-risk tools that don't call decide_permission_from_settings
             "diagnostics",
             "find_path",
+            "find_references",
+            "go_to_definition",
             "grep",
+            "hover",
             "list_directory",
             "now",
             "open",
             "read_file",
             "thinking",
+            "workspace_symbols",
             // streaming_edit_file uses "edit_file" for permission lookups,
             // so its rules are configured under the edit_file entry.
             "streaming_edit_file",
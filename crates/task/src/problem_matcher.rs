@@ -0,0 +1,609 @@
+use anyhow::Context as _;
+use regex::{Captures, Regex};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use util::ResultExt as _;
+
+/// A way to extract problems (compiler errors, linter warnings, etc.) from the output of a task,
+/// to show them as diagnostics in the project.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(untagged)]
+pub enum ProblemMatcher {
+    /// One of the matchers shipped with Zed, referenced by its name, e.g. `$rustc`.
+    BuiltIn(BuiltInProblemMatcher),
+    /// A matcher with custom regex patterns.
+    Custom(CustomProblemMatcher),
+}
+
+/// Problem matchers shipped with Zed.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+pub enum BuiltInProblemMatcher {
+    /// Errors and warnings of `rustc` and `cargo`.
+    #[serde(rename = "$rustc")]
+    Rustc,
+    /// Errors of the TypeScript compiler.
+    #[serde(rename = "$tsc", alias = "$tsc-watch")]
+    Tsc,
+    /// Errors, warnings and notes of `gcc` and `clang`.
+    #[serde(rename = "$gcc", alias = "$clang")]
+    Gcc,
+    /// ESLint output in the `stylish` (default) format.
+    #[serde(rename = "$eslint-stylish")]
+    EslintStylish,
+    /// ESLint output in the `compact` format.
+    #[serde(rename = "$eslint-compact")]
+    EslintCompact,
+    /// Errors of the Go compiler and `go vet`.
+    #[serde(rename = "$go")]
+    Go,
+}
+
+/// A problem matcher, defined by one or more regex patterns.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub struct CustomProblemMatcher {
+    /// Name of the diagnostics source, displayed next to every problem matched.
+    #[serde(default)]
+    pub source: Option<String>,
+    /// Severity of the problems, for patterns that do not capture one.
+    #[serde(default)]
+    pub severity: ProblemSeverity,
+    /// Patterns to match against consecutive lines of the output.
+    /// A problem is reported once all of them match, one line per pattern.
+    pub patterns: Vec<ProblemPattern>,
+}
+
+/// A regex to match a single line of the output with,
+/// and the indices of its capture groups that contain parts of the problem.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub struct ProblemPattern {
+    /// The regex to match the line with.
+    pub regexp: String,
+    /// Capture group with the path to the file, absolute or relative to the task's working directory.
+    #[serde(default)]
+    pub file: Option<usize>,
+    /// Capture group with the 1-based line of the problem.
+    #[serde(default)]
+    pub line: Option<usize>,
+    /// Capture group with the 1-based column of the problem.
+    #[serde(default)]
+    pub column: Option<usize>,
+    /// Capture group with the 1-based line the problem ends at.
+    #[serde(default)]
+    pub end_line: Option<usize>,
+    /// Capture group with the 1-based column the problem ends at.
+    #[serde(default)]
+    pub end_column: Option<usize>,
+    /// Capture group with the severity of the problem: `error`, `warning`, `info` or `hint`.
+    #[serde(default)]
+    pub severity: Option<usize>,
+    /// Capture group with the code of the problem.
+    #[serde(default)]
+    pub code: Option<usize>,
+    /// Capture group with the message of the problem.
+    #[serde(default)]
+    pub message: Option<usize>,
+    /// Whether the pattern may match multiple lines in a row, reporting a problem for each of them.
+    /// Only applies to the last pattern of the matcher.
+    #[serde(default, rename = "loop")]
+    pub r#loop: bool,
+}
+
+/// Severity of a problem matched in the task output.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ProblemSeverity {
+    #[default]
+    Error,
+    Warning,
+    Info,
+    Hint,
+}
+
+impl ProblemSeverity {
+    fn parse(text: &str) -> Option<Self> {
+        match text.trim().to_lowercase().as_str() {
+            "error" | "fatal" | "fatal error" | "e" => Some(Self::Error),
+            "warning" | "warn" | "w" => Some(Self::Warning),
+            "info" | "information" | "note" | "i" => Some(Self::Info),
+            "hint" | "help" => Some(Self::Hint),
+            _ => None,
+        }
+    }
+}
+
+/// A problem, found in the task output by a [`ProblemMatcher`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct TaskProblem {
+    /// Name of the matcher that found the problem.
+    pub source: String,
+    /// Path to the file, as printed by the task: absolute or relative to the task's working directory.
+    pub path: String,
+    /// 1-based line of the problem.
+    pub line: u32,
+    /// 1-based column of the problem.
+    pub column: u32,
+    /// 1-based line and column the problem ends at, if known.
+    pub end: Option<(u32, u32)>,
+    pub severity: ProblemSeverity,
+    pub code: Option<String>,
+    pub message: String,
+}
+
+impl ProblemMatcher {
+    /// Name of the diagnostics source the problems found by this matcher are reported with.
+    pub fn source(&self) -> &str {
+        match self {
+            Self::BuiltIn(BuiltInProblemMatcher::Rustc) => "rustc",
+            Self::BuiltIn(BuiltInProblemMatcher::Tsc) => "tsc",
+            Self::BuiltIn(BuiltInProblemMatcher::Gcc) => "gcc",
+            Self::BuiltIn(
+                BuiltInProblemMatcher::EslintStylish | BuiltInProblemMatcher::EslintCompact,
+            ) => "eslint",
+            Self::BuiltIn(BuiltInProblemMatcher::Go) => "go",
+            Self::Custom(matcher) => matcher.source.as_deref().unwrap_or("task"),
+        }
+    }
+
+    fn definition(&self) -> CustomProblemMatcher {
+        match self {
+            Self::BuiltIn(built_in) => built_in.definition(),
+            Self::Custom(matcher) => matcher.clone(),
+        }
+    }
+}
+
+impl BuiltInProblemMatcher {
+    fn definition(&self) -> CustomProblemMatcher {
+        let patterns = match self {
+            Self::Rustc => vec![
+                ProblemPattern {
+                    regexp: r"^(warning|error)(?:\[(\w+)\])?: (.*)$".to_string(),
+                    severity: Some(1),
+                    code: Some(2),
+                    message: Some(3),
+                    ..ProblemPattern::default()
+                },
+                ProblemPattern {
+                    regexp: r"^\s*--> (.+?):(\d+):(\d+)$".to_string(),
+                    file: Some(1),
+                    line: Some(2),
+                    column: Some(3),
+                    ..ProblemPattern::default()
+                },
+            ],
+            Self::Tsc => vec![ProblemPattern {
+                regexp: r"^([^\s].*)[\(:](\d+)[,:](\d+)(?:\):\s+|\s+-\s+)(error|warning|info)\s+(TS\d+)\s*:\s*(.*)$"
+                    .to_string(),
+                file: Some(1),
+                line: Some(2),
+                column: Some(3),
+                severity: Some(4),
+                code: Some(5),
+                message: Some(6),
+                ..ProblemPattern::default()
+            }],
+            Self::Gcc => vec![ProblemPattern {
+                regexp: r"^(.*?):(\d+):(\d*):?\s+(?:fatal\s+)?(warning|error|note):\s+(.*)$"
+                    .to_string(),
+                file: Some(1),
+                line: Some(2),
+                column: Some(3),
+                severity: Some(4),
+                message: Some(5),
+                ..ProblemPattern::default()
+            }],
+            Self::EslintStylish => vec![
+                ProblemPattern {
+                    regexp: r"^((?:[a-zA-Z]:)*[./\\]+.*?)$".to_string(),
+                    file: Some(1),
+                    ..ProblemPattern::default()
+                },
+                ProblemPattern {
+                    regexp: r"^\s+(\d+):(\d+)\s+(error|warning|info)\s+(.+?)(?:\s\s+(.*))?$"
+                        .to_string(),
+                    line: Some(1),
+                    column: Some(2),
+                    severity: Some(3),
+                    message: Some(4),
+                    code: Some(5),
+                    r#loop: true,
+                    ..ProblemPattern::default()
+                },
+            ],
+            Self::EslintCompact => vec![ProblemPattern {
+                regexp: r"^(.+):\sline\s(\d+),\scol\s(\d+),\s(Error|Warning|Info)\s-\s(.+)\s\((.+)\)$"
+                    .to_string(),
+                file: Some(1),
+                line: Some(2),
+                column: Some(3),
+                severity: Some(4),
+                message: Some(5),
+                code: Some(6),
+                ..ProblemPattern::default()
+            }],
+            Self::Go => vec![ProblemPattern {
+                regexp: r"^\s*(.+?\.go):(\d+):(?:(\d+):)?\s*(.*)$".to_string(),
+                file: Some(1),
+                line: Some(2),
+                column: Some(3),
+                message: Some(4),
+                ..ProblemPattern::default()
+            }],
+        };
+        CustomProblemMatcher {
+            source: None,
+            severity: ProblemSeverity::Error,
+            patterns,
+        }
+    }
+}
+
+/// Runs all problem matchers given over the task output lines, returning all problems found.
+/// Matchers with invalid patterns are skipped.
+pub fn match_problems<'a>(
+    problem_matchers: &[ProblemMatcher],
+    lines: impl IntoIterator<Item = &'a str>,
+) -> Vec<TaskProblem> {
+    let mut matching = ProblemMatching::new(problem_matchers);
+    if matching.is_empty() {
+        return Vec::new();
+    }
+    lines
+        .into_iter()
+        .flat_map(|line| matching.match_line(line))
+        .collect()
+}
+
+/// Problem matchers of a task, compiled to match its output line by line, as it is printed.
+/// Matchers with invalid patterns are skipped.
+pub struct ProblemMatching {
+    matchers: Vec<LineMatcher>,
+}
+
+impl ProblemMatching {
+    pub fn new(problem_matchers: &[ProblemMatcher]) -> Self {
+        Self {
+            matchers: problem_matchers
+                .iter()
+                .filter_map(|matcher| LineMatcher::new(matcher).log_err())
+                .collect(),
+        }
+    }
+
+    /// Whether there are no valid matchers, so no problems can be found.
+    pub fn is_empty(&self) -> bool {
+        self.matchers.is_empty()
+    }
+
+    /// Matches the next line of the output, returning the problems it completes.
+    pub fn match_line(&mut self, line: &str) -> Vec<TaskProblem> {
+        self.matchers
+            .iter_mut()
+            .filter_map(|matcher| matcher.match_line(line))
+            .collect()
+    }
+
+    /// Drops the partial multi-line matches, e.g. when the output got cleared.
+    pub fn reset(&mut self) {
+        for matcher in &mut self.matchers {
+            matcher.reset();
+        }
+    }
+}
+
+/// A compiled problem matcher, keeping the state of its multi-line matches.
+struct LineMatcher {
+    source: String,
+    default_severity: ProblemSeverity,
+    patterns: Vec<(Regex, ProblemPattern)>,
+    next_pattern: usize,
+    /// Problem data, captured by the patterns matched so far.
+    captured: PartialProblem,
+    /// Whether all patterns matched and the last one keeps matching the following lines.
+    looping: bool,
+}
+
+#[derive(Clone, Debug, Default)]
+struct PartialProblem {
+    path: Option<String>,
+    line: Option<u32>,
+    column: Option<u32>,
+    end_line: Option<u32>,
+    end_column: Option<u32>,
+    severity: Option<ProblemSeverity>,
+    code: Option<String>,
+    message: Option<String>,
+}
+
+impl LineMatcher {
+    fn new(problem_matcher: &ProblemMatcher) -> anyhow::Result<Self> {
+        let definition = problem_matcher.definition();
+        anyhow::ensure!(
+            !definition.patterns.is_empty(),
+            "Problem matcher `{}` has no patterns",
+            problem_matcher.source()
+        );
+        let patterns = definition
+            .patterns
+            .into_iter()
+            .map(|pattern| {
+                let regex = Regex::new(&pattern.regexp).with_context(|| {
+                    format!("Invalid problem matcher pattern `{}`", pattern.regexp)
+                })?;
+                anyhow::Ok((regex, pattern))
+            })
+            .collect::<anyhow::Result<Vec<_>>>()?;
+        Ok(Self {
+            source: problem_matcher.source().to_string(),
+            default_severity: definition.severity,
+            patterns,
+            next_pattern: 0,
+            captured: PartialProblem::default(),
+            looping: false,
+        })
+    }
+
+    fn match_line(&mut self, line: &str) -> Option<TaskProblem> {
+        if self.looping {
+            let (regex, pattern) = self.patterns.last()?;
+            if let Some(captures) = regex.captures(line) {
+                let mut problem = self.captured.clone();
+                problem.apply(pattern, &captures);
+                return self.finish(problem);
+            }
+            self.reset();
+        }
+
+        let (regex, pattern) = &self.patterns[self.next_pattern];
+        let Some(captures) = regex.captures(line) else {
+            if self.next_pattern > 0 {
+                // The multi-line match got interrupted, the line may start a new one.
+                self.reset();
+                return self.match_line(line);
+            }
+            return None;
+        };
+
+        if self.next_pattern + 1 < self.patterns.len() {
+            if self.next_pattern == 0 {
+                self.captured = PartialProblem::default();
+            }
+            self.captured.apply(pattern, &captures);
+            self.next_pattern += 1;
+            return None;
+        }
+
+        let mut problem = if self.next_pattern == 0 {
+            PartialProblem::default()
+        } else {
+            self.captured.clone()
+        };
+        problem.apply(pattern, &captures);
+        if pattern.r#loop {
+            if self.next_pattern == 0 {
+                self.captured = PartialProblem::default();
+            }
+            self.looping = true;
+        }
+        self.next_pattern = 0;
+        self.finish(problem)
+    }
+
+    fn reset(&mut self) {
+        self.next_pattern = 0;
+        self.looping = false;
+        self.captured = PartialProblem::default();
+    }
+
+    fn finish(&self, problem: PartialProblem) -> Option<TaskProblem> {
+        let line = problem.line.filter(|line| *line > 0)?;
+        let column = problem.column.filter(|column| *column > 0).unwrap_or(1);
+        Some(TaskProblem {
+            source: self.source.clone(),
+            path: problem.path.filter(|path| !path.is_empty())?,
+            line,
+            column,
+            end: problem
+                .end_line
+                .map(|end_line| (end_line, problem.end_column.unwrap_or(column))),
+            severity: problem.severity.unwrap_or(self.default_severity),
+            code: problem.code,
+            message: problem.message.unwrap_or_default(),
+        })
+    }
+}
+
+impl PartialProblem {
+    fn apply(&mut self, pattern: &ProblemPattern, captures: &Captures<'_>) {
+        let capture = |group: Option<usize>| {
+            let text = captures.get(group?)?.as_str().trim();
+            (!text.is_empty()).then(|| text.to_string())
+        };
+        let number = |group: Option<usize>| capture(group)?.parse::<u32>().ok();
+
+        if let Some(path) = capture(pattern.file) {
+            self.path = Some(path);
+        }
+        if let Some(line) = number(pattern.line) {
+            self.line = Some(line);
+        }
+        if let Some(column) = number(pattern.column) {
+            self.column = Some(column);
+        }
+        if let Some(end_line) = number(pattern.end_line) {
+            self.end_line = Some(end_line);
+        }
+        if let Some(end_column) = number(pattern.end_column) {
+            self.end_column = Some(end_column);
+        }
+        if let Some(severity) = capture(pattern.severity).and_then(|s| ProblemSeverity::parse(&s)) {
+            self.severity = Some(severity);
+        }
+        if let Some(code) = capture(pattern.code) {
+            self.code = Some(code);
+        }
+        if let Some(message) = capture(pattern.message) {
+            self.message = Some(message);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn problems(matcher: BuiltInProblemMatcher, output: &str) -> Vec<TaskProblem> {
+        match_problems(&[ProblemMatcher::BuiltIn(matcher)], output.lines())
+    }
+
+    #[test]
+    fn test_rustc_problems() {
+        let output = r#"   Compiling demo v0.1.0 (/work/demo)
+warning: unused variable: `x`
+ --> src/main.rs:2:9
+  |
+2 |     let x = 5;
+  |         ^ help: if this is intentional, prefix it with an underscore: `_x`
+error[E0308]: mismatched types
+  --> src/lib.rs:10:18
+   |
+warning: `demo` (bin "demo") generated 1 warning
+error: could not compile `demo` (bin "demo") due to 1 previous error"#;
+
+        assert_eq!(
+            problems(BuiltInProblemMatcher::Rustc, output),
+            vec![
+                TaskProblem {
+                    source: "rustc".to_string(),
+                    path: "src/main.rs".to_string(),
+                    line: 2,
+                    column: 9,
+                    end: None,
+                    severity: ProblemSeverity::Warning,
+                    code: None,
+                    message: "unused variable: `x`".to_string(),
+                },
+                TaskProblem {
+                    source: "rustc".to_string(),
+                    path: "src/lib.rs".to_string(),
+                    line: 10,
+                    column: 18,
+                    end: None,
+                    severity: ProblemSeverity::Error,
+                    code: Some("E0308".to_string()),
+                    message: "mismatched types".to_string(),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_single_line_problems() {
+        let tsc = problems(
+            BuiltInProblemMatcher::Tsc,
+            "src/index.ts(3,7): error TS2322: Type 'string' is not assignable to type 'number'.",
+        );
+        assert_eq!(tsc.len(), 1);
+        assert_eq!(tsc[0].path, "src/index.ts");
+        assert_eq!((tsc[0].line, tsc[0].column), (3, 7));
+        assert_eq!(tsc[0].code.as_deref(), Some("TS2322"));
+
+        let gcc = problems(
+            BuiltInProblemMatcher::Gcc,
+            "main.c:4:12: fatal error: missing.h: No such file or directory\nmain.c:9:1: warning: control reaches end of non-void function",
+        );
+        assert_eq!(
+            gcc.iter()
+                .map(|problem| (problem.line, problem.severity))
+                .collect::<Vec<_>>(),
+            vec![(4, ProblemSeverity::Error), (9, ProblemSeverity::Warning)]
+        );
+
+        let go = problems(
+            BuiltInProblemMatcher::Go,
+            "./main.go:7:2: undefined: foo\n./util.go:12: missing return",
+        );
+        assert_eq!(
+            go.iter()
+                .map(|problem| (problem.path.as_str(), problem.line, problem.column))
+                .collect::<Vec<_>>(),
+            vec![("./main.go", 7, 2), ("./util.go", 12, 1)]
+        );
+    }
+
+    #[test]
+    fn test_looping_patterns() {
+        let output = "
+/work/app/src/index.js
+   1:10  error    'foo' is defined but never used  no-unused-vars
+   3:1   warning  Unexpected console statement      no-console
+
+/work/app/src/other.js
+  7:3  error  Missing semicolon  semi
+
+✖ 3 problems (2 errors, 1 warning)";
+
+        assert_eq!(
+            problems(BuiltInProblemMatcher::EslintStylish, output)
+                .into_iter()
+                .map(|problem| (problem.path, problem.line, problem.code))
+                .collect::<Vec<_>>(),
+            vec![
+                (
+                    "/work/app/src/index.js".to_string(),
+                    1,
+                    Some("no-unused-vars".to_string())
+                ),
+                (
+                    "/work/app/src/index.js".to_string(),
+                    3,
+                    Some("no-console".to_string())
+                ),
+                (
+                    "/work/app/src/other.js".to_string(),
+                    7,
+                    Some("semi".to_string())
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_custom_problem_matcher() {
+        let matcher: ProblemMatcher = serde_json::from_value(serde_json::json!({
+            "source": "lint",
+            "severity": "warning",
+            "patterns": [
+                { "regexp": "^(\\S+):(\\d+): (.*)$", "file": 1, "line": 2, "message": 3 }
+            ]
+        }))
+        .unwrap();
+        assert_eq!(
+            match_problems(&[matcher], ["a.txt:3: trailing whitespace", "done"]),
+            vec![TaskProblem {
+                source: "lint".to_string(),
+                path: "a.txt".to_string(),
+                line: 3,
+                column: 1,
+                end: None,
+                severity: ProblemSeverity::Warning,
+                code: None,
+                message: "trailing whitespace".to_string(),
+            }]
+        );
+
+        let built_in: ProblemMatcher = serde_json::from_value(serde_json::json!("$rustc")).unwrap();
+        assert_eq!(
+            built_in,
+            ProblemMatcher::BuiltIn(BuiltInProblemMatcher::Rustc)
+        );
+
+        let invalid: ProblemMatcher = serde_json::from_value(serde_json::json!({
+            "patterns": [{ "regexp": "(", "file": 1, "line": 1 }]
+        }))
+        .unwrap();
+        assert!(match_problems(&[invalid], ["(1"]).is_empty());
+    }
+}
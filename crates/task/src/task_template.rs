@@ -3,14 +3,15 @@ use collections::{HashMap, HashSet};
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use sha2::{Digest, Sha256};
+use std::borrow::Cow;
 use std::path::PathBuf;
 use util::schemars::{AllowTrailingCommas, DefaultDenyUnknownFields};
 use util::serde::default_true;
 use util::{ResultExt, truncate_and_remove_front};
 
 use crate::{
-    AttachRequest, ResolvedTask, RevealTarget, Shell, SpawnInTerminal, TaskContext, TaskId,
-    VariableName, ZED_VARIABLE_NAME_PREFIX, serde_helpers::non_empty_string_vec,
+    AttachRequest, ProblemMatcher, ResolvedTask, RevealTarget, Shell, SpawnInTerminal, TaskContext,
+    TaskId, VariableName, ZED_VARIABLE_NAME_PREFIX, serde_helpers::non_empty_string_vec,
 };
 
 /// A template definition of a Zed task to run.
@@ -25,6 +26,8 @@ pub struct TaskTemplate {
     /// Human readable name of the task to display in the UI.
     pub label: String,
     /// Executable command to spawn.
+    /// May be omitted for tasks that only run their dependencies.
+    #[serde(default)]
     pub command: String,
     /// Arguments to the command.
     #[serde(default)]
@@ -72,6 +75,87 @@ pub struct TaskTemplate {
     /// Whether to show the command line in the task output.
     #[serde(default = "default_true")]
     pub show_command: bool,
+    /// Labels of the tasks to run before this one.
+    /// If any of them fails, the rest of the chain (including this task) is not spawned.
+    /// A task with dependencies may have an empty `command`, to only group other tasks together.
+    #[serde(default)]
+    pub depends_on: Vec<String>,
+    /// How to run the tasks listed in `depends_on`:
+    /// * `sequence` — one after another, in the order they are listed (default)
+    /// * `parallel` — all at once, waiting for all of them to finish
+    #[serde(default)]
+    pub depends_order: DependsOrder,
+    /// Problem matchers to extract diagnostics (compiler errors, linter warnings, etc.) from the task output with.
+    /// Either names of the built-in matchers (`$rustc`, `$tsc`, `$gcc`, `$eslint-stylish`, `$eslint-compact`, `$go`),
+    /// or custom matchers with regex patterns.
+    /// The diagnostics are shown as the task prints them and are cleared when the task is run again.
+    #[serde(default)]
+    pub problem_matchers: Vec<ProblemMatcher>,
+    /// Values to ask the user for before spawning the task.
+    /// Each input is available in the other fields of the template as `$ZED_INPUT_<id>`.
+    #[serde(default)]
+    pub inputs: Vec<TaskInput>,
+}
+
+/// A value of a [`TaskTemplate`] that is provided by the user before the task is spawned,
+/// with the template referencing it as `$ZED_INPUT_<id>`.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub struct TaskInput {
+    /// Identifier of the input, used in the name of its variable.
+    /// Should consist of alphanumeric characters and underscores only.
+    pub id: String,
+    /// Human readable description of the input, shown when asking for its value.
+    #[serde(default)]
+    pub description: Option<String>,
+    /// How the value is asked for.
+    #[serde(flatten)]
+    pub kind: TaskInputKind,
+}
+
+/// How the value of a [`TaskInput`] is asked for.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum TaskInputKind {
+    /// Free-form text, typed by the user.
+    Prompt {
+        /// Value to suggest, when the task was not run with this input before.
+        #[serde(default)]
+        default: Option<String>,
+    },
+    /// One of the options listed.
+    Pick {
+        /// Values to pick from.
+        options: Vec<String>,
+        /// Value to preselect, when the task was not run with this input before.
+        #[serde(default)]
+        default: Option<String>,
+    },
+    /// One of the lines, printed by the shell command.
+    Command {
+        /// Command to run in the task's working directory, with the system shell.
+        command: String,
+        /// Arguments to the command.
+        #[serde(default)]
+        args: Vec<String>,
+    },
+}
+
+impl TaskInput {
+    /// Name of the task variable, that holds the value of the input.
+    pub fn variable_name(&self) -> VariableName {
+        VariableName::Input(Cow::Owned(self.id.clone()))
+    }
+
+    /// Value to suggest when the task was not run with this input before.
+    pub fn default_value(&self) -> Option<&str> {
+        match &self.kind {
+            TaskInputKind::Prompt { default } | TaskInputKind::Pick { default, .. } => {
+                default.as_deref()
+            }
+            TaskInputKind::Command { .. } => None,
+        }
+    }
 }
 
 #[derive(Deserialize, Eq, PartialEq, Clone, Debug)]
@@ -109,6 +193,17 @@ pub enum HideStrategy {
     OnSuccess,
 }
 
+/// How the dependencies of a task are run.
+#[derive(Default, Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum DependsOrder {
+    /// Run the dependencies one after another, in the order they are listed.
+    #[default]
+    Sequence,
+    /// Run all dependencies at once.
+    Parallel,
+}
+
 /// A group of Tasks defined in a JSON file.
 #[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
 pub struct TaskTemplates(pub Vec<TaskTemplate>);
@@ -132,16 +227,39 @@ impl TaskTemplate {
     /// Every [`ResolvedTask`] gets a [`TaskId`], based on the `id_base` (to avoid collision with various task sources),
     /// and hashes of its template and [`TaskContext`], see [`ResolvedTask`] fields' documentation for more details.
     pub fn resolve_task(&self, id_base: &str, cx: &TaskContext) -> Option<ResolvedTask> {
-        if self.label.trim().is_empty() || self.command.trim().is_empty() {
+        if self.label.trim().is_empty()
+            || (self.command.trim().is_empty() && self.depends_on.is_empty())
+        {
             return None;
         }
 
+        // Inputs without values are substituted with their own names, until the user provides the values.
+        let pending_inputs = self
+            .inputs
+            .iter()
+            .filter(|input| cx.task_variables.get(&input.variable_name()).is_none())
+            .cloned()
+            .collect::<Vec<_>>();
+        let pending_input_variables = pending_inputs
+            .iter()
+            .map(|input| {
+                let variable_name = input.variable_name();
+                let placeholder = variable_name.template_value();
+                (variable_name, placeholder)
+            })
+            .collect::<Vec<_>>();
+
         let mut variable_names = HashMap::default();
         let mut substituted_variables = HashSet::default();
         let task_variables = cx
             .task_variables
             .0
             .iter()
+            .chain(
+                pending_input_variables
+                    .iter()
+                    .map(|(variable_name, placeholder)| (variable_name, placeholder)),
+            )
             .map(|(key, value)| {
                 let key_string = key.to_string();
                 if !variable_names.contains_key(&key_string) {
@@ -244,6 +362,8 @@ impl TaskTemplate {
             id: id.clone(),
             substituted_variables,
             original_task: self.clone(),
+            task_context: cx.clone(),
+            pending_inputs,
             resolved_label: full_label.clone(),
             resolved: SpawnInTerminal {
                 id,
@@ -258,7 +378,7 @@ impl TaskTemplate {
                         command_label
                     },
                 ),
-                command: Some(command),
+                command: (!command.trim().is_empty()).then_some(command),
                 args: args_with_substitutions,
                 env,
                 use_new_terminal: self.use_new_terminal,
@@ -270,6 +390,7 @@ impl TaskTemplate {
                 show_summary: self.show_summary,
                 show_command: self.show_command,
                 show_rerun: true,
+                problem_matchers: self.problem_matchers.clone(),
             },
         })
     }
@@ -523,6 +644,75 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_resolving_templates_with_dependencies_only() {
+        let task_with_dependencies = TaskTemplate {
+            label: "build all".to_string(),
+            depends_on: vec!["build client".to_string(), "build server".to_string()],
+            depends_order: DependsOrder::Parallel,
+            ..TaskTemplate::default()
+        };
+
+        let resolved_task = task_with_dependencies
+            .resolve_task(TEST_ID_BASE, &TaskContext::default())
+            .expect("should resolve a task without a command but with dependencies");
+        assert!(resolved_task.is_dependencies_only());
+        assert_eq!(resolved_task.resolved.command, None);
+        assert_eq!(resolved_task.original_task(), &task_with_dependencies);
+    }
+
+    #[test]
+    fn test_resolving_templates_with_inputs() {
+        let target_input = TaskInput {
+            id: "target".to_string(),
+            description: Some("Target to build".to_string()),
+            kind: TaskInputKind::Pick {
+                options: vec!["x86_64".to_string(), "aarch64".to_string()],
+                default: None,
+            },
+        };
+        let task_with_inputs = TaskTemplate {
+            label: "build $ZED_INPUT_target".to_string(),
+            command: "cargo".to_string(),
+            args: vec![
+                "build".to_string(),
+                "--target".to_string(),
+                "$ZED_INPUT_target".to_string(),
+            ],
+            inputs: vec![target_input.clone()],
+            ..TaskTemplate::default()
+        };
+
+        let pending_task = task_with_inputs
+            .resolve_task(TEST_ID_BASE, &TaskContext::default())
+            .expect("should resolve a task with inputs not provided yet");
+        assert_eq!(pending_task.pending_inputs(), &[target_input.clone()]);
+        assert_eq!(pending_task.resolved_label, "build $ZED_INPUT_target");
+        assert_eq!(
+            pending_task.task_context(),
+            &TaskContext::default(),
+            "Placeholders of the pending inputs should not leak into the task context"
+        );
+
+        let mut task_context = TaskContext::default();
+        task_context
+            .task_variables
+            .insert(target_input.variable_name(), "aarch64".to_string());
+        let resolved_task = task_with_inputs
+            .resolve_task(TEST_ID_BASE, &task_context)
+            .expect("should resolve a task with inputs provided");
+        assert!(resolved_task.pending_inputs().is_empty());
+        assert_eq!(resolved_task.resolved_label, "build aarch64");
+        assert_eq!(
+            resolved_task.resolved.args,
+            vec!["build", "--target", "aarch64"]
+        );
+        assert_ne!(
+            pending_task.id, resolved_task.id,
+            "Tasks with different input values should have different ids"
+        );
+    }
+
     #[test]
     fn test_template_cwd_resolution() {
         let task_without_cwd = TaskTemplate {
@@ -87,6 +87,8 @@ fn task_type_to_adapter_name(task_type: &str) -> String {
         "pwa-node" | "node" | "node-terminal" | "chrome" | "pwa-chrome" | "edge" | "pwa-edge"
         | "msedge" | "pwa-msedge" => "JavaScript",
         "go" => "Delve",
+        "java" => "Java",
+        "coreclr" => "netcoredbg",
         "php" => "Xdebug",
         "cppdbg" | "lldb" => "CodeLLDB",
         "debugpy" => "Debugpy",
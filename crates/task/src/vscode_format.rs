@@ -1,9 +1,12 @@
-use anyhow::bail;
+use anyhow::{Context as _, bail};
 use collections::HashMap;
 use serde::Deserialize;
 use util::ResultExt;
 
-use crate::{EnvVariableReplacer, TaskTemplate, TaskTemplates, VariableName};
+use crate::{
+    BuiltInProblemMatcher, CustomProblemMatcher, DependsOrder, EnvVariableReplacer, ProblemMatcher,
+    ProblemPattern, ProblemSeverity, TaskTemplate, TaskTemplates, VariableName,
+};
 
 #[derive(Clone, Debug, Deserialize, PartialEq)]
 #[serde(rename_all = "camelCase")]
@@ -19,6 +22,9 @@ struct VsCodeTaskDefinition {
     command: Option<Command>,
     other_attributes: HashMap<String, serde_json_lenient::Value>,
     options: Option<TaskOptions>,
+    depends_on: Vec<String>,
+    depends_order: DependsOrder,
+    problem_matchers: Vec<ProblemMatcher>,
 }
 
 impl<'de> serde::Deserialize<'de> for VsCodeTaskDefinition {
@@ -36,6 +42,12 @@ impl<'de> serde::Deserialize<'de> for VsCodeTaskDefinition {
             #[serde(flatten)]
             other_attributes: HashMap<String, serde_json_lenient::Value>,
             options: Option<TaskOptions>,
+            #[serde(default)]
+            depends_on: Option<DependsOn>,
+            #[serde(default)]
+            depends_order: Option<DependsOrder>,
+            #[serde(default)]
+            problem_matcher: Option<VsCodeProblemMatchers>,
         }
 
         let helper = TaskHelper::deserialize(deserializer)?;
@@ -49,6 +61,16 @@ impl<'de> serde::Deserialize<'de> for VsCodeTaskDefinition {
             command: helper.command,
             other_attributes: helper.other_attributes,
             options: helper.options,
+            depends_on: helper
+                .depends_on
+                .map(DependsOn::into_labels)
+                .unwrap_or_default(),
+            // Code runs the dependencies in parallel, unless told otherwise.
+            depends_order: helper.depends_order.unwrap_or(DependsOrder::Parallel),
+            problem_matchers: helper
+                .problem_matcher
+                .map(VsCodeProblemMatchers::into_zed_format)
+                .unwrap_or_default(),
         })
     }
 }
@@ -70,6 +92,160 @@ enum Command {
     },
 }
 
+/// A `dependsOn` value: either a single task reference or a list of them.
+#[derive(Clone, Deserialize, PartialEq, Debug)]
+#[serde(untagged)]
+enum DependsOn {
+    Single(TaskReference),
+    Multiple(Vec<TaskReference>),
+}
+
+/// A reference to another task, either by its label or by its task identifier (e.g. `{ "type": "npm", "script": "build" }`).
+#[derive(Clone, Deserialize, PartialEq, Debug)]
+#[serde(untagged)]
+enum TaskReference {
+    Label(String),
+    Identifier(Command),
+}
+
+impl DependsOn {
+    fn into_labels(self) -> Vec<String> {
+        let references = match self {
+            Self::Single(reference) => vec![reference],
+            Self::Multiple(references) => references,
+        };
+        references
+            .into_iter()
+            .map(|reference| match reference {
+                TaskReference::Label(label) => label,
+                TaskReference::Identifier(command) => generate_label(&Some(command)),
+            })
+            .collect()
+    }
+}
+
+/// A `problemMatcher` value: either a single problem matcher or a list of them.
+#[derive(Clone, Deserialize, PartialEq, Debug)]
+#[serde(untagged)]
+enum VsCodeProblemMatchers {
+    Single(VsCodeProblemMatcher),
+    Multiple(Vec<VsCodeProblemMatcher>),
+}
+
+/// A problem matcher, either referenced by its name (e.g. `$tsc`) or defined in place.
+#[derive(Clone, Deserialize, PartialEq, Debug)]
+#[serde(untagged)]
+enum VsCodeProblemMatcher {
+    Name(String),
+    Definition(VsCodeProblemMatcherDefinition),
+}
+
+#[derive(Clone, Deserialize, PartialEq, Debug)]
+#[serde(rename_all = "camelCase")]
+struct VsCodeProblemMatcherDefinition {
+    #[serde(default)]
+    base: Option<String>,
+    #[serde(default)]
+    owner: Option<String>,
+    #[serde(default)]
+    source: Option<String>,
+    #[serde(default)]
+    severity: Option<ProblemSeverity>,
+    #[serde(default)]
+    pattern: Option<VsCodeProblemPatterns>,
+}
+
+/// A `pattern` value: a single pattern, a list of them for multi-line problems, or a reference to a named pattern.
+#[derive(Clone, Deserialize, PartialEq, Debug)]
+#[serde(untagged)]
+enum VsCodeProblemPatterns {
+    Single(VsCodeProblemPattern),
+    Multiple(Vec<VsCodeProblemPattern>),
+    Name(String),
+}
+
+#[derive(Clone, Deserialize, PartialEq, Debug)]
+#[serde(rename_all = "camelCase")]
+struct VsCodeProblemPattern {
+    regexp: String,
+    #[serde(default)]
+    file: Option<usize>,
+    #[serde(default)]
+    line: Option<usize>,
+    #[serde(default)]
+    column: Option<usize>,
+    #[serde(default)]
+    end_line: Option<usize>,
+    #[serde(default)]
+    end_column: Option<usize>,
+    #[serde(default)]
+    severity: Option<usize>,
+    #[serde(default)]
+    code: Option<usize>,
+    #[serde(default)]
+    message: Option<usize>,
+    #[serde(default, rename = "loop")]
+    r#loop: bool,
+}
+
+impl VsCodeProblemMatchers {
+    fn into_zed_format(self) -> Vec<ProblemMatcher> {
+        let matchers = match self {
+            Self::Single(matcher) => vec![matcher],
+            Self::Multiple(matchers) => matchers,
+        };
+        matchers
+            .into_iter()
+            .filter_map(|matcher| matcher.into_zed_format().log_err())
+            .collect()
+    }
+}
+
+impl VsCodeProblemMatcher {
+    fn into_zed_format(self) -> anyhow::Result<ProblemMatcher> {
+        let definition = match self {
+            Self::Name(name) => return built_in_problem_matcher(&name),
+            Self::Definition(definition) => definition,
+        };
+        let patterns = match definition.pattern {
+            Some(VsCodeProblemPatterns::Single(pattern)) => vec![pattern],
+            Some(VsCodeProblemPatterns::Multiple(patterns)) => patterns,
+            // Named patterns are not supported, but the base matcher usually brings the same ones along.
+            Some(VsCodeProblemPatterns::Name(_)) | None => {
+                let Some(base) = definition.base else {
+                    bail!("Problem matcher has neither `base` nor `pattern`");
+                };
+                return built_in_problem_matcher(&base);
+            }
+        };
+        Ok(ProblemMatcher::Custom(CustomProblemMatcher {
+            source: definition.source.or(definition.owner),
+            severity: definition.severity.unwrap_or_default(),
+            patterns: patterns
+                .into_iter()
+                .map(|pattern| ProblemPattern {
+                    regexp: pattern.regexp,
+                    file: pattern.file,
+                    line: pattern.line,
+                    column: pattern.column,
+                    end_line: pattern.end_line,
+                    end_column: pattern.end_column,
+                    severity: pattern.severity,
+                    code: pattern.code,
+                    message: pattern.message,
+                    r#loop: pattern.r#loop,
+                })
+                .collect(),
+        }))
+    }
+}
+
+fn built_in_problem_matcher(name: &str) -> anyhow::Result<ProblemMatcher> {
+    let matcher = serde_json::from_value::<BuiltInProblemMatcher>(name.into())
+        .with_context(|| format!("Unsupported problem matcher `{name}`"))?;
+    Ok(ProblemMatcher::BuiltIn(matcher))
+}
+
 fn generate_label(command: &Option<Command>) -> String {
     match command {
         Some(Command::Npm { script }) => format!("npm: {}", script),
@@ -86,29 +262,16 @@ fn generate_label(command: &Option<Command>) -> String {
 }
 
 impl VsCodeTaskDefinition {
-    fn into_zed_format(
-        self,
-        replacer: &EnvVariableReplacer,
-    ) -> anyhow::Result<Option<TaskTemplate>> {
-        if self.other_attributes.contains_key("dependsOn") {
-            log::warn!(
-                "Skipping deserializing of a task `{}` with the unsupported `dependsOn` key",
-                self.label
-            );
-            return Ok(None);
-        }
-        // `type` might not be set in e.g. tasks that use `dependsOn`; we still want to deserialize the whole object though (hence command is an Option),
+    fn into_zed_format(self, replacer: &EnvVariableReplacer) -> anyhow::Result<TaskTemplate> {
+        // `type` might not be set in e.g. tasks that use `dependsOn` to only group other tasks together;
+        // we still want to deserialize the whole object though (hence command is an Option),
         // as that way we can provide more specific description of why deserialization failed.
-        // E.g. if the command is missing due to `dependsOn` presence, we can check other_attributes first before doing this (and provide nice error message)
-        // catch-all if on value.command presence.
-        let Some(command) = self.command else {
-            bail!("Missing `type` field in task");
-        };
-
-        let (command, args) = match command {
-            Command::Npm { script } => ("npm".to_owned(), vec!["run".to_string(), script]),
-            Command::Shell { command, args } => (command, args),
-            Command::Gulp { task } => ("gulp".to_owned(), vec![task]),
+        let (command, args) = match self.command {
+            Some(Command::Npm { script }) => ("npm".to_owned(), vec!["run".to_string(), script]),
+            Some(Command::Shell { command, args }) => (command, args),
+            Some(Command::Gulp { task }) => ("gulp".to_owned(), vec![task]),
+            None if !self.depends_on.is_empty() => (String::new(), Vec::new()),
+            None => bail!("Missing `type` field in task"),
         };
         // Per VSC docs, only `command`, `args` and `options` support variable substitution.
         let command = replacer.replace(&command);
@@ -117,13 +280,16 @@ impl VsCodeTaskDefinition {
             label: self.label,
             command,
             args,
+            depends_on: self.depends_on,
+            depends_order: self.depends_order,
+            problem_matchers: self.problem_matchers,
             ..TaskTemplate::default()
         };
         if let Some(options) = self.options {
             template.cwd = options.cwd.map(|cwd| replacer.replace(&cwd));
             template.env = options.env;
         }
-        Ok(Some(template))
+        Ok(template)
     }
 }
 
@@ -152,12 +318,7 @@ impl TryFrom<VsCodeTaskFile> for TaskTemplates {
         let templates = value
             .tasks
             .into_iter()
-            .filter_map(|vscode_definition| {
-                vscode_definition
-                    .into_zed_format(&replacer)
-                    .log_err()
-                    .flatten()
-            })
+            .filter_map(|vscode_definition| vscode_definition.into_zed_format(&replacer).log_err())
             .collect();
         Ok(Self(templates))
     }
@@ -168,7 +329,8 @@ mod tests {
     use std::collections::HashMap;
 
     use crate::{
-        TaskTemplate, TaskTemplates, VsCodeTaskFile,
+        BuiltInProblemMatcher, CustomProblemMatcher, DependsOrder, ProblemMatcher, ProblemPattern,
+        ProblemSeverity, TaskTemplate, TaskTemplates, VsCodeTaskFile,
         vscode_format::{Command, VsCodeTaskDefinition},
     };
 
@@ -225,6 +387,9 @@ mod tests {
                     script: "build:tests:notypecheck".to_string(),
                 }),
                 other_attributes: Default::default(),
+                depends_on: Vec::new(),
+                depends_order: DependsOrder::Parallel,
+                problem_matchers: vec![ProblemMatcher::BuiltIn(BuiltInProblemMatcher::Tsc)],
                 options: None,
             },
             VsCodeTaskDefinition {
@@ -239,6 +404,9 @@ mod tests {
                     ],
                 }),
                 other_attributes: Default::default(),
+                depends_on: Vec::new(),
+                depends_order: DependsOrder::Parallel,
+                problem_matchers: vec![ProblemMatcher::BuiltIn(BuiltInProblemMatcher::Tsc)],
                 options: None,
             },
             VsCodeTaskDefinition {
@@ -247,6 +415,9 @@ mod tests {
                     script: "build:compiler".to_string(),
                 }),
                 other_attributes: Default::default(),
+                depends_on: Vec::new(),
+                depends_order: DependsOrder::Parallel,
+                problem_matchers: vec![ProblemMatcher::BuiltIn(BuiltInProblemMatcher::Tsc)],
                 options: None,
             },
             VsCodeTaskDefinition {
@@ -255,6 +426,9 @@ mod tests {
                     script: "build:tests:notypecheck".to_string(),
                 }),
                 other_attributes: Default::default(),
+                depends_on: Vec::new(),
+                depends_order: DependsOrder::Parallel,
+                problem_matchers: vec![ProblemMatcher::BuiltIn(BuiltInProblemMatcher::Tsc)],
                 options: None,
             },
         ];
@@ -271,6 +445,7 @@ mod tests {
                 label: "gulp: tests".to_string(),
                 command: "npm".to_string(),
                 args: vec!["run".to_string(), "build:tests:notypecheck".to_string()],
+                problem_matchers: vec![ProblemMatcher::BuiltIn(BuiltInProblemMatcher::Tsc)],
                 ..Default::default()
             },
             TaskTemplate {
@@ -282,18 +457,21 @@ mod tests {
                     "${ZED_WORKTREE_ROOT}/src".to_string(),
                     "--watch".to_string(),
                 ],
+                problem_matchers: vec![ProblemMatcher::BuiltIn(BuiltInProblemMatcher::Tsc)],
                 ..Default::default()
             },
             TaskTemplate {
                 label: "npm: build:compiler".to_string(),
                 command: "npm".to_string(),
                 args: vec!["run".to_string(), "build:compiler".to_string()],
+                problem_matchers: vec![ProblemMatcher::BuiltIn(BuiltInProblemMatcher::Tsc)],
                 ..Default::default()
             },
             TaskTemplate {
                 label: "npm: build:tests".to_string(),
                 command: "npm".to_string(),
                 args: vec!["run".to_string(), "build:tests:notypecheck".to_string()],
+                problem_matchers: vec![ProblemMatcher::BuiltIn(BuiltInProblemMatcher::Tsc)],
                 ..Default::default()
             },
         ];
@@ -315,6 +493,9 @@ mod tests {
                 }),
                 options: None,
                 other_attributes: Default::default(),
+                depends_on: Vec::new(),
+                depends_order: DependsOrder::Parallel,
+                problem_matchers: vec![ProblemMatcher::BuiltIn(BuiltInProblemMatcher::Tsc)],
             },
             VsCodeTaskDefinition {
                 label: "Build Extension".to_string(),
@@ -323,6 +504,9 @@ mod tests {
                 }),
                 options: None,
                 other_attributes: Default::default(),
+                depends_on: Vec::new(),
+                depends_order: DependsOrder::Parallel,
+                problem_matchers: vec![ProblemMatcher::BuiltIn(BuiltInProblemMatcher::Tsc)],
             },
             VsCodeTaskDefinition {
                 label: "Build Server".to_string(),
@@ -332,6 +516,9 @@ mod tests {
                 }),
                 options: None,
                 other_attributes: Default::default(),
+                depends_on: Vec::new(),
+                depends_order: DependsOrder::Parallel,
+                problem_matchers: vec![ProblemMatcher::BuiltIn(BuiltInProblemMatcher::Rustc)],
             },
             VsCodeTaskDefinition {
                 label: "Build Server (Release)".to_string(),
@@ -341,6 +528,9 @@ mod tests {
                 }),
                 options: None,
                 other_attributes: Default::default(),
+                depends_on: Vec::new(),
+                depends_order: DependsOrder::Parallel,
+                problem_matchers: vec![ProblemMatcher::BuiltIn(BuiltInProblemMatcher::Rustc)],
             },
             VsCodeTaskDefinition {
                 label: "Pretest".to_string(),
@@ -349,18 +539,30 @@ mod tests {
                 }),
                 options: None,
                 other_attributes: Default::default(),
+                depends_on: Vec::new(),
+                depends_order: DependsOrder::Parallel,
+                problem_matchers: vec![ProblemMatcher::BuiltIn(BuiltInProblemMatcher::Tsc)],
             },
             VsCodeTaskDefinition {
                 label: "Build Server and Extension".to_string(),
                 command: None,
                 options: None,
                 other_attributes: Default::default(),
+                depends_on: vec!["Build Server".to_string(), "Build Extension".to_string()],
+                depends_order: DependsOrder::Parallel,
+                problem_matchers: vec![ProblemMatcher::BuiltIn(BuiltInProblemMatcher::Rustc)],
             },
             VsCodeTaskDefinition {
                 label: "Build Server (Release) and Extension".to_string(),
                 command: None,
                 options: None,
                 other_attributes: Default::default(),
+                depends_on: vec![
+                    "Build Server (Release)".to_string(),
+                    "Build Extension".to_string(),
+                ],
+                depends_order: DependsOrder::Parallel,
+                problem_matchers: vec![ProblemMatcher::BuiltIn(BuiltInProblemMatcher::Rustc)],
             },
         ];
         assert_eq!(vscode_definitions.tasks.len(), expected.len());
@@ -374,28 +576,50 @@ mod tests {
                 label: "Build Extension in Background".to_string(),
                 command: "npm".to_string(),
                 args: vec!["run".to_string(), "watch".to_string()],
+                problem_matchers: vec![ProblemMatcher::BuiltIn(BuiltInProblemMatcher::Tsc)],
                 ..Default::default()
             },
             TaskTemplate {
                 label: "Build Extension".to_string(),
                 command: "npm".to_string(),
                 args: vec!["run".to_string(), "build".to_string()],
+                problem_matchers: vec![ProblemMatcher::BuiltIn(BuiltInProblemMatcher::Tsc)],
                 ..Default::default()
             },
             TaskTemplate {
                 label: "Build Server".to_string(),
                 command: "cargo build --package rust-analyzer".to_string(),
+                problem_matchers: vec![ProblemMatcher::BuiltIn(BuiltInProblemMatcher::Rustc)],
                 ..Default::default()
             },
             TaskTemplate {
                 label: "Build Server (Release)".to_string(),
                 command: "cargo build --release --package rust-analyzer".to_string(),
+                problem_matchers: vec![ProblemMatcher::BuiltIn(BuiltInProblemMatcher::Rustc)],
                 ..Default::default()
             },
             TaskTemplate {
                 label: "Pretest".to_string(),
                 command: "npm".to_string(),
                 args: vec!["run".to_string(), "pretest".to_string()],
+                problem_matchers: vec![ProblemMatcher::BuiltIn(BuiltInProblemMatcher::Tsc)],
+                ..Default::default()
+            },
+            TaskTemplate {
+                label: "Build Server and Extension".to_string(),
+                depends_on: vec!["Build Server".to_string(), "Build Extension".to_string()],
+                depends_order: DependsOrder::Parallel,
+                problem_matchers: vec![ProblemMatcher::BuiltIn(BuiltInProblemMatcher::Rustc)],
+                ..Default::default()
+            },
+            TaskTemplate {
+                label: "Build Server (Release) and Extension".to_string(),
+                depends_on: vec![
+                    "Build Server (Release)".to_string(),
+                    "Build Extension".to_string(),
+                ],
+                depends_order: DependsOrder::Parallel,
+                problem_matchers: vec![ProblemMatcher::BuiltIn(BuiltInProblemMatcher::Rustc)],
                 ..Default::default()
             },
         ];
@@ -416,6 +640,121 @@ mod tests {
         assert_eq!(vscode_definitions.tasks[3].label, "echo hello");
     }
 
+    #[test]
+    fn can_deserialize_task_dependencies() {
+        let vscode_definitions: VsCodeTaskFile = serde_json_lenient::from_str(
+            r#"{
+                "version": "2.0.0",
+                "tasks": [
+                    {
+                        "label": "test",
+                        "type": "shell",
+                        "command": "cargo test",
+                        "dependsOn": "build"
+                    },
+                    {
+                        "label": "ci",
+                        "dependsOn": ["lint", { "type": "npm", "script": "check" }],
+                        "dependsOrder": "sequence"
+                    },
+                    {
+                        "label": "broken"
+                    }
+                ]
+            }"#,
+        )
+        .unwrap();
+
+        let tasks: TaskTemplates = vscode_definitions.try_into().unwrap();
+        assert_eq!(
+            tasks.0,
+            vec![
+                TaskTemplate {
+                    label: "test".to_string(),
+                    command: "cargo test".to_string(),
+                    depends_on: vec!["build".to_string()],
+                    depends_order: DependsOrder::Parallel,
+                    ..Default::default()
+                },
+                TaskTemplate {
+                    label: "ci".to_string(),
+                    depends_on: vec!["lint".to_string(), "npm: check".to_string()],
+                    depends_order: DependsOrder::Sequence,
+                    ..Default::default()
+                },
+            ],
+            "Tasks without both a command and dependencies should be skipped"
+        );
+    }
+
+    #[test]
+    fn can_deserialize_problem_matchers() {
+        let vscode_definitions: VsCodeTaskFile = serde_json_lenient::from_str(
+            r#"{
+                "version": "2.0.0",
+                "tasks": [
+                    {
+                        "label": "build",
+                        "type": "shell",
+                        "command": "make",
+                        "problemMatcher": ["$gcc", "$unknown", { "base": "$eslint-compact" }]
+                    },
+                    {
+                        "label": "lint",
+                        "type": "shell",
+                        "command": "lint",
+                        "problemMatcher": {
+                            "owner": "lint",
+                            "severity": "warning",
+                            "pattern": {
+                                "regexp": "^(.*):(\\d+):(\\d+): (.*)$",
+                                "file": 1,
+                                "line": 2,
+                                "column": 3,
+                                "message": 4
+                            }
+                        }
+                    }
+                ]
+            }"#,
+        )
+        .unwrap();
+
+        let tasks: TaskTemplates = vscode_definitions.try_into().unwrap();
+        assert_eq!(
+            tasks.0,
+            vec![
+                TaskTemplate {
+                    label: "build".to_string(),
+                    command: "make".to_string(),
+                    problem_matchers: vec![
+                        ProblemMatcher::BuiltIn(BuiltInProblemMatcher::Gcc),
+                        ProblemMatcher::BuiltIn(BuiltInProblemMatcher::EslintCompact),
+                    ],
+                    ..Default::default()
+                },
+                TaskTemplate {
+                    label: "lint".to_string(),
+                    command: "lint".to_string(),
+                    problem_matchers: vec![ProblemMatcher::Custom(CustomProblemMatcher {
+                        source: Some("lint".to_string()),
+                        severity: ProblemSeverity::Warning,
+                        patterns: vec![ProblemPattern {
+                            regexp: r"^(.*):(\d+):(\d+): (.*)$".to_string(),
+                            file: Some(1),
+                            line: Some(2),
+                            column: Some(3),
+                            message: Some(4),
+                            ..Default::default()
+                        }],
+                    })],
+                    ..Default::default()
+                },
+            ],
+            "Unknown problem matchers should be skipped"
+        );
+    }
+
     #[test]
     fn test_generate_label() {
         assert_eq!(
@@ -0,0 +1,498 @@
+use std::{path::PathBuf, sync::Arc};
+
+use anyhow::Context as _;
+use futures::channel::oneshot;
+use fuzzy::{StringMatch, StringMatchCandidate};
+use gpui::{
+    App, AppContext as _, Context, DismissEvent, Entity, EventEmitter, Focusable,
+    InteractiveElement, ParentElement, Render, SharedString, Styled, Subscription, Task, Window,
+    rems,
+};
+use picker::{Picker, PickerDelegate, highlighted_match_with_paths::HighlightedMatch};
+use task::{Shell, ShellBuilder, TaskInput, TaskInputKind};
+use ui::{Color, ListItem, ListItemSpacing, Toggleable, prelude::*};
+use workspace::ModalView;
+
+/// A modal used to ask the user for the value of a task input.
+pub struct TaskInputModalDelegate {
+    candidates: Arc<[String]>,
+    /// Whether the query is a value on its own, rather than a filter for the candidates.
+    free_form: bool,
+    default: Option<String>,
+    matches: Vec<StringMatch>,
+    selected_index: usize,
+    placeholder_text: Arc<str>,
+    error: Option<SharedString>,
+    sender: Option<oneshot::Sender<Option<String>>>,
+}
+
+impl TaskInputModalDelegate {
+    fn new(
+        input: &TaskInput,
+        default: Option<String>,
+        sender: oneshot::Sender<Option<String>>,
+    ) -> Self {
+        let (candidates, free_form) = match &input.kind {
+            TaskInputKind::Prompt { .. } => (default.clone().into_iter().collect(), true),
+            TaskInputKind::Pick { options, .. } => (options.iter().cloned().collect(), false),
+            TaskInputKind::Command { .. } => (Arc::default(), false),
+        };
+        let placeholder_text = match &input.description {
+            Some(description) => Arc::from(description.as_str()),
+            None if free_form => Arc::from(format!("Enter the value of `{}`", input.id)),
+            None => Arc::from(format!("Pick the value of `{}`", input.id)),
+        };
+        Self {
+            candidates,
+            free_form,
+            default,
+            matches: Vec::new(),
+            selected_index: 0,
+            placeholder_text,
+            error: None,
+            sender: Some(sender),
+        }
+    }
+
+    fn send(&mut self, value: Option<String>) {
+        if let Some(sender) = self.sender.take() {
+            sender.send(value).ok();
+        }
+    }
+}
+
+pub struct TaskInputModal {
+    pub picker: Entity<Picker<TaskInputModalDelegate>>,
+    _subscription: Subscription,
+}
+
+impl TaskInputModal {
+    pub fn new(
+        input: TaskInput,
+        default: Option<String>,
+        cwd: Option<PathBuf>,
+        is_local: bool,
+        sender: oneshot::Sender<Option<String>>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Self {
+        let picker = cx.new(|cx| {
+            Picker::uniform_list(
+                TaskInputModalDelegate::new(&input, default, sender),
+                window,
+                cx,
+            )
+            .modal(true)
+        });
+
+        if let TaskInputKind::Command { command, args } = &input.kind {
+            let command_output = if is_local {
+                command_output_lines(command.clone(), args.clone(), cwd, cx)
+            } else {
+                Task::ready(Err(anyhow::anyhow!(
+                    "Command inputs are only supported in local projects"
+                )))
+            };
+            let picker = picker.downgrade();
+            cx.spawn_in(window, async move |_, cx| {
+                let command_output = command_output.await;
+                picker.update_in(cx, |picker, window, cx| {
+                    match command_output {
+                        Ok(lines) => picker.delegate.candidates = lines.into(),
+                        Err(e) => picker.delegate.error = Some(format!("{e:#}").into()),
+                    }
+                    picker.refresh(window, cx);
+                })
+            })
+            .detach_and_log_err(cx);
+        }
+
+        Self {
+            _subscription: cx.subscribe(&picker, |_, _, _: &DismissEvent, cx| {
+                cx.emit(DismissEvent);
+            }),
+            picker,
+        }
+    }
+}
+
+fn command_output_lines(
+    command: String,
+    args: Vec<String>,
+    cwd: Option<PathBuf>,
+    cx: &App,
+) -> Task<anyhow::Result<Vec<String>>> {
+    cx.background_spawn(async move {
+        let mut command_to_run = ShellBuilder::new(&Shell::System, cfg!(windows))
+            .non_interactive()
+            .build_smol_command(Some(command.clone()), &args);
+        if let Some(cwd) = cwd {
+            command_to_run.current_dir(cwd);
+        }
+        let output = command_to_run
+            .output()
+            .await
+            .with_context(|| format!("running `{command}`"))?;
+        anyhow::ensure!(
+            output.status.success(),
+            "`{command}` failed: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        );
+        Ok(String::from_utf8_lossy(&output.stdout)
+            .lines()
+            .map(str::trim)
+            .filter(|line| !line.is_empty())
+            .map(ToOwned::to_owned)
+            .collect())
+    })
+}
+
+impl Render for TaskInputModal {
+    fn render(&mut self, _window: &mut Window, _: &mut Context<Self>) -> impl IntoElement {
+        v_flex()
+            .key_context("TaskInputModal")
+            .w(rems(34.))
+            .child(self.picker.clone())
+    }
+}
+
+impl EventEmitter<DismissEvent> for TaskInputModal {}
+
+impl Focusable for TaskInputModal {
+    fn focus_handle(&self, cx: &App) -> gpui::FocusHandle {
+        self.picker.read(cx).focus_handle(cx)
+    }
+}
+
+impl ModalView for TaskInputModal {}
+
+impl PickerDelegate for TaskInputModalDelegate {
+    type ListItem = ListItem;
+
+    fn match_count(&self) -> usize {
+        self.matches.len()
+    }
+
+    fn selected_index(&self) -> usize {
+        self.selected_index
+    }
+
+    fn set_selected_index(
+        &mut self,
+        ix: usize,
+        _window: &mut Window,
+        _: &mut Context<Picker<Self>>,
+    ) {
+        self.selected_index = ix;
+    }
+
+    fn placeholder_text(&self, _window: &mut Window, _: &mut App) -> Arc<str> {
+        self.placeholder_text.clone()
+    }
+
+    fn no_matches_text(&self, _window: &mut Window, _: &mut App) -> Option<SharedString> {
+        if let Some(error) = &self.error {
+            Some(error.clone())
+        } else if self.free_form {
+            Some("Type in the value".into())
+        } else {
+            Some("No matches".into())
+        }
+    }
+
+    fn update_matches(
+        &mut self,
+        query: String,
+        _window: &mut Window,
+        cx: &mut Context<Picker<Self>>,
+    ) -> Task<()> {
+        if self.free_form && !query.is_empty() {
+            self.matches = vec![StringMatch {
+                candidate_id: 0,
+                score: 1.0,
+                positions: Vec::new(),
+                string: query,
+            }];
+            self.selected_index = 0;
+            return Task::ready(());
+        }
+
+        let candidates = self.candidates.clone();
+        cx.spawn(async move |picker, cx| {
+            let matches = fuzzy::match_strings(
+                &candidates
+                    .iter()
+                    .enumerate()
+                    .map(|(id, candidate)| StringMatchCandidate::new(id, candidate))
+                    .collect::<Vec<_>>(),
+                &query,
+                true,
+                true,
+                1000,
+                &Default::default(),
+                cx.background_executor().clone(),
+            )
+            .await;
+
+            picker
+                .update(cx, |picker, _| {
+                    let delegate = &mut picker.delegate;
+                    delegate.selected_index = query
+                        .is_empty()
+                        .then(|| {
+                            let default = delegate.default.as_ref()?;
+                            matches.iter().position(|hit| &hit.string == default)
+                        })
+                        .flatten()
+                        .unwrap_or(0);
+                    delegate.matches = matches;
+                })
+                .ok();
+        })
+    }
+
+    fn confirm(&mut self, _secondary: bool, _window: &mut Window, cx: &mut Context<Picker<Self>>) {
+        let Some(value) = self
+            .matches
+            .get(self.selected_index)
+            .map(|hit| hit.string.clone())
+        else {
+            return;
+        };
+        self.send(Some(value));
+        cx.emit(DismissEvent);
+    }
+
+    fn confirm_input(
+        &mut self,
+        _secondary: bool,
+        _window: &mut Window,
+        cx: &mut Context<Picker<Self>>,
+    ) {
+        if !self.free_form {
+            return;
+        }
+        let value = self
+            .matches
+            .first()
+            .map(|hit| hit.string.clone())
+            .unwrap_or_default();
+        self.send(Some(value));
+        cx.emit(DismissEvent);
+    }
+
+    fn dismissed(&mut self, _window: &mut Window, cx: &mut Context<Picker<Self>>) {
+        self.send(None);
+        cx.emit(DismissEvent);
+    }
+
+    fn render_match(
+        &self,
+        ix: usize,
+        selected: bool,
+        _window: &mut Window,
+        _: &mut Context<Picker<Self>>,
+    ) -> Option<Self::ListItem> {
+        let hit = self.matches.get(ix)?;
+        Some(
+            ListItem::new(SharedString::from(format!("task-input-{ix}")))
+                .inset(true)
+                .spacing(ListItemSpacing::Sparse)
+                .toggle_state(selected)
+                .child(HighlightedMatch {
+                    text: hit.string.clone(),
+                    highlight_positions: hit.positions.clone(),
+                    color: Color::Default,
+                }),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use gpui::{TestAppContext, VisualTestContext};
+    use project::{FakeFs, Project, TaskSourceKind};
+    use serde_json::json;
+    use task::{TaskContext, TaskTemplate};
+    use util::path;
+    use workspace::{MultiWorkspace, Workspace};
+
+    use crate::tests::init_test;
+
+    use super::*;
+
+    #[gpui::test]
+    async fn test_task_inputs(cx: &mut TestAppContext) {
+        init_test(cx);
+        let fs = FakeFs::new(cx.executor());
+        fs.insert_tree(path!("/dir"), json!({ "a.txt": "" })).await;
+        let project = Project::test(fs, [path!("/dir").as_ref()], cx).await;
+        let (multi_workspace, cx) =
+            cx.add_window_view(|window, cx| MultiWorkspace::test_new(project.clone(), window, cx));
+        let workspace = multi_workspace.read_with(cx, |mw, _| mw.workspace().clone());
+
+        let template = TaskTemplate {
+            label: "greet".to_string(),
+            command: "echo".to_string(),
+            args: vec![
+                "$ZED_INPUT_greeting".to_string(),
+                "$ZED_INPUT_name".to_string(),
+            ],
+            inputs: vec![
+                TaskInput {
+                    id: "greeting".to_string(),
+                    description: None,
+                    kind: TaskInputKind::Pick {
+                        options: vec!["hello".to_string(), "hi".to_string()],
+                        default: None,
+                    },
+                },
+                TaskInput {
+                    id: "name".to_string(),
+                    description: Some("Who to greet".to_string()),
+                    kind: TaskInputKind::Prompt {
+                        default: Some("world".to_string()),
+                    },
+                },
+            ],
+            ..TaskTemplate::default()
+        };
+
+        schedule_task(&workspace, &template, cx);
+        let input_picker = active_input_picker(&workspace, cx);
+        assert_eq!(input_picker_matches(&input_picker, cx), ["hello", "hi"]);
+        assert_eq!(selected_match(&input_picker, cx), Some("hello".to_string()));
+        cx.dispatch_action(menu::SelectNext);
+        cx.dispatch_action(menu::Confirm);
+        cx.run_until_parked();
+
+        let input_picker = active_input_picker(&workspace, cx);
+        assert_eq!(
+            input_picker_matches(&input_picker, cx),
+            ["world"],
+            "The default value should be suggested"
+        );
+        cx.simulate_input("there");
+        assert_eq!(input_picker_matches(&input_picker, cx), ["there"]);
+        cx.dispatch_action(picker::ConfirmInput { secondary: false });
+        cx.run_until_parked();
+
+        assert!(
+            workspace.read_with(cx, |workspace, cx| {
+                workspace.active_modal::<TaskInputModal>(cx).is_none()
+            }),
+            "No more inputs should be asked for"
+        );
+        assert_eq!(
+            last_scheduled_args(&project, cx),
+            Some(vec!["hi".to_string(), "there".to_string()])
+        );
+
+        schedule_task(&workspace, &template, cx);
+        let input_picker = active_input_picker(&workspace, cx);
+        assert_eq!(
+            selected_match(&input_picker, cx),
+            Some("hi".to_string()),
+            "The value used last time should be preselected"
+        );
+        cx.simulate_input("hel");
+        assert_eq!(input_picker_matches(&input_picker, cx), ["hello"]);
+        cx.dispatch_action(menu::Confirm);
+        cx.run_until_parked();
+
+        let input_picker = active_input_picker(&workspace, cx);
+        assert_eq!(
+            input_picker_matches(&input_picker, cx),
+            ["there"],
+            "The value used last time should be suggested instead of the default"
+        );
+        cx.dispatch_action(menu::Cancel);
+        cx.run_until_parked();
+
+        assert!(workspace.read_with(cx, |workspace, cx| {
+            workspace.active_modal::<TaskInputModal>(cx).is_none()
+        }));
+        assert_eq!(
+            last_scheduled_args(&project, cx),
+            Some(vec!["hi".to_string(), "there".to_string()]),
+            "Dismissing an input should not schedule the task"
+        );
+    }
+
+    fn schedule_task(
+        workspace: &Entity<Workspace>,
+        template: &TaskTemplate,
+        cx: &mut VisualTestContext,
+    ) {
+        workspace.update_in(cx, |workspace, window, cx| {
+            workspace.schedule_task(
+                TaskSourceKind::UserInput,
+                template,
+                &TaskContext::default(),
+                false,
+                window,
+                cx,
+            )
+        });
+        cx.run_until_parked();
+    }
+
+    fn active_input_picker(
+        workspace: &Entity<Workspace>,
+        cx: &mut VisualTestContext,
+    ) -> Entity<Picker<TaskInputModalDelegate>> {
+        workspace.read_with(cx, |workspace, cx| {
+            workspace
+                .active_modal::<TaskInputModal>(cx)
+                .expect("an input should be asked for")
+                .read(cx)
+                .picker
+                .clone()
+        })
+    }
+
+    fn input_picker_matches(
+        input_picker: &Entity<Picker<TaskInputModalDelegate>>,
+        cx: &mut VisualTestContext,
+    ) -> Vec<String> {
+        input_picker.read_with(cx, |input_picker, _| {
+            input_picker
+                .delegate
+                .matches
+                .iter()
+                .map(|hit| hit.string.clone())
+                .collect()
+        })
+    }
+
+    fn selected_match(
+        input_picker: &Entity<Picker<TaskInputModalDelegate>>,
+        cx: &mut VisualTestContext,
+    ) -> Option<String> {
+        input_picker.read_with(cx, |input_picker, _| {
+            let delegate = &input_picker.delegate;
+            Some(
+                delegate
+                    .matches
+                    .get(delegate.selected_index)?
+                    .string
+                    .clone(),
+            )
+        })
+    }
+
+    fn last_scheduled_args(
+        project: &Entity<Project>,
+        cx: &mut VisualTestContext,
+    ) -> Option<Vec<String>> {
+        project.read_with(cx, |project, cx| {
+            let (_, task) = project
+                .task_store()
+                .read(cx)
+                .task_inventory()?
+                .read(cx)
+                .last_scheduled_task(None)?;
+            Some(task.resolved.args)
+        })
+    }
+}
@@ -1,19 +1,28 @@
-use std::{path::Path, sync::Arc};
+use std::{
+    path::{Path, PathBuf},
+    sync::Arc,
+};
 
 use collections::HashMap;
 use editor::Editor;
-use gpui::{App, AppContext as _, Context, Entity, Task, Window};
+use futures::channel::oneshot;
+use gpui::{App, AppContext as _, Context, Entity, Task, WeakEntity, Window};
 use project::{Location, TaskContexts, TaskSourceKind, Worktree};
-use task::{RevealTarget, TaskContext, TaskId, TaskTemplate, TaskVariables, VariableName};
+use task::{
+    RevealTarget, TaskContext, TaskId, TaskInput, TaskTemplate, TaskVariables, VariableName,
+};
 use workspace::Workspace;
 
+mod input_modal;
 mod modal;
 
+pub use input_modal::TaskInputModal;
 pub use modal::{Rerun, ShowAttachModal, Spawn, TaskOverrides, TasksModal};
 
 pub fn init(cx: &mut App) {
     cx.observe_new(
-        |workspace: &mut Workspace, _: Option<&mut Window>, _: &mut Context<Workspace>| {
+        |workspace: &mut Workspace, _: Option<&mut Window>, cx: &mut Context<Workspace>| {
+            workspace.set_task_input_provider(TaskInputProvider(cx.weak_entity()));
             workspace
                 .register_action(spawn_task_or_modal)
                 .register_action(move |workspace, action: &modal::Rerun, window, cx| {
@@ -95,6 +104,31 @@ pub fn init(cx: &mut App) {
     .detach();
 }
 
+struct TaskInputProvider(WeakEntity<Workspace>);
+
+impl workspace::TaskInputProvider for TaskInputProvider {
+    fn prompt_for_input(
+        &self,
+        input: TaskInput,
+        default: Option<String>,
+        cwd: Option<PathBuf>,
+        window: &mut Window,
+        cx: &mut App,
+    ) -> Task<Option<String>> {
+        let Some(workspace) = self.0.upgrade() else {
+            return Task::ready(None);
+        };
+        let (tx, rx) = oneshot::channel();
+        workspace.update(cx, |workspace, cx| {
+            let is_local = workspace.project().read(cx).is_local();
+            workspace.toggle_modal(window, cx, |window, cx| {
+                TaskInputModal::new(input, default, cwd, is_local, tx, window, cx)
+            });
+        });
+        cx.background_spawn(async move { rx.await.ok().flatten() })
+    }
+}
+
 fn spawn_task_or_modal(
     workspace: &mut Workspace,
     action: &Spawn,
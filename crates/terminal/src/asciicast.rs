@@ -0,0 +1,216 @@
+//! Records terminal sessions in the [asciicast v2](https://docs.asciinema.org/manual/asciicast/v2/)
+//! format, and reads the recordings back so that they can be replayed.
+//!
+//! A recording is a JSON header line followed by one JSON array per event, of the form
+//! `[time, code, data]`, where `time` is the number of seconds since the recording started.
+//! Only the output (`"o"`) and resize (`"r"`) events are recorded: what's typed is echoed
+//! back as output anyway, and leaving input out keeps the passwords typed at prompts out of
+//! the recordings.
+
+use std::{
+    fs::File,
+    io::Write as _,
+    path::{Path, PathBuf},
+    time::{Duration, Instant, SystemTime},
+};
+
+use anyhow::{Context as _, Result, bail};
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct AsciicastHeader {
+    pub version: u32,
+    pub width: usize,
+    pub height: usize,
+    /// When the recording started, in seconds since the Unix epoch.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub timestamp: Option<u64>,
+    /// The longest pause to keep when replaying, in seconds.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub idle_time_limit: Option<f64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub title: Option<String>,
+}
+
+/// A recorded session, read back from an asciicast file.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Asciicast {
+    pub header: AsciicastHeader,
+    /// The output written to the terminal, with the time since the start of the recording
+    /// at which it was written.
+    pub output: Vec<(Duration, String)>,
+}
+
+impl Asciicast {
+    pub fn parse(content: &str) -> Result<Self> {
+        let mut lines = content.lines().filter(|line| !line.trim().is_empty());
+        let header: AsciicastHeader =
+            serde_json::from_str(lines.next().context("the recording is empty")?)
+                .context("invalid asciicast header")?;
+        if header.version != 2 {
+            bail!("unsupported asciicast version {}", header.version);
+        }
+
+        let mut output = Vec::new();
+        for (ix, line) in lines.enumerate() {
+            let (time, code, data): (f64, String, String) = serde_json::from_str(line)
+                .with_context(|| format!("invalid asciicast event {}", ix + 1))?;
+            let time = Duration::try_from_secs_f64(time)
+                .with_context(|| format!("invalid time in asciicast event {}", ix + 1))?;
+            // Replays fit the view they're shown in, so resizes are left out along with the
+            // input and markers.
+            if code == "o" {
+                output.push((time, data));
+            }
+        }
+        Ok(Self { header, output })
+    }
+}
+
+/// A session being recorded to an asciicast file.
+pub(crate) struct Recording {
+    path: PathBuf,
+    file: File,
+    start: Instant,
+    /// The end of the output, if it stops partway through a UTF-8 character.
+    incomplete: Vec<u8>,
+}
+
+impl Recording {
+    pub fn create(path: &Path, columns: usize, lines: usize, title: String) -> Result<Self> {
+        let header = AsciicastHeader {
+            version: 2,
+            width: columns,
+            height: lines,
+            timestamp: SystemTime::now()
+                .duration_since(SystemTime::UNIX_EPOCH)
+                .ok()
+                .map(|timestamp| timestamp.as_secs()),
+            idle_time_limit: None,
+            title: Some(title).filter(|title| !title.is_empty()),
+        };
+        let mut file = File::create(path)
+            .with_context(|| format!("failed to create the recording {path:?}"))?;
+        writeln!(file, "{}", serde_json::to_string(&header)?)?;
+        Ok(Self {
+            path: path.to_path_buf(),
+            file,
+            start: Instant::now(),
+            incomplete: Vec::new(),
+        })
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    pub fn output(&mut self, bytes: &[u8]) -> Result<()> {
+        self.incomplete.extend_from_slice(bytes);
+        let complete = self.incomplete.len() - incomplete_char_len(&self.incomplete);
+        if complete == 0 {
+            return Ok(());
+        }
+        let data = String::from_utf8_lossy(&self.incomplete[..complete]).into_owned();
+        self.incomplete.drain(..complete);
+        self.write_event("o", &data)
+    }
+
+    pub fn resize(&mut self, columns: usize, lines: usize) -> Result<()> {
+        self.write_event("r", &format!("{columns}x{lines}"))
+    }
+
+    /// Writes out the rest of the output, and returns where the recording was saved.
+    pub fn finish(mut self) -> Result<PathBuf> {
+        if !self.incomplete.is_empty() {
+            let data = String::from_utf8_lossy(&self.incomplete).into_owned();
+            self.write_event("o", &data)?;
+        }
+        self.file.sync_all()?;
+        Ok(self.path)
+    }
+
+    fn write_event(&mut self, code: &str, data: &str) -> Result<()> {
+        let time = self.start.elapsed().as_secs_f64();
+        // Each event is written as it happens, so that the recording survives a crash.
+        writeln!(self.file, "{}", serde_json::to_string(&(time, code, data))?)?;
+        Ok(())
+    }
+}
+
+/// Returns the length of the UTF-8 character the bytes end partway through, if any.
+fn incomplete_char_len(bytes: &[u8]) -> usize {
+    for len in 1..=bytes.len().min(3) {
+        let byte = bytes[bytes.len() - len];
+        if byte & 0b1100_0000 != 0b1000_0000 {
+            let char_len = match byte {
+                0b1100_0000..=0b1101_1111 => 2,
+                0b1110_0000..=0b1110_1111 => 3,
+                0b1111_0000..=0b1111_0111 => 4,
+                _ => 1,
+            };
+            return if char_len > len { len } else { 0 };
+        }
+    }
+    0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_record_and_parse() {
+        let dir = std::env::temp_dir().join(format!("asciicast-test-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("session.cast");
+
+        let mut recording = Recording::create(&path, 80, 24, "ssh web-1".into()).unwrap();
+        recording.output(b"$ echo caf").unwrap();
+        // A character split across two reads is written out once it's complete.
+        recording.output(&"é".as_bytes()[..1]).unwrap();
+        recording.output(&"é".as_bytes()[1..]).unwrap();
+        recording.resize(100, 30).unwrap();
+        recording.output(b"\r\n\x1b[1mcaf\xc3").unwrap();
+        assert_eq!(recording.finish().unwrap(), path);
+
+        let cast = Asciicast::parse(&std::fs::read_to_string(&path).unwrap()).unwrap();
+        assert_eq!(cast.header.version, 2);
+        assert_eq!((cast.header.width, cast.header.height), (80, 24));
+        assert_eq!(cast.header.title.as_deref(), Some("ssh web-1"));
+        assert!(cast.header.timestamp.is_some());
+        assert_eq!(
+            cast.output
+                .iter()
+                .map(|(_, output)| output.as_str())
+                .collect::<Vec<_>>(),
+            ["$ echo caf", "é", "\r\n\x1b[1mcaf", "\u{fffd}"]
+        );
+        assert!(cast.output.is_sorted_by_key(|(time, _)| *time));
+
+        std::fs::remove_dir_all(&dir).ok();
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        assert!(Asciicast::parse("").is_err());
+        assert!(Asciicast::parse(r#"{"version": 1, "width": 80, "height": 24}"#).is_err());
+        assert!(
+            Asciicast::parse("{\"version\": 2, \"width\": 80, \"height\": 24}\n[-1, \"o\", \"\"]")
+                .is_err()
+        );
+
+        let cast = Asciicast::parse(
+            "{\"version\": 2, \"width\": 80, \"height\": 24, \"idle_time_limit\": 1.5}\n\
+             [0.5, \"o\", \"hello\"]\n[0.7, \"i\", \"x\"]\n\n[1.25, \"m\", \"\"]\n[2.0, \"o\", \"!\"]\n",
+        )
+        .unwrap();
+        assert_eq!(cast.header.idle_time_limit, Some(1.5));
+        assert_eq!(
+            cast.output,
+            [
+                (Duration::from_millis(500), "hello".to_string()),
+                (Duration::from_secs(2), "!".to_string()),
+            ]
+        );
+    }
+}
@@ -0,0 +1,824 @@
+//! Inline images, drawn by programs with the kitty graphics protocol, iTerm2's inline
+//! images protocol or sixels.
+//!
+//! Alacritty ignores the sequences carrying images, so they're picked out of the output
+//! as it's parsed, like the shell integration marks. Each image is placed at the cursor, over the cells it
+//! covers, and the cursor is moved past it so that the output after it isn't drawn over it.
+//! Images are anchored to lines the same way the marks are, so they scroll with the output.
+
+use std::{collections::VecDeque, io::Read as _, sync::Arc};
+
+use alacritty_terminal::{
+    event::{EventListener, WindowSize},
+    grid::Dimensions,
+    index::Line,
+    term::{Term, TermMode},
+    vte::ansi::Handler,
+};
+use anyhow::{Context as _, Result, bail};
+use base64::{
+    Engine as _, alphabet,
+    engine::{DecodePaddingMode, GeneralPurpose, GeneralPurposeConfig},
+};
+use flate2::read::ZlibDecoder;
+use gpui::{RenderImage, hsla};
+use image::{DynamicImage, Frame, ImageReader, Limits, RgbImage, RgbaImage};
+use util::ResultExt as _;
+
+use crate::shell_integration::AbsolutePoint;
+
+/// Image sequences longer than this are ignored.
+pub(crate) const MAX_IMAGE_LEN: usize = 128 << 20;
+/// Images wider or taller than this, in pixels, are ignored.
+const MAX_IMAGE_SIDE: u32 = 8192;
+/// The number of images kept for each terminal, after which the oldest are dropped.
+const MAX_IMAGES: usize = 256;
+/// The number of images kitty programs can transmit to place later.
+const MAX_STORED_IMAGES: usize = 64;
+
+const BASE64: GeneralPurpose = GeneralPurpose::new(
+    &alphabet::STANDARD,
+    GeneralPurposeConfig::new().with_decode_padding_mode(DecodePaddingMode::Indifferent),
+);
+
+/// The payload of a sequence carrying an image.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub(crate) enum ImageSequence {
+    /// `APC G <control data> ; <payload> ST`, without the `G`.
+    Kitty(Vec<u8>),
+    /// `OSC 1337 ; File = <arguments> : <payload> ST`, without the `1337;File=`.
+    ITerm(Vec<u8>),
+    /// `DCS <parameters> q <sixels> ST`.
+    Sixel(Vec<u8>),
+}
+
+impl ImageSequence {
+    pub fn is_iterm(osc: &[u8]) -> bool {
+        osc.starts_with(b"1337;File=")
+    }
+
+    pub fn is_kitty(apc: &[u8]) -> bool {
+        apc.starts_with(b"G")
+    }
+
+    pub fn is_sixel(dcs: &[u8]) -> bool {
+        dcs.iter()
+            .find(|byte| !matches!(byte, b'0'..=b'9' | b';'))
+            .is_none_or(|&byte| byte == b'q')
+    }
+}
+
+#[derive(Clone)]
+struct DecodedImage {
+    image: Arc<RenderImage>,
+    width: u32,
+    height: u32,
+}
+
+impl DecodedImage {
+    fn new(mut pixels: RgbaImage) -> Self {
+        let (width, height) = pixels.dimensions();
+        // Convert from RGBA to BGRA.
+        for pixel in pixels.chunks_exact_mut(4) {
+            pixel.swap(0, 2);
+        }
+        Self {
+            image: Arc::new(RenderImage::new(vec![Frame::new(pixels)])),
+            width,
+            height,
+        }
+    }
+
+    fn load(bytes: &[u8]) -> Result<Self> {
+        let mut reader = ImageReader::new(std::io::Cursor::new(bytes)).with_guessed_format()?;
+        let mut limits = Limits::default();
+        limits.max_image_width = Some(MAX_IMAGE_SIDE);
+        limits.max_image_height = Some(MAX_IMAGE_SIDE);
+        reader.limits(limits);
+        Ok(Self::new(reader.decode()?.into_rgba8()))
+    }
+}
+
+fn check_size(width: u32, height: u32) -> Result<()> {
+    if width == 0 || height == 0 || width > MAX_IMAGE_SIDE || height > MAX_IMAGE_SIDE {
+        bail!("invalid image size {width}x{height}");
+    }
+    Ok(())
+}
+
+/// How much space an image takes in one direction.
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum Dimension {
+    /// The image's own size.
+    Auto,
+    Cells(usize),
+    Pixels(u32),
+    /// A percentage of the terminal's size.
+    Percent(u32),
+}
+
+impl Dimension {
+    fn parse(value: &str) -> Self {
+        let parsed = if let Some(pixels) = value.strip_suffix("px") {
+            pixels.parse().map(Self::Pixels)
+        } else if let Some(percent) = value.strip_suffix('%') {
+            percent.parse().map(Self::Percent)
+        } else {
+            value.parse().map(Self::Cells)
+        };
+        parsed.unwrap_or(Self::Auto)
+    }
+
+    fn to_pixels(self, cell_size: f32, cells: usize) -> Option<f32> {
+        match self {
+            Self::Auto => None,
+            Self::Cells(count) => Some(count as f32 * cell_size),
+            Self::Pixels(pixels) => Some(pixels as f32),
+            Self::Percent(percent) => Some(percent as f32 / 100. * cells as f32 * cell_size),
+        }
+    }
+}
+
+/// Where the cursor goes once an image has been placed.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum CursorMovement {
+    /// Right after the image, on its last line.
+    PastImage,
+    /// To the line below the image.
+    NextLine,
+    Stay,
+}
+
+pub(crate) struct ImagePlacement {
+    image: DecodedImage,
+    width: Dimension,
+    height: Dimension,
+    preserve_aspect_ratio: bool,
+    cursor: CursorMovement,
+    kitty_id: Option<u32>,
+}
+
+impl ImagePlacement {
+    /// Places the image at the cursor, and moves the cursor past it.
+    pub fn place<T: EventListener>(
+        self,
+        term: &mut Term<T>,
+        window_size: WindowSize,
+    ) -> TerminalImage {
+        let cell_width = window_size.cell_width.max(1) as f32;
+        let cell_height = window_size.cell_height.max(1) as f32;
+        let cursor = term.grid().cursor.point;
+        let image_width = self.image.width as f32;
+        let image_height = self.image.height as f32;
+
+        let width = self.width.to_pixels(cell_width, term.columns());
+        let height = self.height.to_pixels(cell_height, term.screen_lines());
+        let preserve_aspect_ratio = self.preserve_aspect_ratio;
+        let (mut width, mut height) = match (width, height) {
+            (None, None) => (image_width, image_height),
+            (Some(width), None) if preserve_aspect_ratio => {
+                (width, width * image_height / image_width)
+            }
+            (None, Some(height)) if preserve_aspect_ratio => {
+                (height * image_width / image_height, height)
+            }
+            (width, height) => (width.unwrap_or(image_width), height.unwrap_or(image_height)),
+        };
+
+        // Images wider than the space right of the cursor are scaled down to fit in it.
+        let available_columns = term.columns() - cursor.column.0;
+        let available_width = available_columns as f32 * cell_width;
+        if width > available_width {
+            if preserve_aspect_ratio {
+                height *= available_width / width;
+            }
+            width = available_width;
+        }
+        let columns = ((width / cell_width).ceil() as usize).clamp(1, available_columns);
+        let rows = ((height / cell_height).ceil() as usize).max(1);
+
+        let image = TerminalImage {
+            image: self.image.image,
+            width: self.image.width,
+            height: self.image.height,
+            origin: AbsolutePoint::from_grid(cursor, term),
+            columns,
+            rows,
+            stretch: !preserve_aspect_ratio,
+            alt_screen: term.mode().contains(TermMode::ALT_SCREEN),
+            kitty_id: self.kitty_id,
+        };
+
+        match self.cursor {
+            CursorMovement::PastImage => {
+                for _ in 1..rows {
+                    term.linefeed();
+                }
+                term.goto_col(cursor.column.0 + columns);
+            }
+            CursorMovement::NextLine => {
+                for _ in 0..rows {
+                    term.linefeed();
+                }
+                term.goto_col(cursor.column.0);
+            }
+            CursorMovement::Stay => {}
+        }
+        image
+    }
+}
+
+pub(crate) enum ImageAction {
+    Place(ImagePlacement),
+    /// Deletes the images placed with the given kitty image id, or all of them.
+    Delete {
+        kitty_id: Option<u32>,
+    },
+}
+
+/// An image placed in the terminal.
+#[derive(Clone)]
+pub struct TerminalImage {
+    pub image: Arc<RenderImage>,
+    /// The image's size in pixels.
+    pub width: u32,
+    pub height: u32,
+    /// The cell the image's top left corner is in.
+    pub origin: AbsolutePoint,
+    /// The number of cells the image covers.
+    pub columns: usize,
+    pub rows: usize,
+    /// Whether the image is stretched over its cells, rather than fit in them.
+    pub stretch: bool,
+    alt_screen: bool,
+    kitty_id: Option<u32>,
+}
+
+/// The images placed in a terminal, oldest first.
+#[derive(Default)]
+pub(crate) struct TerminalImages {
+    images: VecDeque<TerminalImage>,
+}
+
+impl TerminalImages {
+    pub fn push(&mut self, image: TerminalImage) {
+        if self.images.len() == MAX_IMAGES {
+            self.images.pop_front();
+        }
+        self.images.push_back(image);
+    }
+
+    pub fn delete(&mut self, kitty_id: Option<u32>) {
+        match kitty_id {
+            Some(id) => self.images.retain(|image| image.kitty_id != Some(id)),
+            None => self.images.clear(),
+        }
+    }
+
+    pub fn clear(&mut self) {
+        self.images.clear();
+    }
+
+    /// Drops the images placed on the alternate screen, which are gone once it's left.
+    pub fn retain_main_screen(&mut self) {
+        self.images.retain(|image| !image.alt_screen);
+    }
+
+    /// Moves the images down by `lines`, or up if it's negative, after content has been
+    /// inserted or removed above them.
+    pub fn offset_lines(&mut self, lines: isize) {
+        for image in &mut self.images {
+            image.origin.line = image.origin.line.saturating_add_signed(lines);
+        }
+    }
+
+    /// The images shown in the viewport, with the grid line of their top.
+    pub fn visible<T>(&self, term: &Term<T>) -> Vec<(Line, TerminalImage)> {
+        let alt_screen = term.mode().contains(TermMode::ALT_SCREEN);
+        let viewport_top = -(term.grid().display_offset() as i32);
+        let viewport_bottom = viewport_top + term.screen_lines() as i32;
+        self.images
+            .iter()
+            .filter(|image| image.alt_screen == alt_screen)
+            .filter_map(|image| {
+                let top = image.origin.line as i32 - term.history_size() as i32;
+                (top < viewport_bottom && top + image.rows as i32 > viewport_top)
+                    .then(|| (Line(top), image.clone()))
+            })
+            .collect()
+    }
+}
+
+/// Decodes image sequences, keeping the state kitty's protocol needs across them.
+#[derive(Default)]
+pub(crate) struct ImageDecoder {
+    /// The first chunk of a kitty image sent in chunks, and the payload received so far.
+    kitty_chunks: Option<(KittyCommand, Vec<u8>)>,
+    /// The images kitty programs transmitted with an id, oldest first.
+    kitty_images: VecDeque<(u32, DecodedImage)>,
+}
+
+impl ImageDecoder {
+    /// Decodes an image sequence, calling `reply` with the response to send to the program
+    /// if there's one.
+    pub fn decode(
+        &mut self,
+        sequence: ImageSequence,
+        reply: &mut dyn FnMut(&[u8]),
+    ) -> Option<ImageAction> {
+        match sequence {
+            ImageSequence::Kitty(data) => self.decode_kitty(&data, reply),
+            ImageSequence::ITerm(data) => decode_iterm(&data).log_err().flatten(),
+            ImageSequence::Sixel(data) => {
+                let image = decode_sixel(&data).log_err()?;
+                Some(ImageAction::Place(ImagePlacement {
+                    image,
+                    width: Dimension::Auto,
+                    height: Dimension::Auto,
+                    preserve_aspect_ratio: true,
+                    cursor: CursorMovement::NextLine,
+                    kitty_id: None,
+                }))
+            }
+        }
+    }
+
+    fn decode_kitty(&mut self, data: &[u8], reply: &mut dyn FnMut(&[u8])) -> Option<ImageAction> {
+        let (control, payload) = match data.iter().position(|&byte| byte == b';') {
+            Some(ix) => (&data[..ix], &data[ix + 1..]),
+            None => (data, &[][..]),
+        };
+        let chunk = KittyCommand::parse(control);
+        // The chunks after the first only say whether more follow.
+        let (command, payload) = match self.kitty_chunks.take() {
+            Some((command, mut chunks)) => {
+                chunks.extend_from_slice(payload);
+                (
+                    KittyCommand {
+                        more: chunk.more,
+                        ..command
+                    },
+                    chunks,
+                )
+            }
+            None => (chunk, payload.to_vec()),
+        };
+        if command.more {
+            if payload.len() < MAX_IMAGE_LEN {
+                self.kitty_chunks = Some((command, payload));
+            }
+            return None;
+        }
+
+        match command.action {
+            b't' | b'T' | b'q' => {
+                let image = command.decode(&payload);
+                command.reply(&image, reply);
+                let image = image.log_err()?;
+                if command.action == b'q' {
+                    return None;
+                }
+                if command.id != 0 {
+                    self.kitty_images.retain(|(id, _)| *id != command.id);
+                    if self.kitty_images.len() == MAX_STORED_IMAGES {
+                        self.kitty_images.pop_front();
+                    }
+                    self.kitty_images.push_back((command.id, image.clone()));
+                }
+                (command.action == b'T').then(|| ImageAction::Place(command.placement(image)))
+            }
+            b'p' => {
+                let image = self
+                    .kitty_images
+                    .iter()
+                    .find(|(id, _)| *id == command.id)
+                    .map(|(_, image)| image.clone())
+                    .context("no image with this id");
+                command.reply(&image, reply);
+                Some(ImageAction::Place(command.placement(image.ok()?)))
+            }
+            b'd' => {
+                let kitty_id = matches!(command.delete, b'i' | b'I').then_some(command.id);
+                // Upper case deletions also free the images' data.
+                match command.delete {
+                    b'A' => self.kitty_images.clear(),
+                    b'I' => self.kitty_images.retain(|(id, _)| *id != command.id),
+                    _ => {}
+                }
+                Some(ImageAction::Delete { kitty_id })
+            }
+            _ => None,
+        }
+    }
+}
+
+/// The control data of a kitty graphics command.
+#[derive(Clone, Debug, PartialEq, Eq)]
+struct KittyCommand {
+    action: u8,
+    format: u32,
+    medium: u8,
+    compressed: bool,
+    width: u32,
+    height: u32,
+    id: u32,
+    more: bool,
+    columns: usize,
+    rows: usize,
+    keep_cursor: bool,
+    quiet: u32,
+    delete: u8,
+}
+
+impl KittyCommand {
+    fn parse(control: &[u8]) -> Self {
+        let mut command = Self {
+            action: b't',
+            format: 32,
+            medium: b'd',
+            compressed: false,
+            width: 0,
+            height: 0,
+            id: 0,
+            more: false,
+            columns: 0,
+            rows: 0,
+            keep_cursor: false,
+            quiet: 0,
+            delete: b'a',
+        };
+        for pair in control.split(|&byte| byte == b',') {
+            let [key, b'=', value @ ..] = pair else {
+                continue;
+            };
+            let number = std::str::from_utf8(value)
+                .ok()
+                .and_then(|value| value.parse::<u32>().ok());
+            let byte = value.first().copied();
+            match key {
+                b'a' => command.action = byte.unwrap_or(command.action),
+                b'f' => command.format = number.unwrap_or(command.format),
+                b't' => command.medium = byte.unwrap_or(command.medium),
+                b'o' => command.compressed = value == b"z",
+                b's' => command.width = number.unwrap_or(0),
+                b'v' => command.height = number.unwrap_or(0),
+                b'i' => command.id = number.unwrap_or(0),
+                b'm' => command.more = number == Some(1),
+                b'c' => command.columns = number.unwrap_or(0) as usize,
+                b'r' => command.rows = number.unwrap_or(0) as usize,
+                b'C' => command.keep_cursor = number == Some(1),
+                b'q' => command.quiet = number.unwrap_or(0),
+                b'd' => command.delete = byte.unwrap_or(command.delete),
+                _ => {}
+            }
+        }
+        command
+    }
+
+    fn decode(&self, payload: &[u8]) -> Result<DecodedImage> {
+        if self.medium != b'd' {
+            bail!("only direct transmission is supported");
+        }
+        let mut bytes = BASE64.decode(payload)?;
+        if self.compressed {
+            let mut inflated = Vec::new();
+            ZlibDecoder::new(bytes.as_slice())
+                .take(MAX_IMAGE_LEN as u64)
+                .read_to_end(&mut inflated)?;
+            bytes = inflated;
+        }
+
+        match self.format {
+            100 => DecodedImage::load(&bytes),
+            24 | 32 => {
+                check_size(self.width, self.height)?;
+                let channels = if self.format == 24 { 3 } else { 4 };
+                let len = self.width as usize * self.height as usize * channels;
+                if bytes.len() < len {
+                    bail!("expected {len} bytes of pixel data, got {}", bytes.len());
+                }
+                bytes.truncate(len);
+                let pixels = if self.format == 24 {
+                    RgbImage::from_raw(self.width, self.height, bytes)
+                        .map(|pixels| DynamicImage::ImageRgb8(pixels).into_rgba8())
+                } else {
+                    RgbaImage::from_raw(self.width, self.height, bytes)
+                };
+                Ok(DecodedImage::new(pixels.context("invalid pixel data")?))
+            }
+            format => bail!("unsupported format {format}"),
+        }
+    }
+
+    /// Replies to the program, if it gave the image an id and didn't ask for quiet.
+    fn reply<T>(&self, result: &Result<T>, reply: &mut dyn FnMut(&[u8])) {
+        if self.id == 0 {
+            return;
+        }
+        let message = match result {
+            Ok(_) if self.quiet == 0 => "OK".to_string(),
+            Err(error) if self.quiet < 2 => {
+                format!("EINVAL:{error}").replace(|c: char| c.is_control(), " ")
+            }
+            _ => return,
+        };
+        reply(format!("\x1b_Gi={};{message}\x1b\\", self.id).as_bytes());
+    }
+
+    fn placement(&self, image: DecodedImage) -> ImagePlacement {
+        let dimension = |cells| match cells {
+            0 => Dimension::Auto,
+            cells => Dimension::Cells(cells),
+        };
+        ImagePlacement {
+            image,
+            width: dimension(self.columns),
+            height: dimension(self.rows),
+            // Images are only stretched when given both a width and a height.
+            preserve_aspect_ratio: self.columns == 0 || self.rows == 0,
+            cursor: if self.keep_cursor {
+                CursorMovement::Stay
+            } else {
+                CursorMovement::PastImage
+            },
+            kitty_id: (self.id != 0).then_some(self.id),
+        }
+    }
+}
+
+/// Decodes an iTerm2 inline image, or returns `None` if it's a file download, which isn't
+/// supported.
+fn decode_iterm(data: &[u8]) -> Result<Option<ImageAction>> {
+    let colon = data
+        .iter()
+        .position(|&byte| byte == b':')
+        .context("missing image data")?;
+    let arguments = std::str::from_utf8(&data[..colon])?;
+    let mut inline = false;
+    let mut width = Dimension::Auto;
+    let mut height = Dimension::Auto;
+    let mut preserve_aspect_ratio = true;
+    for argument in arguments.split(';') {
+        let Some((key, value)) = argument.split_once('=') else {
+            continue;
+        };
+        match key {
+            "inline" => inline = value == "1",
+            "width" => width = Dimension::parse(value),
+            "height" => height = Dimension::parse(value),
+            "preserveAspectRatio" => preserve_aspect_ratio = value != "0",
+            _ => {}
+        }
+    }
+    if !inline {
+        return Ok(None);
+    }
+
+    let payload = data[colon + 1..]
+        .iter()
+        .copied()
+        .filter(|byte| !byte.is_ascii_whitespace())
+        .collect::<Vec<_>>();
+    let image = DecodedImage::load(&BASE64.decode(payload)?)?;
+    Ok(Some(ImageAction::Place(ImagePlacement {
+        image,
+        width,
+        height,
+        preserve_aspect_ratio,
+        cursor: CursorMovement::PastImage,
+        kitty_id: None,
+    })))
+}
+
+/// The colors sixel images start with, those of the VT340, in percents.
+const SIXEL_PALETTE: [[u32; 3]; 16] = [
+    [0, 0, 0],
+    [20, 20, 80],
+    [80, 13, 13],
+    [20, 80, 20],
+    [80, 20, 80],
+    [20, 80, 80],
+    [80, 80, 20],
+    [53, 53, 53],
+    [26, 26, 26],
+    [33, 33, 60],
+    [60, 26, 26],
+    [33, 60, 33],
+    [60, 33, 60],
+    [33, 60, 60],
+    [60, 60, 33],
+    [80, 80, 80],
+];
+
+enum SixelCommand {
+    Raster(Vec<u32>),
+    Color(Vec<u32>),
+    Draw { sixel: u8, count: u32 },
+    CarriageReturn,
+    NewLine,
+}
+
+fn parse_sixel_commands(data: &[u8]) -> Vec<SixelCommand> {
+    fn numbers(bytes: &mut std::iter::Peekable<impl Iterator<Item = u8>>) -> Vec<u32> {
+        let mut numbers = vec![0u32];
+        while let Some(&byte) = bytes.peek() {
+            match byte {
+                b'0'..=b'9' => {
+                    let last = numbers.last_mut().unwrap();
+                    *last = last.saturating_mul(10).saturating_add((byte - b'0') as u32);
+                }
+                b';' => numbers.push(0),
+                _ => break,
+            }
+            bytes.next();
+        }
+        numbers
+    }
+
+    let mut commands = Vec::new();
+    let mut bytes = data.iter().copied().peekable();
+    while let Some(byte) = bytes.next() {
+        match byte {
+            b'"' => commands.push(SixelCommand::Raster(numbers(&mut bytes))),
+            b'#' => commands.push(SixelCommand::Color(numbers(&mut bytes))),
+            b'!' => {
+                let count = numbers(&mut bytes)[0].clamp(1, MAX_IMAGE_SIDE);
+                if let Some(sixel @ b'?'..=b'~') = bytes.next() {
+                    commands.push(SixelCommand::Draw { sixel, count });
+                }
+            }
+            b'$' => commands.push(SixelCommand::CarriageReturn),
+            b'-' => commands.push(SixelCommand::NewLine),
+            b'?'..=b'~' => commands.push(SixelCommand::Draw {
+                sixel: byte,
+                count: 1,
+            }),
+            _ => {}
+        }
+    }
+    commands
+}
+
+fn decode_sixel(data: &[u8]) -> Result<DecodedImage> {
+    let start = data
+        .iter()
+        .position(|&byte| byte == b'q')
+        .context("missing sixel data")?;
+    let commands = parse_sixel_commands(&data[start + 1..]);
+
+    // Size the image from the sixels drawn, since the raster attributes are optional.
+    let (mut x, mut y, mut width, mut height) = (0u32, 0u32, 0u32, 0u32);
+    for command in &commands {
+        match command {
+            SixelCommand::Draw { count, .. } => {
+                x = x.saturating_add(*count);
+                width = width.max(x);
+                height = height.max(y + 6);
+            }
+            SixelCommand::CarriageReturn => x = 0,
+            SixelCommand::NewLine => {
+                x = 0;
+                y = y.saturating_add(6);
+            }
+            SixelCommand::Raster(_) | SixelCommand::Color(_) => {}
+        }
+    }
+    if let Some(SixelCommand::Raster(raster)) = commands.first()
+        && let [_, _, raster_width, raster_height] = raster[..]
+        && raster_width > 0
+        && raster_height > 0
+    {
+        width = width.min(raster_width);
+        height = height.min(raster_height);
+    }
+    check_size(width, height)?;
+
+    let percent = |value: u32| (value.min(100) * 255 / 100) as u8;
+    let mut palette = [[0, 0, 0, 255]; 256];
+    for (color, [r, g, b]) in palette.iter_mut().zip(SIXEL_PALETTE) {
+        *color = [percent(r), percent(g), percent(b), 255];
+    }
+
+    let mut pixels = RgbaImage::new(width, height);
+    let (mut x, mut y, mut color) = (0u32, 0u32, 0usize);
+    for command in commands {
+        match command {
+            SixelCommand::Color(parameters) => match parameters[..] {
+                [index] => color = index.min(255) as usize,
+                [index, 1, hue, lightness, saturation] => {
+                    color = index.min(255) as usize;
+                    // Sixel hues start from blue rather than red.
+                    let rgba = hsla(
+                        ((hue + 240) % 360) as f32 / 360.,
+                        saturation.min(100) as f32 / 100.,
+                        lightness.min(100) as f32 / 100.,
+                        1.,
+                    )
+                    .to_rgb();
+                    palette[color] = [
+                        (rgba.r * 255.) as u8,
+                        (rgba.g * 255.) as u8,
+                        (rgba.b * 255.) as u8,
+                        255,
+                    ];
+                }
+                [index, 2, r, g, b] => {
+                    color = index.min(255) as usize;
+                    palette[color] = [percent(r), percent(g), percent(b), 255];
+                }
+                _ => {}
+            },
+            SixelCommand::Draw { sixel, count } => {
+                let bits = sixel - b'?';
+                for column in x..x.saturating_add(count).min(width) {
+                    for bit in 0..6 {
+                        if bits & (1 << bit) != 0 && y + bit < height {
+                            pixels.put_pixel(column, y + bit, image::Rgba(palette[color]));
+                        }
+                    }
+                }
+                x = x.saturating_add(count);
+            }
+            SixelCommand::CarriageReturn => x = 0,
+            SixelCommand::NewLine => {
+                x = 0;
+                y = y.saturating_add(6);
+            }
+            SixelCommand::Raster(_) => {}
+        }
+    }
+    Ok(DecodedImage::new(pixels))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_kitty_chunks() {
+        let mut decoder = ImageDecoder::default();
+        let mut replies = Vec::new();
+        let mut reply = |bytes: &[u8]| replies.push(String::from_utf8_lossy(bytes).into_owned());
+
+        // A 1x2 RGB image, sent in two chunks.
+        let payload = BASE64.encode([255, 0, 0, 0, 0, 255]);
+        let (first, second) = payload.split_at(4);
+        let first_chunk = format!("a=T,f=24,s=1,v=2,i=7,m=1;{first}");
+        let second_chunk = format!("m=0;{second}");
+        assert!(
+            decoder
+                .decode(ImageSequence::Kitty(first_chunk.into_bytes()), &mut reply)
+                .is_none()
+        );
+        let Some(ImageAction::Place(placement)) =
+            decoder.decode(ImageSequence::Kitty(second_chunk.into_bytes()), &mut reply)
+        else {
+            panic!("expected the image to be placed");
+        };
+        assert_eq!((placement.image.width, placement.image.height), (1, 2));
+        assert_eq!(placement.kitty_id, Some(7));
+        assert_eq!(
+            placement.image.image.as_bytes(0),
+            Some(&[0, 0, 255, 255, 255, 0, 0, 255][..])
+        );
+
+        // The image can be placed again by its id.
+        assert!(matches!(
+            decoder.decode(ImageSequence::Kitty(b"a=p,i=7".to_vec()), &mut reply),
+            Some(ImageAction::Place(_))
+        ));
+        assert!(
+            decoder
+                .decode(
+                    ImageSequence::Kitty(b"a=q,i=8,s=1,v=1;AAAA".to_vec()),
+                    &mut reply
+                )
+                .is_none()
+        );
+        assert_eq!(
+            replies,
+            [
+                "\x1b_Gi=7;OK\x1b\\",
+                "\x1b_Gi=7;OK\x1b\\",
+                "\x1b_Gi=8;EINVAL:expected 4 bytes of pixel data, got 3\x1b\\"
+            ]
+        );
+    }
+
+    #[test]
+    fn test_decode_sixel() {
+        // Two columns, the first red and 6 pixels high, the second blue and 1 pixel high,
+        // repeated, then a new line of one green pixel.
+        let image = decode_sixel(b"0;1;0q#1;2;100;0;0#2;2;0;0;100#1~#2@$-#3;2;0;100;0@").unwrap();
+        assert_eq!((image.width, image.height), (2, 12));
+        let pixel = |x: u32, y: u32| {
+            let bytes = image.image.as_bytes(0).unwrap();
+            let ix = (y * image.width + x) as usize * 4;
+            bytes[ix..ix + 4].to_vec()
+        };
+        assert_eq!(pixel(0, 5), [0, 0, 255, 255]);
+        assert_eq!(pixel(1, 0), [255, 0, 0, 255]);
+        assert_eq!(pixel(1, 1), [0, 0, 0, 0]);
+        assert_eq!(pixel(0, 6), [0, 255, 0, 255]);
+    }
+}
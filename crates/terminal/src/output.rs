@@ -0,0 +1,433 @@
+//! Handles the escape sequences alacritty ignores in the program's output: the shell
+//! integration marks, the sequences carrying images, and the ones that erase the
+//! scrollback, which takes the images in it along.
+
+use std::{sync::Arc, time::Instant};
+
+use alacritty_terminal::{
+    event::{Event as AlacTermEvent, EventListener, WindowSize},
+    sync::FairMutex,
+    term::Term,
+    vte::ansi::{Processor, StdSyncHandler},
+};
+use futures::FutureExt as _;
+use gpui::{BackgroundExecutor, Task};
+use parking_lot::Mutex;
+use util::ResultExt as _;
+
+use crate::{
+    ZedListener,
+    asciicast::Recording,
+    images::{ImageAction, ImageDecoder, ImageSequence, MAX_IMAGE_LEN, TerminalImage},
+    pty_event_loop::{PtyOutput, PtySender},
+    shell_integration::{MarkKind, ShellMark},
+    task_output::{MatchedProblems, TaskOutputMatcher},
+};
+
+const ESC: u8 = 0x1b;
+const BEL: u8 = 0x07;
+/// Strings longer than this aren't buffered, unless they carry images.
+const MAX_MARK_LEN: usize = 128;
+/// CSI sequences longer than this aren't buffered.
+const MAX_CSI_LEN: usize = 16;
+
+/// An intercepted sequence.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub(crate) enum Sequence {
+    Mark(MarkKind),
+    Image(ImageSequence),
+    /// `CSI 3 J` or `ESC c`, which erase the scrollback.
+    EraseScrollback,
+}
+
+impl Sequence {
+    /// Decodes the sequence, which takes a while for images, so is done before locking the
+    /// terminal to record it. `reply` is called with the response to send to the program,
+    /// if there's one.
+    pub fn decode(
+        self,
+        images: &mut ImageDecoder,
+        reply: &mut dyn FnMut(&[u8]),
+    ) -> Option<Decoded> {
+        match self {
+            Self::Mark(kind) => Some(Decoded::Mark(kind)),
+            Self::Image(image) => images.decode(image, reply).map(Decoded::Image),
+            Self::EraseScrollback => Some(Decoded::Image(ImageAction::Delete { kitty_id: None })),
+        }
+    }
+}
+
+pub(crate) enum Decoded {
+    Mark(MarkKind),
+    Image(ImageAction),
+}
+
+impl Decoded {
+    /// Records the sequence at the cursor, once the output before it has been parsed.
+    pub fn record<T: EventListener>(self, term: &mut Term<T>, window_size: WindowSize) -> Recorded {
+        match self {
+            Self::Mark(kind) => Recorded::Mark(ShellMark::at_cursor(kind, term)),
+            Self::Image(ImageAction::Place(placement)) => {
+                Recorded::Image(placement.place(term, window_size))
+            }
+            Self::Image(ImageAction::Delete { kitty_id }) => Recorded::DeleteImages { kitty_id },
+        }
+    }
+}
+
+/// A sequence recorded in the terminal, to be handled by the [`crate::Terminal`].
+pub(crate) enum Recorded {
+    Mark(ShellMark),
+    Image(TerminalImage),
+    /// Deletes the images placed with the given kitty image id, or all of them.
+    DeleteImages {
+        kitty_id: Option<u32>,
+    },
+    TaskProblems(MatchedProblems),
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum StringKind {
+    Osc,
+    Apc,
+    Dcs,
+}
+
+#[derive(Clone, Debug, Default)]
+enum ScanState {
+    #[default]
+    Ground,
+    Escape,
+    Csi(Vec<u8>),
+    String {
+        kind: StringKind,
+        data: Vec<u8>,
+        /// Whether the string was longer than what's buffered of it.
+        truncated: bool,
+        /// Whether the last byte was an `ESC`, which may start the string terminator.
+        escape: bool,
+    },
+}
+
+/// Finds the intercepted sequences in a stream of output, which may split them across reads.
+#[derive(Debug, Default)]
+pub(crate) struct SequenceScanner {
+    state: ScanState,
+}
+
+impl SequenceScanner {
+    /// Scans `bytes` up to the first intercepted sequence in them, returning the sequence
+    /// and the offset of the byte after it.
+    pub fn scan(&mut self, bytes: &[u8]) -> Option<(usize, Sequence)> {
+        for (ix, &byte) in bytes.iter().enumerate() {
+            if let Some(sequence) = self.advance(byte) {
+                return Some((ix + 1, sequence));
+            }
+        }
+        None
+    }
+
+    fn advance(&mut self, byte: u8) -> Option<Sequence> {
+        let start_string = |kind| ScanState::String {
+            kind,
+            data: Vec::new(),
+            truncated: false,
+            escape: false,
+        };
+        match (&mut self.state, byte) {
+            (ScanState::Ground, ESC) => self.state = ScanState::Escape,
+            (ScanState::Ground, _) => {}
+            (ScanState::Escape, ESC) => {}
+            (ScanState::Escape, b']') => self.state = start_string(StringKind::Osc),
+            (ScanState::Escape, b'_') => self.state = start_string(StringKind::Apc),
+            (ScanState::Escape, b'P') => self.state = start_string(StringKind::Dcs),
+            (ScanState::Escape, b'[') => self.state = ScanState::Csi(Vec::new()),
+            (ScanState::Escape, b'c') => {
+                self.state = ScanState::Ground;
+                return Some(Sequence::EraseScrollback);
+            }
+            (ScanState::Escape, _) => self.state = ScanState::Ground,
+            (ScanState::Csi(_), ESC) => self.state = ScanState::Escape,
+            (ScanState::Csi(parameters), 0x40..=0x7e) => {
+                let erases_scrollback = parameters.as_slice() == b"3" && byte == b'J';
+                self.state = ScanState::Ground;
+                if erases_scrollback {
+                    return Some(Sequence::EraseScrollback);
+                }
+            }
+            (ScanState::Csi(parameters), _) => {
+                if parameters.len() < MAX_CSI_LEN {
+                    parameters.push(byte);
+                }
+            }
+            (
+                ScanState::String {
+                    kind,
+                    data,
+                    truncated,
+                    escape: true,
+                },
+                b'\\',
+            ) => {
+                let (kind, data, truncated) = (*kind, std::mem::take(data), *truncated);
+                self.state = ScanState::Ground;
+                if !truncated {
+                    return Self::finish_string(kind, data);
+                }
+            }
+            (ScanState::String { escape: true, .. }, _) => {
+                // Anything but a string terminator aborts the string.
+                self.state = ScanState::Escape;
+                return self.advance(byte);
+            }
+            (ScanState::String { escape, .. }, ESC) => *escape = true,
+            (
+                ScanState::String {
+                    kind: StringKind::Osc,
+                    data,
+                    truncated,
+                    ..
+                },
+                BEL,
+            ) => {
+                let (data, truncated) = (std::mem::take(data), *truncated);
+                self.state = ScanState::Ground;
+                if !truncated {
+                    return Self::finish_string(StringKind::Osc, data);
+                }
+            }
+            (
+                ScanState::String {
+                    kind,
+                    data,
+                    truncated,
+                    ..
+                },
+                _,
+            ) => {
+                let max_len = match kind {
+                    _ if data.len() < MAX_MARK_LEN => MAX_MARK_LEN,
+                    StringKind::Osc if ImageSequence::is_iterm(data) => MAX_IMAGE_LEN,
+                    StringKind::Apc if ImageSequence::is_kitty(data) => MAX_IMAGE_LEN,
+                    StringKind::Dcs if ImageSequence::is_sixel(data) => MAX_IMAGE_LEN,
+                    _ => MAX_MARK_LEN,
+                };
+                if data.len() < max_len {
+                    data.push(byte);
+                } else {
+                    *truncated = true;
+                }
+            }
+        }
+        None
+    }
+
+    fn finish_string(kind: StringKind, data: Vec<u8>) -> Option<Sequence> {
+        match kind {
+            StringKind::Osc => {
+                if let Some(kind) = MarkKind::parse(&data) {
+                    Some(Sequence::Mark(kind))
+                } else if ImageSequence::is_iterm(&data) {
+                    let arguments = data[b"1337;File=".len()..].to_vec();
+                    Some(Sequence::Image(ImageSequence::ITerm(arguments)))
+                } else {
+                    None
+                }
+            }
+            StringKind::Apc if ImageSequence::is_kitty(&data) => {
+                Some(Sequence::Image(ImageSequence::Kitty(data[1..].to_vec())))
+            }
+            StringKind::Dcs if ImageSequence::is_sixel(&data) => {
+                Some(Sequence::Image(ImageSequence::Sixel(data)))
+            }
+            StringKind::Apc | StringKind::Dcs => None,
+        }
+    }
+}
+
+/// Parses the program's output into the terminal, recording the intercepted sequences in it
+/// along the way. The output is also written to the session's recording while there's one,
+/// and its problems matched while a task with problem matchers is running.
+pub(crate) struct OutputProcessor {
+    parser: Processor<StdSyncHandler>,
+    scanner: SequenceScanner,
+    images: ImageDecoder,
+    recording: Arc<Mutex<Option<Recording>>>,
+    task_output: Arc<Mutex<Option<TaskOutputMatcher>>>,
+    window_size: Arc<Mutex<WindowSize>>,
+}
+
+impl OutputProcessor {
+    pub fn new(
+        recording: Arc<Mutex<Option<Recording>>>,
+        task_output: Arc<Mutex<Option<TaskOutputMatcher>>>,
+        window_size: Arc<Mutex<WindowSize>>,
+    ) -> Self {
+        Self {
+            parser: Processor::new(),
+            scanner: SequenceScanner::default(),
+            images: ImageDecoder::default(),
+            recording,
+            task_output,
+            window_size,
+        }
+    }
+
+    /// Parses `output` into the terminal, returning what was recorded along the way.
+    /// `reply` is called with the responses to send to the program.
+    ///
+    /// The terminal is only locked while parsing, not while decoding images.
+    pub fn process<T: EventListener>(
+        &mut self,
+        output: &[u8],
+        term: &FairMutex<Term<T>>,
+        reply: &mut dyn FnMut(&[u8]),
+    ) -> Vec<Recorded> {
+        let mut recorded = Vec::new();
+        if let Some(recording) = self.recording.lock().as_mut() {
+            recording.output(output).log_err();
+        }
+        if let Some(task_output) = self.task_output.lock().as_mut() {
+            let matched = task_output.advance(output);
+            if !matched.is_empty() {
+                recorded.push(Recorded::TaskProblems(matched));
+            }
+        }
+
+        let mut output = output;
+        while let Some((end, sequence)) = self.scanner.scan(output) {
+            self.parser.advance(&mut *term.lock(), &output[..end]);
+            output = &output[end..];
+            if let Some(decoded) = sequence.decode(&mut self.images, reply) {
+                let mut term = term.lock();
+                recorded.push(decoded.record(&mut term, *self.window_size.lock()));
+            }
+        }
+        self.parser.advance(&mut *term.lock(), output);
+        recorded
+    }
+
+    /// Processes the output the PTY event loop passes on, in the background, until the loop
+    /// stops. What's recorded is pushed to `recorded` before waking the terminal up.
+    pub fn spawn_for_pty(
+        mut self,
+        output: smol::channel::Receiver<PtyOutput>,
+        term: Arc<FairMutex<Term<ZedListener>>>,
+        recorded: Arc<Mutex<Vec<Recorded>>>,
+        pty_tx: PtySender,
+        listener: ZedListener,
+        executor: &BackgroundExecutor,
+    ) -> Task<()> {
+        let timers = executor.clone();
+        executor.spawn(async move {
+            loop {
+                let received = match self.sync_deadline() {
+                    Some(deadline) => {
+                        let timeout =
+                            timers.timer(deadline.saturating_duration_since(Instant::now()));
+                        smol::future::or(output.recv().map(Some), timeout.map(|_| None)).await
+                    }
+                    None => Some(output.recv().await),
+                };
+                match received {
+                    Some(Ok(PtyOutput::Output(bytes))) => {
+                        let new_recorded = self.process(&bytes, &term, &mut |reply| {
+                            pty_tx.notify(reply.to_vec());
+                        });
+                        recorded.lock().extend(new_recorded);
+                        // Synchronized updates are only drawn once they end.
+                        if self.sync_deadline().is_some() {
+                            continue;
+                        }
+                    }
+                    Some(Ok(PtyOutput::Exited)) => listener.send_event(AlacTermEvent::Exit),
+                    // The synchronized update timed out.
+                    None => self.stop_sync(&term),
+                    Some(Err(_)) => break,
+                }
+                listener.send_event(AlacTermEvent::Wakeup);
+            }
+        })
+    }
+
+    /// When the synchronized update the program started times out, if it's in one.
+    pub fn sync_deadline(&self) -> Option<Instant> {
+        self.parser.sync_timeout().sync_timeout()
+    }
+
+    /// Draws the synchronized update the program started, once it timed out.
+    pub fn stop_sync<T: EventListener>(&mut self, term: &FairMutex<Term<T>>) {
+        self.parser.stop_sync(&mut *term.lock());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_scan_marks() {
+        let mut scanner = SequenceScanner::default();
+        let output = b"\x1b]133;A\x07$ \x1b]133;B\x1b\\ls\r\n\x1b]133;C\x07a b\r\n\x1b]133;D;2\x07";
+        let mut marks = Vec::new();
+        let mut offset = 0;
+        while let Some((end, sequence)) = scanner.scan(&output[offset..]) {
+            offset += end;
+            marks.push((offset, sequence));
+        }
+        assert_eq!(
+            marks,
+            [
+                (8, Sequence::Mark(MarkKind::PromptStart)),
+                (19, Sequence::Mark(MarkKind::CommandStart)),
+                (31, Sequence::Mark(MarkKind::OutputStart)),
+                (
+                    46,
+                    Sequence::Mark(MarkKind::CommandFinished { exit_code: Some(2) })
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_scan_marks_split_across_reads() {
+        let mut scanner = SequenceScanner::default();
+        assert_eq!(scanner.scan(b"out\x1b]13"), None);
+        assert_eq!(scanner.scan(b"3;D\x1b"), None);
+        assert_eq!(
+            scanner.scan(b"\\more"),
+            Some((
+                1,
+                Sequence::Mark(MarkKind::CommandFinished { exit_code: None })
+            ))
+        );
+        // Other OSCs, like titles, aren't marks.
+        assert_eq!(scanner.scan(b"\x1b]0;133;A\x07\x1b[1m"), None);
+    }
+
+    #[test]
+    fn test_scan_images() {
+        let mut scanner = SequenceScanner::default();
+        let iterm_payload = "A".repeat(1000);
+        let output = format!(
+            "\x1b_Ga=T,f=100;AAAA\x1b\\\x1b]1337;File=inline=1:{iterm_payload}\x07\x1bP0;1q#0~\x1b\\\x1b[2J\x1b[3J"
+        );
+        let mut sequences = Vec::new();
+        let mut output = output.as_bytes();
+        while let Some((end, sequence)) = scanner.scan(output) {
+            output = &output[end..];
+            sequences.push(sequence);
+        }
+        assert_eq!(
+            sequences,
+            [
+                Sequence::Image(ImageSequence::Kitty(b"a=T,f=100;AAAA".to_vec())),
+                Sequence::Image(ImageSequence::ITerm(
+                    format!("inline=1:{iterm_payload}").into_bytes()
+                )),
+                Sequence::Image(ImageSequence::Sixel(b"0;1q#0~".to_vec())),
+                Sequence::EraseScrollback,
+            ]
+        );
+    }
+}
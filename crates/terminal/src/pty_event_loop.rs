@@ -0,0 +1,257 @@
+//! Reads the program's output from the PTY and writes its input to it, on a thread of its
+//! own.
+//!
+//! Alacritty's event loop parses the output itself, while holding the terminal lock, which
+//! leaves no room to handle the sequences it ignores at the right point in the output. This
+//! loop only passes the output on instead, to be parsed by an
+//! [`OutputProcessor`](crate::output::OutputProcessor) off this thread.
+
+use std::{
+    borrow::Cow,
+    collections::VecDeque,
+    io::{self, ErrorKind, Read as _, Write as _},
+    num::NonZeroUsize,
+    sync::{Arc, mpsc},
+    thread,
+};
+
+use alacritty_terminal::{
+    event::{Event as AlacTermEvent, EventListener, OnResize},
+    event_loop::Msg,
+    tty::{self, ChildEvent, EventedPty, EventedReadWrite},
+};
+use anyhow::{Context as _, Result};
+use polling::{Event as PollingEvent, Events, PollMode, Poller};
+
+/// The most output read at once.
+const READ_BUFFER_SIZE: usize = 1 << 16;
+/// The most output read before checking for input to write.
+const MAX_READ: usize = 1 << 20;
+/// The number of reads passed on that can wait to be parsed, after which reading waits.
+const OUTPUT_CAPACITY: usize = 16;
+
+/// What the loop passes on from the PTY.
+pub(crate) enum PtyOutput {
+    Output(Vec<u8>),
+    /// The program exited, after all of its output was passed on.
+    Exited,
+}
+
+/// Sends input and other messages to a [`PtyEventLoop`].
+#[derive(Clone)]
+pub(crate) struct PtySender {
+    sender: mpsc::Sender<Msg>,
+    poller: Arc<Poller>,
+}
+
+impl PtySender {
+    pub fn send(&self, message: Msg) -> Result<()> {
+        self.sender
+            .send(message)
+            .ok()
+            .context("the PTY event loop has stopped")?;
+        self.poller.notify()?;
+        Ok(())
+    }
+
+    /// Writes `input` to the PTY.
+    pub fn notify(&self, input: impl Into<Cow<'static, [u8]>>) {
+        let input = input.into();
+        if !input.is_empty() {
+            self.send(Msg::Input(input)).ok();
+        }
+    }
+}
+
+pub(crate) struct PtyEventLoop<T, U> {
+    pty: T,
+    listener: U,
+    poller: Arc<Poller>,
+    sender: mpsc::Sender<Msg>,
+    receiver: mpsc::Receiver<Msg>,
+    output: smol::channel::Sender<PtyOutput>,
+    drain_on_exit: bool,
+    /// The input waiting to be written, and how much of the first of it was written.
+    input: VecDeque<Cow<'static, [u8]>>,
+    written: usize,
+}
+
+impl<T, U> PtyEventLoop<T, U>
+where
+    T: EventedPty + OnResize + Send + 'static,
+    U: EventListener + Send + 'static,
+{
+    /// Creates the loop, and the channel it passes the output on to.
+    pub fn new(
+        pty: T,
+        listener: U,
+        drain_on_exit: bool,
+    ) -> io::Result<(Self, smol::channel::Receiver<PtyOutput>)> {
+        let (sender, receiver) = mpsc::channel();
+        let (output, output_rx) = smol::channel::bounded(OUTPUT_CAPACITY);
+        let event_loop = Self {
+            pty,
+            listener,
+            poller: Arc::new(Poller::new()?),
+            sender,
+            receiver,
+            output,
+            drain_on_exit,
+            input: VecDeque::new(),
+            written: 0,
+        };
+        Ok((event_loop, output_rx))
+    }
+
+    pub fn sender(&self) -> PtySender {
+        PtySender {
+            sender: self.sender.clone(),
+            poller: self.poller.clone(),
+        }
+    }
+
+    pub fn spawn(self) -> io::Result<thread::JoinHandle<()>> {
+        thread::Builder::new()
+            .name("PTY event loop".into())
+            .spawn(move || self.run())
+    }
+
+    fn run(mut self) {
+        let poll_mode = PollMode::Level;
+        let mut interest = PollingEvent::readable(0);
+        // SAFETY: the PTY is deregistered before it's dropped, at the end of the loop.
+        if let Err(error) = unsafe { self.pty.register(&self.poller, interest, poll_mode) } {
+            log::error!("failed to register the PTY: {error}");
+            return;
+        }
+
+        let mut events = Events::with_capacity(NonZeroUsize::new(1024).unwrap());
+        let mut buf = vec![0; READ_BUFFER_SIZE];
+        'event_loop: loop {
+            events.clear();
+            if let Err(error) = self.poller.wait(&mut events, None) {
+                if error.kind() == ErrorKind::Interrupted {
+                    continue;
+                }
+                log::error!("failed to poll the PTY: {error}");
+                break;
+            }
+            if !self.receive_messages() {
+                break;
+            }
+
+            for event in events.iter() {
+                match event.key {
+                    tty::PTY_CHILD_EVENT_TOKEN => {
+                        if let Some(ChildEvent::Exited(code)) = self.pty.next_child_event() {
+                            if let Some(code) = code {
+                                self.listener.send_event(AlacTermEvent::ChildExit(code));
+                            }
+                            if self.drain_on_exit {
+                                self.read(&mut buf).ok();
+                            }
+                            self.output.send_blocking(PtyOutput::Exited).ok();
+                            break 'event_loop;
+                        }
+                    }
+                    tty::PTY_READ_WRITE_TOKEN => {
+                        // The PTY is gone.
+                        if event.is_interrupt() {
+                            continue;
+                        }
+                        if event.readable
+                            && let Err(error) = self.read(&mut buf)
+                        {
+                            // Linux reports that the PTY was closed as an error.
+                            #[cfg(target_os = "linux")]
+                            if error.raw_os_error() == Some(libc::EIO) {
+                                continue;
+                            }
+                            log::error!("failed to read from the PTY: {error}");
+                            break 'event_loop;
+                        }
+                        if event.writable
+                            && let Err(error) = self.write()
+                        {
+                            log::error!("failed to write to the PTY: {error}");
+                            break 'event_loop;
+                        }
+                    }
+                    _ => {}
+                }
+            }
+
+            let needs_write = !self.input.is_empty();
+            if needs_write != interest.writable {
+                interest.writable = needs_write;
+                if let Err(error) = self.pty.reregister(&self.poller, interest, poll_mode) {
+                    log::error!("failed to register the PTY: {error}");
+                    break;
+                }
+            }
+        }
+        self.pty.deregister(&self.poller).ok();
+    }
+
+    /// Handles the messages sent to the loop, returning whether it should go on.
+    fn receive_messages(&mut self) -> bool {
+        while let Ok(message) = self.receiver.try_recv() {
+            match message {
+                Msg::Input(input) => self.input.push_back(input),
+                Msg::Resize(window_size) => self.pty.on_resize(window_size),
+                Msg::Shutdown => return false,
+            }
+        }
+        true
+    }
+
+    /// Passes on the output available, up to [`MAX_READ`] of it. The output channel is
+    /// bounded, so this waits while the output before it is still being parsed.
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<()> {
+        let mut total = 0;
+        while total < MAX_READ {
+            match self.pty.reader().read(buf) {
+                Ok(0) => break,
+                Ok(len) => {
+                    total += len;
+                    if self
+                        .output
+                        .send_blocking(PtyOutput::Output(buf[..len].to_vec()))
+                        .is_err()
+                    {
+                        break;
+                    }
+                }
+                Err(error)
+                    if matches!(error.kind(), ErrorKind::Interrupted | ErrorKind::WouldBlock) =>
+                {
+                    break;
+                }
+                Err(error) => return Err(error),
+            }
+        }
+        Ok(())
+    }
+
+    fn write(&mut self) -> io::Result<()> {
+        while let Some(input) = self.input.front() {
+            match self.pty.writer().write(&input[self.written..]) {
+                Ok(0) => break,
+                Ok(len) => {
+                    self.written += len;
+                    if self.written == input.len() {
+                        self.input.pop_front();
+                        self.written = 0;
+                    }
+                }
+                Err(error)
+                    if matches!(error.kind(), ErrorKind::Interrupted | ErrorKind::WouldBlock) =>
+                {
+                    break;
+                }
+                Err(error) => return Err(error),
+            }
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,160 @@
+//! Saves the terminal's content as text with the SGR escape sequences for its colors and
+//! styles, so that it can be written back into a new terminal after a restart.
+
+use std::fmt::Write as _;
+
+use alacritty_terminal::{
+    grid::Dimensions,
+    index::{Column, Line, Point as AlacPoint},
+    term::{
+        Term,
+        cell::{Cell, Flags},
+    },
+    vte::ansi::{Color, NamedColor},
+};
+
+/// Written between the restored scrollback and the output of the new shell.
+pub(crate) const RESTORED_SEPARATOR: &str = "\x1b[0;2m* Restored from the previous session\x1b[0m";
+
+const STYLE_FLAGS: Flags = Flags::BOLD
+    .union(Flags::DIM)
+    .union(Flags::ITALIC)
+    .union(Flags::ALL_UNDERLINES)
+    .union(Flags::INVERSE)
+    .union(Flags::HIDDEN)
+    .union(Flags::STRIKEOUT);
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+struct Style {
+    fg: Color,
+    bg: Color,
+    flags: Flags,
+}
+
+impl Default for Style {
+    fn default() -> Self {
+        Self {
+            fg: Color::Named(NamedColor::Foreground),
+            bg: Color::Named(NamedColor::Background),
+            flags: Flags::empty(),
+        }
+    }
+}
+
+impl Style {
+    fn of(cell: &Cell) -> Self {
+        Self {
+            fg: cell.fg,
+            bg: cell.bg,
+            flags: cell.flags & STYLE_FLAGS,
+        }
+    }
+
+    /// Writes the SGR sequence that resets the style and then sets this one.
+    fn write(&self, output: &mut String) {
+        output.push_str("\x1b[0");
+        for (flag, code) in [
+            (Flags::BOLD, "1"),
+            (Flags::DIM, "2"),
+            (Flags::ITALIC, "3"),
+            (Flags::ALL_UNDERLINES, "4"),
+            (Flags::INVERSE, "7"),
+            (Flags::HIDDEN, "8"),
+            (Flags::STRIKEOUT, "9"),
+        ] {
+            if self.flags.intersects(flag) {
+                output.push(';');
+                output.push_str(code);
+            }
+        }
+        write_color(output, self.fg, 30);
+        write_color(output, self.bg, 40);
+        output.push('m');
+    }
+}
+
+fn write_color(output: &mut String, color: Color, base: u8) {
+    match color {
+        Color::Named(named) => {
+            let index = named as usize;
+            if index < 8 {
+                write!(output, ";{}", base as usize + index).ok();
+            } else if index < 16 {
+                write!(output, ";{}", base as usize + 60 + index - 8).ok();
+            }
+            // The default and dim colors are what the reset leaves.
+        }
+        Color::Indexed(index) => {
+            write!(output, ";{};5;{index}", base + 8).ok();
+        }
+        Color::Spec(rgb) => {
+            write!(output, ";{};2;{};{};{}", base + 8, rgb.r, rgb.g, rgb.b).ok();
+        }
+    }
+}
+
+fn is_blank(cell: &Cell) -> bool {
+    cell.c == ' '
+        && cell.bg == Color::Named(NamedColor::Background)
+        && !cell.flags.intersects(Flags::INVERSE)
+}
+
+/// Serializes the content from the start of the `start` line up to `end`, exclusive.
+///
+/// Blank cells at the end of every line but the last are dropped, except on the lines that
+/// wrap onto the next one, so that writing the output to a terminal as wide as this one
+/// wraps them again.
+pub(crate) fn serialize<T>(term: &Term<T>, start: Line, end: AlacPoint) -> String {
+    let grid = term.grid();
+    let columns = grid.columns();
+    let mut output = String::new();
+    let mut style = Style::default();
+    for line in start.0..=end.line.0 {
+        let row = &grid[Line(line)];
+        let is_last = line == end.line.0;
+        let wraps = !is_last && row[Column(columns - 1)].flags.contains(Flags::WRAPLINE);
+        let len = if is_last {
+            end.column.0.min(columns)
+        } else if wraps {
+            columns
+        } else {
+            (0..columns)
+                .rev()
+                .find(|&column| !is_blank(&row[Column(column)]))
+                .map_or(0, |column| column + 1)
+        };
+
+        for column in 0..len {
+            let cell = &row[Column(column)];
+            if cell
+                .flags
+                .intersects(Flags::WIDE_CHAR_SPACER | Flags::LEADING_WIDE_CHAR_SPACER)
+            {
+                continue;
+            }
+            let cell_style = Style::of(cell);
+            if cell_style != style {
+                cell_style.write(&mut output);
+                style = cell_style;
+            }
+            output.push(cell.c);
+            if let Some(zerowidth) = cell.zerowidth() {
+                output.extend(zerowidth);
+            }
+        }
+
+        if !is_last && !wraps {
+            // Reset the style first, so that the background isn't painted to the end of
+            // the line in the new terminal.
+            if style != Style::default() {
+                output.push_str("\x1b[0m");
+                style = Style::default();
+            }
+            output.push_str("\r\n");
+        }
+    }
+    if style != Style::default() {
+        output.push_str("\x1b[0m");
+    }
+    output
+}
@@ -0,0 +1,204 @@
+//! Shell integration through the OSC 133 marks shells emit around their prompts:
+//!
+//! - `OSC 133 ; A ST` where the prompt starts,
+//! - `OSC 133 ; B ST` where the prompt ends and the command typed at it starts,
+//! - `OSC 133 ; C ST` where the command is run and its output starts,
+//! - `OSC 133 ; D [; exit code] ST` where the command finishes.
+//!
+//! Alacritty ignores these sequences, so the output is scanned for them as it's parsed,
+//! and each mark is recorded at the cursor once the output before it has been parsed.
+
+use alacritty_terminal::{
+    grid::Dimensions,
+    index::{Column, Line, Point as AlacPoint},
+    term::Term,
+};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum MarkKind {
+    PromptStart,
+    CommandStart,
+    OutputStart,
+    CommandFinished { exit_code: Option<i32> },
+}
+
+impl MarkKind {
+    pub(crate) fn parse(osc: &[u8]) -> Option<Self> {
+        let osc = std::str::from_utf8(osc).ok()?;
+        let mut params = osc.strip_prefix("133;")?.split(';');
+        match params.next()? {
+            "A" => Some(Self::PromptStart),
+            "B" => Some(Self::CommandStart),
+            "C" => Some(Self::OutputStart),
+            "D" => Some(Self::CommandFinished {
+                exit_code: params.next().and_then(|code| code.parse().ok()),
+            }),
+            _ => None,
+        }
+    }
+}
+
+/// A point in the terminal, with lines counted from the top of the scrollback rather than
+/// the top of the screen, so that it doesn't move as output scrolls the screen.
+///
+/// Once the scrollback is full, the lines dropped from its top shift the content up
+/// without changing the count, so points recorded before then drift down.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub struct AbsolutePoint {
+    pub line: usize,
+    pub column: Column,
+}
+
+impl AbsolutePoint {
+    pub fn from_grid<T>(point: AlacPoint, term: &Term<T>) -> Self {
+        Self {
+            line: (term.history_size() as i32 + point.line.0).max(0) as usize,
+            column: point.column,
+        }
+    }
+
+    /// Returns the point in the grid, or `None` if it has scrolled out of the scrollback.
+    pub fn to_grid<T>(self, term: &Term<T>) -> Option<AlacPoint> {
+        let line = self.line as i32 - term.history_size() as i32;
+        (line >= term.topmost_line().0 && line <= term.bottommost_line().0)
+            .then(|| AlacPoint::new(Line(line), self.column))
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ShellMark {
+    pub kind: MarkKind,
+    pub point: AbsolutePoint,
+}
+
+impl ShellMark {
+    pub fn at_cursor<T>(kind: MarkKind, term: &Term<T>) -> Self {
+        Self {
+            kind,
+            point: AbsolutePoint::from_grid(term.grid().cursor.point, term),
+        }
+    }
+}
+
+/// A command run in the shell, put together from the marks emitted around it.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct ShellCommand {
+    pub prompt_start: Option<AbsolutePoint>,
+    pub command_start: Option<AbsolutePoint>,
+    pub output_start: Option<AbsolutePoint>,
+    pub output_end: Option<AbsolutePoint>,
+    pub exit_code: Option<i32>,
+}
+
+impl ShellCommand {
+    /// Where the command's prompt is, or where the command starts if the shell doesn't
+    /// mark its prompt.
+    pub fn prompt(&self) -> Option<AbsolutePoint> {
+        self.prompt_start.or(self.command_start)
+    }
+
+    pub fn has_run(&self) -> bool {
+        self.output_start.is_some()
+    }
+
+    pub fn has_finished(&self) -> bool {
+        self.output_end.is_some()
+    }
+
+    /// The range of lines the command's output covers, if it has any.
+    pub fn output_lines(&self) -> Option<(usize, usize)> {
+        let start = self.output_start?;
+        let end = self.output_end?;
+        // The shell marks the end of the output where the cursor is after it, which is
+        // the start of the next line unless the output doesn't end in a newline.
+        let end_line = if end.column.0 == 0 {
+            end.line.checked_sub(1)?
+        } else {
+            end.line
+        };
+        (end_line >= start.line).then_some((start.line, end_line))
+    }
+}
+
+/// The commands run in a terminal, in the order they were run.
+#[derive(Debug, Default)]
+pub struct ShellCommands {
+    commands: Vec<ShellCommand>,
+}
+
+impl ShellCommands {
+    pub fn push_mark(&mut self, mark: ShellMark) {
+        let last = self.commands.last_mut();
+        match mark.kind {
+            MarkKind::PromptStart => self.commands.push(ShellCommand {
+                prompt_start: Some(mark.point),
+                ..Default::default()
+            }),
+            MarkKind::CommandStart => match last {
+                Some(last) if last.command_start.is_none() && !last.has_run() => {
+                    last.command_start = Some(mark.point);
+                }
+                _ => self.commands.push(ShellCommand {
+                    command_start: Some(mark.point),
+                    ..Default::default()
+                }),
+            },
+            MarkKind::OutputStart => match last {
+                Some(last) if !last.has_run() => last.output_start = Some(mark.point),
+                _ => self.commands.push(ShellCommand {
+                    output_start: Some(mark.point),
+                    ..Default::default()
+                }),
+            },
+            MarkKind::CommandFinished { exit_code } => {
+                // Some shells mark the end of a command at every prompt, including the
+                // ones at which nothing was run.
+                if let Some(last) = last
+                    && last.has_run()
+                    && !last.has_finished()
+                {
+                    last.output_end = Some(mark.point);
+                    last.exit_code = exit_code;
+                }
+            }
+        }
+    }
+
+    pub fn clear(&mut self) {
+        self.commands.clear();
+    }
+
+    /// Moves the commands down by `lines`, or up if it's negative, after content has been
+    /// inserted or removed above them.
+    pub fn offset_lines(&mut self, lines: isize) {
+        for command in &mut self.commands {
+            for point in [
+                &mut command.prompt_start,
+                &mut command.command_start,
+                &mut command.output_start,
+                &mut command.output_end,
+            ]
+            .into_iter()
+            .flatten()
+            {
+                point.line = point.line.saturating_add_signed(lines);
+            }
+        }
+    }
+
+    pub fn get(&self, ix: usize) -> Option<&ShellCommand> {
+        self.commands.get(ix)
+    }
+
+    pub fn len(&self) -> usize {
+        self.commands.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.commands.is_empty()
+    }
+
+    pub fn iter(&self) -> impl DoubleEndedIterator<Item = &ShellCommand> {
+        self.commands.iter()
+    }
+}
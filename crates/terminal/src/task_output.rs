@@ -0,0 +1,214 @@
+//! Matches the problems in a task's output as the PTY prints it, see [`task::ProblemMatcher`].
+
+use alacritty_terminal::vte::{Params, Parser, Perform};
+use task::{ProblemMatcher, ProblemMatching, TaskProblem};
+
+const TAB_WIDTH: usize = 8;
+
+/// Problems matched in a chunk of the task's output.
+#[derive(Debug, Default, PartialEq, Eq)]
+pub(crate) struct MatchedProblems {
+    /// Whether the scrollback got erased, which drops the problems matched before.
+    pub cleared: bool,
+    pub problems: Vec<TaskProblem>,
+}
+
+impl MatchedProblems {
+    pub fn is_empty(&self) -> bool {
+        !self.cleared && self.problems.is_empty()
+    }
+}
+
+/// Splits the task output into lines, and runs the task's problem matchers over each of them.
+pub(crate) struct TaskOutputMatcher {
+    parser: Parser,
+    lines: OutputLines,
+    matching: ProblemMatching,
+}
+
+impl TaskOutputMatcher {
+    /// Returns `None` if there are no valid problem matchers to run.
+    pub fn new(problem_matchers: &[ProblemMatcher]) -> Option<Self> {
+        let matching = ProblemMatching::new(problem_matchers);
+        if matching.is_empty() {
+            return None;
+        }
+        Some(Self {
+            parser: Parser::new(),
+            lines: OutputLines::default(),
+            matching,
+        })
+    }
+
+    /// Matches the lines completed by the output given.
+    pub fn advance(&mut self, output: &[u8]) -> MatchedProblems {
+        self.parser.advance(&mut self.lines, output);
+        let mut matched = MatchedProblems::default();
+        for event in self.lines.events.drain(..) {
+            match event {
+                LineEvent::Line(line) => matched.problems.extend(self.matching.match_line(&line)),
+                LineEvent::Clear => {
+                    self.matching.reset();
+                    matched.cleared = true;
+                    matched.problems.clear();
+                }
+            }
+        }
+        matched
+    }
+
+    /// Matches the last line of the output, once the task has finished, even if it didn't end
+    /// with a newline.
+    pub fn finish(&mut self) -> MatchedProblems {
+        let mut matched = self.advance(&[]);
+        if let Some(line) = self.lines.take_line() {
+            matched.problems.extend(self.matching.match_line(&line));
+        }
+        matched
+    }
+}
+
+enum LineEvent {
+    Line(String),
+    Clear,
+}
+
+/// The text of the output's lines, without the escape sequences, with the carriage returns,
+/// backspaces and line erasures applied the way a terminal would.
+#[derive(Default)]
+struct OutputLines {
+    line: Vec<char>,
+    cursor: usize,
+    events: Vec<LineEvent>,
+}
+
+impl OutputLines {
+    fn take_line(&mut self) -> Option<String> {
+        self.cursor = 0;
+        let line = std::mem::take(&mut self.line)
+            .into_iter()
+            .collect::<String>();
+        let line = line.trim_end();
+        (!line.is_empty()).then(|| line.to_string())
+    }
+
+    fn put(&mut self, c: char) {
+        if self.cursor < self.line.len() {
+            self.line[self.cursor] = c;
+        } else {
+            self.line.resize(self.cursor, ' ');
+            self.line.push(c);
+        }
+        self.cursor += 1;
+    }
+}
+
+impl Perform for OutputLines {
+    fn print(&mut self, c: char) {
+        self.put(c);
+    }
+
+    fn execute(&mut self, byte: u8) {
+        match byte {
+            b'\n' => {
+                let line = self.take_line().unwrap_or_default();
+                self.events.push(LineEvent::Line(line));
+            }
+            b'\r' => self.cursor = 0,
+            b'\t' => self.cursor = (self.cursor / TAB_WIDTH + 1) * TAB_WIDTH,
+            0x08 => self.cursor = self.cursor.saturating_sub(1),
+            _ => {}
+        }
+    }
+
+    fn csi_dispatch(&mut self, params: &Params, intermediates: &[u8], ignore: bool, action: char) {
+        if ignore || !intermediates.is_empty() {
+            return;
+        }
+        let mode = params
+            .iter()
+            .next()
+            .and_then(|param| param.first().copied())
+            .unwrap_or(0);
+        match (action, mode) {
+            // Erase the line, from the cursor or entirely.
+            ('K', 0) => self.line.truncate(self.cursor),
+            ('K', 2) => self.line.clear(),
+            ('G', column) => self.cursor = usize::from(column.max(1)) - 1,
+            // Erase the scrollback.
+            ('J', 3) => {
+                self.take_line();
+                self.events.push(LineEvent::Clear);
+            }
+            _ => {}
+        }
+    }
+
+    fn esc_dispatch(&mut self, intermediates: &[u8], ignore: bool, byte: u8) {
+        // Reset the terminal.
+        if !ignore && intermediates.is_empty() && byte == b'c' {
+            self.take_line();
+            self.events.push(LineEvent::Clear);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use task::BuiltInProblemMatcher;
+
+    use super::*;
+
+    #[test]
+    fn test_match_output_as_printed() {
+        let mut matcher =
+            TaskOutputMatcher::new(&[ProblemMatcher::BuiltIn(BuiltInProblemMatcher::Rustc)])
+                .unwrap();
+        let problem_lines = |matched: MatchedProblems| {
+            (
+                matched.cleared,
+                matched
+                    .problems
+                    .into_iter()
+                    .map(|problem| (problem.path, problem.line))
+                    .collect::<Vec<_>>(),
+            )
+        };
+
+        // Problems are matched once their lines are complete, even if split across reads.
+        assert_eq!(
+            problem_lines(matcher.advance(b"\x1b[1m\x1b[91merror\x1b[0m: mismatched types\r\n  -")),
+            (false, vec![])
+        );
+        assert_eq!(
+            problem_lines(matcher.advance(b"-> src/lib.rs:10:18\r\n")),
+            (false, vec![("src/lib.rs".to_string(), 10)])
+        );
+
+        // Progress lines, overwritten with carriage returns, don't get in the way.
+        assert_eq!(
+            problem_lines(matcher.advance(
+                b"    Building [===>  ] 3/5\r\x1b[Kerror: unused import\r\n --> src/main.rs:1:5\r\n"
+            )),
+            (false, vec![("src/main.rs".to_string(), 1)])
+        );
+
+        // Erasing the scrollback drops what was matched before it.
+        assert_eq!(
+            problem_lines(
+                matcher
+                    .advance(b"error: one\r\n --> a.rs:1:1\r\n\x1b[2J\x1b[3J\x1b[Herror: two\r\n")
+            ),
+            (true, vec![])
+        );
+        assert_eq!(
+            problem_lines(matcher.advance(b" --> b.rs:2:1")),
+            (false, vec![])
+        );
+        // The last line is matched once the task finishes, even without a newline.
+        assert_eq!(
+            problem_lines(matcher.finish()),
+            (false, vec![("b.rs".to_string(), 2)])
+        );
+    }
+}
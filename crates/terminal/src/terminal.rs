@@ -2,14 +2,21 @@ pub mod mappings;
 
 pub use alacritty_terminal;
 
+mod asciicast;
+mod images;
+mod output;
+mod pty_event_loop;
 mod pty_info;
+mod scrollback;
+mod shell_integration;
+mod task_output;
 mod terminal_hyperlinks;
 pub mod terminal_settings;
 
 use alacritty_terminal::{
     Term,
-    event::{Event as AlacTermEvent, EventListener, Notify, WindowSize},
-    event_loop::{EventLoop, Msg, Notifier},
+    event::{Event as AlacTermEvent, EventListener, WindowSize},
+    event_loop::Msg,
     grid::{Dimensions, Grid, Row, Scroll as AlacScroll},
     index::{Boundary, Column, Direction as AlacDirection, Line, Point as AlacPoint},
     selection::{Selection, SelectionRange, SelectionType},
@@ -39,18 +46,28 @@ use mappings::mouse::{
     scroll_report,
 };
 
+use asciicast::Recording;
+pub use asciicast::{Asciicast, AsciicastHeader};
 use collections::{HashMap, VecDeque};
 use futures::StreamExt;
+pub use images::TerminalImage;
+use images::TerminalImages;
+use output::{OutputProcessor, Recorded};
+use parking_lot::Mutex;
+use pty_event_loop::{PtyEventLoop, PtySender};
 use pty_info::{ProcessIdGetter, PtyProcessInfo};
 use serde::{Deserialize, Serialize};
 use settings::Settings;
+use shell_integration::ShellCommands;
+pub use shell_integration::{AbsolutePoint, ShellCommand};
 use smol::channel::{Receiver, Sender};
-use task::{HideStrategy, Shell, SpawnInTerminal};
+use task::{HideStrategy, Shell, SpawnInTerminal, TaskProblem};
+use task_output::TaskOutputMatcher;
 use terminal_hyperlinks::RegexSearches;
 use terminal_settings::{AlternateScroll, CursorShape, TerminalSettings};
 use theme::{ActiveTheme, Theme};
 use urlencoding;
-use util::{paths::PathStyle, truncate_and_trailoff};
+use util::{ResultExt as _, paths::PathStyle, truncate_and_trailoff};
 
 #[cfg(unix)]
 use std::os::unix::process::ExitStatusExt;
@@ -59,7 +76,7 @@ use std::{
     cmp::{self, min},
     fmt::Display,
     ops::{Deref, RangeInclusive},
-    path::PathBuf,
+    path::{Path, PathBuf},
     process::ExitStatus,
     sync::Arc,
     time::{Duration, Instant},
@@ -107,6 +124,16 @@ actions!(
         ToggleViMode,
         /// Selects all text in the terminal.
         SelectAll,
+        /// Scrolls to the prompt of the previous command run in the shell.
+        ScrollToPreviousPrompt,
+        /// Scrolls to the prompt of the next command run in the shell.
+        ScrollToNextPrompt,
+        /// Selects the output of the command scrolled to, or of the last command.
+        SelectCommandOutput,
+        /// Copies the output of the command scrolled to, or of the last command.
+        CopyCommandOutput,
+        /// Runs the command scrolled to, or the last command, again.
+        RerunCommand,
     ]
 );
 
@@ -140,6 +167,9 @@ pub enum Event {
     SelectionsChanged,
     NewNavigationTarget(Option<MaybeNavigationTarget>),
     Open(MaybeNavigationTarget),
+    /// The problem matchers of the task found new problems in its output, or the old ones
+    /// got erased along with the scrollback, see [`Terminal::task_problems`].
+    TaskProblemsChanged,
 }
 
 #[derive(Clone, Debug, PartialEq, Eq)]
@@ -168,6 +198,8 @@ enum InternalEvent {
     // FocusNextMatch,
     Scroll(AlacScroll),
     ScrollToAlacPoint(AlacPoint),
+    /// Scrolls the prompt of the shell command at the index to the top of the screen.
+    ScrollToCommand(usize),
     SetSelection(Option<(Selection, AlacPoint)>),
     UpdateSelection(Point<Pixels>),
     FindHyperlink(Point<Pixels>, bool),
@@ -375,6 +407,12 @@ impl TerminalBuilder {
 
         let term = Arc::new(FairMutex::new(term));
 
+        let recording = Arc::<Mutex<Option<Recording>>>::default();
+        let task_output = Arc::<Mutex<Option<TaskOutputMatcher>>>::default();
+        let window_size = Arc::new(Mutex::new(TerminalBounds::default().into()));
+        let output =
+            OutputProcessor::new(recording.clone(), task_output.clone(), window_size.clone());
+
         let terminal = Terminal {
             task: None,
             terminal_type: TerminalType::DisplayOnly,
@@ -415,6 +453,16 @@ impl TerminalBuilder {
             event_loop_task: Task::ready(Ok(())),
             background_executor: background_executor.clone(),
             path_style,
+            recorded: Arc::default(),
+            recording,
+            task_output,
+            window_size,
+            output,
+            _output_task: Task::ready(()),
+            task_problems: Vec::new(),
+            shell_commands: ShellCommands::default(),
+            images: TerminalImages::default(),
+            scrolled_to_command: None,
             #[cfg(any(test, feature = "test-support"))]
             input_log: Vec::new(),
         };
@@ -588,24 +636,42 @@ impl TerminalBuilder {
 
             let pty_info = PtyProcessInfo::new(&pty);
 
+            let recorded = Arc::<Mutex<Vec<Recorded>>>::default();
+            let recording = Arc::<Mutex<Option<Recording>>>::default();
+            let task_output =
+                Arc::new(Mutex::new(task.as_ref().and_then(|task| {
+                    TaskOutputMatcher::new(&task.spawned_task.problem_matchers)
+                })));
+            let window_size = Arc::new(Mutex::new(TerminalBounds::default().into()));
+
             //And connect them together
-            let event_loop = EventLoop::new(
-                term.clone(),
-                ZedListener(events_tx),
+            let (event_loop, output_rx) = PtyEventLoop::new(
                 pty,
+                ZedListener(events_tx.clone()),
                 pty_options.drain_on_exit,
-                false,
             )
             .context("failed to create event loop")?;
 
-            let pty_tx = event_loop.channel();
-            let _io_thread = event_loop.spawn(); // DANGER
+            let pty_tx = event_loop.sender();
+            let _io_thread = event_loop
+                .spawn()
+                .context("failed to spawn the event loop")?; // DANGER
+            let output_task =
+                OutputProcessor::new(recording.clone(), task_output.clone(), window_size.clone())
+                    .spawn_for_pty(
+                        output_rx,
+                        term.clone(),
+                        recorded.clone(),
+                        pty_tx.clone(),
+                        ZedListener(events_tx),
+                        &background_executor,
+                    );
 
             let no_task = task.is_none();
             let terminal = Terminal {
                 task,
                 terminal_type: TerminalType::Pty {
-                    pty_tx: Notifier(pty_tx),
+                    pty_tx,
                     info: Arc::new(pty_info),
                 },
                 completion_tx,
@@ -648,6 +714,20 @@ impl TerminalBuilder {
                 event_loop_task: Task::ready(Ok(())),
                 background_executor,
                 path_style,
+                recorded,
+                recording,
+                task_output,
+                output: OutputProcessor::new(
+                    recording.clone(),
+                    task_output.clone(),
+                    window_size.clone(),
+                ),
+                window_size,
+                _output_task: output_task,
+                task_problems: Vec::new(),
+                shell_commands: ShellCommands::default(),
+                images: TerminalImages::default(),
+                scrolled_to_command: None,
                 #[cfg(any(test, feature = "test-support"))]
                 input_log: Vec::new(),
             };
@@ -798,6 +878,10 @@ pub struct TerminalContent {
     pub last_hovered_word: Option<HoveredWord>,
     pub scrolled_to_top: bool,
     pub scrolled_to_bottom: bool,
+    /// The exit codes of the finished shell commands, by the line of their prompt.
+    pub command_exit_codes: Vec<(Line, i32)>,
+    /// The images in the viewport, by the line of their top.
+    pub images: Vec<(Line, TerminalImage)>,
 }
 
 #[derive(Debug, Clone, Eq, PartialEq)]
@@ -824,6 +908,8 @@ impl Default for TerminalContent {
             last_hovered_word: None,
             scrolled_to_top: false,
             scrolled_to_bottom: false,
+            command_exit_codes: Vec::new(),
+            images: Vec::new(),
         }
     }
 }
@@ -836,7 +922,7 @@ pub enum SelectionPhase {
 
 enum TerminalType {
     Pty {
-        pty_tx: Notifier,
+        pty_tx: PtySender,
         info: Arc<PtyProcessInfo>,
     },
     DisplayOnly,
@@ -874,6 +960,24 @@ pub struct Terminal {
     event_loop_task: Task<Result<(), anyhow::Error>>,
     background_executor: BackgroundExecutor,
     path_style: PathStyle,
+    /// Sequences intercepted from the PTY's output, which haven't been handled yet.
+    recorded: Arc<Mutex<Vec<Recorded>>>,
+    /// The asciicast recording of the session, which the PTY's output is written to.
+    recording: Arc<Mutex<Option<Recording>>>,
+    /// Matches the problems in the output of the task, while it's running.
+    task_output: Arc<Mutex<Option<TaskOutputMatcher>>>,
+    /// The size of the terminal, which images are placed with.
+    window_size: Arc<Mutex<WindowSize>>,
+    /// Processes output written directly to the terminal.
+    output: OutputProcessor,
+    /// Processes the PTY's output, in the background.
+    _output_task: Task<()>,
+    /// Problems matched in the output of the task so far.
+    task_problems: Vec<TaskProblem>,
+    shell_commands: ShellCommands,
+    images: TerminalImages,
+    /// The command last scrolled to with [`ScrollToPreviousPrompt`] or [`ScrollToNextPrompt`].
+    scrolled_to_command: Option<usize>,
     #[cfg(any(test, feature = "test-support"))]
     input_log: Vec<Vec<u8>>,
 }
@@ -924,6 +1028,9 @@ impl TaskStatus {
 
 const FIND_HYPERLINK_THROTTLE_PX: Pixels = px(5.0);
 
+/// The longest pause kept when replaying a recording that doesn't set an idle time limit.
+pub const MAX_REPLAY_PAUSE: Duration = Duration::from_secs(2);
+
 impl Terminal {
     fn process_event(&mut self, event: AlacTermEvent, cx: &mut Context<Self>) {
         match event {
@@ -979,7 +1086,16 @@ impl Terminal {
                 //NOOP, Handled in render
             }
             AlacTermEvent::Wakeup => {
+                let recorded = std::mem::take(&mut *self.recorded.lock());
+                let mut task_problems_changed = false;
+                for recorded in recorded {
+                    task_problems_changed |= matches!(recorded, Recorded::TaskProblems(_));
+                    self.record(recorded);
+                }
                 cx.emit(Event::Wakeup);
+                if task_problems_changed {
+                    cx.emit(Event::TaskProblemsChanged);
+                }
 
                 if let TerminalType::Pty { info, .. } = &self.terminal_type {
                     info.emit_title_changed_if_changed(cx);
@@ -1022,10 +1138,21 @@ impl Terminal {
                     cmp::max(new_bounds.line_height, new_bounds.height());
                 new_bounds.bounds.size.width = cmp::max(new_bounds.cell_width, new_bounds.width());
 
+                // The lines are reflowed when the width changes, which would leave the
+                // images over the wrong lines.
+                if new_bounds.num_columns() != self.last_content.terminal_bounds.num_columns() {
+                    self.images.clear();
+                }
+                if let Some(recording) = self.recording.lock().as_mut() {
+                    recording
+                        .resize(new_bounds.num_columns(), new_bounds.num_lines())
+                        .log_err();
+                }
                 self.last_content.terminal_bounds = new_bounds;
+                *self.window_size.lock() = new_bounds.into();
 
                 if let TerminalType::Pty { pty_tx, .. } = &self.terminal_type {
-                    pty_tx.0.send(Msg::Resize(new_bounds.into())).ok();
+                    pty_tx.send(Msg::Resize(new_bounds.into())).ok();
                 }
 
                 term.resize(new_bounds);
@@ -1062,6 +1189,11 @@ impl Terminal {
                     AlacPoint::new(Line(0), term.grid_mut().cursor.point.column);
                 let new_cursor = term.grid().cursor.point;
 
+                // The commands' marks and the images were cleared along with the output
+                self.shell_commands.clear();
+                self.images.clear();
+                self.scrolled_to_command = None;
+
                 // Clear the lines below the new cursor
                 if (new_cursor.line.0 as usize) < term.screen_lines() - 1 {
                     term.grid_mut().reset_region((new_cursor.line + 1)..);
@@ -1164,6 +1296,18 @@ impl Terminal {
                 term.scroll_to_point(*point);
                 self.refresh_hovered_word(window);
             }
+            InternalEvent::ScrollToCommand(ix) => {
+                trace!("Scrolling to command: ix={ix}");
+                if let Some(point) = self
+                    .shell_commands
+                    .get(*ix)
+                    .and_then(|command| command.prompt()?.to_grid(term))
+                {
+                    let display_offset = term.grid().display_offset() as i32;
+                    term.scroll_display(AlacScroll::Delta((-point.line.0).max(0) - display_offset));
+                    self.refresh_hovered_word(window);
+                }
+            }
             InternalEvent::MoveViCursorToAlacPoint(point) => {
                 trace!("Move vi cursor to point: point={point:?}");
                 term.vi_goto_point(*point);
@@ -1309,13 +1453,10 @@ impl Terminal {
             converted.push(byte);
             prev_byte = byte;
         }
-
-        let mut processor = alacritty_terminal::vte::ansi::Processor::<
-            alacritty_terminal::vte::ansi::StdSyncHandler,
-        >::new();
-        {
-            let mut term = self.term.lock();
-            processor.advance(&mut *term, &converted);
+        // Display-only terminals have no program to reply to.
+        let recorded = self.output.process(&converted, &self.term, &mut |_| {});
+        for recorded in recorded {
+            self.record(recorded);
         }
         cx.emit(Event::Wakeup);
     }
@@ -1328,6 +1469,136 @@ impl Terminal {
         self.term.lock_unfair().screen_lines()
     }
 
+    /// The content of the terminal up to the cursor, including up to `max_lines` lines,
+    /// with the escape sequences for its colors and styles.
+    ///
+    /// Returns `None` if there's nothing to save, or if a program is using the alternate
+    /// screen, whose content is of no use once the program is gone.
+    pub fn serialize_scrollback(&self, max_lines: usize) -> Option<String> {
+        let term = self.term.lock_unfair();
+        if max_lines == 0 || term.mode().contains(TermMode::ALT_SCREEN) {
+            return None;
+        }
+        let cursor = term.grid().cursor.point;
+        let max_lines = i32::try_from(max_lines).unwrap_or(i32::MAX);
+        let start = Line(
+            cursor
+                .line
+                .0
+                .saturating_sub(max_lines)
+                .saturating_add(1)
+                .max(term.topmost_line().0),
+        );
+        let scrollback = scrollback::serialize(&term, start, cursor);
+        (!scrollback.trim().is_empty()).then_some(scrollback)
+    }
+
+    /// Writes the scrollback saved from a previous session at the top of the terminal,
+    /// moving what the shell has output so far below it.
+    pub fn restore_scrollback(&mut self, scrollback: &str, cx: &mut Context<Self>) {
+        let mut processor = alacritty_terminal::vte::ansi::Processor::<
+            alacritty_terminal::vte::ansi::StdSyncHandler,
+        >::new();
+        let (recorded, line_offset) = {
+            let mut term = self.term.lock();
+            // What was recorded before the restore is moved along with the shell's output.
+            let recorded = std::mem::take(&mut *self.recorded.lock());
+            let cursor = term.grid().cursor.point;
+            let cursor_line = AbsolutePoint::from_grid(cursor, &term).line;
+            let shell_output = scrollback::serialize(&term, term.topmost_line(), cursor);
+
+            // Clearing the screen scrolls its content into the scrollback, so the
+            // scrollback is cleared after it.
+            term.clear_screen(ClearMode::All);
+            term.clear_screen(ClearMode::Saved);
+            term.goto(0, 0);
+            for output in [
+                scrollback,
+                "\r\n",
+                scrollback::RESTORED_SEPARATOR,
+                "\r\n",
+                &shell_output,
+            ] {
+                processor.advance(&mut *term, output.as_bytes());
+            }
+
+            let new_cursor_line = AbsolutePoint::from_grid(term.grid().cursor.point, &term).line;
+            (recorded, new_cursor_line as isize - cursor_line as isize)
+        };
+        for recorded in recorded {
+            self.record(recorded);
+        }
+        self.shell_commands.offset_lines(line_offset);
+        self.images.offset_lines(line_offset);
+        cx.emit(Event::Wakeup);
+    }
+
+    /// Starts recording the session to an asciicast file at `path`, beginning with what's
+    /// on the screen.
+    pub fn start_recording(&mut self, path: &Path) -> Result<()> {
+        let bounds = self.last_content.terminal_bounds;
+        let mut recording = Recording::create(
+            path,
+            bounds.num_columns(),
+            bounds.num_lines(),
+            self.title(false),
+        )?;
+        {
+            let term = self.term.lock();
+            let screen = scrollback::serialize(&term, Line(0), term.grid().cursor.point);
+            recording.output(screen.as_bytes())?;
+            // The lock is held until the recording is in place, so that no output is
+            // parsed between the screen being saved and the recording starting.
+            *self.recording.lock() = Some(recording);
+        }
+        Ok(())
+    }
+
+    /// Stops recording the session, and returns where the recording was saved.
+    pub fn stop_recording(&mut self) -> Option<Result<PathBuf>> {
+        let recording = self.recording.lock().take()?;
+        Some(recording.finish())
+    }
+
+    pub fn is_recording(&self) -> bool {
+        self.recording.lock().is_some()
+    }
+
+    /// Where the session is being recorded, if it is.
+    pub fn recording_path(&self) -> Option<PathBuf> {
+        self.recording
+            .lock()
+            .as_ref()
+            .map(|recording| recording.path().to_path_buf())
+    }
+
+    /// Writes the output of a recorded session to the terminal with the timing it was
+    /// recorded with, except that pauses are cut down to the recording's idle time limit,
+    /// or to [`MAX_REPLAY_PAUSE`] if it has none.
+    pub fn replay(&mut self, asciicast: Asciicast, cx: &mut Context<Self>) -> Task<()> {
+        let max_pause = asciicast
+            .header
+            .idle_time_limit
+            .and_then(|limit| Duration::try_from_secs_f64(limit).ok())
+            .unwrap_or(MAX_REPLAY_PAUSE);
+        cx.spawn(async move |this, cx| {
+            let mut last_time = Duration::ZERO;
+            for (time, output) in asciicast.output {
+                let pause = time.saturating_sub(last_time).min(max_pause);
+                last_time = time;
+                if !pause.is_zero() {
+                    cx.background_executor().timer(pause).await;
+                }
+                if this
+                    .update(cx, |this, cx| this.write_output(output.as_bytes(), cx))
+                    .is_err()
+                {
+                    break;
+                }
+            }
+        })
+    }
+
     //To test:
     //- Activate match on terminal (scrolling and selection)
     //- Editor search snapping behavior
@@ -1419,6 +1690,137 @@ impl Terminal {
     pub fn scroll_to_bottom(&mut self) {
         self.events
             .push_back(InternalEvent::Scroll(AlacScroll::Bottom));
+        self.scrolled_to_command = None;
+    }
+
+    fn record(&mut self, recorded: Recorded) {
+        match recorded {
+            Recorded::Mark(mark) => self.shell_commands.push_mark(mark),
+            Recorded::Image(image) => self.images.push(image),
+            Recorded::DeleteImages { kitty_id } => self.images.delete(kitty_id),
+            Recorded::TaskProblems(matched) => {
+                if matched.cleared {
+                    self.task_problems.clear();
+                }
+                self.task_problems.extend(matched.problems);
+            }
+        }
+    }
+
+    /// The commands run in the shell, if it marks them with OSC 133 sequences.
+    pub fn shell_commands(&self) -> impl DoubleEndedIterator<Item = &ShellCommand> {
+        self.shell_commands.iter()
+    }
+
+    pub fn scroll_to_previous_prompt(&mut self) {
+        let commands = &self.shell_commands;
+        let current = self.scrolled_to_command.unwrap_or_else(|| {
+            // Skip the prompt the shell is waiting at.
+            match commands.iter().last() {
+                Some(last) if !last.has_run() => commands.len() - 1,
+                _ => commands.len(),
+            }
+        });
+        if let Some(ix) = (0..current).rev().find(|&ix| {
+            commands
+                .get(ix)
+                .is_some_and(|command| command.prompt().is_some())
+        }) {
+            self.scroll_to_command(ix);
+        }
+    }
+
+    pub fn scroll_to_next_prompt(&mut self) {
+        let Some(current) = self.scrolled_to_command else {
+            return;
+        };
+        let commands = &self.shell_commands;
+        match (current + 1..commands.len()).find(|&ix| {
+            commands
+                .get(ix)
+                .is_some_and(|command| command.has_run() && command.prompt().is_some())
+        }) {
+            Some(ix) => self.scroll_to_command(ix),
+            None => self.scroll_to_bottom(),
+        }
+    }
+
+    fn scroll_to_command(&mut self, ix: usize) {
+        self.scrolled_to_command = Some(ix);
+        self.events.push_back(InternalEvent::ScrollToCommand(ix));
+    }
+
+    /// The command last scrolled to, or the last command that finished.
+    fn current_command(&self) -> Option<&ShellCommand> {
+        match self.scrolled_to_command {
+            Some(ix) => self.shell_commands.get(ix),
+            None => self
+                .shell_commands
+                .iter()
+                .rev()
+                .find(|command| command.has_finished()),
+        }
+    }
+
+    fn command_output_range(&self, term: &Term<ZedListener>) -> Option<RangeInclusive<AlacPoint>> {
+        let (start_line, end_line) = self.current_command()?.output_lines()?;
+        let start = AbsolutePoint {
+            line: start_line,
+            column: Column(0),
+        }
+        .to_grid(term)?;
+        let end = AbsolutePoint {
+            line: end_line,
+            column: term.last_column(),
+        }
+        .to_grid(term)?;
+        Some(start..=end)
+    }
+
+    pub fn select_command_output(&mut self) {
+        let term = self.term.lock();
+        let range = self.command_output_range(&term);
+        drop(term);
+        if let Some(range) = range {
+            self.set_selection(Some((make_selection(&range), *range.end())));
+        }
+    }
+
+    pub fn copy_command_output(&mut self, cx: &mut Context<Self>) {
+        let term = self.term.lock();
+        if let Some(range) = self.command_output_range(&term) {
+            let output = term.bounds_to_string(*range.start(), *range.end());
+            cx.write_to_clipboard(ClipboardItem::new_string(output.trim_end().to_string()));
+        }
+    }
+
+    /// Types the command last scrolled to, or the last command that finished, at the
+    /// prompt and runs it.
+    pub fn rerun_command(&mut self) {
+        let term = self.term.lock();
+        let command_text = self.current_command().and_then(|command| {
+            let start = command.command_start?.to_grid(&term)?;
+            // The output starts on the line after the command, once it's entered.
+            let output_start = command.output_start?;
+            let end_line = if output_start.column.0 == 0 {
+                output_start.line.checked_sub(1)?
+            } else {
+                output_start.line
+            };
+            let end = AbsolutePoint {
+                line: end_line,
+                column: term.last_column(),
+            }
+            .to_grid(&term)?;
+            (start <= end).then(|| term.bounds_to_string(start, end))
+        });
+        drop(term);
+
+        if let Some(command_text) = command_text
+            && !command_text.trim().is_empty()
+        {
+            self.input(format!("{}\r", command_text.trim()).into_bytes());
+        }
     }
 
     pub fn scrolled_to_top(&self) -> bool {
@@ -1456,6 +1858,7 @@ impl Terminal {
         self.events
             .push_back(InternalEvent::Scroll(AlacScroll::Bottom));
         self.events.push_back(InternalEvent::SetSelection(None));
+        self.scrolled_to_command = None;
 
         let input = input.into();
         #[cfg(any(test, feature = "test-support"))]
@@ -1617,7 +2020,22 @@ impl Terminal {
             self.process_terminal_event(&e, &mut terminal, window, cx)
         }
 
+        if !terminal.mode().contains(TermMode::ALT_SCREEN) {
+            self.images.retain_main_screen();
+        }
+
         self.last_content = Self::make_content(&terminal, &self.last_content);
+        self.last_content.images = self.images.visible(&terminal);
+        self.last_content.command_exit_codes = self
+            .shell_commands
+            .iter()
+            .filter_map(|command| {
+                Some((
+                    command.prompt()?.to_grid(&terminal)?.line,
+                    command.exit_code?,
+                ))
+            })
+            .collect();
     }
 
     fn make_content(term: &Term<ZedListener>, last_content: &TerminalContent) -> TerminalContent {
@@ -1650,6 +2068,8 @@ impl Terminal {
             last_hovered_word: last_content.last_hovered_word.clone(),
             scrolled_to_top: content.display_offset == term.history_size(),
             scrolled_to_bottom: content.display_offset == 0,
+            command_exit_codes: last_content.command_exit_codes.clone(),
+            images: last_content.images.clone(),
         }
     }
 
@@ -1685,6 +2105,32 @@ impl Terminal {
         lines
     }
 
+    /// All lines of the terminal, including the scrollback, with the wrapped lines joined.
+    #[cfg(test)]
+    fn logical_lines(&self) -> Vec<String> {
+        let terminal = self.term.lock_unfair();
+        let grid = terminal.grid();
+        let mut lines = Vec::new();
+
+        let mut current_line = grid.topmost_line().0;
+        let bottommost_line = grid.bottommost_line().0;
+        let last_column = Column(grid.columns() - 1);
+        while current_line <= bottommost_line {
+            let mut logical_line_end = current_line;
+            while logical_line_end < bottommost_line
+                && grid[Line(logical_line_end)][last_column]
+                    .flags
+                    .contains(Flags::WRAPLINE)
+            {
+                logical_line_end += 1;
+            }
+            let logical_line = self.construct_logical_line(grid, current_line, logical_line_end);
+            lines.push(logical_line.trim_end().to_string());
+            current_line = logical_line_end + 1;
+        }
+        lines
+    }
+
     fn find_logical_line_start(&self, grid: &Grid<Cell>, current: i32, topmost: i32) -> i32 {
         let mut line_start = current;
         while line_start > topmost {
@@ -2207,6 +2653,29 @@ impl Terminal {
         self.task.as_ref()
     }
 
+    /// Problems the problem matchers of the task found in its output so far.
+    pub fn task_problems(&self) -> &[TaskProblem] {
+        &self.task_problems
+    }
+
+    /// Stops matching problems in the task output, matching its last line and whatever
+    /// was matched but not recorded yet. Returns whether the task problems changed.
+    fn finish_task_output(&mut self) -> bool {
+        let Some(mut task_output) = self.task_output.lock().take() else {
+            return false;
+        };
+        let mut recorded = std::mem::take(&mut *self.recorded.lock());
+        recorded.push(Recorded::TaskProblems(task_output.finish()));
+        let mut task_problems_changed = false;
+        for recorded in recorded {
+            if let Recorded::TaskProblems(matched) = &recorded {
+                task_problems_changed |= !matched.is_empty();
+            }
+            self.record(recorded);
+        }
+        task_problems_changed
+    }
+
     pub fn wait_for_completed_task(&self, cx: &App) -> Task<Option<ExitStatus>> {
         if let Some(task) = self.task() {
             if task.status == TaskStatus::Running {
@@ -2237,6 +2706,7 @@ impl Terminal {
         if let Some(e) = exit_status {
             self.child_exited = Some(e);
         }
+        let task_problems_changed = self.finish_task_output();
         let task = match &mut self.task {
             Some(task) => task,
             None => {
@@ -2275,6 +2745,10 @@ impl Terminal {
             unsafe { append_text_to_term(&mut self.term.lock(), &lines_to_show) };
         }
 
+        if task_problems_changed {
+            cx.emit(Event::TaskProblemsChanged);
+        }
+
         match task.spawned_task.hide {
             HideStrategy::Never => {}
             HideStrategy::Always => {
@@ -2404,7 +2878,7 @@ impl Drop for Terminal {
         if let TerminalType::Pty { pty_tx, info } =
             std::mem::replace(&mut self.terminal_type, TerminalType::DisplayOnly)
         {
-            pty_tx.0.send(Msg::Shutdown).ok();
+            pty_tx.send(Msg::Shutdown).ok();
 
             let timer = self.background_executor.timer(Duration::from_millis(100));
             self.background_executor
@@ -2605,6 +3079,22 @@ mod tests {
         (terminal, completion_rx)
     }
 
+    /// Helper to build a test terminal with no PTY, which only displays the output written to it.
+    fn build_display_only_terminal(cx: &mut TestAppContext) -> Entity<Terminal> {
+        cx.new(|cx| {
+            TerminalBuilder::new_display_only(
+                CursorShape::default(),
+                AlternateScroll::On,
+                None,
+                0,
+                cx.background_executor(),
+                PathStyle::local(),
+            )
+            .unwrap()
+            .subscribe(cx)
+        })
+    }
+
     fn init_ctrl_click_hyperlink_test(cx: &mut TestAppContext, output: &[u8]) -> Entity<Terminal> {
         cx.update(|cx| {
             let settings_store = settings::SettingsStore::test(cx);
@@ -3044,6 +3534,155 @@ mod tests {
         assert!(line2_col0, "Second line should start at column 0");
     }
 
+    #[gpui::test]
+    async fn test_shell_integration_marks(cx: &mut TestAppContext) {
+        let terminal = build_display_only_terminal(cx);
+
+        terminal.update(cx, |terminal, cx| {
+            terminal.write_output(
+                b"\x1b]133;A\x07$ \x1b]133;B\x07echo hi\n\x1b]133;C\x07hi\n\x1b]133;D;1\x07",
+                cx,
+            );
+            terminal.write_output(b"\x1b]133;A\x07$ \x1b]133;B\x07", cx);
+
+            let commands = terminal.shell_commands().cloned().collect::<Vec<_>>();
+            assert_eq!(commands.len(), 2);
+            assert_eq!(commands[0].exit_code, Some(1));
+            assert_eq!(commands[0].output_lines(), Some((1, 1)));
+            assert!(!commands[1].has_run());
+
+            terminal.copy_command_output(cx);
+            assert_eq!(
+                cx.read_from_clipboard().and_then(|item| item.text()),
+                Some("hi".to_string())
+            );
+
+            terminal.rerun_command();
+            assert_eq!(terminal.take_input_log(), [b"echo hi\r".to_vec()]);
+        });
+    }
+
+    #[gpui::test]
+    async fn test_inline_images(cx: &mut TestAppContext) {
+        let terminal = build_display_only_terminal(cx);
+
+        // A 10x10 PNG, which covers 2x2 of the 5x5 cells.
+        const PNG: &str = "iVBORw0KGgoAAAANSUhEUgAAAAoAAAAKCAIAAAACUFjqAAAAEklEQVR4nGP4z8CAB+GTG8HSALfKY52fTcuYAAAAAElFTkSuQmCC";
+        terminal.update(cx, |terminal, cx| {
+            terminal.write_output(
+                format!("$ \x1b]1337;File=inline=1:{PNG}\x07after").as_bytes(),
+                cx,
+            );
+
+            let term = terminal.term.lock_unfair();
+            let images = terminal.images.visible(&term);
+            assert_eq!(images.len(), 1);
+            let (line, image) = &images[0];
+            assert_eq!(*line, Line(0));
+            assert_eq!(image.origin.column, Column(2));
+            assert_eq!((image.columns, image.rows), (2, 2));
+            assert_eq!((image.width, image.height), (10, 10));
+            // The output after the image is written past it.
+            assert_eq!(term.grid()[Line(1)][Column(4)].c, 'a');
+            drop(term);
+
+            terminal.write_output(b"\x1b[H\x1b[2J\x1b[3J", cx);
+            let term = terminal.term.lock_unfair();
+            assert!(terminal.images.visible(&term).is_empty());
+        });
+    }
+
+    #[gpui::test]
+    async fn test_scrollback_restore(cx: &mut TestAppContext) {
+        let previous = build_display_only_terminal(cx);
+        let scrollback = previous.update(cx, |terminal, cx| {
+            terminal.write_output(b"\x1b[31mred\x1b[0m plain\n$ ", cx);
+            assert_eq!(terminal.serialize_scrollback(0), None);
+            assert_eq!(
+                terminal.serialize_scrollback(usize::MAX),
+                terminal.serialize_scrollback(1000)
+            );
+            terminal.serialize_scrollback(1000).unwrap()
+        });
+        assert_eq!(scrollback, "\x1b[0;31mred\x1b[0m plain\r\n$ ");
+
+        let terminal = build_display_only_terminal(cx);
+        terminal.update(cx, |terminal, cx| {
+            terminal.write_output(b"\x1b]133;A\x07$ ", cx);
+            terminal.restore_scrollback(&scrollback, cx);
+
+            let mut lines = terminal.logical_lines();
+            while lines.last().is_some_and(|line| line.is_empty()) {
+                lines.pop();
+            }
+            assert_eq!(
+                lines,
+                [
+                    "red plain",
+                    "$",
+                    "* Restored from the previous session",
+                    "$"
+                ]
+            );
+
+            let term = terminal.term.lock_unfair();
+            assert_eq!(
+                term.grid()[Line(0)][Column(0)].fg,
+                alacritty_terminal::vte::ansi::Color::Named(
+                    alacritty_terminal::vte::ansi::NamedColor::Red
+                )
+            );
+            assert_eq!(term.grid().cursor.point, AlacPoint::new(Line(3), Column(2)));
+            drop(term);
+
+            let prompt = terminal.shell_commands().next().and_then(|c| c.prompt());
+            assert_eq!(prompt.map(|point| point.line), Some(3));
+        });
+    }
+
+    #[gpui::test]
+    async fn test_record_and_replay(cx: &mut TestAppContext) {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("session.cast");
+
+        let terminal = build_display_only_terminal(cx);
+        terminal.update(cx, |terminal, cx| {
+            terminal.write_output(b"$ ssh web-1\n", cx);
+            terminal.start_recording(&path).unwrap();
+            assert_eq!(terminal.recording_path(), Some(path.clone()));
+            terminal.write_output(b"web-1$ uptime\n", cx);
+            assert_eq!(terminal.stop_recording().unwrap().unwrap(), path);
+            assert_eq!(terminal.recording_path(), None);
+            terminal.write_output(b"web-1$ exit\n", cx);
+        });
+
+        let asciicast = Asciicast::parse(&std::fs::read_to_string(&path).unwrap()).unwrap();
+        // The recording starts with what was on the screen.
+        assert_eq!(
+            asciicast
+                .output
+                .iter()
+                .map(|(_, output)| output.as_str())
+                .collect::<Vec<_>>(),
+            ["$ ssh web-1\r\n", "web-1$ uptime\r\n"]
+        );
+
+        let replayed = build_display_only_terminal(cx);
+        let events = asciicast.output.len();
+        let replay = replayed.update(cx, |terminal, cx| terminal.replay(asciicast, cx));
+        for _ in 0..events {
+            cx.executor().advance_clock(MAX_REPLAY_PAUSE);
+        }
+        replay.await;
+        replayed.update(cx, |terminal, _| {
+            let mut lines = terminal.logical_lines();
+            while lines.last().is_some_and(|line| line.is_empty()) {
+                lines.pop();
+            }
+            assert_eq!(lines, ["$ ssh web-1", "web-1$ uptime"]);
+        });
+    }
+
     #[gpui::test]
     async fn test_write_output_preserves_existing_crlf(cx: &mut TestAppContext) {
         let terminal = cx.new(|cx| {
@@ -1,19 +1,19 @@
 use editor::{CursorLayout, EditorSettings, HighlightedRange, HighlightedRangeLine};
 use gpui::{
-    AbsoluteLength, AnyElement, App, AvailableSpace, Bounds, ContentMask, Context, DispatchPhase,
-    Element, ElementId, Entity, FocusHandle, Font, FontFeatures, FontStyle, FontWeight,
-    GlobalElementId, HighlightStyle, Hitbox, Hsla, InputHandler, InteractiveElement, Interactivity,
-    IntoElement, LayoutId, Length, ModifiersChangedEvent, MouseButton, MouseMoveEvent, Pixels,
-    Point, StatefulInteractiveElement, StrikethroughStyle, Styled, TextRun, TextStyle,
-    UTF16Selection, UnderlineStyle, WeakEntity, WhiteSpace, Window, div, fill, point, px, relative,
-    size,
+    AbsoluteLength, AnyElement, App, AvailableSpace, Bounds, ContentMask, Context, Corners,
+    DispatchPhase, Element, ElementId, Entity, FocusHandle, Font, FontFeatures, FontStyle,
+    FontWeight, GlobalElementId, HighlightStyle, Hitbox, Hsla, InputHandler, InteractiveElement,
+    Interactivity, IntoElement, LayoutId, Length, ModifiersChangedEvent, MouseButton,
+    MouseMoveEvent, Pixels, Point, StatefulInteractiveElement, StrikethroughStyle, Styled, TextRun,
+    TextStyle, UTF16Selection, UnderlineStyle, WeakEntity, WhiteSpace, Window, div, fill, point,
+    px, relative, size,
 };
 use itertools::Itertools;
 use language::CursorShape;
 use settings::Settings;
 use std::time::Instant;
 use terminal::{
-    IndexedCell, Terminal, TerminalBounds, TerminalContent,
+    IndexedCell, Terminal, TerminalBounds, TerminalContent, TerminalImage,
     alacritty_terminal::{
         grid::Dimensions,
         index::Point as AlacPoint,
@@ -53,6 +53,10 @@ pub struct LayoutState {
     block_below_cursor_element: Option<AnyElement>,
     base_text_style: TextStyle,
     content_mode: ContentMode,
+    /// The lines of the finished shell commands' prompts, colored by their exit status.
+    command_markers: Vec<(i32, Hsla)>,
+    /// The images in the viewport, by the displayed line of their top.
+    images: Vec<(i32, TerminalImage)>,
 }
 
 /// Helper struct for converting data between Alacritty's cursor points, and displayed cursor points.
@@ -1030,11 +1034,33 @@ impl Element for TerminalElement {
                     cursor_char,
                     selection,
                     cursor,
+                    command_exit_codes,
+                    images,
                     ..
                 } = &self.terminal.read(cx).last_content;
                 let mode = *mode;
                 let display_offset = *display_offset;
 
+                let command_markers = command_exit_codes
+                    .iter()
+                    .filter_map(|(line, exit_code)| {
+                        let line = line.0 + display_offset as i32;
+                        let color = if *exit_code == 0 {
+                            theme.status().success
+                        } else {
+                            theme.status().error
+                        };
+                        (0..dimensions.num_lines() as i32)
+                            .contains(&line)
+                            .then_some((line, color))
+                    })
+                    .collect();
+
+                let images = images
+                    .iter()
+                    .map(|(line, image)| (line.0 + display_offset as i32, image.clone()))
+                    .collect();
+
                 // searches, highlights to a single range representations
                 let mut relative_highlighted_ranges = Vec::new();
                 for search_match in search_matches {
@@ -1222,6 +1248,8 @@ impl Element for TerminalElement {
                     block_below_cursor_element,
                     base_text_style: text_style,
                     content_mode,
+                    command_markers,
+                    images,
                 }
             },
         )
@@ -1302,6 +1330,25 @@ impl Element for TerminalElement {
                         rect.paint(origin, &layout.dimensions, window);
                     }
 
+                    let marker_width = px(3.);
+                    for (line, color) in &layout.command_markers {
+                        let marker_origin = point(
+                            bounds.origin.x + (layout.gutter - marker_width) / 2.,
+                            origin.y + *line as f32 * layout.dimensions.line_height,
+                        );
+                        window.paint_quad(fill(
+                            Bounds::new(
+                                marker_origin,
+                                size(marker_width, layout.dimensions.line_height),
+                            ),
+                            *color,
+                        ));
+                    }
+
+                    for (line, image) in &layout.images {
+                        paint_image(image, *line, origin, &layout.dimensions, window);
+                    }
+
                     for (relative_highlighted_range, color) in &layout.relative_highlighted_ranges {
                         if let Some((start_y, highlighted_range_lines)) =
                             to_highlighted_range_lines(relative_highlighted_range, layout, origin)
@@ -1552,6 +1599,43 @@ pub fn is_blank(cell: &IndexedCell) -> bool {
     true
 }
 
+/// Paints an image over the cells it covers, fit in them unless it's stretched over them.
+fn paint_image(
+    image: &TerminalImage,
+    line: i32,
+    origin: Point<Pixels>,
+    dimensions: &TerminalBounds,
+    window: &mut Window,
+) {
+    let cells = size(
+        dimensions.cell_width * image.columns as f32,
+        dimensions.line_height * image.rows as f32,
+    );
+    let image_size = if image.stretch {
+        cells
+    } else {
+        let scale = (f32::from(cells.width) / image.width as f32)
+            .min(f32::from(cells.height) / image.height as f32);
+        size(
+            px(image.width as f32 * scale),
+            px(image.height as f32 * scale),
+        )
+    };
+    let image_origin = point(
+        origin.x + dimensions.cell_width * image.origin.column.0 as f32,
+        origin.y + dimensions.line_height * line as f32,
+    );
+    window
+        .paint_image(
+            Bounds::new(image_origin, image_size),
+            Corners::default(),
+            image.image.clone(),
+            0,
+            false,
+        )
+        .log_err();
+}
+
 fn to_highlighted_range_lines(
     range: &RangeInclusive<AlacPoint>,
     layout: &LayoutState,
@@ -11,9 +11,9 @@ use collections::HashMap;
 use db::kvp::KEY_VALUE_STORE;
 use futures::{channel::oneshot, future::join_all};
 use gpui::{
-    Action, AnyView, App, AsyncApp, AsyncWindowContext, Context, Corner, Entity, EventEmitter,
-    FocusHandle, Focusable, IntoElement, ParentElement, Pixels, Render, Styled, Task, WeakEntity,
-    Window, actions,
+    Action, AnyView, App, AsyncApp, AsyncWindowContext, Context, Corner, Entity, EntityId,
+    EventEmitter, FocusHandle, Focusable, IntoElement, Keystroke, ParentElement, Pixels, Render,
+    Styled, Task, WeakEntity, Window, actions,
 };
 use itertools::Itertools;
 use project::{Fs, Project};
@@ -31,10 +31,12 @@ use workspace::{
     ActivatePaneUp, ActivatePreviousPane, DraggedTab, ItemId, MoveItemToPane,
     MoveItemToPaneInDirection, MovePaneDown, MovePaneLeft, MovePaneRight, MovePaneUp, Pane,
     PaneGroup, SplitDirection, SplitDown, SplitLeft, SplitMode, SplitRight, SplitUp, SwapPaneDown,
-    SwapPaneLeft, SwapPaneRight, SwapPaneUp, ToggleZoom, Workspace,
+    SwapPaneLeft, SwapPaneRight, SwapPaneUp, Toast, ToggleZoom, Workspace,
     dock::{DockPosition, Panel, PanelEvent, PanelHandle},
     item::SerializableItem,
-    move_active_item, pane,
+    move_active_item,
+    notifications::NotificationId,
+    pane,
 };
 
 use anyhow::{Result, anyhow};
@@ -48,7 +50,10 @@ actions!(
         /// Toggles the terminal panel.
         Toggle,
         /// Toggles focus on the terminal panel.
-        ToggleFocus
+        ToggleFocus,
+        /// Toggles mirroring the input typed into a terminal to the terminals in the
+        /// other panes of the terminal panel.
+        ToggleBroadcastInput
     ]
 );
 
@@ -69,6 +74,11 @@ pub fn init(cx: &mut App) {
                     }
                 }
             });
+            workspace.register_action(|workspace, _: &ToggleBroadcastInput, _window, cx| {
+                if let Some(terminal_panel) = workspace.panel::<TerminalPanel>(cx) {
+                    terminal_panel.update(cx, TerminalPanel::toggle_broadcast_input);
+                }
+            });
         },
     )
     .detach();
@@ -87,6 +97,28 @@ pub struct TerminalPanel {
     assistant_enabled: bool,
     assistant_tab_bar_button: Option<AnyView>,
     active: bool,
+    broadcast_input: bool,
+}
+
+/// Input typed into a terminal, which is mirrored to the terminals in the other panes while
+/// the terminal panel broadcasts input.
+#[derive(Clone, Debug)]
+pub(crate) enum BroadcastInput {
+    Keystroke(Keystroke),
+    Text(String),
+    Paste(String),
+}
+
+impl BroadcastInput {
+    pub(crate) fn write_to(&self, terminal: &mut Terminal, cx: &App) {
+        match self {
+            Self::Keystroke(keystroke) => {
+                terminal.try_keystroke(keystroke, TerminalSettings::get_global(cx).option_as_meta);
+            }
+            Self::Text(text) => terminal.input(text.clone().into_bytes()),
+            Self::Paste(text) => terminal.paste(text),
+        }
+    }
 }
 
 impl TerminalPanel {
@@ -107,6 +139,7 @@ impl TerminalPanel {
             assistant_enabled: false,
             assistant_tab_bar_button: None,
             active: false,
+            broadcast_input: false,
         };
         terminal_panel.apply_tab_bar_buttons(&terminal_panel.active_pane, cx);
         terminal_panel
@@ -133,12 +166,53 @@ impl TerminalPanel {
         }
     }
 
+    pub fn broadcasts_input(&self) -> bool {
+        self.broadcast_input
+    }
+
+    pub fn toggle_broadcast_input(&mut self, cx: &mut Context<Self>) {
+        self.broadcast_input = !self.broadcast_input;
+        for pane in self.center.panes() {
+            pane.update(cx, |_, cx| cx.notify());
+        }
+        cx.notify();
+    }
+
+    /// The terminals that input typed into the terminal shown by `source` is mirrored to:
+    /// the ones shown in the other panes, if input is broadcast and `source` is shown in one
+    /// of the panes.
+    ///
+    /// `source` isn't read, so that this can be called while it's being updated.
+    pub(crate) fn broadcast_targets(&self, source: EntityId, cx: &App) -> Vec<Entity<Terminal>> {
+        if !self.broadcast_input {
+            return Vec::new();
+        }
+        let terminal_views = self
+            .center
+            .panes()
+            .into_iter()
+            .filter_map(|pane| pane.read(cx).active_item()?.downcast::<TerminalView>())
+            .collect::<Vec<_>>();
+        if !terminal_views
+            .iter()
+            .any(|terminal_view| terminal_view.entity_id() == source)
+        {
+            return Vec::new();
+        }
+        terminal_views
+            .into_iter()
+            .filter(|terminal_view| terminal_view.entity_id() != source)
+            .map(|terminal_view| terminal_view.read(cx).terminal().clone())
+            .collect()
+    }
+
     pub(crate) fn apply_tab_bar_buttons(
         &self,
         terminal_pane: &Entity<Pane>,
         cx: &mut Context<Self>,
     ) {
         let assistant_tab_bar_button = self.assistant_tab_bar_button.clone();
+        let terminal_panel = cx.entity().downgrade();
         terminal_pane.update(cx, |pane, cx| {
             pane.set_render_tab_bar_buttons(cx, move |pane, window, cx| {
                 let split_context = pane
@@ -187,6 +261,36 @@ impl TerminalPanel {
                             }),
                     )
                     .children(assistant_tab_bar_button.clone())
+                    .when_some(
+                        terminal_panel.upgrade().filter(|terminal_panel| {
+                            let terminal_panel = terminal_panel.read(cx);
+                            terminal_panel.broadcast_input
+                                || terminal_panel.center.panes().len() > 1
+                        }),
+                        |this, terminal_panel| {
+                            let broadcast_input = terminal_panel.read(cx).broadcast_input;
+                            this.child(
+                                IconButton::new("toggle_broadcast_input", IconName::Keyboard)
+                                    .icon_size(IconSize::Small)
+                                    .toggle_state(broadcast_input)
+                                    .on_click(move |_, _, cx| {
+                                        terminal_panel
+                                            .update(cx, TerminalPanel::toggle_broadcast_input);
+                                    })
+                                    .tooltip(move |_window, cx| {
+                                        Tooltip::for_action(
+                                            if broadcast_input {
+                                                "Stop Broadcasting Input"
+                                            } else {
+                                                "Broadcast Input to All Panes"
+                                            },
+                                            &ToggleBroadcastInput,
+                                            cx,
+                                        )
+                                    }),
+                            )
+                        },
+                    )
                     .child(
                         PopoverMenu::new("terminal-pane-tab-bar-split")
                             .trigger_with_tooltip(
@@ -562,6 +666,9 @@ impl TerminalPanel {
             return Task::ready(Err(anyhow!("cannot spawn tasks as a guest")));
         }
 
+        let lsp_store = project.lsp_store();
+        let is_via_remote_server = project.is_via_remote_server();
+
         let remote_client = project.remote_client();
         let is_windows = project.path_style(cx).is_windows();
         let remote_shell = remote_client
@@ -578,6 +685,29 @@ impl TerminalPanel {
 
         let task = prepare_task_for_spawn(task, &shell, is_windows);
 
+        if !task.problem_matchers.is_empty() {
+            if is_via_remote_server {
+                struct ProblemMatchersUnsupported;
+                workspace.update(cx, |workspace, cx| {
+                    workspace.show_toast(
+                        Toast::new(
+                            NotificationId::unique::<ProblemMatchersUnsupported>(),
+                            format!(
+                                "Problem matchers of task '{}' are not supported in remote projects yet",
+                                task.label
+                            ),
+                        )
+                        .autohide(),
+                        cx,
+                    );
+                });
+            } else {
+                lsp_store.update(cx, |lsp_store, cx| {
+                    lsp_store.clear_task_diagnostics(&task.id, cx).log_err();
+                });
+            }
+        }
+
         if task.allow_concurrent_runs && task.use_new_terminal {
             return self.spawn_in_new_terminal(task, window, cx);
         }
@@ -1779,6 +1909,127 @@ mod tests {
         );
     }
 
+    #[gpui::test]
+    async fn test_broadcast_input(cx: &mut TestAppContext) {
+        init_test(cx);
+
+        let fs = FakeFs::new(cx.executor());
+        let project = Project::test(fs, [], cx).await;
+        let window_handle =
+            cx.add_window(|window, cx| MultiWorkspace::test_new(project.clone(), window, cx));
+
+        let (terminal_panel, terminal_views) = window_handle
+            .update(cx, |multi_workspace, window, cx| {
+                let workspace = multi_workspace.workspace().clone();
+                let terminal_panel = workspace.update(cx, |workspace, cx| {
+                    let terminal_panel = cx.new(|cx| TerminalPanel::new(workspace, window, cx));
+                    workspace.add_panel(terminal_panel.clone(), window, cx);
+                    terminal_panel
+                });
+                let terminal_views = (0..3)
+                    .map(|_| {
+                        let terminal = cx.new(|cx| {
+                            terminal::TerminalBuilder::new_display_only(
+                                Default::default(),
+                                terminal::terminal_settings::AlternateScroll::On,
+                                None,
+                                0,
+                                cx.background_executor(),
+                                util::paths::PathStyle::local(),
+                            )
+                            .unwrap()
+                            .subscribe(cx)
+                        });
+                        cx.new(|cx| {
+                            TerminalView::new(
+                                terminal,
+                                workspace.downgrade(),
+                                None,
+                                project.downgrade(),
+                                window,
+                                cx,
+                            )
+                        })
+                    })
+                    .collect::<Vec<_>>();
+                terminal_panel.update(cx, |terminal_panel, cx| {
+                    let first_pane = terminal_panel.active_pane.clone();
+                    let second_pane = new_terminal_pane(
+                        workspace.downgrade(),
+                        project.clone(),
+                        false,
+                        window,
+                        cx,
+                    );
+                    terminal_panel.center.split(
+                        &first_pane,
+                        &second_pane,
+                        SplitDirection::Right,
+                        cx,
+                    );
+                    first_pane.update(cx, |pane, cx| {
+                        pane.add_item(
+                            Box::new(terminal_views[0].clone()),
+                            true,
+                            true,
+                            None,
+                            window,
+                            cx,
+                        )
+                    });
+                    second_pane.update(cx, |pane, cx| {
+                        pane.add_item(
+                            Box::new(terminal_views[1].clone()),
+                            true,
+                            true,
+                            None,
+                            window,
+                            cx,
+                        );
+                        pane.add_item(
+                            Box::new(terminal_views[2].clone()),
+                            true,
+                            true,
+                            None,
+                            window,
+                            cx,
+                        );
+                    });
+                });
+                (terminal_panel, terminal_views)
+            })
+            .unwrap();
+
+        let type_command = |cx: &mut TestAppContext| {
+            terminal_views[0].update(cx, |terminal_view, cx| {
+                terminal_view.commit_text("uptime", cx);
+                terminal_view.process_keystroke(&Keystroke::parse("enter").unwrap(), cx);
+            });
+        };
+        let take_input_log = |ix: usize, cx: &mut TestAppContext| {
+            terminal_views[ix].update(cx, |terminal_view, cx| {
+                terminal_view
+                    .terminal()
+                    .update(cx, |terminal, _| terminal.take_input_log())
+            })
+        };
+
+        type_command(cx);
+        assert_eq!(take_input_log(0, cx), [b"uptime".to_vec(), b"\r".to_vec()]);
+        assert!(take_input_log(2, cx).is_empty());
+
+        terminal_panel.update(cx, TerminalPanel::toggle_broadcast_input);
+        type_command(cx);
+        assert_eq!(take_input_log(0, cx), [b"uptime".to_vec(), b"\r".to_vec()]);
+        // Only the terminals shown in the other panes get the input.
+        assert_eq!(take_input_log(2, cx), [b"uptime".to_vec(), b"\r".to_vec()]);
+        assert!(take_input_log(1, cx).is_empty());
+
+        terminal_panel.update(cx, TerminalPanel::toggle_broadcast_input);
+        type_command(cx);
+        assert!(take_input_log(2, cx).is_empty());
+    }
+
     #[cfg(unix)]
     #[test]
     fn test_prepare_script_like_task() {
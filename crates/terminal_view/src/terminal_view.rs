@@ -5,13 +5,14 @@ mod terminal_path_like_target;
 pub mod terminal_scrollbar;
 mod terminal_slash_command;
 
+use anyhow::Context as _;
 use assistant_slash_command::SlashCommandRegistry;
 use editor::{Editor, EditorSettings, actions::SelectAll, blink_manager::BlinkManager};
 use gpui::{
     Action, AnyElement, App, ClipboardEntry, DismissEvent, Entity, EventEmitter, ExternalPaths,
     FocusHandle, Focusable, KeyContext, KeyDownEvent, Keystroke, MouseButton, MouseDownEvent,
-    Pixels, Point, Render, ScrollWheelEvent, Styled, Subscription, Task, WeakEntity, actions,
-    anchored, deferred, div,
+    PathPromptOptions, Pixels, Point, Render, ScrollWheelEvent, Styled, Subscription, Task,
+    WeakEntity, actions, anchored, deferred, div,
 };
 use itertools::Itertools;
 use menu;
@@ -22,18 +23,20 @@ use serde::Deserialize;
 use settings::{Settings, SettingsStore, TerminalBlink, WorkingDirectory};
 use std::{
     any::Any,
-    cmp,
+    cmp, mem,
     ops::{Range, RangeInclusive},
     path::{Path, PathBuf},
     rc::Rc,
     sync::Arc,
-    time::Duration,
+    time::{Duration, SystemTime},
 };
 use task::TaskId;
 use terminal::{
-    Clear, Copy, Event, HoveredWord, MaybeNavigationTarget, Paste, ScrollLineDown, ScrollLineUp,
-    ScrollPageDown, ScrollPageUp, ScrollToBottom, ScrollToTop, ShowCharacterPalette, TaskState,
-    TaskStatus, Terminal, TerminalBounds, ToggleViMode,
+    Asciicast, Clear, Copy, CopyCommandOutput, Event, HoveredWord, MaybeNavigationTarget, Paste,
+    RerunCommand, ScrollLineDown, ScrollLineUp, ScrollPageDown, ScrollPageUp, ScrollToBottom,
+    ScrollToNextPrompt, ScrollToPreviousPrompt, ScrollToTop, SelectCommandOutput,
+    ShowCharacterPalette, TaskState, TaskStatus, Terminal, TerminalBounds, TerminalBuilder,
+    ToggleViMode,
     alacritty_terminal::{
         index::Point as AlacPoint,
         term::{TermMode, point_to_viewport, search::RegexSearch},
@@ -41,7 +44,7 @@ use terminal::{
     terminal_settings::{CursorShape, TerminalSettings},
 };
 use terminal_element::TerminalElement;
-use terminal_panel::TerminalPanel;
+use terminal_panel::{BroadcastInput, TerminalPanel};
 use terminal_path_like_target::{hover_path_like_target, open_path_like_target};
 use terminal_scrollbar::TerminalScrollHandle;
 use terminal_slash_command::TerminalSlashCommand;
@@ -52,11 +55,12 @@ use ui::{
 };
 use util::ResultExt;
 use workspace::{
-    CloseActiveItem, DraggedSelection, DraggedTab, NewCenterTerminal, NewTerminal, Pane,
-    ToolbarItemLocation, Workspace, WorkspaceId, delete_unloaded_items,
+    CloseActiveItem, DirectoryLister, DraggedSelection, DraggedTab, NewCenterTerminal, NewTerminal,
+    Pane, Toast, ToolbarItemLocation, Workspace, WorkspaceId, delete_unloaded_items,
     item::{
         BreadcrumbText, Item, ItemEvent, SerializableItem, TabContentParams, TabTooltipContent,
     },
+    notifications::{NotificationId, NotifyTaskExt},
     register_serializable_item,
     searchable::{
         Direction, SearchEvent, SearchOptions, SearchToken, SearchableItem, SearchableItemHandle,
@@ -69,6 +73,8 @@ struct ImeState {
 }
 
 const CURSOR_BLINK_INTERVAL: Duration = Duration::from_millis(500);
+const TASK_DIAGNOSTICS_DEBOUNCE: Duration = Duration::from_millis(100);
+const SCROLLBACK_SAVE_INTERVAL: Duration = Duration::from_secs(30);
 
 /// Event to transmit the scroll from the element to the view
 #[derive(Clone, Debug, PartialEq)]
@@ -89,6 +95,10 @@ actions!(
     [
         /// Reruns the last executed task in the terminal.
         RerunTask,
+        /// Starts recording the terminal session to an asciicast file, or stops recording it.
+        ToggleRecording,
+        /// Replays a terminal session recorded to an asciicast file, in a read-only terminal.
+        ReplayRecording,
     ]
 );
 
@@ -105,6 +115,7 @@ pub fn init(cx: &mut App) {
 
     cx.observe_new(|workspace: &mut Workspace, _window, _cx| {
         workspace.register_action(TerminalView::deploy);
+        workspace.register_action(TerminalView::replay_recording);
     })
     .detach();
     SlashCommandRegistry::global(cx).register_command(TerminalSlashCommand, true);
@@ -135,9 +146,11 @@ pub struct TerminalView {
     mode: TerminalMode,
     blinking_terminal_enabled: bool,
     needs_serialize: bool,
+    scrollback_changed: bool,
     custom_title: Option<String>,
     hover: Option<HoverTarget>,
     hover_tooltip_update: Task<()>,
+    task_diagnostics_update: Task<()>,
     workspace_id: Option<WorkspaceId>,
     show_breadcrumbs: bool,
     block_below_cursor: Option<Rc<BlockProperties>>,
@@ -147,6 +160,7 @@ pub struct TerminalView {
     self_handle: WeakEntity<Self>,
     rename_editor: Option<Entity<Editor>>,
     rename_editor_subscription: Option<Subscription>,
+    _save_scrollback: Task<()>,
     _subscriptions: Vec<Subscription>,
     _terminal_subscriptions: Vec<Subscription>,
 }
@@ -222,6 +236,69 @@ impl TerminalView {
         .detach_and_log_err(cx);
     }
 
+    /// Opens a recorded terminal session and replays it in a new, read-only terminal.
+    fn replay_recording(
+        workspace: &mut Workspace,
+        _: &ReplayRecording,
+        window: &mut Window,
+        cx: &mut Context<Workspace>,
+    ) {
+        let fs = workspace.app_state().fs.clone();
+        let paths = workspace.prompt_for_open_path(
+            PathPromptOptions {
+                files: true,
+                directories: false,
+                multiple: false,
+                prompt: Some("Replay".into()),
+            },
+            DirectoryLister::Local(workspace.project().clone(), fs.clone()),
+            window,
+            cx,
+        );
+        cx.spawn_in(window, async move |workspace, cx| {
+            let Some(path) = paths.await.ok().flatten().and_then(|mut paths| paths.pop()) else {
+                return anyhow::Ok(());
+            };
+            let content = fs.load(&path).await?;
+            let asciicast = Asciicast::parse(&content)
+                .with_context(|| format!("failed to read the recording {path:?}"))?;
+            workspace.update_in(cx, |workspace, window, cx| {
+                let project = workspace.project().clone();
+                let settings = TerminalSettings::get_global(cx);
+                let builder = TerminalBuilder::new_display_only(
+                    settings.cursor_shape,
+                    settings.alternate_scroll,
+                    settings.max_scroll_history_lines,
+                    window.window_handle().window_id().as_u64(),
+                    cx.background_executor(),
+                    project.read(cx).path_style(cx),
+                )?;
+                let terminal = cx.new(|cx| builder.subscribe(cx));
+                let title = path
+                    .file_name()
+                    .map(|file_name| format!("Replay: {}", file_name.to_string_lossy()));
+                let terminal_view = cx.new(|cx| {
+                    let mut terminal_view = TerminalView::new(
+                        terminal.clone(),
+                        workspace.weak_handle(),
+                        None,
+                        project.downgrade(),
+                        window,
+                        cx,
+                    );
+                    terminal_view.set_custom_title(title, cx);
+                    terminal_view
+                });
+                terminal
+                    .update(cx, |terminal, cx| terminal.replay(asciicast, cx))
+                    .detach();
+                workspace.add_item_to_active_pane(Box::new(terminal_view), None, true, window, cx);
+                anyhow::Ok(())
+            })?
+        })
+        .detach_and_notify_err(cx.entity().downgrade(), window, cx);
+    }
+
     pub fn new(
         terminal: Entity<Terminal>,
         workspace: WeakEntity<Workspace>,
@@ -267,6 +344,7 @@ impl TerminalView {
             focus_out,
             cx.observe(&blink_manager, |_, _, cx| cx.notify()),
             cx.observe_global::<SettingsStore>(Self::settings_changed),
+            cx.on_app_quit(Self::save_scrollback),
         ];
 
         Self {
@@ -281,6 +359,7 @@ impl TerminalView {
             blinking_terminal_enabled: false,
             hover: None,
             hover_tooltip_update: Task::ready(()),
+            task_diagnostics_update: Task::ready(()),
             mode: TerminalMode::Standalone,
             workspace_id,
             show_breadcrumbs: TerminalSettings::get_global(cx).toolbar.breadcrumbs,
@@ -288,16 +367,101 @@ impl TerminalView {
             scroll_top: Pixels::ZERO,
             scroll_handle,
             needs_serialize: false,
+            scrollback_changed: false,
             custom_title: None,
             ime_state: None,
             self_handle: cx.entity().downgrade(),
             rename_editor: None,
             rename_editor_subscription: None,
+            _save_scrollback: cx.spawn(async move |this, cx| {
+                loop {
+                    cx.background_executor()
+                        .timer(SCROLLBACK_SAVE_INTERVAL)
+                        .await;
+                    let Ok(save) = this.update(cx, |this, cx| this.save_changed_scrollback(cx))
+                    else {
+                        break;
+                    };
+                    save.await;
+                }
+            }),
             _subscriptions: subscriptions,
             _terminal_subscriptions: terminal_subscriptions,
         }
     }
 
+    /// Saves the scrollback if the terminal has output since it was last saved, so that it
+    /// survives Zed exiting without quitting, e.g. when it crashes.
+    fn save_changed_scrollback(&mut self, cx: &mut Context<Self>) -> Task<()> {
+        if TerminalSettings::get_global(cx).restored_scrollback_lines == 0
+            || !mem::take(&mut self.scrollback_changed)
+        {
+            return Task::ready(());
+        }
+        self.save_scrollback(cx)
+    }
+
+    /// Saves the scrollback of the terminal, to restore it above the prompt of the shell it's
+    /// deserialized with in the next session.
+    fn save_scrollback(&mut self, cx: &mut Context<Self>) -> Task<()> {
+        let terminal = self.terminal.read(cx);
+        let Some(workspace_id) = self.workspace_id else {
+            return Task::ready(());
+        };
+        if terminal.task().is_some() || !matches!(self.mode, TerminalMode::Standalone) {
+            return Task::ready(());
+        }
+
+        let max_lines = TerminalSettings::get_global(cx).restored_scrollback_lines;
+        let scrollback = terminal.serialize_scrollback(max_lines);
+        let item_id = cx.entity_id().as_u64();
+        cx.background_spawn(async move {
+            TERMINAL_DB
+                .save_scrollback(item_id, workspace_id, scrollback)
+                .await
+                .log_err();
+        })
+    }
+
+    /// Publishes the problems matched in the task output as diagnostics, once the output
+    /// settles down for a bit.
+    fn update_task_diagnostics(&mut self, cx: &mut Context<Self>) {
+        self.task_diagnostics_update = cx.spawn(async move |terminal_view, cx| {
+            cx.background_executor()
+                .timer(TASK_DIAGNOSTICS_DEBOUNCE)
+                .await;
+            terminal_view
+                .update(cx, |terminal_view, cx| {
+                    let Some(project) = terminal_view.project.upgrade() else {
+                        return;
+                    };
+                    // Remote projects notify that problem matchers aren't supported when
+                    // spawning the task.
+                    if !project.read(cx).is_local() {
+                        return;
+                    }
+                    let terminal = terminal_view.terminal.read(cx);
+                    let Some(task) = terminal.task() else {
+                        return;
+                    };
+                    let task_id = task.spawned_task.id.clone();
+                    let cwd = task
+                        .spawned_task
+                        .cwd
+                        .clone()
+                        .or_else(|| terminal.working_directory());
+                    let task_problems = terminal.task_problems().to_vec();
+                    let lsp_store = project.read(cx).lsp_store();
+                    lsp_store.update(cx, |lsp_store, cx| {
+                        lsp_store
+                            .update_task_diagnostics(&task_id, cwd.as_deref(), task_problems, cx)
+                            .log_err();
+                    });
+                })
+                .ok();
+        });
+    }
+
     /// Enable 'embedded' mode where the terminal displays the full content with an optional limit of lines.
     pub fn set_embedded_mode(
         &mut self,
@@ -373,6 +537,25 @@ impl TerminalView {
             self.terminal.update(cx, |term, _| {
                 term.input(text.to_string().into_bytes());
             });
+            self.broadcast_input(BroadcastInput::Text(text.to_string()), cx);
+        }
+    }
+
+    /// Mirrors the input to the terminals in the other panes of the terminal panel, if it
+    /// broadcasts input.
+    fn broadcast_input(&self, input: BroadcastInput, cx: &mut Context<Self>) {
+        let Some(terminal_panel) = self
+            .workspace
+            .upgrade()
+            .and_then(|workspace| workspace.read(cx).panel::<TerminalPanel>(cx))
+        else {
+            return;
+        };
+        let terminals = terminal_panel
+            .read(cx)
+            .broadcast_targets(cx.entity_id(), cx);
+        for terminal in terminals {
+            terminal.update(cx, |terminal, cx| input.write_to(terminal, cx));
         }
     }
 
@@ -506,6 +689,12 @@ impl TerminalView {
             .selection_text
             .as_ref()
             .is_some_and(|text| !text.is_empty());
+        let has_finished_command = self
+            .terminal
+            .read(cx)
+            .shell_commands()
+            .any(|command| command.has_finished());
+        let is_recording = self.terminal.read(cx).is_recording();
         let context_menu = ContextMenu::build(window, cx, |menu, _, _| {
             menu.context(self.focus_handle.clone())
                 .action("New Terminal", Box::new(NewTerminal::default()))
@@ -514,6 +703,20 @@ impl TerminalView {
                 .action("Paste", Box::new(Paste))
                 .action("Select All", Box::new(SelectAll))
                 .action("Clear", Box::new(Clear))
+                .action(
+                    if is_recording {
+                        "Stop Recording"
+                    } else {
+                        "Record Session…"
+                    },
+                    Box::new(ToggleRecording),
+                )
+                .when(has_finished_command, |menu| {
+                    menu.separator()
+                        .action("Select Command Output", Box::new(SelectCommandOutput))
+                        .action("Copy Command Output", Box::new(CopyCommandOutput))
+                        .action("Rerun Command", Box::new(RerunCommand))
+                })
                 .when(assistant_enabled, |menu| {
                     menu.separator()
                         .action("Inline Assist", Box::new(InlineAssist::default()))
@@ -622,6 +825,77 @@ impl TerminalView {
         window.dispatch_action(Box::new(task), cx);
     }
 
+    fn toggle_recording(
+        &mut self,
+        _: &ToggleRecording,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        if let Some(result) = self
+            .terminal
+            .update(cx, |terminal, _| terminal.stop_recording())
+        {
+            cx.emit(ItemEvent::UpdateTab);
+            self.workspace
+                .update(cx, |workspace, cx| match result {
+                    Ok(path) => {
+                        struct RecordingSaved;
+                        workspace.show_toast(
+                            Toast::new(
+                                NotificationId::unique::<RecordingSaved>(),
+                                format!("Saved the terminal recording to {}", path.display()),
+                            ),
+                            cx,
+                        );
+                    }
+                    Err(error) => workspace.show_error(&error, cx),
+                })
+                .ok();
+            return;
+        }
+
+        let Some(path) = self
+            .workspace
+            .update(cx, |workspace, cx| {
+                let timestamp = SystemTime::now()
+                    .duration_since(SystemTime::UNIX_EPOCH)
+                    .map_or(0, |timestamp| timestamp.as_secs());
+                workspace.prompt_for_new_path(
+                    DirectoryLister::Local(
+                        workspace.project().clone(),
+                        workspace.app_state().fs.clone(),
+                    ),
+                    Some(format!("terminal-{timestamp}.cast")),
+                    window,
+                    cx,
+                )
+            })
+            .log_err()
+        else {
+            return;
+        };
+        cx.spawn(async move |this, cx| {
+            let Some(path) = path.await.ok().flatten().into_iter().flatten().next() else {
+                return;
+            };
+            this.update(cx, |this, cx| {
+                match this
+                    .terminal
+                    .update(cx, |terminal, _| terminal.start_recording(&path))
+                {
+                    Ok(()) => cx.emit(ItemEvent::UpdateTab),
+                    Err(error) => {
+                        this.workspace
+                            .update(cx, |workspace, cx| workspace.show_error(&error, cx))
+                            .ok();
+                    }
+                }
+            })
+            .ok();
+        })
+        .detach();
+    }
+
     fn clear(&mut self, _: &Clear, _: &mut Window, cx: &mut Context<Self>) {
         self.scroll_top = px(0.);
         self.terminal.update(cx, |term, _| term.clear());
@@ -748,6 +1022,54 @@ impl TerminalView {
         cx.notify();
     }
 
+    fn scroll_to_previous_prompt(
+        &mut self,
+        _: &ScrollToPreviousPrompt,
+        _: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        self.terminal
+            .update(cx, |term, _| term.scroll_to_previous_prompt());
+        cx.notify();
+    }
+
+    fn scroll_to_next_prompt(
+        &mut self,
+        _: &ScrollToNextPrompt,
+        _: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        self.terminal
+            .update(cx, |term, _| term.scroll_to_next_prompt());
+        cx.notify();
+    }
+
+    fn select_command_output(
+        &mut self,
+        _: &SelectCommandOutput,
+        _: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        self.terminal
+            .update(cx, |term, _| term.select_command_output());
+        cx.notify();
+    }
+
+    fn copy_command_output(
+        &mut self,
+        _: &CopyCommandOutput,
+        _: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        self.terminal
+            .update(cx, |term, cx| term.copy_command_output(cx));
+    }
+
+    fn rerun_command(&mut self, _: &RerunCommand, _: &mut Window, cx: &mut Context<Self>) {
+        self.terminal.update(cx, |term, _| term.rerun_command());
+        cx.notify();
+    }
+
     fn toggle_vi_mode(&mut self, _: &ToggleViMode, _: &mut Window, cx: &mut Context<Self>) {
         self.terminal.update(cx, |term, _| term.toggle_vi_mode());
         cx.notify();
@@ -824,6 +1146,7 @@ impl TerminalView {
         if let Some(text) = clipboard.text() {
             self.terminal
                 .update(cx, |terminal, _cx| terminal.paste(&text));
+            self.broadcast_input(BroadcastInput::Paste(text), cx);
         }
     }
 
@@ -849,6 +1172,7 @@ impl TerminalView {
         self.terminal.update(cx, |term, _| {
             term.input(text.0.to_string().into_bytes());
         });
+        self.broadcast_input(BroadcastInput::Text(text.0.clone()), cx);
     }
 
     fn send_keystroke(&mut self, text: &SendKeystroke, _: &mut Window, cx: &mut Context<Self>) {
@@ -1002,6 +1326,7 @@ fn subscribe_for_terminal_events(
 
             match event {
                 Event::Wakeup => {
+                    terminal_view.scrollback_changed = true;
                     cx.notify();
                     cx.emit(Event::Wakeup);
                     cx.emit(ItemEvent::UpdateTab);
@@ -1097,6 +1422,7 @@ fn subscribe_for_terminal_events(
                     window.invalidate_character_coordinates();
                     cx.emit(SearchEvent::ActiveMatchChanged)
                 }
+                Event::TaskProblemsChanged => terminal_view.update_task_diagnostics(cx),
             }
         },
     );
@@ -1149,6 +1475,8 @@ impl TerminalView {
 
         if handled && vi_mode_enabled {
             cx.notify();
+        } else if handled {
+            self.broadcast_input(BroadcastInput::Keystroke(keystroke.clone()), cx);
         }
 
         handled
@@ -1231,10 +1559,16 @@ impl Render for TerminalView {
             .on_action(cx.listener(TerminalView::scroll_page_down))
             .on_action(cx.listener(TerminalView::scroll_to_top))
             .on_action(cx.listener(TerminalView::scroll_to_bottom))
+            .on_action(cx.listener(TerminalView::scroll_to_previous_prompt))
+            .on_action(cx.listener(TerminalView::scroll_to_next_prompt))
+            .on_action(cx.listener(TerminalView::select_command_output))
+            .on_action(cx.listener(TerminalView::copy_command_output))
+            .on_action(cx.listener(TerminalView::rerun_command))
             .on_action(cx.listener(TerminalView::toggle_vi_mode))
             .on_action(cx.listener(TerminalView::show_character_palette))
             .on_action(cx.listener(TerminalView::select_all))
             .on_action(cx.listener(TerminalView::rerun_task))
+            .on_action(cx.listener(TerminalView::toggle_recording))
             .on_action(cx.listener(TerminalView::rename_terminal))
             .on_key_down(cx.listener(Self::key_down))
             .on_mouse_down(
@@ -1348,6 +1682,7 @@ impl Item for TerminalView {
                     }
                 }
             },
+            None if terminal.is_recording() => (IconName::Circle, Color::Error, None),
             None => (IconName::Terminal, Color::Muted, None),
         };
 
@@ -1752,7 +2087,7 @@ impl SerializableItem for TerminalView {
         cx: &mut App,
     ) -> Task<anyhow::Result<Entity<Self>>> {
         window.spawn(cx, async move |cx| {
-            let (cwd, custom_title) = cx
+            let (cwd, custom_title, scrollback) = cx
                 .update(|_window, cx| {
                     let from_db = TERMINAL_DB
                         .get_working_directory(item_id, workspace_id)
@@ -1773,14 +2108,28 @@ impl SerializableItem for TerminalView {
                         .log_err()
                         .flatten()
                         .filter(|title| !title.trim().is_empty());
-                    (cwd, custom_title)
+                    let scrollback =
+                        if TerminalSettings::get_global(cx).restored_scrollback_lines > 0 {
+                            TERMINAL_DB
+                                .get_scrollback(item_id, workspace_id)
+                                .log_err()
+                                .flatten()
+                        } else {
+                            None
+                        };
+                    (cwd, custom_title, scrollback)
                 })
                 .ok()
-                .unwrap_or((None, None));
+                .unwrap_or((None, None, None));
 
             let terminal = project
                 .update(cx, |project, cx| project.create_terminal_shell(cwd, cx))
                 .await?;
+            if let Some(scrollback) = scrollback {
+                terminal.update(cx, |terminal, cx| {
+                    terminal.restore_scrollback(&scrollback, cx)
+                });
+            }
             cx.update(|window, cx| {
                 cx.new(|cx| {
                     let mut view = TerminalView::new(
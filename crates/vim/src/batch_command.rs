@@ -0,0 +1,620 @@
+use std::mem;
+
+use anyhow::{Context as _, Result};
+use collections::HashSet;
+use editor::Editor;
+use futures::{channel::oneshot, future::join_all};
+use gpui::{Action, AsyncWindowContext, Context, Entity, Focusable, Task, WeakEntity, Window};
+use itertools::Itertools;
+use language::{Buffer, DiagnosticSeverity, Point};
+use project::ProjectPath;
+use search::ProjectSearchView;
+use text::ToPoint as _;
+use util::paths::PathMatcher;
+use workspace::{
+    Pane, Toast, Workspace,
+    notifications::{DetachAndPromptErr, NotificationId, NotifyResultExt},
+};
+
+use crate::{
+    Vim, VimAddon,
+    command::{CommandRange, command_interceptor},
+};
+
+/// Runs an ex command in each buffer, window, quickfix entry or argument.
+#[derive(Clone, Debug, PartialEq, Action)]
+#[action(namespace = vim, no_json, no_register)]
+pub(crate) struct BatchCommand {
+    scope: BatchScope,
+    command: String,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum BatchScope {
+    /// `:bufdo`, every open buffer.
+    Buffers,
+    /// `:windo`, the active item of every pane.
+    Windows,
+    /// `:cdo`, every entry in the quickfix list.
+    QuickfixEntries,
+    /// `:cfdo`, every file in the quickfix list.
+    QuickfixFiles,
+    /// `:argdo`, every file in the argument list.
+    Arguments,
+}
+
+/// Sets the files `:argdo` runs in, or shows them if no files are given.
+#[derive(Clone, Debug, PartialEq, Action)]
+#[action(namespace = vim, no_json, no_register)]
+pub(crate) struct VimArgs {
+    pub patterns: Vec<String>,
+}
+
+enum Target {
+    Editor(Entity<Editor>),
+    Pane(Entity<Pane>),
+    Path(ProjectPath),
+    Location(Entity<Buffer>, language::Anchor),
+}
+
+pub(crate) fn register(editor: &mut Editor, cx: &mut Context<Vim>) {
+    Vim::action(editor, cx, |vim, action: &BatchCommand, window, cx| {
+        vim.batch_command(action, window, cx)
+    });
+
+    Vim::action(editor, cx, |vim, action: &VimArgs, window, cx| {
+        let Some(workspace) = vim.workspace(window, cx) else {
+            return;
+        };
+        workspace.update(cx, |workspace, cx| {
+            if action.patterns.is_empty() {
+                show_arg_list(workspace, cx);
+            } else {
+                set_arg_list(&action.patterns, workspace, cx).notify_err(workspace, cx);
+            }
+        })
+    });
+}
+
+impl BatchCommand {
+    pub(crate) fn parse(query: &str, range: Option<CommandRange>) -> Option<Box<dyn Action>> {
+        let (name, command) = query.split_once(' ')?;
+        let scope = match name {
+            "bufd" | "bufdo" => BatchScope::Buffers,
+            "windo" => BatchScope::Windows,
+            "cdo" => BatchScope::QuickfixEntries,
+            "cfd" | "cfdo" => BatchScope::QuickfixFiles,
+            "argdo" => BatchScope::Arguments,
+            _ => return None,
+        };
+        // In Vim, a range picks the buffers, windows or entries by number, which don't map to
+        // Zed's, so ranged batch commands are rejected instead of running everywhere.
+        let command = command.trim();
+        if range.is_some() || command.is_empty() {
+            return None;
+        }
+        Some(
+            Self {
+                scope,
+                command: command.to_string(),
+            }
+            .boxed_clone(),
+        )
+    }
+}
+
+impl Vim {
+    fn batch_command(
+        &mut self,
+        action: &BatchCommand,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let Some(workspace) = self.workspace(window, cx) else {
+            return;
+        };
+        let targets = workspace.update(cx, |workspace, cx| targets(action.scope, workspace, cx));
+        let intercepted = command_interceptor(&action.command, workspace.downgrade(), cx);
+        let command = action.command.clone();
+        let workspace = workspace.downgrade();
+
+        cx.spawn_in(window, async move |_, cx| {
+            let action = intercepted
+                .await
+                .results
+                .into_iter()
+                .next()
+                .map(|result| result.action)
+                .with_context(|| format!("Not an editor command: {command}"))?;
+            for target in targets.await? {
+                let Some(editor) = open_target(target, &workspace, cx).await? else {
+                    continue;
+                };
+                wait_until_drawn(&editor, &workspace, cx).await?;
+                cx.update(|window, cx| {
+                    editor
+                        .focus_handle(cx)
+                        .dispatch_action(action.as_ref(), window, cx);
+                })?;
+                wait_for_command(&editor, cx).await;
+            }
+            anyhow::Ok(())
+        })
+        .detach_and_prompt_err("Failed to run command", window, cx, |error, _, _| {
+            Some(error.to_string())
+        });
+    }
+}
+
+fn targets(
+    scope: BatchScope,
+    workspace: &Workspace,
+    cx: &mut Context<Workspace>,
+) -> Task<Result<Vec<Target>>> {
+    match scope {
+        BatchScope::Buffers => {
+            let mut buffers = HashSet::default();
+            let editors = workspace
+                .items_of_type::<Editor>(cx)
+                .filter(|editor| {
+                    editor
+                        .read(cx)
+                        .buffer()
+                        .read(cx)
+                        .as_singleton()
+                        .is_some_and(|buffer| buffers.insert(buffer.entity_id()))
+                })
+                .map(Target::Editor)
+                .collect();
+            Task::ready(Ok(editors))
+        }
+        BatchScope::Windows => Task::ready(Ok(workspace
+            .panes()
+            .iter()
+            .cloned()
+            .map(Target::Pane)
+            .collect())),
+        BatchScope::QuickfixEntries => {
+            let locations = quickfix_list(workspace, cx);
+            cx.spawn(async move |_, _| {
+                Ok(locations
+                    .await?
+                    .into_iter()
+                    .map(|(buffer, position)| Target::Location(buffer, position))
+                    .collect())
+            })
+        }
+        BatchScope::QuickfixFiles => {
+            let locations = quickfix_list(workspace, cx);
+            cx.spawn(async move |_, _| {
+                Ok(locations
+                    .await?
+                    .into_iter()
+                    .unique_by(|(buffer, _)| buffer.entity_id())
+                    .map(|(buffer, position)| Target::Location(buffer, position))
+                    .collect())
+            })
+        }
+        BatchScope::Arguments => {
+            let workspace_id = cx.entity_id();
+            let arg_list = Vim::globals(cx)
+                .arg_lists
+                .get(&workspace_id)
+                .cloned()
+                .unwrap_or_default();
+            if arg_list.is_empty() {
+                return Task::ready(Err(anyhow::anyhow!(
+                    "The argument list is empty, set it with :args"
+                )));
+            }
+            Task::ready(Ok(arg_list.into_iter().map(Target::Path).collect()))
+        }
+    }
+}
+
+/// Returns the results of the project search, or the project's errors and warnings if
+/// there's no search, like Vim's quickfix list.
+fn quickfix_list(
+    workspace: &Workspace,
+    cx: &mut Context<Workspace>,
+) -> Task<Result<Vec<(Entity<Buffer>, language::Anchor)>>> {
+    let search = workspace
+        .active_item_as::<ProjectSearchView>(cx)
+        .or_else(|| workspace.items_of_type::<ProjectSearchView>(cx).next());
+    if let Some(search) = search {
+        let locations = search.read(cx).match_locations(cx);
+        if !locations.is_empty() {
+            return Task::ready(Ok(locations));
+        }
+    }
+
+    let project = workspace.project().clone();
+    let paths = project
+        .read(cx)
+        .diagnostic_summaries(false, cx)
+        .filter(|(_, _, summary)| summary.error_count + summary.warning_count > 0)
+        .map(|(path, _, _)| path)
+        .sorted()
+        .dedup()
+        .collect::<Vec<_>>();
+    cx.spawn(async move |_, cx| {
+        let mut locations = Vec::new();
+        for path in paths {
+            let buffer = project
+                .update(cx, |project, cx| project.open_buffer(path, cx))
+                .await?;
+            let snapshot = buffer.read_with(cx, |buffer, _| buffer.snapshot());
+            let mut positions = snapshot
+                .diagnostics_in_range::<_, Point>(0..snapshot.len(), false)
+                .filter(|entry| {
+                    entry.diagnostic.is_primary
+                        && entry.diagnostic.severity <= DiagnosticSeverity::WARNING
+                })
+                .map(|entry| entry.range.start)
+                .collect::<Vec<_>>();
+            positions.sort();
+            locations.extend(
+                positions
+                    .into_iter()
+                    .map(|position| (buffer.clone(), snapshot.anchor_before(position))),
+            );
+        }
+        anyhow::ensure!(
+            !locations.is_empty(),
+            "The quickfix list is empty, run a project search first"
+        );
+        Ok(locations)
+    })
+}
+
+/// Opens `target` in the workspace and focuses it, returning its editor.
+async fn open_target(
+    target: Target,
+    workspace: &WeakEntity<Workspace>,
+    cx: &mut AsyncWindowContext,
+) -> Result<Option<Entity<Editor>>> {
+    match target {
+        Target::Editor(editor) => workspace.update_in(cx, |workspace, window, cx| {
+            workspace
+                .activate_item(&editor, true, true, window, cx)
+                .then_some(editor)
+        }),
+        Target::Pane(pane) => workspace.update_in(cx, |_, window, cx| {
+            let editor = pane.read(cx).active_item()?.downcast::<Editor>()?;
+            window.focus(&pane.focus_handle(cx), cx);
+            Some(editor)
+        }),
+        Target::Path(path) => {
+            let item = workspace
+                .update_in(cx, |workspace, window, cx| {
+                    workspace.open_path(path, None, true, window, cx)
+                })?
+                .await?;
+            Ok(item.downcast::<Editor>())
+        }
+        Target::Location(buffer, position) => workspace.update_in(cx, |workspace, window, cx| {
+            let pane = workspace.active_pane().clone();
+            let editor = workspace.open_project_item::<Editor>(
+                pane,
+                buffer.clone(),
+                true,
+                true,
+                true,
+                true,
+                window,
+                cx,
+            );
+            let point = position.to_point(&buffer.read(cx).snapshot());
+            editor.update(cx, |editor, cx| {
+                editor.change_selections(Default::default(), window, cx, |s| {
+                    s.select_ranges([point..point])
+                });
+            });
+            Some(editor)
+        }),
+    }
+}
+
+/// Waits until `editor` is part of the last frame drawn, as actions are dispatched through the
+/// elements of that frame.
+async fn wait_until_drawn(
+    editor: &Entity<Editor>,
+    workspace: &WeakEntity<Workspace>,
+    cx: &mut AsyncWindowContext,
+) -> Result<()> {
+    loop {
+        let next_frame = workspace.update_in(cx, |workspace, window, cx| {
+            let pane = workspace
+                .pane_for(editor)
+                .context("The editor was closed")?;
+            if pane
+                .focus_handle(cx)
+                .contains(&editor.focus_handle(cx), window)
+            {
+                return anyhow::Ok(None);
+            }
+            let (tx, rx) = oneshot::channel();
+            window.on_next_frame(move |_, _| {
+                tx.send(()).ok();
+            });
+            window.refresh();
+            Ok(Some(rx))
+        })??;
+        let Some(next_frame) = next_frame else {
+            return Ok(());
+        };
+        next_frame.await?;
+    }
+}
+
+/// Waits for the work started by the command dispatched to `editor`, and any work
+/// started by that.
+async fn wait_for_command(editor: &Entity<Editor>, cx: &mut AsyncWindowContext) {
+    loop {
+        let tasks = editor.update(cx, |editor, cx| {
+            editor
+                .addon::<VimAddon>()
+                .map(|addon| {
+                    addon
+                        .entity
+                        .update(cx, |vim, _| mem::take(&mut vim.command_tasks))
+                })
+                .unwrap_or_default()
+        });
+        if tasks.is_empty() {
+            return;
+        }
+        join_all(tasks).await;
+    }
+}
+
+fn set_arg_list(
+    patterns: &[String],
+    workspace: &mut Workspace,
+    cx: &mut Context<Workspace>,
+) -> Result<()> {
+    let project = workspace.project().read(cx);
+    let matcher = PathMatcher::new(patterns, project.path_style(cx))?;
+    let paths = project
+        .visible_worktrees(cx)
+        .flat_map(|worktree| {
+            let worktree = worktree.read(cx);
+            let worktree_id = worktree.id();
+            worktree
+                .files(false, 0)
+                .filter(|entry| matcher.is_match(&entry.path))
+                .map(move |entry| ProjectPath {
+                    worktree_id,
+                    path: entry.path.clone(),
+                })
+        })
+        .collect::<Vec<_>>();
+    anyhow::ensure!(!paths.is_empty(), "No files match {}", patterns.join(" "));
+
+    let workspace_id = cx.entity_id();
+    Vim::update_globals(cx, |globals, _| {
+        globals.arg_lists.insert(workspace_id, paths);
+    });
+    Ok(())
+}
+
+/// The notification showing the argument list.
+struct ArgList;
+
+fn show_arg_list(workspace: &mut Workspace, cx: &mut Context<Workspace>) {
+    let path_style = workspace.project().read(cx).path_style(cx);
+    let workspace_id = cx.entity_id();
+    let arg_list = Vim::globals(cx)
+        .arg_lists
+        .get(&workspace_id)
+        .map(|paths| {
+            paths
+                .iter()
+                .map(|path| path.path.display(path_style))
+                .join(" ")
+        })
+        .unwrap_or_default();
+    let message = if arg_list.is_empty() {
+        "The argument list is empty".to_string()
+    } else {
+        arg_list
+    };
+    workspace.show_toast(
+        Toast::new(NotificationId::unique::<ArgList>(), message).autohide(),
+        cx,
+    );
+}
+
+#[cfg(test)]
+mod test {
+    use std::path::PathBuf;
+
+    use gpui::TestAppContext;
+    use language::{Diagnostic, DiagnosticEntry, DiagnosticSourceKind, PointUtf16, Unclipped};
+    use lsp::LanguageServerId;
+    use util::path;
+
+    use super::*;
+    use crate::{state::Mode, test::VimTestContext};
+
+    /// The text of every open buffer, by file name.
+    fn buffer_texts(cx: &mut VimTestContext) -> Vec<(String, String)> {
+        cx.workspace(|workspace, _, cx| {
+            workspace
+                .project()
+                .read(cx)
+                .opened_buffers(cx)
+                .into_iter()
+                .filter_map(|buffer| {
+                    let buffer = buffer.read(cx);
+                    Some((buffer.file()?.file_name(cx).to_string(), buffer.text()))
+                })
+                .sorted()
+                .collect()
+        })
+    }
+
+    async fn insert_file(cx: &mut VimTestContext, path: &str, text: &str) {
+        let fs = cx.workspace(|workspace, _, cx| workspace.project().read(cx).fs().clone());
+        fs.as_fake()
+            .insert_file(path, text.as_bytes().to_vec())
+            .await;
+    }
+
+    fn take_prompt(cx: &mut VimTestContext) -> Option<String> {
+        let (_, detail) = cx.pending_prompt()?;
+        cx.simulate_prompt_answer("Ok");
+        Some(detail)
+    }
+
+    #[gpui::test]
+    async fn test_command_windo(cx: &mut TestAppContext) {
+        let mut cx = VimTestContext::new(cx, true).await;
+        insert_file(&mut cx, path!("/root/dir/other.rs"), "foo\n").await;
+
+        cx.set_state("ˇfoo\n", Mode::Normal);
+        cx.simulate_keystrokes(": vsplit space dir/other.rs enter");
+        cx.run_until_parked();
+        cx.workspace(|workspace, _, _| assert_eq!(workspace.panes().len(), 2));
+
+        cx.simulate_keystrokes(": windo space s / ^ / x / enter");
+        cx.run_until_parked();
+        assert_eq!(
+            buffer_texts(&mut cx),
+            [
+                ("file.rs".to_string(), "xfoo\n".to_string()),
+                ("other.rs".to_string(), "xfoo\n".to_string()),
+            ]
+        );
+
+        // Ranges don't map to Zed's buffers, so the command is rejected instead of running everywhere.
+        cx.simulate_keystrokes(": 1 , 3 bufdo space s / ^ / y / enter");
+        cx.run_until_parked();
+        assert_eq!(
+            buffer_texts(&mut cx),
+            [
+                ("file.rs".to_string(), "xfoo\n".to_string()),
+                ("other.rs".to_string(), "xfoo\n".to_string()),
+            ]
+        );
+    }
+
+    #[gpui::test]
+    async fn test_command_cdo_and_cfdo(cx: &mut TestAppContext) {
+        let mut cx = VimTestContext::new(cx, true).await;
+        insert_file(&mut cx, path!("/root/dir/other.rs"), "foo\n").await;
+        cx.set_state("ˇfoo\nfoo\nfoo\n", Mode::Normal);
+
+        cx.simulate_keystrokes(": cdo space s / ^ / x / enter");
+        cx.run_until_parked();
+        assert_eq!(
+            take_prompt(&mut cx).as_deref(),
+            Some("The quickfix list is empty, run a project search first")
+        );
+
+        let lsp_store = cx.workspace(|workspace, _, cx| workspace.project().read(cx).lsp_store());
+        let error = |row| DiagnosticEntry {
+            range: Unclipped(PointUtf16::new(row, 0))..Unclipped(PointUtf16::new(row, 3)),
+            diagnostic: Diagnostic {
+                severity: DiagnosticSeverity::ERROR,
+                is_primary: true,
+                message: "error".to_string(),
+                source_kind: DiagnosticSourceKind::Pushed,
+                ..Diagnostic::default()
+            },
+        };
+        cx.update(|_, cx| {
+            lsp_store.update(cx, |lsp_store, cx| {
+                for (path, rows) in [
+                    (path!("/root/dir/file.rs"), vec![0, 2]),
+                    (path!("/root/dir/other.rs"), vec![0]),
+                ] {
+                    lsp_store
+                        .update_diagnostic_entries(
+                            LanguageServerId(0),
+                            PathBuf::from(path),
+                            None,
+                            None,
+                            rows.into_iter().map(error).collect(),
+                            cx,
+                        )
+                        .unwrap();
+                }
+            })
+        });
+        cx.run_until_parked();
+
+        // `:cdo` runs at every diagnostic.
+        cx.simulate_keystrokes(": cdo space s / ^ / x / enter");
+        cx.run_until_parked();
+        assert_eq!(
+            buffer_texts(&mut cx),
+            [
+                ("file.rs".to_string(), "xfoo\nfoo\nxfoo\n".to_string()),
+                ("other.rs".to_string(), "xfoo\n".to_string()),
+            ]
+        );
+
+        // `:cfdo` runs once in each file, at its first diagnostic.
+        cx.simulate_keystrokes(": cfdo space s / ^ / y / enter");
+        cx.run_until_parked();
+        assert_eq!(
+            buffer_texts(&mut cx),
+            [
+                ("file.rs".to_string(), "yxfoo\nfoo\nxfoo\n".to_string()),
+                ("other.rs".to_string(), "yxfoo\n".to_string()),
+            ]
+        );
+        assert_eq!(take_prompt(&mut cx), None);
+    }
+
+    #[gpui::test]
+    async fn test_command_args_and_argdo(cx: &mut TestAppContext) {
+        let mut cx = VimTestContext::new(cx, true).await;
+        insert_file(&mut cx, path!("/root/dir/other.rs"), "foo\n").await;
+        cx.set_state("ˇfoo\n", Mode::Normal);
+        let arg_list_shown = |cx: &mut VimTestContext| {
+            cx.workspace(|workspace, _, _| {
+                workspace
+                    .notification_ids()
+                    .contains(&NotificationId::unique::<ArgList>())
+            })
+        };
+
+        cx.simulate_keystrokes(": argdo space s / ^ / x / enter");
+        cx.run_until_parked();
+        assert_eq!(
+            take_prompt(&mut cx).as_deref(),
+            Some("The argument list is empty, set it with :args")
+        );
+
+        cx.simulate_keystrokes(": args enter");
+        cx.run_until_parked();
+        assert!(arg_list_shown(&mut cx));
+
+        cx.simulate_keystrokes(": args space dir/o*.rs enter");
+        cx.run_until_parked();
+        let arg_list = cx.workspace(|workspace, _, cx| {
+            let workspace_id = cx.entity_id();
+            Vim::globals(cx)
+                .arg_lists
+                .get(&workspace_id)
+                .into_iter()
+                .flatten()
+                .map(|path| path.path.as_unix_str().to_string())
+                .collect::<Vec<_>>()
+        });
+        assert_eq!(arg_list, ["dir/other.rs"]);
+
+        cx.simulate_keystrokes(": argdo space s / ^ / x / enter");
+        cx.run_until_parked();
+        assert_eq!(
+            buffer_texts(&mut cx),
+            [
+                ("file.rs".to_string(), "foo\n".to_string()),
+                ("other.rs".to_string(), "xfoo\n".to_string()),
+            ]
+        );
+        assert_eq!(take_prompt(&mut cx), None);
+    }
+}
@@ -41,6 +41,7 @@ use zed_actions::{OpenDocs, RevealTarget};
 
 use crate::{
     ToggleMarksView, ToggleRegistersView, Vim, VimSettings,
+    batch_command::{BatchCommand, VimArgs},
     motion::{EndOfDocument, Motion, MotionKind, StartOfDocument},
     normal::{
         JoinLines,
@@ -823,7 +824,7 @@ pub fn register(editor: &mut Editor, cx: &mut Context<Vim>) {
         let had_range = action.range.is_some();
         let had_override = action.override_rows.is_some();
 
-        cx.spawn_in(window, async move |vim, cx| {
+        let task = cx.spawn_in(window, async move |vim, cx| {
             task.await;
             vim.update_in(cx, |vim, window, cx| {
                 if matches!(vim.mode, Mode::Insert | Mode::Replace) {
@@ -850,8 +851,8 @@ pub fn register(editor: &mut Editor, cx: &mut Context<Vim>) {
                 }
             })
             .log_err();
-        })
-        .detach();
+        });
+        vim.track_command(task);
     });
 
     Vim::action(editor, cx, |vim, _: &CountCommand, window, cx| {
@@ -1667,6 +1668,14 @@ fn generate_commands(_: &App) -> Vec<VimCommand> {
         VimCommand::new(("bl", "ast"), workspace::ActivateLastItem),
         VimCommand::str(("buffers", ""), "tab_switcher::ToggleAll"),
         VimCommand::str(("ls", ""), "tab_switcher::ToggleAll"),
+        VimCommand::new(("ar", "gs"), VimArgs { patterns: vec![] }).args(|_, args| {
+            Some(
+                VimArgs {
+                    patterns: args.split_whitespace().map(String::from).collect(),
+                }
+                .boxed_clone(),
+            )
+        }),
         VimCommand::new(("new", ""), workspace::NewFileSplitHorizontal),
         VimCommand::new(("vne", "w"), workspace::NewFileSplitVertical),
         VimCommand::new(("tabn", "ext"), workspace::ActivateNextItem).count(),
@@ -1895,6 +1904,8 @@ pub fn command_interceptor(
         } else {
             None
         }
+    } else if let Some(action) = BatchCommand::parse(query, range.clone()) {
+        Some(action)
     } else if query.contains('!') {
         ShellExec::parse(query, range.clone())
     } else if on_matching_lines.is_some() {
@@ -2190,7 +2201,7 @@ impl OnMatchingLines {
             });
         };
 
-        vim.update_editor(cx, |_, editor, cx| {
+        vim.update_editor(cx, |vim, editor, cx| {
             let snapshot = editor.snapshot(window, cx);
             let mut row = range.start.0;
 
@@ -2198,7 +2209,7 @@ impl OnMatchingLines {
                 ..snapshot
                     .buffer_snapshot()
                     .clip_point(Point::new(range.end.0 + 1, 0), Bias::Left);
-            cx.spawn_in(window, async move |editor, cx| {
+            let task = cx.spawn_in(window, async move |editor, cx| {
                 let new_selections = cx
                     .background_spawn(async move {
                         let mut line = String::new();
@@ -2268,8 +2279,8 @@ impl OnMatchingLines {
                         })
                     })
                     .log_err();
-            })
-            .detach();
+            });
+            vim.track_command(task);
         });
     }
 }
@@ -2284,6 +2295,12 @@ pub struct ShellExec {
 }
 
 impl Vim {
+    /// Keeps `task` running until it completes, so that batch commands can wait for it.
+    pub(crate) fn track_command(&mut self, task: Task<()>) {
+        self.command_tasks.retain(|task| !task.is_ready());
+        self.command_tasks.push(task);
+    }
+
     pub fn cancel_running_command(&mut self, window: &mut Window, cx: &mut Context<Self>) {
         if self.running_command.take().is_some() {
             self.update_editor(cx, |_, editor, cx| {
@@ -2479,6 +2496,7 @@ impl ShellExec {
                     show_summary: false,
                     show_command: false,
                     show_rerun: false,
+                    problem_matchers: Vec::new(),
                 };
 
                 let task_status = workspace.spawn_in_terminal(spawn_in_terminal, window, cx);
@@ -3328,6 +3346,34 @@ mod test {
         });
     }
 
+    #[gpui::test]
+    async fn test_command_bufdo(cx: &mut TestAppContext) {
+        let mut cx = VimTestContext::new(cx, true).await;
+        let fs = cx.workspace(|workspace, _, cx| workspace.project().read(cx).fs().clone());
+        fs.as_fake()
+            .insert_file(path!("/root/dir/file_2.rs"), "foo\n".as_bytes().to_vec())
+            .await;
+
+        cx.set_state("ˇfoo foo\nfoo", Mode::Normal);
+        cx.simulate_keystrokes(": tabnew space dir/file_2.rs");
+        cx.simulate_keystrokes("enter");
+        cx.run_until_parked();
+
+        cx.simulate_keystrokes(": bufdo space % s / f o o / b a r / g");
+        cx.simulate_keystrokes("enter");
+        cx.run_until_parked();
+
+        cx.update_editor(|editor, _, cx| assert_eq!(editor.text(cx), "bar bar\nbar"));
+        cx.workspace(|workspace, _, cx| {
+            let texts = workspace
+                .items_of_type::<Editor>(cx)
+                .map(|editor| editor.read(cx).text(cx))
+                .collect::<Vec<_>>();
+            assert_eq!(texts.len(), 2);
+            assert!(texts.contains(&"bar\n".to_string()));
+        });
+    }
+
     #[gpui::test]
     async fn test_command_tabedit(cx: &mut TestAppContext) {
         let mut cx = VimTestContext::new(cx, true).await;
@@ -324,7 +324,8 @@ impl Vim {
 #[cfg(test)]
 mod test {
     use crate::{
-        state::{Mode, Register},
+        VimGlobals,
+        state::{Mode, PersistVimState, Register, SAVE_REGISTERS_DEBOUNCE},
         test::{NeovimBackedTestContext, VimTestContext},
     };
     use gpui::ClipboardItem;
@@ -898,6 +899,43 @@ mod test {
         cx.shared_register('a').await.assert_eq(" over");
     }
 
+    #[gpui::test]
+    async fn test_restore_named_registers(cx: &mut gpui::TestAppContext) {
+        let mut cx = VimTestContext::new(cx, true).await;
+        cx.update(|_, cx| cx.set_global(PersistVimState(true)));
+
+        cx.set_state(
+            indoc! {"
+                ˇThe quick brown
+                fox jumps over"},
+            Mode::Normal,
+        );
+        cx.simulate_keystrokes("\" k y i w d d");
+        cx.executor().advance_clock(SAVE_REGISTERS_DEBOUNCE);
+        cx.run_until_parked();
+
+        // Disabling vim mode clears the registers, and enabling it restores the saved ones.
+        cx.disable_vim();
+        cx.enable_vim();
+        cx.run_until_parked();
+
+        cx.update(|_, cx| {
+            let registers = &cx.global::<VimGlobals>().registers;
+            assert_eq!(
+                registers
+                    .get(&'k')
+                    .map(|register| register.text.to_string()),
+                Some("The".to_string())
+            );
+            assert!(!registers.contains_key(&'"'));
+            assert!(!registers.contains_key(&'1'));
+        });
+
+        cx.set_state("ˇfox", Mode::Normal);
+        cx.simulate_keystrokes("\" k p");
+        cx.assert_state("fThˇeox", Mode::Normal);
+    }
+
     #[gpui::test]
     async fn test_special_registers(cx: &mut gpui::TestAppContext) {
         let mut cx = NeovimBackedTestContext::new(cx).await;
@@ -69,7 +69,8 @@ pub(crate) fn register(editor: &mut Editor, cx: &mut Context<Vim>) {
     Vim::action(editor, cx, |vim, _: &ToggleRecord, window, cx| {
         let globals = Vim::globals(cx);
         if let Some(char) = globals.recording_register.take() {
-            globals.last_recorded_register = Some(char)
+            globals.last_recorded_register = Some(char);
+            Vim::update_globals(cx, |globals, cx| globals.save_recording(char, cx));
         } else {
             vim.push_operator(Operator::RecordRegister, window, cx);
         }
@@ -402,7 +403,7 @@ mod test {
     use gpui::EntityInputHandler;
 
     use crate::{
-        VimGlobals,
+        Vim, VimGlobals,
         state::Mode,
         test::{NeovimBackedTestContext, VimTestContext},
     };
@@ -822,6 +823,25 @@ mod test {
         cx.shared_state().await.assert_eq("000ˇ!");
     }
 
+    #[gpui::test]
+    async fn test_record_replay_restored(cx: &mut gpui::TestAppContext) {
+        let mut cx = VimTestContext::new(cx, true).await;
+
+        cx.set_state("ˇhello world", Mode::Normal);
+        cx.simulate_keystrokes("q a c w j escape q");
+        cx.assert_state("ˇj world", Mode::Normal);
+
+        cx.update(|_, cx| {
+            Vim::update_globals(cx, |globals, cx| {
+                let recording = globals.serialize_recording('a', cx).unwrap();
+                globals.recordings.clear();
+                globals.restored(Vec::new(), vec![('a', recording)], Vec::new(), cx);
+            })
+        });
+        cx.simulate_keystrokes("w @ a");
+        cx.assert_state("j ˇj", Mode::Normal);
+    }
+
     #[gpui::test]
     async fn test_record_replay_dot(cx: &mut gpui::TestAppContext) {
         let mut cx = NeovimBackedTestContext::new(cx).await;
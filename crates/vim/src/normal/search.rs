@@ -6,6 +6,7 @@ use search::{BufferSearchBar, SearchOptions, buffer_search};
 use serde::Deserialize;
 use settings::Settings;
 use std::{iter::Peekable, str::Chars};
+use util::ResultExt as _;
 use util::serde::default_true;
 use workspace::{notifications::NotifyResultExt, searchable::Direction};
 
@@ -235,10 +236,12 @@ impl Vim {
             return;
         };
 
+        let search_history = Vim::globals(cx).search_history.clone();
         let shown = search_bar.update(cx, |search_bar, cx| {
             if !search_bar.show(window, cx) {
                 return false;
             }
+            search_bar.seed_search_history(search_history);
 
             search_bar.select_query(window, cx);
             cx.focus_self(window);
@@ -337,8 +340,8 @@ impl Vim {
                 let prior_mode = self.search.prior_mode;
                 let prior_operator = self.search.prior_operator.take();
 
-                let query = search_bar.query(cx).into();
-                Vim::globals(cx).registers.insert('/', query);
+                let query = search_bar.query(cx);
+                Vim::update_globals(cx, |globals, cx| globals.push_search_history(query, cx));
                 Some((prior_selections, prior_mode, prior_operator))
             })
         });
@@ -632,38 +635,42 @@ impl Vim {
         }
         let Some(search) = search else { return };
         let search_bar = search_bar.downgrade();
-        cx.spawn_in(window, async move |vim, cx| {
-            search.await?;
-            search_bar.update_in(cx, |search_bar, window, cx| {
-                if replacement.flag_c {
-                    search_bar.select_first_match(window, cx);
-                    return;
-                }
-                search_bar.select_last_match(window, cx);
-                search_bar.replace_all(&Default::default(), window, cx);
-                editor.update(cx, |editor, cx| editor.clear_search_within_ranges(cx));
-                let _ = search_bar.search(&search_bar.query(cx), None, false, window, cx);
-                vim.update(cx, |vim, cx| {
-                    vim.move_cursor(
-                        Motion::StartOfLine {
-                            display_lines: false,
-                        },
-                        None,
-                        window,
-                        cx,
-                    )
+        let task = cx.spawn_in(window, async move |vim, cx| {
+            if search.await.log_err().is_none() {
+                return;
+            }
+            search_bar
+                .update_in(cx, |search_bar, window, cx| {
+                    if replacement.flag_c {
+                        search_bar.select_first_match(window, cx);
+                        return;
+                    }
+                    search_bar.select_last_match(window, cx);
+                    search_bar.replace_all(&Default::default(), window, cx);
+                    editor.update(cx, |editor, cx| editor.clear_search_within_ranges(cx));
+                    let _ = search_bar.search(&search_bar.query(cx), None, false, window, cx);
+                    vim.update(cx, |vim, cx| {
+                        vim.move_cursor(
+                            Motion::StartOfLine {
+                                display_lines: false,
+                            },
+                            None,
+                            window,
+                            cx,
+                        )
+                    })
+                    .ok();
+
+                    // Disable the `ONE_MATCH_PER_LINE` search option when finished, as
+                    // this is not properly supported outside of vim mode, and
+                    // not disabling it makes the "Replace All Matches" button
+                    // actually replace only the first match on each line.
+                    options.set(SearchOptions::ONE_MATCH_PER_LINE, false);
+                    search_bar.set_search_options(options, cx);
                 })
-                .ok();
-
-                // Disable the `ONE_MATCH_PER_LINE` search option when finished, as
-                // this is not properly supported outside of vim mode, and
-                // not disabling it makes the "Replace All Matches" button
-                // actually replace only the first match on each line.
-                options.set(SearchOptions::ONE_MATCH_PER_LINE, false);
-                search_bar.set_search_options(options, cx);
-            })
-        })
-        .detach_and_log_err(cx);
+                .log_err();
+        });
+        self.track_command(task);
     }
 }
 
@@ -24,8 +24,9 @@ use project::{Project, ProjectItem, ProjectPath};
 use serde::{Deserialize, Serialize};
 use settings::{Settings, SettingsStore};
 use std::borrow::BorrowMut;
-use std::collections::HashSet;
+use std::collections::{HashSet, VecDeque};
 use std::path::Path;
+use std::time::Duration;
 use std::{fmt::Display, ops::Range, sync::Arc};
 use text::{Bias, ToPoint};
 use theme::ThemeSettings;
@@ -208,6 +209,24 @@ impl From<String> for Register {
     }
 }
 
+/// The number of searches kept in the search history, like Vim's `'history'` option.
+const MAX_SEARCH_HISTORY: usize = 100;
+
+/// How long to wait after a named register is written before saving the registers.
+pub(crate) const SAVE_REGISTERS_DEBOUNCE: Duration = Duration::from_secs(1);
+
+/// Whether registers, macros and search history are saved to the database and restored
+/// when vim mode is enabled. Tests turn this off, as they all share one database.
+pub(crate) struct PersistVimState(pub bool);
+
+impl Global for PersistVimState {}
+
+impl PersistVimState {
+    fn enabled(cx: &App) -> bool {
+        cx.try_global::<Self>().is_some_and(|persist| persist.0)
+    }
+}
+
 #[derive(Default)]
 pub struct VimGlobals {
     pub last_find: Option<Motion>,
@@ -236,10 +255,14 @@ pub struct VimGlobals {
     pub last_yank: Option<SharedString>,
     pub registers: HashMap<char, Register>,
     pub recordings: HashMap<char, Vec<ReplayableAction>>,
+    pub search_history: VecDeque<String>,
+    save_registers_task: Option<Task<()>>,
 
     pub focused_vim: Option<WeakEntity<Vim>>,
 
     pub marks: HashMap<EntityId, Entity<MarksState>>,
+    /// The files `:argdo` runs in, set with `:args`, by workspace.
+    pub arg_lists: HashMap<EntityId, Vec<ProjectPath>>,
 }
 
 pub struct MarksState {
@@ -695,6 +718,20 @@ impl Global for VimGlobals {}
 impl VimGlobals {
     pub(crate) fn register(cx: &mut App) {
         cx.set_global(VimGlobals::default());
+        cx.set_global(PersistVimState(true));
+
+        cx.on_app_quit(|cx| {
+            let save = PersistVimState::enabled(cx)
+                && Vim::enabled(cx)
+                && Vim::globals(cx).save_registers_task.take().is_some();
+            let registers = Vim::globals(cx).named_registers();
+            async move {
+                if save {
+                    DB.set_registers(registers).await.log_err();
+                }
+            }
+        })
+        .detach();
 
         cx.observe_keystrokes(|event, _, cx| {
             let Some(action) = event.action.as_ref().map(|action| action.boxed_clone()) else {
@@ -724,6 +761,7 @@ impl VimGlobals {
             was_enabled = Some(is_enabled);
             if is_enabled {
                 KeyBinding::set_vim_mode(cx, true);
+                VimGlobals::restore(cx);
                 CommandPaletteFilter::update_global(cx, |filter, _| {
                     filter.show_namespace(Vim::NAMESPACE);
                 });
@@ -765,6 +803,7 @@ impl VimGlobals {
         cx.observe_release(&cx.entity(), move |_, _, cx| {
             Vim::update_globals(cx, |globals, _| {
                 globals.marks.remove(&entity_id);
+                globals.arg_lists.remove(&entity_id);
             })
         })
         .detach();
@@ -843,6 +882,9 @@ impl VimGlobals {
                 }
             }
         }
+        if register.is_some_and(|register| register.is_ascii_alphabetic()) {
+            self.save_named_registers(cx);
+        }
     }
 
     pub(crate) fn read_register(
@@ -955,6 +997,149 @@ impl VimGlobals {
         }
     }
 
+    /// Loads the registers, macros and search history saved by earlier sessions,
+    /// keeping anything already written in this one.
+    pub(crate) fn restore(cx: &mut App) {
+        if !PersistVimState::enabled(cx) {
+            return;
+        }
+        cx.spawn(async move |cx| {
+            let (registers, recordings, search_history) = cx
+                .background_spawn(async move {
+                    anyhow::Ok((
+                        DB.get_registers()?,
+                        DB.get_recordings()?,
+                        DB.get_search_history()?,
+                    ))
+                })
+                .await?;
+            cx.update(|cx| {
+                Vim::update_globals(cx, |globals, cx| {
+                    globals.restored(registers, recordings, search_history, cx)
+                })
+            });
+            anyhow::Ok(())
+        })
+        .detach_and_log_err(cx);
+    }
+
+    pub(crate) fn restored(
+        &mut self,
+        registers: Vec<(char, Register)>,
+        recordings: Vec<(char, Vec<SerializedReplayableAction>)>,
+        search_history: Vec<String>,
+        cx: &App,
+    ) {
+        for (name, register) in registers {
+            if name.is_ascii_lowercase() {
+                self.registers.entry(name).or_insert(register);
+            }
+        }
+        for (name, actions) in recordings {
+            if self.recordings.contains_key(&name) || self.recording_register == Some(name) {
+                continue;
+            }
+            match actions
+                .into_iter()
+                .map(|action| action.deserialize(cx))
+                .collect::<Result<Vec<_>>>()
+            {
+                Ok(actions) => {
+                    self.recordings.insert(name, actions);
+                }
+                Err(error) => log::warn!("Failed to restore the macro in register {name}: {error}"),
+            }
+        }
+        // Searches made before the history was loaded are more recent than the loaded ones.
+        for query in search_history.into_iter().rev() {
+            if self.search_history.len() >= MAX_SEARCH_HISTORY {
+                break;
+            }
+            if !self.search_history.contains(&query) {
+                self.search_history.push_front(query);
+            }
+        }
+        if let Some(query) = self.search_history.back() {
+            self.registers
+                .entry('/')
+                .or_insert_with(|| Register::from(query.clone()));
+        }
+    }
+
+    /// The registers that are kept across sessions. Unnamed, numbered and delete registers
+    /// aren't, so that text deleted while editing isn't written to disk.
+    fn named_registers(&self) -> Vec<(char, Register)> {
+        self.registers
+            .iter()
+            .filter(|(name, _)| name.is_ascii_lowercase())
+            .map(|(name, register)| (*name, register.clone()))
+            .collect()
+    }
+
+    fn save_named_registers(&mut self, cx: &mut App) {
+        if !PersistVimState::enabled(cx) {
+            return;
+        }
+        self.save_registers_task = Some(cx.spawn(async move |cx| {
+            cx.background_executor()
+                .timer(SAVE_REGISTERS_DEBOUNCE)
+                .await;
+            let registers = cx.update(|cx| {
+                let globals = Vim::globals(cx);
+                globals.save_registers_task = None;
+                globals.named_registers()
+            });
+            DB.set_registers(registers).await.log_err();
+        }));
+    }
+
+    /// Returns the macro recorded in `register` in a form that can be saved, or `None`
+    /// if it runs an action that can't be rebuilt from its name and keybinding.
+    pub(crate) fn serialize_recording(
+        &self,
+        register: char,
+        cx: &App,
+    ) -> Option<Vec<SerializedReplayableAction>> {
+        self.recordings
+            .get(&register)?
+            .iter()
+            .map(|action| SerializedReplayableAction::serialize(action, cx))
+            .collect()
+    }
+
+    pub(crate) fn save_recording(&self, register: char, cx: &App) {
+        if !PersistVimState::enabled(cx) {
+            return;
+        }
+        let actions = self.serialize_recording(register, cx);
+        if actions.is_none() {
+            log::info!("The macro recorded in register {register} won't be kept across sessions");
+        }
+        cx.background_spawn(async move {
+            match actions {
+                Some(actions) => DB.set_recording(register, actions).await,
+                None => DB.delete_recording(register).await,
+            }
+        })
+        .detach_and_log_err(cx);
+    }
+
+    pub(crate) fn push_search_history(&mut self, query: String, cx: &App) {
+        self.registers.insert('/', Register::from(query.clone()));
+        if query.is_empty() {
+            return;
+        }
+        self.search_history.retain(|previous| previous != &query);
+        if self.search_history.len() >= MAX_SEARCH_HISTORY {
+            self.search_history.pop_front();
+        }
+        self.search_history.push_back(query.clone());
+        if PersistVimState::enabled(cx) {
+            cx.background_spawn(async move { DB.push_search_history(query).await })
+                .detach_and_log_err(cx);
+        }
+    }
+
     pub fn focused_vim(&self) -> Option<Entity<Vim>> {
         self.focused_vim.as_ref().and_then(|vim| vim.upgrade())
     }
@@ -997,6 +1182,70 @@ impl Clone for ReplayableAction {
     }
 }
 
+/// A [`ReplayableAction`] as it's saved in the database.
+#[derive(Debug, Serialize, Deserialize)]
+pub(crate) enum SerializedReplayableAction {
+    Action {
+        name: String,
+        input: Option<serde_json::Value>,
+    },
+    Insertion {
+        text: String,
+        utf16_range_to_replace: Option<Range<isize>>,
+    },
+}
+
+impl SerializedReplayableAction {
+    fn serialize(action: &ReplayableAction, cx: &App) -> Option<Self> {
+        match action {
+            ReplayableAction::Action(action) => {
+                let name = action.name();
+                let input = if cx
+                    .build_action(name, None)
+                    .is_ok_and(|default| default.partial_eq(&**action))
+                {
+                    None
+                } else {
+                    // Actions can't be serialized, but the keybinding that dispatched
+                    // this one keeps the input it was built from.
+                    let input = cx
+                        .key_bindings()
+                        .borrow()
+                        .bindings_for_action(&**action)
+                        .find_map(|binding| binding.action_input())?;
+                    Some(serde_json::from_str(&input).log_err()?)
+                };
+                Some(Self::Action {
+                    name: name.to_string(),
+                    input,
+                })
+            }
+            ReplayableAction::Insertion {
+                text,
+                utf16_range_to_replace,
+            } => Some(Self::Insertion {
+                text: text.to_string(),
+                utf16_range_to_replace: utf16_range_to_replace.clone(),
+            }),
+        }
+    }
+
+    fn deserialize(self, cx: &App) -> Result<ReplayableAction> {
+        Ok(match self {
+            Self::Action { name, input } => {
+                ReplayableAction::Action(cx.build_action(&name, input)?)
+            }
+            Self::Insertion {
+                text,
+                utf16_range_to_replace,
+            } => ReplayableAction::Insertion {
+                text: text.into(),
+                utf16_range_to_replace,
+            },
+        })
+    }
+}
+
 #[derive(Default, Debug)]
 pub struct SearchState {
     pub direction: Direction,
@@ -1747,6 +1996,20 @@ impl Domain for VimDb {
             CREATE UNIQUE INDEX idx_vim_global_marks_paths
             ON vim_global_marks_paths(workspace_id, mark_name);
         ),
+        sql! (
+            CREATE TABLE vim_registers(
+                register_name TEXT PRIMARY KEY,
+                value TEXT NOT NULL,
+                clipboard_selections TEXT
+            );
+            CREATE TABLE vim_recordings(
+                register_name TEXT PRIMARY KEY,
+                actions TEXT NOT NULL
+            );
+            CREATE TABLE vim_search_history(
+                search_query TEXT PRIMARY KEY
+            );
+        ),
     ];
 }
 
@@ -1865,4 +2128,113 @@ impl VimDb {
         })
         .await
     }
+
+    pub(crate) async fn set_registers(&self, registers: Vec<(char, Register)>) -> Result<()> {
+        self.write(move |conn| {
+            let mut query = conn.exec_bound(sql!(
+                INSERT OR REPLACE INTO vim_registers
+                    (register_name, value, clipboard_selections)
+                VALUES
+                    (?, ?, ?)
+            ))?;
+            for (name, register) in registers {
+                let clipboard_selections = register
+                    .clipboard_selections
+                    .map(|selections| serde_json::to_string(&selections))
+                    .transpose()?;
+                query((
+                    name.to_string(),
+                    register.text.to_string(),
+                    clipboard_selections,
+                ))?;
+            }
+            Ok(())
+        })
+        .await
+    }
+
+    fn get_registers(&self) -> Result<Vec<(char, Register)>> {
+        let result: Vec<(String, String, Option<String>)> = self.select(sql!(
+            SELECT register_name, value, clipboard_selections FROM vim_registers
+        ))?()?;
+
+        Ok(result
+            .into_iter()
+            .filter_map(|(name, value, clipboard_selections)| {
+                let name = name.chars().next()?;
+                let clipboard_selections = clipboard_selections
+                    .and_then(|selections| serde_json::from_str(&selections).log_err());
+                Some((
+                    name,
+                    Register {
+                        text: value.into(),
+                        clipboard_selections,
+                    },
+                ))
+            })
+            .collect())
+    }
+
+    pub(crate) async fn set_recording(
+        &self,
+        register: char,
+        actions: Vec<SerializedReplayableAction>,
+    ) -> Result<()> {
+        let actions = serde_json::to_string(&actions)?;
+        self.write(move |conn| {
+            conn.exec_bound(sql!(
+                INSERT OR REPLACE INTO vim_recordings
+                    (register_name, actions)
+                VALUES
+                    (?, ?)
+            ))?((register.to_string(), actions))
+        })
+        .await
+    }
+
+    fn get_recordings(&self) -> Result<Vec<(char, Vec<SerializedReplayableAction>)>> {
+        let result: Vec<(String, String)> = self.select(sql!(
+            SELECT register_name, actions FROM vim_recordings
+        ))?()?;
+
+        Ok(result
+            .into_iter()
+            .filter_map(|(name, actions)| {
+                Some((
+                    name.chars().next()?,
+                    serde_json::from_str(&actions).log_err()?,
+                ))
+            })
+            .collect())
+    }
+
+    pub(crate) async fn delete_recording(&self, register: char) -> Result<()> {
+        self.write(move |conn| {
+            conn.exec_bound(sql!(
+                DELETE FROM vim_recordings WHERE register_name = ?
+            ))?(register.to_string())
+        })
+        .await
+    }
+
+    pub(crate) async fn push_search_history(&self, query: String) -> Result<()> {
+        self.write(move |conn| {
+            // Replacing the row moves the query to the end of the history.
+            conn.exec_bound(sql!(
+                INSERT OR REPLACE INTO vim_search_history (search_query) VALUES (?)
+            ))?(query)?;
+            conn.exec_bound(sql!(
+                DELETE FROM vim_search_history WHERE rowid NOT IN (
+                    SELECT rowid FROM vim_search_history ORDER BY rowid DESC LIMIT ?
+                )
+            ))?(MAX_SEARCH_HISTORY as i64)
+        })
+        .await
+    }
+
+    fn get_search_history(&self) -> Result<Vec<String>> {
+        self.select(sql!(
+            SELECT search_query FROM vim_search_history ORDER BY rowid
+        ))?()
+    }
 }
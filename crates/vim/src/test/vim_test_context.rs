@@ -26,6 +26,8 @@ impl VimTestContext {
             outline_panel::init(cx);
             git_ui::init(cx);
             crate::init(cx);
+            // Tests share one database, so they shouldn't save or restore registers by default.
+            cx.set_global(crate::state::PersistVimState(false));
             search::init(cx);
             theme::init(theme::LoadThemes::JustBase, cx);
             settings_ui::init(cx);
@@ -3,6 +3,7 @@
 #[cfg(test)]
 mod test;
 
+mod batch_command;
 mod change_list;
 mod command;
 mod digraph;
@@ -521,6 +522,9 @@ pub(crate) struct Vim {
 
     last_command: Option<String>,
     running_command: Option<Task<()>>,
+    /// Work started by ex commands that continues after their action returns,
+    /// so that batch commands like `:bufdo` can wait for it.
+    command_tasks: Vec<Task<()>>,
     _subscriptions: Vec<Subscription>,
 }
 
@@ -580,6 +584,7 @@ impl Vim {
 
             last_command: None,
             running_command: None,
+            command_tasks: Vec::new(),
 
             editor: editor.downgrade(),
             _subscriptions: vec![
@@ -952,6 +957,7 @@ impl Vim {
             helix::register(editor, cx);
             motion::register(editor, cx);
             command::register(editor, cx);
+            batch_command::register(editor, cx);
             replace::register(editor, cx);
             indent::register(editor, cx);
             rewrap::register(editor, cx);
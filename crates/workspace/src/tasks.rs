@@ -1,12 +1,18 @@
-use std::process::ExitStatus;
+use std::{process::ExitStatus, sync::Arc};
 
-use anyhow::Result;
-use gpui::{AppContext, Context, Entity, Task};
+use anyhow::{Context as _, Result, anyhow};
+use collections::HashMap;
+use futures::{
+    FutureExt as _,
+    future::{LocalBoxFuture, Shared},
+};
+use gpui::{AppContext, AsyncWindowContext, Context, Entity, Task, WeakEntity};
 use language::Buffer;
-use project::{TaskSourceKind, WorktreeId};
+use project::{TaskChain, TaskSourceKind, WorktreeId};
 use remote::ConnectionState;
 use task::{
-    DebugScenario, ResolvedTask, SharedTaskContext, SpawnInTerminal, TaskContext, TaskTemplate,
+    DebugScenario, DependsOrder, ResolvedTask, SharedTaskContext, SpawnInTerminal, TaskContext,
+    TaskId, TaskTemplate,
 };
 use ui::Window;
 
@@ -56,7 +62,41 @@ impl Workspace {
         window: &mut Window,
         cx: &mut Context<Workspace>,
     ) {
+        if !resolved_task.pending_inputs().is_empty() {
+            self.schedule_task_with_inputs(
+                task_source_kind,
+                resolved_task,
+                omit_history,
+                window,
+                cx,
+            );
+            return;
+        }
+
         let spawn_in_terminal = resolved_task.resolved.clone();
+        let task_chain = if resolved_task.original_task().depends_on.is_empty() {
+            None
+        } else {
+            let task_inventory = self
+                .project()
+                .read(cx)
+                .task_store()
+                .read(cx)
+                .task_inventory()
+                .cloned();
+            Some(match task_inventory {
+                Some(task_inventory) => task_inventory.read(cx).resolve_task_chain(
+                    task_source_kind.clone(),
+                    resolved_task.clone(),
+                    cx,
+                ),
+                None => Task::ready(Err(anyhow!(
+                    "Cannot resolve dependencies of the task `{}`",
+                    resolved_task.display_label()
+                ))),
+            })
+        };
+
         if !omit_history {
             if let Some(debugger_provider) = self.debugger_provider.as_ref() {
                 debugger_provider.task_scheduled(cx);
@@ -73,7 +113,30 @@ impl Workspace {
             });
         }
 
-        if let Some(terminal_provider) = self.terminal_provider.as_ref() {
+        if let Some(task_chain) = task_chain {
+            let task = cx.spawn_in(window, async move |workspace, cx| {
+                let result = async {
+                    let task_chain = Arc::new(task_chain.await?);
+                    run_task_chain(
+                        workspace.clone(),
+                        task_chain,
+                        &mut HashMap::default(),
+                        cx.clone(),
+                    )
+                    .await
+                    .map_err(|e| anyhow!("{e:#}"))
+                }
+                .await;
+                if let Err(e) = result {
+                    log::error!("Task chain failed: {e:#}");
+                    _ = workspace.update(cx, |workspace, cx| {
+                        let id = NotificationId::unique::<TaskChain>();
+                        workspace.show_toast(Toast::new(id, format!("Task chain failed: {e}")), cx);
+                    })
+                }
+            });
+            self.scheduled_tasks.push(task);
+        } else if let Some(terminal_provider) = self.terminal_provider.as_ref() {
             let task_status = terminal_provider.spawn(spawn_in_terminal, window, cx);
 
             let task = cx.spawn(async |w, cx| {
@@ -100,6 +163,100 @@ impl Workspace {
         }
     }
 
+    /// Asks the user for the values of the task's pending inputs, then schedules the task, resolved with them.
+    /// The values used last time for the same task template are suggested first.
+    fn schedule_task_with_inputs(
+        &mut self,
+        task_source_kind: TaskSourceKind,
+        resolved_task: ResolvedTask,
+        omit_history: bool,
+        window: &mut Window,
+        cx: &mut Context<Workspace>,
+    ) {
+        let Some(input_provider) = self.task_input_provider.clone() else {
+            let message = format!(
+                "Cannot ask for the inputs of the task `{}`",
+                resolved_task.display_label()
+            );
+            log::error!("{message}");
+            let id = NotificationId::unique::<ResolvedTask>();
+            self.show_toast(Toast::new(id, message), cx);
+            return;
+        };
+        let last_input_values = self
+            .project()
+            .read(cx)
+            .task_store()
+            .read(cx)
+            .task_inventory()
+            .map(|inventory| {
+                inventory
+                    .read(cx)
+                    .last_input_values(resolved_task.original_task())
+            })
+            .unwrap_or_default();
+
+        let task = cx.spawn_in(window, async move |workspace, cx| {
+            let result = async {
+                let mut task_context = resolved_task.task_context().clone();
+                for input in resolved_task.pending_inputs() {
+                    let default = last_input_values
+                        .get(&input.id)
+                        .cloned()
+                        .or_else(|| input.default_value().map(ToOwned::to_owned));
+                    let value = cx
+                        .update(|window, cx| {
+                            input_provider.prompt_for_input(
+                                input.clone(),
+                                default,
+                                resolved_task.resolved.cwd.clone(),
+                                window,
+                                cx,
+                            )
+                        })?
+                        .await;
+                    let Some(value) = value else {
+                        return Ok(());
+                    };
+                    task_context
+                        .task_variables
+                        .insert(input.variable_name(), value);
+                }
+
+                let task_with_inputs = resolved_task
+                    .original_task()
+                    .resolve_task(&task_source_kind.to_id_base(), &task_context)
+                    .with_context(|| {
+                        format!(
+                            "Failed to resolve the task `{}` with its inputs",
+                            resolved_task.display_label()
+                        )
+                    })?;
+                workspace.update_in(cx, |workspace, window, cx| {
+                    workspace.schedule_resolved_task(
+                        task_source_kind,
+                        task_with_inputs,
+                        omit_history,
+                        window,
+                        cx,
+                    );
+                })
+            }
+            .await;
+            if let Err(e) = result {
+                log::error!("Failed to schedule the task: {e:#}");
+                _ = workspace.update(cx, |workspace, cx| {
+                    let id = NotificationId::unique::<ResolvedTask>();
+                    workspace.show_toast(
+                        Toast::new(id, format!("Failed to schedule the task: {e:#}")),
+                        cx,
+                    );
+                })
+            }
+        });
+        self.scheduled_tasks.push(task);
+    }
+
     pub fn start_debug_session(
         &mut self,
         scenario: DebugScenario,
@@ -134,3 +291,63 @@ impl Workspace {
         }
     }
 }
+
+type TaskChainRun = Shared<LocalBoxFuture<'static, Result<(), Arc<anyhow::Error>>>>;
+
+/// Runs all dependencies of the task chain in their [`DependsOrder`], then the task itself.
+/// Stops at the first task that fails, so none of its dependents get spawned.
+///
+/// Tasks shared by several dependents run once, and all of the dependents wait for that run.
+fn run_task_chain(
+    workspace: WeakEntity<Workspace>,
+    task_chain: Arc<TaskChain>,
+    runs: &mut HashMap<TaskId, TaskChainRun>,
+    mut cx: AsyncWindowContext,
+) -> TaskChainRun {
+    let task_id = task_chain.task.id.clone();
+    if let Some(run) = runs.get(&task_id) {
+        return run.clone();
+    }
+
+    let dependencies = task_chain
+        .dependencies
+        .iter()
+        .map(|dependency| run_task_chain(workspace.clone(), dependency.clone(), runs, cx.clone()))
+        .collect::<Vec<_>>();
+    let depends_order = task_chain.depends_order;
+    let run = async move {
+        match depends_order {
+            DependsOrder::Sequence => {
+                for dependency in dependencies {
+                    dependency.await?;
+                }
+            }
+            DependsOrder::Parallel => {
+                futures::future::try_join_all(dependencies).await?;
+            }
+        }
+
+        let task = &task_chain.task;
+        if task.is_dependencies_only() {
+            return Ok(());
+        }
+        let label = task.display_label().to_owned();
+        let spawn_in_terminal = task.resolved.clone();
+        let task_status = workspace
+            .update_in(&mut cx, |workspace, window, cx| {
+                workspace.spawn_in_terminal(spawn_in_terminal, window, cx)
+            })
+            .map_err(Arc::new)?;
+        match task_status.await {
+            Some(Ok(status)) if status.success() => Ok(()),
+            Some(Ok(status)) => Err(anyhow!("Task `{label}` failed, code: {:?}", status.code())),
+            Some(Err(e)) => Err(e).with_context(|| format!("spawning task `{label}`")),
+            None => Err(anyhow!("Task `{label}` got cancelled")),
+        }
+        .map_err(Arc::new)
+    }
+    .boxed_local()
+    .shared();
+    runs.insert(task_id, run.clone());
+    run
+}
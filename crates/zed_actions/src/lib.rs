@@ -264,6 +264,10 @@ pub mod git {
             Branch,
             /// Opens the git stash selector.
             ViewStash,
+            /// Opens the git tag selector.
+            ViewTags,
+            /// Opens the reflog, listing the movements of HEAD.
+            ViewReflog,
             /// Opens the git worktree selector.
             Worktree,
             /// Creates a pull request for the current branch.